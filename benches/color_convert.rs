@@ -0,0 +1,74 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use flowrs_img::color::{resize_rgb8, rgb_to_luma, scalar, swap_rb_in_place};
+use flowrs_img::transform::{resize, ResizeNodeConfig};
+use image::{DynamicImage, RgbImage};
+
+const SIZES: [(&str, u32, u32); 2] = [("1080p", 1920, 1080), ("4k", 3840, 2160)];
+
+fn frame(width: u32, height: u32) -> RgbImage {
+    RgbImage::from_fn(width, height, |x, y| image::Rgb([x as u8, y as u8, (x ^ y) as u8]))
+}
+
+fn bench_rgb_to_luma(c: &mut Criterion) {
+    let mut group = c.benchmark_group("rgb_to_luma");
+    for (name, width, height) in SIZES {
+        let img = frame(width, height);
+        group.bench_with_input(BenchmarkId::new("kernel", name), &img, |b, img| {
+            b.iter(|| rgb_to_luma(black_box(img)))
+        });
+        group.bench_with_input(BenchmarkId::new("scalar", name), &img, |b, img| {
+            b.iter(|| scalar::rgb_to_luma(black_box(img)))
+        });
+        group.bench_with_input(BenchmarkId::new("image-rs", name), &img, |b, img| {
+            b.iter(|| image::DynamicImage::ImageRgb8(black_box(img).clone()).into_luma8())
+        });
+    }
+    group.finish();
+}
+
+fn bench_swap_rb(c: &mut Criterion) {
+    let mut group = c.benchmark_group("bgr_to_rgb");
+    for (name, width, height) in SIZES {
+        let mut pixels = frame(width, height).into_raw();
+        group.bench_function(BenchmarkId::new("kernel", name), |b| b.iter(|| swap_rb_in_place(black_box(&mut pixels))));
+        group.bench_function(BenchmarkId::new("scalar", name), |b| b.iter(|| scalar::swap_rb_in_place(black_box(&mut pixels))));
+    }
+    group.finish();
+}
+
+fn bench_resize(c: &mut Criterion) {
+    let mut group = c.benchmark_group("resize_half");
+    for (name, width, height) in SIZES {
+        let img = frame(width, height);
+        group.bench_with_input(BenchmarkId::new("kernel", name), &img, |b, img| {
+            b.iter(|| resize_rgb8(black_box(img), width / 2, height / 2))
+        });
+        group.bench_with_input(BenchmarkId::new("scalar", name), &img, |b, img| {
+            b.iter(|| scalar::resize_rgb8(black_box(img), width / 2, height / 2))
+        });
+        group.bench_with_input(BenchmarkId::new("image-rs", name), &img, |b, img| {
+            b.iter(|| image::imageops::resize(black_box(img), width / 2, height / 2, image::imageops::FilterType::Triangle))
+        });
+    }
+    group.finish();
+}
+
+/// Both paths of the resize node: fitting into a box and stretching to an exact size.
+fn bench_resize_node(c: &mut Criterion) {
+    let mut group = c.benchmark_group("resize_node");
+    for (name, width, height) in SIZES {
+        let img = DynamicImage::ImageRgb8(frame(width, height));
+        for keep_aspect in [true, false] {
+            // A square target, so fitting and stretching produce different sizes.
+            let config = ResizeNodeConfig { width: 640, height: 640, keep_aspect };
+            let path = if keep_aspect { "fit" } else { "exact" };
+            group.bench_with_input(BenchmarkId::new(path, name), &img, |b, img| {
+                b.iter(|| resize(black_box(img), &config).unwrap())
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_rgb_to_luma, bench_swap_rb, bench_resize, bench_resize_node);
+criterion_main!(benches);
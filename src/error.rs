@@ -0,0 +1,72 @@
+use std::fmt;
+use std::io;
+
+use flowrs::node::{InitError, ShutdownError, UpdateError};
+use image::ImageError;
+
+/// Failure kinds of the crate's image and capture functions.
+///
+/// Nodes hand these to flowrs wrapped in [`UpdateError::Other`]; flows can get
+/// them back with `anyhow::Error::downcast_ref::<flowrs_img::Error>()`, e.g. to
+/// retry after a [`Error::CameraRead`] but give up after a [`Error::CameraOpen`].
+#[derive(Debug)]
+pub enum Error {
+    /// A capture device could not be found, opened or configured.
+    CameraOpen(String),
+    /// An opened capture device failed to deliver a frame.
+    CameraRead(String),
+    Decode(ImageError),
+    Encode(ImageError),
+    /// Pixel data does not fit the layout it was declared with.
+    Conversion(String),
+    UnsupportedFormat(String),
+    Io(io::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::CameraOpen(reason) => write!(f, "Cannot open camera: {}", reason),
+            Error::CameraRead(reason) => write!(f, "Cannot read from camera: {}", reason),
+            Error::Decode(e) => write!(f, "Cannot decode image: {}", e),
+            Error::Encode(e) => write!(f, "Cannot encode image: {}", e),
+            Error::Conversion(reason) => write!(f, "Cannot convert image: {}", reason),
+            Error::UnsupportedFormat(format) => write!(f, "Unsupported format: {}", format),
+            Error::Io(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Decode(e) | Error::Encode(e) => Some(e),
+            Error::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+impl From<Error> for UpdateError {
+    fn from(e: Error) -> Self {
+        UpdateError::Other(e.into())
+    }
+}
+
+impl From<Error> for InitError {
+    fn from(e: Error) -> Self {
+        InitError::Other(e.into())
+    }
+}
+
+impl From<Error> for ShutdownError {
+    fn from(e: Error) -> Self {
+        ShutdownError::Other(e.into())
+    }
+}
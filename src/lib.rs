@@ -1,5 +1,98 @@
+/// Enters a debug span until the end of the enclosing block, if the `tracing` feature is enabled.
+macro_rules! frame_span {
+    ($name:expr $(, $field:ident = $value:expr)* $(,)?) => {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!($name $(, $field = $value)*).entered();
+        #[cfg(not(feature = "tracing"))]
+        let _ = ($(&$value,)*);
+    };
+}
+
+/// Adds `builder()` to a node config, with one setter per listed field and a validating
+/// `build`, e.g. `CaptureNodeConfig::builder().fps(30.0).build()?`. With `for XNode`, also
+/// adds `XNode::try_new`, which validates the config before building the node.
+macro_rules! config_builder {
+    ($config:ident $(for $node:ident)? { $($field:ident: $ty:ty),* $(,)? }) => {
+        impl $config {
+            /// Starts from the default config.
+            pub fn builder() -> $crate::config::ConfigBuilder<Self> {
+                $crate::config::ConfigBuilder::new(Self::default())
+            }
+        }
+
+        impl $crate::config::ConfigBuilder<$config> {
+            $(
+                pub fn $field(mut self, $field: $ty) -> Self {
+                    self.config.$field = $field;
+                    self
+                }
+            )*
+        }
+
+        $(
+            impl $node {
+                /// Like `new`, but rejects an invalid config instead of failing on it later.
+                pub fn try_new(config: $config, change_observer: Option<&flowrs::node::ChangeObserver>) -> Result<Self, $crate::config::ConfigError> {
+                    Ok(Self::new($crate::config::Validate::validated(config)?, change_observer))
+                }
+            }
+        )?
+    };
+}
+
+mod error;
 mod nodes;
+pub mod types;
+
+pub use self::error::Error;
 
+#[cfg(all(feature = "tracing", target_arch = "wasm32"))]
 use wasm_bindgen::prelude::wasm_bindgen;
 
+pub use self::nodes::analysis;
+pub use self::nodes::color;
+pub use self::nodes::config;
+pub use self::nodes::control;
+pub use self::nodes::debug;
+#[cfg(feature = "dicom")]
+pub use self::nodes::dicom;
+pub use self::nodes::depth;
+pub use self::nodes::document;
+pub use self::nodes::expr;
+pub use self::nodes::features;
+pub use self::nodes::filter;
+pub use self::nodes::flow;
+pub use self::nodes::forensics;
+#[cfg(feature = "gpu")]
+pub use self::nodes::gpu;
+pub use self::nodes::hashing;
+pub use self::nodes::inspection;
+pub use self::nodes::media;
+#[cfg(feature = "onnx")]
+pub use self::nodes::ml;
+pub use self::nodes::net;
+#[cfg(feature = "ocr")]
+pub use self::nodes::ocr;
+pub use self::nodes::overlay;
+pub use self::nodes::registry;
+pub use self::nodes::replay;
+pub use self::nodes::sequence;
+pub use self::nodes::shape;
+pub use self::nodes::source;
+pub use self::nodes::storage;
+#[cfg(feature = "otlp")]
+pub use self::nodes::telemetry;
+pub use self::nodes::testing;
+pub use self::nodes::tracking;
 pub use self::nodes::transform;
+pub use self::nodes::transport;
+pub use self::nodes::video;
+#[cfg(target_arch = "wasm32")]
+pub use self::nodes::web;
+
+/// Routes the spans of all nodes to the browser console, with timings in the performance panel.
+#[cfg(all(feature = "tracing", target_arch = "wasm32"))]
+#[wasm_bindgen]
+pub fn init_tracing() {
+    tracing_wasm::set_as_global_default();
+}
@@ -0,0 +1,753 @@
+use flowrs::{node::{Node, UpdateError, ChangeObserver}, connection::{Input, Output}};
+use flowrs::RuntimeConnectable;
+
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, VecDeque};
+use std::path::PathBuf;
+
+use ndarray::Array2;
+
+use image::{DynamicImage, GrayImage, Luma, Rgba, imageops::{self, FilterType}};
+use imageproc::contrast::otsu_level;
+use imageproc::drawing::draw_hollow_rect_mut;
+use imageproc::rect::Rect as DrawRect;
+use imageproc::template_matching::{match_template, MatchTemplateMethod};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::{apply_configs, ensure, ConfigError, Validate};
+use crate::types::{Detection, ObjectMeasurement, Rect};
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct LogoTemplate {
+    pub label: String,
+    pub path: PathBuf,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct LogoDetectNodeConfig {
+    pub templates: Vec<LogoTemplate>,
+    /// Frames are downscaled to this width before matching.
+    pub working_width: u32,
+    /// Template scales relative to their size in the full-resolution frame.
+    pub scales: Vec<f32>,
+    /// Minimum normalized cross-correlation (`0.0..=1.0`) for a detection.
+    pub threshold: f32,
+}
+
+impl Default for LogoDetectNodeConfig {
+    fn default() -> Self {
+        Self { templates: Vec::new(), working_width: 640, scales: vec![1.0], threshold: 0.8 }
+    }
+}
+
+impl Validate for LogoDetectNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        ensure(self.working_width > 0, "working_width", "must be positive")?;
+        ensure(!self.scales.is_empty() && self.scales.iter().all(|s| *s > 0.0), "scales", "must be positive and not empty")
+    }
+}
+
+config_builder!(LogoDetectNodeConfig for LogoDetectNode {
+    templates: Vec<LogoTemplate>,
+    working_width: u32,
+    scales: Vec<f32>,
+    threshold: f32,
+});
+
+/// Matches frames against a gallery of logo templates using normalized cross-correlation.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct LogoDetectNode {
+    #[output]
+    pub output: Output<Vec<Detection>>,
+
+    #[input]
+    pub input: Input<DynamicImage>,
+
+    pub config: LogoDetectNodeConfig,
+
+    #[serde(skip)]
+    gallery: Option<Vec<(String, GrayImage)>>,
+}
+
+impl LogoDetectNode {
+    pub fn new(config: LogoDetectNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            input: Input::new(),
+            config,
+            gallery: None,
+        }
+    }
+
+    fn load_gallery(&self) -> Result<Vec<(String, GrayImage)>, anyhow::Error> {
+        self.config
+            .templates
+            .iter()
+            .map(|t| Ok((t.label.clone(), image::open(&t.path)?.into_luma8())))
+            .collect()
+    }
+}
+
+/// Best match of `template` in `frame`, as (x, y, score).
+fn best_match(frame: &GrayImage, template: &GrayImage) -> (u32, u32, f32) {
+    let scores = match_template(frame, template, MatchTemplateMethod::CrossCorrelationNormalized);
+    scores
+        .enumerate_pixels()
+        .fold((0, 0, f32::MIN), |best, (x, y, p)| if p[0] > best.2 { (x, y, p[0]) } else { best })
+}
+
+pub fn detect_logos(img: &DynamicImage, gallery: &[(String, GrayImage)], config: &LogoDetectNodeConfig) -> Vec<Detection> {
+    let scale = (config.working_width as f32 / img.width().max(1) as f32).min(1.0);
+    let frame = if scale < 1.0 {
+        img.resize(config.working_width, u32::MAX, FilterType::Triangle).into_luma8()
+    } else {
+        img.to_luma8()
+    };
+
+    let mut detections = Vec::new();
+    for (label, template) in gallery {
+        let mut best: Option<Detection> = None;
+        for s in &config.scales {
+            let tw = (template.width() as f32 * s * scale).round() as u32;
+            let th = (template.height() as f32 * s * scale).round() as u32;
+            if tw < 4 || th < 4 || tw > frame.width() || th > frame.height() {
+                continue;
+            }
+            let scaled = imageops::resize(template, tw, th, FilterType::Triangle);
+            let (x, y, score) = best_match(&frame, &scaled);
+            if score >= config.threshold && best.as_ref().is_none_or(|b| score > b.score) {
+                let rect = Rect::new(
+                    (x as f32 / scale) as u32,
+                    (y as f32 / scale) as u32,
+                    (tw as f32 / scale) as u32,
+                    (th as f32 / scale) as u32,
+                );
+                best = Some(Detection { rect, label: label.clone(), score });
+            }
+        }
+        detections.extend(best);
+    }
+    detections
+}
+
+/// Greedy non-maximum suppression among detections of the same label.
+pub fn non_max_suppression(mut detections: Vec<Detection>, iou_threshold: f32) -> Vec<Detection> {
+    detections.sort_by(|a, b| b.score.total_cmp(&a.score));
+    let mut kept: Vec<Detection> = Vec::new();
+    for d in detections {
+        if kept.iter().all(|k| k.label != d.label || k.rect.iou(&d.rect) <= iou_threshold) {
+            kept.push(d);
+        }
+    }
+    kept
+}
+
+impl Node for LogoDetectNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        if let Ok(img) = self.input.next() {
+            if self.gallery.is_none() {
+                self.gallery = Some(self.load_gallery().map_err(UpdateError::Other)?);
+            }
+            let detections = detect_logos(&img, self.gallery.as_ref().expect("loaded above"), &self.config);
+            self.output.send(detections).map_err(|e| UpdateError::Other(e.into()))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize)]
+pub enum ThresholdMode {
+    #[default]
+    Otsu,
+    Fixed(u8),
+}
+
+/// Foreground mask (255) of pixels brighter than the threshold, or darker if `dark_objects`.
+pub fn binarize(gray: &GrayImage, mode: ThresholdMode, dark_objects: bool) -> GrayImage {
+    let level = match mode {
+        ThresholdMode::Otsu => otsu_level(gray),
+        ThresholdMode::Fixed(level) => level,
+    };
+    GrayImage::from_fn(gray.width(), gray.height(), |x, y| {
+        let v = gray.get_pixel(x, y)[0];
+        let fg = if dark_objects { v <= level } else { v > level };
+        Luma([if fg { 255 } else { 0 }])
+    })
+}
+
+/// Chamfer (3-4) distance from each foreground pixel to the nearest background pixel, in pixels.
+pub fn distance_to_background(mask: &GrayImage) -> Vec<f32> {
+    let (w, h) = (mask.width() as usize, mask.height() as usize);
+    let inf = f32::MAX / 2.0;
+    let mut d: Vec<f32> = mask.as_raw().iter().map(|v| if *v > 0 { inf } else { 0.0 }).collect();
+    let at = |x: isize, y: isize, d: &Vec<f32>| {
+        if x < 0 || y < 0 || x >= w as isize || y >= h as isize { 0.0 } else { d[y as usize * w + x as usize] }
+    };
+
+    for y in 0..h as isize {
+        for x in 0..w as isize {
+            let i = y as usize * w + x as usize;
+            if d[i] == 0.0 {
+                continue;
+            }
+            let best = (at(x - 1, y, &d) + 3.0)
+                .min(at(x, y - 1, &d) + 3.0)
+                .min(at(x - 1, y - 1, &d) + 4.0)
+                .min(at(x + 1, y - 1, &d) + 4.0);
+            d[i] = d[i].min(best);
+        }
+    }
+    for y in (0..h as isize).rev() {
+        for x in (0..w as isize).rev() {
+            let i = y as usize * w + x as usize;
+            if d[i] == 0.0 {
+                continue;
+            }
+            let best = (at(x + 1, y, &d) + 3.0)
+                .min(at(x, y + 1, &d) + 3.0)
+                .min(at(x + 1, y + 1, &d) + 4.0)
+                .min(at(x - 1, y + 1, &d) + 4.0);
+            d[i] = d[i].min(best);
+        }
+    }
+    d.iter_mut().for_each(|v| *v /= 3.0);
+    d
+}
+
+const NEIGHBOURS: [(isize, isize); 8] = [(-1, -1), (0, -1), (1, -1), (-1, 0), (1, 0), (-1, 1), (0, 1), (1, 1)];
+
+#[derive(PartialEq)]
+struct Flood(f32, usize, u32);
+
+impl Eq for Flood {}
+
+impl PartialOrd for Flood {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Flood {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+/// Labels connected foreground regions, splitting touching objects by a distance-transform watershed.
+///
+/// Returns per-pixel labels (0 is background) and the number of labels.
+pub fn watershed_labels(mask: &GrayImage, separate: bool, min_marker_distance: f32) -> (Vec<u32>, u32) {
+    let (w, h) = (mask.width() as usize, mask.height() as usize);
+    let dist = distance_to_background(mask);
+    let mut labels = vec![0u32; w * h];
+    let neighbours = |i: usize| {
+        let (x, y) = ((i % w) as isize, (i / w) as isize);
+        NEIGHBOURS.iter().filter_map(move |(dx, dy)| {
+            let (nx, ny) = (x + dx, y + dy);
+            (nx >= 0 && ny >= 0 && (nx as usize) < w && (ny as usize) < h).then(|| ny as usize * w + nx as usize)
+        })
+    };
+
+    // Markers are regional maxima of the distance map, or whole components without separation.
+    let is_seed = |i: usize| {
+        dist[i] > 0.0
+            && (!separate || (dist[i] >= min_marker_distance && neighbours(i).all(|n| dist[n] <= dist[i])))
+    };
+
+    let mut next_label = 0u32;
+    for start in 0..w * h {
+        if labels[start] != 0 || !is_seed(start) {
+            continue;
+        }
+        next_label += 1;
+        labels[start] = next_label;
+        let mut queue = VecDeque::from([start]);
+        while let Some(i) = queue.pop_front() {
+            for n in neighbours(i) {
+                let grow = if separate { dist[n] == dist[start] && is_seed(n) } else { dist[n] > 0.0 };
+                if labels[n] == 0 && grow {
+                    labels[n] = next_label;
+                    queue.push_back(n);
+                }
+            }
+        }
+    }
+
+    if separate {
+        let mut heap = BinaryHeap::new();
+        for i in 0..w * h {
+            if labels[i] != 0 {
+                heap.push(Flood(dist[i], i, labels[i]));
+            }
+        }
+        while let Some(Flood(_, i, label)) = heap.pop() {
+            for n in neighbours(i) {
+                if labels[n] == 0 && dist[n] > 0.0 {
+                    labels[n] = label;
+                    heap.push(Flood(dist[n], n, label));
+                }
+            }
+        }
+    }
+    (labels, next_label)
+}
+
+/// Area, bounds, centroid and boundary length of every labelled object.
+pub fn measure_labels(labels: &[u32], count: u32, width: u32, height: u32) -> Vec<ObjectMeasurement> {
+    let (w, h) = (width as usize, height as usize);
+    let mut stats = vec![(u32::MAX, u32::MAX, 0u32, 0u32, 0u64, 0f64, 0f64, 0u64); count as usize + 1];
+    for (i, &l) in labels.iter().enumerate() {
+        if l == 0 {
+            continue;
+        }
+        let (x, y) = ((i % w) as u32, (i / w) as u32);
+        let s = &mut stats[l as usize];
+        s.0 = s.0.min(x);
+        s.1 = s.1.min(y);
+        s.2 = s.2.max(x);
+        s.3 = s.3.max(y);
+        s.4 += 1;
+        s.5 += x as f64;
+        s.6 += y as f64;
+        let boundary = x == 0 || y == 0 || x as usize == w - 1 || y as usize == h - 1
+            || labels[i - 1] != l || labels[i + 1] != l || labels[i - w] != l || labels[i + w] != l;
+        if boundary {
+            s.7 += 1;
+        }
+    }
+
+    stats
+        .iter()
+        .enumerate()
+        .skip(1)
+        .filter(|(_, s)| s.4 > 0)
+        .map(|(id, s)| ObjectMeasurement {
+            id: id as u32,
+            bounds: Rect::new(s.0, s.1, s.2 - s.0 + 1, s.3 - s.1 + 1),
+            area: s.4 as f64,
+            perimeter: s.7 as f64,
+            centroid: (s.5 / s.4 as f64, s.6 / s.4 as f64),
+        })
+        .collect()
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ParticleCountNodeConfig {
+    pub threshold: ThresholdMode,
+    /// Objects are darker than the background (e.g. brightfield microscopy).
+    pub dark_objects: bool,
+    /// Split touching objects with a watershed on the distance transform.
+    pub separate_touching: bool,
+    /// Minimum object radius, in pixels, for a watershed marker.
+    pub min_marker_distance: f32,
+    pub min_area: f64,
+    pub max_area: f64,
+}
+
+impl Default for ParticleCountNodeConfig {
+    fn default() -> Self {
+        Self {
+            threshold: ThresholdMode::Otsu,
+            dark_objects: false,
+            separate_touching: false,
+            min_marker_distance: 3.0,
+            min_area: 0.0,
+            max_area: f64::MAX,
+        }
+    }
+}
+
+impl Validate for ParticleCountNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        ensure(self.min_area <= self.max_area, "min_area", "must not exceed max_area")
+    }
+}
+
+config_builder!(ParticleCountNodeConfig for ParticleCountNode {
+    threshold: ThresholdMode,
+    dark_objects: bool,
+    separate_touching: bool,
+    min_marker_distance: f32,
+    min_area: f64,
+    max_area: f64,
+});
+
+/// Counts and measures particles or cells, emitting measurements and an annotated overlay.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct ParticleCountNode {
+    #[output]
+    pub measurements: Output<Vec<ObjectMeasurement>>,
+
+    #[output]
+    pub overlay: Output<DynamicImage>,
+
+    #[input]
+    pub input: Input<DynamicImage>,
+
+    pub config: ParticleCountNodeConfig,
+}
+
+impl ParticleCountNode {
+    pub fn new(config: ParticleCountNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            measurements: Output::new(change_observer),
+            overlay: Output::new(change_observer),
+            input: Input::new(),
+            config,
+        }
+    }
+}
+
+/// Distinct, stable color per object id.
+fn label_color(id: u32) -> [u8; 3] {
+    let h = id.wrapping_mul(2_654_435_761);
+    [(h >> 16) as u8 | 0x40, (h >> 8) as u8 | 0x40, h as u8 | 0x40]
+}
+
+impl Node for ParticleCountNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        if let Ok(img) = self.input.next() {
+            let c = &self.config;
+            let gray = img.to_luma8();
+            let mask = binarize(&gray, c.threshold, c.dark_objects);
+            let (labels, count) = watershed_labels(&mask, c.separate_touching, c.min_marker_distance);
+            let objects: Vec<ObjectMeasurement> = measure_labels(&labels, count, gray.width(), gray.height())
+                .into_iter()
+                .filter(|o| o.area >= c.min_area && o.area <= c.max_area)
+                .collect();
+
+            let mut overlay = img.to_rgba8();
+            let kept: std::collections::HashSet<u32> = objects.iter().map(|o| o.id).collect();
+            for (px, &l) in overlay.pixels_mut().zip(&labels) {
+                if kept.contains(&l) {
+                    let color = label_color(l);
+                    (0..3).for_each(|i| px[i] = ((px[i] as u16 + color[i] as u16) / 2) as u8);
+                }
+            }
+            for o in &objects {
+                let rect = DrawRect::at(o.bounds.x as i32, o.bounds.y as i32).of_size(o.bounds.width, o.bounds.height);
+                draw_hollow_rect_mut(&mut overlay, rect, Rgba([255, 255, 0, 255]));
+            }
+
+            self.measurements.send(objects).map_err(|e| UpdateError::Other(e.into()))?;
+            self.overlay.send(DynamicImage::ImageRgba8(overlay)).map_err(|e| UpdateError::Other(e.into()))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ThermalAnomalyNodeConfig {
+    /// Temperatures above this value always raise an alarm.
+    pub absolute_limit: Option<f32>,
+    /// Alarm when a region exceeds the scene median by more than this many degrees.
+    pub relative_limit: Option<f32>,
+    /// Minimum region size in pixels, to suppress single hot pixels.
+    pub min_area: u32,
+}
+
+impl Default for ThermalAnomalyNodeConfig {
+    fn default() -> Self {
+        Self { absolute_limit: None, relative_limit: Some(10.0), min_area: 4 }
+    }
+}
+
+impl Validate for ThermalAnomalyNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        ensure(self.absolute_limit.is_some() || self.relative_limit.is_some(), "relative_limit", "one of the limits must be set")?;
+        ensure(self.absolute_limit.is_none_or(f32::is_finite), "absolute_limit", "must be finite")?;
+        ensure(self.relative_limit.is_none_or(|d| d > 0.0), "relative_limit", "must be positive")?;
+        ensure(self.min_area > 0, "min_area", "must be positive")
+    }
+}
+
+config_builder!(ThermalAnomalyNodeConfig for ThermalAnomalyNode {
+    absolute_limit: Option<f32>,
+    relative_limit: Option<f32>,
+    min_area: u32,
+});
+
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+pub enum ThermalThreshold {
+    Absolute,
+    Relative,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct ThermalAlarm {
+    pub bounds: Rect,
+    pub area: u32,
+    pub max_temperature: f32,
+    /// Pixel position of the hottest point in the region.
+    pub hotspot: (u32, u32),
+    pub ambient_temperature: f32,
+    /// The strictest threshold the region violated.
+    pub threshold: ThermalThreshold,
+}
+
+/// Finds connected regions of a temperature map exceeding the configured limits.
+pub fn detect_thermal_anomalies(temperatures: &Array2<f32>, config: &ThermalAnomalyNodeConfig) -> Vec<ThermalAlarm> {
+    let (h, w) = temperatures.dim();
+    let mut sorted: Vec<f32> = temperatures.iter().copied().filter(|t| t.is_finite()).collect();
+    if sorted.is_empty() {
+        return Vec::new();
+    }
+    let mid = sorted.len() / 2;
+    let ambient = *sorted.select_nth_unstable_by(mid, f32::total_cmp).1;
+
+    let limit = match (config.absolute_limit, config.relative_limit) {
+        (Some(a), Some(r)) => a.min(ambient + r),
+        (Some(a), None) => a,
+        (None, Some(r)) => ambient + r,
+        (None, None) => return Vec::new(),
+    };
+    let mask = GrayImage::from_fn(w as u32, h as u32, |x, y| {
+        Luma([if temperatures[[y as usize, x as usize]] > limit { 255 } else { 0 }])
+    });
+
+    let (labels, count) = watershed_labels(&mask, false, 0.0);
+    let mut peaks = vec![(f32::MIN, 0usize); count as usize + 1];
+    for (i, &l) in labels.iter().enumerate() {
+        let t = temperatures[[i / w, i % w]];
+        if l != 0 && t > peaks[l as usize].0 {
+            peaks[l as usize] = (t, i);
+        }
+    }
+
+    measure_labels(&labels, count, w as u32, h as u32)
+        .into_iter()
+        .filter(|o| o.area >= config.min_area as f64)
+        .map(|o| {
+            let (max_temperature, i) = peaks[o.id as usize];
+            let threshold = match config.absolute_limit {
+                Some(a) if max_temperature > a => ThermalThreshold::Absolute,
+                _ => ThermalThreshold::Relative,
+            };
+            ThermalAlarm {
+                bounds: o.bounds,
+                area: o.area as u32,
+                max_temperature,
+                hotspot: ((i % w) as u32, (i / w) as u32),
+                ambient_temperature: ambient,
+                threshold,
+            }
+        })
+        .collect()
+}
+
+/// Raises alarms for hot regions in temperature maps, e.g. for equipment monitoring.
+///
+/// Input arrays are indexed `[row, column]` and hold temperatures in a single
+/// consistent unit; nothing is sent for frames without anomalies.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct ThermalAnomalyNode {
+    #[output]
+    pub output: Output<Vec<ThermalAlarm>>,
+
+    #[input]
+    pub input: Input<Array2<f32>>,
+
+    pub config: ThermalAnomalyNodeConfig,
+}
+
+impl ThermalAnomalyNode {
+    pub fn new(config: ThermalAnomalyNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            input: Input::new(),
+            config,
+        }
+    }
+}
+
+impl Node for ThermalAnomalyNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        if let Ok(temperatures) = self.input.next() {
+            let alarms = detect_thermal_anomalies(&temperatures, &self.config);
+            if !alarms.is_empty() {
+                self.output.send(alarms).map_err(|e| UpdateError::Other(e.into()))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Longest side of the copy frame quality is measured on, so that sharpness compares across resolutions.
+const QUALITY_SIZE: u32 = 640;
+
+/// Luma statistics of a frame, each on a 0 to 1 scale.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+pub struct FrameQuality {
+    /// Variance of the Laplacian; low for blurred or out of focus frames.
+    pub sharpness: f32,
+    pub brightness: f32,
+    /// Standard deviation of the luma (RMS contrast).
+    pub contrast: f32,
+}
+
+/// Measures `img`; empty frames measure as all zeros.
+pub fn measure_quality(img: &DynamicImage) -> FrameQuality {
+    if img.width() == 0 || img.height() == 0 {
+        return FrameQuality { sharpness: 0.0, brightness: 0.0, contrast: 0.0 };
+    }
+    let scale = (QUALITY_SIZE as f32 / img.width().max(img.height()) as f32).min(1.0);
+    let gray = if scale < 1.0 {
+        img.resize(((img.width() as f32 * scale) as u32).max(1), ((img.height() as f32 * scale) as u32).max(1), FilterType::Triangle).to_luma8()
+    } else {
+        img.to_luma8()
+    };
+    let (w, h) = gray.dimensions();
+    let luma = |x: u32, y: u32| gray.get_pixel(x, y)[0] as f32 / 255.0;
+
+    let n = (w * h) as f32;
+    let brightness = gray.pixels().map(|p| p[0] as f32 / 255.0).sum::<f32>() / n;
+    let contrast = (gray.pixels().map(|p| (p[0] as f32 / 255.0 - brightness).powi(2)).sum::<f32>() / n).sqrt();
+
+    let mut laplacian = Vec::with_capacity((w.saturating_sub(2) * h.saturating_sub(2)) as usize);
+    for y in 1..h.saturating_sub(1) {
+        for x in 1..w.saturating_sub(1) {
+            laplacian.push(luma(x - 1, y) + luma(x + 1, y) + luma(x, y - 1) + luma(x, y + 1) - 4.0 * luma(x, y));
+        }
+    }
+    let sharpness = if laplacian.is_empty() {
+        0.0
+    } else {
+        let mean = laplacian.iter().sum::<f32>() / laplacian.len() as f32;
+        laplacian.iter().map(|l| (l - mean).powi(2)).sum::<f32>() / laplacian.len() as f32
+    };
+
+    FrameQuality { sharpness, brightness, contrast }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum QualityIssue {
+    Blurry,
+    TooDark,
+    TooBright,
+    LowContrast,
+    /// The frame has no pixels; rejected whatever the thresholds.
+    Empty,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct QualityRejection {
+    pub reasons: Vec<QualityIssue>,
+    pub quality: FrameQuality,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct QualityGateNodeConfig {
+    pub min_sharpness: Option<f32>,
+    pub min_brightness: Option<f32>,
+    pub max_brightness: Option<f32>,
+    pub min_contrast: Option<f32>,
+}
+
+impl Default for QualityGateNodeConfig {
+    fn default() -> Self {
+        Self { min_sharpness: Some(0.001), min_brightness: Some(0.05), max_brightness: Some(0.95), min_contrast: Some(0.03) }
+    }
+}
+
+impl Validate for QualityGateNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        let unit = |v: Option<f32>| v.is_none_or(|v| (0.0..=1.0).contains(&v));
+        ensure(unit(self.min_sharpness), "min_sharpness", "must be between 0 and 1")?;
+        ensure(unit(self.min_brightness), "min_brightness", "must be between 0 and 1")?;
+        ensure(unit(self.max_brightness), "max_brightness", "must be between 0 and 1")?;
+        ensure(unit(self.min_contrast), "min_contrast", "must be between 0 and 1")?;
+        if let (Some(min), Some(max)) = (self.min_brightness, self.max_brightness) {
+            ensure(min <= max, "max_brightness", "must not be below min_brightness")?;
+        }
+        Ok(())
+    }
+}
+
+config_builder!(QualityGateNodeConfig for QualityGateNode {
+    min_sharpness: Option<f32>,
+    min_brightness: Option<f32>,
+    max_brightness: Option<f32>,
+    min_contrast: Option<f32>,
+});
+
+/// The thresholds of `config` that `quality` violates, empty for good frames.
+pub fn quality_issues(quality: &FrameQuality, config: &QualityGateNodeConfig) -> Vec<QualityIssue> {
+    let below = |value: f32, limit: Option<f32>| limit.is_some_and(|l| value < l);
+    let mut issues = Vec::new();
+    if below(quality.sharpness, config.min_sharpness) {
+        issues.push(QualityIssue::Blurry);
+    }
+    if below(quality.brightness, config.min_brightness) {
+        issues.push(QualityIssue::TooDark);
+    }
+    if config.max_brightness.is_some_and(|l| quality.brightness > l) {
+        issues.push(QualityIssue::TooBright);
+    }
+    if below(quality.contrast, config.min_contrast) {
+        issues.push(QualityIssue::LowContrast);
+    }
+    issues
+}
+
+/// Forwards only frames that are sharp, well exposed and contrasty enough, e.g. ahead of
+/// inference; the reasons for dropping a frame go to `rejected`.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct QualityGateNode {
+    #[output]
+    pub output: Output<DynamicImage>,
+
+    #[output]
+    pub rejected: Output<QualityRejection>,
+
+    #[input]
+    pub input: Input<DynamicImage>,
+
+    /// Replaces the thresholds for all following frames, e.g. from a [`crate::config::ConfigFileWatcherNode`].
+    #[input]
+    pub reconfigure: Input<QualityGateNodeConfig>,
+
+    pub config: QualityGateNodeConfig,
+}
+
+impl QualityGateNode {
+    pub fn new(config: QualityGateNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            rejected: Output::new(change_observer),
+            input: Input::new(),
+            reconfigure: Input::new(),
+            config,
+        }
+    }
+}
+
+impl Node for QualityGateNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        apply_configs(&mut self.reconfigure, &mut self.config).map_err(|e| UpdateError::Other(e.into()))?;
+
+        if let Ok(img) = self.input.next() {
+            frame_span!("quality_gate", width = img.width(), height = img.height());
+            let quality = measure_quality(&img);
+            let reasons = if img.width() == 0 || img.height() == 0 {
+                vec![QualityIssue::Empty]
+            } else {
+                quality_issues(&quality, &self.config)
+            };
+            if reasons.is_empty() {
+                self.output.send(img).map_err(|e| UpdateError::Other(e.into()))?;
+            } else {
+                self.rejected.send(QualityRejection { reasons, quality }).map_err(|e| UpdateError::Other(e.into()))?;
+            }
+        }
+        Ok(())
+    }
+}
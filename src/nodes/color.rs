@@ -0,0 +1,1318 @@
+use flowrs::{node::{Node, UpdateError, ChangeObserver}, connection::{Input, Output}};
+use flowrs::RuntimeConnectable;
+
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+
+use image::{DynamicImage, GrayImage, Luma, Rgb32FImage, RgbImage, Rgba32FImage, imageops::FilterType};
+use imageproc::gradients::sobel_gradients;
+use imageproc::region_labelling::{connected_components, Connectivity};
+
+use anyhow::anyhow;
+use ndarray::Array2;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::{ensure, ConfigError, Validate};
+use crate::filter::match_format;
+use crate::flow::{push_bounded, OverflowPolicy};
+
+/// Rec. 709 luma weights, matching `DynamicImage::to_luma8`.
+const LUMA_R: f32 = 0.2126;
+const LUMA_G: f32 = 0.7152;
+const LUMA_B: f32 = 0.0722;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum ColorFormat {
+    Luma8,
+    LumaA8,
+    #[default]
+    Rgb8,
+    Rgba8,
+    Luma16,
+    Rgb16,
+    Rgba16,
+    Rgb32F,
+    Rgba32F,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ColorConvertNodeConfig {
+    pub target: ColorFormat,
+}
+
+impl Validate for ColorConvertNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        // Every format can be converted to every other.
+        Ok(())
+    }
+}
+
+config_builder!(ColorConvertNodeConfig for ColorConvertNode { target: ColorFormat });
+
+/// Converts incoming images to a fixed pixel format.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct ColorConvertNode {
+    #[output]
+    pub output: Output<DynamicImage>,
+
+    #[input]
+    pub input: Input<DynamicImage>,
+
+    pub config: ColorConvertNodeConfig,
+
+    #[serde(skip)]
+    frames: u64,
+}
+
+impl ColorConvertNode {
+    pub fn new(config: ColorConvertNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            input: Input::new(),
+            config,
+            frames: 0,
+        }
+    }
+}
+
+/// Converts `img` to `target`. 8-bit RGB to luma goes through [`rgb_to_luma`], which uses
+/// explicit SIMD when the `simd` feature is enabled.
+pub fn convert(img: DynamicImage, target: ColorFormat) -> DynamicImage {
+    match (target, img) {
+        (ColorFormat::Luma8, DynamicImage::ImageRgb8(rgb)) => DynamicImage::ImageLuma8(rgb_to_luma(&rgb)),
+        (ColorFormat::Luma8, img) => DynamicImage::ImageLuma8(img.into_luma8()),
+        (ColorFormat::LumaA8, img) => DynamicImage::ImageLumaA8(img.into_luma_alpha8()),
+        (ColorFormat::Rgb8, img) => DynamicImage::ImageRgb8(img.into_rgb8()),
+        (ColorFormat::Rgba8, img) => DynamicImage::ImageRgba8(img.into_rgba8()),
+        (ColorFormat::Luma16, img) => DynamicImage::ImageLuma16(img.into_luma16()),
+        (ColorFormat::Rgb16, img) => DynamicImage::ImageRgb16(img.into_rgb16()),
+        (ColorFormat::Rgba16, img) => DynamicImage::ImageRgba16(img.into_rgba16()),
+        (ColorFormat::Rgb32F, img) => DynamicImage::ImageRgb32F(img.into_rgb32f()),
+        (ColorFormat::Rgba32F, img) => DynamicImage::ImageRgba32F(img.into_rgba32f()),
+    }
+}
+
+impl Node for ColorConvertNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        if let Ok(img) = self.input.next() {
+            frame_span!("convert_color", frame = self.frames);
+            self.frames += 1;
+            let converted = convert(img, self.config.target);
+            self.output.send(converted).map_err(|e| UpdateError::Other(e.into()))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(not(feature = "simd"))]
+use self::scalar as kernels;
+#[cfg(feature = "simd")]
+use self::simd as kernels;
+
+/// Swaps the first and third channel of packed 3-channel pixels, e.g. BGR to RGB.
+pub fn swap_rb_in_place(pixels: &mut [u8]) {
+    kernels::swap_rb_in_place(pixels);
+}
+
+/// Converts a packed 8-bit BGR buffer, as delivered by many capture backends, into an `RgbImage`.
+pub fn bgr_to_rgb(width: u32, height: u32, mut pixels: Vec<u8>) -> Option<RgbImage> {
+    swap_rb_in_place(&mut pixels);
+    RgbImage::from_raw(width, height, pixels)
+}
+
+/// Converts an 8-bit RGB image to luma.
+pub fn rgb_to_luma(img: &RgbImage) -> GrayImage {
+    luma_with(img, kernels::rgb_to_luma_slice)
+}
+
+/// Resizes an 8-bit RGB image with a triangle (bilinear) filter, widened when downscaling
+/// so every source pixel contributes.
+pub fn resize_rgb8(img: &RgbImage, width: u32, height: u32) -> RgbImage {
+    resize_with(img, width, height, kernels::accumulate_row)
+}
+
+/// Like `DynamicImage::resize` with `FilterType::Triangle`: scales `img` to fit within
+/// `width` x `height` keeping its aspect ratio, going through [`resize_rgb8`] for 8-bit RGB.
+pub fn resize_to_fit(img: &DynamicImage, width: u32, height: u32) -> DynamicImage {
+    let DynamicImage::ImageRgb8(rgb) = img else {
+        return img.resize(width, height, FilterType::Triangle);
+    };
+    let ratio = (width as f64 / rgb.width() as f64).min(height as f64 / rgb.height() as f64);
+    let fit = |side: u32| ((side as f64 * ratio).round() as u32).max(1);
+    DynamicImage::ImageRgb8(resize_rgb8(rgb, fit(rgb.width()), fit(rgb.height())))
+}
+
+/// Like `DynamicImage::resize_exact` with `FilterType::Triangle`, going through
+/// [`resize_rgb8`] for 8-bit RGB.
+pub fn resize_exact(img: &DynamicImage, width: u32, height: u32) -> DynamicImage {
+    match img {
+        DynamicImage::ImageRgb8(rgb) => DynamicImage::ImageRgb8(resize_rgb8(rgb, width, height)),
+        _ => img.resize_exact(width, height, FilterType::Triangle),
+    }
+}
+
+fn luma_with(img: &RgbImage, kernel: fn(&[u8], &mut [u8])) -> GrayImage {
+    let mut out = vec![0u8; (img.width() * img.height()) as usize];
+    kernel(img.as_raw(), &mut out);
+    GrayImage::from_raw(img.width(), img.height(), out).expect("Luma buffer sized from source image.")
+}
+
+fn luma(r: u8, g: u8, b: u8) -> u8 {
+    (r as f32 * LUMA_R + g as f32 * LUMA_G + b as f32 * LUMA_B + 0.5) as u8
+}
+
+/// Taps of a triangle filter resampling `src` samples to `dst`, as the first source index
+/// and the normalized weights from there on.
+fn triangle_taps(src: u32, dst: u32) -> Vec<(usize, Vec<f32>)> {
+    let ratio = src as f32 / dst as f32;
+    let scale = ratio.max(1.0);
+    (0..dst)
+        .map(|i| {
+            let center = (i as f32 + 0.5) * ratio;
+            let left = ((center - scale).floor() as i64).clamp(0, src as i64 - 1) as usize;
+            let right = ((center + scale).ceil() as i64).clamp(left as i64 + 1, src as i64) as usize;
+            let mut weights: Vec<f32> = (left..right)
+                .map(|j| (1.0 - ((j as f32 + 0.5 - center) / scale).abs()).max(0.0))
+                .collect();
+            let sum: f32 = weights.iter().sum();
+            match sum > 0.0 {
+                true => weights.iter_mut().for_each(|w| *w /= sum),
+                // The source is narrower than one tap; take the nearest sample.
+                false => weights.iter_mut().enumerate().for_each(|(j, w)| *w = (j == 0) as u8 as f32),
+            }
+            (left, weights)
+        })
+        .collect()
+}
+
+/// Separable resize: rows are blended vertically into a float buffer by `accumulate`,
+/// which is where the time goes, then each output row is filtered horizontally.
+fn resize_with(img: &RgbImage, width: u32, height: u32, accumulate: fn(&mut [f32], &[u8], f32)) -> RgbImage {
+    let (src_width, src_height) = img.dimensions();
+    let mut out = RgbImage::new(width, height);
+    if src_width == 0 || src_height == 0 || width == 0 || height == 0 {
+        return out;
+    }
+
+    let stride = src_width as usize * 3;
+    let rows = triangle_taps(src_height, height);
+    let columns = triangle_taps(src_width, width);
+    let mut blended = vec![0f32; stride];
+    for ((first, weights), out_row) in rows.iter().zip(out.chunks_exact_mut(width as usize * 3)) {
+        blended.fill(0.0);
+        for (k, &weight) in weights.iter().enumerate() {
+            let row = (first + k) * stride;
+            accumulate(&mut blended, &img.as_raw()[row..row + stride], weight);
+        }
+        for ((first, weights), px) in columns.iter().zip(out_row.chunks_exact_mut(3)) {
+            for (c, v) in px.iter_mut().enumerate() {
+                let sum: f32 = weights.iter().enumerate().map(|(k, w)| w * blended[(first + k) * 3 + c]).sum();
+                *v = sum.round().clamp(0.0, 255.0) as u8;
+            }
+        }
+    }
+    out
+}
+
+/// Plain loops over the pixel kernels. These are what every build runs without the `simd`
+/// feature, and the reference the SIMD kernels are tested against.
+pub mod scalar {
+    use image::{GrayImage, RgbImage};
+
+    use super::luma;
+
+    /// Swaps the first and third channel of packed 3-channel pixels.
+    pub fn swap_rb_in_place(pixels: &mut [u8]) {
+        for px in pixels.chunks_exact_mut(3) {
+            px.swap(0, 2);
+        }
+    }
+
+    pub fn rgb_to_luma(img: &RgbImage) -> GrayImage {
+        super::luma_with(img, rgb_to_luma_slice)
+    }
+
+    pub fn resize_rgb8(img: &RgbImage, width: u32, height: u32) -> RgbImage {
+        super::resize_with(img, width, height, accumulate_row)
+    }
+
+    pub(super) fn rgb_to_luma_slice(rgb: &[u8], out: &mut [u8]) {
+        for (px, y) in rgb.chunks_exact(3).zip(out.iter_mut()) {
+            *y = luma(px[0], px[1], px[2]);
+        }
+    }
+
+    /// Adds `weight` times each sample of `row` onto `acc`.
+    pub(super) fn accumulate_row(acc: &mut [f32], row: &[u8], weight: f32) {
+        for (a, &v) in acc.iter_mut().zip(row) {
+            *a += weight * v as f32;
+        }
+    }
+}
+
+/// `wide` kernels working on 16 bytes at a time. Each does the same float operations in the
+/// same order as its [`scalar`] counterpart, so both produce identical pixels.
+#[cfg(feature = "simd")]
+mod simd {
+    use wide::{f32x8, i32x8, u16x8, u8x16};
+
+    use super::{luma, LUMA_B, LUMA_G, LUMA_R};
+
+    /// Out-of-range lane index, which `swizzle` turns into zero.
+    const Z: u8 = 0x80;
+
+    fn load(bytes: &[u8]) -> u8x16 {
+        u8x16::from(<[u8; 16]>::try_from(&bytes[..16]).expect("16 bytes"))
+    }
+
+    fn low_f32(v: u8x16) -> f32x8 {
+        f32x8::from_i32x8(i32x8::from_u16x8(u16x8::from_u8x16_low(v)))
+    }
+
+    fn high_f32(v: u8x16) -> f32x8 {
+        f32x8::from_i32x8(i32x8::from_u16x8(u16x8::from_u8x16_high(v)))
+    }
+
+    /// Five pixels per shuffle; the 16th byte belongs to the next pixel and maps to itself.
+    pub fn swap_rb_in_place(pixels: &mut [u8]) {
+        let order = u8x16::from([2, 1, 0, 5, 4, 3, 8, 7, 6, 11, 10, 9, 14, 13, 12, 15]);
+        let mut i = 0;
+        while i + 16 <= pixels.len() {
+            let swapped = load(&pixels[i..]).swizzle_relaxed(order);
+            pixels[i..i + 16].copy_from_slice(&swapped.to_array());
+            i += 15;
+        }
+        super::scalar::swap_rb_in_place(&mut pixels[i..]);
+    }
+
+    /// Eight pixels per step: two overlapping 16-byte loads cover their 24 bytes, and one
+    /// shuffle per load and channel gathers the channel into the low 8 lanes.
+    pub(super) fn rgb_to_luma_slice(rgb: &[u8], out: &mut [u8]) {
+        let channel = |offset: u8| {
+            let low = u8x16::from(core::array::from_fn(|i| if i < 5 { offset + 3 * i as u8 } else { Z }));
+            let high = u8x16::from(core::array::from_fn(|i| if (5..8).contains(&i) { offset + 3 * i as u8 - 8 } else { Z }));
+            (low, high)
+        };
+        let (r_order, g_order, b_order) = (channel(0), channel(1), channel(2));
+        let gather = |lo: u8x16, hi: u8x16, (low, high): (u8x16, u8x16)| low_f32(lo.swizzle(low) | hi.swizzle(high));
+        let (wr, wg, wb) = (f32x8::splat(LUMA_R), f32x8::splat(LUMA_G), f32x8::splat(LUMA_B));
+        let half = f32x8::splat(0.5);
+
+        let mut rgb_chunks = rgb.chunks_exact(24);
+        let mut out_chunks = out.chunks_exact_mut(8);
+        for (px, y) in (&mut rgb_chunks).zip(&mut out_chunks) {
+            let (lo, hi) = (load(px), load(&px[8..]));
+            let (r, g, b) = (gather(lo, hi, r_order), gather(lo, hi, g_order), gather(lo, hi, b_order));
+            let luma = (r * wr + g * wg + b * wb + half).trunc_int().to_array();
+            for (dst, v) in y.iter_mut().zip(luma) {
+                *dst = v as u8;
+            }
+        }
+
+        for (px, y) in rgb_chunks.remainder().chunks_exact(3).zip(out_chunks.into_remainder()) {
+            *y = luma(px[0], px[1], px[2]);
+        }
+    }
+
+    pub(super) fn accumulate_row(acc: &mut [f32], row: &[u8], weight: f32) {
+        let w = f32x8::splat(weight);
+        let mut acc_chunks = acc.chunks_exact_mut(16);
+        let mut row_chunks = row.chunks_exact(16);
+        for (a, v) in (&mut acc_chunks).zip(&mut row_chunks) {
+            let v = load(v);
+            let (a_low, a_high) = a.split_at_mut(8);
+            let low = f32x8::from(<[f32; 8]>::try_from(&*a_low).expect("8 lanes")) + w * low_f32(v);
+            let high = f32x8::from(<[f32; 8]>::try_from(&*a_high).expect("8 lanes")) + w * high_f32(v);
+            a_low.copy_from_slice(&low.to_array());
+            a_high.copy_from_slice(&high.to_array());
+        }
+        super::scalar::accumulate_row(acc_chunks.into_remainder(), row_chunks.remainder(), weight);
+    }
+}
+
+/// sRGB reference values of the 24-patch ColorChecker Classic, row by row.
+pub const COLOR_CHECKER_SRGB: [[u8; 3]; 24] = [
+    [115, 82, 68], [194, 150, 130], [98, 122, 157], [87, 108, 67], [133, 128, 177], [103, 189, 170],
+    [214, 126, 44], [80, 91, 166], [193, 90, 99], [94, 60, 108], [157, 188, 64], [224, 163, 46],
+    [56, 61, 150], [70, 148, 73], [175, 54, 60], [231, 199, 31], [187, 86, 149], [8, 133, 161],
+    [243, 243, 242], [200, 200, 200], [160, 160, 160], [122, 122, 121], [85, 85, 85], [52, 52, 52],
+];
+const CHART_COLUMNS: usize = 6;
+const CHART_ROWS: usize = 4;
+/// Frames are downscaled to this width for chart detection.
+const DETECTION_WIDTH: u32 = 640;
+/// Sobel magnitude below which a pixel counts as part of a flat patch.
+const FLAT_GRADIENT: u16 = 60;
+
+pub fn srgb_to_linear(v: f32) -> f32 {
+    if v <= 0.04045 { v / 12.92 } else { ((v + 0.055) / 1.055).powf(2.4) }
+}
+
+pub fn linear_to_srgb(v: f32) -> f32 {
+    let v = v.clamp(0.0, 1.0);
+    if v <= 0.0031308 { v * 12.92 } else { 1.055 * v.powf(1.0 / 2.4) - 0.055 }
+}
+
+fn linear_rgb(px: [f32; 3]) -> [f32; 3] {
+    px.map(|c| srgb_to_linear(c / 255.0))
+}
+
+fn invert3(m: [[f32; 3]; 3]) -> Option<[[f32; 3]; 3]> {
+    let det = m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+        - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+        + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0]);
+    if det.abs() < 1e-9 {
+        return None;
+    }
+    let mut inv = [[0f32; 3]; 3];
+    for (r, row) in inv.iter_mut().enumerate() {
+        for (c, cell) in row.iter_mut().enumerate() {
+            let (r1, r2) = ((c + 1) % 3, (c + 2) % 3);
+            let (c1, c2) = ((r + 1) % 3, (r + 2) % 3);
+            *cell = (m[r1][c1] * m[r2][c2] - m[r1][c2] * m[r2][c1]) / det;
+        }
+    }
+    Some(inv)
+}
+
+/// Row-major 3x3 matrix mapping measured linear RGB to reference linear RGB.
+pub type ColorCorrectionMatrix = [f32; 9];
+
+/// Least-squares color-correction matrix from measured patch colors (sRGB, `0.0..=255.0`).
+pub fn compute_correction_matrix(measured: &[[f32; 3]; 24]) -> Option<ColorCorrectionMatrix> {
+    let a: Vec<[f32; 3]> = measured.iter().map(|p| linear_rgb(*p)).collect();
+    let b: Vec<[f32; 3]> = COLOR_CHECKER_SRGB.iter().map(|p| linear_rgb(p.map(|c| c as f32))).collect();
+
+    let mut ata = [[0f32; 3]; 3];
+    let mut atb = [[0f32; 3]; 3];
+    for (ai, bi) in a.iter().zip(&b) {
+        for r in 0..3 {
+            for c in 0..3 {
+                ata[r][c] += ai[r] * ai[c];
+                atb[r][c] += ai[r] * bi[c];
+            }
+        }
+    }
+    let inv = invert3(ata)?;
+
+    // Solution X of A X = B, transposed so it can be applied as M * column vector.
+    let mut m = [0f32; 9];
+    for out in 0..3 {
+        for i in 0..3 {
+            m[out * 3 + i] = (0..3).map(|k| inv[i][k] * atb[k][out]).sum();
+        }
+    }
+    Some(m)
+}
+
+/// Applies a color-correction matrix in linear light.
+pub fn apply_correction_matrix(img: &RgbImage, m: &ColorCorrectionMatrix) -> RgbImage {
+    let lut: Vec<f32> = (0..256).map(|v| srgb_to_linear(v as f32 / 255.0)).collect();
+    let mut out = img.clone();
+    for px in out.pixels_mut() {
+        let l = [lut[px[0] as usize], lut[px[1] as usize], lut[px[2] as usize]];
+        for c in 0..3 {
+            let v = m[c * 3] * l[0] + m[c * 3 + 1] * l[1] + m[c * 3 + 2] * l[2];
+            px[c] = (linear_to_srgb(v) * 255.0 + 0.5) as u8;
+        }
+    }
+    out
+}
+
+struct Patch {
+    center: (f32, f32),
+    color: [f32; 3],
+}
+
+/// Locates a ColorChecker Classic and returns the mean color of its 24 patches in chart order.
+///
+/// Patches are found as flat, square connected regions of similar size; their centers are
+/// then sorted into a 6x4 grid, and the orientation that best matches the reference
+/// brightness ordering is selected.
+pub fn detect_color_checker(img: &DynamicImage) -> Option<[[f32; 3]; 24]> {
+    let small = if img.width() > DETECTION_WIDTH {
+        img.resize(DETECTION_WIDTH, u32::MAX, FilterType::Triangle).into_rgb8()
+    } else {
+        img.to_rgb8()
+    };
+    let gray = DynamicImage::ImageRgb8(small.clone()).into_luma8();
+    let gradients = sobel_gradients(&gray);
+    let flat = GrayImage::from_fn(gray.width(), gray.height(), |x, y| {
+        Luma([if gradients.get_pixel(x, y)[0] < FLAT_GRADIENT { 255 } else { 0 }])
+    });
+    let labels = connected_components(&flat, Connectivity::Four, Luma([0u8]));
+
+    let count = labels.pixels().map(|p| p[0]).max().unwrap_or(0) as usize;
+    let mut bounds = vec![(u32::MAX, u32::MAX, 0u32, 0u32, 0u64); count + 1];
+    for (x, y, l) in labels.enumerate_pixels() {
+        let b = &mut bounds[l[0] as usize];
+        b.0 = b.0.min(x);
+        b.1 = b.1.min(y);
+        b.2 = b.2.max(x);
+        b.3 = b.3.max(y);
+        b.4 += 1;
+    }
+
+    let min_side = (small.width() / 60).max(4);
+    let mut candidates: Vec<(u32, u32, u32, u32)> = bounds
+        .iter()
+        .skip(1)
+        .filter_map(|&(x0, y0, x1, y1, area)| {
+            let (w, h) = (x1.checked_sub(x0)? + 1, y1.checked_sub(y0)? + 1);
+            let aspect = w as f32 / h as f32;
+            let fill = area as f32 / (w * h) as f32;
+            (w >= min_side && h >= min_side && (0.7..1.4).contains(&aspect) && fill > 0.8).then_some((x0, y0, w, h))
+        })
+        .collect();
+    if candidates.len() < 24 {
+        return None;
+    }
+
+    // Keep the 24 patches closest to the median size.
+    candidates.sort_by_key(|c| c.2 * c.3);
+    let median = candidates[candidates.len() / 2].2 * candidates[candidates.len() / 2].3;
+    candidates.sort_by_key(|c| (c.2 * c.3).abs_diff(median));
+    candidates.truncate(24);
+
+    let patches: Vec<Patch> = candidates
+        .iter()
+        .map(|&(x0, y0, w, h)| {
+            // Sample the inner half to stay clear of borders.
+            let (ix, iy, iw, ih) = (x0 + w / 4, y0 + h / 4, (w / 2).max(1), (h / 2).max(1));
+            let mut sum = [0f32; 3];
+            for y in iy..iy + ih {
+                for x in ix..ix + iw {
+                    let p = small.get_pixel(x, y);
+                    (0..3).for_each(|c| sum[c] += p[c] as f32);
+                }
+            }
+            let n = (iw * ih) as f32;
+            Patch { center: (x0 as f32 + w as f32 / 2.0, y0 as f32 + h as f32 / 2.0), color: sum.map(|s| s / n) }
+        })
+        .collect();
+
+    order_patches(&patches)
+}
+
+fn order_patches(patches: &[Patch]) -> Option<[[f32; 3]; 24]> {
+    let n = patches.len() as f32;
+    let mx = patches.iter().map(|p| p.center.0).sum::<f32>() / n;
+    let my = patches.iter().map(|p| p.center.1).sum::<f32>() / n;
+    let (mut sxx, mut sxy, mut syy) = (0f32, 0f32, 0f32);
+    for p in patches {
+        let (dx, dy) = (p.center.0 - mx, p.center.1 - my);
+        sxx += dx * dx;
+        sxy += dx * dy;
+        syy += dy * dy;
+    }
+    // Principal axis of the centers runs along the six columns.
+    let angle = 0.5 * (2.0 * sxy).atan2(sxx - syy);
+    let (u, v) = ((angle.cos(), angle.sin()), (-angle.sin(), angle.cos()));
+    let project = |p: &Patch, axis: (f32, f32)| (p.center.0 - mx) * axis.0 + (p.center.1 - my) * axis.1;
+
+    let mut by_u: Vec<&Patch> = patches.iter().collect();
+    by_u.sort_by(|a, b| project(a, u).total_cmp(&project(b, u)));
+    let mut grid = [[[0f32; 3]; CHART_COLUMNS]; CHART_ROWS];
+    for (col, column) in by_u.chunks(CHART_ROWS).enumerate() {
+        let mut column: Vec<&&Patch> = column.iter().collect();
+        column.sort_by(|a, b| project(a, v).total_cmp(&project(b, v)));
+        for (row, p) in column.iter().enumerate() {
+            grid[row][col] = p.color;
+        }
+    }
+
+    let reference_luma: Vec<f32> = COLOR_CHECKER_SRGB.iter().map(|c| luma(c[0], c[1], c[2]) as f32).collect();
+    let mut best: Option<(f32, [[f32; 3]; 24])> = None;
+    for (flip_rows, flip_cols) in [(false, false), (false, true), (true, false), (true, true)] {
+        let mut ordered = [[0f32; 3]; 24];
+        for row in 0..CHART_ROWS {
+            for col in 0..CHART_COLUMNS {
+                let r = if flip_rows { CHART_ROWS - 1 - row } else { row };
+                let c = if flip_cols { CHART_COLUMNS - 1 - col } else { col };
+                ordered[row * CHART_COLUMNS + col] = grid[r][c];
+            }
+        }
+        let score: f32 = ordered
+            .iter()
+            .zip(&reference_luma)
+            .map(|(p, r)| (p[0] * LUMA_R + p[1] * LUMA_G + p[2] * LUMA_B) * r)
+            .sum();
+        if best.as_ref().is_none_or(|b| score > b.0) {
+            best = Some((score, ordered));
+        }
+    }
+    best.map(|b| b.1)
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ColorCalibrationNodeConfig {
+    /// Matrix to start with, e.g. from a previous calibration run.
+    pub matrix: Option<ColorCorrectionMatrix>,
+    /// Keep searching for the chart and update the matrix on every frame, not just until the first hit.
+    pub continuous: bool,
+    /// Only emit the matrix instead of also correcting frames.
+    pub emit_only: bool,
+}
+
+impl Validate for ColorCalibrationNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        ensure(self.matrix.is_none_or(|m| m.iter().all(|v| v.is_finite())), "matrix", "must be finite")
+    }
+}
+
+config_builder!(ColorCalibrationNodeConfig for ColorCalibrationNode {
+    matrix: Option<ColorCorrectionMatrix>,
+    continuous: bool,
+    emit_only: bool,
+});
+
+/// Detects a ColorChecker chart, derives a correction matrix and applies it to frames.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct ColorCalibrationNode {
+    #[output]
+    pub output: Output<DynamicImage>,
+
+    #[output]
+    pub matrix: Output<ColorCorrectionMatrix>,
+
+    #[input]
+    pub input: Input<DynamicImage>,
+
+    pub config: ColorCalibrationNodeConfig,
+
+    #[serde(skip)]
+    current: Option<ColorCorrectionMatrix>,
+}
+
+impl ColorCalibrationNode {
+    pub fn new(config: ColorCalibrationNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            matrix: Output::new(change_observer),
+            input: Input::new(),
+            current: config.matrix,
+            config,
+        }
+    }
+}
+
+impl Node for ColorCalibrationNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        if let Ok(img) = self.input.next() {
+            if self.current.is_none() || self.config.continuous {
+                if let Some(m) = detect_color_checker(&img).and_then(|p| compute_correction_matrix(&p)) {
+                    self.current = Some(m);
+                    self.matrix.send(m).map_err(|e| UpdateError::Other(e.into()))?;
+                }
+            }
+
+            if !self.config.emit_only {
+                let corrected = match &self.current {
+                    Some(m) => DynamicImage::ImageRgb8(apply_correction_matrix(&img.to_rgb8(), m)),
+                    None => img,
+                };
+                self.output.send(corrected).map_err(|e| UpdateError::Other(e.into()))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Color lookup table as defined by the Adobe `.cube` format.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Lut {
+    /// Per-channel curves with `table.len()` entries.
+    OneD { table: Vec<[f32; 3]>, domain: ([f32; 3], [f32; 3]) },
+    /// Lattice of `size³` entries with red varying fastest.
+    ThreeD { size: usize, table: Vec<[f32; 3]>, domain: ([f32; 3], [f32; 3]) },
+}
+
+/// Parses a `.cube` file holding either a 1D or a 3D table.
+pub fn parse_cube(text: &str) -> Result<Lut, anyhow::Error> {
+    let (mut size_1d, mut size_3d) = (None, None);
+    let mut domain = ([0.0f32; 3], [1.0f32; 3]);
+    let mut table = Vec::new();
+    let triple = |values: &[&str]| -> Result<[f32; 3], anyhow::Error> {
+        match values {
+            [r, g, b] => Ok([r.parse()?, g.parse()?, b.parse()?]),
+            _ => Err(anyhow!("Expected three values, got {}", values.len())),
+        }
+    };
+
+    for line in text.lines().map(str::trim).filter(|l| !l.is_empty() && !l.starts_with('#')) {
+        let mut words = line.split_whitespace();
+        let keyword = words.next().unwrap_or_default();
+        let rest: Vec<&str> = words.collect();
+        match keyword {
+            "TITLE" => {}
+            "LUT_1D_SIZE" => size_1d = Some(rest.first().ok_or_else(|| anyhow!("Missing LUT_1D_SIZE"))?.parse::<usize>()?),
+            "LUT_3D_SIZE" => size_3d = Some(rest.first().ok_or_else(|| anyhow!("Missing LUT_3D_SIZE"))?.parse::<usize>()?),
+            "DOMAIN_MIN" => domain.0 = triple(&rest)?,
+            "DOMAIN_MAX" => domain.1 = triple(&rest)?,
+            _ => {
+                let values: Vec<&str> = line.split_whitespace().collect();
+                table.push(triple(&values).map_err(|e| anyhow!("Invalid LUT line '{}': {}", line, e))?);
+            }
+        }
+    }
+
+    match (size_1d, size_3d) {
+        (Some(size), None) if size >= 2 && table.len() == size => Ok(Lut::OneD { table, domain }),
+        (None, Some(size)) if size >= 2 && table.len() == size * size * size => Ok(Lut::ThreeD { size, table, domain }),
+        (None, None) => Err(anyhow!("Missing LUT_1D_SIZE or LUT_3D_SIZE")),
+        _ => Err(anyhow!("LUT size does not match its {} entries", table.len())),
+    }
+}
+
+impl Lut {
+    /// Looks up a color in `0.0..=1.0`, interpolating linearly between entries.
+    pub fn apply(&self, rgb: [f32; 3]) -> [f32; 3] {
+        let normalize = |domain: &([f32; 3], [f32; 3]), c: usize| {
+            ((rgb[c] - domain.0[c]) / (domain.1[c] - domain.0[c]).max(1e-6)).clamp(0.0, 1.0)
+        };
+        match self {
+            Lut::OneD { table, domain } => [0, 1, 2].map(|c| {
+                let pos = normalize(domain, c) * (table.len() - 1) as f32;
+                let i = (pos as usize).min(table.len() - 2);
+                let f = pos - i as f32;
+                table[i][c] * (1.0 - f) + table[i + 1][c] * f
+            }),
+            Lut::ThreeD { size, table, domain } => {
+                let pos = [0, 1, 2].map(|c| normalize(domain, c) * (size - 1) as f32);
+                let base = pos.map(|p| (p as usize).min(size - 2));
+                let frac = [0, 1, 2].map(|c| pos[c] - base[c] as f32);
+                let at = |r: usize, g: usize, b: usize| table[(base[2] + b) * size * size + (base[1] + g) * size + base[0] + r];
+                let mut out = [0.0; 3];
+                for corner in 0..8 {
+                    let (r, g, b) = (corner & 1, (corner >> 1) & 1, (corner >> 2) & 1);
+                    let weight = [r, g, b].iter().zip(frac).map(|(&o, f)| if o == 1 { f } else { 1.0 - f }).product::<f32>();
+                    let entry = at(r, g, b);
+                    (0..3).for_each(|c| out[c] += weight * entry[c]);
+                }
+                out
+            }
+        }
+    }
+}
+
+pub fn apply_lut(img: &DynamicImage, lut: &Lut) -> DynamicImage {
+    let mut rgb = img.to_rgb32f();
+    for px in rgb.pixels_mut() {
+        px.0 = lut.apply(px.0);
+    }
+    match_format(rgb, img)
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct LutNodeConfig {
+    /// `.cube` file with a 1D or 3D table.
+    pub path: PathBuf,
+}
+
+impl Validate for LutNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        ensure(!self.path.as_os_str().is_empty(), "path", "must be set")
+    }
+}
+
+config_builder!(LutNodeConfig for LutNode { path: PathBuf });
+
+/// Color grading with a `.cube` LUT; a path sent on `reload` swaps the table at runtime.
+///
+/// A table that fails to load on `reload` is reported on `errors` and the
+/// previous one stays in effect.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct LutNode {
+    #[output]
+    pub output: Output<DynamicImage>,
+
+    #[output]
+    pub errors: Output<String>,
+
+    #[input]
+    pub input: Input<DynamicImage>,
+
+    #[input]
+    pub reload: Input<PathBuf>,
+
+    pub config: LutNodeConfig,
+
+    #[serde(skip)]
+    lut: Option<Lut>,
+}
+
+impl LutNode {
+    pub fn new(config: LutNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            errors: Output::new(change_observer),
+            input: Input::new(),
+            reload: Input::new(),
+            config,
+            lut: None,
+        }
+    }
+}
+
+fn load_cube(path: &Path) -> Result<Lut, anyhow::Error> {
+    parse_cube(&std::fs::read_to_string(path)?)
+}
+
+impl Node for LutNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        while let Ok(path) = self.reload.next() {
+            match load_cube(&path) {
+                Ok(lut) => {
+                    self.config.path = path;
+                    self.lut = Some(lut);
+                }
+                Err(e) => {
+                    let message = format!("{}: {}", path.display(), e);
+                    self.errors.send(message).map_err(|e| UpdateError::Other(e.into()))?;
+                }
+            }
+        }
+        if self.lut.is_none() {
+            self.lut = Some(load_cube(&self.config.path).map_err(UpdateError::Other)?);
+        }
+
+        if let Ok(img) = self.input.next() {
+            frame_span!("lut", width = img.width(), height = img.height());
+            let graded = apply_lut(&img, self.lut.as_ref().expect("loaded above"));
+            self.output.send(graded).map_err(|e| UpdateError::Other(e.into()))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub enum PaletteMethod {
+    /// Splits the color box with the widest range at its median until enough boxes exist.
+    #[default]
+    MedianCut,
+    /// Neural network quantizer; `sample_factor` 1 is slowest and best, 30 fastest.
+    NeuQuant { sample_factor: i32 },
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct QuantizeNodeConfig {
+    /// Palette size, at most 256.
+    pub colors: usize,
+    pub method: PaletteMethod,
+    /// Floyd–Steinberg error diffusion, trading noise for smoother gradients.
+    pub dither: bool,
+}
+
+impl Default for QuantizeNodeConfig {
+    fn default() -> Self {
+        Self { colors: 16, method: PaletteMethod::MedianCut, dither: false }
+    }
+}
+
+impl Validate for QuantizeNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        ensure((2..=256).contains(&self.colors), "colors", "must be between 2 and 256")
+    }
+}
+
+config_builder!(QuantizeNodeConfig for QuantizeNode { colors: usize, method: PaletteMethod, dither: bool });
+
+/// Palettized image, one index per pixel in row-major order.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct IndexedImage {
+    pub width: u32,
+    pub height: u32,
+    pub palette: Vec<[u8; 3]>,
+    pub indices: Vec<u8>,
+}
+
+impl IndexedImage {
+    pub fn to_rgb(&self) -> RgbImage {
+        let pixels = self.indices.iter().flat_map(|&i| self.palette[i as usize]).collect();
+        RgbImage::from_raw(self.width, self.height, pixels).expect("indices match the image size")
+    }
+}
+
+/// Median cut palette of at most `colors` entries.
+pub fn median_cut(img: &RgbImage, colors: usize) -> Vec<[u8; 3]> {
+    let mut boxes: Vec<Vec<[u8; 3]>> = vec![img.pixels().map(|p| p.0).collect()];
+    while boxes.len() < colors.clamp(1, 256) {
+        let range = |b: &Vec<[u8; 3]>, c: usize| {
+            let (lo, hi) = b.iter().fold((255, 0), |(lo, hi), p| (p[c].min(lo), p[c].max(hi)));
+            hi.saturating_sub(lo)
+        };
+        let Some((i, channel)) = boxes
+            .iter()
+            .enumerate()
+            .filter(|(_, b)| b.len() > 1)
+            .flat_map(|(i, b)| (0..3).map(move |c| (i, c, range(b, c))))
+            .filter(|(_, _, r)| *r > 0)
+            .max_by_key(|(_, _, r)| *r)
+            .map(|(i, c, _)| (i, c))
+        else { break };
+        let mut split = boxes.swap_remove(i);
+        split.sort_unstable_by_key(|p| p[channel]);
+        let upper = split.split_off(split.len() / 2);
+        boxes.push(split);
+        boxes.push(upper);
+    }
+    boxes
+        .iter()
+        .filter(|b| !b.is_empty())
+        .map(|b| {
+            let mut sum = [0u64; 3];
+            b.iter().for_each(|p| (0..3).for_each(|c| sum[c] += p[c] as u64));
+            sum.map(|s| (s / b.len() as u64) as u8)
+        })
+        .collect()
+}
+
+fn nearest(palette: &[[u8; 3]], rgb: [f32; 3]) -> usize {
+    let distance = |p: &[u8; 3]| (0..3).map(|c| (p[c] as f32 - rgb[c]).powi(2)).sum::<f32>();
+    (0..palette.len()).min_by(|&a, &b| distance(&palette[a]).total_cmp(&distance(&palette[b]))).unwrap_or(0)
+}
+
+/// Maps every pixel to its nearest palette entry, diffusing the error to unvisited neighbours if `dither` is set.
+pub fn quantize(img: &RgbImage, palette: Vec<[u8; 3]>, dither: bool) -> IndexedImage {
+    let (width, height) = img.dimensions();
+    let mut errors = vec![[0f32; 3]; (width * height) as usize];
+    let mut indices = Vec::with_capacity(errors.len());
+    for y in 0..height {
+        for x in 0..width {
+            let i = (y * width + x) as usize;
+            let px = img.get_pixel(x, y);
+            let wanted = [0, 1, 2].map(|c| (px[c] as f32 + errors[i][c]).clamp(0.0, 255.0));
+            let index = nearest(&palette, wanted);
+            indices.push(index as u8);
+            if !dither {
+                continue;
+            }
+            let error = [0, 1, 2].map(|c| wanted[c] - palette[index][c] as f32);
+            for (dx, dy, weight) in [(1i64, 0i64, 7.0), (-1, 1, 3.0), (0, 1, 5.0), (1, 1, 1.0)] {
+                let (nx, ny) = (x as i64 + dx, y as i64 + dy);
+                if nx >= 0 && nx < width as i64 && ny < height as i64 {
+                    let n = (ny as u32 * width + nx as u32) as usize;
+                    (0..3).for_each(|c| errors[n][c] += error[c] * weight / 16.0);
+                }
+            }
+        }
+    }
+    IndexedImage { width, height, palette, indices }
+}
+
+/// Reduces frames to a small palette, e.g. for e-ink displays or GIF encoding.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct QuantizeNode {
+    /// Palettized preview.
+    #[output]
+    pub output: Output<DynamicImage>,
+
+    #[output]
+    pub indexed: Output<IndexedImage>,
+
+    #[input]
+    pub input: Input<DynamicImage>,
+
+    pub config: QuantizeNodeConfig,
+}
+
+impl QuantizeNode {
+    pub fn new(config: QuantizeNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            indexed: Output::new(change_observer),
+            input: Input::new(),
+            config,
+        }
+    }
+}
+
+impl Node for QuantizeNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        if let Ok(img) = self.input.next() {
+            frame_span!("quantize", width = img.width(), height = img.height());
+            let colors = self.config.colors.clamp(1, 256);
+            let palette = match self.config.method {
+                PaletteMethod::MedianCut => median_cut(&img.to_rgb8(), colors),
+                PaletteMethod::NeuQuant { sample_factor } => {
+                    let nq = color_quant::NeuQuant::new(sample_factor.clamp(1, 30), colors, img.to_rgba8().as_raw());
+                    nq.color_map_rgb().chunks_exact(3).map(|c| [c[0], c[1], c[2]]).collect()
+                }
+            };
+            let indexed = quantize(&img.to_rgb8(), palette, self.config.dither);
+            self.output.send(DynamicImage::ImageRgb8(indexed.to_rgb())).map_err(|e| UpdateError::Other(e.into()))?;
+            self.indexed.send(indexed).map_err(|e| UpdateError::Other(e.into()))?;
+        }
+        Ok(())
+    }
+}
+
+/// Converts a floating point RGBA result back to the pixel layout of `like`, keeping alpha and bit depth.
+fn match_alpha_format(result: Rgba32FImage, like: &DynamicImage) -> DynamicImage {
+    let result = DynamicImage::ImageRgba32F(result);
+    match like {
+        DynamicImage::ImageLumaA8(_) => DynamicImage::ImageLumaA8(result.into_luma_alpha8()),
+        DynamicImage::ImageRgba8(_) => DynamicImage::ImageRgba8(result.into_rgba8()),
+        DynamicImage::ImageLumaA16(_) => DynamicImage::ImageLumaA16(result.into_luma_alpha16()),
+        DynamicImage::ImageRgba16(_) => DynamicImage::ImageRgba16(result.into_rgba16()),
+        DynamicImage::ImageRgba32F(_) => result,
+        _ => DynamicImage::ImageRgba8(result.into_rgba8()),
+    }
+}
+
+/// Color and alpha of an image, the alpha as a grayscale mask of the same bit depth.
+pub fn split_alpha(img: &DynamicImage) -> (DynamicImage, DynamicImage) {
+    match img {
+        DynamicImage::ImageLumaA8(_) | DynamicImage::ImageRgba8(_) | DynamicImage::ImageLuma8(_) | DynamicImage::ImageRgb8(_) => {
+            let rgba = img.to_rgba8();
+            let alpha = GrayImage::from_fn(rgba.width(), rgba.height(), |x, y| Luma([rgba.get_pixel(x, y)[3]]));
+            (DynamicImage::ImageRgb8(img.to_rgb8()), DynamicImage::ImageLuma8(alpha))
+        }
+        _ => {
+            let rgba = img.to_rgba16();
+            let alpha = image::ImageBuffer::from_fn(rgba.width(), rgba.height(), |x, y| Luma([rgba.get_pixel(x, y)[3]]));
+            (DynamicImage::ImageRgb16(img.to_rgb16()), DynamicImage::ImageLuma16(alpha))
+        }
+    }
+}
+
+/// Combines color with an alpha mask, scaling the mask to the color image if sizes differ.
+pub fn merge_alpha(color: &DynamicImage, alpha: &DynamicImage) -> DynamicImage {
+    let alpha = match alpha.width() == color.width() && alpha.height() == color.height() {
+        true => alpha.to_luma32f(),
+        false => alpha.resize_exact(color.width(), color.height(), FilterType::Triangle).to_luma32f(),
+    };
+    let rgb: Rgb32FImage = color.to_rgb32f();
+    let merged = Rgba32FImage::from_fn(rgb.width(), rgb.height(), |x, y| {
+        let [r, g, b] = rgb.get_pixel(x, y).0;
+        image::Rgba([r, g, b, alpha.get_pixel(x, y)[0]])
+    });
+    match color {
+        DynamicImage::ImageLuma16(_) | DynamicImage::ImageRgb16(_) | DynamicImage::ImageRgba16(_) => {
+            DynamicImage::ImageRgba16(DynamicImage::ImageRgba32F(merged).into_rgba16())
+        }
+        DynamicImage::ImageRgb32F(_) | DynamicImage::ImageRgba32F(_) => DynamicImage::ImageRgba32F(merged),
+        _ => DynamicImage::ImageRgba8(DynamicImage::ImageRgba32F(merged).into_rgba8()),
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum AlphaConversion {
+    /// Straight to premultiplied: color is scaled by alpha.
+    #[default]
+    Premultiply,
+    /// Premultiplied to straight; fully transparent pixels become black.
+    Unpremultiply,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct PremultiplyNodeConfig {
+    pub conversion: AlphaConversion,
+}
+
+impl Validate for PremultiplyNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        Ok(())
+    }
+}
+
+config_builder!(PremultiplyNodeConfig for PremultiplyNode { conversion: AlphaConversion });
+
+pub fn convert_alpha(img: &DynamicImage, conversion: AlphaConversion) -> DynamicImage {
+    let mut rgba = img.to_rgba32f();
+    for px in rgba.pixels_mut() {
+        let alpha = px[3];
+        for c in 0..3 {
+            px[c] = match conversion {
+                AlphaConversion::Premultiply => px[c] * alpha,
+                AlphaConversion::Unpremultiply if alpha > 0.0 => (px[c] / alpha).min(1.0),
+                AlphaConversion::Unpremultiply => 0.0,
+            };
+        }
+    }
+    match_alpha_format(rgba, img)
+}
+
+/// Splits an image into its color and an alpha mask, e.g. to filter them separately.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct AlphaSplitNode {
+    #[output]
+    pub color: Output<DynamicImage>,
+
+    #[output]
+    pub alpha: Output<DynamicImage>,
+
+    #[input]
+    pub input: Input<DynamicImage>,
+}
+
+impl AlphaSplitNode {
+    pub fn new(change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            color: Output::new(change_observer),
+            alpha: Output::new(change_observer),
+            input: Input::new(),
+        }
+    }
+}
+
+impl Node for AlphaSplitNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        if let Ok(img) = self.input.next() {
+            let (color, alpha) = split_alpha(&img);
+            self.color.send(color).map_err(|e| UpdateError::Other(e.into()))?;
+            self.alpha.send(alpha).map_err(|e| UpdateError::Other(e.into()))?;
+        }
+        Ok(())
+    }
+}
+
+/// Frames kept per input of [`AlphaMergeNode`] while waiting for a partner; older ones are dropped.
+const MAX_PENDING: usize = 8;
+
+/// Combines color frames with alpha masks, pairing them in arrival order.
+///
+/// If one input stalls, only the latest frames of the other are kept.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct AlphaMergeNode {
+    #[output]
+    pub output: Output<DynamicImage>,
+
+    #[input]
+    pub color: Input<DynamicImage>,
+
+    #[input]
+    pub alpha: Input<DynamicImage>,
+
+    #[serde(skip)]
+    colors: VecDeque<DynamicImage>,
+    #[serde(skip)]
+    masks: VecDeque<DynamicImage>,
+}
+
+impl AlphaMergeNode {
+    pub fn new(change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            color: Input::new(),
+            alpha: Input::new(),
+            colors: VecDeque::new(),
+            masks: VecDeque::new(),
+        }
+    }
+}
+
+impl Node for AlphaMergeNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        while let Ok(img) = self.color.next() {
+            push_bounded(&mut self.colors, img, MAX_PENDING, OverflowPolicy::DropOldest);
+        }
+        while let Ok(mask) = self.alpha.next() {
+            push_bounded(&mut self.masks, mask, MAX_PENDING, OverflowPolicy::DropOldest);
+        }
+        while !self.colors.is_empty() && !self.masks.is_empty() {
+            let (color, mask) = (self.colors.pop_front().expect("checked"), self.masks.pop_front().expect("checked"));
+            self.output.send(merge_alpha(&color, &mask)).map_err(|e| UpdateError::Other(e.into()))?;
+        }
+        Ok(())
+    }
+}
+
+/// Converts between straight and premultiplied alpha.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct PremultiplyNode {
+    #[output]
+    pub output: Output<DynamicImage>,
+
+    #[input]
+    pub input: Input<DynamicImage>,
+
+    pub config: PremultiplyNodeConfig,
+}
+
+impl PremultiplyNode {
+    pub fn new(config: PremultiplyNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            input: Input::new(),
+            config,
+        }
+    }
+}
+
+impl Node for PremultiplyNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        if let Ok(img) = self.input.next() {
+            self.output.send(convert_alpha(&img, self.config.conversion)).map_err(|e| UpdateError::Other(e.into()))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum Colormap {
+    #[default]
+    Viridis,
+    Inferno,
+    Jet,
+    Turbo,
+}
+
+/// Palettes sampled at nine evenly spaced points, interpolated linearly in between.
+const VIRIDIS: [[u8; 3]; 9] = [
+    [68, 1, 84], [71, 44, 122], [59, 81, 139], [44, 113, 142], [33, 144, 141],
+    [39, 173, 129], [92, 200, 99], [170, 220, 50], [253, 231, 37],
+];
+const INFERNO: [[u8; 3]; 9] = [
+    [0, 0, 4], [31, 12, 72], [85, 15, 109], [136, 34, 106], [186, 54, 85],
+    [227, 89, 51], [249, 140, 10], [249, 201, 50], [252, 255, 164],
+];
+const TURBO: [[u8; 3]; 9] = [
+    [48, 18, 59], [70, 107, 227], [40, 187, 236], [49, 242, 153], [162, 252, 60],
+    [237, 208, 58], [251, 128, 34], [208, 47, 5], [122, 4, 3],
+];
+
+impl Colormap {
+    /// Color for `t` in `0.0..=1.0`.
+    pub fn color(&self, t: f32) -> [u8; 3] {
+        let t = if t.is_nan() { 0.0 } else { t.clamp(0.0, 1.0) };
+        let table = match self {
+            Colormap::Viridis => &VIRIDIS,
+            Colormap::Inferno => &INFERNO,
+            Colormap::Turbo => &TURBO,
+            Colormap::Jet => {
+                let ramp = |offset: f32| ((1.5 - (4.0 * t - offset).abs()).clamp(0.0, 1.0) * 255.0).round() as u8;
+                return [ramp(3.0), ramp(2.0), ramp(1.0)];
+            }
+        };
+        let pos = t * (table.len() - 1) as f32;
+        let i = (pos as usize).min(table.len() - 2);
+        let f = pos - i as f32;
+        [0, 1, 2].map(|c| (table[i][c] as f32 * (1.0 - f) + table[i + 1][c] as f32 * f).round() as u8)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub enum ColormapRange {
+    /// Values in the units of the input: `0..=255` for 8-bit, `0..=65535` for 16-bit, as-is for floats.
+    Fixed { min: f32, max: f32 },
+    /// Stretches the minimum and maximum of each frame over the palette.
+    #[default]
+    Auto,
+    /// Like `Auto` but between two percentiles, ignoring outliers such as hot pixels.
+    Percentile { low: f32, high: f32 },
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ColormapNodeConfig {
+    pub colormap: Colormap,
+    pub range: ColormapRange,
+}
+
+impl Validate for ColormapNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        match self.range {
+            ColormapRange::Fixed { min, max } => ensure(min < max, "range", "min must be below max"),
+            ColormapRange::Percentile { low, high } => ensure(0.0 <= low && low < high && high <= 100.0, "range", "percentiles must satisfy 0 <= low < high <= 100"),
+            ColormapRange::Auto => Ok(()),
+        }
+    }
+}
+
+config_builder!(ColormapNodeConfig for ColormapNode { colormap: Colormap, range: ColormapRange });
+
+/// Value range a [`ColormapRange`] resolves to for the given samples; NaNs are ignored.
+pub fn resolve_range(values: &[f32], range: ColormapRange) -> (f32, f32) {
+    let percentile = |low: f32, high: f32| {
+        let mut sorted: Vec<f32> = values.iter().copied().filter(|v| !v.is_nan()).collect();
+        if sorted.is_empty() {
+            return (0.0, 1.0);
+        }
+        sorted.sort_by(f32::total_cmp);
+        let at = |p: f32| sorted[((p.clamp(0.0, 100.0) / 100.0) * (sorted.len() - 1) as f32).round() as usize];
+        (at(low), at(high))
+    };
+    match range {
+        ColormapRange::Fixed { min, max } => (min, max),
+        ColormapRange::Auto => percentile(0.0, 100.0),
+        ColormapRange::Percentile { low, high } => percentile(low, high),
+    }
+}
+
+/// Maps row-major single-channel values to false color.
+pub fn apply_colormap(values: &[f32], width: u32, height: u32, colormap: Colormap, range: ColormapRange) -> RgbImage {
+    let (min, max) = resolve_range(values, range);
+    let span = (max - min).abs().max(f32::EPSILON) * (max - min).signum();
+    let pixels = values.iter().flat_map(|v| colormap.color((v - min) / span)).collect();
+    RgbImage::from_raw(width, height, pixels).expect("one value per pixel")
+}
+
+fn channel_values(img: &DynamicImage) -> Vec<f32> {
+    match img {
+        DynamicImage::ImageLuma16(_) | DynamicImage::ImageLumaA16(_) | DynamicImage::ImageRgb16(_) | DynamicImage::ImageRgba16(_) => {
+            img.to_luma16().into_raw().into_iter().map(|v| v as f32).collect()
+        }
+        DynamicImage::ImageRgb32F(_) | DynamicImage::ImageRgba32F(_) => img.to_luma32f().into_raw(),
+        _ => img.to_luma8().into_raw().into_iter().map(|v| v as f32).collect(),
+    }
+}
+
+/// False-color visualization of single-channel data such as depth maps, heatmaps or temperatures.
+///
+/// Accepts images on `input`, color images being reduced to luma first, and
+/// raw values such as the output of a thermal camera on `values`.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct ColormapNode {
+    #[output]
+    pub output: Output<DynamicImage>,
+
+    #[input]
+    pub input: Input<DynamicImage>,
+
+    #[input]
+    pub values: Input<Array2<f32>>,
+
+    pub config: ColormapNodeConfig,
+}
+
+impl ColormapNode {
+    pub fn new(config: ColormapNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            input: Input::new(),
+            values: Input::new(),
+            config,
+        }
+    }
+}
+
+impl Node for ColormapNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        if let Ok(img) = self.input.next() {
+            let colored = apply_colormap(&channel_values(&img), img.width(), img.height(), self.config.colormap, self.config.range);
+            self.output.send(DynamicImage::ImageRgb8(colored)).map_err(|e| UpdateError::Other(e.into()))?;
+        }
+        if let Ok(values) = self.values.next() {
+            let (rows, cols) = values.dim();
+            let data: Vec<f32> = values.iter().copied().collect();
+            let colored = apply_colormap(&data, cols as u32, rows as u32, self.config.colormap, self.config.range);
+            self.output.send(DynamicImage::ImageRgb8(colored)).map_err(|e| UpdateError::Other(e.into()))?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,217 @@
+use flowrs::{node::{Node, UpdateError, ChangeObserver}, connection::{Input, Output}};
+use flowrs::RuntimeConnectable;
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+use anyhow::anyhow;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum ConfigFormat {
+    Json,
+    #[cfg(feature = "toml")]
+    Toml,
+}
+
+impl ConfigFormat {
+    /// Format from the file extension.
+    pub fn of(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()?.to_ascii_lowercase().as_str() {
+            "json" => Some(ConfigFormat::Json),
+            #[cfg(feature = "toml")]
+            "toml" => Some(ConfigFormat::Toml),
+            _ => None,
+        }
+    }
+}
+
+/// Parses a config document, optionally selecting a part of it by JSON pointer, e.g. `/encoder`.
+pub fn parse_config<C: DeserializeOwned>(text: &str, format: ConfigFormat, pointer: Option<&str>) -> Result<C, anyhow::Error> {
+    let document: Value = match format {
+        ConfigFormat::Json => serde_json::from_str(text)?,
+        #[cfg(feature = "toml")]
+        ConfigFormat::Toml => toml::from_str(text)?,
+    };
+    let section = match pointer {
+        Some(pointer) => document.pointer(pointer).ok_or_else(|| anyhow!("No config at {}", pointer))?.clone(),
+        None => document,
+    };
+    Ok(serde_json::from_value(section)?)
+}
+
+/// A config value a node would fail on, found before the node is built.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConfigError {
+    /// Path of the offending field, e.g. `source.width`.
+    pub field: String,
+    pub reason: String,
+}
+
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Invalid {}: {}", self.field, self.reason)
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// Fails for `field` with `reason` unless `ok`.
+pub fn ensure(ok: bool, field: &str, reason: &str) -> Result<(), ConfigError> {
+    if ok { Ok(()) } else { Err(ConfigError { field: field.to_string(), reason: reason.to_string() }) }
+}
+
+/// Node configs that can be checked up front, so a flow fails when it is
+/// built rather than on the first frame.
+pub trait Validate {
+    fn validate(&self) -> Result<(), ConfigError>;
+
+    /// The config itself if it is valid, for `XNode::new(config.validated()?, ..)`.
+    fn validated(self) -> Result<Self, ConfigError>
+    where Self: Sized {
+        self.validate()?;
+        Ok(self)
+    }
+
+    /// Validates a config nested under `field`, prefixing the field path of errors.
+    fn validate_in(&self, field: &str) -> Result<(), ConfigError> {
+        self.validate().map_err(|e| ConfigError { field: format!("{}.{}", field, e.field), ..e })
+    }
+}
+
+/// A node config under construction, started from its defaults by `XNodeConfig::builder()`.
+#[derive(Clone, Debug)]
+pub struct ConfigBuilder<C> {
+    pub(crate) config: C,
+}
+
+impl<C> ConfigBuilder<C> {
+    pub fn new(config: C) -> Self {
+        Self { config }
+    }
+}
+
+impl<C: Validate> ConfigBuilder<C> {
+    /// The config, if it is valid.
+    pub fn build(self) -> Result<C, ConfigError> {
+        self.config.validated()
+    }
+}
+
+/// Takes the configs queued on a node's `reconfigure` input, in order, into `config`.
+///
+/// A config failing [`Validate`] is skipped, so the one before it stays in effect;
+/// the error of the last such config is returned once the queue is drained.
+pub fn apply_configs<C: Validate>(input: &mut Input<C>, config: &mut C) -> Result<(), ConfigError> {
+    let mut result = Ok(());
+    while let Ok(next) = input.next() {
+        match next.validate() {
+            Ok(()) => *config = next,
+            Err(e) => result = Err(e),
+        }
+    }
+    result
+}
+
+impl<T: Validate> Validate for Option<T> {
+    fn validate(&self) -> Result<(), ConfigError> {
+        self.as_ref().map_or(Ok(()), T::validate)
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ConfigFileWatcherNodeConfig {
+    pub path: PathBuf,
+    pub poll_interval_ms: u64,
+    /// Section of the file holding the config, so one file can configure several nodes.
+    pub pointer: Option<String>,
+}
+
+impl Default for ConfigFileWatcherNodeConfig {
+    fn default() -> Self {
+        Self { path: PathBuf::new(), poll_interval_ms: 1000, pointer: None }
+    }
+}
+
+impl Validate for ConfigFileWatcherNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        ensure(ConfigFormat::of(&self.path).is_some(), "path", "must be a JSON or TOML file")
+    }
+}
+
+config_builder!(ConfigFileWatcherNodeConfig { path: PathBuf, poll_interval_ms: u64, pointer: Option<String> });
+
+/// Sends the config parsed from a JSON or TOML file whenever the file changes.
+///
+/// Connect `output` to the `reconfigure` input of a running node, e.g. a
+/// [`crate::source::CaptureNode`] or [`crate::analysis::QualityGateNode`], to retune
+/// it without restarting the flow. A file that fails to parse is reported on
+/// `errors`, and one the node rejects as invalid fails that node's update; either
+/// way the previous config stays in effect.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct ConfigFileWatcherNode<C> {
+    #[output]
+    pub output: Output<C>,
+
+    #[output]
+    pub errors: Output<String>,
+
+    pub config: ConfigFileWatcherNodeConfig,
+
+    #[serde(skip)]
+    modified: Option<SystemTime>,
+    #[serde(skip)]
+    last_poll: Option<Instant>,
+}
+
+impl<C> ConfigFileWatcherNode<C>
+where C: DeserializeOwned + Send {
+    pub fn new(config: ConfigFileWatcherNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            errors: Output::new(change_observer),
+            config,
+            modified: None,
+            last_poll: None,
+        }
+    }
+
+    fn load(&self) -> Result<C, anyhow::Error> {
+        let format = ConfigFormat::of(&self.config.path)
+            .ok_or_else(|| anyhow!("Unsupported config file type: {}", self.config.path.display()))?;
+        let text = std::fs::read_to_string(&self.config.path)?;
+        parse_config(&text, format, self.config.pointer.as_deref())
+    }
+}
+
+impl<C> Node for ConfigFileWatcherNode<C>
+where C: DeserializeOwned + Send {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        let interval = Duration::from_millis(self.config.poll_interval_ms);
+        if self.last_poll.is_some_and(|t| t.elapsed() < interval) {
+            return Ok(());
+        }
+        self.last_poll = Some(Instant::now());
+
+        // A missing file is expected while editors replace it.
+        let Ok(modified) = std::fs::metadata(&self.config.path).and_then(|m| m.modified()) else { return Ok(()) };
+        if self.modified == Some(modified) {
+            return Ok(());
+        }
+        self.modified = Some(modified);
+
+        match self.load() {
+            Ok(config) => self.output.send(config).map_err(|e| UpdateError::Other(e.into()))?,
+            Err(e) => {
+                let message = format!("{}: {}", self.config.path.display(), e);
+                self.errors.send(message).map_err(|e| UpdateError::Other(e.into()))?
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,174 @@
+use flowrs::{node::{Node, UpdateError, ChangeObserver}, connection::{Input, Output}};
+use flowrs::RuntimeConnectable;
+
+use image::DynamicImage;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::{ensure, ConfigError, Validate};
+use crate::types::CameraControl;
+
+/// Luma values at or above this count as clipped highlights.
+const CLIP_LEVEL: usize = 250;
+
+pub fn luma_histogram(img: &DynamicImage) -> [u32; 256] {
+    let mut histogram = [0u32; 256];
+    for p in img.to_luma8().pixels() {
+        histogram[p[0] as usize] += 1;
+    }
+    histogram
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct AutoExposureNodeConfig {
+    /// Desired mean luma in `0.0..=1.0`.
+    pub target_luma: f32,
+    /// Relative luma error below which the exposure is left alone, to avoid hunting.
+    pub deadband: f32,
+    /// Fraction of the correction applied per frame in `0.0..=1.0`; lower is smoother.
+    pub damping: f32,
+    pub min_exposure_us: f32,
+    pub max_exposure_us: f32,
+    /// Exposure the camera starts with.
+    pub initial_exposure_us: f32,
+    /// Highest fraction of clipped pixels tolerated before exposing for the highlights.
+    pub max_clipped: f32,
+    /// Also derive gray-world white balance gains.
+    pub white_balance: bool,
+}
+
+impl Default for AutoExposureNodeConfig {
+    fn default() -> Self {
+        Self {
+            target_luma: 0.45,
+            deadband: 0.05,
+            damping: 0.5,
+            min_exposure_us: 100.0,
+            max_exposure_us: 20000.0,
+            initial_exposure_us: 5000.0,
+            max_clipped: 0.02,
+            white_balance: false,
+        }
+    }
+}
+
+impl Validate for AutoExposureNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        ensure((0.0..=1.0).contains(&self.target_luma), "target_luma", "must be in 0..=1")?;
+        ensure((0.0..=1.0).contains(&self.damping), "damping", "must be in 0..=1")?;
+        ensure(0.0 < self.min_exposure_us && self.min_exposure_us <= self.max_exposure_us, "min_exposure_us", "must be positive and at most max_exposure_us")?;
+        ensure((self.min_exposure_us..=self.max_exposure_us).contains(&self.initial_exposure_us), "initial_exposure_us", "must be within the exposure limits")
+    }
+}
+
+config_builder!(AutoExposureNodeConfig for AutoExposureNode {
+    target_luma: f32,
+    deadband: f32,
+    damping: f32,
+    min_exposure_us: f32,
+    max_exposure_us: f32,
+    initial_exposure_us: f32,
+    max_clipped: f32,
+    white_balance: bool,
+});
+
+/// Proportional exposure and white balance control from frame statistics.
+#[derive(Clone, Debug, Default)]
+pub struct ExposureController {
+    exposure_us: Option<f32>,
+    white_balance: Option<(f32, f32)>,
+}
+
+impl ExposureController {
+    /// Adjustments for a frame taken with the current settings, if any are needed.
+    pub fn update(&mut self, img: &DynamicImage, config: &AutoExposureNodeConfig) -> Option<CameraControl> {
+        let exposure = *self.exposure_us.get_or_insert(config.initial_exposure_us);
+        let histogram = luma_histogram(img);
+        let total = histogram.iter().sum::<u32>().max(1) as f32;
+        let mean = histogram.iter().enumerate().map(|(v, &n)| v as f32 * n as f32).sum::<f32>() / total / 255.0;
+        let clipped = histogram[CLIP_LEVEL..].iter().sum::<u32>() as f32 / total;
+
+        // A mean already on target can still hide blown highlights.
+        let error = if clipped > config.max_clipped {
+            (1.0 - (clipped - config.max_clipped)).max(0.5)
+        } else {
+            config.target_luma / mean.max(1.0 / 255.0)
+        };
+        let mut control = CameraControl::default();
+        if (error - 1.0).abs() > config.deadband {
+            let next = (exposure * error.powf(config.damping.clamp(0.0, 1.0)))
+                .clamp(config.min_exposure_us, config.max_exposure_us);
+            if next != exposure {
+                self.exposure_us = Some(next);
+                control.exposure_us = Some(next);
+            }
+        }
+
+        if config.white_balance {
+            if let Some(gains) = gray_world_gains(img) {
+                let (r, b) = self.white_balance.unwrap_or((1.0, 1.0));
+                let damping = config.damping.clamp(0.0, 1.0);
+                let smoothed = (r * gains.0.powf(damping), b * gains.1.powf(damping));
+                if (smoothed.0 / r - 1.0).abs() > config.deadband || (smoothed.1 / b - 1.0).abs() > config.deadband {
+                    self.white_balance = Some(smoothed);
+                    control.white_balance = Some(smoothed);
+                }
+            }
+        }
+
+        (control != CameraControl::default()).then_some(control)
+    }
+}
+
+/// Corrections of the current red and blue gains that make the unclipped pixels average to gray.
+fn gray_world_gains(img: &DynamicImage) -> Option<(f32, f32)> {
+    let mut sum = [0u64; 3];
+    for p in img.to_rgb8().pixels() {
+        if p.0.iter().all(|&c| (c as usize) < CLIP_LEVEL) {
+            (0..3).for_each(|c| sum[c] += p[c] as u64);
+        }
+    }
+    (sum[0] > 0 && sum[2] > 0).then(|| (sum[1] as f32 / sum[0] as f32, sum[1] as f32 / sum[2] as f32))
+}
+
+/// Closes an auto exposure and white balance loop through the flow graph.
+///
+/// Frame statistics are turned into [`CameraControl`] messages, meant for the
+/// control input of the capturing camera node. Only changes are sent.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct AutoExposureNode {
+    #[output]
+    pub output: Output<CameraControl>,
+
+    #[input]
+    pub input: Input<DynamicImage>,
+
+    pub config: AutoExposureNodeConfig,
+
+    #[serde(skip)]
+    controller: ExposureController,
+}
+
+impl AutoExposureNode {
+    pub fn new(config: AutoExposureNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            input: Input::new(),
+            config,
+            controller: ExposureController::default(),
+        }
+    }
+}
+
+impl Node for AutoExposureNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        if let Ok(img) = self.input.next() {
+            if let Some(control) = self.controller.update(&img, &self.config) {
+                self.output.send(control).map_err(|e| UpdateError::Other(e.into()))?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,573 @@
+use flowrs::{node::{Node, UpdateError, ChangeObserver}, connection::{Input, Output}};
+use flowrs::RuntimeConnectable;
+
+use std::fmt::Write;
+
+use image::{DynamicImage, Rgba, RgbaImage, imageops::FilterType};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::{ensure, ConfigError, Validate};
+use crate::transform::{encode_image, EncodeFormat};
+
+/// Dark to bright.
+const ASCII_RAMP: &[u8] = b" .:-=+*#%@";
+
+/// 3x5 glyphs for `0`-`9` and `.`, one row per 3-bit value, top row first.
+const GLYPHS: [[u8; 5]; 11] = [
+    [0b111, 0b101, 0b101, 0b101, 0b111],
+    [0b010, 0b110, 0b010, 0b010, 0b111],
+    [0b111, 0b001, 0b111, 0b100, 0b111],
+    [0b111, 0b001, 0b111, 0b001, 0b111],
+    [0b101, 0b101, 0b111, 0b001, 0b001],
+    [0b111, 0b100, 0b111, 0b001, 0b111],
+    [0b111, 0b100, 0b111, 0b101, 0b111],
+    [0b111, 0b001, 0b010, 0b010, 0b010],
+    [0b111, 0b101, 0b111, 0b101, 0b111],
+    [0b111, 0b101, 0b111, 0b001, 0b111],
+    [0b000, 0b000, 0b000, 0b000, 0b010],
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+pub struct ChannelStats {
+    pub min: f32,
+    pub max: f32,
+    pub mean: f32,
+}
+
+/// Dimensions, format and per-channel statistics of a frame, in the native value range.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct FrameSummary {
+    pub width: u32,
+    pub height: u32,
+    pub format: String,
+    pub channels: Vec<ChannelStats>,
+}
+
+pub fn summarize(img: &DynamicImage) -> FrameSummary {
+    let color = img.color();
+    let channels = color.channel_count() as usize;
+    let bytes = img.as_bytes();
+    let width = color.bytes_per_pixel() as usize / channels;
+    let samples: Box<dyn Iterator<Item = f32>> = match width {
+        1 => Box::new(bytes.iter().map(|&v| v as f32)),
+        2 => Box::new(bytes.chunks_exact(2).map(|b| u16::from_ne_bytes([b[0], b[1]]) as f32)),
+        _ => Box::new(bytes.chunks_exact(4).map(|b| f32::from_ne_bytes([b[0], b[1], b[2], b[3]]))),
+    };
+
+    let mut stats = vec![ChannelStats { min: f32::MAX, max: f32::MIN, mean: 0.0 }; channels];
+    let mut count = 0usize;
+    for (i, v) in samples.enumerate() {
+        let s = &mut stats[i % channels];
+        s.min = s.min.min(v);
+        s.max = s.max.max(v);
+        s.mean += v;
+        count += 1;
+    }
+    let pixels = (count / channels).max(1) as f32;
+    for s in &mut stats {
+        s.mean /= pixels;
+        if count == 0 {
+            *s = ChannelStats { min: 0.0, max: 0.0, mean: 0.0 };
+        }
+    }
+    FrameSummary { width: img.width(), height: img.height(), format: format!("{:?}", color), channels: stats }
+}
+
+/// Renders the luma as characters, `width` per line; characters are about twice as tall as wide.
+pub fn ascii_thumbnail(img: &DynamicImage, width: u32) -> String {
+    let width = width.max(1);
+    let height = ((img.height() as u64 * width as u64) / (2 * img.width().max(1) as u64)).max(1) as u32;
+    let thumb = img.resize_exact(width, height, FilterType::Triangle).to_luma8();
+    let mut out = String::with_capacity(((width + 1) * height) as usize);
+    for row in thumb.rows() {
+        for p in row {
+            out.push(ASCII_RAMP[p[0] as usize * (ASCII_RAMP.len() - 1) / 255] as char);
+        }
+        out.push('\n');
+    }
+    out
+}
+
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let n = chunk.iter().enumerate().fold(0u32, |n, (i, &b)| n | (b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+/// Draws a number like `29.9` at the top left on a dark box, `scale` pixels per glyph dot.
+///
+/// Only digits and `.` are drawn; this avoids depending on a font for FPS overlays.
+pub fn draw_number(img: &mut RgbaImage, text: &str, scale: u32) {
+    let scale = scale.max(1);
+    let glyphs: Vec<&[u8; 5]> = text
+        .chars()
+        .filter_map(|c| match c {
+            '0'..='9' => Some(&GLYPHS[c as usize - '0' as usize]),
+            '.' => Some(&GLYPHS[10]),
+            _ => None,
+        })
+        .collect();
+    let (box_w, box_h) = ((glyphs.len() as u32 * 4 + 1) * scale, 7 * scale);
+    for y in 0..box_h.min(img.height()) {
+        for x in 0..box_w.min(img.width()) {
+            let glyph = (x / scale).checked_sub(1).map(|gx| (gx / 4, gx % 4));
+            let row = (y / scale).checked_sub(1).filter(|&r| r < 5);
+            let lit = match (glyph, row) {
+                (Some((i, col)), Some(row)) if col < 3 => glyphs[i as usize][row as usize] >> (2 - col) & 1 == 1,
+                _ => false,
+            };
+            img.put_pixel(x, y, if lit { Rgba([255, 255, 0, 255]) } else { Rgba([0, 0, 0, 255]) });
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum DebugThumbnail {
+    Ascii { width: u32 },
+    /// A PNG data URL, which can be pasted into a browser.
+    DataUrl { width: u32 },
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ImageDebugNodeConfig {
+    /// Prefix of every log line.
+    pub name: String,
+    /// Only every n-th frame is logged.
+    pub every_nth: u64,
+    pub thumbnail: Option<DebugThumbnail>,
+}
+
+impl Default for ImageDebugNodeConfig {
+    fn default() -> Self {
+        Self { name: "image".into(), every_nth: 1, thumbnail: None }
+    }
+}
+
+impl Validate for ImageDebugNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        ensure(self.every_nth > 0, "every_nth", "must be positive")?;
+        let width = match self.thumbnail {
+            Some(DebugThumbnail::Ascii { width } | DebugThumbnail::DataUrl { width }) => width,
+            None => 1,
+        };
+        ensure(width > 0, "thumbnail.width", "must be positive")
+    }
+}
+
+config_builder!(ImageDebugNodeConfig for ImageDebugNode {
+    name: String,
+    every_nth: u64,
+    thumbnail: Option<DebugThumbnail>,
+});
+
+#[cfg(all(target_arch = "wasm32", not(feature = "tracing")))]
+#[wasm_bindgen::prelude::wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_namespace = console)]
+    fn log(s: &str);
+}
+
+/// Writes a log line to `tracing`, the browser console or stderr, see [`ImageDebugNode`].
+pub(crate) fn emit(message: &str) {
+    #[cfg(feature = "tracing")]
+    tracing::info!(target: "flowrs_img::debug", "{}", message);
+    #[cfg(all(target_arch = "wasm32", not(feature = "tracing")))]
+    log(message);
+    #[cfg(all(not(target_arch = "wasm32"), not(feature = "tracing")))]
+    eprintln!("{}", message);
+}
+
+/// Logs what passes through a connection and forwards frames unchanged.
+///
+/// Messages go to `tracing` if the feature is enabled, else to the browser
+/// console on wasm and to stderr natively.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct ImageDebugNode {
+    #[output]
+    pub output: Output<DynamicImage>,
+
+    #[input]
+    pub input: Input<DynamicImage>,
+
+    pub config: ImageDebugNodeConfig,
+
+    #[serde(skip)]
+    frame: u64,
+}
+
+impl ImageDebugNode {
+    pub fn new(config: ImageDebugNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            input: Input::new(),
+            config,
+            frame: 0,
+        }
+    }
+
+    fn describe(&self, img: &DynamicImage) -> Result<String, anyhow::Error> {
+        let summary = summarize(img);
+        let mut message = format!("[{}] frame {}: {}x{} {}", self.config.name, self.frame, summary.width, summary.height, summary.format);
+        for (i, c) in summary.channels.iter().enumerate() {
+            write!(message, " | c{} min {} max {} mean {:.2}", i, c.min, c.max, c.mean)?;
+        }
+        match &self.config.thumbnail {
+            Some(DebugThumbnail::Ascii { width }) => {
+                message.push('\n');
+                message.push_str(&ascii_thumbnail(img, *width));
+            }
+            Some(DebugThumbnail::DataUrl { width }) => {
+                let thumb = img.resize(*width, u32::MAX, FilterType::Triangle);
+                write!(message, "\ndata:image/png;base64,{}", base64(&encode_image(&thumb, EncodeFormat::Png)?))?;
+            }
+            None => {}
+        }
+        Ok(message)
+    }
+}
+
+impl Node for ImageDebugNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        if let Ok(img) = self.input.next() {
+            if self.frame.is_multiple_of(self.config.every_nth.max(1)) {
+                emit(&self.describe(&img).map_err(UpdateError::Other)?);
+            }
+            self.frame += 1;
+            self.output.send(img).map_err(|e| UpdateError::Other(e.into()))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "preview")]
+pub use self::preview::{PreviewWindowNode, PreviewWindowNodeConfig};
+
+#[cfg(feature = "preview")]
+mod preview {
+    use flowrs::{node::{Node, UpdateError, ChangeObserver}, connection::{Input, Output}};
+    use flowrs::RuntimeConnectable;
+
+    use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+    use std::time::{Duration, Instant};
+
+    use image::DynamicImage;
+    use anyhow::anyhow;
+    use minifb::{Window, WindowOptions};
+
+    use serde::{Deserialize, Serialize};
+
+    use crate::config::{ConfigError, Validate};
+
+    use super::draw_number;
+
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    #[serde(default)]
+    pub struct PreviewWindowNodeConfig {
+        /// Window title.
+        pub name: String,
+        pub show_fps: bool,
+    }
+
+    impl Default for PreviewWindowNodeConfig {
+        fn default() -> Self {
+            Self { name: "flowrs-img".into(), show_fps: true }
+        }
+    }
+
+    impl Validate for PreviewWindowNodeConfig {
+        fn validate(&self) -> Result<(), ConfigError> {
+            Ok(())
+        }
+    }
+
+    config_builder!(PreviewWindowNodeConfig for PreviewWindowNode { name: String, show_fps: bool });
+
+    /// Runs the window on its own thread, as windows cannot move between threads.
+    fn run_window(title: String, show_fps: bool, frames: Receiver<DynamicImage>) -> Result<(), anyhow::Error> {
+        let mut window: Option<(Window, usize, usize)> = None;
+        let mut buffer: Vec<u32> = Vec::new();
+        let mut fps = 0.0f32;
+        let mut last_frame: Option<Instant> = None;
+        loop {
+            match frames.recv_timeout(Duration::from_millis(16)) {
+                Ok(img) => {
+                    let now = Instant::now();
+                    if let Some(last) = last_frame.replace(now) {
+                        // Smoothed, so the number stays readable.
+                        fps = 0.9 * fps + 0.1 / (now - last).as_secs_f32().max(1e-6);
+                    }
+                    let mut rgba = img.into_rgba8();
+                    if show_fps {
+                        draw_number(&mut rgba, &format!("{:.1}", fps), 2);
+                    }
+                    let (w, h) = (rgba.width() as usize, rgba.height() as usize);
+                    if window.as_ref().map(|(_, ww, wh)| (*ww, *wh)) != Some((w, h)) {
+                        let created = Window::new(&title, w, h, WindowOptions::default())
+                            .map_err(|e| anyhow!("Could not open preview window: {}", e))?;
+                        window = Some((created, w, h));
+                    }
+                    buffer.clear();
+                    buffer.extend(rgba.pixels().map(|p| (p[0] as u32) << 16 | (p[1] as u32) << 8 | p[2] as u32));
+                    let (win, _, _) = window.as_mut().expect("created above");
+                    win.update_with_buffer(&buffer, w, h)?;
+                }
+                // Keep the window responsive between frames.
+                Err(RecvTimeoutError::Timeout) => {
+                    if let Some((win, _, _)) = window.as_mut() {
+                        win.update();
+                    }
+                }
+                Err(RecvTimeoutError::Disconnected) => return Ok(()),
+            }
+            if window.as_ref().is_some_and(|(win, _, _)| !win.is_open()) {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Shows incoming frames in a desktop window and passes them through.
+    ///
+    /// Closing the window stops the preview but not the flow. On macOS
+    /// windows can only be opened from the main thread, so this node is meant
+    /// for Linux and Windows development machines.
+    #[derive(RuntimeConnectable, Deserialize, Serialize)]
+    pub struct PreviewWindowNode {
+        #[output]
+        pub output: Output<DynamicImage>,
+
+        #[input]
+        pub input: Input<DynamicImage>,
+
+        pub config: PreviewWindowNodeConfig,
+
+        #[serde(skip)]
+        window: Option<Sender<DynamicImage>>,
+    }
+
+    impl PreviewWindowNode {
+        pub fn new(config: PreviewWindowNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+            Self {
+                output: Output::new(change_observer),
+                input: Input::new(),
+                config,
+                window: None,
+            }
+        }
+    }
+
+    impl Node for PreviewWindowNode {
+        fn on_update(&mut self) -> Result<(), UpdateError> {
+
+            if let Ok(img) = self.input.next() {
+                if self.window.is_none() {
+                    let (tx, rx) = mpsc::channel();
+                    let (title, show_fps) = (self.config.name.clone(), self.config.show_fps);
+                    std::thread::spawn(move || run_window(title, show_fps, rx));
+                    self.window = Some(tx);
+                }
+                if let Some(window) = &self.window {
+                    // A closed window only ends the preview.
+                    let _ = window.send(img.clone());
+                }
+                self.output.send(img).map_err(|e| UpdateError::Other(e.into()))?;
+            }
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "clipboard")]
+pub use self::clipboard::{clipboard_data, clipboard_image, ClipboardSinkNode, ClipboardSourceNode, ClipboardSourceNodeConfig};
+
+#[cfg(feature = "clipboard")]
+mod clipboard {
+    use flowrs::{node::{Node, UpdateError, ChangeObserver}, connection::{Input, Output}};
+    use flowrs::RuntimeConnectable;
+
+    use std::borrow::Cow;
+    use std::time::{Duration, Instant};
+
+    use arboard::{Clipboard, ImageData};
+    use image::{DynamicImage, RgbaImage};
+
+    use serde::{Deserialize, Serialize};
+
+    use crate::config::{ConfigError, Validate};
+    use crate::flow::StatusReporter;
+    use crate::types::NodeStatus;
+
+    fn open(clipboard: &mut Option<Clipboard>) -> Result<&mut Clipboard, UpdateError> {
+        if clipboard.is_none() {
+            *clipboard = Some(Clipboard::new().map_err(|e| UpdateError::Other(e.into()))?);
+        }
+        Ok(clipboard.as_mut().expect("opened above"))
+    }
+
+    /// Converts a frame to the RGBA layout the clipboard expects.
+    pub fn clipboard_data(img: &DynamicImage) -> ImageData<'static> {
+        let rgba = img.to_rgba8();
+        ImageData {
+            width: rgba.width() as usize,
+            height: rgba.height() as usize,
+            bytes: Cow::Owned(rgba.into_raw()),
+        }
+    }
+
+    /// Converts clipboard contents back to an image, rejecting data that does not match its size.
+    pub fn clipboard_image(data: ImageData) -> Result<RgbaImage, anyhow::Error> {
+        let (width, height) = (u32::try_from(data.width)?, u32::try_from(data.height)?);
+        RgbaImage::from_raw(width, height, data.bytes.into_owned())
+            .ok_or_else(|| anyhow::anyhow!("Clipboard image has an invalid size"))
+    }
+
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    #[serde(default)]
+    pub struct ClipboardSourceNodeConfig {
+        pub poll_interval_ms: u64,
+    }
+
+    impl Default for ClipboardSourceNodeConfig {
+        fn default() -> Self {
+            Self { poll_interval_ms: 500 }
+        }
+    }
+
+    impl Validate for ClipboardSourceNodeConfig {
+        fn validate(&self) -> Result<(), ConfigError> {
+            // `0` polls on every update.
+            Ok(())
+        }
+    }
+
+    config_builder!(ClipboardSourceNodeConfig for ClipboardSourceNode { poll_interval_ms: u64 });
+
+    /// Emits the image on the system clipboard whenever it changes.
+    ///
+    /// Clipboards holding no image, or only text, are ignored.
+    #[derive(RuntimeConnectable, Deserialize, Serialize)]
+    pub struct ClipboardSourceNode {
+        #[output]
+        pub output: Output<DynamicImage>,
+
+        #[output]
+        pub health: Output<NodeStatus>,
+
+        pub config: ClipboardSourceNodeConfig,
+
+        #[serde(skip)]
+        clipboard: Option<Clipboard>,
+        #[serde(skip)]
+        last_poll: Option<Instant>,
+        #[serde(skip)]
+        last: Option<RgbaImage>,
+        #[serde(skip)]
+        reporter: StatusReporter,
+    }
+
+    impl ClipboardSourceNode {
+        pub fn new(config: ClipboardSourceNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+            Self {
+                output: Output::new(change_observer),
+                health: Output::new(change_observer),
+                config,
+                clipboard: None,
+                last_poll: None,
+                last: None,
+                reporter: StatusReporter::default(),
+            }
+        }
+
+        fn update(&mut self) -> Result<(), UpdateError> {
+
+            let interval = Duration::from_millis(self.config.poll_interval_ms);
+            if self.last_poll.is_some_and(|t| t.elapsed() < interval) {
+                return Ok(());
+            }
+            self.last_poll = Some(Instant::now());
+
+            let data = match open(&mut self.clipboard)?.get_image() {
+                Ok(data) => data,
+                Err(arboard::Error::ContentNotAvailable) => return Ok(()),
+                Err(e) => return Err(UpdateError::Other(e.into())),
+            };
+            let img = clipboard_image(data).map_err(UpdateError::Other)?;
+            if self.last.as_ref() == Some(&img) {
+                return Ok(());
+            }
+            self.last = Some(img.clone());
+            self.reporter.frame();
+            self.output.send(DynamicImage::ImageRgba8(img)).map_err(|e| UpdateError::Other(e.into()))?;
+            Ok(())
+        }
+    }
+
+    impl Node for ClipboardSourceNode {
+        fn on_update(&mut self) -> Result<(), UpdateError> {
+            let result = self.update();
+            self.reporter.track(result, &mut self.health)
+        }
+    }
+
+    /// Copies incoming frames to the system clipboard and passes them through.
+    ///
+    /// On X11 and Wayland the clipboard is served by the process that set it,
+    /// so the last image stays available only while the node is alive.
+    #[derive(RuntimeConnectable, Deserialize, Serialize)]
+    pub struct ClipboardSinkNode {
+        #[output]
+        pub output: Output<DynamicImage>,
+
+        #[output]
+        pub health: Output<NodeStatus>,
+
+        #[input]
+        pub input: Input<DynamicImage>,
+
+        #[serde(skip)]
+        clipboard: Option<Clipboard>,
+        #[serde(skip)]
+        reporter: StatusReporter,
+    }
+
+    impl ClipboardSinkNode {
+        pub fn new(change_observer: Option<&ChangeObserver>) -> Self {
+            Self {
+                output: Output::new(change_observer),
+                health: Output::new(change_observer),
+                input: Input::new(),
+                clipboard: None,
+                reporter: StatusReporter::default(),
+            }
+        }
+
+        fn update(&mut self) -> Result<(), UpdateError> {
+
+            if let Ok(img) = self.input.next() {
+                open(&mut self.clipboard)?.set_image(clipboard_data(&img)).map_err(|e| UpdateError::Other(e.into()))?;
+                self.reporter.frame();
+                self.output.send(img).map_err(|e| UpdateError::Other(e.into()))?;
+            }
+            Ok(())
+        }
+    }
+
+    impl Node for ClipboardSinkNode {
+        fn on_update(&mut self) -> Result<(), UpdateError> {
+            let result = self.update();
+            self.reporter.track(result, &mut self.health)
+        }
+    }
+}
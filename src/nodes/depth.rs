@@ -0,0 +1,355 @@
+use flowrs::{node::{Node, UpdateError, ChangeObserver}, connection::{Input, Output}};
+use flowrs::RuntimeConnectable;
+
+use std::path::{Path, PathBuf};
+
+use image::{DynamicImage, ImageBuffer, Luma};
+use ndarray::Array2;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::{ensure, ConfigError, Validate};
+use crate::error::Error;
+use crate::flow::{SourceGate, StatusReporter};
+use crate::transform::decode_image;
+use crate::types::{CameraIntrinsics, NodeState, NodeStatus, SourceControl};
+
+/// Color frame with depth registered to its pixel grid.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RgbdFrame {
+    pub color: DynamicImage,
+    /// Depth per color pixel in sensor units; `0` means no measurement.
+    pub depth: Array2<u16>,
+    /// Meters per depth unit, e.g. `0.001` for millimeters.
+    pub depth_scale: f32,
+    pub intrinsics: Option<CameraIntrinsics>,
+}
+
+/// A backend delivering frames to a [`DepthCameraNode`].
+pub trait DepthSource: Send {
+    /// The next frame, or `None` if there is none right now or the source is exhausted.
+    fn next_frame(&mut self) -> Result<Option<RgbdFrame>, Error>;
+}
+
+/// Converts a 16-bit depth image to an array, for sources that store depth as PNG.
+pub fn depth_from_image(img: &DynamicImage) -> Result<Array2<u16>, Error> {
+    let DynamicImage::ImageLuma16(depth) = img else {
+        return Err(Error::UnsupportedFormat(format!("Depth images must be 16-bit grayscale, got {:?}", img.color())));
+    };
+    let (width, height) = depth.dimensions();
+    Array2::from_shape_vec((height as usize, width as usize), depth.as_raw().clone()).map_err(|e| Error::Conversion(e.to_string()))
+}
+
+pub fn depth_to_image(depth: &Array2<u16>) -> DynamicImage {
+    let (rows, cols) = depth.dim();
+    let buffer = ImageBuffer::<Luma<u16>, Vec<u16>>::from_raw(cols as u32, rows as u32, depth.iter().copied().collect());
+    DynamicImage::ImageLuma16(buffer.expect("one value per pixel"))
+}
+
+/// Replays recorded RGB-D footage: `color_*.png` and `depth_*.png` pairs in name order, with an
+/// optional `intrinsics.json`.
+pub struct DepthReplaySource {
+    pairs: Vec<(PathBuf, PathBuf)>,
+    position: usize,
+    looping: bool,
+    depth_scale: f32,
+    intrinsics: Option<CameraIntrinsics>,
+}
+
+impl DepthReplaySource {
+    pub fn open(directory: &Path, depth_scale: f32, looping: bool) -> Result<Self, anyhow::Error> {
+        let mut colors = Vec::new();
+        for entry in std::fs::read_dir(directory)? {
+            let name = entry?.file_name().to_string_lossy().to_string();
+            if name.starts_with("color_") {
+                colors.push(name);
+            }
+        }
+        colors.sort();
+        let pairs = colors
+            .into_iter()
+            .map(|name| (directory.join(&name), directory.join(name.replacen("color_", "depth_", 1))))
+            .filter(|(_, depth)| depth.is_file())
+            .collect();
+        let intrinsics_path = directory.join("intrinsics.json");
+        let intrinsics = match intrinsics_path.is_file() {
+            true => Some(serde_json::from_slice(&std::fs::read(intrinsics_path)?)?),
+            false => None,
+        };
+        Ok(Self { pairs, position: 0, looping, depth_scale, intrinsics })
+    }
+}
+
+impl DepthSource for DepthReplaySource {
+    fn next_frame(&mut self) -> Result<Option<RgbdFrame>, Error> {
+        if self.position == self.pairs.len() && self.looping {
+            self.position = 0;
+        }
+        let Some((color, depth)) = self.pairs.get(self.position) else { return Ok(None) };
+        self.position += 1;
+        let color = decode_image(std::fs::read(color)?)?;
+        let depth = depth_from_image(&decode_image(std::fs::read(depth)?)?)?;
+        if depth.dim() != (color.height() as usize, color.width() as usize) {
+            return Err(Error::Conversion(format!("Depth is {:?} but color is {}x{}", depth.dim(), color.width(), color.height())));
+        }
+        Ok(Some(RgbdFrame { color, depth, depth_scale: self.depth_scale, intrinsics: self.intrinsics }))
+    }
+}
+
+#[cfg(feature = "realsense")]
+pub use self::realsense::RealSenseSource;
+
+#[cfg(feature = "realsense")]
+mod realsense {
+    use std::ffi::CString;
+    use std::sync::mpsc::{self, Receiver, SyncSender, TryRecvError};
+    use std::time::Duration;
+
+    use image::{DynamicImage, RgbImage};
+    use ndarray::Array2;
+    use anyhow::anyhow;
+    use realsense_rust::{
+        config::Config,
+        context::Context,
+        frame::{ColorFrame, DepthFrame, FrameEx, PixelKind},
+        kind::{Rs2Format, Rs2StreamKind},
+        pipeline::InactivePipeline,
+        processing_blocks::align::Align,
+    };
+
+    use super::{DepthSource, Error, RgbdFrame};
+    use crate::types::CameraIntrinsics;
+
+    /// Intel RealSense camera with depth aligned to the color stream.
+    ///
+    /// The device is driven from its own thread, since the librealsense
+    /// processing blocks cannot move between threads.
+    pub struct RealSenseSource {
+        frames: Receiver<Result<RgbdFrame, String>>,
+    }
+
+    impl RealSenseSource {
+        pub fn open(width: u32, height: u32, fps: u32, serial: Option<String>) -> Result<Self, anyhow::Error> {
+            let (tx, frames) = mpsc::sync_channel(2);
+            std::thread::spawn(move || {
+                if let Err(e) = stream(width as usize, height as usize, fps as usize, serial, &tx) {
+                    let _ = tx.send(Err(e.to_string()));
+                }
+            });
+            Ok(Self { frames })
+        }
+    }
+
+    fn stream(width: usize, height: usize, fps: usize, serial: Option<String>, tx: &SyncSender<Result<RgbdFrame, String>>) -> Result<(), anyhow::Error> {
+        let context = Context::new()?;
+        let mut config = Config::new();
+        if let Some(serial) = serial {
+            config.enable_device_from_serial(&CString::new(serial)?)?;
+        }
+        config
+            .disable_all_streams()?
+            .enable_stream(Rs2StreamKind::Depth, None, width, height, Rs2Format::Z16, fps)?
+            .enable_stream(Rs2StreamKind::Color, None, width, height, Rs2Format::Rgb8, fps)?;
+        let mut pipeline = InactivePipeline::try_from(&context)?.start(Some(config))?;
+        let mut align = Align::new(Rs2StreamKind::Color, 2)?;
+
+        loop {
+            let frames = pipeline.wait(Some(Duration::from_secs(5)))?;
+            align.queue(frames)?;
+            let aligned = align.wait(Duration::from_secs(1))?;
+            let (Some(depth), Some(color)) = (
+                aligned.frames_of_type::<DepthFrame>().pop(),
+                aligned.frames_of_type::<ColorFrame>().pop(),
+            ) else { continue };
+
+            let (w, h) = (color.width(), color.height());
+            let rgb: Vec<u8> = color
+                .iter()
+                .flat_map(|p| match p {
+                    PixelKind::Rgb8 { r, g, b } => [*r, *g, *b],
+                    _ => [0, 0, 0],
+                })
+                .collect();
+            let values: Vec<u16> = depth.iter().map(|p| match p { PixelKind::Z16 { depth } => *depth, _ => 0 }).collect();
+            let intrinsics = color.stream_profile().intrinsics().ok().map(|i| CameraIntrinsics {
+                width: i.width() as u32,
+                height: i.height() as u32,
+                fx: i.fx(),
+                fy: i.fy(),
+                ppx: i.ppx(),
+                ppy: i.ppy(),
+            });
+            let frame = RgbdFrame {
+                color: DynamicImage::ImageRgb8(RgbImage::from_raw(w as u32, h as u32, rgb).ok_or_else(|| anyhow!("Short color frame"))?),
+                depth: Array2::from_shape_vec((depth.height(), depth.width()), values)?,
+                depth_scale: depth.depth_units()?,
+                intrinsics,
+            };
+            // The node was dropped.
+            if tx.send(Ok(frame)).is_err() {
+                return Ok(());
+            }
+        }
+    }
+
+    impl DepthSource for RealSenseSource {
+        fn next_frame(&mut self) -> Result<Option<RgbdFrame>, Error> {
+            match self.frames.try_recv() {
+                Ok(frame) => frame.map(Some).map_err(Error::CameraRead),
+                Err(TryRecvError::Empty) => Ok(None),
+                Err(TryRecvError::Disconnected) => Err(Error::CameraRead("RealSense capture thread stopped".into())),
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum DepthSourceConfig {
+    Directory { path: PathBuf, depth_scale: f32, looping: bool },
+    #[cfg(feature = "realsense")]
+    RealSense { width: u32, height: u32, fps: u32, serial: Option<String> },
+}
+
+impl Default for DepthSourceConfig {
+    fn default() -> Self {
+        DepthSourceConfig::Directory { path: PathBuf::new(), depth_scale: 0.001, looping: false }
+    }
+}
+
+impl Validate for DepthSourceConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        match self {
+            DepthSourceConfig::Directory { depth_scale, .. } => ensure(*depth_scale > 0.0, "depth_scale", "must be positive"),
+            #[cfg(feature = "realsense")]
+            DepthSourceConfig::RealSense { width, height, fps, .. } => {
+                ensure(*width > 0 && *height > 0, "width", "frames must not be empty")?;
+                ensure(*fps > 0, "fps", "must be positive")
+            }
+        }
+    }
+}
+
+impl DepthSourceConfig {
+    pub fn open(&self) -> Result<Box<dyn DepthSource>, Error> {
+        let open_failed = |e: anyhow::Error| Error::CameraOpen(e.to_string());
+        Ok(match self {
+            DepthSourceConfig::Directory { path, depth_scale, looping } => {
+                Box::new(DepthReplaySource::open(path, *depth_scale, *looping).map_err(open_failed)?)
+            }
+            #[cfg(feature = "realsense")]
+            DepthSourceConfig::RealSense { width, height, fps, serial } => {
+                Box::new(RealSenseSource::open(*width, *height, *fps, serial.clone()).map_err(open_failed)?)
+            }
+        })
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct DepthCameraNodeConfig {
+    pub source: DepthSourceConfig,
+}
+
+impl Validate for DepthCameraNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        self.source.validate_in("source")
+    }
+}
+
+config_builder!(DepthCameraNodeConfig for DepthCameraNode { source: DepthSourceConfig });
+
+/// Emits aligned color and depth frames from an RGB-D camera or a recording.
+///
+/// Depth is sent both as an array and as a 16-bit image, at the resolution of
+/// the color frame. Intrinsics are sent whenever they change. Stopping the node
+/// through `control` closes a camera opened from the config.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct DepthCameraNode {
+    #[output]
+    pub color: Output<DynamicImage>,
+
+    #[output]
+    pub depth: Output<Array2<u16>>,
+
+    #[output]
+    pub depth_image: Output<DynamicImage>,
+
+    #[output]
+    pub intrinsics: Output<CameraIntrinsics>,
+
+    #[output]
+    pub health: Output<NodeStatus>,
+
+    #[input]
+    pub control: Input<SourceControl>,
+
+    pub config: DepthCameraNodeConfig,
+
+    #[serde(skip)]
+    source: Option<Box<dyn DepthSource>>,
+    #[serde(skip)]
+    injected: bool,
+    #[serde(skip)]
+    last_intrinsics: Option<CameraIntrinsics>,
+    #[serde(skip)]
+    reporter: StatusReporter,
+    #[serde(skip)]
+    gate: SourceGate,
+}
+
+impl DepthCameraNode {
+    pub fn new(config: DepthCameraNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            color: Output::new(change_observer),
+            depth: Output::new(change_observer),
+            depth_image: Output::new(change_observer),
+            intrinsics: Output::new(change_observer),
+            health: Output::new(change_observer),
+            control: Input::new(),
+            config,
+            source: None,
+            injected: false,
+            last_intrinsics: None,
+            reporter: StatusReporter::default(),
+            gate: SourceGate::default(),
+        }
+    }
+
+    pub fn with_source(config: DepthCameraNodeConfig, source: Box<dyn DepthSource>, change_observer: Option<&ChangeObserver>) -> Self {
+        Self { source: Some(source), injected: true, ..Self::new(config, change_observer) }
+    }
+
+    fn update(&mut self) -> Result<(), UpdateError> {
+
+        if self.gate.poll(&mut self.control) && !self.injected {
+            self.source = None;
+        }
+        if !self.gate.running() {
+            self.reporter.set_state(NodeState::Paused);
+            return Ok(());
+        }
+        if self.source.is_none() {
+            self.source = Some(self.config.source.open()?);
+        }
+        let source = self.source.as_mut().expect("opened above");
+        let Some(frame) = source.next_frame()? else { return Ok(()) };
+        self.reporter.frame();
+        self.gate.produced();
+
+        if let Some(intrinsics) = frame.intrinsics.filter(|i| self.last_intrinsics != Some(*i)) {
+            self.last_intrinsics = Some(intrinsics);
+            self.intrinsics.send(intrinsics).map_err(|e| UpdateError::Other(e.into()))?;
+        }
+        self.depth_image.send(depth_to_image(&frame.depth)).map_err(|e| UpdateError::Other(e.into()))?;
+        self.depth.send(frame.depth).map_err(|e| UpdateError::Other(e.into()))?;
+        self.color.send(frame.color).map_err(|e| UpdateError::Other(e.into()))?;
+        Ok(())
+    }
+}
+
+impl Node for DepthCameraNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+        let result = self.update();
+        self.reporter.track(result, &mut self.health)
+    }
+}
@@ -0,0 +1,102 @@
+use flowrs::{node::{Node, UpdateError, ChangeObserver}, connection::{Input, Output}};
+use flowrs::RuntimeConnectable;
+
+use image::DynamicImage;
+use image::error::{DecodingError, ImageFormatHint};
+use image::ImageError;
+use dicom_object::{OpenFileOptions, file::ReadPreamble};
+use dicom_pixeldata::{ConvertOptions, PixelDecoder, VoiLutOption, WindowLevel};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::{ensure, ConfigError, Validate};
+use crate::error::Error;
+
+/// VOI window mapping `center ± width / 2` to the full output range.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+pub struct DicomWindow {
+    pub center: f64,
+    pub width: f64,
+}
+
+impl Validate for DicomWindow {
+    fn validate(&self) -> Result<(), ConfigError> {
+        ensure(self.width > 0.0, "width", "must be positive")
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct DicomDecodeNodeConfig {
+    /// Window to apply instead of the first one stored in the object; objects without
+    /// either are stretched over their value range.
+    pub window: Option<DicomWindow>,
+    /// Emit every frame of multi-frame objects instead of only the first.
+    pub all_frames: bool,
+}
+
+impl Validate for DicomDecodeNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        self.window.validate_in("window")
+    }
+}
+
+config_builder!(DicomDecodeNodeConfig for DicomDecodeNode { window: Option<DicomWindow>, all_frames: bool });
+
+/// Decodes DICOM files, with or without the 128-byte preamble, into 8 or 16 bit images.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct DicomDecodeNode {
+    #[output]
+    pub output: Output<DynamicImage>,
+
+    #[input]
+    pub input: Input<Vec<u8>>,
+
+    pub config: DicomDecodeNodeConfig,
+}
+
+impl DicomDecodeNode {
+    pub fn new(config: DicomDecodeNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            input: Input::new(),
+            config,
+        }
+    }
+}
+
+impl Node for DicomDecodeNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        if let Ok(data) = self.input.next() {
+            for img in decode_dicom(&data, self.config.window, self.config.all_frames)? {
+                self.output.send(img).map_err(|e| UpdateError::Other(e.into()))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Decodes the pixel data of a DICOM file, converting it with `window` if given.
+///
+/// Without `all_frames`, only the first frame of multi-frame objects is returned.
+pub fn decode_dicom(data: &[u8], window: Option<DicomWindow>, all_frames: bool) -> Result<Vec<DynamicImage>, Error> {
+    // Files written to disk start with the preamble, network and archive payloads often do not.
+    let preamble = if data.get(128..132) == Some(b"DICM") { ReadPreamble::Always } else { ReadPreamble::Never };
+    let object = OpenFileOptions::new().read_preamble(preamble).from_reader(data).map_err(dicom_error)?;
+    let pixels = object.decode_pixel_data().map_err(dicom_error)?;
+
+    let voi_lut = match window {
+        Some(DicomWindow { center, width }) => VoiLutOption::Custom(WindowLevel { width, center }),
+        None => VoiLutOption::First,
+    };
+    let options = ConvertOptions::new().with_voi_lut(voi_lut);
+    let frames = if all_frames { pixels.number_of_frames() } else { pixels.number_of_frames().min(1) };
+    (0..frames)
+        .map(|frame| pixels.to_dynamic_image_with_options(frame, &options).map_err(dicom_error))
+        .collect()
+}
+
+fn dicom_error(e: impl std::error::Error + Send + Sync + 'static) -> Error {
+    Error::Decode(ImageError::Decoding(DecodingError::new(ImageFormatHint::Name("DICOM".into()), e)))
+}
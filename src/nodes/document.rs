@@ -0,0 +1,273 @@
+use flowrs::{node::{Node, UpdateError, ChangeObserver}, connection::{Input, Output}};
+use flowrs::RuntimeConnectable;
+
+use image::{DynamicImage, GrayImage, Luma, Rgb, RgbImage};
+use image::imageops::FilterType;
+use imageproc::contours::{find_contours, BorderType};
+use imageproc::contrast::adaptive_threshold;
+use imageproc::geometric_transformations::{rotate_about_center, warp_into, Interpolation, Projection};
+use imageproc::geometry::{approximate_polygon_dp, arc_length, convex_hull};
+use imageproc::point::Point;
+
+use serde::{Deserialize, Serialize};
+
+use crate::analysis::{binarize, ThresholdMode};
+use crate::config::{ensure, ConfigError, Validate};
+
+/// Longest side of the copy the page outline is searched on.
+const DETECTION_SIZE: u32 = 512;
+
+/// Longest side of the copy skew is estimated on; text needs more detail than page outlines.
+const SKEW_SIZE: u32 = 1024;
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct DocumentScanNodeConfig {
+    /// Smallest page outline accepted, as a fraction of the frame area; smaller or missing
+    /// outlines leave the frame unwarped.
+    pub min_area: f32,
+    /// Threshold the rectified page against its local mean, giving black text on white.
+    pub binarize: bool,
+    /// Radius in pixels of the neighbourhood each pixel is compared with when binarizing.
+    pub block_radius: u32,
+}
+
+impl Default for DocumentScanNodeConfig {
+    fn default() -> Self {
+        Self {
+            min_area: 0.2,
+            binarize: true,
+            block_radius: 15,
+        }
+    }
+}
+
+impl Validate for DocumentScanNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        ensure((0.0..=1.0).contains(&self.min_area), "min_area", "must be between 0 and 1")?;
+        ensure(self.block_radius > 0, "block_radius", "must be positive")
+    }
+}
+
+config_builder!(DocumentScanNodeConfig for DocumentScanNode { min_area: f32, binarize: bool, block_radius: u32 });
+
+/// Corners of the largest bright quadrilateral, e.g. a sheet of paper on a desk, in
+/// top-left, top-right, bottom-right, bottom-left order.
+pub fn find_page(gray: &GrayImage, min_area: f32) -> Option<[(f32, f32); 4]> {
+    let (width, height) = gray.dimensions();
+    let scale = (DETECTION_SIZE as f32 / width.max(height) as f32).min(1.0);
+    let small = image::imageops::resize(gray, ((width as f32 * scale) as u32).max(1), ((height as f32 * scale) as u32).max(1), FilterType::Triangle);
+    let small = image::imageops::blur(&small, 1.5);
+    let mask = binarize(&small, ThresholdMode::Otsu, false);
+
+    let min_area = min_area * (small.width() * small.height()) as f32;
+    let mut best: Option<([Point<i32>; 4], f32)> = None;
+    for contour in find_contours::<i32>(&mask).into_iter().filter(|c| c.border_type == BorderType::Outer) {
+        let hull = convex_hull(&contour.points);
+        let quad = approximate_polygon_dp(&hull, 0.02 * arc_length(&hull, true), true);
+        let Ok(quad) = <[Point<i32>; 4]>::try_from(quad) else { continue };
+        let area = polygon_area(&quad);
+        if area >= min_area && best.is_none_or(|(_, a)| area > a) {
+            best = Some((quad, area));
+        }
+    }
+
+    let (quad, _) = best?;
+    Some(order_corners(quad.map(|p| ((p.x as f32 + 0.5) / scale, (p.y as f32 + 0.5) / scale))))
+}
+
+fn polygon_area(points: &[Point<i32>]) -> f32 {
+    let twice: i64 = (0..points.len())
+        .map(|i| {
+            let (a, b) = (points[i], points[(i + 1) % points.len()]);
+            a.x as i64 * b.y as i64 - b.x as i64 * a.y as i64
+        })
+        .sum();
+    twice.abs() as f32 / 2.0
+}
+
+/// Sorts corners into top-left, top-right, bottom-right, bottom-left.
+fn order_corners(corners: [(f32, f32); 4]) -> [(f32, f32); 4] {
+    let max_by = |key: fn(&(f32, f32)) -> f32| {
+        corners.iter().copied().max_by(|a, b| key(a).total_cmp(&key(b))).expect("four corners")
+    };
+    [max_by(|p| -p.0 - p.1), max_by(|p| p.0 - p.1), max_by(|p| p.0 + p.1), max_by(|p| p.1 - p.0)]
+}
+
+/// Maps the quadrilateral `corners` (as returned by [`find_page`]) onto an upright rectangle
+/// sized after its longer opposite edges.
+pub fn rectify(img: &RgbImage, corners: [(f32, f32); 4]) -> Option<RgbImage> {
+    let dist = |a: (f32, f32), b: (f32, f32)| ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)).sqrt();
+    let [tl, tr, br, bl] = corners;
+    let width = dist(tl, tr).max(dist(bl, br)).round().max(1.0);
+    let height = dist(tl, bl).max(dist(tr, br)).round().max(1.0);
+
+    let target = [(0.0, 0.0), (width, 0.0), (width, height), (0.0, height)];
+    let projection = Projection::from_control_points(corners, target)?;
+    let mut out = RgbImage::new(width as u32, height as u32);
+    warp_into(img, &projection, Interpolation::Bilinear, Rgb([255, 255, 255]), &mut out);
+    Some(out)
+}
+
+/// Finds the page in a photo, corrects its perspective and optionally binarizes it.
+pub fn scan_document(img: &DynamicImage, config: &DocumentScanNodeConfig) -> DynamicImage {
+    let rgb = img.to_rgb8();
+    let page = find_page(&img.to_luma8(), config.min_area)
+        .and_then(|corners| rectify(&rgb, corners))
+        .unwrap_or(rgb);
+    if config.binarize {
+        DynamicImage::ImageLuma8(adaptive_threshold(&DynamicImage::ImageRgb8(page).to_luma8(), config.block_radius))
+    } else {
+        DynamicImage::ImageRgb8(page)
+    }
+}
+
+/// Turns photos of documents into flat, upright "scanned" pages.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct DocumentScanNode {
+    #[output]
+    pub output: Output<DynamicImage>,
+
+    #[input]
+    pub input: Input<DynamicImage>,
+
+    pub config: DocumentScanNodeConfig,
+}
+
+impl DocumentScanNode {
+    pub fn new(config: DocumentScanNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            input: Input::new(),
+            config,
+        }
+    }
+}
+
+impl Node for DocumentScanNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        if let Ok(img) = self.input.next() {
+            frame_span!("document_scan", width = img.width(), height = img.height());
+            self.output.send(scan_document(&img, &self.config)).map_err(|e| UpdateError::Other(e.into()))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct DeskewNodeConfig {
+    /// Largest skew searched for, in degrees either way.
+    pub max_angle: f32,
+    /// Resolution of the estimate in degrees.
+    pub precision: f32,
+}
+
+impl Default for DeskewNodeConfig {
+    fn default() -> Self {
+        Self {
+            max_angle: 15.0,
+            precision: 0.1,
+        }
+    }
+}
+
+impl Validate for DeskewNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        ensure(self.max_angle > 0.0 && self.max_angle < 45.0, "max_angle", "must be between 0 and 45")?;
+        ensure(self.precision > 0.0, "precision", "must be positive")
+    }
+}
+
+config_builder!(DeskewNodeConfig for DeskewNode { max_angle: f32, precision: f32 });
+
+/// Skew of the text lines in degrees, positive when they rise to the right.
+///
+/// Dark pixels are projected onto rows at each candidate angle; the angle whose profile is
+/// the most peaked, i.e. separates lines and gaps best, wins. The search runs in whole degrees
+/// first and is then refined around the best one down to `precision`.
+pub fn estimate_skew(gray: &GrayImage, max_angle: f32, precision: f32) -> f32 {
+    let (width, height) = gray.dimensions();
+    let scale = (SKEW_SIZE as f32 / width.max(height) as f32).min(1.0);
+    let small = image::imageops::resize(gray, ((width as f32 * scale) as u32).max(1), ((height as f32 * scale) as u32).max(1), FilterType::Triangle);
+    let ink: Vec<(f32, f32)> = binarize(&small, ThresholdMode::Otsu, true)
+        .enumerate_pixels()
+        .filter(|(_, _, p)| p[0] > 0)
+        .map(|(x, y, _)| (x as f32, y as f32))
+        .collect();
+    // Mostly dark frames have no text lines to go by.
+    if ink.is_empty() || ink.len() * 2 > (small.width() * small.height()) as usize {
+        return 0.0;
+    }
+
+    let diagonal = (small.width() as f32).hypot(small.height() as f32);
+    let score = |angle: f32| {
+        let (sin, cos) = angle.to_radians().sin_cos();
+        let mut rows = vec![0u32; 2 * diagonal as usize + 2];
+        for (x, y) in &ink {
+            rows[(x * sin + y * cos + diagonal) as usize] += 1;
+        }
+        rows.iter().map(|&n| (n as u64).pow(2)).sum::<u64>()
+    };
+    let best_of = |from: f32, to: f32, step: f32| {
+        let steps = ((to - from) / step).round() as i32;
+        (0..=steps).map(|i| from + i as f32 * step).max_by_key(|&a| score(a)).unwrap_or(0.0)
+    };
+
+    let coarse = best_of(-max_angle.floor(), max_angle.floor(), 1.0);
+    if precision >= 1.0 {
+        return coarse;
+    }
+    best_of(coarse - 1.0, coarse + 1.0, precision).clamp(-max_angle, max_angle)
+}
+
+/// Rotates `img` by `-angle` degrees about its center, filling the uncovered corners with white.
+pub fn deskew(img: &DynamicImage, angle: f32) -> DynamicImage {
+    let theta = angle.to_radians();
+    match img {
+        DynamicImage::ImageLuma8(gray) => DynamicImage::ImageLuma8(rotate_about_center(gray, theta, Interpolation::Bilinear, Luma([255]))),
+        _ => DynamicImage::ImageRgb8(rotate_about_center(&img.to_rgb8(), theta, Interpolation::Bilinear, Rgb([255, 255, 255]))),
+    }
+}
+
+/// Straightens scanned or photographed text, e.g. ahead of OCR.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct DeskewNode {
+    #[output]
+    pub output: Output<DynamicImage>,
+
+    /// Detected skew in degrees, positive when the text rose to the right.
+    #[output]
+    pub angle: Output<f32>,
+
+    #[input]
+    pub input: Input<DynamicImage>,
+
+    pub config: DeskewNodeConfig,
+}
+
+impl DeskewNode {
+    pub fn new(config: DeskewNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            angle: Output::new(change_observer),
+            input: Input::new(),
+            config,
+        }
+    }
+}
+
+impl Node for DeskewNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        if let Ok(img) = self.input.next() {
+            frame_span!("deskew", width = img.width(), height = img.height());
+            let angle = estimate_skew(&img.to_luma8(), self.config.max_angle, self.config.precision);
+            let straight = if angle == 0.0 { img } else { deskew(&img, angle) };
+            self.angle.send(angle).map_err(|e| UpdateError::Other(e.into()))?;
+            self.output.send(straight).map_err(|e| UpdateError::Other(e.into()))?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,420 @@
+use flowrs::{node::{Node, UpdateError, ChangeObserver}, connection::{Input, Output}};
+use flowrs::RuntimeConnectable;
+
+use std::collections::VecDeque;
+
+use image::{DynamicImage, GrayImage, RgbImage, RgbaImage, imageops::FilterType};
+use anyhow::anyhow;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::{ensure, ConfigError, Validate};
+use crate::flow::{push_bounded, OverflowPolicy};
+
+/// Values an expression can read for the current pixel; channels are in `0.0..=255.0`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PixelContext {
+    pub a: [f32; 4],
+    pub b: [f32; 4],
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Var {
+    Channel { image: usize, channel: usize },
+    X,
+    Y,
+    Width,
+    Height,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Func {
+    Abs,
+    Sqrt,
+    Floor,
+    Round,
+    Min,
+    Max,
+    Pow,
+    Clamp,
+    Select,
+}
+
+impl Func {
+    fn parse(name: &str) -> Option<(Func, usize)> {
+        Some(match name {
+            "abs" => (Func::Abs, 1),
+            "sqrt" => (Func::Sqrt, 1),
+            "floor" => (Func::Floor, 1),
+            "round" => (Func::Round, 1),
+            "min" => (Func::Min, 2),
+            "max" => (Func::Max, 2),
+            "pow" => (Func::Pow, 2),
+            "clamp" => (Func::Clamp, 3),
+            "select" => (Func::Select, 3),
+            _ => return None,
+        })
+    }
+}
+
+/// Instructions of a stack machine, in evaluation order.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Op {
+    Const(f32),
+    Load(Var),
+    Neg,
+    Binary(char),
+    Compare(&'static str),
+    Call(Func),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Number(f32),
+    Ident(String),
+    Symbol(&'static str),
+}
+
+fn tokenize(src: &str) -> Result<Vec<Token>, anyhow::Error> {
+    const SYMBOLS: [&str; 14] = ["<=", ">=", "==", "!=", "<", ">", "+", "-", "*", "/", "%", "(", ")", ","];
+    let mut tokens = Vec::new();
+    let mut rest = src.trim_start();
+    while let Some(c) = rest.chars().next() {
+        if let Some(symbol) = SYMBOLS.iter().find(|s| rest.starts_with(**s)) {
+            tokens.push(Token::Symbol(symbol));
+            rest = &rest[symbol.len()..];
+        } else if c.is_ascii_digit() || c == '.' {
+            let end = rest.find(|c: char| !(c.is_ascii_digit() || c == '.')).unwrap_or(rest.len());
+            tokens.push(Token::Number(rest[..end].parse().map_err(|_| anyhow!("Invalid number '{}'", &rest[..end]))?));
+            rest = &rest[end..];
+        } else if c.is_ascii_alphabetic() {
+            let end = rest.find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '.')).unwrap_or(rest.len());
+            tokens.push(Token::Ident(rest[..end].to_string()));
+            rest = &rest[end..];
+        } else {
+            return Err(anyhow!("Unexpected character '{}'", c));
+        }
+        rest = rest.trim_start();
+    }
+    Ok(tokens)
+}
+
+/// Recursive descent parser emitting stack machine code.
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    ops: Vec<Op>,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn eat(&mut self, symbol: &str) -> bool {
+        let found = matches!(self.peek(), Some(Token::Symbol(s)) if *s == symbol);
+        self.pos += found as usize;
+        found
+    }
+
+    fn expect(&mut self, symbol: &str) -> Result<(), anyhow::Error> {
+        self.eat(symbol).then_some(()).ok_or_else(|| anyhow!("Expected '{}' at token {}", symbol, self.pos + 1))
+    }
+
+    fn comparison(&mut self) -> Result<(), anyhow::Error> {
+        self.additive()?;
+        while let Some(Token::Symbol(s)) = self.peek().cloned() {
+            if !["<", ">", "<=", ">=", "==", "!="].contains(&s) {
+                break;
+            }
+            self.pos += 1;
+            self.additive()?;
+            self.ops.push(Op::Compare(s));
+        }
+        Ok(())
+    }
+
+    fn additive(&mut self) -> Result<(), anyhow::Error> {
+        self.multiplicative()?;
+        loop {
+            let op = if self.eat("+") { '+' } else if self.eat("-") { '-' } else { return Ok(()) };
+            self.multiplicative()?;
+            self.ops.push(Op::Binary(op));
+        }
+    }
+
+    fn multiplicative(&mut self) -> Result<(), anyhow::Error> {
+        self.unary()?;
+        loop {
+            let op = if self.eat("*") { '*' } else if self.eat("/") { '/' } else if self.eat("%") { '%' } else { return Ok(()) };
+            self.unary()?;
+            self.ops.push(Op::Binary(op));
+        }
+    }
+
+    fn unary(&mut self) -> Result<(), anyhow::Error> {
+        if self.eat("-") {
+            self.unary()?;
+            self.ops.push(Op::Neg);
+            return Ok(());
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<(), anyhow::Error> {
+        let token = self.peek().cloned().ok_or_else(|| anyhow!("Unexpected end of expression"))?;
+        self.pos += 1;
+        match token {
+            Token::Number(v) => self.ops.push(Op::Const(v)),
+            Token::Symbol("(") => {
+                self.comparison()?;
+                self.expect(")")?;
+            }
+            Token::Ident(name) if self.eat("(") => {
+                let (func, arity) = Func::parse(&name).ok_or_else(|| anyhow!("Unknown function '{}'", name))?;
+                for i in 0..arity {
+                    if i > 0 {
+                        self.expect(",")?;
+                    }
+                    self.comparison()?;
+                }
+                self.expect(")")?;
+                self.ops.push(Op::Call(func));
+            }
+            Token::Ident(name) => self.ops.push(Op::Load(variable(&name)?)),
+            Token::Symbol(s) => return Err(anyhow!("Unexpected '{}'", s)),
+        }
+        Ok(())
+    }
+}
+
+fn variable(name: &str) -> Result<Var, anyhow::Error> {
+    Ok(match name {
+        "x" => Var::X,
+        "y" => Var::Y,
+        "width" => Var::Width,
+        "height" => Var::Height,
+        _ => {
+            let (image, channel) = name.split_once('.').ok_or_else(|| anyhow!("Unknown variable '{}'", name))?;
+            let image = ["a", "b"].iter().position(|i| *i == image).ok_or_else(|| anyhow!("Unknown image '{}'", image))?;
+            let channel = ["r", "g", "b", "a"].iter().position(|c| *c == channel).ok_or_else(|| anyhow!("Unknown channel '{}'", name))?;
+            Var::Channel { image, channel }
+        }
+    })
+}
+
+/// Per-pixel arithmetic over the channels of images `a` and `b`, e.g. `clamp(a.r - b.r, 0, 255)`.
+///
+/// Supports `+ - * / %`, comparisons yielding `1` or `0`, the variables
+/// `a.r`..`a.a`, `b.r`..`b.a`, `x`, `y`, `width` and `height`, and the functions
+/// `abs`, `sqrt`, `floor`, `round`, `min`, `max`, `pow`, `clamp` and
+/// `select(condition, then, else)`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PixelExpr {
+    ops: Vec<Op>,
+}
+
+impl PixelExpr {
+    pub fn compile(src: &str) -> Result<Self, anyhow::Error> {
+        let mut parser = Parser { tokens: tokenize(src)?, pos: 0, ops: Vec::new() };
+        parser.comparison()?;
+        if parser.pos < parser.tokens.len() {
+            return Err(anyhow!("Unexpected {:?} after expression", parser.tokens[parser.pos]));
+        }
+        Ok(Self { ops: parser.ops })
+    }
+
+    /// Whether the expression reads the second image.
+    pub fn uses_b(&self) -> bool {
+        self.ops.iter().any(|op| matches!(op, Op::Load(Var::Channel { image: 1, .. })))
+    }
+
+    pub fn eval(&self, ctx: &PixelContext, stack: &mut Vec<f32>) -> f32 {
+        stack.clear();
+        for op in &self.ops {
+            let value = match *op {
+                Op::Const(v) => v,
+                Op::Load(var) => match var {
+                    Var::Channel { image: 0, channel } => ctx.a[channel],
+                    Var::Channel { channel, .. } => ctx.b[channel],
+                    Var::X => ctx.x,
+                    Var::Y => ctx.y,
+                    Var::Width => ctx.width,
+                    Var::Height => ctx.height,
+                },
+                Op::Neg => -stack.pop().unwrap_or_default(),
+                Op::Binary(op) => {
+                    let (r, l) = (stack.pop().unwrap_or_default(), stack.pop().unwrap_or_default());
+                    match op {
+                        '+' => l + r,
+                        '-' => l - r,
+                        '*' => l * r,
+                        '/' => l / r,
+                        _ => l % r,
+                    }
+                }
+                Op::Compare(op) => {
+                    let (r, l) = (stack.pop().unwrap_or_default(), stack.pop().unwrap_or_default());
+                    let holds = match op {
+                        "<" => l < r,
+                        ">" => l > r,
+                        "<=" => l <= r,
+                        ">=" => l >= r,
+                        "==" => l == r,
+                        _ => l != r,
+                    };
+                    holds as u8 as f32
+                }
+                Op::Call(func) => {
+                    let mut arg = || stack.pop().unwrap_or_default();
+                    match func {
+                        Func::Abs => arg().abs(),
+                        Func::Sqrt => arg().sqrt(),
+                        Func::Floor => arg().floor(),
+                        Func::Round => arg().round(),
+                        Func::Min => arg().min(arg()),
+                        Func::Max => arg().max(arg()),
+                        Func::Pow => {
+                            let (e, b) = (arg(), arg());
+                            b.powf(e)
+                        }
+                        Func::Clamp => {
+                            let (hi, lo, v) = (arg(), arg(), arg());
+                            v.max(lo).min(hi)
+                        }
+                        Func::Select => {
+                            let (otherwise, then, condition) = (arg(), arg(), arg());
+                            if condition != 0.0 { then } else { otherwise }
+                        }
+                    }
+                }
+            };
+            stack.push(value);
+        }
+        stack.pop().unwrap_or_default()
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct PixelExprNodeConfig {
+    /// One expression for a grayscale result, three for RGB or four for RGBA.
+    pub expressions: Vec<String>,
+}
+
+impl Validate for PixelExprNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        ensure(matches!(self.expressions.len(), 1 | 3 | 4), "expressions", "need 1, 3 or 4 expressions")?;
+        for (i, expr) in self.expressions.iter().enumerate() {
+            PixelExpr::compile(expr).map_err(|e| ConfigError { field: format!("expressions[{}]", i), reason: e.to_string() })?;
+        }
+        Ok(())
+    }
+}
+
+config_builder!(PixelExprNodeConfig for PixelExprNode { expressions: Vec<String> });
+
+/// Evaluates every expression for every pixel; results are rounded and saturated to 8 bits.
+pub fn evaluate(exprs: &[PixelExpr], a: &DynamicImage, b: Option<&DynamicImage>) -> Result<DynamicImage, anyhow::Error> {
+    let (width, height) = (a.width(), a.height());
+    let a = a.to_rgba32f();
+    let b = b.map(|b| match b.width() == width && b.height() == height {
+        true => b.to_rgba32f(),
+        false => b.resize_exact(width, height, FilterType::Triangle).to_rgba32f(),
+    });
+    let mut data = Vec::with_capacity((width * height) as usize * exprs.len());
+    let mut stack = Vec::new();
+    let mut ctx = PixelContext { width: width as f32, height: height as f32, ..PixelContext::default() };
+    for (x, y, px) in a.enumerate_pixels() {
+        ctx.a = px.0.map(|v| v * 255.0);
+        ctx.b = b.as_ref().map_or([0.0; 4], |b| b.get_pixel(x, y).0.map(|v| v * 255.0));
+        (ctx.x, ctx.y) = (x as f32, y as f32);
+        data.extend(exprs.iter().map(|e| e.eval(&ctx, &mut stack).round().clamp(0.0, 255.0) as u8));
+    }
+    Ok(match exprs.len() {
+        1 => DynamicImage::ImageLuma8(GrayImage::from_raw(width, height, data).expect("sized above")),
+        3 => DynamicImage::ImageRgb8(RgbImage::from_raw(width, height, data).expect("sized above")),
+        4 => DynamicImage::ImageRgba8(RgbaImage::from_raw(width, height, data).expect("sized above")),
+        n => return Err(anyhow!("Expected 1, 3 or 4 expressions, got {}", n)),
+    })
+}
+
+/// Frames kept per input while waiting for a partner; older ones are dropped.
+const MAX_PENDING: usize = 8;
+
+/// Per-pixel arithmetic over one or two images, e.g. difference maps or NDVI.
+///
+/// Expressions are compiled at the first update. When they read `b`, frames
+/// on `a` and `b` are paired in arrival order. If one input stalls, only the
+/// latest frames of the other are kept.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct PixelExprNode {
+    #[output]
+    pub output: Output<DynamicImage>,
+
+    #[input]
+    pub a: Input<DynamicImage>,
+
+    #[input]
+    pub b: Input<DynamicImage>,
+
+    pub config: PixelExprNodeConfig,
+
+    #[serde(skip)]
+    compiled: Option<Vec<PixelExpr>>,
+    #[serde(skip)]
+    pending: (VecDeque<DynamicImage>, VecDeque<DynamicImage>),
+}
+
+impl PixelExprNode {
+    pub fn new(config: PixelExprNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            a: Input::new(),
+            b: Input::new(),
+            config,
+            compiled: None,
+            pending: (VecDeque::new(), VecDeque::new()),
+        }
+    }
+}
+
+impl Node for PixelExprNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        if self.compiled.is_none() {
+            let compiled = self
+                .config
+                .expressions
+                .iter()
+                .map(|e| PixelExpr::compile(e).map_err(|err| anyhow!("In '{}': {}", e, err)))
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(UpdateError::Other)?;
+            self.compiled = Some(compiled);
+        }
+        let exprs = self.compiled.as_ref().expect("compiled above");
+        let uses_b = exprs.iter().any(PixelExpr::uses_b);
+
+        while let Ok(img) = self.a.next() {
+            push_bounded(&mut self.pending.0, img, MAX_PENDING, OverflowPolicy::DropOldest);
+        }
+        while let Ok(img) = self.b.next() {
+            if uses_b {
+                push_bounded(&mut self.pending.1, img, MAX_PENDING, OverflowPolicy::DropOldest);
+            }
+        }
+
+        while !self.pending.0.is_empty() && (!uses_b || !self.pending.1.is_empty()) {
+            let a = self.pending.0.pop_front().expect("checked");
+            let b = self.pending.1.pop_front();
+            frame_span!("pixel_expr", width = a.width(), height = a.height());
+            let result = evaluate(exprs, &a, b.as_ref()).map_err(UpdateError::Other)?;
+            self.output.send(result).map_err(|e| UpdateError::Other(e.into()))?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,570 @@
+use flowrs::{node::{Node, UpdateError, ChangeObserver}, connection::{Input, Output}};
+use flowrs::RuntimeConnectable;
+
+use anyhow::anyhow;
+use image::{DynamicImage, GrayImage, Rgba, RgbaImage, imageops};
+use imageproc::corners::corners_fast9;
+use imageproc::geometric_transformations::Projection;
+use imageproc::suppress::local_maxima;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::{ensure, ConfigError, Validate};
+
+/// Radius of the patch used for orientation and descriptors.
+const PATCH_RADIUS: i32 = 12;
+/// Keypoints closer to the border than this cannot be described under any rotation.
+const BORDER: u32 = 18;
+
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+pub struct Keypoint {
+    pub x: f32,
+    pub y: f32,
+    /// Dominant orientation in radians.
+    pub angle: f32,
+    pub score: f32,
+}
+
+/// 256-bit binary descriptor, compared by Hamming distance.
+pub type Descriptor = [u8; 32];
+
+/// Keypoints of one frame with their descriptors, if computed, in the same order.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub struct Features {
+    pub width: u32,
+    pub height: u32,
+    pub keypoints: Vec<Keypoint>,
+    pub descriptors: Vec<Descriptor>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum FeatureKind {
+    /// FAST corners without descriptors, e.g. for optical flow seeding.
+    Fast,
+    /// Oriented FAST keypoints with rotated BRIEF descriptors.
+    #[default]
+    Orb,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct FeatureExtractNodeConfig {
+    pub kind: FeatureKind,
+    /// Keep at most this many of the strongest keypoints.
+    pub max_features: usize,
+    /// FAST intensity threshold; lower values find more, weaker corners.
+    pub fast_threshold: u8,
+}
+
+impl Default for FeatureExtractNodeConfig {
+    fn default() -> Self {
+        Self { kind: FeatureKind::Orb, max_features: 500, fast_threshold: 20 }
+    }
+}
+
+impl Validate for FeatureExtractNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        ensure(self.max_features > 0, "max_features", "must be positive")
+    }
+}
+
+config_builder!(FeatureExtractNodeConfig for FeatureExtractNode {
+    kind: FeatureKind,
+    max_features: usize,
+    fast_threshold: u8,
+});
+
+/// Deterministic BRIEF sampling pattern: 256 point pairs within the patch.
+fn brief_pattern() -> Vec<[(f32, f32); 2]> {
+    let mut state = 0x9E37_79B9u32;
+    let mut next = move || {
+        state ^= state << 13;
+        state ^= state >> 17;
+        state ^= state << 5;
+        state as f32 / u32::MAX as f32
+    };
+    // A sum of uniforms approximates the isotropic Gaussian sampling of BRIEF.
+    let mut coord = || {
+        let g = (0..4).map(|_| next()).sum::<f32>() - 2.0;
+        (g * PATCH_RADIUS as f32 / 1.5).clamp(-(PATCH_RADIUS as f32), PATCH_RADIUS as f32)
+    };
+    (0..256).map(|_| [(coord(), coord()), (coord(), coord())]).collect()
+}
+
+/// Orientation from the intensity centroid of a circular patch.
+fn orientation(gray: &GrayImage, x: u32, y: u32) -> f32 {
+    let (mut m10, mut m01) = (0f32, 0f32);
+    for dy in -PATCH_RADIUS..=PATCH_RADIUS {
+        for dx in -PATCH_RADIUS..=PATCH_RADIUS {
+            if dx * dx + dy * dy > PATCH_RADIUS * PATCH_RADIUS {
+                continue;
+            }
+            let v = gray.get_pixel((x as i32 + dx) as u32, (y as i32 + dy) as u32)[0] as f32;
+            m10 += dx as f32 * v;
+            m01 += dy as f32 * v;
+        }
+    }
+    m01.atan2(m10)
+}
+
+fn describe(smoothed: &GrayImage, kp: &Keypoint, pattern: &[[(f32, f32); 2]]) -> Descriptor {
+    let (sin, cos) = kp.angle.sin_cos();
+    let at = |(px, py): (f32, f32)| {
+        let x = kp.x + px * cos - py * sin;
+        let y = kp.y + px * sin + py * cos;
+        smoothed.get_pixel(x.round() as u32, y.round() as u32)[0]
+    };
+    let mut d = [0u8; 32];
+    for (bit, [a, b]) in pattern.iter().enumerate() {
+        if at(*a) < at(*b) {
+            d[bit / 8] |= 1 << (bit % 8);
+        }
+    }
+    d
+}
+
+/// Detects keypoints and, for [`FeatureKind::Orb`], computes their descriptors.
+pub fn extract_features(img: &DynamicImage, config: &FeatureExtractNodeConfig) -> Features {
+    let gray = img.to_luma8();
+    let (w, h) = gray.dimensions();
+    let corners: Vec<_> = corners_fast9(&gray, config.fast_threshold)
+        .into_iter()
+        .filter(|c| c.x >= BORDER && c.y >= BORDER && c.x + BORDER < w && c.y + BORDER < h)
+        .collect();
+    let mut corners = local_maxima(&corners, 3);
+    corners.sort_by(|a, b| b.score.total_cmp(&a.score));
+    corners.truncate(config.max_features);
+
+    let keypoints: Vec<Keypoint> = corners
+        .iter()
+        .map(|c| Keypoint {
+            x: c.x as f32,
+            y: c.y as f32,
+            angle: if config.kind == FeatureKind::Orb { orientation(&gray, c.x, c.y) } else { 0.0 },
+            score: c.score,
+        })
+        .collect();
+
+    let descriptors = match config.kind {
+        FeatureKind::Fast => Vec::new(),
+        FeatureKind::Orb => {
+            let smoothed = imageops::blur(&gray, 2.0);
+            let pattern = brief_pattern();
+            keypoints.iter().map(|kp| describe(&smoothed, kp, &pattern)).collect()
+        }
+    };
+    Features { width: w, height: h, keypoints, descriptors }
+}
+
+/// Extracts keypoints and binary descriptors from every frame.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct FeatureExtractNode {
+    #[output]
+    pub output: Output<Features>,
+
+    #[input]
+    pub input: Input<DynamicImage>,
+
+    pub config: FeatureExtractNodeConfig,
+}
+
+impl FeatureExtractNode {
+    pub fn new(config: FeatureExtractNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            input: Input::new(),
+            config,
+        }
+    }
+}
+
+impl Node for FeatureExtractNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        if let Ok(img) = self.input.next() {
+            let features = extract_features(&img, &self.config);
+            self.output.send(features).map_err(|e| UpdateError::Other(e.into()))?;
+        }
+        Ok(())
+    }
+}
+
+pub fn hamming(a: &Descriptor, b: &Descriptor) -> u32 {
+    a.iter().zip(b).map(|(x, y)| (x ^ y).count_ones()).sum()
+}
+
+/// A matched keypoint pair between the left and right feature sets.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+pub struct Correspondence {
+    pub left: usize,
+    pub right: usize,
+    pub from: (f32, f32),
+    pub to: (f32, f32),
+    pub distance: u32,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct FeatureMatchNodeConfig {
+    /// Maximum Hamming distance of an accepted match, out of 256 bits.
+    pub max_distance: u32,
+    /// Lowe's ratio test: the best match must be this much closer than the second best.
+    pub ratio: f32,
+    /// Keep only matches that are also the best match in the reverse direction.
+    pub cross_check: bool,
+}
+
+impl Default for FeatureMatchNodeConfig {
+    fn default() -> Self {
+        Self { max_distance: 64, ratio: 0.8, cross_check: true }
+    }
+}
+
+impl Validate for FeatureMatchNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        ensure(self.ratio > 0.0 && self.ratio <= 1.0, "ratio", "must be in (0, 1]")
+    }
+}
+
+config_builder!(FeatureMatchNodeConfig for FeatureMatchNode { max_distance: u32, ratio: f32, cross_check: bool });
+
+/// Best and second best Hamming distances from `d` into `candidates`.
+fn nearest(d: &Descriptor, candidates: &[Descriptor]) -> Option<(usize, u32, u32)> {
+    let mut best = (0, u32::MAX, u32::MAX);
+    for (j, c) in candidates.iter().enumerate() {
+        let dist = hamming(d, c);
+        if dist < best.1 {
+            best = (j, dist, best.1);
+        } else if dist < best.2 {
+            best.2 = dist;
+        }
+    }
+    (!candidates.is_empty()).then_some(best)
+}
+
+/// Brute-force descriptor matching with ratio test and optional cross-check.
+pub fn match_features(left: &Features, right: &Features, config: &FeatureMatchNodeConfig) -> Vec<Correspondence> {
+    left.descriptors
+        .iter()
+        .enumerate()
+        .filter_map(|(i, d)| {
+            let (j, best, second) = nearest(d, &right.descriptors)?;
+            let distinct = second == u32::MAX || (best as f32) < config.ratio * second as f32;
+            if best > config.max_distance || !distinct {
+                return None;
+            }
+            if config.cross_check && nearest(&right.descriptors[j], &left.descriptors).map(|m| m.0) != Some(i) {
+                return None;
+            }
+            let (a, b) = (left.keypoints[i], right.keypoints[j]);
+            Some(Correspondence { left: i, right: j, from: (a.x, a.y), to: (b.x, b.y), distance: best })
+        })
+        .collect()
+}
+
+/// Matches features between two streams, e.g. a reference view and live frames.
+///
+/// The latest set from each input is kept, and matches are emitted whenever
+/// either side receives a new set once both are available.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct FeatureMatchNode {
+    #[output]
+    pub output: Output<Vec<Correspondence>>,
+
+    #[input]
+    pub left: Input<Features>,
+
+    #[input]
+    pub right: Input<Features>,
+
+    pub config: FeatureMatchNodeConfig,
+
+    #[serde(skip)]
+    latest: (Option<Features>, Option<Features>),
+}
+
+impl FeatureMatchNode {
+    pub fn new(config: FeatureMatchNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            left: Input::new(),
+            right: Input::new(),
+            config,
+            latest: (None, None),
+        }
+    }
+}
+
+impl Node for FeatureMatchNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        let mut changed = false;
+        while let Ok(features) = self.left.next() {
+            self.latest.0 = Some(features);
+            changed = true;
+        }
+        while let Ok(features) = self.right.next() {
+            self.latest.1 = Some(features);
+            changed = true;
+        }
+
+        if let (true, (Some(left), Some(right))) = (changed, &self.latest) {
+            let matches = match_features(left, right, &self.config);
+            self.output.send(matches).map_err(|e| UpdateError::Other(e.into()))?;
+        }
+        Ok(())
+    }
+}
+
+/// Minimal xorshift generator so RANSAC results are reproducible.
+struct XorShift(u64);
+
+impl XorShift {
+    fn below(&mut self, n: usize) -> usize {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 % n as u64) as usize
+    }
+}
+
+/// Projection mapping `to` points onto their `from` counterparts, with its inlier count.
+///
+/// Candidates are fitted to random minimal samples; the one agreeing with the
+/// most correspondences within `threshold` pixels wins.
+pub fn estimate_homography(matches: &[Correspondence], threshold: f32, iterations: usize) -> Option<(Projection, usize)> {
+    if matches.len() < 4 {
+        return None;
+    }
+    let mut rng = XorShift(0x2545_F491_4F6C_DD1D);
+    let mut best: Option<(Projection, usize)> = None;
+    for _ in 0..iterations {
+        let mut idx: Vec<usize> = Vec::with_capacity(4);
+        while idx.len() < 4 {
+            let i = rng.below(matches.len());
+            if !idx.contains(&i) {
+                idx.push(i);
+            }
+        }
+        let from = [0, 1, 2, 3].map(|k| matches[idx[k]].to);
+        let to = [0, 1, 2, 3].map(|k| matches[idx[k]].from);
+        let Some(h) = Projection::from_control_points(from, to) else { continue };
+        let inliers = matches
+            .iter()
+            .filter(|m| {
+                let (x, y) = h * m.to;
+                (x - m.from.0).hypot(y - m.from.1) <= threshold
+            })
+            .count();
+        if best.as_ref().is_none_or(|b| inliers > b.1) {
+            best = Some((h, inliers));
+        }
+    }
+    best
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct StitchNodeConfig {
+    pub features: FeatureExtractNodeConfig,
+    pub matching: FeatureMatchNodeConfig,
+    /// Maximum reprojection error, in pixels, for a match to support a homography.
+    pub ransac_threshold: f32,
+    pub ransac_iterations: usize,
+    /// Frames registering with fewer inliers are skipped.
+    pub min_inliers: usize,
+    /// Emit the panorama after every added frame instead of only on flush.
+    pub incremental: bool,
+    /// Refuse to render panoramas larger than this many pixels.
+    pub max_pixels: u64,
+}
+
+impl Default for StitchNodeConfig {
+    fn default() -> Self {
+        Self {
+            features: FeatureExtractNodeConfig::default(),
+            matching: FeatureMatchNodeConfig::default(),
+            ransac_threshold: 3.0,
+            ransac_iterations: 1000,
+            min_inliers: 12,
+            incremental: false,
+            max_pixels: 100_000_000,
+        }
+    }
+}
+
+impl Validate for StitchNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        self.features.validate_in("features")?;
+        self.matching.validate_in("matching")?;
+        ensure(self.ransac_iterations > 0, "ransac_iterations", "must be positive")?;
+        ensure(self.min_inliers >= 4, "min_inliers", "a homography needs at least 4")
+    }
+}
+
+config_builder!(StitchNodeConfig for StitchNode {
+    features: FeatureExtractNodeConfig,
+    matching: FeatureMatchNodeConfig,
+    ransac_threshold: f32,
+    ransac_iterations: usize,
+    min_inliers: usize,
+    incremental: bool,
+    max_pixels: u64,
+});
+
+struct StitchFrame {
+    image: RgbaImage,
+    /// Maps frame pixels into the coordinate system of the first frame.
+    to_reference: Projection,
+}
+
+/// Accumulates overlapping frames into a panorama.
+///
+/// Every frame is registered to its predecessor by feature matching and a
+/// RANSAC-fitted homography, then all frames are blended with weights falling
+/// off towards their borders. A `true` flush emits the panorama and starts a new one.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct StitchNode {
+    #[output]
+    pub output: Output<DynamicImage>,
+
+    #[input]
+    pub input: Input<DynamicImage>,
+
+    #[input]
+    pub flush: Input<bool>,
+
+    pub config: StitchNodeConfig,
+
+    #[serde(skip)]
+    frames: Vec<StitchFrame>,
+    #[serde(skip)]
+    previous: Option<Features>,
+}
+
+impl StitchNode {
+    pub fn new(config: StitchNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            input: Input::new(),
+            flush: Input::new(),
+            config,
+            frames: Vec::new(),
+            previous: None,
+        }
+    }
+
+    /// Registers the frame against its predecessor; returns whether it was added.
+    fn add(&mut self, img: &DynamicImage) -> bool {
+        let features = extract_features(img, &self.config.features);
+        let to_reference = match (&self.previous, self.frames.last()) {
+            (Some(previous), Some(last)) => {
+                let matches = match_features(previous, &features, &self.config.matching);
+                match estimate_homography(&matches, self.config.ransac_threshold, self.config.ransac_iterations) {
+                    Some((h, inliers)) if inliers >= self.config.min_inliers => h.and_then(last.to_reference),
+                    _ => return false,
+                }
+            }
+            _ => Projection::translate(0.0, 0.0),
+        };
+        self.frames.push(StitchFrame { image: img.to_rgba8(), to_reference });
+        self.previous = Some(features);
+        true
+    }
+}
+
+fn bilinear(img: &RgbaImage, x: f32, y: f32) -> [f32; 4] {
+    let (x0, y0) = (x.floor() as u32, y.floor() as u32);
+    let (x1, y1) = ((x0 + 1).min(img.width() - 1), (y0 + 1).min(img.height() - 1));
+    let (fx, fy) = (x - x0 as f32, y - y0 as f32);
+    let mut out = [0f32; 4];
+    for (c, o) in out.iter_mut().enumerate() {
+        let p = |x, y| img.get_pixel(x, y)[c] as f32;
+        *o = (p(x0, y0) * (1.0 - fx) + p(x1, y0) * fx) * (1.0 - fy) + (p(x0, y1) * (1.0 - fx) + p(x1, y1) * fx) * fy;
+    }
+    out
+}
+
+fn render_panorama(frames: &[StitchFrame], max_pixels: u64) -> Result<RgbaImage, anyhow::Error> {
+    let corners = |f: &StitchFrame| {
+        let (w, h) = (f.image.width() as f32, f.image.height() as f32);
+        [(0.0, 0.0), (w, 0.0), (0.0, h), (w, h)].map(|p| f.to_reference * p)
+    };
+    let (mut min, mut max) = ((f32::MAX, f32::MAX), (f32::MIN, f32::MIN));
+    for (x, y) in frames.iter().flat_map(corners) {
+        // A near-singular homography maps corners to infinity, which min/max would hide or propagate.
+        if !x.is_finite() || !y.is_finite() {
+            return Err(anyhow!("Panorama bounds are not finite; a frame was registered with a degenerate homography."));
+        }
+        min = (min.0.min(x), min.1.min(y));
+        max = (max.0.max(x), max.1.max(y));
+    }
+    let (width, height) = ((max.0 - min.0).ceil() as u64, (max.1 - min.1).ceil() as u64);
+    if width == 0 || height == 0 || width.checked_mul(height).is_none_or(|pixels| pixels > max_pixels) {
+        return Err(anyhow!("Panorama of {}x{} exceeds the configured size limit.", width, height));
+    }
+    let (Ok(width), Ok(height)) = (u32::try_from(width), u32::try_from(height)) else {
+        return Err(anyhow!("Panorama of {}x{} exceeds the largest image size.", width, height));
+    };
+
+    let mut acc = vec![[0f32; 5]; width as usize * height as usize];
+    for f in frames {
+        let to_canvas = f.to_reference.and_then(Projection::translate(-min.0, -min.1));
+        let from_canvas = to_canvas.invert();
+        let (fw, fh) = (f.image.width() as f32, f.image.height() as f32);
+        let bounds = [(0.0, 0.0), (fw, 0.0), (0.0, fh), (fw, fh)].map(|p| to_canvas * p);
+        let x_range = bounds.iter().map(|p| p.0).fold(f32::MAX, f32::min).max(0.0) as u32
+            ..(bounds.iter().map(|p| p.0).fold(f32::MIN, f32::max).ceil() as u32).min(width);
+        let y_range = bounds.iter().map(|p| p.1).fold(f32::MAX, f32::min).max(0.0) as u32
+            ..(bounds.iter().map(|p| p.1).fold(f32::MIN, f32::max).ceil() as u32).min(height);
+
+        for y in y_range {
+            for x in x_range.clone() {
+                let (sx, sy) = from_canvas * (x as f32, y as f32);
+                if sx < 0.0 || sy < 0.0 || sx > fw - 1.0 || sy > fh - 1.0 {
+                    continue;
+                }
+                // Feathering: pixels near a frame border contribute less to the blend.
+                let weight = sx.min(sy).min(fw - 1.0 - sx).min(fh - 1.0 - sy) + 1.0;
+                let px = bilinear(&f.image, sx, sy);
+                let a = &mut acc[(y * width + x) as usize];
+                (0..4).for_each(|c| a[c] += px[c] * weight);
+                a[4] += weight;
+            }
+        }
+    }
+
+    Ok(RgbaImage::from_fn(width, height, |x, y| {
+        let a = acc[(y * width + x) as usize];
+        if a[4] == 0.0 {
+            Rgba([0, 0, 0, 0])
+        } else {
+            Rgba([0, 1, 2, 3].map(|c| (a[c] / a[4]).round().clamp(0.0, 255.0) as u8))
+        }
+    }))
+}
+
+impl Node for StitchNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        let mut flush = false;
+        while let Ok(triggered) = self.flush.next() {
+            flush |= triggered;
+        }
+
+        if let Ok(img) = self.input.next() {
+            if self.add(&img) && self.config.incremental {
+                let panorama = render_panorama(&self.frames, self.config.max_pixels).map_err(UpdateError::Other)?;
+                self.output.send(DynamicImage::ImageRgba8(panorama)).map_err(|e| UpdateError::Other(e.into()))?;
+            }
+        }
+
+        if flush && !self.frames.is_empty() {
+            let frames = std::mem::take(&mut self.frames);
+            self.previous = None;
+            let panorama = render_panorama(&frames, self.config.max_pixels).map_err(UpdateError::Other)?;
+            self.output.send(DynamicImage::ImageRgba8(panorama)).map_err(|e| UpdateError::Other(e.into()))?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,917 @@
+use flowrs::{node::{Node, UpdateError, ChangeObserver}, connection::{Input, Output}};
+use flowrs::RuntimeConnectable;
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+use std::path::PathBuf;
+
+use image::{DynamicImage, GrayImage, Rgb32FImage};
+use anyhow::anyhow;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::{ensure, ConfigError, Validate};
+use crate::types::Rect;
+
+/// Converts a floating point result back to the pixel layout of `like`, keeping bit depth where possible.
+pub fn match_format(result: Rgb32FImage, like: &DynamicImage) -> DynamicImage {
+    let result = DynamicImage::ImageRgb32F(result);
+    match like {
+        DynamicImage::ImageLuma8(_) => DynamicImage::ImageLuma8(result.into_luma8()),
+        DynamicImage::ImageLumaA8(_) => DynamicImage::ImageLumaA8(result.into_luma_alpha8()),
+        DynamicImage::ImageRgb8(_) => DynamicImage::ImageRgb8(result.into_rgb8()),
+        DynamicImage::ImageRgba8(_) => DynamicImage::ImageRgba8(result.into_rgba8()),
+        DynamicImage::ImageLuma16(_) => DynamicImage::ImageLuma16(result.into_luma16()),
+        DynamicImage::ImageLumaA16(_) => DynamicImage::ImageLumaA16(result.into_luma_alpha16()),
+        DynamicImage::ImageRgb16(_) => DynamicImage::ImageRgb16(result.into_rgb16()),
+        DynamicImage::ImageRgba16(_) => DynamicImage::ImageRgba16(result.into_rgba16()),
+        _ => result,
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum CalibrationTarget {
+    Dark,
+    Flat,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct FlatFieldNodeConfig {
+    pub dark_frame_path: Option<PathBuf>,
+    pub flat_field_path: Option<PathBuf>,
+    /// Number of frames averaged into a master frame when a calibration is triggered.
+    pub calibration_frames: usize,
+}
+
+impl Default for FlatFieldNodeConfig {
+    fn default() -> Self {
+        Self { dark_frame_path: None, flat_field_path: None, calibration_frames: 16 }
+    }
+}
+
+impl Validate for FlatFieldNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        ensure(self.calibration_frames > 0, "calibration_frames", "must be positive")
+    }
+}
+
+config_builder!(FlatFieldNodeConfig for FlatFieldNode {
+    dark_frame_path: Option<PathBuf>,
+    flat_field_path: Option<PathBuf>,
+    calibration_frames: usize,
+});
+
+/// Largest gain applied to a pixel; dead pixels in the flat would otherwise be boosted to saturation.
+const MAX_FLAT_GAIN: f32 = 16.0;
+
+/// Master frames and the per-pixel gain derived from them.
+#[derive(Default)]
+struct Calibration {
+    dark: Option<Rgb32FImage>,
+    gain: Option<Rgb32FImage>,
+    flat: Option<Rgb32FImage>,
+}
+
+impl Calibration {
+    /// Gain normalizes `flat - dark` to its per-channel mean, so correction preserves overall brightness.
+    /// Channels without any response in the flat are left uncorrected.
+    fn update_gain(&mut self) {
+        self.gain = self.flat.as_ref().map(|flat| {
+            let mut response = flat.clone();
+            if let Some(dark) = self.dark.as_ref().filter(|d| d.dimensions() == flat.dimensions()) {
+                for (r, d) in response.iter_mut().zip(dark.iter()) {
+                    *r -= d;
+                }
+            }
+            let pixels = (response.width() * response.height()).max(1) as f32;
+            let mut mean = [0f32; 3];
+            for px in response.pixels() {
+                (0..3).for_each(|c| mean[c] += px[c] / pixels);
+            }
+            for px in response.pixels_mut() {
+                for c in 0..3 {
+                    px[c] = if mean[c] > 0.0 { (mean[c] / px[c].max(1e-6)).min(MAX_FLAT_GAIN) } else { 1.0 };
+                }
+            }
+            response
+        });
+    }
+
+    fn apply(&self, raw: &DynamicImage) -> Result<Rgb32FImage, anyhow::Error> {
+        let mut out = raw.to_rgb32f();
+        for master in [&self.dark, &self.gain].into_iter().flatten() {
+            if master.dimensions() != out.dimensions() {
+                return Err(anyhow!(
+                    "Calibration frame is {:?} but image is {:?}.", master.dimensions(), out.dimensions()));
+            }
+        }
+        if let Some(dark) = &self.dark {
+            out.iter_mut().zip(dark.iter()).for_each(|(o, d)| *o = (*o - d).max(0.0));
+        }
+        if let Some(gain) = &self.gain {
+            out.iter_mut().zip(gain.iter()).for_each(|(o, g)| *o *= g);
+        }
+        Ok(out)
+    }
+}
+
+/// Dark-frame subtraction and flat-field division for scientific capture flows.
+///
+/// Master frames are loaded from disk at the first update, or recorded by sending a
+/// [`CalibrationTarget`] on `calibrate`, which averages the next `calibration_frames` frames.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct FlatFieldNode {
+    #[output]
+    pub output: Output<DynamicImage>,
+
+    #[input]
+    pub input: Input<DynamicImage>,
+
+    #[input]
+    pub calibrate: Input<CalibrationTarget>,
+
+    pub config: FlatFieldNodeConfig,
+
+    #[serde(skip)]
+    calibration: Option<Calibration>,
+    #[serde(skip)]
+    recording: Option<(CalibrationTarget, Vec<Rgb32FImage>)>,
+}
+
+impl FlatFieldNode {
+    pub fn new(config: FlatFieldNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            input: Input::new(),
+            calibrate: Input::new(),
+            config,
+            calibration: None,
+            recording: None,
+        }
+    }
+
+    fn load(&self) -> Result<Calibration, anyhow::Error> {
+        let open = |path: &Option<PathBuf>| -> Result<Option<Rgb32FImage>, anyhow::Error> {
+            path.as_ref().map(|p| Ok(image::open(p)?.into_rgb32f())).transpose()
+        };
+        let mut calibration = Calibration {
+            dark: open(&self.config.dark_frame_path)?,
+            flat: open(&self.config.flat_field_path)?,
+            gain: None,
+        };
+        calibration.update_gain();
+        Ok(calibration)
+    }
+
+    fn record(&mut self, img: &DynamicImage) {
+        let Some((target, frames)) = self.recording.as_mut() else { return };
+        frames.push(img.to_rgb32f());
+        if frames.len() < self.config.calibration_frames.max(1) {
+            return;
+        }
+
+        let target = *target;
+        let frames = std::mem::take(frames);
+        self.recording = None;
+        if frames.iter().any(|f| f.dimensions() != frames[0].dimensions()) {
+            return;
+        }
+        let mut master = frames[0].clone();
+        master.iter_mut().for_each(|v| *v = 0.0);
+        let n = frames.len() as f32;
+        for frame in &frames {
+            master.iter_mut().zip(frame.iter()).for_each(|(m, v)| *m += v / n);
+        }
+
+        let calibration = self.calibration.get_or_insert_with(Calibration::default);
+        match target {
+            CalibrationTarget::Dark => calibration.dark = Some(master),
+            CalibrationTarget::Flat => calibration.flat = Some(master),
+        }
+        calibration.update_gain();
+    }
+}
+
+impl Node for FlatFieldNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        if self.calibration.is_none() {
+            self.calibration = Some(self.load().map_err(UpdateError::Other)?);
+        }
+        while let Ok(target) = self.calibrate.next() {
+            self.recording = Some((target, Vec::new()));
+        }
+
+        if let Ok(img) = self.input.next() {
+            frame_span!("flat_field", width = img.width(), height = img.height());
+            self.record(&img);
+            let corrected = self.calibration.as_ref().expect("loaded above").apply(&img).map_err(UpdateError::Other)?;
+            self.output.send(match_format(corrected, &img)).map_err(|e| UpdateError::Other(e.into()))?;
+        }
+        Ok(())
+    }
+}
+
+/// Field of an interlaced frame; the top field holds the even lines.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum Field {
+    #[default]
+    Top,
+    Bottom,
+}
+
+impl Field {
+    fn parity(&self) -> u32 {
+        match self {
+            Field::Top => 0,
+            Field::Bottom => 1,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum DeinterlaceMode {
+    /// Sends each field as a frame of its own, interpolating the missing lines; doubles the frame rate.
+    Bob,
+    /// Keeps both fields, for progressive content carried in an interlaced signal.
+    Weave,
+    /// Blends every line with its neighbours, trading vertical resolution for no combing.
+    #[default]
+    LinearBlend,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct DeinterlaceNodeConfig {
+    pub mode: DeinterlaceMode,
+    /// Field captured first, sent first in [`DeinterlaceMode::Bob`].
+    pub field_order: Field,
+}
+
+impl Validate for DeinterlaceNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        Ok(())
+    }
+}
+
+config_builder!(DeinterlaceNodeConfig for DeinterlaceNode { mode: DeinterlaceMode, field_order: Field });
+
+/// Rebuilds a full frame from one field by averaging the lines above and below each missing line.
+pub fn bob_field(img: &Rgb32FImage, field: Field) -> Rgb32FImage {
+    let (width, height) = img.dimensions();
+    let mut out = img.clone();
+    if height < 2 {
+        return out;
+    }
+    let own = |y: u32| y % 2 == field.parity();
+    for y in (0..height).filter(|&y| !own(y)) {
+        // Lines at the image border only have one neighbour in the field.
+        let above = y.checked_sub(1).unwrap_or(y + 1);
+        let below = if y + 1 < height { y + 1 } else { above };
+        for x in 0..width {
+            let (a, b) = (img.get_pixel(x, above), img.get_pixel(x, below));
+            out.put_pixel(x, y, image::Rgb([0, 1, 2].map(|c| (a[c] + b[c]) / 2.0)));
+        }
+    }
+    out
+}
+
+/// Vertical `[1 2 1] / 4` filter merging both fields into every line.
+pub fn linear_blend(img: &Rgb32FImage) -> Rgb32FImage {
+    let (width, height) = img.dimensions();
+    let mut out = img.clone();
+    for y in 0..height {
+        let above = y.saturating_sub(1);
+        let below = (y + 1).min(height - 1);
+        for x in 0..width {
+            let (a, p, b) = (img.get_pixel(x, above), img.get_pixel(x, y), img.get_pixel(x, below));
+            out.put_pixel(x, y, image::Rgb([0, 1, 2].map(|c| (a[c] + 2.0 * p[c] + b[c]) / 4.0)));
+        }
+    }
+    out
+}
+
+/// Removes combing from interlaced frames, e.g. from analog capture cards.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct DeinterlaceNode {
+    #[output]
+    pub output: Output<DynamicImage>,
+
+    #[input]
+    pub input: Input<DynamicImage>,
+
+    pub config: DeinterlaceNodeConfig,
+}
+
+impl DeinterlaceNode {
+    pub fn new(config: DeinterlaceNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            input: Input::new(),
+            config,
+        }
+    }
+}
+
+impl Node for DeinterlaceNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        if let Ok(img) = self.input.next() {
+            frame_span!("deinterlace", width = img.width(), height = img.height());
+            let frames = match self.config.mode {
+                DeinterlaceMode::Weave => vec![img],
+                DeinterlaceMode::LinearBlend => vec![match_format(linear_blend(&img.to_rgb32f()), &img)],
+                DeinterlaceMode::Bob => {
+                    let frame = img.to_rgb32f();
+                    let second = match self.config.field_order {
+                        Field::Top => Field::Bottom,
+                        Field::Bottom => Field::Top,
+                    };
+                    [self.config.field_order, second].map(|f| match_format(bob_field(&frame, f), &img)).to_vec()
+                }
+            };
+            for frame in frames {
+                self.output.send(frame).map_err(|e| UpdateError::Other(e.into()))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Optical parameters of a lens, as measured once per lens and aperture.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct LensProfile {
+    /// Coefficients `k1..k3` of the gain `1 + k1 r² + k2 r⁴ + k3 r⁶`, with `r = 1` at the corners.
+    pub vignette: [f32; 3],
+    /// Magnification of the red and blue channels relative to green, correcting lateral aberration.
+    pub red_scale: f32,
+    pub blue_scale: f32,
+    /// Optical center relative to the image size; the image center if unset.
+    pub center: Option<(f32, f32)>,
+}
+
+impl Default for LensProfile {
+    fn default() -> Self {
+        Self { vignette: [0.0; 3], red_scale: 1.0, blue_scale: 1.0, center: None }
+    }
+}
+
+impl Validate for LensProfile {
+    fn validate(&self) -> Result<(), ConfigError> {
+        ensure(self.vignette.iter().all(|k| k.is_finite()), "vignette", "must be finite")?;
+        ensure(self.red_scale > 0.0 && self.blue_scale > 0.0, "red_scale", "channel scales must be positive")?;
+        ensure(self.center.is_none_or(|(x, y)| (0.0..=1.0).contains(&x) && (0.0..=1.0).contains(&y)), "center", "must lie within the image")
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct LensCorrectionNodeConfig {
+    pub profile: LensProfile,
+    /// JSON calibration profile replacing `profile`.
+    pub profile_path: Option<PathBuf>,
+}
+
+impl Validate for LensCorrectionNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        self.profile.validate_in("profile")
+    }
+}
+
+config_builder!(LensCorrectionNodeConfig for LensCorrectionNode {
+    profile: LensProfile,
+    profile_path: Option<PathBuf>,
+});
+
+fn sample_bilinear(img: &Rgb32FImage, channel: usize, x: f32, y: f32) -> f32 {
+    let (w, h) = img.dimensions();
+    let x = x.clamp(0.0, (w - 1) as f32);
+    let y = y.clamp(0.0, (h - 1) as f32);
+    let (x0, y0) = (x.floor() as u32, y.floor() as u32);
+    let (x1, y1) = ((x0 + 1).min(w - 1), (y0 + 1).min(h - 1));
+    let (fx, fy) = (x - x0 as f32, y - y0 as f32);
+    let top = img.get_pixel(x0, y0)[channel] * (1.0 - fx) + img.get_pixel(x1, y0)[channel] * fx;
+    let bottom = img.get_pixel(x0, y1)[channel] * (1.0 - fx) + img.get_pixel(x1, y1)[channel] * fx;
+    top * (1.0 - fy) + bottom * fy
+}
+
+/// Compensates radial light falloff and rescales the red and blue channels around the optical center.
+pub fn correct_lens(img: &Rgb32FImage, profile: &LensProfile) -> Rgb32FImage {
+    let (w, h) = img.dimensions();
+    if w == 0 || h == 0 {
+        return img.clone();
+    }
+    let (cx, cy) = profile.center.unwrap_or((0.5, 0.5));
+    let (cx, cy) = (cx * (w - 1) as f32, cy * (h - 1) as f32);
+    let norm = (cx.max(w as f32 - 1.0 - cx).powi(2) + cy.max(h as f32 - 1.0 - cy).powi(2)).max(1.0);
+    let [k1, k2, k3] = profile.vignette;
+    let scales = [profile.red_scale, 1.0, profile.blue_scale];
+
+    Rgb32FImage::from_fn(w, h, |x, y| {
+        let (dx, dy) = (x as f32 - cx, y as f32 - cy);
+        let r2 = (dx * dx + dy * dy) / norm;
+        let gain = 1.0 + k1 * r2 + k2 * r2 * r2 + k3 * r2 * r2 * r2;
+        image::Rgb([0, 1, 2].map(|c| {
+            let value = if scales[c] == 1.0 {
+                img.get_pixel(x, y)[c]
+            } else {
+                sample_bilinear(img, c, cx + dx * scales[c], cy + dy * scales[c])
+            };
+            value * gain
+        }))
+    })
+}
+
+/// Vignette and lateral chromatic aberration correction, needed before photometric measurements.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct LensCorrectionNode {
+    #[output]
+    pub output: Output<DynamicImage>,
+
+    #[input]
+    pub input: Input<DynamicImage>,
+
+    pub config: LensCorrectionNodeConfig,
+
+    #[serde(skip)]
+    profile: Option<LensProfile>,
+}
+
+impl LensCorrectionNode {
+    pub fn new(config: LensCorrectionNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            input: Input::new(),
+            config,
+            profile: None,
+        }
+    }
+
+    fn load(&self) -> Result<LensProfile, anyhow::Error> {
+        match &self.config.profile_path {
+            Some(path) => Ok(serde_json::from_slice(&std::fs::read(path)?)?),
+            None => Ok(self.config.profile.clone()),
+        }
+    }
+}
+
+impl Node for LensCorrectionNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        if self.profile.is_none() {
+            self.profile = Some(self.load().map_err(UpdateError::Other)?);
+        }
+
+        if let Ok(img) = self.input.next() {
+            frame_span!("lens_correction", width = img.width(), height = img.height());
+            let corrected = correct_lens(&img.to_rgb32f(), self.profile.as_ref().expect("loaded above"));
+            self.output.send(match_format(corrected, &img)).map_err(|e| UpdateError::Other(e.into()))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+pub enum TemporalMode {
+    /// Running average where each new frame has weight `alpha`.
+    ExponentialAverage { alpha: f32 },
+    /// Median of the last `frames` frames, which rejects flicker and single-frame noise.
+    Median { frames: usize },
+}
+
+impl Default for TemporalMode {
+    fn default() -> Self {
+        TemporalMode::ExponentialAverage { alpha: 0.2 }
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct TemporalDenoiseNodeConfig {
+    pub mode: TemporalMode,
+    /// Pixels differing from the history by more than this, in `0.0..=1.0`, are treated as
+    /// motion and taken from the current frame to avoid ghosting; `None` disables it.
+    pub motion_threshold: Option<f32>,
+}
+
+impl Validate for TemporalDenoiseNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        match self.mode {
+            TemporalMode::ExponentialAverage { alpha } => ensure(alpha > 0.0 && alpha <= 1.0, "mode.alpha", "must be in (0, 1]"),
+            TemporalMode::Median { frames } => ensure(frames > 0, "mode.frames", "must be positive"),
+        }
+    }
+}
+
+config_builder!(TemporalDenoiseNodeConfig for TemporalDenoiseNode {
+    mode: TemporalMode,
+    motion_threshold: Option<f32>,
+});
+
+/// History of a temporal filter; restarts whenever the frame size changes.
+#[derive(Default)]
+pub struct TemporalFilter {
+    average: Option<Rgb32FImage>,
+    history: VecDeque<Rgb32FImage>,
+}
+
+impl TemporalFilter {
+    pub fn apply(&mut self, frame: &Rgb32FImage, config: &TemporalDenoiseNodeConfig) -> Rgb32FImage {
+        if self.average.as_ref().is_some_and(|a| a.dimensions() != frame.dimensions()) {
+            *self = Self::default();
+        }
+        let mut smoothed = match config.mode {
+            TemporalMode::ExponentialAverage { alpha } => {
+                let alpha = alpha.clamp(0.0, 1.0);
+                let mut average = self.average.take().unwrap_or_else(|| frame.clone());
+                average.iter_mut().zip(frame.iter()).for_each(|(a, v)| *a += alpha * (v - *a));
+                average
+            }
+            TemporalMode::Median { frames } => {
+                self.history.push_back(frame.clone());
+                while self.history.len() > frames.max(1) {
+                    self.history.pop_front();
+                }
+                let mut values = Vec::with_capacity(self.history.len());
+                let mut median = frame.clone();
+                for (i, m) in median.iter_mut().enumerate() {
+                    values.clear();
+                    values.extend(self.history.iter().map(|f| f.as_raw()[i]));
+                    values.sort_by(f32::total_cmp);
+                    *m = values[values.len() / 2];
+                }
+                median
+            }
+        };
+
+        if let Some(threshold) = config.motion_threshold {
+            for (px, current) in smoothed.pixels_mut().zip(frame.pixels()) {
+                if (0..3).any(|c| (px[c] - current[c]).abs() > threshold) {
+                    *px = *current;
+                }
+            }
+        }
+        self.average = Some(smoothed.clone());
+        smoothed
+    }
+}
+
+/// Smooths sensor noise over time, e.g. for low-light webcam feeds ahead of detection.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct TemporalDenoiseNode {
+    #[output]
+    pub output: Output<DynamicImage>,
+
+    #[input]
+    pub input: Input<DynamicImage>,
+
+    pub config: TemporalDenoiseNodeConfig,
+
+    #[serde(skip)]
+    filter: TemporalFilter,
+}
+
+impl TemporalDenoiseNode {
+    pub fn new(config: TemporalDenoiseNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            input: Input::new(),
+            config,
+            filter: TemporalFilter::default(),
+        }
+    }
+}
+
+impl Node for TemporalDenoiseNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        if let Ok(img) = self.input.next() {
+            frame_span!("temporal_denoise", width = img.width(), height = img.height());
+            let smoothed = self.filter.apply(&img.to_rgb32f(), &self.config);
+            self.output.send(match_format(smoothed, &img)).map_err(|e| UpdateError::Other(e.into()))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+pub enum DenoiseMethod {
+    /// Average of the neighbourhood weighted by distance and color difference, which keeps edges.
+    /// `sigma_color` is in `0.0..=1.0` units; smaller keeps more edges.
+    Bilateral { radius: u32, sigma_space: f32, sigma_color: f32 },
+    /// Average of the pixels in the search window whose surrounding patch looks alike; slower
+    /// than bilateral, but keeps fine texture. Larger `h` smooths more.
+    NonLocalMeans { patch_radius: u32, search_radius: u32, h: f32 },
+}
+
+impl Default for DenoiseMethod {
+    fn default() -> Self {
+        DenoiseMethod::Bilateral { radius: 3, sigma_space: 2.0, sigma_color: 0.15 }
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct DenoiseNodeConfig {
+    pub method: DenoiseMethod,
+}
+
+impl Validate for DenoiseNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        match self.method {
+            DenoiseMethod::Bilateral { radius, sigma_space, sigma_color } => {
+                ensure(radius > 0, "method.radius", "must be positive")?;
+                ensure(sigma_space > 0.0 && sigma_color > 0.0, "method.sigma_space", "sigmas must be positive")
+            }
+            DenoiseMethod::NonLocalMeans { search_radius, h, .. } => {
+                ensure(search_radius > 0, "method.search_radius", "must be positive")?;
+                ensure(h > 0.0, "method.h", "must be positive")
+            }
+        }
+    }
+}
+
+config_builder!(DenoiseNodeConfig for DenoiseNode { method: DenoiseMethod });
+
+/// Pixel at `(x + dx, y + dy)`, clamped to the image.
+fn clamped(img: &Rgb32FImage, x: u32, y: u32, dx: i32, dy: i32) -> [f32; 3] {
+    let cx = (x as i32 + dx).clamp(0, img.width() as i32 - 1) as u32;
+    let cy = (y as i32 + dy).clamp(0, img.height() as i32 - 1) as u32;
+    img.get_pixel(cx, cy).0
+}
+
+/// Bilateral filter over a `(2 * radius + 1)²` window.
+pub fn bilateral(img: &Rgb32FImage, radius: u32, sigma_space: f32, sigma_color: f32) -> Rgb32FImage {
+    let r = radius as i32;
+    let space: Vec<f32> = (-r..=r)
+        .flat_map(|dy| (-r..=r).map(move |dx| (-((dx * dx + dy * dy) as f32) / (2.0 * sigma_space * sigma_space)).exp()))
+        .collect();
+    let color_scale = -1.0 / (2.0 * sigma_color * sigma_color);
+
+    Rgb32FImage::from_fn(img.width(), img.height(), |x, y| {
+        let center = img.get_pixel(x, y).0;
+        let mut sum = [0.0f32; 3];
+        let mut total = 0.0;
+        for (i, (dx, dy)) in (-r..=r).flat_map(|dy| (-r..=r).map(move |dx| (dx, dy))).enumerate() {
+            let p = clamped(img, x, y, dx, dy);
+            let diff: f32 = (0..3).map(|c| (p[c] - center[c]).powi(2)).sum();
+            let w = space[i] * (diff * color_scale).exp();
+            (0..3).for_each(|c| sum[c] += w * p[c]);
+            total += w;
+        }
+        image::Rgb(sum.map(|s| s / total))
+    })
+}
+
+/// Non-local means with square patches and search window, both clamped at the border.
+pub fn non_local_means(img: &Rgb32FImage, patch_radius: u32, search_radius: u32, h: f32) -> Rgb32FImage {
+    let (p, s) = (patch_radius as i32, search_radius as i32);
+    let patch_len = ((2 * p + 1) * (2 * p + 1) * 3) as f32;
+    let scale = -1.0 / (h * h);
+
+    Rgb32FImage::from_fn(img.width(), img.height(), |x, y| {
+        let mut sum = [0.0f32; 3];
+        let mut total = 0.0;
+        for sy in -s..=s {
+            for sx in -s..=s {
+                let mut distance = 0.0;
+                for py in -p..=p {
+                    for px in -p..=p {
+                        let a = clamped(img, x, y, px, py);
+                        let b = clamped(img, x, y, sx + px, sy + py);
+                        distance += (0..3).map(|c| (a[c] - b[c]).powi(2)).sum::<f32>();
+                    }
+                }
+                let w = (distance / patch_len * scale).exp();
+                let q = clamped(img, x, y, sx, sy);
+                (0..3).for_each(|c| sum[c] += w * q[c]);
+                total += w;
+            }
+        }
+        image::Rgb(sum.map(|v| v / total))
+    })
+}
+
+/// Spatial denoising of single frames, a higher quality alternative to a Gaussian blur.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct DenoiseNode {
+    #[output]
+    pub output: Output<DynamicImage>,
+
+    #[input]
+    pub input: Input<DynamicImage>,
+
+    pub config: DenoiseNodeConfig,
+}
+
+impl DenoiseNode {
+    pub fn new(config: DenoiseNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            input: Input::new(),
+            config,
+        }
+    }
+}
+
+impl Node for DenoiseNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        if let Ok(img) = self.input.next() {
+            frame_span!("denoise", width = img.width(), height = img.height());
+            let rgb = img.to_rgb32f();
+            let denoised = match self.config.method {
+                DenoiseMethod::Bilateral { radius, sigma_space, sigma_color } => bilateral(&rgb, radius, sigma_space, sigma_color),
+                DenoiseMethod::NonLocalMeans { patch_radius, search_radius, h } => non_local_means(&rgb, patch_radius, search_radius, h),
+            };
+            self.output.send(match_format(denoised, &img)).map_err(|e| UpdateError::Other(e.into()))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+pub enum InpaintMethod {
+    /// Fills from the border inwards in fast-marching order, after Telea, averaging the known
+    /// pixels within `radius` weighted by distance and by how close they are to the border.
+    Telea { radius: u32 },
+    /// Starts from the Telea fill and relaxes it towards a smooth membrane over the hole.
+    Diffusion { iterations: u32 },
+}
+
+impl Default for InpaintMethod {
+    fn default() -> Self {
+        InpaintMethod::Telea { radius: 5 }
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct InpaintNodeConfig {
+    pub method: InpaintMethod,
+    /// Fixed areas to fill in every frame, e.g. a burned-in timestamp, on top of the mask input.
+    pub regions: Vec<Rect>,
+}
+
+impl Validate for InpaintNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        match self.method {
+            InpaintMethod::Telea { radius } => ensure(radius > 0, "method.radius", "must be positive"),
+            InpaintMethod::Diffusion { .. } => Ok(()),
+        }
+    }
+}
+
+config_builder!(InpaintNodeConfig for InpaintNode { method: InpaintMethod, regions: Vec<Rect> });
+
+/// Fills the pixels where `mask` is nonzero from their surroundings.
+pub fn inpaint(img: &Rgb32FImage, mask: &GrayImage, method: InpaintMethod) -> Rgb32FImage {
+    let (width, height) = img.dimensions();
+    let index = |x: u32, y: u32| (y * width + x) as usize;
+    let mut out = img.clone();
+    // Distance from the border of the hole, in fill order; 0 for known pixels.
+    let mut level = vec![f32::INFINITY; (width * height) as usize];
+    let mut queue = BinaryHeap::new();
+    for (x, y, m) in mask.enumerate_pixels() {
+        if m[0] == 0 {
+            level[index(x, y)] = 0.0;
+        }
+    }
+    for (x, y, m) in mask.enumerate_pixels() {
+        if m[0] != 0 && neighbours(x, y, width, height).any(|(nx, ny)| level[index(nx, ny)] == 0.0) {
+            level[index(x, y)] = 1.0;
+            queue.push(Reverse((1u32, index(x, y))));
+        }
+    }
+
+    let radius = match method {
+        InpaintMethod::Telea { radius } => radius as i32,
+        InpaintMethod::Diffusion { .. } => 3,
+    };
+    let mut filled = vec![false; level.len()];
+    while let Some(Reverse((t, i))) = queue.pop() {
+        if filled[i] {
+            continue;
+        }
+        filled[i] = true;
+        let (x, y) = (i as u32 % width, i as u32 / width);
+
+        let mut sum = [0.0f32; 3];
+        let mut total = 0.0;
+        for dy in -radius..=radius {
+            for dx in -radius..=radius {
+                let (qx, qy) = (x as i32 + dx, y as i32 + dy);
+                if qx < 0 || qy < 0 || qx >= width as i32 || qy >= height as i32 || (dx == 0 && dy == 0) {
+                    continue;
+                }
+                let q = index(qx as u32, qy as u32);
+                let d2 = (dx * dx + dy * dy) as f32;
+                if d2 > (radius * radius) as f32 || !(level[q] == 0.0 || filled[q]) {
+                    continue;
+                }
+                // Pixels nearer the original border carry more reliable information.
+                let w = 1.0 / (d2 * (1.0 + (t as f32 - level[q]).abs()));
+                let p = out.get_pixel(qx as u32, qy as u32);
+                (0..3).for_each(|c| sum[c] += w * p[c]);
+                total += w;
+            }
+        }
+        if total > 0.0 {
+            out.put_pixel(x, y, image::Rgb(sum.map(|s| s / total)));
+        }
+
+        for (nx, ny) in neighbours(x, y, width, height) {
+            let n = index(nx, ny);
+            if !filled[n] && level[n] != 0.0 && (t + 1) as f32 <= level[n] {
+                level[n] = (t + 1) as f32;
+                queue.push(Reverse((t + 1, n)));
+            }
+        }
+    }
+
+    if let InpaintMethod::Diffusion { iterations } = method {
+        let hole: Vec<(u32, u32)> = mask.enumerate_pixels().filter(|(_, _, m)| m[0] != 0).map(|(x, y, _)| (x, y)).collect();
+        for _ in 0..iterations {
+            let previous = out.clone();
+            for &(x, y) in &hole {
+                let mut sum = [0.0f32; 3];
+                let mut count = 0.0;
+                for (nx, ny) in neighbours(x, y, width, height) {
+                    let p = previous.get_pixel(nx, ny);
+                    (0..3).for_each(|c| sum[c] += p[c]);
+                    count += 1.0;
+                }
+                out.put_pixel(x, y, image::Rgb(sum.map(|s| s / count)));
+            }
+        }
+    }
+    out
+}
+
+/// The 4-connected neighbours of `(x, y)` inside the image.
+fn neighbours(x: u32, y: u32, width: u32, height: u32) -> impl Iterator<Item = (u32, u32)> {
+    [(-1, 0), (1, 0), (0, -1), (0, 1)].into_iter().filter_map(move |(dx, dy): (i32, i32)| {
+        let (nx, ny) = (x as i32 + dx, y as i32 + dy);
+        (nx >= 0 && ny >= 0 && nx < width as i32 && ny < height as i32).then_some((nx as u32, ny as u32))
+    })
+}
+
+/// Removes overlays, timestamps or sensor defects by filling masked areas from their surroundings.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct InpaintNode {
+    #[output]
+    pub output: Output<DynamicImage>,
+
+    #[input]
+    pub input: Input<DynamicImage>,
+
+    /// Nonzero pixels are filled; used for all following frames of the same size.
+    #[input]
+    pub mask: Input<GrayImage>,
+
+    pub config: InpaintNodeConfig,
+
+    #[serde(skip)]
+    current_mask: Option<GrayImage>,
+}
+
+impl InpaintNode {
+    pub fn new(config: InpaintNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            input: Input::new(),
+            mask: Input::new(),
+            config,
+            current_mask: None,
+        }
+    }
+}
+
+impl Node for InpaintNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        while let Ok(mask) = self.mask.next() {
+            self.current_mask = Some(mask);
+        }
+
+        if let Ok(img) = self.input.next() {
+            frame_span!("inpaint", width = img.width(), height = img.height());
+            let (width, height) = (img.width(), img.height());
+            let mut mask = match &self.current_mask {
+                Some(mask) if mask.dimensions() == (width, height) => mask.clone(),
+                _ => GrayImage::new(width, height),
+            };
+            for r in self.config.regions.iter().filter_map(|r| r.clamp_to(width, height)) {
+                for y in r.y..r.y + r.height {
+                    for x in r.x..r.x + r.width {
+                        mask.put_pixel(x, y, image::Luma([255]));
+                    }
+                }
+            }
+            let filled = inpaint(&img.to_rgb32f(), &mask, self.config.method);
+            self.output.send(match_format(filled, &img)).map_err(|e| UpdateError::Other(e.into()))?;
+        }
+        Ok(())
+    }
+}
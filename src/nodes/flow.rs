@@ -0,0 +1,844 @@
+use flowrs::{node::{Node, ShutdownError, UpdateError, ChangeObserver}, connection::{connect, Input, Output}};
+use flowrs::RuntimeConnectable;
+
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant, SystemTime};
+
+use image::DynamicImage;
+use anyhow::anyhow;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::config::{ensure, ConfigError, Validate};
+use crate::debug::emit;
+use crate::types::{ImagePacket, NodeState, NodeStatus, SourceControl, TimedImage};
+
+/// Forwards only the most recent queued frame and discards the rest.
+///
+/// Placing this in front of a slow consumer bounds its latency to a single
+/// frame instead of letting the backlog grow without limit.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct DropOldFramesNode {
+    #[output]
+    pub output: Output<DynamicImage>,
+
+    /// Total number of frames discarded so far, sent whenever it changes.
+    #[output]
+    pub dropped: Output<u64>,
+
+    #[input]
+    pub input: Input<DynamicImage>,
+
+    #[serde(skip)]
+    dropped_total: u64,
+}
+
+impl DropOldFramesNode {
+    pub fn new(change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            dropped: Output::new(change_observer),
+            input: Input::new(),
+            dropped_total: 0,
+        }
+    }
+}
+
+impl Node for DropOldFramesNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        let mut latest = None;
+        let mut skipped = 0;
+        while let Ok(img) = self.input.next() {
+            if latest.replace(img).is_some() {
+                skipped += 1;
+            }
+        }
+
+        if let Some(img) = latest {
+            frame_span!("drop_old_frames", skipped = skipped);
+            self.output.send(img).map_err(|e| UpdateError::Other(e.into()))?;
+        }
+
+        if skipped > 0 {
+            self.dropped_total += skipped;
+            self.dropped.send(self.dropped_total).map_err(|e| UpdateError::Other(e.into()))?;
+        }
+        Ok(())
+    }
+}
+
+/// What a full queue does with the next item.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum OverflowPolicy {
+    /// Leave further items waiting upstream until there is room again; nothing is
+    /// lost, but they pile up unbounded in the input channel instead.
+    Block,
+    #[default]
+    DropOldest,
+    DropNewest,
+    /// Keep only the newest item, whatever the capacity.
+    CoalesceToLatest,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct BoundedQueueNodeConfig {
+    pub capacity: usize,
+    pub policy: OverflowPolicy,
+    /// Items the consumer may receive before it has to grant more on `ready`.
+    /// Without credits one item is forwarded per update.
+    pub credits: Option<u32>,
+}
+
+impl Default for BoundedQueueNodeConfig {
+    fn default() -> Self {
+        Self { capacity: 8, policy: OverflowPolicy::DropOldest, credits: None }
+    }
+}
+
+impl Validate for BoundedQueueNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        ensure(self.capacity > 0, "capacity", "must be positive")
+    }
+}
+
+config_builder!(BoundedQueueNodeConfig { capacity: usize, policy: OverflowPolicy, credits: Option<u32> });
+
+/// Adds an item to a bounded queue and returns how many items were dropped.
+///
+/// `Block` never drops; callers stop pushing once the queue is full.
+pub fn push_bounded<T>(queue: &mut VecDeque<T>, item: T, capacity: usize, policy: OverflowPolicy) -> u64 {
+    let capacity = capacity.max(1);
+    match policy {
+        OverflowPolicy::Block => {
+            queue.push_back(item);
+            0
+        }
+        OverflowPolicy::DropNewest if queue.len() >= capacity => 1,
+        OverflowPolicy::CoalesceToLatest => {
+            let dropped = queue.len() as u64;
+            queue.clear();
+            queue.push_back(item);
+            dropped
+        }
+        _ => {
+            queue.push_back(item);
+            let excess = queue.len().saturating_sub(capacity);
+            queue.drain(..excess);
+            excess as u64
+        }
+    }
+}
+
+/// Bounded buffer between a fast producer and a slow consumer.
+///
+/// The dropping policies bound the items held to `capacity` and decide which
+/// ones to give up; [`OverflowPolicy::Block`] loses nothing but leaves the
+/// backlog in the unbounded input channel, so memory is only bounded if the
+/// producer slows down by itself. When the consumer reports how many items it is
+/// ready for on `ready`, delivery follows its pace instead of the producer's.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct BoundedQueueNode<T> {
+    #[output]
+    pub output: Output<T>,
+
+    /// Total number of items dropped so far, sent whenever it changes.
+    #[output]
+    pub dropped: Output<u64>,
+
+    #[input]
+    pub input: Input<T>,
+
+    #[input]
+    pub ready: Input<u32>,
+
+    pub config: BoundedQueueNodeConfig,
+
+    #[serde(skip)]
+    queue: VecDeque<T>,
+    #[serde(skip)]
+    credits: u32,
+    #[serde(skip)]
+    dropped_total: u64,
+}
+
+impl<T> BoundedQueueNode<T>
+where T: Send {
+    pub fn new(config: BoundedQueueNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            dropped: Output::new(change_observer),
+            input: Input::new(),
+            ready: Input::new(),
+            credits: config.credits.unwrap_or(0),
+            config,
+            queue: VecDeque::new(),
+            dropped_total: 0,
+        }
+    }
+}
+
+impl<T> Node for BoundedQueueNode<T>
+where T: Send {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        while let Ok(n) = self.ready.next() {
+            self.credits = self.credits.saturating_add(n);
+        }
+
+        let mut dropped = 0;
+        while self.config.policy != OverflowPolicy::Block || self.queue.len() < self.config.capacity.max(1) {
+            let Ok(item) = self.input.next() else { break };
+            dropped += push_bounded(&mut self.queue, item, self.config.capacity, self.config.policy);
+        }
+
+        let mut budget = if self.config.credits.is_some() { self.credits } else { 1 };
+        while budget > 0 {
+            let Some(item) = self.queue.pop_front() else { break };
+            frame_span!("bounded_queue", queued = self.queue.len());
+            budget -= 1;
+            if self.config.credits.is_some() {
+                self.credits -= 1;
+            }
+            self.output.send(item).map_err(|e| UpdateError::Other(e.into()))?;
+        }
+
+        if dropped > 0 {
+            self.dropped_total += dropped;
+            self.dropped.send(self.dropped_total).map_err(|e| UpdateError::Other(e.into()))?;
+        }
+        Ok(())
+    }
+}
+
+type WorkerResult<O> = (u64, Result<Vec<O>, anyhow::Error>);
+
+/// How long [`ParallelizeNode`] waits for items in flight at shutdown unless set otherwise.
+pub const DEFAULT_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Runs several instances of a node on their own threads and restores input order on output.
+///
+/// Items are handed to the workers round-robin. Everything a worker emits in
+/// response to one item is forwarded as soon as all earlier items are done, so
+/// downstream nodes see the same sequence as with a single instance. Updates
+/// never wait for the workers; they forward what is ready and keep the rest in
+/// order, and shutdown waits for the items still in flight, failing if they are
+/// not done within the shutdown timeout. A worker that died makes every later
+/// update fail. Only nodes that keep no state between
+/// items give identical results, e.g. OCR, inference or encoding.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct ParallelizeNode<I, O> {
+    #[output]
+    pub output: Output<O>,
+
+    #[input]
+    pub input: Input<I>,
+
+    #[serde(skip)]
+    workers: Vec<Sender<(u64, I)>>,
+    #[serde(skip)]
+    threads: Vec<JoinHandle<()>>,
+    #[serde(skip)]
+    results: Option<Receiver<WorkerResult<O>>>,
+    #[serde(skip)]
+    finished: BTreeMap<u64, Vec<O>>,
+    #[serde(skip)]
+    next_in: u64,
+    #[serde(skip)]
+    next_out: u64,
+    #[serde(skip)]
+    received: u64,
+    #[serde(skip)]
+    shutdown_timeout: Duration,
+}
+
+impl<I, O> ParallelizeNode<I, O>
+where I: Send + 'static, O: Send + 'static {
+    /// Spawns `workers` instances built by `factory`; `input` and `output` select the wrapped node's ports.
+    pub fn new<N, F>(
+        workers: usize,
+        factory: F,
+        input: fn(&N) -> &Input<I>,
+        output: fn(&N) -> &Output<O>,
+        change_observer: Option<&ChangeObserver>,
+    ) -> Self
+    where N: Node + Send + 'static, F: Fn() -> N {
+        let (result_tx, result_rx) = mpsc::channel();
+        let (workers, threads) = (0..workers.max(1))
+            .map(|_| {
+                let mut node = factory();
+                let mut collected = Input::new();
+                connect(output(&node).clone(), collected.clone());
+                let (tx, rx) = mpsc::channel::<(u64, I)>();
+                let results = result_tx.clone();
+                let thread = std::thread::spawn(move || {
+                    while let Ok((seq, item)) = rx.recv() {
+                        let result = input(&node)
+                            .send(item)
+                            .map_err(|e| anyhow!("Could not pass item to worker: {:?}", e))
+                            .and_then(|_| node.on_update().map_err(|e| anyhow!("Worker failed: {:?}", e)))
+                            .map(|_| std::iter::from_fn(|| collected.next().ok()).collect());
+                        if results.send((seq, result)).is_err() {
+                            return;
+                        }
+                    }
+                });
+                (tx, thread)
+            })
+            .unzip();
+
+        Self {
+            output: Output::new(change_observer),
+            input: Input::new(),
+            workers,
+            threads,
+            results: Some(result_rx),
+            finished: BTreeMap::new(),
+            next_in: 0,
+            next_out: 0,
+            received: 0,
+            shutdown_timeout: DEFAULT_SHUTDOWN_TIMEOUT,
+        }
+    }
+
+    /// Sets how long shutdown waits for items in flight, e.g. for slow inference workers.
+    pub fn with_shutdown_timeout(mut self, timeout: Duration) -> Self {
+        self.shutdown_timeout = timeout;
+        self
+    }
+}
+
+impl<I, O> Node for ParallelizeNode<I, O>
+where I: Send + 'static, O: Send + 'static {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        if self.workers.is_empty() {
+            return Err(UpdateError::Other(anyhow!("ParallelizeNode has no workers; it must be built with new.")));
+        }
+        while let Ok(item) = self.input.next() {
+            frame_span!("parallelize", frame = self.next_in);
+            let worker = &self.workers[(self.next_in % self.workers.len() as u64) as usize];
+            worker.send((self.next_in, item)).map_err(|_| UpdateError::Other(anyhow!("Worker thread exited.")))?;
+            self.next_in += 1;
+        }
+        let collected = self.collect(false);
+        self.forward().map_err(UpdateError::Other)?;
+        collected.map_err(UpdateError::Other)
+    }
+
+    fn on_shutdown(&mut self) -> Result<(), ShutdownError> {
+        let collected = self.collect(true);
+        self.forward().map_err(ShutdownError::Other)?;
+        collected.map_err(ShutdownError::Other)
+    }
+}
+
+impl<I, O> ParallelizeNode<I, O>
+where I: Send + 'static, O: Send + 'static {
+    /// Moves results into the reorder buffer, waiting up to the shutdown timeout for
+    /// all outstanding items if `wait` is set.
+    fn collect(&mut self, wait: bool) -> Result<(), anyhow::Error> {
+        let Some(results) = &self.results else { return Ok(()) };
+        let deadline = Instant::now() + self.shutdown_timeout;
+        let mut error = None;
+        while self.received < self.next_in {
+            let next = if wait {
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                if remaining.is_zero() {
+                    let outstanding = self.next_in - self.received;
+                    return Err(error.unwrap_or_else(|| {
+                        anyhow!("{} items still outstanding after {:?}; a worker may be stuck.", outstanding, self.shutdown_timeout)
+                    }));
+                }
+                results.recv_timeout(remaining.min(Duration::from_millis(50))).map_err(|_| ())
+            } else {
+                results.try_recv().map_err(|_| ())
+            };
+            let Ok((seq, result)) = next else {
+                // Workers only exit early when the wrapped node panicked, taking its items with it.
+                if self.threads.iter().any(|t| t.is_finished()) {
+                    return Err(error.unwrap_or_else(|| anyhow!("Worker thread exited with items outstanding.")));
+                }
+                if wait { continue } else { break }
+            };
+            self.received += 1;
+            match result {
+                Ok(items) => { self.finished.insert(seq, items); }
+                // A failed item must not stall the ones behind it.
+                Err(e) => {
+                    self.finished.insert(seq, Vec::new());
+                    error.get_or_insert(e);
+                }
+            }
+        }
+        error.map_or(Ok(()), Err)
+    }
+
+    fn forward(&mut self) -> Result<(), anyhow::Error> {
+        while let Some(items) = self.finished.remove(&self.next_out) {
+            self.next_out += 1;
+            for item in items {
+                self.output.send(item)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Stamps frames with a sequence number and the current time.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct TimestampNode {
+    #[output]
+    pub output: Output<TimedImage>,
+
+    #[input]
+    pub input: Input<DynamicImage>,
+
+    #[serde(skip)]
+    seq: u64,
+}
+
+impl TimestampNode {
+    pub fn new(change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            input: Input::new(),
+            seq: 0,
+        }
+    }
+}
+
+impl Node for TimestampNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        if let Ok(image) = self.input.next() {
+            frame_span!("timestamp", frame = self.seq);
+            let frame = TimedImage { seq: self.seq, captured_at: SystemTime::now(), image };
+            self.seq += 1;
+            self.output.send(frame).map_err(|e| UpdateError::Other(e.into()))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct PacketizeNodeConfig {
+    /// Added to every packet, e.g. `{"camera": "left"}`.
+    pub metadata: HashMap<String, Value>,
+}
+
+impl Validate for PacketizeNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        Ok(())
+    }
+}
+
+config_builder!(PacketizeNodeConfig for PacketizeNode { metadata: HashMap<String, Value> });
+
+/// Wraps frames into [`ImagePacket`]s with a sequence number, the current time and fixed metadata.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct PacketizeNode {
+    #[output]
+    pub output: Output<ImagePacket>,
+
+    #[input]
+    pub input: Input<DynamicImage>,
+
+    pub config: PacketizeNodeConfig,
+
+    #[serde(skip)]
+    seq: u64,
+}
+
+impl PacketizeNode {
+    pub fn new(config: PacketizeNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            input: Input::new(),
+            config,
+            seq: 0,
+        }
+    }
+}
+
+impl Node for PacketizeNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        while let Ok(image) = self.input.next() {
+            frame_span!("packetize", frame = self.seq);
+            let packet = ImagePacket { metadata: self.config.metadata.clone(), ..ImagePacket::new(image, self.seq) };
+            self.seq += 1;
+            self.output.send(packet).map_err(|e| UpdateError::Other(e.into()))?;
+        }
+        Ok(())
+    }
+}
+
+/// Runs an image node on the images of [`ImagePacket`]s, so their metadata survives it.
+///
+/// Every image the wrapped node emits for a packet is sent on with that packet's
+/// metadata, timestamp and sequence number. This suits nodes that respond to
+/// each image right away, such as resizing or color conversion.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct PacketAdapterNode {
+    #[output]
+    pub output: Output<ImagePacket>,
+
+    #[input]
+    pub input: Input<ImagePacket>,
+
+    #[serde(skip)]
+    node: Option<Box<dyn Node>>,
+    #[serde(skip)]
+    feed: Option<Input<DynamicImage>>,
+    #[serde(skip)]
+    collected: Option<Input<DynamicImage>>,
+}
+
+impl PacketAdapterNode {
+    /// Wraps `node`; `input` and `output` select its image ports.
+    pub fn new<N>(
+        node: N,
+        input: fn(&N) -> &Input<DynamicImage>,
+        output: fn(&N) -> &Output<DynamicImage>,
+        change_observer: Option<&ChangeObserver>,
+    ) -> Self
+    where N: Node + 'static {
+        let collected = Input::new();
+        connect(output(&node).clone(), collected.clone());
+        let feed = input(&node).clone();
+        Self {
+            output: Output::new(change_observer),
+            input: Input::new(),
+            node: Some(Box::new(node)),
+            feed: Some(feed),
+            collected: Some(collected),
+        }
+    }
+}
+
+impl Node for PacketAdapterNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        let (Some(node), Some(feed), Some(collected)) = (&mut self.node, &self.feed, &mut self.collected) else {
+            return Err(UpdateError::Other(anyhow!("PacketAdapterNode wraps no node; it must be built with new.")));
+        };
+        while let Ok(mut packet) = self.input.next() {
+            frame_span!("packet_adapter", frame = packet.seq);
+            // Hands the image over without copying it; results get the rest of the packet.
+            let image = std::mem::replace(&mut packet.image, DynamicImage::new_luma8(0, 0));
+            feed.send(image).map_err(|e| UpdateError::Other(anyhow!("Could not pass image to wrapped node: {:?}", e)))?;
+            node.on_update()?;
+            while let Ok(image) = collected.next() {
+                self.output.send(packet.with_image(image)).map_err(|e| UpdateError::Other(e.into()))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Splits [`ImagePacket`]s into their image and metadata, e.g. in front of a sink.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct UnpackNode {
+    #[output]
+    pub image: Output<DynamicImage>,
+
+    #[output]
+    pub metadata: Output<HashMap<String, Value>>,
+
+    #[input]
+    pub input: Input<ImagePacket>,
+}
+
+impl UnpackNode {
+    pub fn new(change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            image: Output::new(change_observer),
+            metadata: Output::new(change_observer),
+            input: Input::new(),
+        }
+    }
+}
+
+impl Node for UnpackNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        while let Ok(packet) = self.input.next() {
+            frame_span!("unpack", frame = packet.seq);
+            self.metadata.send(packet.metadata).map_err(|e| UpdateError::Other(e.into()))?;
+            self.image.send(packet.image).map_err(|e| UpdateError::Other(e.into()))?;
+        }
+        Ok(())
+    }
+}
+
+/// Items that may carry the time they were captured at.
+pub trait CaptureTime {
+    fn captured_at(&self) -> Option<SystemTime>;
+}
+
+impl CaptureTime for DynamicImage {
+    fn captured_at(&self) -> Option<SystemTime> {
+        None
+    }
+}
+
+impl CaptureTime for TimedImage {
+    fn captured_at(&self) -> Option<SystemTime> {
+        Some(self.captured_at)
+    }
+}
+
+impl CaptureTime for ImagePacket {
+    fn captured_at(&self) -> Option<SystemTime> {
+        Some(self.timestamp)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub struct ThroughputStats {
+    pub frames: u64,
+    pub fps: f64,
+    /// Time from capture to this probe; only available for [`TimedImage`]s.
+    pub mean_latency_ms: Option<f64>,
+    pub max_latency_ms: Option<f64>,
+    /// Standard deviation of the time between frames.
+    pub jitter_ms: f64,
+}
+
+/// Arrivals and latencies of one reporting interval.
+#[derive(Clone, Debug, Default)]
+pub struct ThroughputWindow {
+    arrivals: Vec<Instant>,
+    latencies: Vec<Duration>,
+}
+
+impl ThroughputWindow {
+    pub fn record(&mut self, arrival: Instant, latency: Option<Duration>) {
+        self.arrivals.push(arrival);
+        self.latencies.extend(latency);
+    }
+
+    /// Statistics over the recorded frames, relative to an interval of length `elapsed`.
+    pub fn stats(&self, elapsed: Duration) -> ThroughputStats {
+        let ms = |d: Duration| d.as_secs_f64() * 1000.0;
+        let gaps: Vec<f64> = self.arrivals.windows(2).map(|w| ms(w[1] - w[0])).collect();
+        let mean_gap = gaps.iter().sum::<f64>() / gaps.len().max(1) as f64;
+        let variance = gaps.iter().map(|g| (g - mean_gap).powi(2)).sum::<f64>() / gaps.len().max(1) as f64;
+        let latencies = (!self.latencies.is_empty()).then_some(&self.latencies);
+        ThroughputStats {
+            frames: self.arrivals.len() as u64,
+            fps: self.arrivals.len() as f64 / elapsed.as_secs_f64().max(1e-9),
+            mean_latency_ms: latencies.map(|l| l.iter().map(|d| ms(*d)).sum::<f64>() / l.len() as f64),
+            max_latency_ms: latencies.and_then(|l| l.iter().max()).map(|d| ms(*d)),
+            jitter_ms: variance.sqrt(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ThroughputProbeNodeConfig {
+    pub report_interval_secs: f32,
+    /// Also log every report, to `tracing` if enabled and to stderr or the browser console otherwise.
+    pub log: bool,
+    /// Name used in log lines.
+    pub name: String,
+}
+
+impl Default for ThroughputProbeNodeConfig {
+    fn default() -> Self {
+        Self { report_interval_secs: 1.0, log: false, name: "throughput".into() }
+    }
+}
+
+impl Validate for ThroughputProbeNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        ensure(self.report_interval_secs > 0.0, "report_interval_secs", "must be positive")
+    }
+}
+
+config_builder!(ThroughputProbeNodeConfig { report_interval_secs: f32, log: bool, name: String });
+
+/// Passes items through unchanged while measuring frame rate, latency and jitter.
+///
+/// A [`ThroughputStats`] report is sent on `stats` every `report_interval_secs`.
+/// Latency is measured for [`TimedImage`]s, stamped e.g. by a [`TimestampNode`]
+/// at the start of the flow.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct ThroughputProbeNode<T> {
+    #[output]
+    pub output: Output<T>,
+
+    #[output]
+    pub stats: Output<ThroughputStats>,
+
+    #[input]
+    pub input: Input<T>,
+
+    pub config: ThroughputProbeNodeConfig,
+
+    #[serde(skip)]
+    window: ThroughputWindow,
+    #[serde(skip)]
+    window_start: Option<Instant>,
+}
+
+impl<T> ThroughputProbeNode<T>
+where T: CaptureTime + Send {
+    pub fn new(config: ThroughputProbeNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            stats: Output::new(change_observer),
+            input: Input::new(),
+            config,
+            window: ThroughputWindow::default(),
+            window_start: None,
+        }
+    }
+}
+
+impl<T> Node for ThroughputProbeNode<T>
+where T: CaptureTime + Send {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        let now = Instant::now();
+        let start = *self.window_start.get_or_insert(now);
+        while let Ok(item) = self.input.next() {
+            frame_span!("throughput_probe");
+            let latency = item.captured_at().and_then(|t| t.elapsed().ok());
+            self.window.record(Instant::now(), latency);
+            self.output.send(item).map_err(|e| UpdateError::Other(e.into()))?;
+        }
+
+        let elapsed = now - start;
+        if elapsed.as_secs_f32() >= self.config.report_interval_secs {
+            let stats = std::mem::take(&mut self.window).stats(elapsed);
+            self.window_start = Some(now);
+            if self.config.log {
+                emit(&format!(
+                    "[{}] {:.1} fps, latency {} ms (max {} ms), jitter {:.1} ms",
+                    self.config.name,
+                    stats.fps,
+                    stats.mean_latency_ms.map_or("-".to_string(), |l| format!("{:.1}", l)),
+                    stats.max_latency_ms.map_or("-".to_string(), |l| format!("{:.1}", l)),
+                    stats.jitter_ms,
+                ));
+            }
+            self.stats.send(stats).map_err(|e| UpdateError::Other(e.into()))?;
+        }
+        Ok(())
+    }
+}
+
+/// Minimum time between two [`NodeStatus`] reports that do not change the state.
+pub const STATUS_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Keeps the [`NodeStatus`] of a source or sink node.
+///
+/// Nodes count their frames and handled errors, and pass the result of each
+/// update to [`StatusReporter::track`], which sends the status on every state
+/// change and otherwise at most once per [`STATUS_INTERVAL`].
+#[derive(Debug, Default)]
+pub struct StatusReporter {
+    status: NodeStatus,
+    changed: bool,
+    window_frames: u64,
+    window_start: Option<Instant>,
+    last_sent: Option<Instant>,
+}
+
+impl StatusReporter {
+    pub fn status(&self) -> &NodeStatus {
+        &self.status
+    }
+
+    /// Counts a frame read from or written to the outside world.
+    pub fn frame(&mut self) {
+        self.status.frames += 1;
+        self.window_frames += 1;
+        self.set_state(NodeState::Running);
+    }
+
+    /// Counts a failure; a [`NodeState::Failed`] node stays failed until its next frame.
+    pub fn error(&mut self, error: impl std::fmt::Display) {
+        self.status.errors += 1;
+        self.status.last_error = Some(error.to_string());
+        if self.status.state != NodeState::Failed {
+            self.set_state(NodeState::Degraded);
+        }
+    }
+
+    pub fn set_state(&mut self, state: NodeState) {
+        if self.status.state != state {
+            self.status.state = state;
+            self.changed = true;
+        }
+    }
+
+    /// Counts a failed update, sends the status if it is due and hands `result` back.
+    pub fn track(&mut self, result: Result<(), UpdateError>, health: &mut Output<NodeStatus>) -> Result<(), UpdateError> {
+        if let Err(UpdateError::Other(e)) = &result {
+            self.error(e);
+        }
+
+        let now = Instant::now();
+        let start = *self.window_start.get_or_insert(now);
+        let elapsed = now - start;
+        if elapsed >= STATUS_INTERVAL {
+            self.status.fps = self.window_frames as f64 / elapsed.as_secs_f64();
+            self.window_frames = 0;
+            self.window_start = Some(now);
+        }
+        if self.changed || self.last_sent.is_none_or(|t| now - t >= STATUS_INTERVAL) {
+            self.changed = false;
+            self.last_sent = Some(now);
+            health.send(self.status.clone()).map_err(|e| UpdateError::Other(e.into()))?;
+        }
+        result
+    }
+}
+
+/// The [`SourceControl`] mode of a source node.
+///
+/// Sources start running; commands only take effect between frames.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SourceGate {
+    mode: SourceControl,
+}
+
+impl SourceGate {
+    pub fn mode(&self) -> SourceControl {
+        self.mode
+    }
+
+    pub fn apply(&mut self, command: SourceControl) {
+        self.mode = command;
+    }
+
+    /// Applies the commands queued on `control`; returns `true` if they stopped a source that was not stopped before.
+    pub fn poll(&mut self, control: &mut Input<SourceControl>) -> bool {
+        let was_stopped = self.mode == SourceControl::Stop;
+        while let Ok(command) = control.next() {
+            self.apply(command);
+        }
+        !was_stopped && self.mode == SourceControl::Stop
+    }
+
+    /// Whether the source may produce a frame now.
+    pub fn running(&self) -> bool {
+        matches!(self.mode, SourceControl::Start | SourceControl::SingleShot)
+    }
+
+    /// Records a produced frame, which ends a single shot.
+    pub fn produced(&mut self) {
+        if self.mode == SourceControl::SingleShot {
+            self.mode = SourceControl::Pause;
+        }
+    }
+}
@@ -0,0 +1,737 @@
+use flowrs::{node::{Node, UpdateError, ChangeObserver}, connection::{Input, Output}};
+use flowrs::RuntimeConnectable;
+
+use std::collections::VecDeque;
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use image::{DynamicImage, GrayImage, ImageFormat, Luma, Rgb, RgbImage};
+use sha2::{Digest, Sha256};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::{ensure, ConfigError, Validate};
+use crate::filter::match_format;
+use crate::transform::{encode_image, EncodeFormat};
+use crate::tracking::point_in_polygon;
+use crate::types::{Detection, Rect};
+
+/// Marker preceding an LSB watermark: magic, little-endian payload length, payload, FNV-1a checksum.
+const WATERMARK_MAGIC: &[u8; 4] = b"FLWM";
+const WATERMARK_HEADER_LEN: usize = 8;
+const WATERMARK_CHECKSUM_LEN: usize = 4;
+
+fn fnv1a(data: &[u8]) -> u32 {
+    data.iter().fold(0x811c9dc5u32, |hash, b| (hash ^ *b as u32).wrapping_mul(0x01000193))
+}
+
+/// Embeds `payload` into the least significant bits of the RGB channels.
+///
+/// Returns `None` if the image is too small to carry the payload.
+pub fn embed_lsb_watermark(img: &DynamicImage, payload: &[u8]) -> Option<RgbImage> {
+    let mut message = Vec::with_capacity(WATERMARK_HEADER_LEN + payload.len() + WATERMARK_CHECKSUM_LEN);
+    message.extend_from_slice(WATERMARK_MAGIC);
+    message.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    message.extend_from_slice(payload);
+    message.extend_from_slice(&fnv1a(payload).to_le_bytes());
+
+    let mut rgb = img.to_rgb8();
+    let channels: &mut [u8] = &mut rgb;
+    if channels.len() < message.len() * 8 {
+        return None;
+    }
+    for (i, byte) in message.iter().enumerate() {
+        for bit in 0..8 {
+            let c = &mut channels[i * 8 + bit];
+            *c = (*c & !1) | ((byte >> (7 - bit)) & 1);
+        }
+    }
+    Some(rgb)
+}
+
+fn read_lsb_bytes(channels: &[u8], offset: usize, len: usize) -> Option<Vec<u8>> {
+    let bits = channels.get(offset * 8..(offset + len) * 8)?;
+    Some(bits.chunks_exact(8).map(|b| b.iter().fold(0u8, |acc, c| (acc << 1) | (c & 1))).collect())
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct WatermarkReport {
+    /// A watermark header was found.
+    pub present: bool,
+    /// The payload checksum matched (and the expected payload, if configured).
+    pub passed: bool,
+    pub payload: Option<Vec<u8>>,
+}
+
+/// Extracts and validates an LSB watermark written by [`embed_lsb_watermark`].
+pub fn extract_lsb_watermark(img: &DynamicImage, expected: Option<&[u8]>) -> WatermarkReport {
+    let rgb = img.to_rgb8();
+    let channels = rgb.as_raw();
+
+    let header = match read_lsb_bytes(channels, 0, WATERMARK_HEADER_LEN) {
+        Some(header) if &header[..4] == WATERMARK_MAGIC => header,
+        _ => return WatermarkReport::default(),
+    };
+    let len = u32::from_le_bytes([header[4], header[5], header[6], header[7]]) as usize;
+    let payload = read_lsb_bytes(channels, WATERMARK_HEADER_LEN, len);
+    let checksum = read_lsb_bytes(channels, WATERMARK_HEADER_LEN + len, WATERMARK_CHECKSUM_LEN);
+
+    match (payload, checksum) {
+        (Some(payload), Some(checksum)) => {
+            let checksum_ok = fnv1a(&payload).to_le_bytes()[..] == checksum[..];
+            let expected_ok = expected.is_none_or(|e| e == payload.as_slice());
+            WatermarkReport { present: true, passed: checksum_ok && expected_ok, payload: Some(payload) }
+        }
+        _ => WatermarkReport { present: true, passed: false, payload: None },
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct WatermarkVerifyNodeConfig {
+    /// If set, verification also requires the extracted payload to equal this value.
+    pub expected_payload: Option<Vec<u8>>,
+}
+
+impl Validate for WatermarkVerifyNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        ensure(self.expected_payload.as_ref().is_none_or(|p| !p.is_empty()), "expected_payload", "must not be empty")
+    }
+}
+
+config_builder!(WatermarkVerifyNodeConfig for WatermarkVerifyNode { expected_payload: Option<Vec<u8>> });
+
+/// Detects and verifies LSB watermarks in incoming images.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct WatermarkVerifyNode {
+    #[output]
+    pub output: Output<WatermarkReport>,
+
+    #[input]
+    pub input: Input<DynamicImage>,
+
+    pub config: WatermarkVerifyNodeConfig,
+}
+
+impl WatermarkVerifyNode {
+    pub fn new(config: WatermarkVerifyNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            input: Input::new(),
+            config,
+        }
+    }
+}
+
+impl Node for WatermarkVerifyNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        if let Ok(img) = self.input.next() {
+            let report = extract_lsb_watermark(&img, self.config.expected_payload.as_deref());
+            self.output.send(report).map_err(|e| UpdateError::Other(e.into()))?;
+        }
+        Ok(())
+    }
+}
+
+/// Qualities probed when searching for JPEG ghosts.
+const GHOST_QUALITIES: [u8; 10] = [50, 55, 60, 65, 70, 75, 80, 85, 90, 95];
+/// A quality counts as a ghost if its error is below this fraction of its neighbours' mean.
+const GHOST_DIP_RATIO: f32 = 0.85;
+
+fn recompress(rgb: &RgbImage, quality: u8) -> Result<RgbImage, anyhow::Error> {
+    let data = encode_image(&DynamicImage::ImageRgb8(rgb.clone()), EncodeFormat::Jpeg { quality })?;
+    Ok(image::load_from_memory_with_format(&data, ImageFormat::Jpeg)?.into_rgb8())
+}
+
+fn mean_abs_error(a: &RgbImage, b: &RgbImage) -> f32 {
+    let sum: u64 = a.as_raw().iter().zip(b.as_raw()).map(|(x, y)| x.abs_diff(*y) as u64).sum();
+    sum as f32 / a.as_raw().len().max(1) as f32
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub struct ElaReport {
+    pub mean_error: f32,
+    pub max_error: u8,
+    /// Qualities at which re-compression error dips, hinting at earlier JPEG compression.
+    pub ghost_qualities: Vec<u8>,
+    /// More than one ghost was found, suggesting the image was JPEG-compressed twice.
+    pub double_compression_suspected: bool,
+}
+
+/// Error-level analysis: per-pixel residual after re-compression, amplified for display.
+pub fn error_level_analysis(img: &DynamicImage, quality: u8, amplification: f32) -> Result<(RgbImage, ElaReport), anyhow::Error> {
+    let rgb = img.to_rgb8();
+    let recompressed = recompress(&rgb, quality)?;
+
+    let mut max_error = 0u8;
+    let residual = RgbImage::from_fn(rgb.width(), rgb.height(), |x, y| {
+        let (a, b) = (rgb.get_pixel(x, y), recompressed.get_pixel(x, y));
+        let mut px = [0u8; 3];
+        for c in 0..3 {
+            let diff = a[c].abs_diff(b[c]);
+            max_error = max_error.max(diff);
+            px[c] = (diff as f32 * amplification).min(255.0) as u8;
+        }
+        Rgb(px)
+    });
+
+    let errors = GHOST_QUALITIES
+        .iter()
+        .map(|q| recompress(&rgb, *q).map(|r| mean_abs_error(&rgb, &r)))
+        .collect::<Result<Vec<f32>, _>>()?;
+    let ghost_qualities: Vec<u8> = (1..errors.len() - 1)
+        .filter(|i| errors[*i] < GHOST_DIP_RATIO * (errors[i - 1] + errors[i + 1]) / 2.0)
+        .map(|i| GHOST_QUALITIES[i])
+        .collect();
+
+    let report = ElaReport {
+        mean_error: mean_abs_error(&rgb, &recompressed),
+        max_error,
+        double_compression_suspected: ghost_qualities.len() > 1,
+        ghost_qualities,
+    };
+    Ok((residual, report))
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ElaNodeConfig {
+    /// JPEG quality used for the re-compression (typically 90-95).
+    pub quality: u8,
+    /// Factor applied to residuals so small differences become visible.
+    pub amplification: f32,
+}
+
+impl Default for ElaNodeConfig {
+    fn default() -> Self {
+        Self { quality: 90, amplification: 20.0 }
+    }
+}
+
+impl Validate for ElaNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        ensure((1..=100).contains(&self.quality), "quality", "must be between 1 and 100")
+    }
+}
+
+config_builder!(ElaNodeConfig for ElaNode { quality: u8, amplification: f32 });
+
+/// Error-level analysis and basic double-JPEG detection for media forensics.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct ElaNode {
+    #[output]
+    pub output: Output<DynamicImage>,
+
+    #[output]
+    pub report: Output<ElaReport>,
+
+    #[input]
+    pub input: Input<DynamicImage>,
+
+    pub config: ElaNodeConfig,
+}
+
+impl ElaNode {
+    pub fn new(config: ElaNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            report: Output::new(change_observer),
+            input: Input::new(),
+            config,
+        }
+    }
+}
+
+impl Node for ElaNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        if let Ok(img) = self.input.next() {
+            let (residual, report) = error_level_analysis(&img, self.config.quality, self.config.amplification)
+                .map_err(UpdateError::Other)?;
+            self.output.send(DynamicImage::ImageRgb8(residual)).map_err(|e| UpdateError::Other(e.into()))?;
+            self.report.send(report).map_err(|e| UpdateError::Other(e.into()))?;
+        }
+        Ok(())
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// SHA-256 of the frame size and raw 8-bit RGBA pixels.
+pub fn frame_hash(img: &DynamicImage) -> String {
+    let rgba = img.to_rgba8();
+    let mut hasher = Sha256::new();
+    hasher.update(rgba.width().to_le_bytes());
+    hasher.update(rgba.height().to_le_bytes());
+    hasher.update(rgba.as_raw());
+    hex(&hasher.finalize())
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct RedactedRegion {
+    pub rect: Rect,
+    pub reason: String,
+    /// Hash of the region as it appears in the redacted frame.
+    pub hash: String,
+}
+
+/// One link of the redaction audit chain.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct AuditEntry {
+    pub seq: u64,
+    pub timestamp_ms: u64,
+    pub frame_hash: String,
+    pub regions: Vec<RedactedRegion>,
+    /// `entry_hash` of the preceding entry, or all zeros for the first one.
+    pub previous_hash: String,
+    /// Hash over all other fields, chaining every entry to its predecessors.
+    pub entry_hash: String,
+}
+
+impl AuditEntry {
+    fn digest(&self) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(self.previous_hash.as_bytes());
+        hasher.update(self.seq.to_le_bytes());
+        hasher.update(self.timestamp_ms.to_le_bytes());
+        hasher.update(self.frame_hash.as_bytes());
+        for r in &self.regions {
+            for v in [r.rect.x, r.rect.y, r.rect.width, r.rect.height] {
+                hasher.update(v.to_le_bytes());
+            }
+            hasher.update((r.reason.len() as u32).to_le_bytes());
+            hasher.update(r.reason.as_bytes());
+            hasher.update(r.hash.as_bytes());
+        }
+        hex(&hasher.finalize())
+    }
+}
+
+const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// Index of the first entry that was altered, reordered or removed, if any.
+pub fn verify_audit_chain(entries: &[AuditEntry]) -> Result<(), usize> {
+    let mut previous = GENESIS_HASH.to_string();
+    for (i, e) in entries.iter().enumerate() {
+        if e.previous_hash != previous || e.digest() != e.entry_hash {
+            return Err(i);
+        }
+        previous = e.entry_hash.clone();
+    }
+    Ok(())
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct AnonymizationAuditNodeConfig {
+    /// Append every entry as a JSON line to this file; an existing log is continued.
+    pub log_path: Option<PathBuf>,
+}
+
+impl Validate for AnonymizationAuditNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        ensure(self.log_path.as_ref().is_none_or(|p| !p.as_os_str().is_empty()), "log_path", "must not be empty")
+    }
+}
+
+config_builder!(AnonymizationAuditNodeConfig for AnonymizationAuditNode { log_path: Option<PathBuf> });
+
+/// Frames or region lists waiting for their counterpart; beyond this they are given up as unpaired.
+const MAX_PENDING: usize = 8;
+
+/// Records which regions of which frames were redacted in a hash-chained log.
+///
+/// Frames and region lists are paired in arrival order (detection labels serve
+/// as reasons), and each frame is forwarded unchanged once logged. A frame
+/// waits for its list; when more than `MAX_PENDING` frames wait, the oldest is
+/// logged with no redactions, so the gap remains visible. Lists that arrive
+/// more than `MAX_PENDING` ahead of their frames are dropped. Both count as
+/// unpaired on `unpaired`, as pairing after them is no longer trustworthy.
+/// When `log_path` already holds entries, the chain continues from its last line.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct AnonymizationAuditNode {
+    #[output]
+    pub output: Output<DynamicImage>,
+
+    #[output]
+    pub log: Output<AuditEntry>,
+
+    /// Total number of frames and region lists that could not be paired, sent whenever it changes.
+    #[output]
+    pub unpaired: Output<u64>,
+
+    #[input]
+    pub input: Input<DynamicImage>,
+
+    #[input]
+    pub regions: Input<Vec<Detection>>,
+
+    pub config: AnonymizationAuditNodeConfig,
+
+    #[serde(skip)]
+    frames: VecDeque<DynamicImage>,
+    #[serde(skip)]
+    pending: VecDeque<Vec<Detection>>,
+    #[serde(skip)]
+    unpaired_total: u64,
+    #[serde(skip)]
+    seq: u64,
+    #[serde(skip)]
+    previous_hash: Option<String>,
+    #[serde(skip)]
+    resumed: bool,
+}
+
+impl AnonymizationAuditNode {
+    pub fn new(config: AnonymizationAuditNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            log: Output::new(change_observer),
+            unpaired: Output::new(change_observer),
+            input: Input::new(),
+            regions: Input::new(),
+            config,
+            frames: VecDeque::new(),
+            pending: VecDeque::new(),
+            unpaired_total: 0,
+            seq: 0,
+            previous_hash: None,
+            resumed: false,
+        }
+    }
+
+    /// Picks up `seq` and `previous_hash` from the last entry of an existing log.
+    fn resume(&mut self) -> Result<(), anyhow::Error> {
+        let Some(path) = &self.config.log_path else { return Ok(()) };
+        let log = match std::fs::read_to_string(path) {
+            Ok(log) => log,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e.into()),
+        };
+        if let Some(line) = log.lines().rev().find(|l| !l.trim().is_empty()) {
+            let last: AuditEntry = serde_json::from_str(line)
+                .map_err(|e| anyhow::anyhow!("Last entry of {} is unreadable: {}", path.display(), e))?;
+            self.seq = last.seq + 1;
+            self.previous_hash = Some(last.entry_hash);
+        }
+        Ok(())
+    }
+
+    fn record(&mut self, img: &DynamicImage, detections: Vec<Detection>) -> AuditEntry {
+        let regions = detections
+            .into_iter()
+            .filter_map(|d| {
+                let rect = d.rect.clamp_to(img.width(), img.height())?;
+                let hash = frame_hash(&img.crop_imm(rect.x, rect.y, rect.width, rect.height));
+                Some(RedactedRegion { rect, reason: d.label, hash })
+            })
+            .collect();
+        let timestamp_ms = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_millis() as u64);
+        let mut entry = AuditEntry {
+            seq: self.seq,
+            timestamp_ms,
+            frame_hash: frame_hash(img),
+            regions,
+            previous_hash: self.previous_hash.clone().unwrap_or_else(|| GENESIS_HASH.to_string()),
+            entry_hash: String::new(),
+        };
+        entry.entry_hash = entry.digest();
+        self.seq += 1;
+        self.previous_hash = Some(entry.entry_hash.clone());
+        entry
+    }
+}
+
+impl Node for AnonymizationAuditNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        if !self.resumed {
+            self.resume().map_err(UpdateError::Other)?;
+            self.resumed = true;
+        }
+
+        while let Ok(regions) = self.regions.next() {
+            self.pending.push_back(regions);
+        }
+        while let Ok(img) = self.input.next() {
+            self.frames.push_back(img);
+        }
+
+        let mut unpaired = 0;
+        while let Some(img) = self.frames.pop_front() {
+            let detections = match self.pending.pop_front() {
+                Some(detections) => detections,
+                None if self.frames.len() >= MAX_PENDING => {
+                    unpaired += 1;
+                    Vec::new()
+                }
+                None => {
+                    self.frames.push_front(img);
+                    break;
+                }
+            };
+            let entry = self.record(&img, detections);
+
+            if let Some(path) = &self.config.log_path {
+                let line = serde_json::to_string(&entry).map_err(|e| UpdateError::Other(e.into()))?;
+                std::fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path)
+                    .and_then(|mut f| writeln!(f, "{}", line))
+                    .map_err(|e| UpdateError::Other(e.into()))?;
+            }
+
+            self.log.send(entry).map_err(|e| UpdateError::Other(e.into()))?;
+            self.output.send(img).map_err(|e| UpdateError::Other(e.into()))?;
+        }
+
+        // The oldest lists belong to the frames that arrive next, so the newest are dropped.
+        if self.pending.len() > MAX_PENDING {
+            unpaired += (self.pending.len() - MAX_PENDING) as u64;
+            self.pending.truncate(MAX_PENDING);
+        }
+        if unpaired > 0 {
+            self.unpaired_total += unpaired;
+            self.unpaired.send(self.unpaired_total).map_err(|e| UpdateError::Other(e.into()))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+pub enum MaskStyle {
+    Blur { sigma: f32 },
+    Pixelate { block_size: u32 },
+    Fill([u8; 3]),
+}
+
+impl Default for MaskStyle {
+    fn default() -> Self {
+        MaskStyle::Pixelate { block_size: 16 }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct PrivacyMaskNodeConfig {
+    /// Static areas always masked, e.g. neighbouring windows, in pixel coordinates.
+    pub polygons: Vec<Vec<(f32, f32)>>,
+    pub style: MaskStyle,
+    /// Pixels added around each detected region, covering detector imprecision.
+    pub padding: u32,
+    /// Detection labels to mask; all detections if empty.
+    pub labels: Vec<String>,
+    /// Hold every frame until its detection list arrives on `regions`. Disable only when no
+    /// detector is connected and the static `polygons` are all that is masked.
+    pub wait_for_detections: bool,
+    /// Frames held while the detector lags; older frames are sent blacked out instead.
+    pub max_pending: usize,
+}
+
+impl Default for PrivacyMaskNodeConfig {
+    fn default() -> Self {
+        Self {
+            polygons: Vec::new(),
+            style: MaskStyle::default(),
+            padding: 0,
+            labels: Vec::new(),
+            wait_for_detections: true,
+            max_pending: 8,
+        }
+    }
+}
+
+impl Validate for PrivacyMaskNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        ensure(self.polygons.iter().all(|p| p.len() >= 3), "polygons", "need at least 3 points each")?;
+        ensure(self.max_pending > 0, "max_pending", "must be positive")
+    }
+}
+
+config_builder!(PrivacyMaskNodeConfig for PrivacyMaskNode {
+    polygons: Vec<Vec<(f32, f32)>>,
+    style: MaskStyle,
+    padding: u32,
+    labels: Vec<String>,
+    wait_for_detections: bool,
+    max_pending: usize,
+});
+
+/// Label of the region reported for a frame blacked out because its detections never arrived.
+pub const UNPAIRED_LABEL: &str = "unpaired";
+
+/// Mask of the pixels covered by any polygon or rectangle; masked pixels are 255.
+pub fn privacy_mask(width: u32, height: u32, polygons: &[Vec<(f32, f32)>], rects: &[Rect]) -> GrayImage {
+    GrayImage::from_fn(width, height, |x, y| {
+        let center = (x as f32 + 0.5, y as f32 + 0.5);
+        let covered = rects.iter().any(|r| r.contains(x, y))
+            || polygons.iter().any(|p| p.len() >= 3 && point_in_polygon(center, p));
+        Luma([if covered { 255 } else { 0 }])
+    })
+}
+
+/// Replaces the masked pixels; blur and pixelation only sample the masked bounding box.
+pub fn apply_privacy_mask(img: &mut RgbImage, mask: &GrayImage, style: MaskStyle) {
+    let covered = mask.enumerate_pixels().filter(|(_, _, m)| m[0] > 0);
+    let Some(bounds) = covered.fold(None, |acc: Option<(u32, u32, u32, u32)>, (x, y, _)| match acc {
+        None => Some((x, y, x, y)),
+        Some((x0, y0, x1, y1)) => Some((x0.min(x), y0.min(y), x1.max(x), y1.max(y))),
+    }) else { return };
+    let (x0, y0) = (bounds.0, bounds.1);
+    let (w, h) = (bounds.2 - x0 + 1, bounds.3 - y0 + 1);
+
+    let replacement = match style {
+        MaskStyle::Fill(color) => RgbImage::from_pixel(w, h, Rgb(color)),
+        MaskStyle::Blur { sigma } => image::imageops::blur(&image::imageops::crop_imm(img, x0, y0, w, h).to_image(), sigma),
+        MaskStyle::Pixelate { block_size } => {
+            let block = block_size.max(1);
+            let region = image::imageops::crop_imm(img, x0, y0, w, h).to_image();
+            let mut out = region.clone();
+            for by in (0..h).step_by(block as usize) {
+                for bx in (0..w).step_by(block as usize) {
+                    let (bw, bh) = (block.min(w - bx), block.min(h - by));
+                    let mut sum = [0u64; 3];
+                    for y in by..by + bh {
+                        for x in bx..bx + bw {
+                            (0..3).for_each(|c| sum[c] += region.get_pixel(x, y)[c] as u64);
+                        }
+                    }
+                    let mean = Rgb(sum.map(|s| (s / (bw * bh) as u64) as u8));
+                    for y in by..by + bh {
+                        for x in bx..bx + bw {
+                            out.put_pixel(x, y, mean);
+                        }
+                    }
+                }
+            }
+            out
+        }
+    };
+
+    for y in 0..h {
+        for x in 0..w {
+            if mask.get_pixel(x0 + x, y0 + y)[0] > 0 {
+                img.put_pixel(x0 + x, y0 + y, *replacement.get_pixel(x, y));
+            }
+        }
+    }
+}
+
+/// Blurs, pixelates or blacks out static areas and detected regions before recording.
+///
+/// The node fails closed: each frame is held until its own detection list (e.g.
+/// faces) arrives, pairing frames and lists in arrival order, so no frame leaves
+/// with missing or stale regions. When more than `max_pending` frames wait, the
+/// oldest is sent blacked out. The masked regions are sent on `masked` for an
+/// [`AnonymizationAuditNode`].
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct PrivacyMaskNode {
+    #[output]
+    pub output: Output<DynamicImage>,
+
+    #[output]
+    pub masked: Output<Vec<Detection>>,
+
+    #[input]
+    pub input: Input<DynamicImage>,
+
+    #[input]
+    pub regions: Input<Vec<Detection>>,
+
+    pub config: PrivacyMaskNodeConfig,
+
+    #[serde(skip)]
+    frames: VecDeque<DynamicImage>,
+    #[serde(skip)]
+    detections: VecDeque<Vec<Detection>>,
+}
+
+impl PrivacyMaskNode {
+    pub fn new(config: PrivacyMaskNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            masked: Output::new(change_observer),
+            input: Input::new(),
+            regions: Input::new(),
+            config,
+            frames: VecDeque::new(),
+            detections: VecDeque::new(),
+        }
+    }
+
+    /// Masks the polygons and `detections`, grown by the padding, keeping the format of `img`.
+    fn mask(&self, img: &DynamicImage, detections: Vec<Detection>) -> (DynamicImage, Vec<Detection>) {
+        let (width, height) = (img.width(), img.height());
+        let pad = self.config.padding;
+        let masked: Vec<Detection> = detections
+            .into_iter()
+            .filter(|d| self.config.labels.is_empty() || self.config.labels.contains(&d.label))
+            .filter_map(|d| {
+                let r = d.rect;
+                let (x0, y0) = (r.x.saturating_sub(pad), r.y.saturating_sub(pad));
+                let x1 = r.x.saturating_add(r.width).saturating_add(pad).min(width);
+                let y1 = r.y.saturating_add(r.height).saturating_add(pad).min(height);
+                let padded = Rect::new(x0, y0, x1.saturating_sub(x0), y1.saturating_sub(y0));
+                Some(Detection { rect: padded.clamp_to(width, height)?, ..d })
+            })
+            .collect();
+        let rects: Vec<Rect> = masked.iter().map(|d| d.rect).collect();
+        let mask = privacy_mask(width, height, &self.config.polygons, &rects);
+
+        // Only masked pixels go through 8 bits; the rest keep the depth of the input.
+        let mut rgb = img.to_rgb8();
+        apply_privacy_mask(&mut rgb, &mask, self.config.style);
+        let mut out = img.to_rgb32f();
+        for (x, y, m) in mask.enumerate_pixels() {
+            if m[0] > 0 {
+                out.put_pixel(x, y, Rgb(rgb.get_pixel(x, y).0.map(|v| v as f32 / 255.0)));
+            }
+        }
+        (match_format(out, img), masked)
+    }
+
+    fn send(&mut self, img: DynamicImage, masked: Vec<Detection>) -> Result<(), UpdateError> {
+        self.masked.send(masked).map_err(|e| UpdateError::Other(e.into()))?;
+        self.output.send(img).map_err(|e| UpdateError::Other(e.into()))
+    }
+}
+
+impl Node for PrivacyMaskNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        while let Ok(regions) = self.regions.next() {
+            if self.config.wait_for_detections {
+                self.detections.push_back(regions);
+            }
+        }
+        while let Ok(img) = self.input.next() {
+            self.frames.push_back(img);
+        }
+
+        while !self.frames.is_empty() {
+            let detections = match self.detections.pop_front() {
+                Some(detections) => detections,
+                None if !self.config.wait_for_detections => Vec::new(),
+                None if self.frames.len() > self.config.max_pending.max(1) => {
+                    let img = self.frames.pop_front().expect("checked");
+                    let (width, height) = (img.width(), img.height());
+                    let blank = match_format(image::Rgb32FImage::new(width, height), &img);
+                    let region = Detection { rect: Rect::new(0, 0, width, height), label: UNPAIRED_LABEL.into(), score: 1.0 };
+                    self.send(blank, vec![region])?;
+                    continue;
+                }
+                None => break,
+            };
+            let img = self.frames.pop_front().expect("checked");
+            frame_span!("privacy_mask", width = img.width(), height = img.height());
+            let (masked_img, masked) = self.mask(&img, detections);
+            self.send(masked_img, masked)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,673 @@
+use flowrs::{node::{Node, UpdateError, ChangeObserver}, connection::{Input, Output}};
+use flowrs::RuntimeConnectable;
+
+use std::borrow::Cow;
+use std::collections::VecDeque;
+use std::sync::{mpsc, Arc, OnceLock};
+
+use image::{DynamicImage, RgbaImage};
+use anyhow::anyhow;
+use wgpu::util::DeviceExt;
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::prelude::{wasm_bindgen, JsValue};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::{ensure, ConfigError, Validate};
+
+const RESIZE_SHADER: &str = r#"
+struct Params { src_w: u32, src_h: u32, dst_w: u32, dst_h: u32 }
+
+@group(0) @binding(0) var<storage, read> src: array<u32>;
+@group(0) @binding(1) var<storage, read_write> dst: array<u32>;
+@group(0) @binding(2) var<uniform> params: Params;
+
+fn load(x: i32, y: i32) -> vec4<f32> {
+    let cx = u32(clamp(x, 0, i32(params.src_w) - 1));
+    let cy = u32(clamp(y, 0, i32(params.src_h) - 1));
+    return unpack4x8unorm(src[cy * params.src_w + cx]);
+}
+
+@compute @workgroup_size(16, 16)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    if (id.x >= params.dst_w || id.y >= params.dst_h) { return; }
+    let sx = (f32(id.x) + 0.5) * f32(params.src_w) / f32(params.dst_w) - 0.5;
+    let sy = (f32(id.y) + 0.5) * f32(params.src_h) / f32(params.dst_h) - 0.5;
+    let x0 = i32(floor(sx));
+    let y0 = i32(floor(sy));
+    let fx = sx - floor(sx);
+    let fy = sy - floor(sy);
+    let top = mix(load(x0, y0), load(x0 + 1, y0), fx);
+    let bottom = mix(load(x0, y0 + 1), load(x0 + 1, y0 + 1), fx);
+    dst[id.y * params.dst_w + id.x] = pack4x8unorm(mix(top, bottom, fy));
+}
+"#;
+
+const BLUR_SHADER: &str = r#"
+struct Params { width: u32, height: u32, radius: u32, horizontal: u32, sigma: f32, _pad0: f32, _pad1: f32, _pad2: f32 }
+
+@group(0) @binding(0) var<storage, read> src: array<u32>;
+@group(0) @binding(1) var<storage, read_write> dst: array<u32>;
+@group(0) @binding(2) var<uniform> params: Params;
+
+@compute @workgroup_size(16, 16)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    if (id.x >= params.width || id.y >= params.height) { return; }
+    var acc = vec4<f32>(0.0);
+    var total = 0.0;
+    let r = i32(params.radius);
+    for (var i = -r; i <= r; i = i + 1) {
+        var x = i32(id.x);
+        var y = i32(id.y);
+        if (params.horizontal == 1u) { x = clamp(x + i, 0, i32(params.width) - 1); }
+        else { y = clamp(y + i, 0, i32(params.height) - 1); }
+        let w = exp(-f32(i * i) / (2.0 * params.sigma * params.sigma));
+        acc = acc + w * unpack4x8unorm(src[u32(y) * params.width + u32(x)]);
+        total = total + w;
+    }
+    dst[id.y * params.width + id.x] = pack4x8unorm(acc / total);
+}
+"#;
+
+const COLOR_SHADER: &str = r#"
+struct Params { width: u32, height: u32, mode: u32, _pad: u32 }
+
+@group(0) @binding(0) var<storage, read> src: array<u32>;
+@group(0) @binding(1) var<storage, read_write> dst: array<u32>;
+@group(0) @binding(2) var<uniform> params: Params;
+
+@compute @workgroup_size(16, 16)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    if (id.x >= params.width || id.y >= params.height) { return; }
+    let i = id.y * params.width + id.x;
+    let c = unpack4x8unorm(src[i]);
+    var out = c;
+    if (params.mode == 0u) {
+        let l = dot(c.rgb, vec3<f32>(0.2126, 0.7152, 0.0722));
+        out = vec4<f32>(l, l, l, c.a);
+    } else if (params.mode == 1u) {
+        out = c.bgra;
+    } else if (params.mode == 2u) {
+        out = vec4<f32>(1.0 - c.rgb, c.a);
+    }
+    dst[i] = pack4x8unorm(out);
+}
+"#;
+
+const WARP_SHADER: &str = r#"
+struct Params { src_w: u32, src_h: u32, dst_w: u32, dst_h: u32, row0: vec4<f32>, row1: vec4<f32>, row2: vec4<f32> }
+
+@group(0) @binding(0) var<storage, read> src: array<u32>;
+@group(0) @binding(1) var<storage, read_write> dst: array<u32>;
+@group(0) @binding(2) var<uniform> params: Params;
+
+fn load(x: i32, y: i32) -> vec4<f32> {
+    if (x < 0 || y < 0 || x >= i32(params.src_w) || y >= i32(params.src_h)) { return vec4<f32>(0.0); }
+    return unpack4x8unorm(src[u32(y) * params.src_w + u32(x)]);
+}
+
+@compute @workgroup_size(16, 16)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    if (id.x >= params.dst_w || id.y >= params.dst_h) { return; }
+    let p = vec3<f32>(f32(id.x), f32(id.y), 1.0);
+    let w = dot(params.row2.xyz, p);
+    let sx = dot(params.row0.xyz, p) / w;
+    let sy = dot(params.row1.xyz, p) / w;
+    let x0 = i32(floor(sx));
+    let y0 = i32(floor(sy));
+    let fx = sx - floor(sx);
+    let fy = sy - floor(sy);
+    let top = mix(load(x0, y0), load(x0 + 1, y0), fx);
+    let bottom = mix(load(x0, y0 + 1), load(x0 + 1, y0 + 1), fx);
+    dst[id.y * params.dst_w + id.x] = pack4x8unorm(mix(top, bottom, fy));
+}
+"#;
+
+const WORKGROUP_SIZE: u32 = 16;
+
+/// Device and queue shared by all GPU nodes, so images can stay in GPU memory between them.
+pub struct GpuContext {
+    pub device: wgpu::Device,
+    pub queue: wgpu::Queue,
+}
+
+static SHARED_CONTEXT: OnceLock<Result<Arc<GpuContext>, String>> = OnceLock::new();
+
+impl GpuContext {
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn new() -> Result<Self, anyhow::Error> {
+        pollster::block_on(Self::request())
+    }
+
+    /// Picks an adapter and opens a device; in the browser this goes through WebGPU.
+    pub async fn request() -> Result<Self, anyhow::Error> {
+        let instance = wgpu::Instance::new(wgpu::InstanceDescriptor::default());
+        let adapter = instance
+            .request_adapter(&wgpu::RequestAdapterOptions {
+                power_preference: wgpu::PowerPreference::HighPerformance,
+                compatible_surface: None,
+                force_fallback_adapter: false,
+            })
+            .await
+            .ok_or_else(|| anyhow!("No GPU adapter available."))?;
+
+        let (device, queue) = adapter.request_device(&wgpu::DeviceDescriptor::default(), None).await?;
+        Ok(Self { device, queue })
+    }
+
+    /// Lazily created process-wide context.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn shared() -> Result<Arc<GpuContext>, anyhow::Error> {
+        SHARED_CONTEXT
+            .get_or_init(|| GpuContext::new().map(Arc::new).map_err(|e| e.to_string()))
+            .clone()
+            .map_err(|e| anyhow!("GPU initialization failed: {}", e))
+    }
+
+    /// Context created by [`init_gpu`], as the browser cannot block until a device is ready.
+    #[cfg(target_arch = "wasm32")]
+    pub fn shared() -> Result<Arc<GpuContext>, anyhow::Error> {
+        SHARED_CONTEXT
+            .get()
+            .ok_or_else(|| anyhow!("GPU is not initialized; await init_gpu() before starting the flow."))?
+            .clone()
+            .map_err(|e| anyhow!("GPU initialization failed: {}", e))
+    }
+
+    fn pipeline(&self, source: &str) -> wgpu::ComputePipeline {
+        let module = self.device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: None,
+            source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(source)),
+        });
+        self.device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: None,
+            layout: None,
+            module: &module,
+            entry_point: "main",
+        })
+    }
+
+    fn storage_buffer(&self, size: u64) -> wgpu::Buffer {
+        self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        })
+    }
+
+    /// Runs `pipeline` reading `src` and producing a new image of the given size.
+    fn dispatch(&self, pipeline: &wgpu::ComputePipeline, src: &GpuImage, width: u32, height: u32, params: &[u8]) -> GpuImage {
+        let dst = self.storage_buffer(width as u64 * height as u64 * 4);
+        let uniform = self.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: None,
+            contents: params,
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+        let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: None,
+            layout: &pipeline.get_bind_group_layout(0),
+            entries: &[
+                wgpu::BindGroupEntry { binding: 0, resource: src.buffer.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 1, resource: dst.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 2, resource: uniform.as_entire_binding() },
+            ],
+        });
+
+        let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+        {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor::default());
+            pass.set_pipeline(pipeline);
+            pass.set_bind_group(0, &bind_group, &[]);
+            pass.dispatch_workgroups(width.div_ceil(WORKGROUP_SIZE), height.div_ceil(WORKGROUP_SIZE), 1);
+        }
+        self.queue.submit(Some(encoder.finish()));
+
+        GpuImage { buffer: Arc::new(dst), width, height }
+    }
+}
+
+/// RGBA8 image resident in GPU memory.
+#[derive(Clone)]
+pub struct GpuImage {
+    buffer: Arc<wgpu::Buffer>,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl GpuImage {
+    /// Copies `img` into GPU memory; empty images are rejected, as wgpu does not allow empty buffers.
+    pub fn upload(ctx: &GpuContext, img: &DynamicImage) -> Result<Self, anyhow::Error> {
+        if img.width() == 0 || img.height() == 0 {
+            return Err(anyhow!("Cannot upload an empty {}x{} image to the GPU.", img.width(), img.height()));
+        }
+        let rgba = img.to_rgba8();
+        let buffer = ctx.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: None,
+            contents: rgba.as_raw(),
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+        });
+        Ok(Self { buffer: Arc::new(buffer), width: rgba.width(), height: rgba.height() })
+    }
+
+    /// Starts copying the image back into CPU memory.
+    pub fn start_download(&self, ctx: &GpuContext) -> Readback {
+        let size = self.width as u64 * self.height as u64 * 4;
+        let staging = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let mut encoder = ctx.device.create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+        encoder.copy_buffer_to_buffer(&self.buffer, 0, &staging, 0, size);
+        ctx.queue.submit(Some(encoder.finish()));
+
+        let (tx, mapped) = mpsc::channel();
+        staging.slice(..).map_async(wgpu::MapMode::Read, move |result| {
+            let _ = tx.send(result);
+        });
+        Readback { staging, mapped, width: self.width, height: self.height }
+    }
+
+    /// Blocks until the copy has finished, which browsers do not allow.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn download(&self, ctx: &GpuContext) -> Result<DynamicImage, anyhow::Error> {
+        let readback = self.start_download(ctx);
+        ctx.device.poll(wgpu::Maintain::Wait);
+        readback.try_take()?.ok_or_else(|| anyhow!("GPU readback did not finish."))
+    }
+}
+
+/// A copy from GPU to CPU memory in flight.
+pub struct Readback {
+    staging: wgpu::Buffer,
+    mapped: mpsc::Receiver<Result<(), wgpu::BufferAsyncError>>,
+    width: u32,
+    height: u32,
+}
+
+impl Readback {
+    /// The image, once the device has been polled past the copy.
+    pub fn try_take(&self) -> Result<Option<DynamicImage>, anyhow::Error> {
+        match self.mapped.try_recv() {
+            Ok(result) => result?,
+            Err(mpsc::TryRecvError::Empty) => return Ok(None),
+            Err(e) => return Err(e.into()),
+        }
+        let data = self.staging.slice(..).get_mapped_range().to_vec();
+        self.staging.unmap();
+        RgbaImage::from_raw(self.width, self.height, data)
+            .map(|rgba| Some(DynamicImage::ImageRgba8(rgba)))
+            .ok_or_else(|| anyhow!("GPU readback returned a buffer of unexpected size."))
+    }
+}
+
+/// Creates the shared GPU context in the browser; must be awaited before GPU nodes run.
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen]
+pub async fn init_gpu() -> Result<(), JsValue> {
+    let ctx = GpuContext::request().await.map(Arc::new).map_err(|e| e.to_string());
+    let failed = ctx.as_ref().err().cloned();
+    let _ = SHARED_CONTEXT.set(ctx);
+    failed.map_or(Ok(()), |e| Err(JsValue::from_str(&e)))
+}
+
+fn params_bytes(words: &[u32]) -> Vec<u8> {
+    words.iter().flat_map(|w| w.to_le_bytes()).collect()
+}
+
+fn shared_context() -> Result<Arc<GpuContext>, UpdateError> {
+    GpuContext::shared().map_err(UpdateError::Other)
+}
+
+/// Uploads CPU images into GPU memory.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct GpuUploadNode {
+    #[output]
+    pub output: Output<GpuImage>,
+
+    #[input]
+    pub input: Input<DynamicImage>,
+}
+
+impl GpuUploadNode {
+    pub fn new(change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            input: Input::new(),
+        }
+    }
+}
+
+impl Node for GpuUploadNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+        if let Ok(img) = self.input.next() {
+            let ctx = shared_context()?;
+            let gpu = GpuImage::upload(&ctx, &img).map_err(UpdateError::Other)?;
+            self.output.send(gpu).map_err(|e| UpdateError::Other(e.into()))?;
+        }
+        Ok(())
+    }
+}
+
+/// Reads GPU images back into CPU memory.
+///
+/// Natively each frame is read back within the update that received it. In
+/// the browser the copy completes asynchronously and the frame is sent by a
+/// later update, in the order the frames arrived.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct GpuDownloadNode {
+    #[output]
+    pub output: Output<DynamicImage>,
+
+    #[input]
+    pub input: Input<GpuImage>,
+
+    #[serde(skip)]
+    pending: VecDeque<Readback>,
+}
+
+impl GpuDownloadNode {
+    pub fn new(change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            input: Input::new(),
+            pending: VecDeque::new(),
+        }
+    }
+}
+
+impl Node for GpuDownloadNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+        if let Ok(gpu) = self.input.next() {
+            let ctx = shared_context()?;
+            self.pending.push_back(gpu.start_download(&ctx));
+        }
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+
+        let ctx = shared_context()?;
+        ctx.device.poll(if cfg!(target_arch = "wasm32") { wgpu::Maintain::Poll } else { wgpu::Maintain::Wait });
+        while let Some(readback) = self.pending.front() {
+            let img = match readback.try_take() {
+                Ok(Some(img)) => img,
+                Ok(None) => break,
+                // A failed readback never finishes, so it must not block the ones behind it.
+                Err(e) => {
+                    self.pending.pop_front();
+                    return Err(UpdateError::Other(e));
+                }
+            };
+            self.pending.pop_front();
+            self.output.send(img).map_err(|e| UpdateError::Other(e.into()))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct GpuResizeNodeConfig {
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Default for GpuResizeNodeConfig {
+    fn default() -> Self {
+        Self { width: 640, height: 480 }
+    }
+}
+
+impl Validate for GpuResizeNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        ensure(self.width > 0 && self.height > 0, "width", "frames must not be empty")
+    }
+}
+
+config_builder!(GpuResizeNodeConfig for GpuResizeNode { width: u32, height: u32 });
+
+/// Bilinear resize on the GPU.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct GpuResizeNode {
+    #[output]
+    pub output: Output<GpuImage>,
+
+    #[input]
+    pub input: Input<GpuImage>,
+
+    pub config: GpuResizeNodeConfig,
+
+    #[serde(skip)]
+    pipeline: Option<wgpu::ComputePipeline>,
+}
+
+impl GpuResizeNode {
+    pub fn new(config: GpuResizeNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            input: Input::new(),
+            config,
+            pipeline: None,
+        }
+    }
+}
+
+impl Node for GpuResizeNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+        if let Ok(src) = self.input.next() {
+            let ctx = shared_context()?;
+            let pipeline = self.pipeline.get_or_insert_with(|| ctx.pipeline(RESIZE_SHADER));
+            let (w, h) = (self.config.width, self.config.height);
+            let params = params_bytes(&[src.width, src.height, w, h]);
+            let dst = ctx.dispatch(pipeline, &src, w, h, &params);
+            self.output.send(dst).map_err(|e| UpdateError::Other(e.into()))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct GpuBlurNodeConfig {
+    pub sigma: f32,
+}
+
+impl Default for GpuBlurNodeConfig {
+    fn default() -> Self {
+        Self { sigma: 1.0 }
+    }
+}
+
+impl Validate for GpuBlurNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        ensure(self.sigma > 0.0, "sigma", "must be positive")
+    }
+}
+
+config_builder!(GpuBlurNodeConfig for GpuBlurNode { sigma: f32 });
+
+/// Separable Gaussian blur on the GPU.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct GpuBlurNode {
+    #[output]
+    pub output: Output<GpuImage>,
+
+    #[input]
+    pub input: Input<GpuImage>,
+
+    pub config: GpuBlurNodeConfig,
+
+    #[serde(skip)]
+    pipeline: Option<wgpu::ComputePipeline>,
+}
+
+impl GpuBlurNode {
+    pub fn new(config: GpuBlurNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            input: Input::new(),
+            config,
+            pipeline: None,
+        }
+    }
+}
+
+impl Node for GpuBlurNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+        if let Ok(src) = self.input.next() {
+            let ctx = shared_context()?;
+            let pipeline = self.pipeline.get_or_insert_with(|| ctx.pipeline(BLUR_SHADER));
+            let sigma = self.config.sigma.max(0.01);
+            let radius = (sigma * 3.0).ceil() as u32;
+            let (w, h) = (src.width, src.height);
+
+            let pass = |horizontal: u32| {
+                params_bytes(&[w, h, radius, horizontal, sigma.to_bits(), 0, 0, 0])
+            };
+            let tmp = ctx.dispatch(pipeline, &src, w, h, &pass(1));
+            let dst = ctx.dispatch(pipeline, &tmp, w, h, &pass(0));
+            self.output.send(dst).map_err(|e| UpdateError::Other(e.into()))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize)]
+pub enum GpuColorOp {
+    #[default]
+    Grayscale,
+    SwapRedBlue,
+    Invert,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct GpuColorConvertNodeConfig {
+    pub op: GpuColorOp,
+}
+
+impl Validate for GpuColorConvertNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        Ok(())
+    }
+}
+
+config_builder!(GpuColorConvertNodeConfig for GpuColorConvertNode { op: GpuColorOp });
+
+/// Per-pixel color conversion on the GPU.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct GpuColorConvertNode {
+    #[output]
+    pub output: Output<GpuImage>,
+
+    #[input]
+    pub input: Input<GpuImage>,
+
+    pub config: GpuColorConvertNodeConfig,
+
+    #[serde(skip)]
+    pipeline: Option<wgpu::ComputePipeline>,
+}
+
+impl GpuColorConvertNode {
+    pub fn new(config: GpuColorConvertNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            input: Input::new(),
+            config,
+            pipeline: None,
+        }
+    }
+}
+
+impl Node for GpuColorConvertNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+        if let Ok(src) = self.input.next() {
+            let ctx = shared_context()?;
+            let pipeline = self.pipeline.get_or_insert_with(|| ctx.pipeline(COLOR_SHADER));
+            let mode = match self.config.op {
+                GpuColorOp::Grayscale => 0,
+                GpuColorOp::SwapRedBlue => 1,
+                GpuColorOp::Invert => 2,
+            };
+            let params = params_bytes(&[src.width, src.height, mode, 0]);
+            let dst = ctx.dispatch(pipeline, &src, src.width, src.height, &params);
+            self.output.send(dst).map_err(|e| UpdateError::Other(e.into()))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct GpuWarpNodeConfig {
+    /// Row-major 3x3 homography mapping output pixel coordinates to source coordinates.
+    pub inverse_matrix: [f32; 9],
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Default for GpuWarpNodeConfig {
+    fn default() -> Self {
+        Self {
+            inverse_matrix: [1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0],
+            width: 640,
+            height: 480,
+        }
+    }
+}
+
+impl Validate for GpuWarpNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        ensure(self.width > 0 && self.height > 0, "width", "frames must not be empty")
+    }
+}
+
+config_builder!(GpuWarpNodeConfig for GpuWarpNode { inverse_matrix: [f32; 9], width: u32, height: u32 });
+
+/// Perspective/affine warp on the GPU.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct GpuWarpNode {
+    #[output]
+    pub output: Output<GpuImage>,
+
+    #[input]
+    pub input: Input<GpuImage>,
+
+    pub config: GpuWarpNodeConfig,
+
+    #[serde(skip)]
+    pipeline: Option<wgpu::ComputePipeline>,
+}
+
+impl GpuWarpNode {
+    pub fn new(config: GpuWarpNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            input: Input::new(),
+            config,
+            pipeline: None,
+        }
+    }
+}
+
+impl Node for GpuWarpNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+        if let Ok(src) = self.input.next() {
+            let ctx = shared_context()?;
+            let pipeline = self.pipeline.get_or_insert_with(|| ctx.pipeline(WARP_SHADER));
+            let m = self.config.inverse_matrix;
+            let (w, h) = (self.config.width, self.config.height);
+            let mut words = vec![src.width, src.height, w, h];
+            for row in m.chunks_exact(3) {
+                words.extend(row.iter().map(|v| v.to_bits()));
+                words.push(0);
+            }
+            let dst = ctx.dispatch(pipeline, &src, w, h, &params_bytes(&words));
+            self.output.send(dst).map_err(|e| UpdateError::Other(e.into()))?;
+        }
+        Ok(())
+    }
+}
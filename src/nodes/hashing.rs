@@ -0,0 +1,240 @@
+use flowrs::{node::{Node, UpdateError, ChangeObserver}, connection::{Input, Output}};
+use flowrs::RuntimeConnectable;
+
+use std::collections::VecDeque;
+use std::f32::consts::PI;
+
+use image::{DynamicImage, GrayImage, imageops::FilterType};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::{ensure, ConfigError, Validate};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub enum HashKind {
+    /// Each bit tells whether a pixel of an 8x8 thumbnail is brighter than the mean.
+    Average,
+    /// Each bit tells whether a pixel is darker than its right neighbour.
+    Difference,
+    /// Each bit tells whether a low DCT frequency is above the median; the most robust.
+    #[default]
+    Perceptual,
+}
+
+/// A 64-bit perceptual hash; similar images have a small Hamming distance.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct ImageHash {
+    pub kind: HashKind,
+    pub bits: u64,
+}
+
+impl ImageHash {
+    /// Number of differing bits, or `None` if the hashes were computed differently.
+    pub fn distance(&self, other: &ImageHash) -> Option<u32> {
+        (self.kind == other.kind).then(|| (self.bits ^ other.bits).count_ones())
+    }
+}
+
+fn thumbnail(img: &DynamicImage, width: u32, height: u32) -> GrayImage {
+    img.resize_exact(width, height, FilterType::Triangle).to_luma8()
+}
+
+fn pack(bits: impl Iterator<Item = bool>) -> u64 {
+    bits.take(64).enumerate().fold(0, |acc, (i, bit)| acc | ((bit as u64) << i))
+}
+
+fn dct_1d(values: &[f32]) -> Vec<f32> {
+    let n = values.len() as f32;
+    (0..values.len())
+        .map(|k| {
+            values
+                .iter()
+                .enumerate()
+                .map(|(i, v)| v * (PI * k as f32 * (2.0 * i as f32 + 1.0) / (2.0 * n)).cos())
+                .sum()
+        })
+        .collect()
+}
+
+pub fn image_hash(img: &DynamicImage, kind: HashKind) -> ImageHash {
+    let bits = match kind {
+        HashKind::Average => {
+            let thumb = thumbnail(img, 8, 8);
+            let mean = thumb.as_raw().iter().map(|&v| v as u32).sum::<u32>() / 64;
+            pack(thumb.as_raw().iter().map(|&v| v as u32 > mean))
+        }
+        HashKind::Difference => {
+            let thumb = thumbnail(img, 9, 8);
+            pack((0..8).flat_map(|y| (0..8).map(move |x| (x, y))).map(|(x, y)| {
+                thumb.get_pixel(x, y)[0] < thumb.get_pixel(x + 1, y)[0]
+            }))
+        }
+        HashKind::Perceptual => {
+            const SIZE: usize = 32;
+            let thumb = thumbnail(img, SIZE as u32, SIZE as u32);
+            let rows: Vec<Vec<f32>> = thumb
+                .as_raw()
+                .chunks(SIZE)
+                .map(|row| dct_1d(&row.iter().map(|&v| v as f32).collect::<Vec<_>>()))
+                .collect();
+            // Only the 8x8 lowest frequencies are kept, so only 8 column transforms are needed.
+            let columns: Vec<Vec<f32>> = (0..8)
+                .map(|x| dct_1d(&rows.iter().map(|row| row[x]).collect::<Vec<_>>()))
+                .collect();
+            let low: Vec<f32> = (0..8).flat_map(|y| columns.iter().map(move |c| c[y])).collect();
+            // The DC term only reflects overall brightness and would skew the median.
+            let mut sorted = low[1..].to_vec();
+            sorted.sort_by(|a, b| a.total_cmp(b));
+            let median = sorted[sorted.len() / 2];
+            pack(low.iter().map(|&v| v > median))
+        }
+    };
+    ImageHash { kind, bits }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ImageHashNodeConfig {
+    pub kind: HashKind,
+}
+
+impl Validate for ImageHashNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        Ok(())
+    }
+}
+
+config_builder!(ImageHashNodeConfig for ImageHashNode { kind: HashKind });
+
+/// Computes a perceptual hash of every image.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct ImageHashNode {
+    #[output]
+    pub output: Output<ImageHash>,
+
+    #[input]
+    pub input: Input<DynamicImage>,
+
+    pub config: ImageHashNodeConfig,
+}
+
+impl ImageHashNode {
+    pub fn new(config: ImageHashNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            input: Input::new(),
+            config,
+        }
+    }
+}
+
+impl Node for ImageHashNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        if let Ok(img) = self.input.next() {
+            self.output.send(image_hash(&img, self.config.kind)).map_err(|e| UpdateError::Other(e.into()))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct HashDistanceNodeConfig {
+    /// Hashes at most this many bits apart count as duplicates.
+    pub max_distance: u32,
+    /// Number of recent hashes each new one is compared against.
+    pub history: usize,
+}
+
+impl Default for HashDistanceNodeConfig {
+    fn default() -> Self {
+        Self { max_distance: 10, history: 16 }
+    }
+}
+
+impl Validate for HashDistanceNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        ensure(self.max_distance <= 64, "max_distance", "must be at most 64 bits")?;
+        ensure(self.history > 0, "history", "must be positive")
+    }
+}
+
+config_builder!(HashDistanceNodeConfig for HashDistanceNode { max_distance: u32, history: usize });
+
+/// Compares hashes against a reference set or the recent history.
+///
+/// As long as no references were received on `reference`, every hash is
+/// compared with the last `history` hashes, which detects repeated frames.
+/// With references it detects known images, e.g. for near-duplicate alerts.
+/// `distance` carries the smallest distance found.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct HashDistanceNode {
+    #[output]
+    pub distance: Output<u32>,
+
+    #[output]
+    pub duplicate: Output<bool>,
+
+    #[input]
+    pub input: Input<ImageHash>,
+
+    #[input]
+    pub reference: Input<ImageHash>,
+
+    pub config: HashDistanceNodeConfig,
+
+    #[serde(skip)]
+    references: Vec<ImageHash>,
+    #[serde(skip)]
+    recent: VecDeque<ImageHash>,
+}
+
+impl HashDistanceNode {
+    pub fn new(config: HashDistanceNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            distance: Output::new(change_observer),
+            duplicate: Output::new(change_observer),
+            input: Input::new(),
+            reference: Input::new(),
+            config,
+            references: Vec::new(),
+            recent: VecDeque::new(),
+        }
+    }
+}
+
+fn min_distance<'a>(hash: &ImageHash, candidates: impl Iterator<Item = &'a ImageHash>) -> Option<u32> {
+    candidates.filter_map(|c| c.distance(hash)).min()
+}
+
+impl Node for HashDistanceNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        while let Ok(hash) = self.reference.next() {
+            self.references.push(hash);
+        }
+
+        if let Ok(hash) = self.input.next() {
+            let distance = if self.references.is_empty() {
+                min_distance(&hash, self.recent.iter())
+            } else {
+                min_distance(&hash, self.references.iter())
+            };
+
+            if self.references.is_empty() && self.config.history > 0 {
+                if self.recent.len() == self.config.history {
+                    self.recent.pop_front();
+                }
+                self.recent.push_back(hash);
+            }
+
+            // The first hash has nothing to be compared with.
+            if let Some(distance) = distance {
+                self.distance.send(distance).map_err(|e| UpdateError::Other(e.into()))?;
+                self.duplicate.send(distance <= self.config.max_distance).map_err(|e| UpdateError::Other(e.into()))?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,874 @@
+use flowrs::{node::{Node, UpdateError, ChangeObserver}, connection::{Input, Output}};
+use flowrs::RuntimeConnectable;
+
+use std::path::PathBuf;
+
+use image::{DynamicImage, GrayImage, Luma, imageops::{self, FilterType}};
+
+use anyhow::anyhow;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::{ensure, ConfigError, Validate};
+use crate::types::Rect;
+
+/// Bilinearly interpolated intensity; coordinates outside the image are clamped.
+fn sample(img: &GrayImage, x: f64, y: f64) -> f64 {
+    let (w, h) = (img.width() as f64, img.height() as f64);
+    let x = x.clamp(0.0, w - 1.0);
+    let y = y.clamp(0.0, h - 1.0);
+    let (x0, y0) = (x.floor(), y.floor());
+    let (x1, y1) = ((x0 + 1.0).min(w - 1.0), (y0 + 1.0).min(h - 1.0));
+    let (fx, fy) = (x - x0, y - y0);
+    let p = |x: f64, y: f64| img.get_pixel(x as u32, y as u32)[0] as f64;
+    let top = p(x0, y0) * (1.0 - fx) + p(x1, y0) * fx;
+    let bottom = p(x0, y1) * (1.0 - fx) + p(x1, y1) * fx;
+    top * (1.0 - fy) + bottom * fy
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum EdgePolarity {
+    /// Dark to bright along the caliper direction.
+    Rising,
+    /// Bright to dark along the caliper direction.
+    Falling,
+    Any,
+}
+
+/// A measurement line from `start` to `end`, averaged over `half_width` pixels on either side.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Caliper {
+    pub name: String,
+    pub start: (f64, f64),
+    pub end: (f64, f64),
+    pub half_width: u32,
+    pub polarity: EdgePolarity,
+    /// Minimum gray-level step per pixel for an edge to be accepted.
+    pub min_contrast: f64,
+}
+
+/// A value in physical units with its standard uncertainty, if it could be estimated.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+pub struct Measured {
+    pub value: f64,
+    pub uncertainty: Option<f64>,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct EdgePoint {
+    /// Sub-pixel distance from the caliper start, in pixels.
+    pub offset: Measured,
+    /// Image position of the edge on the caliper center line.
+    pub point: (f64, f64),
+    /// Signed gray-level gradient at the edge.
+    pub strength: f64,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct CaliperMeasurement {
+    pub name: String,
+    pub edges: Vec<EdgePoint>,
+    /// Distance between the first and last edge in physical units, e.g. a width or diameter.
+    pub distance: Option<Measured>,
+}
+
+/// Gradient of the profile, lightly smoothed to suppress pixel noise.
+fn gradient(profile: &[f64]) -> Vec<f64> {
+    let n = profile.len();
+    let at = |i: isize| profile[i.clamp(0, n as isize - 1) as usize];
+    (0..n as isize)
+        .map(|i| {
+            let smooth = |j| (at(j - 1) + 2.0 * at(j) + at(j + 1)) / 4.0;
+            (smooth(i + 1) - smooth(i - 1)) / 2.0
+        })
+        .collect()
+}
+
+/// Sub-pixel location of the extremum at `i` from a parabola through its neighbours.
+fn refine_peak(g: &[f64], i: usize) -> f64 {
+    if i == 0 || i + 1 >= g.len() {
+        return i as f64;
+    }
+    let denom = g[i - 1] - 2.0 * g[i] + g[i + 1];
+    if denom.abs() < f64::EPSILON {
+        return i as f64;
+    }
+    i as f64 + (0.5 * (g[i - 1] - g[i + 1]) / denom).clamp(-0.5, 0.5)
+}
+
+fn polarity_matches(polarity: EdgePolarity, g: f64) -> bool {
+    match polarity {
+        EdgePolarity::Rising => g > 0.0,
+        EdgePolarity::Falling => g < 0.0,
+        EdgePolarity::Any => true,
+    }
+}
+
+/// Locates sub-pixel edges along a caliper.
+///
+/// Edges are detected on the profile averaged across the caliper width and then
+/// refined on every parallel profile; the spread of those positions gives the
+/// standard uncertainty of each edge.
+pub fn measure_caliper(img: &GrayImage, caliper: &Caliper, units_per_pixel: f64) -> CaliperMeasurement {
+    let (dx, dy) = (caliper.end.0 - caliper.start.0, caliper.end.1 - caliper.start.1);
+    let length = dx.hypot(dy);
+    let samples = length.floor() as usize + 1;
+    if length < 2.0 {
+        return CaliperMeasurement { name: caliper.name.clone(), edges: Vec::new(), distance: None };
+    }
+    let (ux, uy) = (dx / length, dy / length);
+    let (nx, ny) = (-uy, ux);
+
+    let hw = caliper.half_width as i64;
+    let profiles: Vec<Vec<f64>> = (-hw..=hw)
+        .map(|k| {
+            let (ox, oy) = (caliper.start.0 + nx * k as f64, caliper.start.1 + ny * k as f64);
+            (0..samples).map(|s| sample(img, ox + ux * s as f64, oy + uy * s as f64)).collect()
+        })
+        .collect();
+    let mean: Vec<f64> = (0..samples)
+        .map(|s| profiles.iter().map(|p| p[s]).sum::<f64>() / profiles.len() as f64)
+        .collect();
+    let g = gradient(&mean);
+    let gradients: Vec<Vec<f64>> = profiles.iter().map(|p| gradient(p)).collect();
+
+    let mut edges = Vec::new();
+    for i in 1..samples.saturating_sub(1) {
+        let is_peak = g[i].abs() >= g[i - 1].abs() && g[i].abs() > g[i + 1].abs();
+        if !is_peak || g[i].abs() < caliper.min_contrast || !polarity_matches(caliper.polarity, g[i]) {
+            continue;
+        }
+
+        let positions: Vec<f64> = gradients
+            .iter()
+            .map(|pg| {
+                // The strongest same-signed response within a pixel of the averaged edge.
+                let j = (i - 1..=i + 1)
+                    .max_by(|&a, &b| (pg[a] * g[i].signum()).total_cmp(&(pg[b] * g[i].signum())))
+                    .unwrap_or(i);
+                refine_peak(pg, j)
+            })
+            .collect();
+        let n = positions.len() as f64;
+        let offset = positions.iter().sum::<f64>() / n;
+        let uncertainty = (positions.len() > 1).then(|| {
+            let var = positions.iter().map(|p| (p - offset).powi(2)).sum::<f64>() / (n - 1.0);
+            (var / n).sqrt()
+        });
+
+        edges.push(EdgePoint {
+            offset: Measured { value: offset, uncertainty },
+            point: (caliper.start.0 + ux * offset, caliper.start.1 + uy * offset),
+            strength: g[i],
+        });
+    }
+
+    let distance = match (edges.first(), edges.last()) {
+        (Some(a), Some(b)) if edges.len() > 1 => Some(Measured {
+            value: (b.offset.value - a.offset.value) * units_per_pixel,
+            uncertainty: a.offset.uncertainty.zip(b.offset.uncertainty).map(|(ua, ub)| ua.hypot(ub) * units_per_pixel),
+        }),
+        _ => None,
+    };
+    CaliperMeasurement { name: caliper.name.clone(), edges, distance }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct EdgeMeasureNodeConfig {
+    pub calipers: Vec<Caliper>,
+    /// Physical size of one pixel, e.g. millimeters per pixel.
+    pub units_per_pixel: f64,
+}
+
+impl Default for EdgeMeasureNodeConfig {
+    fn default() -> Self {
+        Self { calipers: Vec::new(), units_per_pixel: 1.0 }
+    }
+}
+
+impl Validate for EdgeMeasureNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        ensure(self.units_per_pixel > 0.0, "units_per_pixel", "must be positive")
+    }
+}
+
+config_builder!(EdgeMeasureNodeConfig for EdgeMeasureNode { calipers: Vec<Caliper>, units_per_pixel: f64 });
+
+/// Measures sub-pixel edge positions and distances along configured calipers.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct EdgeMeasureNode {
+    #[output]
+    pub output: Output<Vec<CaliperMeasurement>>,
+
+    #[input]
+    pub input: Input<DynamicImage>,
+
+    pub config: EdgeMeasureNodeConfig,
+}
+
+impl EdgeMeasureNode {
+    pub fn new(config: EdgeMeasureNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            input: Input::new(),
+            config,
+        }
+    }
+}
+
+impl Node for EdgeMeasureNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        if let Ok(img) = self.input.next() {
+            let gray = img.to_luma8();
+            let measurements = self
+                .config
+                .calipers
+                .iter()
+                .map(|c| measure_caliper(&gray, c, self.config.units_per_pixel))
+                .collect();
+            self.output.send(measurements).map_err(|e| UpdateError::Other(e.into()))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum DefectReference {
+    /// Compare against a defect-free image of the same part, pixel by pixel.
+    Golden { path: PathBuf, tolerance: u8 },
+    /// Learn the texture statistics of the first `training_frames` inputs and flag
+    /// tiles deviating by more than `max_sigma` standard deviations.
+    Learned { training_frames: usize, max_sigma: f64 },
+}
+
+impl Default for DefectReference {
+    fn default() -> Self {
+        DefectReference::Learned { training_frames: 20, max_sigma: 3.0 }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct SurfaceDefectNodeConfig {
+    pub reference: DefectReference,
+    /// Side length of the tiles texture statistics are computed on.
+    pub tile_size: u32,
+    /// A part passes while its defect area stays at or below this many pixels.
+    pub max_defect_pixels: u64,
+}
+
+impl Default for SurfaceDefectNodeConfig {
+    fn default() -> Self {
+        Self { reference: DefectReference::default(), tile_size: 32, max_defect_pixels: 0 }
+    }
+}
+
+impl Validate for SurfaceDefectNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        ensure(self.tile_size > 0, "tile_size", "must be positive")
+    }
+}
+
+config_builder!(SurfaceDefectNodeConfig for SurfaceDefectNode {
+    reference: DefectReference,
+    tile_size: u32,
+    max_defect_pixels: u64,
+});
+
+/// Running mean and variance of per-tile (mean, standard deviation) features.
+#[derive(Clone, Debug, Default)]
+pub struct TextureModel {
+    count: u64,
+    mean: [f64; 2],
+    m2: [f64; 2],
+}
+
+impl TextureModel {
+    pub fn learn(&mut self, img: &GrayImage, tile_size: u32) {
+        for (_, _, features) in tile_features(img, tile_size) {
+            self.count += 1;
+            for (k, f) in features.iter().enumerate() {
+                let delta = f - self.mean[k];
+                self.mean[k] += delta / self.count as f64;
+                self.m2[k] += delta * (f - self.mean[k]);
+            }
+        }
+    }
+
+    /// Largest z-score of a tile's features against the learned distribution.
+    pub fn deviation(&self, features: [f64; 2]) -> f64 {
+        (0..2)
+            .map(|k| {
+                let std = (self.m2[k] / self.count.saturating_sub(1).max(1) as f64).sqrt().max(1e-3);
+                (features[k] - self.mean[k]).abs() / std
+            })
+            .fold(0.0, f64::max)
+    }
+}
+
+/// Mean and standard deviation of every tile, with its top-left corner.
+fn tile_features(img: &GrayImage, tile_size: u32) -> Vec<(u32, u32, [f64; 2])> {
+    let t = tile_size.max(1);
+    let mut tiles = Vec::new();
+    for ty in (0..img.height()).step_by(t as usize) {
+        for tx in (0..img.width()).step_by(t as usize) {
+            let view = imageops::crop_imm(img, tx, ty, t, t).to_image();
+            let n = view.as_raw().len().max(1) as f64;
+            let mean = view.as_raw().iter().map(|&v| v as f64).sum::<f64>() / n;
+            let var = view.as_raw().iter().map(|&v| (v as f64 - mean).powi(2)).sum::<f64>() / n;
+            tiles.push((tx, ty, [mean, var.sqrt()]));
+        }
+    }
+    tiles
+}
+
+/// Defect mask (255 = defect) from the pixel-wise difference to a golden image.
+pub fn golden_defect_mask(img: &GrayImage, golden: &GrayImage, tolerance: u8) -> GrayImage {
+    let img = imageops::blur(img, 1.0);
+    let golden = imageops::blur(golden, 1.0);
+    GrayImage::from_fn(img.width(), img.height(), |x, y| {
+        let diff = img.get_pixel(x, y)[0].abs_diff(golden.get_pixel(x, y)[0]);
+        Luma([if diff > tolerance { 255 } else { 0 }])
+    })
+}
+
+/// Defect mask marking every tile whose texture deviates from the model.
+pub fn texture_defect_mask(img: &GrayImage, model: &TextureModel, tile_size: u32, max_sigma: f64) -> GrayImage {
+    let mut mask = GrayImage::new(img.width(), img.height());
+    let t = tile_size.max(1);
+    for (tx, ty, features) in tile_features(img, t) {
+        if model.deviation(features) > max_sigma {
+            for y in ty..(ty + t).min(img.height()) {
+                for x in tx..(tx + t).min(img.width()) {
+                    mask.put_pixel(x, y, Luma([255]));
+                }
+            }
+        }
+    }
+    mask
+}
+
+/// Highlights surface defects on inspected parts and emits pass/fail per part.
+///
+/// Nothing is emitted while a learned reference is still being trained.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct SurfaceDefectNode {
+    #[output]
+    pub mask: Output<DynamicImage>,
+
+    #[output]
+    pub passed: Output<bool>,
+
+    #[input]
+    pub input: Input<DynamicImage>,
+
+    pub config: SurfaceDefectNodeConfig,
+
+    #[serde(skip)]
+    golden: Option<GrayImage>,
+    #[serde(skip)]
+    model: TextureModel,
+    #[serde(skip)]
+    trained_frames: usize,
+}
+
+impl SurfaceDefectNode {
+    pub fn new(config: SurfaceDefectNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            mask: Output::new(change_observer),
+            passed: Output::new(change_observer),
+            input: Input::new(),
+            config,
+            golden: None,
+            model: TextureModel::default(),
+            trained_frames: 0,
+        }
+    }
+}
+
+impl Node for SurfaceDefectNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        if let Ok(img) = self.input.next() {
+            let gray = img.to_luma8();
+            let mask = match &self.config.reference {
+                DefectReference::Golden { path, tolerance } => {
+                    if self.golden.is_none() {
+                        let golden = image::open(path).map_err(|e| UpdateError::Other(e.into()))?.into_luma8();
+                        self.golden = Some(golden);
+                    }
+                    let golden = self.golden.as_ref().expect("loaded above");
+                    if golden.dimensions() == gray.dimensions() {
+                        golden_defect_mask(&gray, golden, *tolerance)
+                    } else {
+                        let resized = imageops::resize(golden, gray.width(), gray.height(), FilterType::Triangle);
+                        golden_defect_mask(&gray, &resized, *tolerance)
+                    }
+                }
+                DefectReference::Learned { training_frames, max_sigma } => {
+                    if self.trained_frames < *training_frames {
+                        self.model.learn(&gray, self.config.tile_size);
+                        self.trained_frames += 1;
+                        return Ok(());
+                    }
+                    texture_defect_mask(&gray, &self.model, self.config.tile_size, *max_sigma)
+                }
+            };
+
+            let defect_pixels = mask.as_raw().iter().filter(|&&v| v > 0).count() as u64;
+            self.passed.send(defect_pixels <= self.config.max_defect_pixels).map_err(|e| UpdateError::Other(e.into()))?;
+            self.mask.send(DynamicImage::ImageLuma8(mask)).map_err(|e| UpdateError::Other(e.into()))?;
+        }
+        Ok(())
+    }
+}
+
+/// Zero-mean normalized cross-correlation of two equally sized patches, in `-1.0..=1.0`.
+pub fn zncc(a: &GrayImage, b: &GrayImage) -> f64 {
+    let n = a.as_raw().len().min(b.as_raw().len()).max(1) as f64;
+    let mean = |p: &GrayImage| p.as_raw().iter().map(|&v| v as f64).sum::<f64>() / n;
+    let (ma, mb) = (mean(a), mean(b));
+    let (mut cov, mut va, mut vb) = (0.0, 0.0, 0.0);
+    for (&x, &y) in a.as_raw().iter().zip(b.as_raw()) {
+        let (dx, dy) = (x as f64 - ma, y as f64 - mb);
+        cov += dx * dy;
+        va += dx * dx;
+        vb += dy * dy;
+    }
+    if va < f64::EPSILON || vb < f64::EPSILON {
+        // Two flat patches only correlate if they share the same level.
+        return if (ma - mb).abs() < 8.0 && va < f64::EPSILON && vb < f64::EPSILON { 1.0 } else { 0.0 };
+    }
+    cov / (va * vb).sqrt()
+}
+
+fn crop(img: &GrayImage, r: &Rect) -> Option<GrayImage> {
+    let r = r.clamp_to(img.width(), img.height())?;
+    Some(imageops::crop_imm(img, r.x, r.y, r.width, r.height).to_image())
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Fiducial {
+    pub name: String,
+    /// Location of the fiducial on the golden board; the patch there is the template.
+    pub region: Rect,
+    /// Maximum expected misplacement of the board, in pixels.
+    pub search_radius: u32,
+    pub min_score: f64,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(tag = "type")]
+pub enum InspectionCheck {
+    /// The region must resemble the golden board, i.e. the component is mounted.
+    Presence { name: String, region: Rect, min_score: f64 },
+    /// The region must match the golden board better than its 180° rotation,
+    /// i.e. the polarity mark sits on the expected side.
+    Polarity { name: String, region: Rect, min_margin: f64 },
+}
+
+/// PCB inspection plan, usually stored as JSON next to the golden board image.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct InspectionPlan {
+    pub golden_image: PathBuf,
+    pub fiducials: Vec<Fiducial>,
+    pub checks: Vec<InspectionCheck>,
+}
+
+impl InspectionPlan {
+    pub fn load(path: &std::path::Path) -> Result<Self, anyhow::Error> {
+        Ok(serde_json::from_str(&std::fs::read_to_string(path)?)?)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct FiducialResult {
+    pub name: String,
+    pub found: bool,
+    pub offset: (i64, i64),
+    pub score: f64,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct CheckResult {
+    pub name: String,
+    pub passed: bool,
+    pub score: f64,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct InspectionReport {
+    pub passed: bool,
+    /// Board displacement relative to the golden board, from the located fiducials.
+    pub offset: (i64, i64),
+    pub fiducials: Vec<FiducialResult>,
+    pub checks: Vec<CheckResult>,
+}
+
+fn locate_fiducial(board: &GrayImage, golden: &GrayImage, fiducial: &Fiducial) -> FiducialResult {
+    let mut result = FiducialResult { name: fiducial.name.clone(), found: false, offset: (0, 0), score: 0.0 };
+    let Some(template) = crop(golden, &fiducial.region) else { return result };
+    let r = fiducial.search_radius as i64;
+    for dy in -r..=r {
+        for dx in -r..=r {
+            let candidate = fiducial.region.translated(dx, dy);
+            let Some(patch) = crop(board, &candidate).filter(|p| p.dimensions() == template.dimensions()) else {
+                continue;
+            };
+            let score = zncc(&patch, &template);
+            if score > result.score {
+                result.score = score;
+                result.offset = (candidate.x as i64 - fiducial.region.x as i64, candidate.y as i64 - fiducial.region.y as i64);
+            }
+        }
+    }
+    result.found = result.score >= fiducial.min_score;
+    result
+}
+
+/// Aligns a board to the golden board by its fiducials, then runs every check of the plan.
+///
+/// Alignment compensates translation only; boards are expected to be fixtured
+/// closely enough that rotation stays negligible.
+pub fn inspect_board(board: &GrayImage, golden: &GrayImage, plan: &InspectionPlan) -> InspectionReport {
+    let fiducials: Vec<FiducialResult> = plan.fiducials.iter().map(|f| locate_fiducial(board, golden, f)).collect();
+    let found: Vec<&FiducialResult> = fiducials.iter().filter(|f| f.found).collect();
+    let offset = if found.is_empty() {
+        (0, 0)
+    } else {
+        let n = found.len() as i64;
+        (found.iter().map(|f| f.offset.0).sum::<i64>() / n, found.iter().map(|f| f.offset.1).sum::<i64>() / n)
+    };
+
+    let checks: Vec<CheckResult> = plan
+        .checks
+        .iter()
+        .map(|check| {
+            let (name, region) = match check {
+                InspectionCheck::Presence { name, region, .. } | InspectionCheck::Polarity { name, region, .. } => (name, region),
+            };
+            let patches = crop(golden, region).zip(crop(board, &region.translated(offset.0, offset.1)));
+            let (passed, score) = match (check, patches) {
+                (_, Some((g, b))) if g.dimensions() != b.dimensions() => (false, 0.0),
+                (InspectionCheck::Presence { min_score, .. }, Some((g, b))) => {
+                    let score = zncc(&b, &g);
+                    (score >= *min_score, score)
+                }
+                (InspectionCheck::Polarity { min_margin, .. }, Some((g, b))) => {
+                    let margin = zncc(&b, &g) - zncc(&b, &imageops::rotate180(&g));
+                    (margin >= *min_margin, margin)
+                }
+                (_, None) => (false, 0.0),
+            };
+            CheckResult { name: name.clone(), passed, score }
+        })
+        .collect();
+
+    InspectionReport {
+        passed: fiducials.iter().all(|f| f.found) && checks.iter().all(|c| c.passed),
+        offset,
+        fiducials,
+        checks,
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct PcbInspectionNodeConfig {
+    /// JSON file containing an [`InspectionPlan`].
+    pub plan_path: PathBuf,
+}
+
+impl Validate for PcbInspectionNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        ensure(!self.plan_path.as_os_str().is_empty(), "plan_path", "must be set")
+    }
+}
+
+config_builder!(PcbInspectionNodeConfig for PcbInspectionNode { plan_path: PathBuf });
+
+/// Automated optical inspection of PCBs against a golden board.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct PcbInspectionNode {
+    #[output]
+    pub output: Output<InspectionReport>,
+
+    #[input]
+    pub input: Input<DynamicImage>,
+
+    pub config: PcbInspectionNodeConfig,
+
+    #[serde(skip)]
+    plan: Option<(InspectionPlan, GrayImage)>,
+}
+
+impl PcbInspectionNode {
+    pub fn new(config: PcbInspectionNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            input: Input::new(),
+            config,
+            plan: None,
+        }
+    }
+
+    fn load_plan(&self) -> Result<(InspectionPlan, GrayImage), anyhow::Error> {
+        let plan = InspectionPlan::load(&self.config.plan_path)?;
+        // Relative golden image paths are resolved next to the plan file.
+        let golden_path = match self.config.plan_path.parent() {
+            Some(dir) if plan.golden_image.is_relative() => dir.join(&plan.golden_image),
+            _ => plan.golden_image.clone(),
+        };
+        let golden = image::open(&golden_path)
+            .map_err(|e| anyhow!("Failed to open golden board {}: {}", golden_path.display(), e))?
+            .into_luma8();
+        Ok((plan, golden))
+    }
+}
+
+impl Node for PcbInspectionNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        if let Ok(img) = self.input.next() {
+            if self.plan.is_none() {
+                self.plan = Some(self.load_plan().map_err(UpdateError::Other)?);
+            }
+            let (plan, golden) = self.plan.as_ref().expect("loaded above");
+            let report = inspect_board(&img.to_luma8(), golden, plan);
+            self.output.send(report).map_err(|e| UpdateError::Other(e.into()))?;
+        }
+        Ok(())
+    }
+}
+
+/// ISO-style symbol grade, from `A` (best) to `F` (fail).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+pub enum Grade {
+    F,
+    D,
+    C,
+    B,
+    A,
+}
+
+impl Grade {
+    pub fn value(&self) -> f32 {
+        *self as u8 as f32
+    }
+
+    /// Letter grade of an averaged numeric grade (ISO 15416 rounds down to the band).
+    pub fn from_value(v: f32) -> Self {
+        match v {
+            v if v >= 3.5 => Grade::A,
+            v if v >= 2.5 => Grade::B,
+            v if v >= 1.5 => Grade::C,
+            v if v >= 0.5 => Grade::D,
+            _ => Grade::F,
+        }
+    }
+
+    /// Grade of a parameter whose band limits are given for A..D, best first.
+    fn banded(value: f32, limits: [f32; 4], higher_is_better: bool) -> Self {
+        let grades = [Grade::A, Grade::B, Grade::C, Grade::D];
+        grades
+            .iter()
+            .zip(limits)
+            .find(|(_, l)| if higher_is_better { value >= *l } else { value <= *l })
+            .map_or(Grade::F, |(g, _)| *g)
+    }
+}
+
+/// Scan reflectance profile parameters of a single scan line, reflectances in percent.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct ScanGrade {
+    pub r_min: f32,
+    pub r_max: f32,
+    pub symbol_contrast: f32,
+    pub min_edge_contrast: f32,
+    pub modulation: f32,
+    pub defects: f32,
+    pub elements: usize,
+    pub grade: Grade,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct BarcodeGradeReport {
+    pub grade: Grade,
+    /// Mean of the scan grades, `0.0` (F) to `4.0` (A).
+    pub numeric_grade: f32,
+    pub scans: Vec<ScanGrade>,
+}
+
+/// Grades a scan reflectance profile following the ISO/IEC 15416 parameters.
+///
+/// Decodability and quiet zones depend on the symbology and are not assessed,
+/// so this grade is an upper bound on the formal symbol grade.
+pub fn grade_scan(profile: &[f32], min_elements: usize) -> ScanGrade {
+    let n = profile.len();
+    let smooth: Vec<f32> = (0..n)
+        .map(|i| (profile[i.saturating_sub(1)] + 2.0 * profile[i] + profile[(i + 1).min(n - 1)]) / 4.0)
+        .collect();
+    let r_min = smooth.iter().copied().fold(f32::MAX, f32::min);
+    let r_max = smooth.iter().copied().fold(f32::MIN, f32::max);
+    let sc = r_max - r_min;
+    let threshold = r_min + sc / 2.0;
+
+    // Elements are runs on either side of the global threshold: spaces above, bars below.
+    let mut elements: Vec<(usize, usize, bool)> = Vec::new();
+    for (i, &r) in smooth.iter().enumerate() {
+        let space = r >= threshold;
+        match elements.last_mut() {
+            Some((_, end, s)) if *s == space => *end = i + 1,
+            _ => elements.push((i, i + 1, space)),
+        }
+    }
+
+    let reflectance = |&(start, end, space): &(usize, usize, bool)| {
+        let values = smooth[start..end].iter().copied();
+        if space { values.fold(f32::MIN, f32::max) } else { values.fold(f32::MAX, f32::min) }
+    };
+    let levels: Vec<f32> = elements.iter().map(reflectance).collect();
+    let min_edge_contrast = levels.windows(2).map(|w| (w[0] - w[1]).abs()).fold(sc, f32::min);
+
+    // Element reflectance non-uniformity: interior valleys in spaces, interior peaks in bars.
+    let ern = elements
+        .iter()
+        .zip(&levels)
+        .map(|(&(start, end, space), &level)| {
+            (start + 1..end.saturating_sub(1))
+                .filter_map(|i| {
+                    let (l, r) = (smooth[i - 1], smooth[i + 1]);
+                    if space && smooth[i] < l && smooth[i] <= r {
+                        Some(level - smooth[i])
+                    } else if !space && smooth[i] > l && smooth[i] >= r {
+                        Some(smooth[i] - level)
+                    } else {
+                        None
+                    }
+                })
+                .fold(0.0, f32::max)
+        })
+        .fold(0.0, f32::max);
+
+    let (modulation, defects) = if sc > 0.0 { (min_edge_contrast / sc, ern / sc) } else { (0.0, 1.0) };
+    // Quiet zones form the outer spaces, so only what lies between them counts as symbol elements.
+    let symbol_elements = elements.len().saturating_sub(2);
+    let grade = if symbol_elements < min_elements {
+        Grade::F
+    } else {
+        [
+            Grade::banded(sc, [70.0, 55.0, 40.0, 20.0], true),
+            if r_min <= 0.5 * r_max { Grade::A } else { Grade::F },
+            if min_edge_contrast >= 15.0 { Grade::A } else { Grade::F },
+            Grade::banded(modulation, [0.70, 0.60, 0.50, 0.40], true),
+            Grade::banded(defects, [0.15, 0.20, 0.25, 0.30], false),
+        ]
+        .into_iter()
+        .min()
+        .unwrap_or(Grade::F)
+    };
+
+    ScanGrade {
+        r_min,
+        r_max,
+        symbol_contrast: sc,
+        min_edge_contrast,
+        modulation,
+        defects,
+        elements: symbol_elements,
+        grade,
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct BarcodeGradeNodeConfig {
+    /// Area containing a horizontally oriented barcode with its quiet zones; the whole frame if unset.
+    pub region: Option<Rect>,
+    /// Number of scan lines spread over the bar height (ISO uses ten).
+    pub scan_lines: usize,
+    /// Scans with fewer elements than this count as unreadable.
+    pub min_elements: usize,
+}
+
+impl Default for BarcodeGradeNodeConfig {
+    fn default() -> Self {
+        Self { region: None, scan_lines: 10, min_elements: 5 }
+    }
+}
+
+impl Validate for BarcodeGradeNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        ensure(self.scan_lines > 0, "scan_lines", "must be positive")
+    }
+}
+
+config_builder!(BarcodeGradeNodeConfig for BarcodeGradeNode {
+    region: Option<Rect>,
+    scan_lines: usize,
+    min_elements: usize,
+});
+
+/// Grades the print quality of linear barcodes, for label verification.
+///
+/// Reflectance is approximated by luma, so absolute grades require a
+/// calibrated, evenly lit capture setup.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct BarcodeGradeNode {
+    #[output]
+    pub output: Output<BarcodeGradeReport>,
+
+    #[input]
+    pub input: Input<DynamicImage>,
+
+    pub config: BarcodeGradeNodeConfig,
+}
+
+impl BarcodeGradeNode {
+    pub fn new(config: BarcodeGradeNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            input: Input::new(),
+            config,
+        }
+    }
+}
+
+/// Grades evenly spaced horizontal scans through `gray`, skipping the outer tenth of the height.
+pub fn grade_barcode(gray: &GrayImage, scan_lines: usize, min_elements: usize) -> BarcodeGradeReport {
+    let (w, h) = gray.dimensions();
+    let lines = scan_lines.max(1);
+    let scans: Vec<ScanGrade> = (0..lines)
+        .map(|i| {
+            let y = (h as f32 * (0.1 + 0.8 * (i as f32 + 0.5) / lines as f32)) as u32;
+            let profile: Vec<f32> = (0..w).map(|x| gray.get_pixel(x, y.min(h - 1))[0] as f32 * 100.0 / 255.0).collect();
+            grade_scan(&profile, min_elements)
+        })
+        .collect();
+    let numeric_grade = scans.iter().map(|s| s.grade.value()).sum::<f32>() / scans.len() as f32;
+    BarcodeGradeReport { grade: Grade::from_value(numeric_grade), numeric_grade, scans }
+}
+
+impl Node for BarcodeGradeNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        if let Ok(img) = self.input.next() {
+            let gray = img.to_luma8();
+            let gray = match self.config.region.and_then(|r| r.clamp_to(gray.width(), gray.height())) {
+                Some(r) => imageops::crop_imm(&gray, r.x, r.y, r.width, r.height).to_image(),
+                None if self.config.region.is_some() => {
+                    return Err(UpdateError::Other(anyhow!("Barcode region lies outside the frame.")));
+                }
+                None => gray,
+            };
+            if gray.width() < 3 || gray.height() == 0 {
+                return Err(UpdateError::Other(anyhow!("Barcode region is too small to grade.")));
+            }
+            let report = grade_barcode(&gray, self.config.scan_lines, self.config.min_elements);
+            self.output.send(report).map_err(|e| UpdateError::Other(e.into()))?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,626 @@
+use flowrs::{node::{Node, UpdateError, ChangeObserver}, connection::{Input, Output}};
+use flowrs::RuntimeConnectable;
+
+use std::io::Cursor;
+use image::{DynamicImage, GenericImageView, GrayImage, io::Reader as ImageReader};
+use image::imageops::FilterType;
+use imageproc::gradients::sobel_gradients;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::{apply_configs, ensure, ConfigError, Validate};
+use crate::error::Error;
+use crate::transform::{decode_image_scaled, encode_image, EncodeFormat};
+use crate::types::Rect;
+
+/// Longest side of the copy that crop windows are scored on.
+const ENERGY_SIZE: u32 = 256;
+
+/// EXIF orientation (1 to 8) of a JPEG, if it carries one.
+pub fn exif_orientation(data: &[u8]) -> Option<u8> {
+    if !data.starts_with(&[0xff, 0xd8]) {
+        return None;
+    }
+    let mut pos = 2;
+    while data.get(pos) == Some(&0xff) {
+        let marker = *data.get(pos + 1)?;
+        // The metadata segments all come before the start of scan.
+        if marker == 0xda {
+            return None;
+        }
+        let len = u16::from_be_bytes([*data.get(pos + 2)?, *data.get(pos + 3)?]) as usize;
+        let segment = data.get(pos + 4..pos + 2 + len)?;
+        if marker == 0xe1 && segment.starts_with(b"Exif\0\0") {
+            return tiff_orientation(&segment[6..]);
+        }
+        pos += 2 + len;
+    }
+    None
+}
+
+/// Reads the orientation tag from the first IFD of an EXIF TIFF structure.
+fn tiff_orientation(tiff: &[u8]) -> Option<u8> {
+    let big_endian = match tiff.get(0..2)? {
+        b"MM" => true,
+        b"II" => false,
+        _ => return None,
+    };
+    let u16_at = |at: usize| {
+        let b = tiff.get(at..at + 2)?;
+        Some(if big_endian { u16::from_be_bytes([b[0], b[1]]) } else { u16::from_le_bytes([b[0], b[1]]) })
+    };
+    let u32_at = |at: usize| {
+        let b = tiff.get(at..at + 4)?;
+        Some(if big_endian { u32::from_be_bytes([b[0], b[1], b[2], b[3]]) } else { u32::from_le_bytes([b[0], b[1], b[2], b[3]]) })
+    };
+
+    let ifd = u32_at(4)? as usize;
+    for i in 0..u16_at(ifd)? as usize {
+        let entry = ifd + 2 + i * 12;
+        if u16_at(entry)? == 0x0112 {
+            let orientation = u16_at(entry + 8)?;
+            return (1..=8).contains(&orientation).then_some(orientation as u8);
+        }
+    }
+    None
+}
+
+/// Turns an image stored with the given EXIF orientation upright.
+pub fn apply_orientation(img: DynamicImage, orientation: u8) -> DynamicImage {
+    match orientation {
+        2 => img.fliph(),
+        3 => img.rotate180(),
+        4 => img.flipv(),
+        5 => img.rotate90().fliph(),
+        6 => img.rotate90(),
+        7 => img.rotate270().fliph(),
+        8 => img.rotate270(),
+        _ => img,
+    }
+}
+
+/// What makes a region worth keeping when cropping.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum Saliency {
+    /// Sobel gradient magnitude; fast, favors sharp detail.
+    #[default]
+    Edges,
+    /// Gray value entropy of 8x8 blocks; favors texture over single strong edges.
+    Entropy,
+}
+
+/// The largest window of the given aspect ratio (width / height) with the most edge energy,
+/// a cheap stand-in for saliency. See [`salient_crop`].
+pub fn smart_crop(img: &DynamicImage, aspect: f32) -> Rect {
+    salient_crop(img, aspect, Saliency::Edges, &[], 0.0)
+}
+
+/// The largest window of the given aspect ratio (width / height) with the most saliency.
+///
+/// Pixels inside `regions`, e.g. detected faces, add `region_weight` times the peak saliency
+/// of the image. Only the position along the cropped axis is searched; featureless images
+/// are cropped in the center.
+pub fn salient_crop(img: &DynamicImage, aspect: f32, saliency: Saliency, regions: &[Rect], region_weight: f32) -> Rect {
+    let (width, height) = img.dimensions();
+    let horizontal = width as f32 / height as f32 > aspect;
+    let (crop_width, crop_height) = if horizontal {
+        (((height as f32 * aspect).round() as u32).clamp(1, width), height)
+    } else {
+        (width, ((width as f32 / aspect).round() as u32).clamp(1, height))
+    };
+    if (crop_width, crop_height) == (width, height) {
+        return Rect::new(0, 0, width, height);
+    }
+
+    let scale = (ENERGY_SIZE as f32 / width.max(height) as f32).min(1.0);
+    let small = img.resize_exact(
+        ((width as f32 * scale).round() as u32).max(1),
+        ((height as f32 * scale).round() as u32).max(1),
+        FilterType::Triangle,
+    ).to_luma8();
+    let (small_width, small_height) = small.dimensions();
+    let mut energy = saliency_map(&small, saliency);
+
+    let peak = energy.iter().copied().fold(0.0, f32::max).max(1.0);
+    for r in regions.iter().filter_map(|r| r.clamp_to(width, height)) {
+        let x0 = (r.x as f32 * scale) as u32;
+        let y0 = (r.y as f32 * scale) as u32;
+        let x1 = (((r.x + r.width) as f32 * scale).ceil() as u32).min(small_width);
+        let y1 = (((r.y + r.height) as f32 * scale).ceil() as u32).min(small_height);
+        for y in y0..y1 {
+            for x in x0..x1 {
+                energy[(y * small_width + x) as usize] += region_weight * peak;
+            }
+        }
+    }
+
+    let mut profile = vec![0.0f64; if horizontal { small_width } else { small_height } as usize];
+    for (i, e) in energy.iter().enumerate() {
+        let (x, y) = (i as u32 % small_width, i as u32 / small_width);
+        profile[if horizontal { x } else { y } as usize] += *e as f64;
+    }
+
+    let (full, crop) = if horizontal { (width, crop_width) } else { (height, crop_height) };
+    let window = ((crop as f32 * scale).round() as usize).clamp(1, profile.len());
+    let mut prefix = vec![0.0f64; profile.len() + 1];
+    for (i, e) in profile.iter().enumerate() {
+        prefix[i + 1] = prefix[i] + e;
+    }
+    let center = (full - crop) as f32 / 2.0;
+    let start = (0..=profile.len() - window)
+        .max_by(|&a, &b| {
+            let score = |s: usize| prefix[s + window] - prefix[s];
+            let off_center = |s: usize| (s as f32 / scale - center).abs();
+            score(a).total_cmp(&score(b)).then_with(|| off_center(b).total_cmp(&off_center(a)))
+        })
+        .unwrap_or(0);
+
+    let offset = ((start as f32 / scale).round() as u32).min(full - crop);
+    if horizontal {
+        Rect::new(offset, 0, crop_width, crop_height)
+    } else {
+        Rect::new(0, offset, crop_width, crop_height)
+    }
+}
+
+/// Per-pixel saliency of a gray image, row-major.
+fn saliency_map(gray: &GrayImage, saliency: Saliency) -> Vec<f32> {
+    match saliency {
+        Saliency::Edges => sobel_gradients(gray).pixels().map(|p| p[0] as f32).collect(),
+        Saliency::Entropy => {
+            const BLOCK: u32 = 8;
+            let (width, height) = gray.dimensions();
+            let mut map = vec![0.0; (width * height) as usize];
+            for by in (0..height).step_by(BLOCK as usize) {
+                for bx in (0..width).step_by(BLOCK as usize) {
+                    let (x1, y1) = ((bx + BLOCK).min(width), (by + BLOCK).min(height));
+                    let mut histogram = [0u32; 16];
+                    for y in by..y1 {
+                        for x in bx..x1 {
+                            histogram[(gray.get_pixel(x, y)[0] >> 4) as usize] += 1;
+                        }
+                    }
+                    let n = ((x1 - bx) * (y1 - by)) as f32;
+                    let entropy: f32 = histogram.iter().filter(|&&c| c > 0).map(|&c| {
+                        let p = c as f32 / n;
+                        -p * p.log2()
+                    }).sum();
+                    for y in by..y1 {
+                        for x in bx..x1 {
+                            map[(y * width + x) as usize] = entropy;
+                        }
+                    }
+                }
+            }
+            map
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct SmartCropNodeConfig {
+    /// Width divided by height of the crop.
+    pub aspect_ratio: f32,
+    pub saliency: Saliency,
+    /// Weight of received regions relative to the peak saliency of the frame.
+    pub region_weight: f32,
+}
+
+impl Default for SmartCropNodeConfig {
+    fn default() -> Self {
+        Self { aspect_ratio: 1.0, saliency: Saliency::Edges, region_weight: 1.0 }
+    }
+}
+
+impl Validate for SmartCropNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        ensure(self.aspect_ratio.is_finite() && self.aspect_ratio > 0.0, "aspect_ratio", "must be positive")?;
+        ensure(self.region_weight >= 0.0, "region_weight", "must not be negative")
+    }
+}
+
+config_builder!(SmartCropNodeConfig for SmartCropNode { aspect_ratio: f32, saliency: Saliency, region_weight: f32 });
+
+/// Crops each frame to the most salient window of the configured aspect ratio.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct SmartCropNode {
+    #[output]
+    pub output: Output<DynamicImage>,
+
+    /// The chosen window in input coordinates.
+    #[output]
+    pub rect: Output<Rect>,
+
+    #[input]
+    pub input: Input<DynamicImage>,
+
+    /// Faces or other subjects to keep, used for all following frames.
+    #[input]
+    pub regions: Input<Vec<Rect>>,
+
+    /// Replaces the config for all following frames, e.g. from a [`crate::config::ConfigFileWatcherNode`].
+    #[input]
+    pub reconfigure: Input<SmartCropNodeConfig>,
+
+    pub config: SmartCropNodeConfig,
+
+    #[serde(skip)]
+    subjects: Vec<Rect>,
+}
+
+impl SmartCropNode {
+    pub fn new(config: SmartCropNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            rect: Output::new(change_observer),
+            input: Input::new(),
+            regions: Input::new(),
+            reconfigure: Input::new(),
+            config,
+            subjects: Vec::new(),
+        }
+    }
+}
+
+impl Node for SmartCropNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        apply_configs(&mut self.reconfigure, &mut self.config).map_err(|e| UpdateError::Other(e.into()))?;
+
+        while let Ok(regions) = self.regions.next() {
+            self.subjects = regions;
+        }
+
+        if let Ok(img) = self.input.next() {
+            frame_span!("smart_crop");
+            let r = salient_crop(&img, self.config.aspect_ratio, self.config.saliency, &self.subjects, self.config.region_weight);
+            self.output.send(img.crop_imm(r.x, r.y, r.width, r.height)).map_err(|e| UpdateError::Other(e.into()))?;
+            self.rect.send(r).map_err(|e| UpdateError::Other(e.into()))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct ThumbnailSize {
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum ThumbnailFit {
+    /// Fit inside the size, keeping the aspect ratio.
+    #[default]
+    Contain,
+    /// Fill the size exactly, cropping to the region with the most detail.
+    SmartCrop,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ThumbnailNodeConfig {
+    pub sizes: Vec<ThumbnailSize>,
+    pub fit: ThumbnailFit,
+    pub format: EncodeFormat,
+}
+
+impl Default for ThumbnailNodeConfig {
+    fn default() -> Self {
+        Self {
+            sizes: vec![ThumbnailSize { width: 256, height: 256 }],
+            fit: ThumbnailFit::Contain,
+            format: EncodeFormat::Jpeg { quality: 85 },
+        }
+    }
+}
+
+impl Validate for ThumbnailNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        ensure(!self.sizes.is_empty(), "sizes", "must not be empty")?;
+        ensure(self.sizes.iter().all(|s| s.width > 0 && s.height > 0), "sizes", "must be positive")?;
+        self.format.validate_in("format")
+    }
+}
+
+config_builder!(ThumbnailNodeConfig for ThumbnailNode {
+    sizes: Vec<ThumbnailSize>,
+    fit: ThumbnailFit,
+    format: EncodeFormat,
+});
+
+/// An encoded thumbnail and the configured size it was made for.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct Thumbnail {
+    pub size: ThumbnailSize,
+    pub data: Vec<u8>,
+}
+
+/// Makes one thumbnail per configured size from an encoded image.
+///
+/// The image is decoded only at the scale the largest thumbnail needs and turned upright
+/// by its EXIF orientation. Images are never scaled up.
+pub fn make_thumbnails(data: Vec<u8>, config: &ThumbnailNodeConfig) -> Result<Vec<Thumbnail>, Error> {
+    let orientation = exif_orientation(&data).unwrap_or(1);
+    let (width, height) = ImageReader::new(Cursor::new(&data)).with_guessed_format()?.into_dimensions().map_err(Error::Decode)?;
+    // Orientations 5 to 8 swap the axes.
+    let (width, height) = if orientation >= 5 { (height, width) } else { (width, height) };
+
+    // Cropping has to keep the short side, fitting only the long one.
+    let needed = config.sizes.iter().map(|s| {
+        let (sx, sy) = (s.width as f32 / width as f32, s.height as f32 / height as f32);
+        let scale = match config.fit {
+            ThumbnailFit::Contain => sx.min(sy),
+            ThumbnailFit::SmartCrop => sx.max(sy),
+        };
+        (width.max(height) as f32 * scale).ceil() as u32
+    });
+    let max_dimension = needed.max().unwrap_or(1).clamp(1, width.max(height));
+    let img = apply_orientation(decode_image_scaled(data, max_dimension)?, orientation);
+
+    config.sizes.iter().map(|&size| {
+        let thumb = match config.fit {
+            ThumbnailFit::Contain if img.width() <= size.width && img.height() <= size.height => img.clone(),
+            ThumbnailFit::Contain => img.resize(size.width, size.height, FilterType::Triangle),
+            ThumbnailFit::SmartCrop => {
+                let r = smart_crop(&img, size.width as f32 / size.height as f32);
+                let cropped = img.crop_imm(r.x, r.y, r.width, r.height);
+                if r.width <= size.width || r.height <= size.height {
+                    cropped
+                } else {
+                    cropped.resize_exact(size.width, size.height, FilterType::Triangle)
+                }
+            }
+        };
+        Ok(Thumbnail { size, data: encode_image(&thumb, config.format)? })
+    }).collect()
+}
+
+/// Decodes, orients, crops and encodes thumbnails of every configured size in one step.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct ThumbnailNode {
+    #[output]
+    pub output: Output<Thumbnail>,
+
+    #[input]
+    pub input: Input<Vec<u8>>,
+
+    pub config: ThumbnailNodeConfig,
+}
+
+impl ThumbnailNode {
+    pub fn new(config: ThumbnailNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            input: Input::new(),
+            config,
+        }
+    }
+}
+
+impl Node for ThumbnailNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        if let Ok(data) = self.input.next() {
+            frame_span!("thumbnail", bytes = data.len());
+            for thumbnail in make_thumbnails(data, &self.config)? {
+                self.output.send(thumbnail).map_err(|e| UpdateError::Other(e.into()))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "seam-carving")]
+pub use self::seams::{seam_carve, SeamCarveNode, SeamCarveNodeConfig};
+
+#[cfg(feature = "seam-carving")]
+mod seams {
+    use flowrs::{node::{Node, UpdateError, ChangeObserver}, connection::{Input, Output}};
+    use flowrs::RuntimeConnectable;
+
+    use image::DynamicImage;
+    use serde::{Deserialize, Serialize};
+
+    use crate::config::{ensure, ConfigError, Validate};
+    use crate::filter::match_format;
+
+    /// Row-major RGB float pixels, carved in place.
+    struct Grid {
+        pixels: Vec<[f32; 3]>,
+        width: usize,
+        height: usize,
+    }
+
+    impl Grid {
+        fn transpose(&self) -> Grid {
+            let mut pixels = Vec::with_capacity(self.pixels.len());
+            for x in 0..self.width {
+                pixels.extend((0..self.height).map(|y| self.pixels[y * self.width + x]));
+            }
+            Grid { pixels, width: self.height, height: self.width }
+        }
+
+        /// Sum of absolute luminance differences to the horizontal and vertical neighbours.
+        fn energy(&self) -> Vec<f32> {
+            let luma: Vec<f32> = self.pixels.iter().map(|p| 0.299 * p[0] + 0.587 * p[1] + 0.114 * p[2]).collect();
+            let at = |x: usize, y: usize| luma[y * self.width + x];
+            let mut energy = Vec::with_capacity(luma.len());
+            for y in 0..self.height {
+                for x in 0..self.width {
+                    let dx = at((x + 1).min(self.width - 1), y) - at(x.saturating_sub(1), y);
+                    let dy = at(x, (y + 1).min(self.height - 1)) - at(x, y.saturating_sub(1));
+                    energy.push(dx.abs() + dy.abs());
+                }
+            }
+            energy
+        }
+
+        /// Column of the cheapest top-to-bottom 8-connected path in each row.
+        fn cheapest_seam(&self) -> Vec<usize> {
+            let energy = self.energy();
+            let w = self.width;
+            let mut cost = energy[..w].to_vec();
+            let mut from = vec![0usize; w * self.height];
+            for y in 1..self.height {
+                let previous = cost.clone();
+                for x in 0..w {
+                    let (lo, hi) = (x.saturating_sub(1), (x + 1).min(w - 1));
+                    let best = (lo..=hi).min_by(|&a, &b| previous[a].total_cmp(&previous[b])).unwrap_or(x);
+                    from[y * w + x] = best;
+                    cost[x] = previous[best] + energy[y * w + x];
+                }
+            }
+
+            let mut x = (0..w).min_by(|&a, &b| cost[a].total_cmp(&cost[b])).unwrap_or(0);
+            let mut seam = vec![0; self.height];
+            for y in (0..self.height).rev() {
+                seam[y] = x;
+                x = from[y * w + x];
+            }
+            seam
+        }
+
+        fn remove(&mut self, seam: &[usize]) {
+            let w = self.width;
+            let mut i = 0;
+            self.pixels.retain(|_| {
+                let keep = i % w != seam[i / w];
+                i += 1;
+                keep
+            });
+            self.width -= 1;
+        }
+    }
+
+    fn carve_to_width(grid: &mut Grid, width: usize) {
+        while grid.width > width {
+            let seam = grid.cheapest_seam();
+            grid.remove(&seam);
+        }
+        while grid.width < width {
+            let count = width - grid.width;
+            widen(grid, count);
+        }
+    }
+
+    /// Duplicates up to `count` columns, at most one per column, along the cheapest seams.
+    fn widen(grid: &mut Grid, count: usize) {
+        // Find the seams on a copy, tracking original columns, so that each is duplicated
+        // once instead of the same cheapest seam being stretched over and over.
+        let mut work = Grid { pixels: grid.pixels.clone(), width: grid.width, height: grid.height };
+        let mut columns: Vec<usize> = (0..grid.width * grid.height).map(|i| i % grid.width).collect();
+        let mut chosen: Vec<Vec<usize>> = vec![Vec::new(); grid.height];
+        for _ in 0..count.min(grid.width) {
+            let seam = work.cheapest_seam();
+            for (y, &x) in seam.iter().enumerate() {
+                chosen[y].push(columns[y * work.width + x]);
+            }
+            let w = work.width;
+            let mut i = 0;
+            columns.retain(|_| {
+                let keep = i % w != seam[i / w];
+                i += 1;
+                keep
+            });
+            work.remove(&seam);
+        }
+
+        let w = grid.width;
+        let mut pixels = Vec::with_capacity(grid.pixels.len() + chosen.iter().map(Vec::len).sum::<usize>());
+        for (y, chosen) in chosen.iter_mut().enumerate() {
+            chosen.sort_unstable();
+            let row = &grid.pixels[y * w..(y + 1) * w];
+            let mut next = chosen.iter().peekable();
+            for x in 0..w {
+                pixels.push(row[x]);
+                while next.next_if(|&&c| c == x).is_some() {
+                    let right = row[(x + 1).min(w - 1)];
+                    pixels.push([(row[x][0] + right[0]) / 2.0, (row[x][1] + right[1]) / 2.0, (row[x][2] + right[2]) / 2.0]);
+                }
+            }
+        }
+        grid.width += chosen.first().map_or(0, Vec::len);
+        grid.pixels = pixels;
+    }
+
+    /// Resizes `img` to `width` x `height` by removing or inserting low-energy seams, so
+    /// that salient content keeps its proportions. Widening inserts at most as many seams
+    /// per pass as the image has columns; large changes distort more than moderate ones.
+    /// Empty images have no seams and are returned unchanged.
+    pub fn seam_carve(img: &DynamicImage, width: u32, height: u32) -> DynamicImage {
+        if img.width() == 0 || img.height() == 0 {
+            return img.clone();
+        }
+        let rgb = img.to_rgb32f();
+        let mut grid = Grid { pixels: rgb.pixels().map(|p| p.0).collect(), width: rgb.width() as usize, height: rgb.height() as usize };
+        let (width, height) = (width.max(1) as usize, height.max(1) as usize);
+
+        carve_to_width(&mut grid, width);
+        if grid.height != height {
+            let mut transposed = grid.transpose();
+            carve_to_width(&mut transposed, height);
+            grid = transposed.transpose();
+        }
+
+        let data = grid.pixels.into_iter().flatten().collect();
+        let result = image::Rgb32FImage::from_raw(grid.width as u32, grid.height as u32, data).expect("carved pixels match their size");
+        match_format(result, img)
+    }
+
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    #[serde(default)]
+    pub struct SeamCarveNodeConfig {
+        pub width: u32,
+        pub height: u32,
+    }
+
+    impl Default for SeamCarveNodeConfig {
+        fn default() -> Self {
+            Self { width: 640, height: 480 }
+        }
+    }
+
+    impl Validate for SeamCarveNodeConfig {
+        fn validate(&self) -> Result<(), ConfigError> {
+            ensure(self.width > 0, "width", "must be positive")?;
+            ensure(self.height > 0, "height", "must be positive")
+        }
+    }
+
+    config_builder!(SeamCarveNodeConfig for SeamCarveNode { width: u32, height: u32 });
+
+    /// Content-aware resize; costs one full energy pass per removed or inserted seam.
+    #[derive(RuntimeConnectable, Deserialize, Serialize)]
+    pub struct SeamCarveNode {
+        #[output]
+        pub output: Output<DynamicImage>,
+
+        #[input]
+        pub input: Input<DynamicImage>,
+
+        pub config: SeamCarveNodeConfig,
+    }
+
+    impl SeamCarveNode {
+        pub fn new(config: SeamCarveNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+            Self {
+                output: Output::new(change_observer),
+                input: Input::new(),
+                config,
+            }
+        }
+    }
+
+    impl Node for SeamCarveNode {
+        fn on_update(&mut self) -> Result<(), UpdateError> {
+
+            if let Ok(img) = self.input.next() {
+                frame_span!("seam_carve");
+                let carved = seam_carve(&img, self.config.width, self.config.height);
+                self.output.send(carved).map_err(|e| UpdateError::Other(e.into()))?;
+            }
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use flowrs::{node::{Node, UpdateError, ChangeObserver}, connection::{Input, Output}};
+use flowrs::RuntimeConnectable;
+
+use std::path::PathBuf;
+
+use image::{DynamicImage, imageops::FilterType};
+use anyhow::anyhow;
+use tract_onnx::prelude::*;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::{ensure, ConfigError, Validate};
+
+/// Score of a single classifier category.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct CategoryScore {
+    pub label: String,
+    pub score: f32,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ClassifierConfig {
+    /// Path to an ONNX model taking a `1x3xHxW` float tensor.
+    pub model_path: PathBuf,
+    pub input_width: u32,
+    pub input_height: u32,
+    /// Category names in model output order.
+    pub labels: Vec<String>,
+    /// Per-channel normalization applied to `0.0..=1.0` RGB values.
+    pub mean: [f32; 3],
+    pub std: [f32; 3],
+    /// Apply a softmax to the raw model outputs.
+    pub softmax: bool,
+}
+
+impl Default for ClassifierConfig {
+    fn default() -> Self {
+        Self {
+            model_path: PathBuf::new(),
+            input_width: 224,
+            input_height: 224,
+            labels: Vec::new(),
+            mean: [0.485, 0.456, 0.406],
+            std: [0.229, 0.224, 0.225],
+            softmax: true,
+        }
+    }
+}
+
+impl Validate for ClassifierConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        ensure(self.input_width > 0 && self.input_height > 0, "input_width", "model input must not be empty")?;
+        ensure(self.std.iter().all(|s| *s != 0.0), "std", "must not contain zeros")
+    }
+}
+
+config_builder!(ClassifierConfig {
+    model_path: PathBuf,
+    input_width: u32,
+    input_height: u32,
+    labels: Vec<String>,
+    mean: [f32; 3],
+    std: [f32; 3],
+    softmax: bool,
+});
+
+/// Resizes `img` to the model input and normalizes it into a `1x3xHxW` tensor.
+pub fn preprocess(img: &DynamicImage, config: &ClassifierConfig) -> Tensor {
+    let resized = img.resize_exact(config.input_width, config.input_height, FilterType::Triangle).into_rgb8();
+    tract_ndarray::Array4::from_shape_fn(
+        (1, 3, config.input_height as usize, config.input_width as usize),
+        |(_, ch, y, x)| {
+            let v = resized.get_pixel(x as u32, y as u32)[ch] as f32 / 255.0;
+            (v - config.mean[ch]) / config.std[ch]
+        },
+    )
+    .into()
+}
+
+/// Turns raw scores into probabilities, shifted by the maximum so large scores do not overflow.
+pub fn softmax(scores: &mut [f32]) {
+    let max = scores.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+    let sum: f32 = scores.iter().map(|s| (s - max).exp()).sum();
+    scores.iter_mut().for_each(|s| *s = (*s - max).exp() / sum);
+}
+
+/// ONNX image classifier running on the CPU via tract.
+pub struct OnnxClassifier {
+    model: TypedRunnableModel<TypedModel>,
+    config: ClassifierConfig,
+}
+
+impl OnnxClassifier {
+    pub fn load(config: ClassifierConfig) -> Result<Self, anyhow::Error> {
+        let (w, h) = (config.input_width as usize, config.input_height as usize);
+        let model = tract_onnx::onnx()
+            .model_for_path(&config.model_path)?
+            .with_input_fact(0, f32::fact([1, 3, h, w]).into())?
+            .into_optimized()?
+            .into_runnable()?;
+        Ok(Self { model, config })
+    }
+
+    pub fn classify(&self, img: &DynamicImage) -> Result<Vec<CategoryScore>, anyhow::Error> {
+        let c = &self.config;
+        let outputs = self.model.run(tvec!(preprocess(img, c).into()))?;
+        let mut scores: Vec<f32> = outputs[0].to_array_view::<f32>()?.iter().copied().collect();
+        if scores.len() != c.labels.len() {
+            return Err(anyhow!("Model produced {} scores for {} labels.", scores.len(), c.labels.len()));
+        }
+        if c.softmax {
+            softmax(&mut scores);
+        }
+
+        Ok(c.labels
+            .iter()
+            .zip(scores)
+            .map(|(label, score)| CategoryScore { label: label.clone(), score })
+            .collect())
+    }
+}
+
+/// Runs a content-safety classifier on each frame and emits per-category scores.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct ContentModerationNode {
+    #[output]
+    pub output: Output<Vec<CategoryScore>>,
+
+    #[input]
+    pub input: Input<DynamicImage>,
+
+    pub config: ClassifierConfig,
+
+    #[serde(skip)]
+    classifier: Option<OnnxClassifier>,
+}
+
+impl ContentModerationNode {
+    pub fn new(config: ClassifierConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            input: Input::new(),
+            config,
+            classifier: None,
+        }
+    }
+}
+
+impl Node for ContentModerationNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        if let Ok(img) = self.input.next() {
+            if self.classifier.is_none() {
+                self.classifier = Some(OnnxClassifier::load(self.config.clone()).map_err(UpdateError::Other)?);
+            }
+            let scores = self.classifier.as_ref().expect("loaded above").classify(&img).map_err(UpdateError::Other)?;
+            self.output.send(scores).map_err(|e| UpdateError::Other(e.into()))?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,1181 @@
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+/// Outcome of a single upload.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct UploadStatus {
+    pub success: bool,
+    pub attempts: u32,
+    pub status_code: Option<u16>,
+    pub error: Option<String>,
+}
+
+/// Converts days since the Unix epoch into a (year, month, day) civil date.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = (if mp < 10 { mp + 3 } else { mp - 9 }) as u32;
+    (yoe + era * 400 + i64::from(month <= 2), month, day)
+}
+
+/// Expands `{seq}`, `{timestamp}` (Unix milliseconds), `{date}` (UTC `YYYY-MM-DD`) and `{ext}` in a key template.
+pub fn render_key_template(template: &str, seq: u64, time: SystemTime, ext: &str) -> String {
+    let millis = time.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_millis() as u64);
+    let (year, month, day) = civil_from_days((millis / 86_400_000) as i64);
+    template
+        .replace("{seq}", &seq.to_string())
+        .replace("{timestamp}", &millis.to_string())
+        .replace("{date}", &format!("{:04}-{:02}-{:02}", year, month, day))
+        .replace("{ext}", ext)
+}
+
+/// Drops frames arriving faster than a configured rate.
+#[derive(Clone, Debug, Default)]
+pub struct RateLimiter {
+    last: Option<Instant>,
+}
+
+impl RateLimiter {
+    /// Returns `true` if a frame could pass now given a maximum rate in Hz (`None` is unlimited).
+    pub fn ready(&self, max_rate_hz: Option<f32>) -> bool {
+        match (max_rate_hz, self.last) {
+            (Some(rate), Some(last)) if rate > 0.0 => last.elapsed() >= Duration::from_secs_f32(1.0 / rate),
+            _ => true,
+        }
+    }
+
+    /// Like [`RateLimiter::ready`], but records the frame as passed if it is allowed.
+    pub fn allow(&mut self, max_rate_hz: Option<f32>) -> bool {
+        let allowed = self.ready(max_rate_hz);
+        if allowed {
+            self.last = Some(Instant::now());
+        }
+        allowed
+    }
+}
+
+#[cfg(feature = "mqtt")]
+pub use self::mqtt::{mqtt_client_id, MqttImagePublisherNode, MqttImageSubscriberNode, MqttNodeConfig, MqttQos};
+
+#[cfg(feature = "mqtt")]
+mod mqtt {
+    use super::*;
+
+    use flowrs::{node::{Node, UpdateError, ChangeObserver}, connection::{Input, Output}};
+    use flowrs::RuntimeConnectable;
+
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::mpsc::{self, Receiver};
+
+    use image::DynamicImage;
+    use rumqttc::{Client, Event, MqttOptions, Packet, QoS};
+
+    use crate::config::{ensure, ConfigError, Validate};
+    use crate::flow::{SourceGate, StatusReporter};
+    use crate::transform::{decode_image, encode_image, EncodeFormat};
+    use crate::types::{NodeState, NodeStatus, SourceControl};
+
+    /// Capacity of the request queue between a node and its MQTT event loop.
+    const REQUEST_CAPACITY: usize = 10;
+
+    /// Distinguishes the generated client ids of nodes within one process.
+    static CLIENT_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    #[derive(Clone, Copy, Debug, Default, Deserialize, Serialize)]
+    pub enum MqttQos {
+        AtMostOnce,
+        #[default]
+        AtLeastOnce,
+        ExactlyOnce,
+    }
+
+    impl From<MqttQos> for QoS {
+        fn from(qos: MqttQos) -> Self {
+            match qos {
+                MqttQos::AtMostOnce => QoS::AtMostOnce,
+                MqttQos::AtLeastOnce => QoS::AtLeastOnce,
+                MqttQos::ExactlyOnce => QoS::ExactlyOnce,
+            }
+        }
+    }
+
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    #[serde(default)]
+    pub struct MqttNodeConfig {
+        pub host: String,
+        pub port: u16,
+        /// Client id presented to the broker, which disconnects an older client with the same id.
+        /// Empty generates an id unique to the node.
+        pub client_id: String,
+        pub topic: String,
+        pub qos: MqttQos,
+        /// Publishers only: maximum publish rate in frames per second.
+        pub max_rate_hz: Option<f32>,
+        /// Publishers only: JPEG quality of published frames.
+        pub jpeg_quality: u8,
+        /// Largest packet sent or accepted in bytes; encoded frames must fit into one packet.
+        pub max_packet_size: usize,
+    }
+
+    impl Default for MqttNodeConfig {
+        fn default() -> Self {
+            Self {
+                host: "localhost".into(),
+                port: 1883,
+                client_id: String::new(),
+                topic: "images".into(),
+                qos: MqttQos::AtLeastOnce,
+                max_rate_hz: None,
+                jpeg_quality: 80,
+                max_packet_size: 4 * 1024 * 1024,
+            }
+        }
+    }
+
+    impl Validate for MqttNodeConfig {
+        fn validate(&self) -> Result<(), ConfigError> {
+            ensure((1..=100).contains(&self.jpeg_quality), "jpeg_quality", "must be between 1 and 100")?;
+            ensure(self.max_rate_hz.is_none_or(|hz| hz > 0.0), "max_rate_hz", "must be positive")?;
+            ensure(self.max_packet_size > 0, "max_packet_size", "must be positive")
+        }
+    }
+
+    config_builder!(MqttNodeConfig {
+        host: String,
+        port: u16,
+        client_id: String,
+        topic: String,
+        qos: MqttQos,
+        max_rate_hz: Option<f32>,
+        jpeg_quality: u8,
+        max_packet_size: usize,
+    });
+
+    /// The configured client id, or one made unique by process, node and start time.
+    pub fn mqtt_client_id(config: &MqttNodeConfig) -> String {
+        if !config.client_id.is_empty() {
+            return config.client_id.clone();
+        }
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.subsec_nanos());
+        format!("flowrs-img-{}-{}-{:08x}", std::process::id(), CLIENT_COUNTER.fetch_add(1, Ordering::Relaxed), nanos)
+    }
+
+    fn connect(config: &MqttNodeConfig) -> (Client, rumqttc::Connection) {
+        let mut options = MqttOptions::new(mqtt_client_id(config), config.host.clone(), config.port);
+        options.set_keep_alive(Duration::from_secs(5));
+        options.set_max_packet_size(config.max_packet_size, config.max_packet_size);
+        Client::new(options, REQUEST_CAPACITY)
+    }
+
+    /// JPEG-encodes frames and publishes them to an MQTT topic.
+    ///
+    /// Frames are dropped rather than waited on while the connection is down or
+    /// the request queue is full, so a slow broker never stalls the flow.
+    #[derive(RuntimeConnectable, Deserialize, Serialize)]
+    pub struct MqttImagePublisherNode {
+        #[output]
+        pub health: Output<NodeStatus>,
+
+        /// Total number of frames dropped because the request queue was full, sent whenever it changes.
+        #[output]
+        pub dropped: Output<u64>,
+
+        #[input]
+        pub input: Input<DynamicImage>,
+
+        pub config: MqttNodeConfig,
+
+        #[serde(skip)]
+        client: Option<Client>,
+        #[serde(skip)]
+        limiter: RateLimiter,
+        #[serde(skip)]
+        reporter: StatusReporter,
+        #[serde(skip)]
+        dropped_total: u64,
+    }
+
+    impl MqttImagePublisherNode {
+        pub fn new(config: MqttNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+            Self {
+                health: Output::new(change_observer),
+                dropped: Output::new(change_observer),
+                input: Input::new(),
+                config,
+                client: None,
+                limiter: RateLimiter::default(),
+                reporter: StatusReporter::default(),
+                dropped_total: 0,
+            }
+        }
+
+        fn update(&mut self) -> Result<(), UpdateError> {
+
+            if let Ok(img) = self.input.next() {
+                if !self.limiter.allow(self.config.max_rate_hz) {
+                    return Ok(());
+                }
+
+                if self.client.is_none() {
+                    let (client, mut connection) = connect(&self.config);
+                    // The event loop must be polled for requests to go out; it reconnects on its own.
+                    std::thread::spawn(move || {
+                        for event in connection.iter() {
+                            if event.is_err() {
+                                std::thread::sleep(Duration::from_secs(1));
+                            }
+                        }
+                    });
+                    self.client = Some(client);
+                }
+
+                frame_span!("mqtt_publish", width = img.width(), height = img.height());
+                let payload = encode_image(&img, EncodeFormat::Jpeg { quality: self.config.jpeg_quality })?;
+                if payload.len() > self.config.max_packet_size {
+                    return Err(UpdateError::Other(anyhow::anyhow!(
+                        "Encoded frame of {} bytes exceeds max_packet_size of {} bytes.", payload.len(), self.config.max_packet_size)));
+                }
+                let client = self.client.as_mut().expect("connected above");
+                if client.try_publish(self.config.topic.clone(), self.config.qos.into(), false, payload).is_err() {
+                    self.dropped_total += 1;
+                    self.dropped.send(self.dropped_total).map_err(|e| UpdateError::Other(e.into()))?;
+                    return Ok(());
+                }
+                self.reporter.frame();
+            }
+            Ok(())
+        }
+    }
+
+    impl Node for MqttImagePublisherNode {
+        fn on_update(&mut self) -> Result<(), UpdateError> {
+            let result = self.update();
+            self.reporter.track(result, &mut self.health)
+        }
+    }
+
+    /// Receives encoded frames from an MQTT topic and decodes them.
+    ///
+    /// Frames arriving while `control` has paused the node are dropped; stopping it disconnects.
+    #[derive(RuntimeConnectable, Deserialize, Serialize)]
+    pub struct MqttImageSubscriberNode {
+        #[output]
+        pub output: Output<DynamicImage>,
+
+        #[output]
+        pub health: Output<NodeStatus>,
+
+        #[input]
+        pub control: Input<SourceControl>,
+
+        pub config: MqttNodeConfig,
+
+        #[serde(skip)]
+        session: Option<(Client, Receiver<Vec<u8>>)>,
+        #[serde(skip)]
+        reporter: StatusReporter,
+        #[serde(skip)]
+        gate: SourceGate,
+    }
+
+    impl MqttImageSubscriberNode {
+        pub fn new(config: MqttNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+            Self {
+                output: Output::new(change_observer),
+                health: Output::new(change_observer),
+                control: Input::new(),
+                config,
+                session: None,
+                reporter: StatusReporter::default(),
+                gate: SourceGate::default(),
+            }
+        }
+
+        fn update(&mut self) -> Result<(), UpdateError> {
+
+            if self.gate.poll(&mut self.control) {
+                if let Some((mut client, _)) = self.session.take() {
+                    let _ = client.disconnect();
+                }
+            }
+            if !self.gate.running() {
+                self.reporter.set_state(NodeState::Paused);
+            }
+            if self.gate.mode() == SourceControl::Stop {
+                return Ok(());
+            }
+            if self.session.is_none() {
+                let (client, mut connection) = connect(&self.config);
+                let (mut subscriber, topic, qos) = (client.clone(), self.config.topic.clone(), self.config.qos.into());
+
+                let (tx, rx) = mpsc::channel();
+                std::thread::spawn(move || {
+                    for event in connection.iter() {
+                        match event {
+                            Ok(Event::Incoming(Packet::Publish(publish))) => {
+                                if tx.send(publish.payload.to_vec()).is_err() {
+                                    break;
+                                }
+                            }
+                            // Clean sessions lose their subscriptions, so subscribe on every (re)connect.
+                            Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                                let _ = subscriber.try_subscribe(topic.clone(), qos);
+                            }
+                            Ok(_) => {}
+                            Err(_) => std::thread::sleep(Duration::from_secs(1)),
+                        }
+                    }
+                });
+                self.session = Some((client, rx));
+            }
+
+            let (_, rx) = self.session.as_ref().expect("connected above");
+            let payloads: Vec<Vec<u8>> = rx.try_iter().collect();
+            // Payloads arriving while paused are discarded, so they cannot pile up.
+            for payload in payloads {
+                if !self.gate.running() {
+                    continue;
+                }
+                frame_span!("mqtt_subscribe", bytes = payload.len());
+                let img = decode_image(payload)?;
+                self.reporter.frame();
+                self.gate.produced();
+                self.output.send(img).map_err(|e| UpdateError::Other(e.into()))?;
+            }
+            Ok(())
+        }
+    }
+
+    impl Node for MqttImageSubscriberNode {
+        fn on_update(&mut self) -> Result<(), UpdateError> {
+            let result = self.update();
+            self.reporter.track(result, &mut self.health)
+        }
+    }
+}
+
+#[cfg(feature = "websocket")]
+pub use self::websocket::{WebSocketEncoding, WebSocketImageNode, WebSocketImageNodeConfig};
+
+#[cfg(feature = "websocket")]
+mod websocket {
+    use super::*;
+
+    use flowrs::{node::{Node, UpdateError, ChangeObserver}, connection::{Input, Output}};
+    use flowrs::RuntimeConnectable;
+
+    use std::net::{TcpListener, TcpStream};
+    use std::sync::mpsc::{self, Receiver};
+
+    use base64::Engine;
+    use image::DynamicImage;
+    use tungstenite::{Message, WebSocket};
+
+    use crate::config::{ensure, ConfigError, Validate};
+    use crate::flow::StatusReporter;
+    use crate::transform::{encode_image, EncodeFormat};
+    use crate::types::NodeStatus;
+
+    /// Clients that cannot accept a frame within this time are disconnected.
+    const WRITE_TIMEOUT: Duration = Duration::from_millis(500);
+
+    #[derive(Clone, Copy, Debug, Default, Deserialize, Serialize)]
+    pub enum WebSocketEncoding {
+        /// Raw JPEG bytes in binary messages.
+        #[default]
+        Binary,
+        /// Base64 JPEG in text messages, ready for use in a `data:` URL.
+        Base64,
+    }
+
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    #[serde(default)]
+    pub struct WebSocketImageNodeConfig {
+        pub address: String,
+        pub encoding: WebSocketEncoding,
+        pub jpeg_quality: u8,
+        /// Maximum frame rate sent to each client.
+        pub max_rate_hz: Option<f32>,
+    }
+
+    impl Default for WebSocketImageNodeConfig {
+        fn default() -> Self {
+            Self {
+                address: "127.0.0.1:9001".into(),
+                encoding: WebSocketEncoding::Binary,
+                jpeg_quality: 80,
+                max_rate_hz: None,
+            }
+        }
+    }
+
+    impl Validate for WebSocketImageNodeConfig {
+        fn validate(&self) -> Result<(), ConfigError> {
+            ensure((1..=100).contains(&self.jpeg_quality), "jpeg_quality", "must be between 1 and 100")?;
+            ensure(self.max_rate_hz.is_none_or(|hz| hz > 0.0), "max_rate_hz", "must be positive")
+        }
+    }
+
+    config_builder!(WebSocketImageNodeConfig for WebSocketImageNode {
+        address: String,
+        encoding: WebSocketEncoding,
+        jpeg_quality: u8,
+        max_rate_hz: Option<f32>,
+    });
+
+    struct Client {
+        socket: WebSocket<TcpStream>,
+        limiter: RateLimiter,
+    }
+
+    /// Serves incoming frames as JPEG to all connected WebSocket clients.
+    #[derive(RuntimeConnectable, Deserialize, Serialize)]
+    pub struct WebSocketImageNode {
+        #[output]
+        pub health: Output<NodeStatus>,
+
+        #[input]
+        pub input: Input<DynamicImage>,
+
+        pub config: WebSocketImageNodeConfig,
+
+        #[serde(skip)]
+        incoming: Option<Receiver<WebSocket<TcpStream>>>,
+        #[serde(skip)]
+        clients: Vec<Client>,
+        #[serde(skip)]
+        reporter: StatusReporter,
+    }
+
+    impl WebSocketImageNode {
+        pub fn new(config: WebSocketImageNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+            Self {
+                health: Output::new(change_observer),
+                input: Input::new(),
+                config,
+                incoming: None,
+                clients: Vec::new(),
+                reporter: StatusReporter::default(),
+            }
+        }
+
+        fn listen(&self) -> Result<Receiver<WebSocket<TcpStream>>, anyhow::Error> {
+            let listener = TcpListener::bind(&self.config.address)?;
+            let (tx, rx) = mpsc::channel();
+            // Handshakes block, so they are done on a dedicated thread.
+            std::thread::spawn(move || {
+                for stream in listener.incoming().flatten() {
+                    if stream.set_write_timeout(Some(WRITE_TIMEOUT)).is_err() {
+                        continue;
+                    }
+                    if let Ok(socket) = tungstenite::accept(stream) {
+                        if tx.send(socket).is_err() {
+                            break;
+                        }
+                    }
+                }
+            });
+            Ok(rx)
+        }
+
+        fn update(&mut self) -> Result<(), UpdateError> {
+
+            if self.incoming.is_none() {
+                self.incoming = Some(self.listen().map_err(UpdateError::Other)?);
+            }
+            if let Some(incoming) = &self.incoming {
+                self.clients.extend(incoming.try_iter().map(|socket| Client { socket, limiter: RateLimiter::default() }));
+            }
+
+            if let Ok(img) = self.input.next() {
+                let max_rate = self.config.max_rate_hz;
+                if !self.clients.iter().any(|c| c.limiter.ready(max_rate)) {
+                    return Ok(());
+                }
+
+                frame_span!("websocket_send", width = img.width(), height = img.height(), clients = self.clients.len());
+                let jpeg = encode_image(&img, EncodeFormat::Jpeg { quality: self.config.jpeg_quality })?;
+                let message = match self.config.encoding {
+                    WebSocketEncoding::Binary => Message::Binary(jpeg),
+                    WebSocketEncoding::Base64 => Message::Text(base64::engine::general_purpose::STANDARD.encode(jpeg)),
+                };
+
+                self.clients.retain_mut(|client| {
+                    if !client.limiter.allow(max_rate) {
+                        return true;
+                    }
+                    client.socket.send(message.clone()).is_ok()
+                });
+                self.reporter.frame();
+            }
+            Ok(())
+        }
+    }
+
+    impl Node for WebSocketImageNode {
+        fn on_update(&mut self) -> Result<(), UpdateError> {
+            let result = self.update();
+            self.reporter.track(result, &mut self.health)
+        }
+    }
+}
+
+/// Delay before retry `attempt` (starting at 1), doubling from `base` up to `max`.
+pub fn backoff_delay(base: Duration, max: Duration, attempt: u32) -> Duration {
+    base.saturating_mul(1u32 << attempt.saturating_sub(1).min(16)).min(max)
+}
+
+#[cfg(feature = "http")]
+pub use self::http::{HttpImageSourceNode, HttpImageSourceNodeConfig, HttpPostNode, HttpPostNodeConfig};
+
+#[cfg(feature = "http")]
+mod http {
+    use super::*;
+
+    use flowrs::{node::{Node, UpdateError, ChangeObserver}, connection::{Input, Output}};
+    use flowrs::RuntimeConnectable;
+
+    use std::io::Read;
+    use std::sync::mpsc::{self, Receiver, SyncSender, TryRecvError, TrySendError};
+
+    use anyhow::anyhow;
+    use image::DynamicImage;
+
+    use crate::config::{ensure, ConfigError, Validate};
+    use crate::flow::{SourceGate, StatusReporter};
+    use crate::transform::{decode_image, encode_image, EncodeFormat};
+    use crate::types::{NodeState, NodeStatus, SourceControl};
+
+    /// Encoded frames waiting for the upload thread; further frames are dropped.
+    const JOB_CAPACITY: usize = 8;
+
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    #[serde(default)]
+    pub struct HttpPostNodeConfig {
+        pub url: String,
+        pub headers: Vec<(String, String)>,
+        pub format: EncodeFormat,
+        /// Only post frames received while the latest trigger was `true`.
+        pub require_trigger: bool,
+        pub max_attempts: u32,
+        pub backoff_ms: u64,
+        pub max_backoff_ms: u64,
+        pub timeout_ms: u64,
+    }
+
+    impl Default for HttpPostNodeConfig {
+        fn default() -> Self {
+            Self {
+                url: String::new(),
+                headers: Vec::new(),
+                format: EncodeFormat::Jpeg { quality: 85 },
+                require_trigger: false,
+                max_attempts: 3,
+                backoff_ms: 500,
+                max_backoff_ms: 10_000,
+                timeout_ms: 10_000,
+            }
+        }
+    }
+
+    impl Validate for HttpPostNodeConfig {
+        fn validate(&self) -> Result<(), ConfigError> {
+            self.format.validate_in("format")?;
+            ensure(self.max_attempts > 0, "max_attempts", "must be positive")?;
+            ensure(self.backoff_ms <= self.max_backoff_ms, "max_backoff_ms", "must not be below backoff_ms")?;
+            ensure(self.timeout_ms > 0, "timeout_ms", "must be positive")
+        }
+    }
+
+    config_builder!(HttpPostNodeConfig for HttpPostNode {
+        url: String,
+        headers: Vec<(String, String)>,
+        format: EncodeFormat,
+        require_trigger: bool,
+        max_attempts: u32,
+        backoff_ms: u64,
+        max_backoff_ms: u64,
+        timeout_ms: u64,
+    });
+
+    fn content_type(format: EncodeFormat) -> &'static str {
+        match format {
+            EncodeFormat::Png => "image/png",
+            EncodeFormat::Jpeg { .. } => "image/jpeg",
+            EncodeFormat::Bmp => "image/bmp",
+            EncodeFormat::Gif => "image/gif",
+            EncodeFormat::Tiff => "image/tiff",
+            EncodeFormat::Avif { .. } => "image/avif",
+            EncodeFormat::Jxl { .. } => "image/jxl",
+        }
+    }
+
+    fn post_with_retry(config: &HttpPostNodeConfig, body: &[u8]) -> UploadStatus {
+        let mut status = UploadStatus { success: false, attempts: 0, status_code: None, error: None };
+        while status.attempts < config.max_attempts.max(1) {
+            if status.attempts > 0 {
+                let base = Duration::from_millis(config.backoff_ms);
+                let max = Duration::from_millis(config.max_backoff_ms);
+                std::thread::sleep(backoff_delay(base, max, status.attempts));
+            }
+            status.attempts += 1;
+
+            let mut request = ureq::post(&config.url)
+                .timeout(Duration::from_millis(config.timeout_ms))
+                .set("Content-Type", content_type(config.format));
+            for (name, value) in &config.headers {
+                request = request.set(name, value);
+            }
+
+            match request.send_bytes(body) {
+                Ok(response) => {
+                    status.success = true;
+                    status.status_code = Some(response.status());
+                    status.error = None;
+                    return status;
+                }
+                Err(ureq::Error::Status(code, _)) => {
+                    status.status_code = Some(code);
+                    status.error = Some(format!("Server responded with status {}.", code));
+                    // Client errors other than rate limiting will not go away by retrying.
+                    if (400..500).contains(&code) && code != 429 {
+                        return status;
+                    }
+                }
+                Err(e) => status.error = Some(e.to_string()),
+            }
+        }
+        status
+    }
+
+    /// POSTs encoded frames to a webhook, retrying with exponential backoff.
+    ///
+    /// Uploads run on a background thread so slow endpoints do not stall the flow.
+    /// While it is busy, up to `JOB_CAPACITY` frames wait; further frames are
+    /// dropped and counted as errors on `health`.
+    #[derive(RuntimeConnectable, Deserialize, Serialize)]
+    pub struct HttpPostNode {
+        #[output]
+        pub status: Output<UploadStatus>,
+
+        #[input]
+        pub input: Input<DynamicImage>,
+
+        #[input]
+        pub trigger: Input<bool>,
+
+        #[output]
+        pub health: Output<NodeStatus>,
+
+        pub config: HttpPostNodeConfig,
+
+        #[serde(skip)]
+        triggered: bool,
+        #[serde(skip)]
+        worker: Option<(SyncSender<Vec<u8>>, Receiver<UploadStatus>)>,
+        #[serde(skip)]
+        reporter: StatusReporter,
+    }
+
+    impl HttpPostNode {
+        pub fn new(config: HttpPostNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+            Self {
+                status: Output::new(change_observer),
+                input: Input::new(),
+                trigger: Input::new(),
+                health: Output::new(change_observer),
+                config,
+                triggered: false,
+                worker: None,
+                reporter: StatusReporter::default(),
+            }
+        }
+
+        fn spawn_worker(&self) -> (SyncSender<Vec<u8>>, Receiver<UploadStatus>) {
+            let (job_tx, job_rx) = mpsc::sync_channel::<Vec<u8>>(JOB_CAPACITY);
+            let (status_tx, status_rx) = mpsc::channel();
+            let config = self.config.clone();
+            std::thread::spawn(move || {
+                for body in job_rx {
+                    if status_tx.send(post_with_retry(&config, &body)).is_err() {
+                        break;
+                    }
+                }
+            });
+            (job_tx, status_rx)
+        }
+
+        fn update(&mut self) -> Result<(), UpdateError> {
+
+            while let Ok(triggered) = self.trigger.next() {
+                self.triggered = triggered;
+            }
+            if self.worker.is_none() {
+                self.worker = Some(self.spawn_worker());
+            }
+
+            if let Ok(img) = self.input.next() {
+                if !self.config.require_trigger || self.triggered {
+                    frame_span!("http_post", width = img.width(), height = img.height());
+                    let body = encode_image(&img, self.config.format)?;
+                    let (jobs, _) = self.worker.as_ref().expect("spawned above");
+                    match jobs.try_send(body) {
+                        Ok(()) => {}
+                        Err(TrySendError::Full(_)) => self.reporter.error("Upload queue full, frame dropped"),
+                        Err(TrySendError::Disconnected(_)) => return Err(UpdateError::Other(anyhow!("Upload thread exited."))),
+                    }
+                }
+            }
+
+            let (_, statuses) = self.worker.as_ref().expect("spawned above");
+            let finished: Vec<UploadStatus> = statuses.try_iter().collect();
+            for status in finished {
+                if status.success {
+                    self.reporter.frame();
+                } else {
+                    self.reporter.error(status.error.as_deref().unwrap_or("Upload failed"));
+                }
+                self.status.send(status).map_err(|e| UpdateError::Other(e.into()))?;
+            }
+            Ok(())
+        }
+    }
+
+    impl Node for HttpPostNode {
+        fn on_update(&mut self) -> Result<(), UpdateError> {
+            let result = self.update();
+            self.reporter.track(result, &mut self.health)
+        }
+    }
+
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    #[serde(default)]
+    pub struct HttpImageSourceNodeConfig {
+        pub url: String,
+        /// Sent with every request, e.g. `("Authorization", "Basic ...")`.
+        pub headers: Vec<(String, String)>,
+        pub interval_ms: u64,
+        pub timeout_ms: u64,
+        /// Responses larger than this are rejected.
+        pub max_bytes: u64,
+    }
+
+    impl Default for HttpImageSourceNodeConfig {
+        fn default() -> Self {
+            Self {
+                url: String::new(),
+                headers: Vec::new(),
+                interval_ms: 1000,
+                timeout_ms: 10_000,
+                max_bytes: 32 << 20,
+            }
+        }
+    }
+
+    impl Validate for HttpImageSourceNodeConfig {
+        fn validate(&self) -> Result<(), ConfigError> {
+            ensure(self.timeout_ms > 0, "timeout_ms", "must be positive")?;
+            ensure(self.max_bytes > 0, "max_bytes", "must be positive")
+        }
+    }
+
+    config_builder!(HttpImageSourceNodeConfig for HttpImageSourceNode {
+        url: String,
+        headers: Vec<(String, String)>,
+        interval_ms: u64,
+        timeout_ms: u64,
+        max_bytes: u64,
+    });
+
+    fn fetch_image(config: &HttpImageSourceNodeConfig) -> Result<DynamicImage, anyhow::Error> {
+        let mut request = ureq::get(&config.url).timeout(Duration::from_millis(config.timeout_ms));
+        for (name, value) in &config.headers {
+            request = request.set(name, value);
+        }
+        let response = request.call().map_err(|e| match e {
+            ureq::Error::Status(code, _) => anyhow!("Server responded with status {}.", code),
+            e => e.into(),
+        })?;
+        let mut body = Vec::new();
+        response.into_reader().take(config.max_bytes + 1).read_to_end(&mut body)?;
+        if body.len() as u64 > config.max_bytes {
+            return Err(anyhow!("Image is larger than {} bytes", config.max_bytes));
+        }
+        Ok(decode_image(body)?)
+    }
+
+    fn poll(config: &HttpImageSourceNodeConfig, frames: &SyncSender<Result<DynamicImage, String>>) {
+        let interval = Duration::from_millis(config.interval_ms);
+        loop {
+            let started = Instant::now();
+            if frames.send(fetch_image(config).map_err(|e| e.to_string())).is_err() {
+                return;
+            }
+            std::thread::sleep(interval.saturating_sub(started.elapsed()));
+        }
+    }
+
+    /// Periodically GETs a still image, such as the `/snapshot.jpg` of an IP camera.
+    ///
+    /// Requests run on a background thread; failed fetches are reported on
+    /// `errors` and retried at the next interval.
+    #[derive(RuntimeConnectable, Deserialize, Serialize)]
+    pub struct HttpImageSourceNode {
+        #[output]
+        pub output: Output<DynamicImage>,
+
+        #[output]
+        pub errors: Output<String>,
+
+        #[output]
+        pub health: Output<NodeStatus>,
+
+        #[input]
+        pub control: Input<SourceControl>,
+
+        pub config: HttpImageSourceNodeConfig,
+
+        #[serde(skip)]
+        frames: Option<Receiver<Result<DynamicImage, String>>>,
+        #[serde(skip)]
+        reporter: StatusReporter,
+        #[serde(skip)]
+        gate: SourceGate,
+    }
+
+    impl HttpImageSourceNode {
+        pub fn new(config: HttpImageSourceNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+            Self {
+                output: Output::new(change_observer),
+                errors: Output::new(change_observer),
+                health: Output::new(change_observer),
+                control: Input::new(),
+                config,
+                frames: None,
+                reporter: StatusReporter::default(),
+                gate: SourceGate::default(),
+            }
+        }
+
+        fn update(&mut self) -> Result<(), UpdateError> {
+
+            if self.gate.poll(&mut self.control) {
+                // The fetch thread ends once it finds the receiver gone.
+                self.frames = None;
+            }
+            if !self.gate.running() {
+                self.reporter.set_state(NodeState::Paused);
+            }
+            if self.gate.mode() == SourceControl::Stop {
+                return Ok(());
+            }
+            if self.frames.is_none() {
+                let (tx, frames) = mpsc::sync_channel(1);
+                let config = self.config.clone();
+                std::thread::spawn(move || poll(&config, &tx));
+                self.frames = Some(frames);
+            }
+            let frames = self.frames.as_ref().expect("spawned above");
+            loop {
+                match frames.try_recv() {
+                    Ok(Ok(_)) if !self.gate.running() => {}
+                    Ok(Ok(img)) => {
+                        frame_span!("http_image_source", width = img.width(), height = img.height());
+                        self.reporter.frame();
+                        self.gate.produced();
+                        self.output.send(img).map_err(|e| UpdateError::Other(e.into()))?;
+                    }
+                    Ok(Err(e)) => {
+                        self.reporter.error(&e);
+                        self.errors.send(e).map_err(|e| UpdateError::Other(e.into()))?;
+                    }
+                    Err(TryRecvError::Empty) => return Ok(()),
+                    Err(TryRecvError::Disconnected) => return Err(UpdateError::Other(anyhow!("Fetch thread stopped"))),
+                }
+            }
+        }
+    }
+
+    impl Node for HttpImageSourceNode {
+        fn on_update(&mut self) -> Result<(), UpdateError> {
+            let result = self.update();
+            self.reporter.track(result, &mut self.health)
+        }
+    }
+}
+
+#[cfg(feature = "s3")]
+pub use self::object_store::{ObjectStoreUploadNode, ObjectStoreUploadNodeConfig};
+
+#[cfg(feature = "s3")]
+mod object_store {
+    use super::*;
+
+    use flowrs::{node::{Node, UpdateError, ChangeObserver}, connection::{Input, Output}};
+    use flowrs::RuntimeConnectable;
+
+    use std::sync::mpsc::{self, Receiver, SyncSender, TrySendError};
+
+    use anyhow::anyhow;
+    use image::DynamicImage;
+    use s3::{creds::Credentials, Bucket, Region};
+
+    use crate::config::{ensure, ConfigError, Validate};
+    use crate::flow::StatusReporter;
+    use crate::transform::{encode_image, EncodeFormat};
+    use crate::types::NodeStatus;
+
+    /// Batches waiting for the upload thread; further batches are dropped.
+    const BATCH_CAPACITY: usize = 4;
+
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    #[serde(default)]
+    pub struct ObjectStoreUploadNodeConfig {
+        pub bucket: String,
+        pub region: String,
+        /// Endpoint of an S3-compatible service; AWS is used if `None`.
+        pub endpoint: Option<String>,
+        /// Credentials fall back to the environment and profile files if unset.
+        pub access_key: Option<String>,
+        pub secret_key: Option<String>,
+        pub path_style: bool,
+        /// Object key, see [`render_key_template`] for placeholders.
+        pub key_template: String,
+        pub format: EncodeFormat,
+        /// Frames are uploaded in batches of this size ...
+        pub batch_size: usize,
+        /// ... or once the oldest pending frame is this old.
+        pub flush_interval_ms: u64,
+        pub max_attempts: u32,
+        pub backoff_ms: u64,
+        pub max_backoff_ms: u64,
+    }
+
+    impl Default for ObjectStoreUploadNodeConfig {
+        fn default() -> Self {
+            Self {
+                bucket: String::new(),
+                region: "us-east-1".into(),
+                endpoint: None,
+                access_key: None,
+                secret_key: None,
+                path_style: false,
+                key_template: "{date}/{timestamp}-{seq}.{ext}".into(),
+                format: EncodeFormat::Jpeg { quality: 85 },
+                batch_size: 1,
+                flush_interval_ms: 1000,
+                max_attempts: 3,
+                backoff_ms: 500,
+                max_backoff_ms: 10_000,
+            }
+        }
+    }
+
+    impl Validate for ObjectStoreUploadNodeConfig {
+        fn validate(&self) -> Result<(), ConfigError> {
+            self.format.validate_in("format")?;
+            ensure(self.batch_size > 0, "batch_size", "must be positive")?;
+            ensure(self.max_attempts > 0, "max_attempts", "must be positive")?;
+            ensure(self.backoff_ms <= self.max_backoff_ms, "max_backoff_ms", "must not be below backoff_ms")
+        }
+    }
+
+    config_builder!(ObjectStoreUploadNodeConfig for ObjectStoreUploadNode {
+        bucket: String,
+        region: String,
+        endpoint: Option<String>,
+        access_key: Option<String>,
+        secret_key: Option<String>,
+        path_style: bool,
+        key_template: String,
+        format: EncodeFormat,
+        batch_size: usize,
+        flush_interval_ms: u64,
+        max_attempts: u32,
+        backoff_ms: u64,
+        max_backoff_ms: u64,
+    });
+
+    fn format_info(format: EncodeFormat) -> (&'static str, &'static str) {
+        match format {
+            EncodeFormat::Png => ("image/png", "png"),
+            EncodeFormat::Jpeg { .. } => ("image/jpeg", "jpg"),
+            EncodeFormat::Bmp => ("image/bmp", "bmp"),
+            EncodeFormat::Gif => ("image/gif", "gif"),
+            EncodeFormat::Tiff => ("image/tiff", "tiff"),
+            EncodeFormat::Avif { .. } => ("image/avif", "avif"),
+            EncodeFormat::Jxl { .. } => ("image/jxl", "jxl"),
+        }
+    }
+
+    fn open_bucket(config: &ObjectStoreUploadNodeConfig) -> Result<Bucket, anyhow::Error> {
+        let region = match &config.endpoint {
+            Some(endpoint) => Region::Custom { region: config.region.clone(), endpoint: endpoint.clone() },
+            None => config.region.parse()?,
+        };
+        let credentials = match (&config.access_key, &config.secret_key) {
+            (Some(access), Some(secret)) => Credentials::new(Some(access), Some(secret), None, None, None)?,
+            _ => Credentials::default()?,
+        };
+        let mut bucket = Bucket::new(&config.bucket, region, credentials)?;
+        if config.path_style {
+            bucket.set_path_style();
+        }
+        Ok(bucket)
+    }
+
+    fn put_with_retry(bucket: &Bucket, config: &ObjectStoreUploadNodeConfig, key: &str, body: &[u8]) -> UploadStatus {
+        let (content_type, _) = format_info(config.format);
+        let mut status = UploadStatus { success: false, attempts: 0, status_code: None, error: None };
+        while status.attempts < config.max_attempts.max(1) {
+            if status.attempts > 0 {
+                let base = Duration::from_millis(config.backoff_ms);
+                let max = Duration::from_millis(config.max_backoff_ms);
+                std::thread::sleep(backoff_delay(base, max, status.attempts));
+            }
+            status.attempts += 1;
+
+            match bucket.put_object_with_content_type_blocking(key, body, content_type) {
+                Ok(response) => {
+                    let code = response.status_code();
+                    status.status_code = Some(code);
+                    if (200..300).contains(&code) {
+                        status.success = true;
+                        status.error = None;
+                        return status;
+                    }
+                    status.error = Some(format!("Server responded with status {}.", code));
+                    // Client errors other than rate limiting will not go away by retrying.
+                    if (400..500).contains(&code) && code != 429 {
+                        return status;
+                    }
+                }
+                Err(e) => status.error = Some(e.to_string()),
+            }
+        }
+        status
+    }
+
+    type Batch = Vec<(String, Vec<u8>)>;
+
+    /// Encodes frames and uploads them in batches to S3-compatible object storage.
+    ///
+    /// Failed uploads are retried with exponential backoff on a background
+    /// thread. While it is busy, up to `BATCH_CAPACITY` batches wait; further
+    /// batches are dropped and counted as errors on `health`.
+    #[derive(RuntimeConnectable, Deserialize, Serialize)]
+    pub struct ObjectStoreUploadNode {
+        #[output]
+        pub status: Output<UploadStatus>,
+
+        #[input]
+        pub input: Input<DynamicImage>,
+
+        #[output]
+        pub health: Output<NodeStatus>,
+
+        pub config: ObjectStoreUploadNodeConfig,
+
+        #[serde(skip)]
+        pending: Batch,
+        #[serde(skip)]
+        pending_since: Option<Instant>,
+        #[serde(skip)]
+        seq: u64,
+        #[serde(skip)]
+        worker: Option<(SyncSender<Batch>, Receiver<UploadStatus>)>,
+        #[serde(skip)]
+        reporter: StatusReporter,
+    }
+
+    impl ObjectStoreUploadNode {
+        pub fn new(config: ObjectStoreUploadNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+            Self {
+                status: Output::new(change_observer),
+                input: Input::new(),
+                health: Output::new(change_observer),
+                config,
+                pending: Vec::new(),
+                pending_since: None,
+                seq: 0,
+                worker: None,
+                reporter: StatusReporter::default(),
+            }
+        }
+
+        fn spawn_worker(&self) -> Result<(SyncSender<Batch>, Receiver<UploadStatus>), anyhow::Error> {
+            let bucket = open_bucket(&self.config)?;
+            let config = self.config.clone();
+            let (batch_tx, batch_rx) = mpsc::sync_channel::<Batch>(BATCH_CAPACITY);
+            let (status_tx, status_rx) = mpsc::channel();
+            std::thread::spawn(move || {
+                for batch in batch_rx {
+                    for (key, body) in batch {
+                        if status_tx.send(put_with_retry(&bucket, &config, &key, &body)).is_err() {
+                            return;
+                        }
+                    }
+                }
+            });
+            Ok((batch_tx, status_rx))
+        }
+
+        fn update(&mut self) -> Result<(), UpdateError> {
+
+            if self.worker.is_none() {
+                self.worker = Some(self.spawn_worker().map_err(UpdateError::Other)?);
+            }
+
+            if let Ok(img) = self.input.next() {
+                frame_span!("object_store_upload", frame = self.seq);
+                let (_, ext) = format_info(self.config.format);
+                let body = encode_image(&img, self.config.format)?;
+                let key = render_key_template(&self.config.key_template, self.seq, SystemTime::now(), ext);
+                self.seq += 1;
+                self.pending.push((key, body));
+                self.pending_since.get_or_insert_with(Instant::now);
+            }
+
+            let interval = Duration::from_millis(self.config.flush_interval_ms);
+            let due = self.pending_since.map_or(false, |since| since.elapsed() >= interval);
+            if !self.pending.is_empty() && (self.pending.len() >= self.config.batch_size.max(1) || due) {
+                let (batches, _) = self.worker.as_ref().expect("spawned above");
+                self.pending_since = None;
+                match batches.try_send(std::mem::take(&mut self.pending)) {
+                    Ok(()) => {}
+                    Err(TrySendError::Full(batch)) => {
+                        for _ in &batch {
+                            self.reporter.error("Upload queue full, frame dropped");
+                        }
+                    }
+                    Err(TrySendError::Disconnected(_)) => return Err(UpdateError::Other(anyhow!("Upload thread exited."))),
+                }
+            }
+
+            let (_, statuses) = self.worker.as_ref().expect("spawned above");
+            let finished: Vec<UploadStatus> = statuses.try_iter().collect();
+            for status in finished {
+                if status.success {
+                    self.reporter.frame();
+                } else {
+                    self.reporter.error(status.error.as_deref().unwrap_or("Upload failed"));
+                }
+                self.status.send(status).map_err(|e| UpdateError::Other(e.into()))?;
+            }
+            Ok(())
+        }
+    }
+
+    impl Node for ObjectStoreUploadNode {
+        fn on_update(&mut self) -> Result<(), UpdateError> {
+            let result = self.update();
+            self.reporter.track(result, &mut self.health)
+        }
+    }
+}
@@ -0,0 +1,198 @@
+use flowrs::{node::{Node, UpdateError, ChangeObserver}, connection::{Input, Output}};
+use flowrs::RuntimeConnectable;
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use image::DynamicImage;
+use anyhow::anyhow;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::{ensure, ConfigError, Validate};
+use crate::transform::{encode_image, EncodeFormat};
+use crate::types::Rect;
+
+/// Recognized text with its location and confidence in `0.0..=1.0`.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct TextRegion {
+    pub rect: Rect,
+    pub text: String,
+    pub confidence: f32,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum OcrGranularity {
+    Word,
+    #[default]
+    Line,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct OcrNodeConfig {
+    /// Tesseract language codes, e.g. `eng` or `deu+eng`.
+    pub language: String,
+    /// Tesseract page segmentation mode; `7` treats the input as a single line, e.g. a plate.
+    pub page_segmentation_mode: u8,
+    pub granularity: OcrGranularity,
+    /// Results below this confidence, in `0.0..=1.0` like [`TextRegion::confidence`], are dropped.
+    pub min_confidence: f32,
+}
+
+impl Default for OcrNodeConfig {
+    fn default() -> Self {
+        Self {
+            language: "eng".into(),
+            page_segmentation_mode: 3,
+            granularity: OcrGranularity::Line,
+            min_confidence: 0.0,
+        }
+    }
+}
+
+impl Validate for OcrNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        ensure(!self.language.is_empty(), "language", "must be set")?;
+        ensure(self.page_segmentation_mode <= 13, "page_segmentation_mode", "must be between 0 and 13")?;
+        ensure((0.0..=1.0).contains(&self.min_confidence), "min_confidence", "must be between 0 and 1")
+    }
+}
+
+config_builder!(OcrNodeConfig for OcrNode {
+    language: String,
+    page_segmentation_mode: u8,
+    granularity: OcrGranularity,
+    min_confidence: f32,
+});
+
+/// Page, block, paragraph and line number of a word.
+type LineKey = (u32, u32, u32, u32);
+
+/// Parses the TSV output of Tesseract into word or line regions.
+pub fn parse_tesseract_tsv(tsv: &str, granularity: OcrGranularity) -> Vec<TextRegion> {
+    let mut lines: Vec<(LineKey, Vec<TextRegion>)> = Vec::new();
+    for row in tsv.lines().skip(1) {
+        let cols: Vec<&str> = row.splitn(12, '\t').collect();
+        // Only level 5 rows are words; the others describe the page layout.
+        if cols.len() < 12 || cols[0] != "5" || cols[11].trim().is_empty() {
+            continue;
+        }
+        let num = |i: usize| cols[i].trim().parse::<i64>().unwrap_or(0).max(0) as u32;
+        let confidence = cols[10].trim().parse::<f32>().unwrap_or(-1.0);
+        if confidence < 0.0 {
+            continue;
+        }
+        let word = TextRegion {
+            rect: Rect::new(num(6), num(7), num(8), num(9)),
+            text: cols[11].trim().to_string(),
+            confidence: confidence / 100.0,
+        };
+        let key = (num(1), num(2), num(3), num(4));
+        match lines.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, words)) => words.push(word),
+            None => lines.push((key, vec![word])),
+        }
+    }
+
+    match granularity {
+        OcrGranularity::Word => lines.into_iter().flat_map(|(_, words)| words).collect(),
+        OcrGranularity::Line => lines
+            .into_iter()
+            .map(|(_, words)| {
+                let x0 = words.iter().map(|w| w.rect.x).min().unwrap_or(0);
+                let y0 = words.iter().map(|w| w.rect.y).min().unwrap_or(0);
+                let x1 = words.iter().map(|w| w.rect.x + w.rect.width).max().unwrap_or(0);
+                let y1 = words.iter().map(|w| w.rect.y + w.rect.height).max().unwrap_or(0);
+                TextRegion {
+                    rect: Rect::new(x0, y0, x1 - x0, y1 - y0),
+                    text: words.iter().map(|w| w.text.as_str()).collect::<Vec<_>>().join(" "),
+                    confidence: words.iter().map(|w| w.confidence).fold(f32::MAX, f32::min),
+                }
+            })
+            .collect(),
+    }
+}
+
+/// Runs the `tesseract` binary on a single image.
+pub fn recognize(img: &DynamicImage, config: &OcrNodeConfig) -> Result<Vec<TextRegion>, anyhow::Error> {
+    let png = encode_image(img, EncodeFormat::Png)?;
+    let psm = config.page_segmentation_mode.to_string();
+    let mut child = Command::new("tesseract")
+        .args(["stdin", "stdout", "-l", config.language.as_str(), "--psm", psm.as_str(), "tsv"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| anyhow!("Could not start tesseract: {}", e))?;
+    child.stdin.take().ok_or_else(|| anyhow!("tesseract stdin unavailable"))?.write_all(&png)?;
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(anyhow!("tesseract failed: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    let regions = parse_tesseract_tsv(&String::from_utf8_lossy(&output.stdout), config.granularity);
+    Ok(regions.into_iter().filter(|r| r.confidence >= config.min_confidence).collect())
+}
+
+/// Extracts text with bounding boxes and confidences, optionally within regions of interest.
+///
+/// Recognition is delegated to a `tesseract` binary on the `PATH`. When regions
+/// have been received on `regions` (e.g. detected plates), only those are read
+/// and the results are mapped back to frame coordinates.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct OcrNode {
+    #[output]
+    pub output: Output<Vec<TextRegion>>,
+
+    #[input]
+    pub input: Input<DynamicImage>,
+
+    #[input]
+    pub regions: Input<Vec<Rect>>,
+
+    pub config: OcrNodeConfig,
+
+    #[serde(skip)]
+    roi: Option<Vec<Rect>>,
+}
+
+impl OcrNode {
+    pub fn new(config: OcrNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            input: Input::new(),
+            regions: Input::new(),
+            config,
+            roi: None,
+        }
+    }
+}
+
+impl Node for OcrNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        while let Ok(regions) = self.regions.next() {
+            self.roi = Some(regions);
+        }
+
+        if let Ok(img) = self.input.next() {
+            let results = match &self.roi {
+                None => recognize(&img, &self.config).map_err(UpdateError::Other)?,
+                Some(regions) => {
+                    let mut results = Vec::new();
+                    for r in regions.iter().filter_map(|r| r.clamp_to(img.width(), img.height())) {
+                        let crop = img.crop_imm(r.x, r.y, r.width, r.height);
+                        let found = recognize(&crop, &self.config).map_err(UpdateError::Other)?;
+                        results.extend(found.into_iter().map(|mut t| {
+                            t.rect = t.rect.translated(r.x as i64, r.y as i64);
+                            t
+                        }));
+                    }
+                    results
+                }
+            };
+            self.output.send(results).map_err(|e| UpdateError::Other(e.into()))?;
+        }
+        Ok(())
+    }
+}
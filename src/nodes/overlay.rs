@@ -0,0 +1,415 @@
+use flowrs::{node::{Node, UpdateError, ChangeObserver}, connection::{Input, Output}};
+use flowrs::RuntimeConnectable;
+
+use std::path::PathBuf;
+
+use image::{DynamicImage, Rgba, RgbaImage};
+use image::imageops::FilterType;
+use imageproc::drawing::draw_filled_rect_mut;
+use imageproc::rect::Rect as DrawRect;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::{ensure, ConfigError, Validate};
+use crate::types::{Anchor, ObjectMeasurement};
+
+/// Object measurement converted to physical units (micrometers).
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct PhysicalMeasurement {
+    pub id: u32,
+    pub area_um2: f64,
+    pub perimeter_um: f64,
+    pub width_um: f64,
+    pub height_um: f64,
+    pub centroid_um: (f64, f64),
+}
+
+impl PhysicalMeasurement {
+    pub fn from_pixels(m: &ObjectMeasurement, um_per_pixel: f64) -> Self {
+        Self {
+            id: m.id,
+            area_um2: m.area * um_per_pixel * um_per_pixel,
+            perimeter_um: m.perimeter * um_per_pixel,
+            width_um: m.bounds.width as f64 * um_per_pixel,
+            height_um: m.bounds.height as f64 * um_per_pixel,
+            centroid_um: (m.centroid.0 * um_per_pixel, m.centroid.1 * um_per_pixel),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ScaleBarNodeConfig {
+    pub um_per_pixel: f64,
+    pub bar_length_um: f64,
+    pub bar_thickness: u32,
+    pub anchor: Anchor,
+    pub margin: u32,
+    pub color: [u8; 4],
+    /// Backing box drawn behind the bar for contrast, if set.
+    pub background: Option<[u8; 4]>,
+}
+
+impl Default for ScaleBarNodeConfig {
+    fn default() -> Self {
+        Self {
+            um_per_pixel: 1.0,
+            bar_length_um: 100.0,
+            bar_thickness: 4,
+            anchor: Anchor::BottomRight,
+            margin: 16,
+            color: [255; 4],
+            background: None,
+        }
+    }
+}
+
+impl Validate for ScaleBarNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        ensure(self.um_per_pixel > 0.0, "um_per_pixel", "must be positive")?;
+        ensure(self.bar_length_um > 0.0, "bar_length_um", "must be positive")
+    }
+}
+
+config_builder!(ScaleBarNodeConfig for ScaleBarNode {
+    um_per_pixel: f64,
+    bar_length_um: f64,
+    bar_thickness: u32,
+    anchor: Anchor,
+    margin: u32,
+    color: [u8; 4],
+    background: Option<[u8; 4]>,
+});
+
+/// Draws a calibrated scale bar and converts pixel measurements to micrometers.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct ScaleBarNode {
+    #[output]
+    pub output: Output<DynamicImage>,
+
+    #[output]
+    pub measurements_out: Output<Vec<PhysicalMeasurement>>,
+
+    #[input]
+    pub input: Input<DynamicImage>,
+
+    #[input]
+    pub measurements: Input<Vec<ObjectMeasurement>>,
+
+    pub config: ScaleBarNodeConfig,
+}
+
+impl ScaleBarNode {
+    pub fn new(config: ScaleBarNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            measurements_out: Output::new(change_observer),
+            input: Input::new(),
+            measurements: Input::new(),
+            config,
+        }
+    }
+}
+
+/// Bars longer than the image are clamped to its width, and thicker ones to its height.
+pub fn draw_scale_bar(img: &mut RgbaImage, config: &ScaleBarNodeConfig) {
+    let (width, height) = img.dimensions();
+    let length = (config.bar_length_um / config.um_per_pixel).round().clamp(1.0, width.max(1) as f64) as u32;
+    let thickness = config.bar_thickness.clamp(1, height.max(1));
+    let padding = thickness;
+    let outer = (length.saturating_add(padding * 2), thickness.saturating_add(padding * 2));
+    let (x, y) = config.anchor.position(img.dimensions(), outer, config.margin);
+    // Anything pushed off the image by the margin stays off it, at coordinates that fit an i32.
+    let x = x.clamp(-(outer.0 as i64), width as i64) as i32;
+    let y = y.clamp(-(outer.1 as i64), height as i64) as i32;
+
+    if let Some(background) = config.background {
+        let rect = DrawRect::at(x, y).of_size(outer.0, outer.1);
+        draw_filled_rect_mut(img, rect, Rgba(background));
+    }
+    let bar = DrawRect::at(x + padding as i32, y + padding as i32).of_size(length, thickness);
+    draw_filled_rect_mut(img, bar, Rgba(config.color));
+}
+
+impl Node for ScaleBarNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        if let Ok(img) = self.input.next() {
+            let mut rgba = img.into_rgba8();
+            draw_scale_bar(&mut rgba, &self.config);
+            self.output.send(DynamicImage::ImageRgba8(rgba)).map_err(|e| UpdateError::Other(e.into()))?;
+        }
+
+        if let Ok(measurements) = self.measurements.next() {
+            let physical = measurements
+                .iter()
+                .map(|m| PhysicalMeasurement::from_pixels(m, self.config.um_per_pixel))
+                .collect();
+            self.measurements_out.send(physical).map_err(|e| UpdateError::Other(e.into()))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ChromaKeyNodeConfig {
+    /// Backdrop color, e.g. `[0, 177, 64]` for a green screen.
+    pub key: [u8; 3],
+    /// Chroma distance in `0.0..=1.0` below which pixels are fully replaced.
+    pub tolerance: f32,
+    /// Width of the transition band above `tolerance`, softening the matte edge.
+    pub softness: f32,
+    /// Share of the key color spilled onto the foreground that is removed, in `0.0..=1.0`.
+    pub spill_suppression: f32,
+}
+
+impl Default for ChromaKeyNodeConfig {
+    fn default() -> Self {
+        Self { key: [0, 177, 64], tolerance: 0.15, softness: 0.1, spill_suppression: 0.5 }
+    }
+}
+
+impl Validate for ChromaKeyNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        ensure(self.tolerance >= 0.0 && self.softness >= 0.0, "tolerance", "tolerance and softness must not be negative")?;
+        ensure((0.0..=1.0).contains(&self.spill_suppression), "spill_suppression", "must be in 0..=1")
+    }
+}
+
+config_builder!(ChromaKeyNodeConfig for ChromaKeyNode {
+    key: [u8; 3],
+    tolerance: f32,
+    softness: f32,
+    spill_suppression: f32,
+});
+
+fn chroma(rgb: [f32; 3]) -> (f32, f32) {
+    let y = 0.299 * rgb[0] + 0.587 * rgb[1] + 0.114 * rgb[2];
+    ((rgb[2] - y) * 0.564, (rgb[0] - y) * 0.713)
+}
+
+/// Keys out the backdrop color, compositing over `background` if given and leaving transparency otherwise.
+pub fn chroma_key(img: &RgbaImage, background: Option<&RgbaImage>, config: &ChromaKeyNodeConfig) -> RgbaImage {
+    let key = config.key.map(|v| v as f32 / 255.0);
+    let (kb, kr) = chroma(key);
+    // Channel carrying the backdrop, whose excess over the others is spill.
+    let dominant = (0..3).max_by(|&a, &b| key[a].total_cmp(&key[b])).unwrap_or(1);
+    let softness = config.softness.max(1e-6);
+
+    let mut out = img.clone();
+    for (x, y, px) in out.enumerate_pixels_mut() {
+        let rgb = [0, 1, 2].map(|c| px[c] as f32 / 255.0);
+        let (cb, cr) = chroma(rgb);
+        let distance = ((cb - kb).powi(2) + (cr - kr).powi(2)).sqrt();
+        let matte = ((distance - config.tolerance) / softness).clamp(0.0, 1.0);
+
+        let mut fg = rgb;
+        let others = (0..3).filter(|&c| c != dominant).map(|c| rgb[c]).fold(0.0, f32::max);
+        if fg[dominant] > others {
+            fg[dominant] -= (fg[dominant] - others) * config.spill_suppression.clamp(0.0, 1.0);
+        }
+
+        let alpha = matte * px[3] as f32 / 255.0;
+        *px = match background {
+            Some(bg) => {
+                let b = bg.get_pixel(x, y);
+                let mix = |c: usize| (fg[c] * alpha * 255.0 + b[c] as f32 * (1.0 - alpha)).round() as u8;
+                Rgba([mix(0), mix(1), mix(2), b[3].max((alpha * 255.0).round() as u8)])
+            }
+            None => Rgba([fg[0], fg[1], fg[2], alpha].map(|v| (v * 255.0).round() as u8)),
+        };
+    }
+    out
+}
+
+/// Replaces a green or blue screen with transparency or a background image.
+///
+/// The most recent frame received on `background` is scaled to the size of
+/// each input frame; without one, the output carries the matte as alpha.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct ChromaKeyNode {
+    #[output]
+    pub output: Output<DynamicImage>,
+
+    #[input]
+    pub input: Input<DynamicImage>,
+
+    #[input]
+    pub background: Input<DynamicImage>,
+
+    pub config: ChromaKeyNodeConfig,
+
+    #[serde(skip)]
+    backdrop: Option<RgbaImage>,
+}
+
+impl ChromaKeyNode {
+    pub fn new(config: ChromaKeyNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            input: Input::new(),
+            background: Input::new(),
+            config,
+            backdrop: None,
+        }
+    }
+}
+
+impl Node for ChromaKeyNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        while let Ok(background) = self.background.next() {
+            self.backdrop = Some(background.into_rgba8());
+        }
+
+        if let Ok(img) = self.input.next() {
+            frame_span!("chroma_key", width = img.width(), height = img.height());
+            let img = img.into_rgba8();
+            if let Some(bg) = self.backdrop.as_mut().filter(|bg| bg.dimensions() != img.dimensions()) {
+                *bg = image::imageops::resize(bg, img.width(), img.height(), FilterType::Triangle);
+            }
+            let keyed = chroma_key(&img, self.backdrop.as_ref(), &self.config);
+            self.output.send(DynamicImage::ImageRgba8(keyed)).map_err(|e| UpdateError::Other(e.into()))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub enum WatermarkMode {
+    #[default]
+    Single,
+    /// Repeats the logo over the whole frame with the given gap between copies.
+    Tiled { spacing: u32 },
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct WatermarkNodeConfig {
+    pub logo_path: PathBuf,
+    pub mode: WatermarkMode,
+    /// Placement in [`WatermarkMode::Single`].
+    pub anchor: Anchor,
+    pub margin: u32,
+    /// Logo width as a share of the frame width; the logo's own size if unset.
+    pub scale: Option<f32>,
+    pub opacity: f32,
+}
+
+impl Default for WatermarkNodeConfig {
+    fn default() -> Self {
+        Self {
+            logo_path: PathBuf::new(),
+            mode: WatermarkMode::Single,
+            anchor: Anchor::BottomRight,
+            margin: 16,
+            scale: None,
+            opacity: 0.5,
+        }
+    }
+}
+
+impl Validate for WatermarkNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        ensure((0.0..=1.0).contains(&self.opacity), "opacity", "must be in 0..=1")?;
+        ensure(self.scale.is_none_or(|scale| scale > 0.0), "scale", "must be positive")
+    }
+}
+
+config_builder!(WatermarkNodeConfig for WatermarkNode {
+    logo_path: PathBuf,
+    mode: WatermarkMode,
+    anchor: Anchor,
+    margin: u32,
+    scale: Option<f32>,
+    opacity: f32,
+});
+
+/// Alpha-blends `logo` onto `img`, scaled by `opacity`.
+pub fn apply_watermark(img: &mut RgbaImage, logo: &RgbaImage, config: &WatermarkNodeConfig) {
+    let (lw, lh) = logo.dimensions();
+    let positions: Vec<(i64, i64)> = match config.mode {
+        WatermarkMode::Single => vec![config.anchor.position(img.dimensions(), (lw, lh), config.margin)],
+        WatermarkMode::Tiled { spacing } => {
+            let (step_x, step_y) = ((lw + spacing).max(1) as usize, (lh + spacing).max(1) as usize);
+            (0..img.height()).step_by(step_y)
+                .flat_map(|y| (0..img.width()).step_by(step_x).map(move |x| (x as i64, y as i64)))
+                .collect()
+        }
+    };
+    let opacity = config.opacity.clamp(0.0, 1.0);
+    for (ox, oy) in positions {
+        for (x, y, px) in logo.enumerate_pixels() {
+            let (tx, ty) = (ox + x as i64, oy + y as i64);
+            if tx < 0 || ty < 0 || tx >= img.width() as i64 || ty >= img.height() as i64 {
+                continue;
+            }
+            let alpha = px[3] as f32 / 255.0 * opacity;
+            let target = img.get_pixel_mut(tx as u32, ty as u32);
+            for c in 0..3 {
+                target[c] = (px[c] as f32 * alpha + target[c] as f32 * (1.0 - alpha)).round() as u8;
+            }
+        }
+    }
+}
+
+/// Overlays a logo, once at an anchor or tiled across the frame.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct WatermarkNode {
+    #[output]
+    pub output: Output<DynamicImage>,
+
+    #[input]
+    pub input: Input<DynamicImage>,
+
+    pub config: WatermarkNodeConfig,
+
+    #[serde(skip)]
+    logo: Option<RgbaImage>,
+    /// Logo resized for the current frame width.
+    #[serde(skip)]
+    scaled: Option<(u32, RgbaImage)>,
+}
+
+impl WatermarkNode {
+    pub fn new(config: WatermarkNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            input: Input::new(),
+            config,
+            logo: None,
+            scaled: None,
+        }
+    }
+}
+
+/// `logo` resized to `scale` of the frame width, cached until the frame width changes.
+fn scaled_logo<'a>(logo: &'a RgbaImage, cache: &'a mut Option<(u32, RgbaImage)>, scale: Option<f32>, frame_width: u32) -> &'a RgbaImage {
+    let Some(scale) = scale else { return logo };
+    if cache.as_ref().is_none_or(|(w, _)| *w != frame_width) {
+        let width = (frame_width as f32 * scale).round().max(1.0) as u32;
+        let height = (logo.height() as u64 * width as u64 / logo.width().max(1) as u64).max(1) as u32;
+        *cache = Some((frame_width, image::imageops::resize(logo, width, height, FilterType::Lanczos3)));
+    }
+    &cache.as_ref().expect("set above").1
+}
+
+impl Node for WatermarkNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        if self.logo.is_none() {
+            let logo = image::open(&self.config.logo_path).map_err(|e| UpdateError::Other(e.into()))?;
+            self.logo = Some(logo.into_rgba8());
+        }
+
+        if let Ok(img) = self.input.next() {
+            let mut rgba = img.into_rgba8();
+            let logo = self.logo.as_ref().expect("loaded above");
+            let logo = scaled_logo(logo, &mut self.scaled, self.config.scale, rgba.width());
+            apply_watermark(&mut rgba, logo, &self.config);
+            self.output.send(DynamicImage::ImageRgba8(rgba)).map_err(|e| UpdateError::Other(e.into()))?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,263 @@
+use flowrs::node::{Node, ChangeObserver};
+use flowrs::connection::{connect, Input, Output, RuntimeConnectable};
+
+use std::collections::BTreeMap;
+
+use image::DynamicImage;
+use anyhow::anyhow;
+
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::config::Validate;
+
+/// A node whose inputs and outputs can be looked up by index, so flows built at runtime can wire it.
+pub trait RuntimeNode: Node + RuntimeConnectable {}
+
+impl<N: Node + RuntimeConnectable> RuntimeNode for N {}
+
+/// Builds a node from its JSON config.
+pub type NodeConstructor = Box<dyn Fn(Value, Option<&ChangeObserver>) -> Result<Box<dyn RuntimeNode>, anyhow::Error> + Send + Sync>;
+
+/// Connects output `output` of `from` to input `input` of `to`, both carrying `T`.
+///
+/// Ports are numbered in declaration order, separately for inputs and outputs. Fails if
+/// either port carries another type; an index past the last port panics.
+pub fn connect_ports<T: 'static>(from: &dyn RuntimeNode, output: usize, to: &dyn RuntimeNode, input: usize) -> Result<(), anyhow::Error> {
+    let out = from.output_at(output).downcast::<Output<T>>()
+        .map_err(|_| anyhow!("Output {} does not carry {}", output, std::any::type_name::<T>()))?;
+    let inp = to.input_at(input).downcast::<Input<T>>()
+        .map_err(|_| anyhow!("Input {} does not carry {}", input, std::any::type_name::<T>()))?;
+    connect((*out).clone(), (*inp).clone());
+    Ok(())
+}
+
+/// Config of nodes that take none; any object, or `null`, is accepted.
+#[derive(Clone, Copy, Debug, Default, Deserialize)]
+pub struct NoConfig {}
+
+fn parse<C: DeserializeOwned>(name: &str, config: Value) -> Result<C, anyhow::Error> {
+    // A missing config means all defaults.
+    let config = if config.is_null() { Value::Object(Default::default()) } else { config };
+    serde_json::from_value(config).map_err(|e| anyhow!("Invalid config for '{}': {}", name, e))
+}
+
+/// Maps node type names used in declarative flows, e.g. `"decode_image"`, to constructors.
+#[derive(Default)]
+pub struct NodeRegistry {
+    constructors: BTreeMap<String, NodeConstructor>,
+}
+
+impl NodeRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `new` under `name`, replacing any previous constructor of that name.
+    pub fn register<C, N, F>(&mut self, name: &str, new: F)
+    where C: DeserializeOwned, N: RuntimeNode + 'static, F: Fn(C, Option<&ChangeObserver>) -> N + Send + Sync + 'static {
+        let key = name.to_string();
+        self.constructors.insert(name.to_string(), Box::new(move |config, change_observer| {
+            let config = parse(&key, config)?;
+            Ok(Box::new(new(config, change_observer)))
+        }));
+    }
+
+    /// Like [`NodeRegistry::register`], but rejects configs that fail [`Validate`].
+    pub fn register_validated<C, N, F>(&mut self, name: &str, new: F)
+    where C: DeserializeOwned + Validate, N: RuntimeNode + 'static, F: Fn(C, Option<&ChangeObserver>) -> N + Send + Sync + 'static {
+        let key = name.to_string();
+        self.constructors.insert(name.to_string(), Box::new(move |config, change_observer| {
+            let config: C = parse(&key, config)?;
+            config.validate().map_err(|e| anyhow!("Invalid config for '{}': {}", key, e))?;
+            Ok(Box::new(new(config, change_observer)))
+        }));
+    }
+
+    pub fn create(&self, name: &str, config: Value, change_observer: Option<&ChangeObserver>) -> Result<Box<dyn RuntimeNode>, anyhow::Error> {
+        let constructor = self.constructors.get(name).ok_or_else(|| anyhow!("Unknown node type '{}'", name))?;
+        constructor(config, change_observer)
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.constructors.contains_key(name)
+    }
+
+    /// Registered names in alphabetical order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.constructors.keys().map(String::as_str)
+    }
+
+    /// A registry holding every node of this crate enabled by the current features.
+    ///
+    /// Generic nodes are registered for `DynamicImage`; [`crate::flow::ParallelizeNode`],
+    /// [`crate::flow::PacketAdapterNode`], [`crate::config::ConfigFileWatcherNode`] and
+    /// [`crate::transform::ImageToArray3Node`] depend on further type parameters or a
+    /// wrapped node and are left out.
+    pub fn builtin() -> Self {
+        use crate::{analysis, color, control, debug, depth, document, expr, features, filter, flow, forensics, hashing};
+        use crate::{inspection, media, overlay, replay, sequence, source, storage, testing, tracking, transform, transport, video};
+
+        let mut registry = Self::new();
+
+        registry.register_validated("logo_detect", analysis::LogoDetectNode::new);
+        registry.register_validated("particle_count", analysis::ParticleCountNode::new);
+        registry.register_validated("thermal_anomaly", analysis::ThermalAnomalyNode::new);
+        registry.register_validated("quality_gate", analysis::QualityGateNode::new);
+
+        registry.register_validated("color_convert", color::ColorConvertNode::new);
+        registry.register_validated("color_calibration", color::ColorCalibrationNode::new);
+        registry.register_validated("lut", color::LutNode::new);
+        registry.register_validated("quantize", color::QuantizeNode::new);
+        registry.register("alpha_split", |_: NoConfig, co| color::AlphaSplitNode::new(co));
+        registry.register("alpha_merge", |_: NoConfig, co| color::AlphaMergeNode::new(co));
+        registry.register_validated("premultiply", color::PremultiplyNode::new);
+        registry.register_validated("colormap", color::ColormapNode::new);
+
+        registry.register_validated("auto_exposure", control::AutoExposureNode::new);
+
+        registry.register_validated("image_debug", debug::ImageDebugNode::new);
+        #[cfg(feature = "preview")]
+        registry.register_validated("preview_window", debug::PreviewWindowNode::new);
+        #[cfg(feature = "clipboard")]
+        registry.register_validated("clipboard_source", debug::ClipboardSourceNode::new);
+        #[cfg(feature = "clipboard")]
+        registry.register("clipboard_sink", |_: NoConfig, co| debug::ClipboardSinkNode::new(co));
+
+        #[cfg(feature = "dicom")]
+        registry.register_validated("dicom_decode", crate::dicom::DicomDecodeNode::new);
+
+        registry.register_validated("depth_camera", depth::DepthCameraNode::new);
+
+        registry.register_validated("document_scan", document::DocumentScanNode::new);
+        registry.register_validated("deskew", document::DeskewNode::new);
+
+        registry.register_validated("pixel_expr", expr::PixelExprNode::new);
+
+        registry.register_validated("feature_extract", features::FeatureExtractNode::new);
+        registry.register_validated("feature_match", features::FeatureMatchNode::new);
+        registry.register_validated("stitch", features::StitchNode::new);
+
+        registry.register_validated("flat_field", filter::FlatFieldNode::new);
+        registry.register_validated("deinterlace", filter::DeinterlaceNode::new);
+        registry.register_validated("lens_correction", filter::LensCorrectionNode::new);
+        registry.register_validated("temporal_denoise", filter::TemporalDenoiseNode::new);
+        registry.register_validated("denoise", filter::DenoiseNode::new);
+        registry.register_validated("inpaint", filter::InpaintNode::new);
+
+        registry.register("drop_old_frames", |_: NoConfig, co| flow::DropOldFramesNode::new(co));
+        registry.register_validated("bounded_queue", flow::BoundedQueueNode::<DynamicImage>::new);
+        registry.register("timestamp", |_: NoConfig, co| flow::TimestampNode::new(co));
+        registry.register_validated("throughput_probe", flow::ThroughputProbeNode::<DynamicImage>::new);
+        registry.register_validated("packetize", flow::PacketizeNode::new);
+        registry.register("unpack", |_: NoConfig, co| flow::UnpackNode::new(co));
+
+        registry.register_validated("watermark_verify", forensics::WatermarkVerifyNode::new);
+        registry.register_validated("ela", forensics::ElaNode::new);
+        registry.register_validated("anonymization_audit", forensics::AnonymizationAuditNode::new);
+        registry.register_validated("privacy_mask", forensics::PrivacyMaskNode::new);
+
+        #[cfg(feature = "gpu")]
+        {
+            use crate::gpu;
+            registry.register("gpu_upload", |_: NoConfig, co| gpu::GpuUploadNode::new(co));
+            registry.register("gpu_download", |_: NoConfig, co| gpu::GpuDownloadNode::new(co));
+            registry.register_validated("gpu_resize", gpu::GpuResizeNode::new);
+            registry.register_validated("gpu_blur", gpu::GpuBlurNode::new);
+            registry.register_validated("gpu_color_convert", gpu::GpuColorConvertNode::new);
+            registry.register_validated("gpu_warp", gpu::GpuWarpNode::new);
+        }
+
+        registry.register_validated("image_hash", hashing::ImageHashNode::new);
+        registry.register_validated("hash_distance", hashing::HashDistanceNode::new);
+
+        registry.register_validated("edge_measure", inspection::EdgeMeasureNode::new);
+        registry.register_validated("surface_defect", inspection::SurfaceDefectNode::new);
+        registry.register_validated("pcb_inspection", inspection::PcbInspectionNode::new);
+        registry.register_validated("barcode_grade", inspection::BarcodeGradeNode::new);
+
+        registry.register_validated("thumbnail", media::ThumbnailNode::new);
+        registry.register_validated("smart_crop", media::SmartCropNode::new);
+        #[cfg(feature = "seam-carving")]
+        registry.register_validated("seam_carve", media::SeamCarveNode::new);
+
+        #[cfg(feature = "onnx")]
+        registry.register_validated("content_moderation", crate::ml::ContentModerationNode::new);
+
+        #[cfg(feature = "mqtt")]
+        registry.register_validated("mqtt_image_publisher", crate::net::MqttImagePublisherNode::new);
+        #[cfg(feature = "mqtt")]
+        registry.register_validated("mqtt_image_subscriber", crate::net::MqttImageSubscriberNode::new);
+        #[cfg(feature = "websocket")]
+        registry.register_validated("websocket_image", crate::net::WebSocketImageNode::new);
+        #[cfg(feature = "http")]
+        registry.register_validated("http_post", crate::net::HttpPostNode::new);
+        #[cfg(feature = "http")]
+        registry.register_validated("http_image_source", crate::net::HttpImageSourceNode::new);
+        #[cfg(feature = "s3")]
+        registry.register_validated("object_store_upload", crate::net::ObjectStoreUploadNode::new);
+
+        #[cfg(feature = "ocr")]
+        registry.register_validated("ocr", crate::ocr::OcrNode::new);
+
+        registry.register_validated("scale_bar", overlay::ScaleBarNode::new);
+        registry.register_validated("chroma_key", overlay::ChromaKeyNode::new);
+        registry.register_validated("watermark", overlay::WatermarkNode::new);
+
+        registry.register_validated("replay_recorder", replay::ReplayRecorderNode::new);
+        registry.register_validated("replay", replay::ReplayNode::new);
+
+        registry.register_validated("loop", sequence::LoopNode::new);
+        registry.register_validated("dedup", sequence::DedupNode::new);
+        registry.register_validated("event_recorder", sequence::EventRecorderNode::new);
+        registry.register_validated("fps_convert", sequence::FpsConvertNode::new);
+
+        registry.register_validated("test_pattern", source::TestPatternNode::new);
+        #[cfg(feature = "picamera")]
+        registry.register_validated("pi_camera", source::PiCameraNode::new);
+        registry.register_validated("capture", source::CaptureNode::new);
+
+        registry.register_validated("retention", storage::RetentionNode::new);
+        registry.register_validated("watchfolder_transcode", storage::WatchfolderTranscodeNode::new);
+
+        #[cfg(feature = "otlp")]
+        registry.register_validated("otlp_exporter", |config, _| crate::telemetry::OtlpExporterNode::new(config));
+
+        registry.register_validated("assert_image", testing::AssertImageNode::new);
+
+        registry.register_validated("tracker", tracking::TrackerNode::new);
+        registry.register_validated("zone_analytics", tracking::ZoneAnalyticsNode::new);
+
+        registry.register_validated("decode_image", transform::DecodeImageNode::with_config);
+        registry.register_validated("scaled_decode", transform::ScaledDecodeNode::new);
+        registry.register_validated("encode_image", transform::EncodeImageNode::new);
+        registry.register_validated("resize", transform::ResizeNode::new);
+        registry.register_validated("pyramid", transform::PyramidNode::new);
+        registry.register_validated("tile_split", transform::TileSplitNode::new);
+        registry.register_validated("tile_merge", transform::TileMergeNode::new);
+
+        registry.register_validated("image_publisher", transport::ImagePublisherNode::new);
+        registry.register_validated("image_subscriber", transport::ImageSubscriberNode::new);
+        registry.register_validated("lane_split", transport::LaneSplitNode::new);
+        registry.register("lane_merge", |_: NoConfig, co| transport::LaneMergeNode::new(co));
+        #[cfg(feature = "shm")]
+        registry.register_validated("shared_mem_writer", transport::SharedMemWriterNode::new);
+        #[cfg(feature = "shm")]
+        registry.register_validated("shared_mem_reader", transport::SharedMemReaderNode::new);
+
+        registry.register_validated("hls_sink", video::HlsSinkNode::new);
+        registry.register_validated("hw_encode", video::HwEncodeNode::new);
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            use crate::web;
+            registry.register_validated("file_input", web::FileInputNode::new);
+            registry.register_validated("canvas_sink", web::CanvasSinkNode::new);
+            registry.register_validated("canvas_source", web::CanvasSourceNode::new);
+            registry.register_validated("js_callback", web::JsCallbackNode::new);
+        }
+
+        registry
+    }
+}
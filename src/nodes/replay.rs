@@ -0,0 +1,316 @@
+use flowrs::{node::{Node, UpdateError, ChangeObserver}, connection::{Input, Output}};
+use flowrs::RuntimeConnectable;
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use image::DynamicImage;
+use anyhow::anyhow;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::config::{ensure, ConfigError, Validate};
+use crate::flow::{SourceGate, StatusReporter};
+use crate::transform::{decode_image, encode_image, EncodeFormat};
+use crate::types::{NodeState, NodeStatus, SourceControl};
+
+const CONFIGS_FILE: &str = "configs.json";
+const FRAMES_FILE: &str = "frames.jsonl";
+
+/// Index entry of one recorded frame.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct ReplayFrame {
+    pub index: u64,
+    /// Time since the first frame was recorded.
+    pub offset_ms: u64,
+    /// File name within the bundle directory.
+    pub file: String,
+}
+
+/// A recorded bundle: a directory holding the node configs, a frame index and one PNG per frame.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReplayBundle {
+    pub directory: PathBuf,
+    pub configs: BTreeMap<String, Value>,
+    pub frames: Vec<ReplayFrame>,
+}
+
+impl ReplayBundle {
+    pub fn load(directory: &Path) -> Result<Self, anyhow::Error> {
+        let configs = serde_json::from_str(&std::fs::read_to_string(directory.join(CONFIGS_FILE))?)?;
+        let mut frames = Vec::new();
+        for line in BufReader::new(File::open(directory.join(FRAMES_FILE))?).lines() {
+            let line = line?;
+            // A crash while recording may leave a truncated last line behind.
+            match serde_json::from_str(&line) {
+                Ok(frame) => frames.push(frame),
+                Err(_) => break,
+            }
+        }
+        Ok(Self { directory: directory.to_path_buf(), configs, frames })
+    }
+
+    pub fn read_frame(&self, frame: &ReplayFrame) -> Result<DynamicImage, anyhow::Error> {
+        Ok(decode_image(std::fs::read(self.directory.join(&frame.file))?)?)
+    }
+}
+
+/// Writes a [`ReplayBundle`] frame by frame.
+pub struct ReplayWriter {
+    directory: PathBuf,
+    index: File,
+    next: u64,
+}
+
+impl ReplayWriter {
+    /// Creates the bundle directory, replacing the index of an earlier recording.
+    pub fn create(directory: &Path, configs: &BTreeMap<String, Value>) -> Result<Self, anyhow::Error> {
+        std::fs::create_dir_all(directory)?;
+        std::fs::write(directory.join(CONFIGS_FILE), serde_json::to_vec_pretty(configs)?)?;
+        let index = File::create(directory.join(FRAMES_FILE))?;
+        Ok(Self { directory: directory.to_path_buf(), index, next: 0 })
+    }
+
+    pub fn write(&mut self, img: &DynamicImage, offset_ms: u64) -> Result<(), anyhow::Error> {
+        let frame = ReplayFrame { index: self.next, offset_ms, file: format!("frame_{:06}.png", self.next) };
+        std::fs::write(self.directory.join(&frame.file), encode_image(img, EncodeFormat::Png)?)?;
+        // The index entry comes last so it never points to a missing frame.
+        writeln!(self.index, "{}", serde_json::to_string(&frame)?)?;
+        self.index.flush()?;
+        self.next += 1;
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ReplayRecorderNodeConfig {
+    pub directory: PathBuf,
+    /// Configs of the nodes under investigation, stored with the bundle by name.
+    pub configs: BTreeMap<String, Value>,
+    /// Recording stops after this many frames, if set.
+    pub max_frames: Option<u64>,
+}
+
+impl Validate for ReplayRecorderNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        ensure(!self.directory.as_os_str().is_empty(), "directory", "must be set")?;
+        ensure(self.max_frames.is_none_or(|n| n > 0), "max_frames", "must be positive")
+    }
+}
+
+config_builder!(ReplayRecorderNodeConfig for ReplayRecorderNode {
+    directory: PathBuf,
+    configs: BTreeMap<String, Value>,
+    max_frames: Option<u64>,
+});
+
+impl ReplayRecorderNodeConfig {
+    /// Adds the serialized config of a node to the bundle.
+    pub fn with_config<C: Serialize>(mut self, name: &str, config: &C) -> Result<Self, anyhow::Error> {
+        self.configs.insert(name.to_string(), serde_json::to_value(config)?);
+        Ok(self)
+    }
+}
+
+/// Records every input frame, with its timing and the given node configs, into a replay bundle.
+///
+/// Frames are stored as PNG, so 8 and 16-bit images replay bit-exactly.
+/// Feeding the bundle back through a [`ReplayNode`] reproduces the input of a
+/// flow, which makes intermittent bugs debuggable offline.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct ReplayRecorderNode {
+    #[input]
+    pub input: Input<DynamicImage>,
+
+    #[output]
+    pub health: Output<NodeStatus>,
+
+    pub config: ReplayRecorderNodeConfig,
+
+    #[serde(skip)]
+    writer: Option<ReplayWriter>,
+    #[serde(skip)]
+    started: Option<Instant>,
+    #[serde(skip)]
+    recorded: u64,
+    #[serde(skip)]
+    reporter: StatusReporter,
+}
+
+impl ReplayRecorderNode {
+    pub fn new(config: ReplayRecorderNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            input: Input::new(),
+            health: Output::new(change_observer),
+            config,
+            writer: None,
+            started: None,
+            recorded: 0,
+            reporter: StatusReporter::default(),
+        }
+    }
+
+    fn update(&mut self) -> Result<(), UpdateError> {
+
+        while let Ok(img) = self.input.next() {
+            if self.config.max_frames.is_some_and(|n| self.recorded >= n) {
+                continue;
+            }
+            if self.writer.is_none() {
+                let writer = ReplayWriter::create(&self.config.directory, &self.config.configs).map_err(UpdateError::Other)?;
+                self.writer = Some(writer);
+                self.started = Some(Instant::now());
+            }
+            frame_span!("replay_record", frame = self.recorded);
+            let offset_ms = self.started.map_or(0, |t| t.elapsed().as_millis() as u64);
+            let writer = self.writer.as_mut().expect("created above");
+            writer.write(&img, offset_ms).map_err(UpdateError::Other)?;
+            self.recorded += 1;
+            self.reporter.frame();
+        }
+        Ok(())
+    }
+}
+
+impl Node for ReplayRecorderNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+        let result = self.update();
+        self.reporter.track(result, &mut self.health)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum ReplayTiming {
+    /// One frame per update, independent of wall-clock time.
+    #[default]
+    Stepped,
+    /// Frames are spaced as they were recorded.
+    Original,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ReplayNodeConfig {
+    pub directory: PathBuf,
+    pub timing: ReplayTiming,
+    /// Starts over after the last frame.
+    pub looping: bool,
+}
+
+impl Validate for ReplayNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        ensure(!self.directory.as_os_str().is_empty(), "directory", "must be set")
+    }
+}
+
+config_builder!(ReplayNodeConfig for ReplayNode { directory: PathBuf, timing: ReplayTiming, looping: bool });
+
+/// Plays back a bundle written by a [`ReplayRecorderNode`].
+///
+/// The recorded configs are sent once on `configs` before the first frame, so
+/// the flow under investigation can be rebuilt exactly as it was recorded.
+/// Playback can be paused through `control`; stopping it rewinds to the start.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct ReplayNode {
+    #[output]
+    pub output: Output<DynamicImage>,
+
+    #[output]
+    pub configs: Output<BTreeMap<String, Value>>,
+
+    #[output]
+    pub health: Output<NodeStatus>,
+
+    #[input]
+    pub control: Input<SourceControl>,
+
+    pub config: ReplayNodeConfig,
+
+    #[serde(skip)]
+    bundle: Option<ReplayBundle>,
+    #[serde(skip)]
+    position: usize,
+    #[serde(skip)]
+    started: Option<Instant>,
+    #[serde(skip)]
+    reporter: StatusReporter,
+    #[serde(skip)]
+    gate: SourceGate,
+}
+
+impl ReplayNode {
+    pub fn new(config: ReplayNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            configs: Output::new(change_observer),
+            health: Output::new(change_observer),
+            control: Input::new(),
+            config,
+            bundle: None,
+            position: 0,
+            started: None,
+            reporter: StatusReporter::default(),
+            gate: SourceGate::default(),
+        }
+    }
+
+    fn update(&mut self) -> Result<(), UpdateError> {
+
+        if self.gate.poll(&mut self.control) {
+            self.bundle = None;
+            self.position = 0;
+        }
+        if !self.gate.running() {
+            // Timing restarts from the current frame on resume, rather than catching up.
+            self.started = None;
+            self.reporter.set_state(NodeState::Paused);
+            return Ok(());
+        }
+        if self.bundle.is_none() {
+            let bundle = ReplayBundle::load(&self.config.directory).map_err(UpdateError::Other)?;
+            if bundle.frames.is_empty() {
+                return Err(UpdateError::Other(anyhow!("Replay bundle {} holds no frames.", self.config.directory.display())));
+            }
+            self.configs.send(bundle.configs.clone()).map_err(|e| UpdateError::Other(e.into()))?;
+            self.bundle = Some(bundle);
+        }
+        let bundle = self.bundle.as_ref().expect("loaded above");
+
+        if self.position == bundle.frames.len() {
+            if !self.config.looping {
+                return Ok(());
+            }
+            self.position = 0;
+            self.started = None;
+        }
+        let frame = &bundle.frames[self.position];
+
+        if self.config.timing == ReplayTiming::Original {
+            let now = Instant::now();
+            let started = *self.started.get_or_insert_with(|| now.checked_sub(Duration::from_millis(frame.offset_ms)).unwrap_or(now));
+            if started.elapsed() < Duration::from_millis(frame.offset_ms) {
+                return Ok(());
+            }
+        }
+
+        frame_span!("replay", frame = self.position);
+        let img = bundle.read_frame(frame).map_err(UpdateError::Other)?;
+        self.position += 1;
+        self.reporter.frame();
+        self.gate.produced();
+        self.output.send(img).map_err(|e| UpdateError::Other(e.into()))?;
+        Ok(())
+    }
+}
+
+impl Node for ReplayNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+        let result = self.update();
+        self.reporter.track(result, &mut self.health)
+    }
+}
@@ -0,0 +1,526 @@
+use flowrs::{node::{Node, ShutdownError, UpdateError, ChangeObserver}, connection::{Input, Output}};
+use flowrs::RuntimeConnectable;
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use image::{DynamicImage, GenericImageView, GrayImage, imageops::FilterType};
+use anyhow::anyhow;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::{apply_configs, ensure, ConfigError, Validate};
+use crate::filter::match_format;
+
+/// Side length of the luma thumbnails used to compare frames.
+const THUMBNAIL_SIZE: u32 = 32;
+
+/// Mean absolute luma difference between two frames in the range `0.0..=255.0`.
+///
+/// Frames are compared on small grayscale thumbnails, which makes the measure
+/// cheap and robust against sensor noise.
+pub fn frame_distance(a: &DynamicImage, b: &DynamicImage) -> f32 {
+    thumbnail_distance(&thumbnail(a), &thumbnail(b))
+}
+
+fn thumbnail(img: &DynamicImage) -> GrayImage {
+    img.resize_exact(THUMBNAIL_SIZE, THUMBNAIL_SIZE, FilterType::Triangle).to_luma8()
+}
+
+fn thumbnail_distance(a: &GrayImage, b: &GrayImage) -> f32 {
+    let sum: u64 = a
+        .as_raw()
+        .iter()
+        .zip(b.as_raw().iter())
+        .map(|(x, y)| (*x as i16 - *y as i16).unsigned_abs() as u64)
+        .sum();
+    sum as f32 / a.as_raw().len().max(1) as f32
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub enum LoopMode {
+    /// Plays the sequence forward, then backward.
+    #[default]
+    Boomerang,
+    /// Cuts the sequence at the frame most similar to the first one.
+    BestLoopPoint { min_length: usize },
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct LoopNodeConfig {
+    pub mode: LoopMode,
+}
+
+impl Validate for LoopNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        match self.mode {
+            LoopMode::BestLoopPoint { min_length } => ensure(min_length > 1, "mode.min_length", "must be at least 2"),
+            LoopMode::Boomerang => Ok(()),
+        }
+    }
+}
+
+config_builder!(LoopNodeConfig for LoopNode { mode: LoopMode });
+
+/// Turns a short frame sequence into a seamlessly looping one.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct LoopNode {
+    #[output]
+    pub output: Output<Vec<DynamicImage>>,
+
+    #[input]
+    pub input: Input<Vec<DynamicImage>>,
+
+    pub config: LoopNodeConfig,
+}
+
+impl LoopNode {
+    pub fn new(config: LoopNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            input: Input::new(),
+            config,
+        }
+    }
+}
+
+/// Appends the reversed sequence without repeating the turning frames.
+pub fn boomerang(mut frames: Vec<DynamicImage>) -> Vec<DynamicImage> {
+    if frames.len() < 3 {
+        return frames;
+    }
+    let backward: Vec<DynamicImage> = frames[1..frames.len() - 1].iter().rev().cloned().collect();
+    frames.extend(backward);
+    frames
+}
+
+/// Index of the frame (at least `min_length` frames in) that best matches the first frame.
+pub fn best_loop_point(frames: &[DynamicImage], min_length: usize) -> Option<usize> {
+    let first = thumbnail(frames.first()?);
+    frames
+        .iter()
+        .enumerate()
+        .skip(min_length.max(1))
+        .map(|(i, f)| (i, thumbnail_distance(&first, &thumbnail(f))))
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(i, _)| i)
+}
+
+impl Node for LoopNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        if let Ok(frames) = self.input.next() {
+            frame_span!("loop", frames = frames.len());
+            let looped = match self.config.mode {
+                LoopMode::Boomerang => boomerang(frames),
+                LoopMode::BestLoopPoint { min_length } => {
+                    let end = best_loop_point(&frames, min_length)
+                        .ok_or_else(|| UpdateError::Other(anyhow!("Sequence too short to find a loop point.")))?;
+                    // The loop point closely matches frame 0, so it is dropped to avoid a stutter.
+                    frames.into_iter().take(end).collect()
+                }
+            };
+
+            self.output.send(looped).map_err(|e| UpdateError::Other(e.into()))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct DedupNodeConfig {
+    /// Minimum mean absolute luma difference (`0.0..=255.0`) for a frame to count as changed.
+    pub threshold: f32,
+}
+
+impl Default for DedupNodeConfig {
+    fn default() -> Self {
+        Self { threshold: 1.0 }
+    }
+}
+
+impl Validate for DedupNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        ensure((0.0..=255.0).contains(&self.threshold), "threshold", "must be between 0 and 255")
+    }
+}
+
+config_builder!(DedupNodeConfig for DedupNode { threshold: f32 });
+
+/// Forwards only frames that differ meaningfully from the last forwarded frame.
+///
+/// Comparing against the last forwarded frame rather than the immediate
+/// predecessor ensures slow gradual changes are still picked up eventually.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct DedupNode {
+    #[output]
+    pub output: Output<DynamicImage>,
+
+    #[input]
+    pub input: Input<DynamicImage>,
+
+    /// Replaces the threshold for all following frames, e.g. from a [`crate::config::ConfigFileWatcherNode`].
+    #[input]
+    pub reconfigure: Input<DedupNodeConfig>,
+
+    pub config: DedupNodeConfig,
+
+    #[serde(skip)]
+    reference: Option<GrayImage>,
+}
+
+impl DedupNode {
+    pub fn new(config: DedupNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            input: Input::new(),
+            reconfigure: Input::new(),
+            config,
+            reference: None,
+        }
+    }
+}
+
+impl Node for DedupNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        apply_configs(&mut self.reconfigure, &mut self.config).map_err(|e| UpdateError::Other(e.into()))?;
+
+        if let Ok(img) = self.input.next() {
+            frame_span!("dedup", width = img.width(), height = img.height());
+            let current = thumbnail(&img);
+            let changed = match &self.reference {
+                Some(reference) => thumbnail_distance(reference, &current) >= self.config.threshold,
+                None => true,
+            };
+
+            if changed {
+                self.reference = Some(current);
+                self.output.send(img).map_err(|e| UpdateError::Other(e.into()))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct EventRecorderNodeConfig {
+    /// Seconds of footage kept from before the trigger.
+    pub pre_roll_secs: f32,
+    /// Seconds recorded after the latest trigger.
+    pub post_roll_secs: f32,
+    /// Upper bound on buffered frames, regardless of the pre-roll duration, and
+    /// on the frames of one clip; longer recordings are split into several clips.
+    pub max_frames: usize,
+}
+
+impl Default for EventRecorderNodeConfig {
+    fn default() -> Self {
+        Self { pre_roll_secs: 5.0, post_roll_secs: 5.0, max_frames: 600 }
+    }
+}
+
+impl Validate for EventRecorderNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        ensure(self.pre_roll_secs.is_finite() && self.pre_roll_secs >= 0.0, "pre_roll_secs", "must not be negative")?;
+        ensure(self.post_roll_secs.is_finite() && self.post_roll_secs >= 0.0, "post_roll_secs", "must not be negative")?;
+        ensure(self.max_frames > 0, "max_frames", "must be positive")
+    }
+}
+
+config_builder!(EventRecorderNodeConfig for EventRecorderNode {
+    pre_roll_secs: f32,
+    post_roll_secs: f32,
+    max_frames: usize,
+});
+
+struct Recording {
+    frames: Vec<DynamicImage>,
+    until: Instant,
+}
+
+/// Records clips around trigger events from a rolling buffer of recent frames.
+///
+/// A `true` trigger emits the buffered pre-roll plus all frames until the
+/// post-roll has elapsed. Triggers during a recording extend it, so
+/// overlapping events produce a single clip, split every `max_frames` frames.
+/// A clip is sent on the first update after its post-roll, or on shutdown.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct EventRecorderNode {
+    #[output]
+    pub output: Output<Vec<DynamicImage>>,
+
+    #[input]
+    pub input: Input<DynamicImage>,
+
+    #[input]
+    pub trigger: Input<bool>,
+
+    pub config: EventRecorderNodeConfig,
+
+    #[serde(skip)]
+    buffer: VecDeque<(Instant, DynamicImage)>,
+    #[serde(skip)]
+    recording: Option<Recording>,
+}
+
+impl EventRecorderNode {
+    pub fn new(config: EventRecorderNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            input: Input::new(),
+            trigger: Input::new(),
+            config,
+            buffer: VecDeque::new(),
+            recording: None,
+        }
+    }
+
+    fn post_roll_end(&self, now: Instant) -> Instant {
+        now + Duration::from_secs_f32(self.config.post_roll_secs.max(0.0))
+    }
+
+    /// Sends the clip recorded so far, if any, ending the recording.
+    fn finish(&mut self) -> Result<(), anyhow::Error> {
+        match self.recording.take() {
+            Some(recording) if !recording.frames.is_empty() => Ok(self.output.send(recording.frames)?),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Node for EventRecorderNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        if self.recording.as_ref().is_some_and(|r| Instant::now() > r.until) {
+            self.finish().map_err(UpdateError::Other)?;
+        }
+
+        while let Ok(triggered) = self.trigger.next() {
+            if !triggered {
+                continue;
+            }
+            let until = self.post_roll_end(Instant::now());
+            match &mut self.recording {
+                Some(recording) => recording.until = until,
+                None => {
+                    let frames = self.buffer.drain(..).map(|(_, f)| f).collect();
+                    self.recording = Some(Recording { frames, until });
+                }
+            }
+        }
+
+        if let Ok(img) = self.input.next() {
+            frame_span!("event_record", recording = self.recording.is_some());
+            let now = Instant::now();
+            if let Some(recording) = &mut self.recording {
+                if now <= recording.until {
+                    if recording.frames.len() >= self.config.max_frames {
+                        let clip = std::mem::take(&mut recording.frames);
+                        self.output.send(clip).map_err(|e| UpdateError::Other(e.into()))?;
+                    }
+                    recording.frames.push(img);
+                    return Ok(());
+                }
+                self.finish().map_err(UpdateError::Other)?;
+            }
+
+            let pre_roll = Duration::from_secs_f32(self.config.pre_roll_secs.max(0.0));
+            self.buffer.push_back((now, img));
+            while self.buffer.len() > self.config.max_frames
+                || self.buffer.front().is_some_and(|(t, _)| now.duration_since(*t) > pre_roll)
+            {
+                self.buffer.pop_front();
+            }
+        }
+        Ok(())
+    }
+
+    fn on_shutdown(&mut self) -> Result<(), ShutdownError> {
+        self.finish().map_err(ShutdownError::Other)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum FpsConversion {
+    /// Repeats or drops frames, taking the one nearest in time.
+    #[default]
+    Duplicate,
+    /// Cross-fades between the two neighbouring input frames.
+    Blend,
+    /// Blends along block motion vectors, avoiding double edges on moving objects.
+    #[cfg(feature = "motion-interpolation")]
+    MotionCompensated,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct FpsConvertNodeConfig {
+    pub input_fps: f64,
+    pub output_fps: f64,
+    pub conversion: FpsConversion,
+}
+
+impl Default for FpsConvertNodeConfig {
+    fn default() -> Self {
+        Self { input_fps: 30.0, output_fps: 30.0, conversion: FpsConversion::Duplicate }
+    }
+}
+
+impl Validate for FpsConvertNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        ensure(self.input_fps > 0.0 && self.output_fps > 0.0, "input_fps", "frame rates must be positive")
+    }
+}
+
+config_builder!(FpsConvertNodeConfig for FpsConvertNode { input_fps: f64, output_fps: f64, conversion: FpsConversion });
+
+/// Positions of the output frames falling between input frame `index - 1` and `index`.
+///
+/// Each position is a weight in `0.0..=1.0` of the newer frame, so `1.0` means
+/// the output coincides with input frame `index`.
+pub fn output_positions(index: u64, input_fps: f64, output_fps: f64) -> Vec<f64> {
+    let ratio = output_fps / input_fps;
+    let end = index as f64 * ratio;
+    // Output frame `m` is due at `m / output_fps`; the epsilon absorbs rounding of exact multiples.
+    let first = ((end - ratio).floor() as i64 + 1).max(0);
+    let last = (end + 1e-9).floor() as i64;
+    (first..=last).map(|m| (1.0 - (end - m as f64) / ratio).clamp(0.0, 1.0)).collect()
+}
+
+/// Weighted mix of two frames of the same size, `weight` being the share of `b`.
+pub fn blend_frames(a: &DynamicImage, b: &DynamicImage, weight: f32) -> DynamicImage {
+    let mut mixed = a.to_rgb32f();
+    mixed.iter_mut().zip(b.to_rgb32f().iter()).for_each(|(x, y)| *x += weight * (y - *x));
+    match_format(mixed, b)
+}
+
+#[cfg(feature = "motion-interpolation")]
+mod motion {
+    use image::{DynamicImage, GrayImage, Rgb32FImage};
+
+    use crate::filter::match_format;
+
+    const BLOCK: u32 = 8;
+    const SEARCH: i32 = 8;
+
+    /// Motion of each block of `b` relative to `a`, by exhaustive search minimizing the absolute luma difference.
+    fn block_motion(a: &GrayImage, b: &GrayImage) -> Vec<(i32, i32)> {
+        let (w, h) = b.dimensions();
+        let (bw, bh) = (w.div_ceil(BLOCK), h.div_ceil(BLOCK));
+        let mut vectors = Vec::with_capacity((bw * bh) as usize);
+        for by in 0..bh {
+            for bx in 0..bw {
+                let cost = |dx: i32, dy: i32| -> u64 {
+                    let mut sum = 0u64;
+                    for y in by * BLOCK..((by + 1) * BLOCK).min(h) {
+                        for x in bx * BLOCK..((bx + 1) * BLOCK).min(w) {
+                            let sx = (x as i32 - dx).clamp(0, w as i32 - 1) as u32;
+                            let sy = (y as i32 - dy).clamp(0, h as i32 - 1) as u32;
+                            sum += (b.get_pixel(x, y)[0] as i32 - a.get_pixel(sx, sy)[0] as i32).unsigned_abs() as u64;
+                        }
+                    }
+                    sum
+                };
+                let mut best = ((0, 0), cost(0, 0));
+                for dy in -SEARCH..=SEARCH {
+                    for dx in -SEARCH..=SEARCH {
+                        let c = cost(dx, dy);
+                        if c < best.1 {
+                            best = ((dx, dy), c);
+                        }
+                    }
+                }
+                vectors.push(best.0);
+            }
+        }
+        vectors
+    }
+
+    /// Frame at `weight` between `a` and `b`, sampling both along the motion of each block.
+    pub fn interpolate(a: &DynamicImage, b: &DynamicImage, weight: f32) -> DynamicImage {
+        let (w, h) = (b.width(), b.height());
+        let vectors = block_motion(&a.to_luma8(), &b.to_luma8());
+        let (fa, fb) = (a.to_rgb32f(), b.to_rgb32f());
+        let bw = w.div_ceil(BLOCK);
+        let sample = |img: &Rgb32FImage, x: f32, y: f32| {
+            *img.get_pixel(x.round().clamp(0.0, (w - 1) as f32) as u32, y.round().clamp(0.0, (h - 1) as f32) as u32)
+        };
+        let out = Rgb32FImage::from_fn(w, h, |x, y| {
+            let (dx, dy) = vectors[((y / BLOCK) * bw + x / BLOCK) as usize];
+            let (dx, dy) = (dx as f32, dy as f32);
+            let pa = sample(&fa, x as f32 - weight * dx, y as f32 - weight * dy);
+            let pb = sample(&fb, x as f32 + (1.0 - weight) * dx, y as f32 + (1.0 - weight) * dy);
+            image::Rgb([0, 1, 2].map(|c| pa[c] + weight * (pb[c] - pa[c])))
+        });
+        match_format(out, b)
+    }
+}
+
+/// Resamples a stream to a fixed frame rate, so sources of different rates can be mixed in one flow.
+///
+/// Timing follows the configured input rate rather than arrival times, which
+/// keeps the output deterministic for recorded footage.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct FpsConvertNode {
+    #[output]
+    pub output: Output<DynamicImage>,
+
+    #[input]
+    pub input: Input<DynamicImage>,
+
+    pub config: FpsConvertNodeConfig,
+
+    #[serde(skip)]
+    previous: Option<DynamicImage>,
+    #[serde(skip)]
+    frames: u64,
+}
+
+impl FpsConvertNode {
+    pub fn new(config: FpsConvertNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            input: Input::new(),
+            config,
+            previous: None,
+            frames: 0,
+        }
+    }
+}
+
+impl Node for FpsConvertNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        if let Ok(img) = self.input.next() {
+            if self.config.input_fps <= 0.0 || self.config.output_fps <= 0.0 {
+                return Err(UpdateError::Other(anyhow!("Frame rates must be positive.")));
+            }
+            frame_span!("fps_convert", frame = self.frames);
+            let positions = output_positions(self.frames, self.config.input_fps, self.config.output_fps);
+            self.frames += 1;
+
+            for position in positions {
+                let weight = position as f32;
+                let frame = match &self.previous {
+                    Some(previous) if weight < 1.0 && previous.dimensions() == img.dimensions() => {
+                        match self.config.conversion {
+                            FpsConversion::Duplicate if weight < 0.5 => previous.clone(),
+                            FpsConversion::Duplicate => img.clone(),
+                            FpsConversion::Blend => blend_frames(previous, &img, weight),
+                            #[cfg(feature = "motion-interpolation")]
+                            FpsConversion::MotionCompensated => motion::interpolate(previous, &img, weight),
+                        }
+                    }
+                    _ => img.clone(),
+                };
+                self.output.send(frame).map_err(|e| UpdateError::Other(e.into()))?;
+            }
+            self.previous = Some(img);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,250 @@
+use std::collections::VecDeque;
+use std::fmt;
+
+use image::DynamicImage;
+
+use serde::{Deserialize, Serialize};
+
+use crate::color::{ColorCalibrationNode, ColorConvertNode, ColorFormat};
+use crate::filter::FlatFieldNode;
+use crate::flow::{BoundedQueueNode, DropOldFramesNode};
+use crate::source::TestPatternNode;
+use crate::testing::AssertImageNode;
+use crate::transform::TileSplitNode;
+
+/// Size and pixel format of the frames travelling along a connection.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ImageDescriptor {
+    pub width: u32,
+    pub height: u32,
+    pub format: ColorFormat,
+}
+
+impl ImageDescriptor {
+    /// Describes an image; `None` for layouts without a [`ColorFormat`].
+    pub fn of(img: &DynamicImage) -> Option<Self> {
+        let format = match img {
+            DynamicImage::ImageLuma8(_) => ColorFormat::Luma8,
+            DynamicImage::ImageLumaA8(_) => ColorFormat::LumaA8,
+            DynamicImage::ImageRgb8(_) => ColorFormat::Rgb8,
+            DynamicImage::ImageRgba8(_) => ColorFormat::Rgba8,
+            DynamicImage::ImageLuma16(_) => ColorFormat::Luma16,
+            DynamicImage::ImageRgb16(_) => ColorFormat::Rgb16,
+            DynamicImage::ImageRgba16(_) => ColorFormat::Rgba16,
+            DynamicImage::ImageRgb32F(_) => ColorFormat::Rgb32F,
+            DynamicImage::ImageRgba32F(_) => ColorFormat::Rgba32F,
+            _ => return None,
+        };
+        Some(Self { width: img.width(), height: img.height(), format })
+    }
+
+    fn with_format(self, format: ColorFormat) -> Self {
+        Self { format, ..self }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ShapeError {
+    /// A node that needs an image input has no upstream node.
+    MissingInput,
+    /// The upstream nodes of a node produce different frames.
+    ConflictingInputs(Vec<ImageDescriptor>),
+    UnsupportedFormat { format: ColorFormat, supported: Vec<ColorFormat> },
+    /// The output cannot be known before the flow runs.
+    Unknown(String),
+}
+
+impl fmt::Display for ShapeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShapeError::MissingInput => write!(f, "no input is connected"),
+            ShapeError::ConflictingInputs(inputs) => write!(f, "inputs disagree: {:?}", inputs),
+            ShapeError::UnsupportedFormat { format, supported } => {
+                write!(f, "{:?} input is not supported, expected one of {:?}", format, supported)
+            }
+            ShapeError::Unknown(reason) => write!(f, "output is unknown: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for ShapeError {}
+
+/// Nodes that can tell which frames they emit for a given input without running.
+pub trait InferShape {
+    /// Descriptor of the output for frames described by `input`; sources get `None`.
+    fn infer_shape(&self, input: Option<ImageDescriptor>) -> Result<ImageDescriptor, ShapeError>;
+}
+
+fn require(input: Option<ImageDescriptor>) -> Result<ImageDescriptor, ShapeError> {
+    input.ok_or(ShapeError::MissingInput)
+}
+
+impl InferShape for ColorConvertNode {
+    fn infer_shape(&self, input: Option<ImageDescriptor>) -> Result<ImageDescriptor, ShapeError> {
+        Ok(require(input)?.with_format(self.config.target))
+    }
+}
+
+impl InferShape for ColorCalibrationNode {
+    /// A chart can only be found in color frames; corrected frames are RGB8.
+    fn infer_shape(&self, input: Option<ImageDescriptor>) -> Result<ImageDescriptor, ShapeError> {
+        let input = require(input)?;
+        let supported = vec![
+            ColorFormat::Rgb8, ColorFormat::Rgba8, ColorFormat::Rgb16,
+            ColorFormat::Rgba16, ColorFormat::Rgb32F, ColorFormat::Rgba32F,
+        ];
+        if !supported.contains(&input.format) {
+            return Err(ShapeError::UnsupportedFormat { format: input.format, supported });
+        }
+        Ok(input.with_format(ColorFormat::Rgb8))
+    }
+}
+
+impl InferShape for FlatFieldNode {
+    fn infer_shape(&self, input: Option<ImageDescriptor>) -> Result<ImageDescriptor, ShapeError> {
+        require(input)
+    }
+}
+
+impl InferShape for DropOldFramesNode {
+    fn infer_shape(&self, input: Option<ImageDescriptor>) -> Result<ImageDescriptor, ShapeError> {
+        require(input)
+    }
+}
+
+impl InferShape for BoundedQueueNode<DynamicImage> {
+    fn infer_shape(&self, input: Option<ImageDescriptor>) -> Result<ImageDescriptor, ShapeError> {
+        require(input)
+    }
+}
+
+impl InferShape for AssertImageNode {
+    fn infer_shape(&self, input: Option<ImageDescriptor>) -> Result<ImageDescriptor, ShapeError> {
+        require(input)
+    }
+}
+
+impl InferShape for TestPatternNode {
+    fn infer_shape(&self, _input: Option<ImageDescriptor>) -> Result<ImageDescriptor, ShapeError> {
+        Ok(ImageDescriptor { width: self.config.width, height: self.config.height, format: ColorFormat::Rgb8 })
+    }
+}
+
+impl InferShape for TileSplitNode {
+    /// Describes a full tile; tiles at the right and bottom border may be smaller.
+    fn infer_shape(&self, input: Option<ImageDescriptor>) -> Result<ImageDescriptor, ShapeError> {
+        let input = require(input)?;
+        Ok(ImageDescriptor {
+            width: self.config.tile_width.min(input.width),
+            height: self.config.tile_height.min(input.height),
+            ..input
+        })
+    }
+}
+
+/// A node whose shape inference failed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ShapeMismatch {
+    pub node: String,
+    pub input: Option<ImageDescriptor>,
+    pub error: ShapeError,
+}
+
+impl fmt::Display for ShapeMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.input {
+            Some(input) => write!(
+                f, "{} given {}x{} {:?}: {}", self.node, input.width, input.height, input.format, self.error
+            ),
+            None => write!(f, "{}: {}", self.node, self.error),
+        }
+    }
+}
+
+/// Mirror of the image connections of a flow, for validating it before it runs.
+///
+/// Nodes are added with [`ShapeGraph::add`] and wired like the real flow with
+/// [`ShapeGraph::connect`]; [`ShapeGraph::validate`] then propagates the
+/// descriptors from the sources downstream.
+#[derive(Default)]
+pub struct ShapeGraph<'a> {
+    nodes: Vec<(String, &'a dyn InferShape)>,
+    edges: Vec<(usize, usize)>,
+}
+
+impl<'a> ShapeGraph<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a node and returns its handle for [`ShapeGraph::connect`].
+    pub fn add(&mut self, name: &str, node: &'a dyn InferShape) -> usize {
+        self.nodes.push((name.to_string(), node));
+        self.nodes.len() - 1
+    }
+
+    pub fn connect(&mut self, from: usize, to: usize) {
+        self.edges.push((from, to));
+    }
+
+    /// Output descriptor of every node by handle, or all mismatches found.
+    ///
+    /// Nodes downstream of a mismatch are not checked, as their input is unknown.
+    pub fn validate(&self) -> Result<Vec<ImageDescriptor>, Vec<ShapeMismatch>> {
+        let n = self.nodes.len();
+        let mut pending: Vec<usize> = vec![0; n];
+        for &(_, to) in &self.edges {
+            pending[to] += 1;
+        }
+        let mut inputs: Vec<Vec<ImageDescriptor>> = vec![Vec::new(); n];
+        let mut outputs: Vec<Option<ImageDescriptor>> = vec![None; n];
+        let mut mismatches = Vec::new();
+        let mut ready: VecDeque<usize> = (0..n).filter(|&i| pending[i] == 0).collect();
+
+        while let Some(i) = ready.pop_front() {
+            let (name, node) = &self.nodes[i];
+            let mut distinct: Vec<ImageDescriptor> = Vec::new();
+            for input in &inputs[i] {
+                if !distinct.contains(input) {
+                    distinct.push(*input);
+                }
+            }
+            let result = match distinct.as_slice() {
+                [] => node.infer_shape(None),
+                [input] => node.infer_shape(Some(*input)),
+                _ => Err(ShapeError::ConflictingInputs(distinct.clone())),
+            };
+            match result {
+                Ok(output) => {
+                    outputs[i] = Some(output);
+                    for &(_, to) in self.edges.iter().filter(|(from, _)| *from == i) {
+                        inputs[to].push(output);
+                        pending[to] -= 1;
+                        if pending[to] == 0 {
+                            ready.push_back(to);
+                        }
+                    }
+                }
+                Err(error) => mismatches.push(ShapeMismatch { node: name.clone(), input: distinct.first().copied(), error }),
+            }
+        }
+
+        if mismatches.is_empty() {
+            // Nodes on a cycle never become ready.
+            for (i, output) in outputs.iter().enumerate() {
+                if output.is_none() {
+                    mismatches.push(ShapeMismatch {
+                        node: self.nodes[i].0.clone(),
+                        input: None,
+                        error: ShapeError::Unknown("node is part of a cycle".to_string()),
+                    });
+                }
+            }
+        }
+        if mismatches.is_empty() {
+            Ok(outputs.into_iter().flatten().collect())
+        } else {
+            Err(mismatches)
+        }
+    }
+}
@@ -0,0 +1,1520 @@
+use flowrs::{node::{Node, UpdateError, ChangeObserver}, connection::{Input, Output}};
+use flowrs::RuntimeConnectable;
+
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use image::{DynamicImage, Rgb, RgbImage};
+
+use serde::{Deserialize, Serialize};
+
+use crate::color::{bgr_to_rgb, resize_to_fit};
+use crate::config::{apply_configs, ensure, ConfigError, Validate};
+use crate::error::Error;
+use crate::flow::{SourceGate, StatusReporter};
+use crate::net::backoff_delay;
+use crate::replay::ReplayBundle;
+use crate::transform::decode_image;
+use crate::types::{NodeState, NodeStatus, Rect, SourceControl};
+
+/// SMPTE-style bar colors, left to right.
+const COLOR_BARS: [[u8; 3]; 8] = [
+    [255, 255, 255],
+    [255, 255, 0],
+    [0, 255, 255],
+    [0, 255, 0],
+    [255, 0, 255],
+    [255, 0, 0],
+    [0, 0, 255],
+    [0, 0, 0],
+];
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub enum TestPattern {
+    #[default]
+    ColorBars,
+    Checkerboard { cell_size: u32 },
+    /// Horizontal luma ramp from black to white.
+    Gradient,
+    /// Uniform noise; the same seed yields the same sequence.
+    Noise { seed: u64 },
+    /// A white box bouncing over a black background, for motion and tracking tests.
+    MovingBox { size: u32, pixels_per_frame: u32 },
+    Solid([u8; 3]),
+}
+
+/// Renders frame number `frame` of a pattern.
+pub fn render_pattern(pattern: &TestPattern, width: u32, height: u32, frame: u64) -> RgbImage {
+    match pattern {
+        TestPattern::ColorBars => RgbImage::from_fn(width, height, |x, _| {
+            Rgb(COLOR_BARS[(x as u64 * COLOR_BARS.len() as u64 / width as u64) as usize])
+        }),
+        TestPattern::Checkerboard { cell_size } => {
+            let cell = (*cell_size).max(1);
+            RgbImage::from_fn(width, height, |x, y| {
+                if (x / cell + y / cell) % 2 == 0 { Rgb([255, 255, 255]) } else { Rgb([0, 0, 0]) }
+            })
+        }
+        TestPattern::Gradient => RgbImage::from_fn(width, height, |x, _| {
+            let v = (x as u64 * 255 / (width.max(2) - 1) as u64) as u8;
+            Rgb([v, v, v])
+        }),
+        TestPattern::Noise { seed } => {
+            // Xorshift must not start at zero.
+            let mut state = (seed ^ frame.wrapping_mul(0x9E37_79B9_7F4A_7C15)) | 1;
+            RgbImage::from_fn(width, height, |_, _| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                let [r, g, b, ..] = state.to_le_bytes();
+                Rgb([r, g, b])
+            })
+        }
+        TestPattern::MovingBox { size, pixels_per_frame } => {
+            let size = (*size).min(width).min(height);
+            let bounce = |range: u32, step: u64| {
+                if range == 0 {
+                    return 0;
+                }
+                let t = step % (2 * range as u64);
+                (if t < range as u64 { t } else { 2 * range as u64 - t }) as u32
+            };
+            let step = frame * *pixels_per_frame as u64;
+            let (bx, by) = (bounce(width - size, step), bounce(height - size, step));
+            RgbImage::from_fn(width, height, |x, y| {
+                let inside = x >= bx && x < bx + size && y >= by && y < by + size;
+                if inside { Rgb([255, 255, 255]) } else { Rgb([0, 0, 0]) }
+            })
+        }
+        TestPattern::Solid(color) => RgbImage::from_pixel(width, height, Rgb(*color)),
+    }
+}
+
+/// Whether the next frame of a `fps` stream is due, advancing the deadline if so.
+fn frame_due(next_due: &mut Option<Instant>, fps: f32) -> bool {
+    let now = Instant::now();
+    if next_due.is_some_and(|due| now < due) {
+        return false;
+    }
+    let interval = Duration::from_secs_f32(1.0 / fps.max(0.001));
+    // Scheduling from the previous deadline keeps the average rate exact.
+    *next_due = Some(next_due.map_or(now, |due| due.max(now - interval)) + interval);
+    true
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct TestPatternNodeConfig {
+    pub pattern: TestPattern,
+    pub width: u32,
+    pub height: u32,
+    pub fps: f32,
+    /// Stops after this many frames, if set.
+    pub frame_count: Option<u64>,
+}
+
+impl Default for TestPatternNodeConfig {
+    fn default() -> Self {
+        Self {
+            pattern: TestPattern::ColorBars,
+            width: 640,
+            height: 480,
+            fps: 30.0,
+            frame_count: None,
+        }
+    }
+}
+
+impl Validate for TestPatternNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        ensure(self.width > 0 && self.height > 0, "width", "frames must not be empty")?;
+        ensure(self.fps > 0.0, "fps", "must be positive")
+    }
+}
+
+config_builder!(TestPatternNodeConfig for TestPatternNode {
+    pattern: TestPattern,
+    width: u32,
+    height: u32,
+    fps: f32,
+    frame_count: Option<u64>,
+});
+
+/// Emits generated frames at a fixed rate, replacing a camera in tests and demos.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct TestPatternNode {
+    #[output]
+    pub output: Output<DynamicImage>,
+
+    #[output]
+    pub health: Output<NodeStatus>,
+
+    #[input]
+    pub control: Input<SourceControl>,
+
+    pub config: TestPatternNodeConfig,
+
+    #[serde(skip)]
+    frame: u64,
+    #[serde(skip)]
+    next_due: Option<Instant>,
+    #[serde(skip)]
+    gate: SourceGate,
+    #[serde(skip)]
+    reporter: StatusReporter,
+}
+
+impl TestPatternNode {
+    pub fn new(config: TestPatternNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            health: Output::new(change_observer),
+            control: Input::new(),
+            config,
+            frame: 0,
+            next_due: None,
+            gate: SourceGate::default(),
+            reporter: StatusReporter::default(),
+        }
+    }
+
+    fn update(&mut self) -> Result<(), UpdateError> {
+
+        if self.gate.poll(&mut self.control) {
+            // Stopping rewinds the pattern, so a limited run starts over.
+            self.frame = 0;
+        }
+        if !self.gate.running() {
+            self.reporter.set_state(NodeState::Paused);
+            return Ok(());
+        }
+        if self.config.frame_count.is_some_and(|n| self.frame >= n) {
+            return Ok(());
+        }
+        if !frame_due(&mut self.next_due, self.config.fps) {
+            return Ok(());
+        }
+
+        frame_span!("test_pattern", frame = self.frame);
+        let img = render_pattern(&self.config.pattern, self.config.width, self.config.height, self.frame);
+        self.frame += 1;
+        self.reporter.frame();
+        self.gate.produced();
+        self.output.send(DynamicImage::ImageRgb8(img)).map_err(|e| UpdateError::Other(e.into()))?;
+        Ok(())
+    }
+}
+
+impl Node for TestPatternNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+        let result = self.update();
+        self.reporter.track(result, &mut self.health)
+    }
+}
+
+/// A capture backend delivering frames to a [`CaptureNode`].
+pub trait FrameSource: Send {
+    /// The next frame, or `None` if there is none right now or the source is exhausted.
+    fn next_frame(&mut self) -> Result<Option<DynamicImage>, Error>;
+
+    /// Like [`FrameSource::next_frame`], but the frame as the source stores or
+    /// receives it, e.g. a JPEG file, without decoding it.
+    fn next_encoded(&mut self) -> Result<Option<Vec<u8>>, Error> {
+        Err(Error::UnsupportedFormat("Source does not deliver encoded frames".into()))
+    }
+
+    /// Asks the source to deliver only `roi` of each frame, cut out of the raw
+    /// buffer before it is decoded or converted. Returns `false` if the source
+    /// cannot, leaving the cropping to the caller, e.g. through a [`CropSource`].
+    fn set_roi(&mut self, roi: Option<Rect>) -> bool {
+        let _ = roi;
+        false
+    }
+}
+
+/// Clips `roi` to a `width`x`height` frame, failing if it starts outside the frame.
+fn clip_roi(roi: Rect, width: u32, height: u32) -> Result<Rect, Error> {
+    if roi.x >= width || roi.y >= height {
+        return Err(Error::Conversion(format!("Region at ({}, {}) lies outside the {}x{} frame", roi.x, roi.y, width, height)));
+    }
+    Ok(Rect::new(roi.x, roi.y, roi.width.min(width - roi.x), roi.height.min(height - roi.y)))
+}
+
+/// Serves frames from memory, for unit tests.
+pub struct MockSource {
+    frames: VecDeque<DynamicImage>,
+    looping: bool,
+}
+
+impl MockSource {
+    pub fn new(frames: Vec<DynamicImage>, looping: bool) -> Self {
+        Self { frames: frames.into(), looping }
+    }
+}
+
+impl FrameSource for MockSource {
+    fn next_frame(&mut self) -> Result<Option<DynamicImage>, Error> {
+        let frame = self.frames.pop_front();
+        if let (true, Some(frame)) = (self.looping, &frame) {
+            self.frames.push_back(frame.clone());
+        }
+        Ok(frame)
+    }
+}
+
+/// Generates a [`TestPattern`].
+pub struct PatternSource {
+    pattern: TestPattern,
+    width: u32,
+    height: u32,
+    frame: u64,
+}
+
+impl PatternSource {
+    pub fn new(pattern: TestPattern, width: u32, height: u32) -> Self {
+        Self { pattern, width, height, frame: 0 }
+    }
+}
+
+impl FrameSource for PatternSource {
+    fn next_frame(&mut self) -> Result<Option<DynamicImage>, Error> {
+        let img = render_pattern(&self.pattern, self.width, self.height, self.frame);
+        self.frame += 1;
+        Ok(Some(DynamicImage::ImageRgb8(img)))
+    }
+}
+
+/// Delivers only a region of the frames of another source.
+pub struct CropSource {
+    inner: Box<dyn FrameSource>,
+    roi: Rect,
+}
+
+impl CropSource {
+    pub fn new(inner: Box<dyn FrameSource>, roi: Rect) -> Self {
+        Self { inner, roi }
+    }
+
+    /// Restricts `source` to `roi`, letting the source crop its raw frames where
+    /// it can and cropping the delivered frames otherwise.
+    pub fn wrap(mut source: Box<dyn FrameSource>, roi: Option<Rect>) -> Box<dyn FrameSource> {
+        match roi {
+            Some(roi) if !source.set_roi(Some(roi)) => Box::new(Self::new(source, roi)),
+            _ => source,
+        }
+    }
+}
+
+impl FrameSource for CropSource {
+    fn next_frame(&mut self) -> Result<Option<DynamicImage>, Error> {
+        let Some(img) = self.inner.next_frame()? else { return Ok(None) };
+        // Regions reaching past the frame are clipped to it.
+        let r = clip_roi(self.roi, img.width(), img.height())?;
+        Ok(Some(img.crop_imm(r.x, r.y, r.width, r.height)))
+    }
+
+    fn set_roi(&mut self, roi: Option<Rect>) -> bool {
+        match roi {
+            Some(roi) => {
+                self.roi = roi;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Replays recorded frames: the images of a directory in file name order, or a replay bundle.
+pub struct FileReplaySource {
+    files: Vec<PathBuf>,
+    position: usize,
+    looping: bool,
+}
+
+impl FileReplaySource {
+    pub fn from_directory(directory: &std::path::Path, looping: bool) -> Result<Self, Error> {
+        let mut files = Vec::new();
+        for entry in std::fs::read_dir(directory)? {
+            let entry = entry?;
+            if entry.file_type()?.is_file() && image::ImageFormat::from_path(entry.path()).is_ok() {
+                files.push(entry.path());
+            }
+        }
+        files.sort();
+        Ok(Self { files, position: 0, looping })
+    }
+
+    pub fn from_bundle(bundle: &ReplayBundle, looping: bool) -> Self {
+        let files = bundle.frames.iter().map(|f| bundle.directory.join(&f.file)).collect();
+        Self { files, position: 0, looping }
+    }
+}
+
+impl FileReplaySource {
+    fn next_file(&mut self) -> Result<Option<Vec<u8>>, Error> {
+        if self.position == self.files.len() && self.looping {
+            self.position = 0;
+        }
+        let Some(path) = self.files.get(self.position) else { return Ok(None) };
+        self.position += 1;
+        Ok(Some(std::fs::read(path)?))
+    }
+}
+
+impl FrameSource for FileReplaySource {
+    fn next_frame(&mut self) -> Result<Option<DynamicImage>, Error> {
+        self.next_file()?.map(decode_image).transpose()
+    }
+
+    fn next_encoded(&mut self) -> Result<Option<Vec<u8>>, Error> {
+        self.next_file()
+    }
+}
+
+/// Pixel formats a GenICam camera can be asked to deliver.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum GenICamPixelFormat {
+    #[default]
+    Mono8,
+    /// Little-endian, as sent on the wire.
+    Mono16,
+    Rgb8,
+    /// Blue first in memory; swapped to RGB on arrival.
+    Bgr8,
+    BayerRg8,
+    BayerGr8,
+    BayerGb8,
+    BayerBg8,
+}
+
+impl GenICamPixelFormat {
+    fn bytes_per_pixel(self) -> usize {
+        match self {
+            GenICamPixelFormat::Mono16 => 2,
+            GenICamPixelFormat::Rgb8 | GenICamPixelFormat::Bgr8 => 3,
+            _ => 1,
+        }
+    }
+
+    /// The Bayer mosaic seen from a cell shifted by one column and/or row.
+    fn shifted(self, x: bool, y: bool) -> Self {
+        use GenICamPixelFormat::*;
+        let flip_x = |f| match f {
+            BayerRg8 => BayerGr8,
+            BayerGr8 => BayerRg8,
+            BayerGb8 => BayerBg8,
+            BayerBg8 => BayerGb8,
+            other => other,
+        };
+        let flip_y = |f| match f {
+            BayerRg8 => BayerGb8,
+            BayerGb8 => BayerRg8,
+            BayerGr8 => BayerBg8,
+            BayerBg8 => BayerGr8,
+            other => other,
+        };
+        let format = if x { flip_x(self) } else { self };
+        if y { flip_y(format) } else { format }
+    }
+}
+
+/// How exposures of a GenICam camera are started.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub enum GenICamTrigger {
+    /// The camera streams at its own rate.
+    #[default]
+    FreeRun,
+    /// One frame per edge on a hardware input line, such as `"Line1"`.
+    Hardware { line: String },
+    /// One frame per update, so the capture rate follows the node's `fps`.
+    Software,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct GenICamConfig {
+    /// Camera id as reported by aravis; the first camera found if unset.
+    pub camera: Option<String>,
+    pub pixel_format: GenICamPixelFormat,
+    pub trigger: GenICamTrigger,
+    /// Only applies to free-running cameras.
+    pub frame_rate: Option<f64>,
+    pub exposure_us: Option<f64>,
+    /// GigE Vision packet size in bytes; negotiated with the network if unset.
+    pub packet_size: Option<u32>,
+}
+
+impl Validate for GenICamConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        ensure(self.frame_rate.is_none_or(|fps| fps > 0.0), "frame_rate", "must be positive")?;
+        ensure(self.exposure_us.is_none_or(|us| us > 0.0), "exposure_us", "must be positive")?;
+        ensure(self.packet_size.is_none_or(|size| size > 0), "packet_size", "must be positive")
+    }
+}
+
+config_builder!(GenICamConfig {
+    camera: Option<String>,
+    pixel_format: GenICamPixelFormat,
+    trigger: GenICamTrigger,
+    frame_rate: Option<f64>,
+    exposure_us: Option<f64>,
+    packet_size: Option<u32>,
+});
+
+/// Converts a raw GenICam buffer to an image, demosaicing Bayer data per 2x2 cell.
+pub fn decode_raw_frame(format: GenICamPixelFormat, width: u32, height: u32, data: &[u8]) -> Result<DynamicImage, Error> {
+    let pixels = width as usize * height as usize;
+    let bytes_per_pixel = format.bytes_per_pixel();
+    if data.len() < pixels * bytes_per_pixel {
+        return Err(Error::Conversion(format!("Frame of {} bytes is too short for {}x{} {:?}", data.len(), width, height, format)));
+    }
+    let data = &data[..pixels * bytes_per_pixel];
+    let (rx, ry) = match format {
+        GenICamPixelFormat::Mono8 => {
+            return Ok(DynamicImage::ImageLuma8(image::GrayImage::from_raw(width, height, data.to_vec()).expect("length checked above")));
+        }
+        GenICamPixelFormat::Mono16 => {
+            let values = data.chunks_exact(2).map(|b| u16::from_le_bytes([b[0], b[1]])).collect();
+            return Ok(DynamicImage::ImageLuma16(image::ImageBuffer::from_raw(width, height, values).expect("length checked above")));
+        }
+        GenICamPixelFormat::Rgb8 => {
+            return Ok(DynamicImage::ImageRgb8(RgbImage::from_raw(width, height, data.to_vec()).expect("length checked above")));
+        }
+        GenICamPixelFormat::Bgr8 => {
+            return Ok(DynamicImage::ImageRgb8(bgr_to_rgb(width, height, data.to_vec()).expect("length checked above")));
+        }
+        // Offset of the red sample within each 2x2 cell.
+        GenICamPixelFormat::BayerRg8 => (0, 0),
+        GenICamPixelFormat::BayerGr8 => (1, 0),
+        GenICamPixelFormat::BayerGb8 => (0, 1),
+        GenICamPixelFormat::BayerBg8 => (1, 1),
+    };
+    if width < 2 || height < 2 {
+        return Err(Error::Conversion("Bayer frames must be at least 2x2".into()));
+    }
+    let at = |x: u32, y: u32| data[(y * width + x) as usize];
+    Ok(DynamicImage::ImageRgb8(RgbImage::from_fn(width, height, |x, y| {
+        // Odd trailing rows and columns reuse the last complete cell.
+        let (cx, cy) = ((x & !1).min(width - 2), (y & !1).min(height - 2));
+        let r = at(cx + rx, cy + ry);
+        let b = at(cx + 1 - rx, cy + 1 - ry);
+        let g = ((at(cx + 1 - rx, cy + ry) as u16 + at(cx + rx, cy + 1 - ry) as u16) / 2) as u8;
+        Rgb([r, g, b])
+    })))
+}
+
+/// A region cut out of a raw GenICam buffer, still to be decoded.
+pub struct RawFrame {
+    pub format: GenICamPixelFormat,
+    pub width: u32,
+    pub height: u32,
+    pub data: Vec<u8>,
+}
+
+/// Cuts `roi` out of a raw GenICam buffer, so only the region gets decoded.
+///
+/// Regions reaching past the frame are clipped to it. Bayer regions starting on an
+/// odd column or row come back with the format of the shifted mosaic.
+pub fn crop_raw_frame(format: GenICamPixelFormat, width: u32, height: u32, data: &[u8], roi: Rect) -> Result<RawFrame, Error> {
+    let r = clip_roi(roi, width, height)?;
+    let bytes_per_pixel = format.bytes_per_pixel();
+    let stride = width as usize * bytes_per_pixel;
+    if data.len() < stride * height as usize {
+        return Err(Error::Conversion(format!("Frame of {} bytes is too short for {}x{} {:?}", data.len(), width, height, format)));
+    }
+    let row_bytes = r.width as usize * bytes_per_pixel;
+    let data = (r.y..r.y + r.height)
+        .flat_map(|y| {
+            let start = y as usize * stride + r.x as usize * bytes_per_pixel;
+            &data[start..start + row_bytes]
+        })
+        .copied()
+        .collect();
+    Ok(RawFrame { format: format.shifted(r.x % 2 == 1, r.y % 2 == 1), width: r.width, height: r.height, data })
+}
+
+#[cfg(feature = "genicam")]
+pub use self::genicam::GenICamSource;
+
+#[cfg(feature = "genicam")]
+mod genicam {
+    use aravis::prelude::*;
+    use aravis::{AcquisitionMode, Buffer, BufferStatus, Camera, PixelFormat, Stream};
+    use image::DynamicImage;
+
+    use super::{crop_raw_frame, decode_raw_frame, Error, FrameSource, GenICamConfig, GenICamPixelFormat, GenICamTrigger};
+    use crate::types::Rect;
+
+    /// Buffers queued on the stream, so a slow consumer does not drop frames right away.
+    const STREAM_BUFFERS: usize = 4;
+    /// How long a software-triggered frame may take to arrive.
+    const TRIGGER_TIMEOUT_US: u64 = 1_000_000;
+
+    /// GigE Vision or USB3 Vision camera driven through aravis.
+    pub struct GenICamSource {
+        camera: Camera,
+        stream: Stream,
+        pixel_format: GenICamPixelFormat,
+        software_trigger: bool,
+        /// Cut out of each buffer before decoding, on top of the sensor region set at opening.
+        roi: Option<Rect>,
+    }
+
+    impl GenICamSource {
+        /// Opens the camera, reading out only `roi` of the sensor if given.
+        pub fn open(config: &GenICamConfig, roi: Option<Rect>) -> Result<Self, anyhow::Error> {
+            let camera = Camera::new(config.camera.as_deref())?;
+            camera.set_pixel_format(pixel_format(config.pixel_format))?;
+            if let Some(r) = roi {
+                camera.set_region(r.x as i32, r.y as i32, r.width as i32, r.height as i32)?;
+            }
+            if camera.is_gv_device() {
+                match config.packet_size {
+                    Some(size) => camera.gv_set_packet_size(size as i32)?,
+                    None => camera.gv_auto_packet_size()?,
+                }
+            }
+            if let Some(exposure) = config.exposure_us {
+                camera.set_exposure_time(exposure)?;
+            }
+            match &config.trigger {
+                GenICamTrigger::FreeRun => {
+                    camera.clear_triggers()?;
+                    if let Some(fps) = config.frame_rate {
+                        camera.set_frame_rate(fps)?;
+                    }
+                }
+                GenICamTrigger::Hardware { line } => camera.set_trigger(line)?,
+                GenICamTrigger::Software => camera.set_trigger("Software")?,
+            }
+            camera.set_acquisition_mode(AcquisitionMode::Continuous)?;
+
+            let payload = camera.payload()? as usize;
+            let stream = camera.create_stream()?;
+            for _ in 0..STREAM_BUFFERS {
+                stream.push_buffer(Buffer::new_allocate(payload));
+            }
+            camera.start_acquisition()?;
+            Ok(Self {
+                camera,
+                stream,
+                pixel_format: config.pixel_format,
+                software_trigger: matches!(config.trigger, GenICamTrigger::Software),
+                roi: None,
+            })
+        }
+    }
+
+    fn pixel_format(format: GenICamPixelFormat) -> PixelFormat {
+        match format {
+            GenICamPixelFormat::Mono8 => PixelFormat::MONO_8,
+            GenICamPixelFormat::Mono16 => PixelFormat::MONO_16,
+            GenICamPixelFormat::Rgb8 => PixelFormat::RGB_8_PACKED,
+            GenICamPixelFormat::Bgr8 => PixelFormat::BGR_8_PACKED,
+            GenICamPixelFormat::BayerRg8 => PixelFormat::BAYER_RG_8,
+            GenICamPixelFormat::BayerGr8 => PixelFormat::BAYER_GR_8,
+            GenICamPixelFormat::BayerGb8 => PixelFormat::BAYER_GB_8,
+            GenICamPixelFormat::BayerBg8 => PixelFormat::BAYER_BG_8,
+        }
+    }
+
+    impl FrameSource for GenICamSource {
+        fn next_frame(&mut self) -> Result<Option<DynamicImage>, Error> {
+            let buffer = if self.software_trigger {
+                self.camera.software_trigger().map_err(|e| Error::CameraRead(e.to_string()))?;
+                self.stream.timeout_pop_buffer(TRIGGER_TIMEOUT_US)
+            } else {
+                self.stream.try_pop_buffer()
+            };
+            let Some(buffer) = buffer else { return Ok(None) };
+
+            // Frames with lost packets are skipped rather than emitted half-filled.
+            let frame = (buffer.status() == BufferStatus::Success).then(|| {
+                let (width, height) = (buffer.image_width() as u32, buffer.image_height() as u32);
+                match self.roi {
+                    Some(roi) => {
+                        let raw = crop_raw_frame(self.pixel_format, width, height, &buffer.image_data(), roi)?;
+                        decode_raw_frame(raw.format, raw.width, raw.height, &raw.data)
+                    }
+                    None => decode_raw_frame(self.pixel_format, width, height, &buffer.image_data()),
+                }
+            });
+            self.stream.push_buffer(buffer);
+            frame.transpose()
+        }
+
+        fn set_roi(&mut self, roi: Option<Rect>) -> bool {
+            self.roi = roi;
+            true
+        }
+    }
+
+    impl Drop for GenICamSource {
+        fn drop(&mut self) {
+            let _ = self.camera.stop_acquisition();
+        }
+    }
+}
+
+/// Copies `height` rows of `row_bytes` out of a buffer whose rows are `stride` bytes apart.
+pub fn strip_row_padding(data: &[u8], stride: usize, row_bytes: usize, height: usize) -> Result<Vec<u8>, Error> {
+    if stride < row_bytes || data.len() < stride * height.saturating_sub(1) + row_bytes {
+        return Err(Error::Conversion(format!("Buffer of {} bytes does not hold {} rows with stride {}", data.len(), height, stride)));
+    }
+    Ok((0..height).flat_map(|y| &data[y * stride..y * stride + row_bytes]).copied().collect())
+}
+
+/// Frame formats a webcam can be asked to deliver.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum WebcamFormat {
+    /// Uncompressed YUV 4:2:2, converted to RGB on arrival.
+    #[default]
+    Yuyv,
+    /// JPEG compressed by the camera, which allows higher rates over USB 2 and
+    /// passing frames through undecoded.
+    Mjpeg,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct WebcamConfig {
+    /// Camera index, e.g. `0` for `/dev/video0` on Linux.
+    pub index: u32,
+    pub width: u32,
+    pub height: u32,
+    pub fps: u32,
+    pub format: WebcamFormat,
+}
+
+impl Default for WebcamConfig {
+    fn default() -> Self {
+        Self { index: 0, width: 1280, height: 720, fps: 30, format: WebcamFormat::Yuyv }
+    }
+}
+
+impl Validate for WebcamConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        ensure(self.width > 0 && self.height > 0, "width", "frames must not be empty")?;
+        ensure(self.fps > 0, "fps", "must be positive")
+    }
+}
+
+config_builder!(WebcamConfig {
+    index: u32,
+    width: u32,
+    height: u32,
+    fps: u32,
+    format: WebcamFormat,
+});
+
+/// Converts a packed YUYV (YUV 4:2:2) frame to RGB with BT.601 limited-range coefficients.
+pub fn yuyv_to_rgb(width: u32, height: u32, data: &[u8]) -> Result<RgbImage, Error> {
+    yuyv_region_to_rgb(width, height, data, Rect::new(0, 0, width, height))
+}
+
+/// Like [`yuyv_to_rgb`], but converts only `roi` of the frame, clipped to it.
+pub fn yuyv_region_to_rgb(width: u32, height: u32, data: &[u8], roi: Rect) -> Result<RgbImage, Error> {
+    if width % 2 != 0 {
+        return Err(Error::Conversion(format!("YUYV frames must have an even width, not {}", width)));
+    }
+    if data.len() < width as usize * height as usize * 2 {
+        return Err(Error::Conversion(format!("Frame of {} bytes is too short for {}x{} YUYV", data.len(), width, height)));
+    }
+    let r = clip_roi(roi, width, height)?;
+    Ok(RgbImage::from_fn(r.width, r.height, |x, y| {
+        let (x, y) = (x + r.x, y + r.y);
+        // Each pair of pixels shares one U and one V sample: Y0 U Y1 V.
+        let pair = ((y * width + (x & !1)) * 2) as usize;
+        let luma = data[pair + (x as usize & 1) * 2] as i32 - 16;
+        let (u, v) = (data[pair + 1] as i32 - 128, data[pair + 3] as i32 - 128);
+        let channel = |value: i32| ((value + 128) >> 8).clamp(0, 255) as u8;
+        Rgb([channel(298 * luma + 409 * v), channel(298 * luma - 100 * u - 208 * v), channel(298 * luma + 516 * u)])
+    }))
+}
+
+#[cfg(feature = "webcam")]
+pub use self::webcam::WebcamSource;
+
+#[cfg(feature = "webcam")]
+mod webcam {
+    use std::sync::mpsc::{self, Receiver, SyncSender, TryRecvError, TrySendError};
+
+    use anyhow::anyhow;
+    use image::DynamicImage;
+    use nokhwa::pixel_format::RgbFormat;
+    use nokhwa::utils::{CameraFormat, CameraIndex, FrameFormat, RequestedFormat, RequestedFormatType, Resolution};
+    use nokhwa::Camera;
+
+    use super::{yuyv_region_to_rgb, Error, FrameSource, WebcamConfig, WebcamFormat};
+    use crate::transform::decode_image;
+    use crate::types::Rect;
+
+    /// Frames the graph is too slow to take beyond these are dropped.
+    const FRAME_CAPACITY: usize = 2;
+
+    /// A frame as the camera sent it, YUYV or JPEG.
+    struct CapturedFrame {
+        data: Vec<u8>,
+        width: u32,
+        height: u32,
+    }
+
+    /// USB webcam, captured on its own thread as platform camera handles cannot move between threads.
+    pub struct WebcamSource {
+        frames: Receiver<Result<CapturedFrame, String>>,
+        format: WebcamFormat,
+        roi: Option<Rect>,
+    }
+
+    impl WebcamSource {
+        /// Opens the camera, waiting until it streams in the requested format.
+        pub fn open(config: &WebcamConfig) -> Result<Self, anyhow::Error> {
+            let (opened_tx, opened) = mpsc::sync_channel(1);
+            let (tx, frames) = mpsc::sync_channel(FRAME_CAPACITY);
+            let format = config.format;
+            let config = config.clone();
+            std::thread::spawn(move || {
+                let mut camera = match open_camera(&config) {
+                    Ok(camera) => camera,
+                    Err(e) => {
+                        let _ = opened_tx.send(Err(e.to_string()));
+                        return;
+                    }
+                };
+                let _ = opened_tx.send(Ok(()));
+                capture(&mut camera, &tx);
+                let _ = camera.stop_stream();
+            });
+            opened.recv().map_err(|_| anyhow!("Camera thread stopped"))?.map_err(|e| anyhow!(e))?;
+            Ok(Self { frames, format, roi: None })
+        }
+    }
+
+    fn open_camera(config: &WebcamConfig) -> Result<Camera, anyhow::Error> {
+        let frame_format = match config.format {
+            WebcamFormat::Yuyv => FrameFormat::YUYV,
+            WebcamFormat::Mjpeg => FrameFormat::MJPEG,
+        };
+        let format = CameraFormat::new(Resolution::new(config.width, config.height), frame_format, config.fps);
+        let requested = RequestedFormat::new::<RgbFormat>(RequestedFormatType::Closest(format));
+        let mut camera = Camera::new(CameraIndex::Index(config.index), requested)?;
+        if camera.camera_format().format() != frame_format {
+            return Err(anyhow!("Camera cannot deliver {:?} frames", config.format));
+        }
+        camera.open_stream()?;
+        Ok(camera)
+    }
+
+    fn capture(camera: &mut Camera, tx: &SyncSender<Result<CapturedFrame, String>>) {
+        loop {
+            let frame = camera.frame().map(|buffer| {
+                let resolution = buffer.resolution();
+                CapturedFrame { data: buffer.buffer().to_vec(), width: resolution.width(), height: resolution.height() }
+            });
+            let failed = frame.is_err();
+            match tx.try_send(frame.map_err(|e| e.to_string())) {
+                Ok(()) | Err(TrySendError::Full(_)) => {}
+                Err(TrySendError::Disconnected(_)) => return,
+            }
+            if failed {
+                return;
+            }
+        }
+    }
+
+    impl WebcamSource {
+        fn next_captured(&mut self) -> Result<Option<CapturedFrame>, Error> {
+            match self.frames.try_recv() {
+                Ok(Ok(frame)) => Ok(Some(frame)),
+                Ok(Err(e)) => Err(Error::CameraRead(e)),
+                Err(TryRecvError::Empty) => Ok(None),
+                Err(TryRecvError::Disconnected) => Err(Error::CameraRead("Camera thread stopped".into())),
+            }
+        }
+    }
+
+    impl FrameSource for WebcamSource {
+        fn next_frame(&mut self) -> Result<Option<DynamicImage>, Error> {
+            let Some(frame) = self.next_captured()? else { return Ok(None) };
+            match self.format {
+                WebcamFormat::Yuyv => {
+                    let roi = self.roi.unwrap_or(Rect::new(0, 0, frame.width, frame.height));
+                    Ok(Some(DynamicImage::ImageRgb8(yuyv_region_to_rgb(frame.width, frame.height, &frame.data, roi)?)))
+                }
+                WebcamFormat::Mjpeg => decode_image(frame.data).map(Some),
+            }
+        }
+
+        fn next_encoded(&mut self) -> Result<Option<Vec<u8>>, Error> {
+            match self.format {
+                WebcamFormat::Mjpeg => Ok(self.next_captured()?.map(|frame| frame.data)),
+                WebcamFormat::Yuyv => Err(Error::UnsupportedFormat("YUYV webcams do not deliver encoded frames".into())),
+            }
+        }
+
+        /// JPEG frames can only be cropped once decoded, so this only works for YUYV.
+        fn set_roi(&mut self, roi: Option<Rect>) -> bool {
+            if self.format == WebcamFormat::Mjpeg {
+                return false;
+            }
+            self.roi = roi;
+            true
+        }
+    }
+}
+
+#[cfg(feature = "picamera")]
+pub use self::picamera::{PiCameraNode, PiCameraNodeConfig, SensorMode};
+
+#[cfg(feature = "picamera")]
+mod picamera {
+    use flowrs::{node::{Node, UpdateError, ChangeObserver}, connection::{Input, Output}};
+    use flowrs::RuntimeConnectable;
+
+    use std::sync::mpsc::{self, Receiver, SyncSender, TryRecvError, TrySendError};
+    use std::time::Duration;
+
+    use anyhow::anyhow;
+    use image::{DynamicImage, RgbImage};
+    use libcamera::{
+        camera::SensorConfiguration,
+        camera_manager::CameraManager,
+        control::ControlList,
+        controls::{AnalogueGain, ExposureTime, FrameDurationLimits},
+        framebuffer_allocator::{FrameBuffer, FrameBufferAllocator},
+        framebuffer_map::MemoryMappedFrameBuffer,
+        geometry::Size,
+        pixel_format::PixelFormat,
+        request::{ReuseFlag, RequestStatus},
+        stream::StreamRole,
+    };
+    use serde::{Deserialize, Serialize};
+
+    use crate::config::{ensure, ConfigError, Validate};
+    use crate::flow::{SourceGate, StatusReporter};
+    use crate::types::{NodeState, NodeStatus, SourceControl};
+
+    use super::strip_row_padding;
+
+    /// Packed 24-bit RGB with red first in memory, which libcamera calls BGR888.
+    const PIXEL_FORMAT_RGB: PixelFormat = PixelFormat::new(u32::from_le_bytes(*b"BG24"), 0);
+
+    /// Sensor readout mode; smaller modes bin or skip pixels for higher frame rates.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+    pub struct SensorMode {
+        pub width: u32,
+        pub height: u32,
+        pub bit_depth: u32,
+    }
+
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    #[serde(default)]
+    pub struct PiCameraNodeConfig {
+        /// libcamera camera id; the first camera found if unset.
+        pub camera: Option<String>,
+        pub width: u32,
+        pub height: u32,
+        pub fps: Option<f32>,
+        /// Chosen by libcamera to suit the output size if unset.
+        pub sensor_mode: Option<SensorMode>,
+        /// Automatic exposure is used if unset.
+        pub exposure_us: Option<u32>,
+        pub analogue_gain: Option<f32>,
+    }
+
+    impl Default for PiCameraNodeConfig {
+        fn default() -> Self {
+            Self {
+                camera: None,
+                width: 1280,
+                height: 720,
+                fps: None,
+                sensor_mode: None,
+                exposure_us: None,
+                analogue_gain: None,
+            }
+        }
+    }
+
+    impl Validate for PiCameraNodeConfig {
+        fn validate(&self) -> Result<(), ConfigError> {
+            ensure(self.width > 0 && self.height > 0, "width", "frames must not be empty")?;
+            ensure(self.fps.is_none_or(|fps| fps > 0.0), "fps", "must be positive")?;
+            ensure(self.analogue_gain.is_none_or(|gain| gain >= 1.0), "analogue_gain", "must be at least 1")
+        }
+    }
+
+    config_builder!(PiCameraNodeConfig for PiCameraNode {
+        camera: Option<String>,
+        width: u32,
+        height: u32,
+        fps: Option<f32>,
+        sensor_mode: Option<SensorMode>,
+        exposure_us: Option<u32>,
+        analogue_gain: Option<f32>,
+    });
+
+    enum Captured {
+        Configured(Option<SensorMode>),
+        Frame(RgbImage),
+    }
+
+    /// Captures from a Raspberry Pi camera module through libcamera.
+    ///
+    /// The camera is driven from its own thread, since libcamera objects borrow
+    /// the camera manager. Frames the graph is too slow to take are dropped.
+    /// The sensor mode libcamera settled on is sent once capture has started.
+    #[derive(RuntimeConnectable, Deserialize, Serialize)]
+    pub struct PiCameraNode {
+        #[output]
+        pub output: Output<DynamicImage>,
+
+        #[output]
+        pub sensor_mode: Output<SensorMode>,
+
+        #[output]
+        pub health: Output<NodeStatus>,
+
+        #[input]
+        pub control: Input<SourceControl>,
+
+        pub config: PiCameraNodeConfig,
+
+        #[serde(skip)]
+        frames: Option<Receiver<Result<Captured, String>>>,
+        #[serde(skip)]
+        reporter: StatusReporter,
+        #[serde(skip)]
+        gate: SourceGate,
+    }
+
+    impl PiCameraNode {
+        pub fn new(config: PiCameraNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+            Self {
+                output: Output::new(change_observer),
+                sensor_mode: Output::new(change_observer),
+                health: Output::new(change_observer),
+                control: Input::new(),
+                config,
+                frames: None,
+                reporter: StatusReporter::default(),
+                gate: SourceGate::default(),
+            }
+        }
+
+        fn update(&mut self) -> Result<(), UpdateError> {
+
+            if self.gate.poll(&mut self.control) {
+                // Dropping the receiver ends the capture thread, which releases the camera.
+                self.frames = None;
+            }
+            if !self.gate.running() {
+                self.reporter.set_state(NodeState::Paused);
+            }
+            if self.gate.mode() == SourceControl::Stop {
+                return Ok(());
+            }
+            if self.frames.is_none() {
+                let (tx, frames) = mpsc::sync_channel(2);
+                let config = self.config.clone();
+                std::thread::spawn(move || {
+                    if let Err(e) = capture(&config, &tx) {
+                        let _ = tx.send(Err(e.to_string()));
+                    }
+                });
+                self.frames = Some(frames);
+            }
+            let frames = self.frames.as_ref().expect("started above");
+            loop {
+                match frames.try_recv() {
+                    Ok(Ok(Captured::Configured(Some(mode)))) => self.sensor_mode.send(mode).map_err(|e| UpdateError::Other(e.into()))?,
+                    Ok(Ok(Captured::Configured(None))) => {}
+                    // Frames arriving while paused are discarded, so resuming starts with a fresh one.
+                    Ok(Ok(Captured::Frame(_))) if !self.gate.running() => {}
+                    Ok(Ok(Captured::Frame(img))) => {
+                        frame_span!("picamera", width = img.width(), height = img.height());
+                        self.reporter.frame();
+                        self.gate.produced();
+                        self.output.send(DynamicImage::ImageRgb8(img)).map_err(|e| UpdateError::Other(e.into()))?;
+                    }
+                    Ok(Err(e)) => return Err(UpdateError::Other(anyhow!(e))),
+                    Err(TryRecvError::Empty) => return Ok(()),
+                    Err(TryRecvError::Disconnected) => return Err(UpdateError::Other(anyhow!("Camera thread stopped"))),
+                }
+            }
+        }
+    }
+
+    impl Node for PiCameraNode {
+        fn on_update(&mut self) -> Result<(), UpdateError> {
+            let result = self.update();
+            self.reporter.track(result, &mut self.health)
+        }
+    }
+
+    fn capture(config: &PiCameraNodeConfig, tx: &SyncSender<Result<Captured, String>>) -> Result<(), anyhow::Error> {
+        let manager = CameraManager::new()?;
+        let camera = match &config.camera {
+            Some(id) => manager.get(id),
+            None => manager.cameras().get(0),
+        }
+        .ok_or_else(|| anyhow!("No camera found"))?;
+        let mut camera = camera.acquire()?;
+
+        let mut configs = camera
+            .generate_configuration(&[StreamRole::VideoRecording])
+            .ok_or_else(|| anyhow!("Camera has no video configuration"))?;
+        {
+            let mut stream_config = configs.get_mut(0).expect("one role requested");
+            stream_config.set_pixel_format(PIXEL_FORMAT_RGB);
+            stream_config.set_size(Size { width: config.width, height: config.height });
+        }
+        if let Some(mode) = config.sensor_mode {
+            let mut sensor = SensorConfiguration::new();
+            sensor.set_output_size(mode.width, mode.height);
+            sensor.set_bit_depth(mode.bit_depth);
+            configs.set_sensor_configuration(sensor);
+        }
+        if configs.validate().is_invalid() {
+            return Err(anyhow!("Camera does not support the requested configuration"));
+        }
+        camera.configure(&mut configs)?;
+
+        let stream_config = configs.get(0).expect("one role requested");
+        if stream_config.get_pixel_format() != PIXEL_FORMAT_RGB {
+            return Err(anyhow!("Camera cannot deliver RGB frames"));
+        }
+        let size = stream_config.get_size();
+        let stride = stream_config.get_stride() as usize;
+        let stream = stream_config.stream().ok_or_else(|| anyhow!("Camera stream was not configured"))?;
+        let mode = configs.sensor_configuration().map(|sensor| {
+            let output = sensor.output_size();
+            SensorMode { width: output.width, height: output.height, bit_depth: sensor.bit_depth() }
+        });
+
+        let buffers = FrameBufferAllocator::new(&camera).alloc(&stream)?;
+        let mut requests = Vec::new();
+        for buffer in buffers {
+            let mut request = camera.create_request(None).ok_or_else(|| anyhow!("Cannot create capture request"))?;
+            request.add_buffer(&stream, MemoryMappedFrameBuffer::new(buffer)?)?;
+            requests.push(request);
+        }
+        let (completed_tx, completed) = mpsc::channel();
+        camera.on_request_completed(move |request| {
+            let _ = completed_tx.send(request);
+        });
+
+        let mut controls = ControlList::new();
+        if let Some(fps) = config.fps {
+            let frame_us = (1_000_000.0 / fps.max(0.001)) as i64;
+            controls.set(FrameDurationLimits([frame_us, frame_us]))?;
+        }
+        if let Some(exposure) = config.exposure_us {
+            controls.set(ExposureTime(exposure as i32))?;
+        }
+        if let Some(gain) = config.analogue_gain {
+            controls.set(AnalogueGain(gain))?;
+        }
+        camera.start(Some(&controls))?;
+        for request in requests {
+            camera.queue_request(request).map_err(|(_, e)| e)?;
+        }
+        if tx.send(Ok(Captured::Configured(mode))).is_err() {
+            return Ok(());
+        }
+
+        loop {
+            let mut request = completed.recv_timeout(Duration::from_secs(5))?;
+            if request.status() == RequestStatus::Complete {
+                let buffer: &MemoryMappedFrameBuffer<FrameBuffer> = request.buffer(&stream).expect("buffer added above");
+                let planes = buffer.data();
+                let data = strip_row_padding(planes[0], stride, size.width as usize * 3, size.height as usize)?;
+                let img = RgbImage::from_raw(size.width, size.height, data).expect("rows copied above");
+                match tx.try_send(Ok(Captured::Frame(img))) {
+                    Ok(()) | Err(TrySendError::Full(_)) => {}
+                    Err(TrySendError::Disconnected(_)) => break,
+                }
+            }
+            request.reuse(ReuseFlag::REUSE_BUFFERS);
+            camera.queue_request(request).map_err(|(_, e)| e)?;
+        }
+        camera.stop()?;
+        Ok(())
+    }
+}
+
+/// Capture backends that can be selected from a config.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum FrameSourceConfig {
+    Pattern { pattern: TestPattern, width: u32, height: u32 },
+    Directory { path: PathBuf, looping: bool },
+    Replay { path: PathBuf, looping: bool },
+    /// An industrial GigE Vision or USB3 Vision camera.
+    #[cfg(feature = "genicam")]
+    GenICam(GenICamConfig),
+    /// A USB webcam.
+    #[cfg(feature = "webcam")]
+    Webcam(WebcamConfig),
+}
+
+impl Default for FrameSourceConfig {
+    fn default() -> Self {
+        FrameSourceConfig::Pattern { pattern: TestPattern::ColorBars, width: 640, height: 480 }
+    }
+}
+
+impl Validate for FrameSourceConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        match self {
+            FrameSourceConfig::Pattern { width, height, .. } => ensure(*width > 0 && *height > 0, "width", "frames must not be empty"),
+            #[cfg(feature = "genicam")]
+            FrameSourceConfig::GenICam(config) => config.validate(),
+            #[cfg(feature = "webcam")]
+            FrameSourceConfig::Webcam(config) => config.validate(),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl FrameSourceConfig {
+    pub fn open(&self) -> Result<Box<dyn FrameSource>, Error> {
+        self.open_cropped(None)
+    }
+
+    /// Like [`FrameSourceConfig::open`], but the source delivers only `roi` of each frame.
+    ///
+    /// GenICam cameras read out just the region, which also saves transferring and
+    /// converting the rest, and webcams cut it out before converting; other frames
+    /// are cropped as they arrive.
+    pub fn open_cropped(&self, roi: Option<Rect>) -> Result<Box<dyn FrameSource>, Error> {
+        let open_failed = |e: anyhow::Error| Error::CameraOpen(e.to_string());
+        let source: Box<dyn FrameSource> = match self {
+            FrameSourceConfig::Pattern { pattern, width, height } => Box::new(PatternSource::new(pattern.clone(), *width, *height)),
+            FrameSourceConfig::Directory { path, looping } => {
+                Box::new(FileReplaySource::from_directory(path, *looping).map_err(|e| Error::CameraOpen(e.to_string()))?)
+            }
+            FrameSourceConfig::Replay { path, looping } => {
+                Box::new(FileReplaySource::from_bundle(&ReplayBundle::load(path).map_err(open_failed)?, *looping))
+            }
+            #[cfg(feature = "genicam")]
+            FrameSourceConfig::GenICam(config) => return Ok(Box::new(GenICamSource::open(config, roi).map_err(open_failed)?)),
+            #[cfg(feature = "webcam")]
+            FrameSourceConfig::Webcam(config) => Box::new(WebcamSource::open(config).map_err(open_failed)?),
+        };
+        Ok(CropSource::wrap(source, roi))
+    }
+
+    /// Whether sources of this kind support [`FrameSource::next_encoded`], as needed
+    /// by [`CaptureNodeConfig::passthrough`].
+    pub fn delivers_encoded(&self) -> bool {
+        match self {
+            FrameSourceConfig::Pattern { .. } => false,
+            FrameSourceConfig::Directory { .. } | FrameSourceConfig::Replay { .. } => true,
+            #[cfg(feature = "genicam")]
+            FrameSourceConfig::GenICam(_) => false,
+            #[cfg(feature = "webcam")]
+            FrameSourceConfig::Webcam(config) => config.format == WebcamFormat::Mjpeg,
+        }
+    }
+}
+
+/// How a [`CaptureNode`] rides out failures to open or read its source.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct RetryPolicy {
+    /// Failures in a row tolerated before the node reports [`CaptureStatus::Failed`],
+    /// errors and stays stopped until the next [`SourceControl::Start`]. Failures
+    /// to open and to read are counted apart, so a source that opens but never
+    /// delivers still gives up.
+    pub max_consecutive_failures: u32,
+    /// Wait before the first retry, doubling with every further failure up to `max_backoff_ms`.
+    pub backoff_ms: u64,
+    pub max_backoff_ms: u64,
+    /// Close the source after a read failure and open it again on the next attempt,
+    /// e.g. to recover a camera that was unplugged. Sources passed to
+    /// [`CaptureNode::with_source`] are never reopened.
+    pub reopen: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self { max_consecutive_failures: 5, backoff_ms: 200, max_backoff_ms: 5000, reopen: true }
+    }
+}
+
+impl Validate for RetryPolicy {
+    fn validate(&self) -> Result<(), ConfigError> {
+        ensure(self.backoff_ms <= self.max_backoff_ms, "backoff_ms", "must not exceed max_backoff_ms")
+    }
+}
+
+/// Health of a [`CaptureNode`]'s source, sent whenever it changes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum CaptureStatus {
+    Connected,
+    /// The last attempt failed; the node is waiting to retry.
+    Reconnecting,
+    /// The retry policy is exhausted; the node waits for [`SourceControl::Start`].
+    Failed,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct CaptureNodeConfig {
+    pub source: FrameSourceConfig,
+    /// Frames are taken as fast as the source delivers them if unset.
+    pub fps: Option<f32>,
+    /// Region of the frame to stream, e.g. to spare downstream nodes most of a high-resolution sensor.
+    pub roi: Option<Rect>,
+    /// Send frames undecoded on `encoded` instead of `output`, for flows that
+    /// only record or forward them; requires a source supporting [`FrameSource::next_encoded`].
+    pub passthrough: bool,
+    /// Also send every frame scaled down to this width on `preview`, e.g. for a UI,
+    /// so preview branches need no resize of their own.
+    pub preview_width: Option<u32>,
+    pub retry: RetryPolicy,
+}
+
+impl Validate for CaptureNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        self.source.validate_in("source")?;
+        self.retry.validate_in("retry")?;
+        ensure(self.roi.is_none_or(|r| r.width > 0 && r.height > 0), "roi", "must not be empty")?;
+        ensure(self.roi.is_none() || !self.passthrough, "roi", "cannot be applied to passed-through frames")?;
+        ensure(self.preview_width.is_none_or(|w| w > 0), "preview_width", "must be positive")?;
+        ensure(self.preview_width.is_none() || !self.passthrough, "preview_width", "requires decoded frames")?;
+        ensure(!self.passthrough || self.source.delivers_encoded(), "passthrough", "requires a source delivering encoded frames")?;
+        ensure(self.fps.is_none_or(|fps| fps > 0.0), "fps", "must be positive")
+    }
+}
+
+config_builder!(CaptureNodeConfig for CaptureNode {
+    source: FrameSourceConfig,
+    fps: Option<f32>,
+    roi: Option<Rect>,
+    passthrough: bool,
+    preview_width: Option<u32>,
+    retry: RetryPolicy,
+});
+
+enum Grabbed {
+    Image(DynamicImage),
+    Encoded(Vec<u8>),
+}
+
+/// Emits the frames of a [`FrameSource`].
+///
+/// The backend is opened from the config on the first update, unless one was
+/// passed to [`CaptureNode::with_source`], which lets tests and headless CI
+/// drive downstream nodes deterministically without a camera.
+///
+/// Failing sources are retried according to [`CaptureNodeConfig::retry`], so a
+/// transient read error does not stop the flow; `status` reports the outcome,
+/// and `health` the frame and error counts. Commands on `control` pause the
+/// node or close the backend until the next [`SourceControl::Start`]. A config
+/// received on `reconfigure` takes effect by reopening the backend, unless it
+/// was passed to [`CaptureNode::with_source`].
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct CaptureNode {
+    #[output]
+    pub output: Output<DynamicImage>,
+    #[output]
+    pub encoded: Output<Vec<u8>>,
+    #[output]
+    pub preview: Output<DynamicImage>,
+    #[output]
+    pub status: Output<CaptureStatus>,
+    #[output]
+    pub health: Output<NodeStatus>,
+
+    #[input]
+    pub control: Input<SourceControl>,
+
+    /// Replaces the config, e.g. from a [`crate::config::ConfigFileWatcherNode`].
+    #[input]
+    pub reconfigure: Input<CaptureNodeConfig>,
+
+    pub config: CaptureNodeConfig,
+
+    #[serde(skip)]
+    source: Option<Box<dyn FrameSource>>,
+    #[serde(skip)]
+    injected: bool,
+    #[serde(skip)]
+    next_due: Option<Instant>,
+    #[serde(skip)]
+    open_failures: u32,
+    #[serde(skip)]
+    read_failures: u32,
+    #[serde(skip)]
+    failed: bool,
+    #[serde(skip)]
+    retry_at: Option<Instant>,
+    #[serde(skip)]
+    last_status: Option<CaptureStatus>,
+    #[serde(skip)]
+    reporter: StatusReporter,
+    #[serde(skip)]
+    gate: SourceGate,
+    /// Sequence number of the next frame, for tracing.
+    #[serde(skip)]
+    frames: u64,
+}
+
+impl CaptureNode {
+    pub fn new(config: CaptureNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            encoded: Output::new(change_observer),
+            preview: Output::new(change_observer),
+            status: Output::new(change_observer),
+            health: Output::new(change_observer),
+            control: Input::new(),
+            reconfigure: Input::new(),
+            config,
+            source: None,
+            injected: false,
+            next_due: None,
+            open_failures: 0,
+            read_failures: 0,
+            failed: false,
+            retry_at: None,
+            last_status: None,
+            reporter: StatusReporter::default(),
+            gate: SourceGate::default(),
+            frames: 0,
+        }
+    }
+
+    pub fn with_source(config: CaptureNodeConfig, source: Box<dyn FrameSource>, change_observer: Option<&ChangeObserver>) -> Self {
+        let source = CropSource::wrap(source, config.roi);
+        Self { source: Some(source), injected: true, ..Self::new(config, change_observer) }
+    }
+
+    fn report(&mut self, status: CaptureStatus) -> Result<(), UpdateError> {
+        self.reporter.set_state(match status {
+            CaptureStatus::Connected => NodeState::Running,
+            CaptureStatus::Reconnecting => NodeState::Degraded,
+            CaptureStatus::Failed => NodeState::Failed,
+        });
+        if self.last_status != Some(status) {
+            self.last_status = Some(status);
+            self.status.send(status).map_err(|e| UpdateError::Other(e.into()))?;
+        }
+        Ok(())
+    }
+
+    /// Counts a failure and schedules the next attempt, or stops the node once the policy is exhausted.
+    fn fail(&mut self, error: Error) -> Result<(), UpdateError> {
+        let failures = match error {
+            Error::CameraOpen(_) => &mut self.open_failures,
+            _ => &mut self.read_failures,
+        };
+        *failures += 1;
+        let failures = *failures;
+        if failures > self.config.retry.max_consecutive_failures {
+            self.failed = true;
+            self.gate.apply(SourceControl::Stop);
+            if !self.injected {
+                self.source = None;
+            }
+            self.report(CaptureStatus::Failed)?;
+            return Err(error.into());
+        }
+        self.reporter.error(&error);
+        let retry = &self.config.retry;
+        let delay = backoff_delay(Duration::from_millis(retry.backoff_ms), Duration::from_millis(retry.max_backoff_ms), failures);
+        self.retry_at = Some(Instant::now() + delay);
+        self.report(CaptureStatus::Reconnecting)
+    }
+
+    fn update(&mut self) -> Result<(), UpdateError> {
+
+        while let Ok(config) = self.reconfigure.next() {
+            // An invalid config is rejected whole; the one in effect stays.
+            config.validate().map_err(|e| UpdateError::Other(e.into()))?;
+            self.config = config;
+            self.next_due = None;
+            if !self.injected {
+                self.source = None;
+            }
+        }
+        if self.gate.poll(&mut self.control) && !self.injected {
+            self.source = None;
+        }
+        if !self.gate.running() {
+            self.reporter.set_state(if self.failed { NodeState::Failed } else { NodeState::Paused });
+            return Ok(());
+        }
+        if self.failed {
+            // Started again after giving up: the policy applies afresh.
+            self.failed = false;
+            self.open_failures = 0;
+            self.read_failures = 0;
+            self.retry_at = None;
+        }
+        if self.retry_at.is_some_and(|at| Instant::now() < at) {
+            return Ok(());
+        }
+        if self.source.is_none() {
+            match self.config.source.open_cropped(self.config.roi) {
+                Ok(source) => self.source = Some(source),
+                Err(e) => return self.fail(e),
+            }
+            self.open_failures = 0;
+            self.report(CaptureStatus::Connected)?;
+        }
+        if let Some(fps) = self.config.fps {
+            if !frame_due(&mut self.next_due, fps) {
+                return Ok(());
+            }
+        }
+        frame_span!("capture", frame = self.frames, passthrough = self.config.passthrough);
+        let source = self.source.as_mut().expect("opened above");
+        let frame = if self.config.passthrough {
+            source.next_encoded().map(|data| data.map(Grabbed::Encoded))
+        } else {
+            source.next_frame().map(|img| img.map(Grabbed::Image))
+        };
+        match frame {
+            Ok(frame) => {
+                self.read_failures = 0;
+                self.report(CaptureStatus::Connected)?;
+                if let Some(frame) = frame {
+                    self.frames += 1;
+                    self.reporter.frame();
+                    self.gate.produced();
+                    match frame {
+                        Grabbed::Image(img) => {
+                            if let Some(width) = self.config.preview_width {
+                                // Frames already narrower than the preview are not scaled up.
+                                let preview = match img.width() > width {
+                                    true => resize_to_fit(&img, width, u32::MAX),
+                                    false => img.clone(),
+                                };
+                                self.preview.send(preview).map_err(|e| UpdateError::Other(e.into()))?;
+                            }
+                            self.output.send(img).map_err(|e| UpdateError::Other(e.into()))?;
+                        }
+                        Grabbed::Encoded(data) => self.encoded.send(data).map_err(|e| UpdateError::Other(e.into()))?,
+                    }
+                }
+            }
+            Err(e) => {
+                if self.config.retry.reopen && !self.injected {
+                    self.source = None;
+                }
+                return self.fail(e);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Node for CaptureNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+        let result = self.update();
+        self.reporter.track(result, &mut self.health)
+    }
+}
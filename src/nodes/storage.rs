@@ -0,0 +1,493 @@
+use flowrs::{node::{Node, UpdateError, ChangeObserver}, connection::{Input, Output}};
+use flowrs::RuntimeConnectable;
+
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+use image::DynamicImage;
+use image::imageops::FilterType;
+use anyhow::anyhow;
+
+use serde::{Deserialize, Serialize};
+
+use crate::color::{convert, ColorFormat};
+use crate::config::{ensure, ConfigError, Validate};
+use crate::flow::StatusReporter;
+use crate::transform::{decode_image, encode_image_with_depth, BitDepthPolicy, EncodeFormat};
+use crate::types::{NodeStatus, Rect};
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct RetentionNodeConfig {
+    /// Files older than this are deleted.
+    pub retention_secs: u64,
+    /// Directories whose files are managed in addition to explicitly reported paths.
+    pub directories: Vec<PathBuf>,
+    /// Files whose name contains any of these patterns are never deleted, e.g. `".m3u8"`.
+    pub exclude: Vec<String>,
+    /// Minimum time between two sweeps.
+    pub sweep_interval_secs: u64,
+}
+
+impl Default for RetentionNodeConfig {
+    fn default() -> Self {
+        Self {
+            retention_secs: 7 * 24 * 3600,
+            directories: Vec::new(),
+            exclude: Vec::new(),
+            sweep_interval_secs: 3600,
+        }
+    }
+}
+
+impl Validate for RetentionNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        ensure(self.retention_secs > 0, "retention_secs", "must be positive")
+    }
+}
+
+config_builder!(RetentionNodeConfig for RetentionNode {
+    retention_secs: u64,
+    directories: Vec<PathBuf>,
+    exclude: Vec<String>,
+    sweep_interval_secs: u64,
+});
+
+/// Deletes recordings once their retention period has elapsed.
+///
+/// Files are tracked from the configured directories and from paths reported
+/// on `files`; age is taken from the modification time. Paths received on
+/// `hold` (e.g. clips of flagged events) are kept indefinitely; paths are compared
+/// after canonicalization, so `./rec/clip.mp4` holds `rec/clip.mp4`. Sweeps run
+/// from `on_update`, so at least one input must be active for deletions to happen.
+/// Directories that cannot be read and files that cannot be deleted are skipped
+/// and reported together as an error once the sweep is done; such files are
+/// tried again on the next sweep.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct RetentionNode {
+    #[output]
+    pub deleted: Output<PathBuf>,
+
+    #[input]
+    pub files: Input<PathBuf>,
+
+    #[input]
+    pub hold: Input<PathBuf>,
+
+    pub config: RetentionNodeConfig,
+
+    #[serde(skip)]
+    tracked: BTreeMap<PathBuf, SystemTime>,
+    #[serde(skip)]
+    held: HashSet<PathBuf>,
+    #[serde(skip)]
+    last_sweep: Option<SystemTime>,
+}
+
+impl RetentionNode {
+    pub fn new(config: RetentionNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            deleted: Output::new(change_observer),
+            files: Input::new(),
+            hold: Input::new(),
+            config,
+            tracked: BTreeMap::new(),
+            held: HashSet::new(),
+            last_sweep: None,
+        }
+    }
+
+    fn track(&mut self, path: PathBuf) {
+        if let Ok(modified) = std::fs::metadata(&path).and_then(|m| m.modified()) {
+            self.tracked.insert(canonical(path), modified);
+        }
+    }
+
+    /// Tracks the files of every readable directory, returning the errors of the others.
+    fn scan_directories(&mut self) -> Vec<String> {
+        let mut failures = Vec::new();
+        for dir in self.config.directories.clone() {
+            let entries = match std::fs::read_dir(&dir) {
+                Ok(entries) => entries,
+                Err(e) => {
+                    failures.push(format!("{}: {}", dir.display(), e));
+                    continue;
+                }
+            };
+            for entry in entries.flatten() {
+                if entry.file_type().is_ok_and(|t| t.is_file()) {
+                    self.track(entry.path());
+                }
+            }
+        }
+        failures
+    }
+}
+
+/// `path` with symlinks, `.` and `..` resolved, or unchanged if it does not exist.
+fn canonical(path: PathBuf) -> PathBuf {
+    path.canonicalize().unwrap_or(path)
+}
+
+/// Whether a file is exempt from deletion by name or because it is on hold.
+pub fn is_excluded(path: &Path, exclude: &[String], held: &HashSet<PathBuf>) -> bool {
+    let name = path.file_name().map(|n| n.to_string_lossy()).unwrap_or_default();
+    held.contains(path) || exclude.iter().any(|p| name.contains(p.as_str()))
+}
+
+impl Node for RetentionNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        while let Ok(path) = self.hold.next() {
+            self.held.insert(canonical(path));
+        }
+        while let Ok(path) = self.files.next() {
+            self.track(path);
+        }
+
+        let now = SystemTime::now();
+        let interval = Duration::from_secs(self.config.sweep_interval_secs);
+        if self.last_sweep.is_some_and(|t| now.duration_since(t).unwrap_or_default() < interval) {
+            return Ok(());
+        }
+        self.last_sweep = Some(now);
+        frame_span!("retention_sweep", tracked = self.tracked.len());
+        let mut failures = self.scan_directories();
+
+        let retention = Duration::from_secs(self.config.retention_secs);
+        let expired: Vec<PathBuf> = self
+            .tracked
+            .iter()
+            .filter(|(path, modified)| {
+                now.duration_since(**modified).unwrap_or_default() > retention
+                    && !is_excluded(path, &self.config.exclude, &self.held)
+            })
+            .map(|(path, _)| path.clone())
+            .collect();
+
+        for path in expired {
+            match std::fs::remove_file(&path) {
+                Ok(()) => {
+                    self.tracked.remove(&path);
+                    self.deleted.send(path).map_err(|e| UpdateError::Other(e.into()))?;
+                }
+                // Someone else already removed it, which is just as good.
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                    self.tracked.remove(&path);
+                }
+                // The file stays tracked, so the next sweep tries again.
+                Err(e) => failures.push(format!("{}: {}", path.display(), e)),
+            }
+        }
+        if !failures.is_empty() {
+            return Err(UpdateError::Other(anyhow!("Retention sweep failed for {}", failures.join(", "))));
+        }
+        Ok(())
+    }
+}
+
+/// One stage of the per-file transform chain.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum TranscodeStep {
+    /// Fit within the given size, preserving the aspect ratio.
+    Resize { max_width: u32, max_height: u32 },
+    Crop(Rect),
+    Rotate90,
+    Rotate180,
+    Rotate270,
+    FlipHorizontal,
+    FlipVertical,
+    Blur { sigma: f32 },
+    Convert(ColorFormat),
+}
+
+impl TranscodeStep {
+    pub fn apply(&self, img: DynamicImage) -> DynamicImage {
+        match self {
+            TranscodeStep::Resize { max_width, max_height } => img.resize(*max_width, *max_height, FilterType::Lanczos3),
+            TranscodeStep::Crop(r) => match r.clamp_to(img.width(), img.height()) {
+                Some(r) => img.crop_imm(r.x, r.y, r.width, r.height),
+                None => img,
+            },
+            TranscodeStep::Rotate90 => img.rotate90(),
+            TranscodeStep::Rotate180 => img.rotate180(),
+            TranscodeStep::Rotate270 => img.rotate270(),
+            TranscodeStep::FlipHorizontal => img.fliph(),
+            TranscodeStep::FlipVertical => img.flipv(),
+            TranscodeStep::Blur { sigma } => img.blur(*sigma),
+            TranscodeStep::Convert(format) => convert(img, *format),
+        }
+    }
+}
+
+/// What happens to a source file after it was converted successfully.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub enum SourceDisposition {
+    #[default]
+    Keep,
+    Delete,
+    MoveTo(PathBuf),
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct WatchfolderTranscodeNodeConfig {
+    pub input_dir: PathBuf,
+    pub output_dir: PathBuf,
+    /// Lowercase file extensions to pick up; all files if empty.
+    pub extensions: Vec<String>,
+    pub steps: Vec<TranscodeStep>,
+    pub format: EncodeFormat,
+    pub bit_depth: BitDepthPolicy,
+    pub on_success: SourceDisposition,
+    /// Failed sources are moved here, if set, so they are not retried.
+    pub error_dir: Option<PathBuf>,
+    pub poll_interval_ms: u64,
+    /// Files modified more recently than this are assumed to still be written.
+    pub settle_ms: u64,
+}
+
+impl Default for WatchfolderTranscodeNodeConfig {
+    fn default() -> Self {
+        Self {
+            input_dir: PathBuf::new(),
+            output_dir: PathBuf::new(),
+            extensions: Vec::new(),
+            steps: Vec::new(),
+            format: EncodeFormat::Png,
+            bit_depth: BitDepthPolicy::Preserve,
+            on_success: SourceDisposition::Keep,
+            error_dir: None,
+            poll_interval_ms: 1000,
+            settle_ms: 2000,
+        }
+    }
+}
+
+impl Validate for WatchfolderTranscodeNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        ensure(!self.input_dir.as_os_str().is_empty(), "input_dir", "must be set")?;
+        ensure(!self.output_dir.as_os_str().is_empty(), "output_dir", "must be set")?;
+        self.format.validate_in("format")?;
+        if let SourceDisposition::MoveTo(dir) = &self.on_success {
+            ensure(dir != &self.input_dir, "on_success", "must not move sources back into the input directory")?;
+        }
+        ensure(self.error_dir.as_ref() != Some(&self.input_dir), "error_dir", "must differ from the input directory")
+    }
+}
+
+config_builder!(WatchfolderTranscodeNodeConfig for WatchfolderTranscodeNode {
+    input_dir: PathBuf,
+    output_dir: PathBuf,
+    extensions: Vec<String>,
+    steps: Vec<TranscodeStep>,
+    format: EncodeFormat,
+    bit_depth: BitDepthPolicy,
+    on_success: SourceDisposition,
+    error_dir: Option<PathBuf>,
+    poll_interval_ms: u64,
+    settle_ms: u64,
+});
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct TranscodeReport {
+    pub source: PathBuf,
+    pub destination: Option<PathBuf>,
+    pub error: Option<String>,
+    pub succeeded: u64,
+    pub failed: u64,
+    /// Files discovered but not yet converted.
+    pub pending: usize,
+}
+
+fn extension(format: EncodeFormat) -> &'static str {
+    match format {
+        EncodeFormat::Png => "png",
+        EncodeFormat::Jpeg { .. } => "jpg",
+        EncodeFormat::Bmp => "bmp",
+        EncodeFormat::Gif => "gif",
+        EncodeFormat::Tiff => "tiff",
+        EncodeFormat::Avif { .. } => "avif",
+        EncodeFormat::Jxl { .. } => "jxl",
+    }
+}
+
+/// Batch image converter: watches a folder and converts every new file.
+///
+/// Each update converts at most one file, decoding it, running the configured
+/// steps and writing the result to the output directory. Failures are
+/// reported per file and never stop the node.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct WatchfolderTranscodeNode {
+    #[output]
+    pub report: Output<TranscodeReport>,
+
+    #[output]
+    pub health: Output<NodeStatus>,
+
+    pub config: WatchfolderTranscodeNodeConfig,
+
+    #[serde(skip)]
+    queue: VecDeque<PathBuf>,
+    #[serde(skip)]
+    seen: HashSet<PathBuf>,
+    /// Source each destination was written from, so sources sharing a stem do not overwrite each other.
+    #[serde(skip)]
+    written: HashMap<PathBuf, PathBuf>,
+    #[serde(skip)]
+    last_poll: Option<Instant>,
+    #[serde(skip)]
+    counts: (u64, u64),
+    #[serde(skip)]
+    reporter: StatusReporter,
+}
+
+impl WatchfolderTranscodeNode {
+    pub fn new(config: WatchfolderTranscodeNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            report: Output::new(change_observer),
+            health: Output::new(change_observer),
+            config,
+            queue: VecDeque::new(),
+            seen: HashSet::new(),
+            written: HashMap::new(),
+            last_poll: None,
+            counts: (0, 0),
+            reporter: StatusReporter::default(),
+        }
+    }
+
+    fn poll(&mut self) -> Result<(), std::io::Error> {
+        let settle = Duration::from_millis(self.config.settle_ms);
+        let mut found = Vec::new();
+        for entry in std::fs::read_dir(&self.config.input_dir)? {
+            let entry = entry?;
+            let path = entry.path();
+            let meta = entry.metadata()?;
+            let ext = path.extension().map(|e| e.to_string_lossy().to_lowercase()).unwrap_or_default();
+            let settled = meta.modified().ok().and_then(|m| m.elapsed().ok()).is_some_and(|age| age >= settle);
+            let wanted = self.config.extensions.is_empty() || self.config.extensions.contains(&ext);
+            if meta.is_file() && wanted && settled && !self.seen.contains(&path) {
+                found.push(path);
+            }
+        }
+        found.sort();
+        for path in found {
+            self.seen.insert(path.clone());
+            self.queue.push_back(path);
+        }
+        Ok(())
+    }
+
+    /// Whether writing `destination` for `source` would clobber another source or its output.
+    fn is_taken(&self, destination: &Path, source: &Path) -> bool {
+        match self.written.get(destination) {
+            Some(from) => from != source,
+            // Anything already in the watched folder is a source of its own.
+            None => destination.exists() && same_file(&self.config.output_dir, &self.config.input_dir),
+        }
+    }
+
+    /// Output path for `source`; sources sharing a stem with an earlier one keep their
+    /// extension in the name, e.g. `a.png` and `a-tiff.png` for `a.jpg` and `a.tiff`.
+    fn destination(&self, source: &Path) -> Result<PathBuf, anyhow::Error> {
+        let stem = source.file_stem().unwrap_or_default().to_string_lossy();
+        let ext = extension(self.config.format);
+        let mut destination = self.config.output_dir.join(format!("{}.{}", stem, ext));
+        if destination == source || same_file(&destination, source) {
+            return Err(anyhow!("Converting {} would overwrite the source.", source.display()));
+        }
+        if self.is_taken(&destination, source) {
+            let source_ext = source.extension().unwrap_or_default().to_string_lossy();
+            destination = self.config.output_dir.join(format!("{}-{}.{}", stem, source_ext, ext));
+        }
+        if self.is_taken(&destination, source) {
+            return Err(anyhow!("Output {} would overwrite another file.", destination.display()));
+        }
+        Ok(destination)
+    }
+
+    fn transcode(&mut self, source: &Path) -> Result<PathBuf, anyhow::Error> {
+        let destination = self.destination(source)?;
+        let img = decode_image(std::fs::read(source)?)?;
+        let img = self.config.steps.iter().fold(img, |img, step| step.apply(img));
+        let data = encode_image_with_depth(&img, self.config.format, self.config.bit_depth)?;
+
+        std::fs::create_dir_all(&self.config.output_dir)?;
+        // Writing to a temporary name first keeps downstream watchers from seeing partial files.
+        let partial = destination.with_extension("partial");
+        std::fs::write(&partial, data)?;
+        std::fs::rename(&partial, &destination)?;
+        self.written.insert(destination.clone(), source.to_path_buf());
+
+        match &self.config.on_success {
+            SourceDisposition::Keep => {}
+            SourceDisposition::Delete => std::fs::remove_file(source)?,
+            SourceDisposition::MoveTo(dir) => move_into(source, dir)?,
+        }
+        Ok(destination)
+    }
+}
+
+/// Whether both paths exist and resolve to the same file, e.g. through `..` or a symlinked directory.
+fn same_file(a: &Path, b: &Path) -> bool {
+    matches!((a.canonicalize(), b.canonicalize()), (Ok(a), Ok(b)) if a == b)
+}
+
+fn move_into(source: &Path, dir: &Path) -> Result<(), std::io::Error> {
+    std::fs::create_dir_all(dir)?;
+    std::fs::rename(source, dir.join(source.file_name().unwrap_or_default()))
+}
+
+impl WatchfolderTranscodeNode {
+    fn update(&mut self) -> Result<(), UpdateError> {
+
+        let interval = Duration::from_millis(self.config.poll_interval_ms);
+        if self.queue.is_empty() && self.last_poll.is_none_or(|t| t.elapsed() >= interval) {
+            self.last_poll = Some(Instant::now());
+            self.poll().map_err(|e| UpdateError::Other(e.into()))?;
+        }
+
+        let Some(source) = self.queue.pop_front() else { return Ok(()) };
+        frame_span!("watchfolder_transcode", frame = self.counts.0 + self.counts.1, pending = self.queue.len());
+        let (destination, error) = match self.transcode(&source) {
+            Ok(destination) => {
+                self.counts.0 += 1;
+                self.reporter.frame();
+                // Outputs written into the watched folder must not be converted again.
+                self.seen.insert(destination.clone());
+                (Some(destination), None)
+            }
+            Err(e) => {
+                self.counts.1 += 1;
+                self.reporter.error(&e);
+                let mut error = e.to_string();
+                if let Some(dir) = &self.config.error_dir {
+                    if let Err(e) = move_into(&source, dir) {
+                        error = format!("{}; moving to error directory failed: {}", error, e);
+                    }
+                }
+                (None, Some(error))
+            }
+        };
+
+        let report = TranscodeReport {
+            source,
+            destination,
+            error,
+            succeeded: self.counts.0,
+            failed: self.counts.1,
+            pending: self.queue.len(),
+        };
+        self.report.send(report).map_err(|e| UpdateError::Other(e.into()))?;
+        Ok(())
+    }
+}
+
+impl Node for WatchfolderTranscodeNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+        let result = self.update();
+        self.reporter.track(result, &mut self.health)
+    }
+}
@@ -0,0 +1,97 @@
+use flowrs::node::{Node, UpdateError, ShutdownError};
+use flowrs::RuntimeConnectable;
+
+use opentelemetry::KeyValue;
+use opentelemetry::sdk::{trace, Resource};
+use opentelemetry_otlp::WithExportConfig;
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::layer::SubscriberExt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::{ensure, ConfigError, Validate};
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct OtlpExporterNodeConfig {
+    /// OTLP/HTTP traces endpoint, e.g. `http://localhost:4318/v1/traces`.
+    pub endpoint: String,
+    pub service_name: String,
+    /// Most verbose span level exported: `error`, `warn`, `info`, `debug` or `trace`.
+    /// Per-frame node spans are emitted at `debug`, so coarser levels drop them.
+    pub level: String,
+}
+
+impl Default for OtlpExporterNodeConfig {
+    fn default() -> Self {
+        Self {
+            endpoint: "http://localhost:4318/v1/traces".into(),
+            service_name: "flowrs-img".into(),
+            level: "debug".into(),
+        }
+    }
+}
+
+impl Validate for OtlpExporterNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        ensure(!self.endpoint.is_empty(), "endpoint", "must be set")?;
+        ensure(self.level.parse::<LevelFilter>().is_ok(), "level", "must be error, warn, info, debug or trace")
+    }
+}
+
+config_builder!(OtlpExporterNodeConfig { endpoint: String, service_name: String, level: String });
+
+fn install(config: &OtlpExporterNodeConfig) -> Result<(), anyhow::Error> {
+    let exporter = opentelemetry_otlp::new_exporter().http().with_endpoint(config.endpoint.clone());
+    let resource = Resource::new(vec![KeyValue::new("service.name", config.service_name.clone())]);
+    let tracer = opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(exporter)
+        .with_trace_config(trace::config().with_resource(resource))
+        .install_simple()?;
+    let level: LevelFilter = config.level.parse()?;
+    let subscriber = tracing_subscriber::registry()
+        .with(tracing_opentelemetry::layer().with_tracer(tracer))
+        .with(level);
+    tracing::subscriber::set_global_default(subscriber)?;
+    Ok(())
+}
+
+/// Exports the tracing spans of all nodes in the process to an OTLP collector.
+///
+/// Add one instance to a flow; it installs the global subscriber on its first
+/// update and flushes pending spans on shutdown.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct OtlpExporterNode {
+    pub config: OtlpExporterNodeConfig,
+
+    #[serde(skip)]
+    installed: bool,
+}
+
+impl OtlpExporterNode {
+    pub fn new(config: OtlpExporterNodeConfig) -> Self {
+        Self {
+            config,
+            installed: false,
+        }
+    }
+}
+
+impl Node for OtlpExporterNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        if !self.installed {
+            install(&self.config).map_err(UpdateError::Other)?;
+            self.installed = true;
+        }
+        Ok(())
+    }
+
+    fn on_shutdown(&mut self) -> Result<(), ShutdownError> {
+        if self.installed {
+            opentelemetry::global::shutdown_tracer_provider();
+        }
+        Ok(())
+    }
+}
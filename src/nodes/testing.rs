@@ -0,0 +1,205 @@
+use flowrs::{node::{Node, UpdateError, ChangeObserver}, connection::{Input, Output}};
+use flowrs::RuntimeConnectable;
+
+use std::path::PathBuf;
+
+use image::{DynamicImage, GenericImageView, GrayImage, Rgb, RgbImage, RgbaImage};
+use anyhow::anyhow;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::{ensure, ConfigError, Validate};
+use crate::transform::decode_image;
+
+/// How closely a frame has to match its reference.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub enum ImageTolerance {
+    /// Every channel of every pixel must be identical.
+    #[default]
+    Exact,
+    /// Channels may differ by `max_difference`; at most `max_mismatched` of all pixels (`0.0..=1.0`) may exceed it.
+    PerPixel { max_difference: u8, max_mismatched: f32 },
+    /// The mean structural similarity of the luma must reach `min_score`.
+    Ssim { min_score: f64 },
+}
+
+/// Mean structural similarity of two equally sized grayscale images over 8x8 windows, in `-1.0..=1.0`.
+pub fn ssim(a: &GrayImage, b: &GrayImage) -> f64 {
+    const WINDOW: u32 = 8;
+    const C1: f64 = (0.01 * 255.0) * (0.01 * 255.0);
+    const C2: f64 = (0.03 * 255.0) * (0.03 * 255.0);
+
+    let (w, h) = a.dimensions();
+    let mut total = 0.0;
+    let mut windows = 0;
+    for wy in (0..h.saturating_sub(WINDOW - 1)).step_by(WINDOW as usize / 2) {
+        for wx in (0..w.saturating_sub(WINDOW - 1)).step_by(WINDOW as usize / 2) {
+            let pixels = || (wy..wy + WINDOW).flat_map(move |y| (wx..wx + WINDOW).map(move |x| (x, y)));
+            let n = (WINDOW * WINDOW) as f64;
+            let (mut mean_a, mut mean_b) = (0.0, 0.0);
+            for (x, y) in pixels() {
+                mean_a += a.get_pixel(x, y)[0] as f64;
+                mean_b += b.get_pixel(x, y)[0] as f64;
+            }
+            mean_a /= n;
+            mean_b /= n;
+            let (mut var_a, mut var_b, mut cov) = (0.0, 0.0, 0.0);
+            for (x, y) in pixels() {
+                let da = a.get_pixel(x, y)[0] as f64 - mean_a;
+                let db = b.get_pixel(x, y)[0] as f64 - mean_b;
+                var_a += da * da;
+                var_b += db * db;
+                cov += da * db;
+            }
+            let (var_a, var_b, cov) = (var_a / (n - 1.0), var_b / (n - 1.0), cov / (n - 1.0));
+            total += ((2.0 * mean_a * mean_b + C1) * (2.0 * cov + C2))
+                / ((mean_a * mean_a + mean_b * mean_b + C1) * (var_a + var_b + C2));
+            windows += 1;
+        }
+    }
+    // Images smaller than a window are compared as a whole.
+    if windows == 0 {
+        return if a == b { 1.0 } else { 0.0 };
+    }
+    total / windows as f64
+}
+
+/// Checks a frame against its reference; the error describes the mismatch.
+pub fn compare_images(actual: &DynamicImage, expected: &DynamicImage, tolerance: &ImageTolerance) -> Result<(), String> {
+    if actual.dimensions() != expected.dimensions() {
+        let ((aw, ah), (ew, eh)) = (actual.dimensions(), expected.dimensions());
+        return Err(format!("size {}x{} differs from the reference size {}x{}", aw, ah, ew, eh));
+    }
+    let (a, e) = (actual.to_rgba8(), expected.to_rgba8());
+    let differences = || a.pixels().zip(e.pixels()).map(|(p, q)| (0..4).map(|c| p[c].abs_diff(q[c])).max().unwrap_or(0));
+    let max_difference = differences().max().unwrap_or(0);
+
+    match tolerance {
+        ImageTolerance::Exact if max_difference == 0 => Ok(()),
+        ImageTolerance::Exact => {
+            let mismatched = differences().filter(|&d| d > 0).count();
+            Err(format!("{} pixels differ, by up to {}", mismatched, max_difference))
+        }
+        ImageTolerance::PerPixel { max_difference: allowed, max_mismatched } => {
+            let mismatched = differences().filter(|d| d > allowed).count();
+            let fraction = mismatched as f32 / (a.width() * a.height()).max(1) as f32;
+            if fraction <= *max_mismatched {
+                Ok(())
+            } else {
+                Err(format!(
+                    "{} pixels ({:.2}%) differ by more than {}, by up to {}; {:.2}% are allowed",
+                    mismatched, fraction * 100.0, allowed, max_difference, max_mismatched * 100.0
+                ))
+            }
+        }
+        ImageTolerance::Ssim { min_score } => {
+            let score = ssim(&actual.to_luma8(), &expected.to_luma8());
+            if score >= *min_score {
+                Ok(())
+            } else {
+                Err(format!("SSIM {:.4} is below {:.4}", score, min_score))
+            }
+        }
+    }
+}
+
+/// Visualizes differences: the reference dimmed to gray, differing pixels in red scaled by the difference.
+pub fn diff_image(actual: &RgbaImage, expected: &RgbaImage) -> RgbImage {
+    RgbImage::from_fn(expected.width(), expected.height(), |x, y| {
+        let q = expected.get_pixel(x, y);
+        let gray = ((q[0] as u32 + q[1] as u32 + q[2] as u32) / 12) as u8;
+        match actual.get_pixel_checked(x, y) {
+            Some(p) => {
+                let d = (0..4).map(|c| p[c].abs_diff(q[c])).max().unwrap_or(0);
+                if d == 0 { Rgb([gray, gray, gray]) } else { Rgb([128 + d / 2, 0, 0]) }
+            }
+            None => Rgb([255, 0, 255]),
+        }
+    })
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct AssertImageNodeConfig {
+    pub reference: PathBuf,
+    pub tolerance: ImageTolerance,
+    /// A diff image is written here on mismatch, if set.
+    pub diff_path: Option<PathBuf>,
+}
+
+impl Validate for AssertImageNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        ensure(!self.reference.as_os_str().is_empty(), "reference", "must be set")?;
+        match self.tolerance {
+            ImageTolerance::Exact => Ok(()),
+            ImageTolerance::PerPixel { max_mismatched, .. } => ensure((0.0..=1.0).contains(&max_mismatched), "tolerance.max_mismatched", "must be between 0 and 1"),
+            ImageTolerance::Ssim { min_score } => ensure((-1.0..=1.0).contains(&min_score), "tolerance.min_score", "must be between -1 and 1"),
+        }
+    }
+}
+
+config_builder!(AssertImageNodeConfig for AssertImageNode {
+    reference: PathBuf,
+    tolerance: ImageTolerance,
+    diff_path: Option<PathBuf>,
+});
+
+/// Fails the flow when a frame does not match a golden reference image.
+///
+/// Meant for regression tests of flows: matching frames are passed through
+/// unchanged, a mismatch makes `on_update` return an error describing it.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct AssertImageNode {
+    #[output]
+    pub output: Output<DynamicImage>,
+
+    #[input]
+    pub input: Input<DynamicImage>,
+
+    pub config: AssertImageNodeConfig,
+
+    #[serde(skip)]
+    reference: Option<DynamicImage>,
+    #[serde(skip)]
+    frame: u64,
+}
+
+impl AssertImageNode {
+    pub fn new(config: AssertImageNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            input: Input::new(),
+            config,
+            reference: None,
+            frame: 0,
+        }
+    }
+}
+
+impl Node for AssertImageNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        if let Ok(img) = self.input.next() {
+            if self.reference.is_none() {
+                let data = std::fs::read(&self.config.reference).map_err(|e| UpdateError::Other(e.into()))?;
+                self.reference = Some(decode_image(data)?);
+            }
+            let reference = self.reference.as_ref().expect("loaded above");
+            let frame = self.frame;
+            self.frame += 1;
+
+            if let Err(mismatch) = compare_images(&img, reference, &self.config.tolerance) {
+                let mut message = format!("Frame {} does not match {}: {}", frame, self.config.reference.display(), mismatch);
+                if let Some(path) = &self.config.diff_path {
+                    match diff_image(&img.to_rgba8(), &reference.to_rgba8()).save(path) {
+                        Ok(()) => message.push_str(&format!(" (diff written to {})", path.display())),
+                        Err(e) => message.push_str(&format!(" (writing the diff failed: {})", e)),
+                    }
+                }
+                return Err(UpdateError::Other(anyhow!(message)));
+            }
+            self.output.send(img).map_err(|e| UpdateError::Other(e.into()))?;
+        }
+        Ok(())
+    }
+}
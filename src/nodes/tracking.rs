@@ -0,0 +1,488 @@
+use flowrs::{node::{Node, UpdateError, ChangeObserver}, connection::{Input, Output}};
+use flowrs::RuntimeConnectable;
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::{ensure, ConfigError, Validate};
+use crate::types::{Detection, Rect, Track};
+
+/// Constant-velocity Kalman filter for a single coordinate.
+///
+/// With diagonal noise the SORT state model decouples into one such filter per
+/// box parameter, which keeps the update free of matrix algebra.
+#[derive(Clone, Copy, Debug)]
+struct Kalman1D {
+    x: f32,
+    v: f32,
+    /// Covariance `[[pxx, pxv], [pxv, pvv]]`.
+    p: [f32; 3],
+}
+
+impl Kalman1D {
+    fn new(x: f32, measurement_noise: f32) -> Self {
+        // The velocity is unknown at first, so it starts with a large variance.
+        Self { x, v: 0.0, p: [measurement_noise, 0.0, 1000.0] }
+    }
+
+    fn predict(&mut self, q: f32) {
+        let [pxx, pxv, pvv] = self.p;
+        self.x += self.v;
+        self.p = [pxx + 2.0 * pxv + pvv + q, pxv + pvv, pvv + q];
+    }
+
+    fn update(&mut self, z: f32, r: f32) {
+        let [pxx, pxv, pvv] = self.p;
+        let s = pxx + r;
+        let (kx, kv) = (pxx / s, pxv / s);
+        let y = z - self.x;
+        self.x += kx * y;
+        self.v += kv * y;
+        self.p = [(1.0 - kx) * pxx, (1.0 - kx) * pxv, pvv - kv * pxv];
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct TrackerNodeConfig {
+    /// Minimum overlap between a prediction and a detection to associate them.
+    pub iou_threshold: f32,
+    /// Frames a track survives without a matching detection.
+    pub max_age: u32,
+    /// Matches required before a track is reported.
+    pub min_hits: u32,
+    /// Kalman process noise; higher values follow abrupt motion more readily.
+    pub process_noise: f32,
+    /// Kalman measurement noise; higher values smooth jittery detections more.
+    pub measurement_noise: f32,
+}
+
+impl Default for TrackerNodeConfig {
+    fn default() -> Self {
+        Self {
+            iou_threshold: 0.3,
+            max_age: 30,
+            min_hits: 3,
+            process_noise: 1.0,
+            measurement_noise: 4.0,
+        }
+    }
+}
+
+impl Validate for TrackerNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        ensure((0.0..=1.0).contains(&self.iou_threshold), "iou_threshold", "must be in 0..=1")?;
+        ensure(self.max_age > 0, "max_age", "must be positive")?;
+        ensure(self.process_noise.is_finite() && self.process_noise > 0.0, "process_noise", "must be positive and finite")?;
+        ensure(self.measurement_noise.is_finite() && self.measurement_noise > 0.0, "measurement_noise", "must be positive and finite")
+    }
+}
+
+config_builder!(TrackerNodeConfig for TrackerNode {
+    iou_threshold: f32,
+    max_age: u32,
+    min_hits: u32,
+    process_noise: f32,
+    measurement_noise: f32,
+});
+
+struct TrackState {
+    id: u64,
+    /// Filters for center x, center y, width and height.
+    filters: [Kalman1D; 4],
+    label: String,
+    score: f32,
+    age: u32,
+    hits: u32,
+    misses: u32,
+}
+
+impl TrackState {
+    fn rect(&self) -> Rect {
+        let [cx, cy, w, h] = self.filters.map(|f| f.x);
+        let (w, h) = (w.max(1.0), h.max(1.0));
+        Rect::new((cx - w / 2.0).max(0.0) as u32, (cy - h / 2.0).max(0.0) as u32, w as u32, h as u32)
+    }
+
+    fn to_track(&self) -> Track {
+        Track {
+            id: self.id,
+            rect: self.rect(),
+            label: self.label.clone(),
+            score: self.score,
+            velocity: (self.filters[0].v, self.filters[1].v),
+            age: self.age,
+            hits: self.hits,
+        }
+    }
+}
+
+fn measurement(r: &Rect) -> [f32; 4] {
+    [r.x as f32 + r.width as f32 / 2.0, r.y as f32 + r.height as f32 / 2.0, r.width as f32, r.height as f32]
+}
+
+/// SORT-style multi-object tracker assigning persistent ids to detections.
+///
+/// Tracks are predicted with a Kalman filter and greedily associated with the
+/// new detections of the same label by IoU. Only tracks matched in the current
+/// frame and confirmed by enough hits are emitted.
+#[derive(Default)]
+pub struct Tracker {
+    tracks: Vec<TrackState>,
+    next_id: u64,
+    frame: u64,
+}
+
+impl Tracker {
+    pub fn update(&mut self, detections: Vec<Detection>, config: &TrackerNodeConfig) -> Vec<Track> {
+        self.frame += 1;
+        for t in &mut self.tracks {
+            t.filters.iter_mut().for_each(|f| f.predict(config.process_noise));
+            t.age += 1;
+        }
+
+        let mut pairs: Vec<(f32, usize, usize)> = Vec::new();
+        for (ti, t) in self.tracks.iter().enumerate() {
+            let predicted = t.rect();
+            for (di, d) in detections.iter().enumerate() {
+                let iou = predicted.iou(&d.rect);
+                if d.label == t.label && iou >= config.iou_threshold {
+                    pairs.push((iou, ti, di));
+                }
+            }
+        }
+        pairs.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+        let mut track_matched = vec![false; self.tracks.len()];
+        let mut detection_matched = vec![false; detections.len()];
+        for (_, ti, di) in pairs {
+            if track_matched[ti] || detection_matched[di] {
+                continue;
+            }
+            track_matched[ti] = true;
+            detection_matched[di] = true;
+            let t = &mut self.tracks[ti];
+            for (f, z) in t.filters.iter_mut().zip(measurement(&detections[di].rect)) {
+                f.update(z, config.measurement_noise);
+            }
+            t.score = detections[di].score;
+            t.hits += 1;
+            t.misses = 0;
+        }
+        for (t, matched) in self.tracks.iter_mut().zip(&track_matched) {
+            if !matched {
+                t.misses += 1;
+            }
+        }
+        self.tracks.retain(|t| t.misses <= config.max_age);
+
+        for (d, _) in detections.into_iter().zip(detection_matched).filter(|(_, m)| !m) {
+            self.next_id += 1;
+            let filters = measurement(&d.rect).map(|z| Kalman1D::new(z, config.measurement_noise));
+            self.tracks.push(TrackState { id: self.next_id, filters, label: d.label, score: d.score, age: 0, hits: 1, misses: 0 });
+        }
+
+        // During start-up, tracks are reported before they could reach `min_hits`.
+        let warming_up = self.frame <= config.min_hits as u64;
+        self.tracks
+            .iter()
+            .filter(|t| t.misses == 0 && (t.hits >= config.min_hits || warming_up))
+            .map(TrackState::to_track)
+            .collect()
+    }
+}
+
+/// Turns per-frame detections into persistent tracks with velocity and age.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct TrackerNode {
+    #[output]
+    pub output: Output<Vec<Track>>,
+
+    #[input]
+    pub input: Input<Vec<Detection>>,
+
+    pub config: TrackerNodeConfig,
+
+    #[serde(skip)]
+    tracker: Tracker,
+}
+
+impl TrackerNode {
+    pub fn new(config: TrackerNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            input: Input::new(),
+            config,
+            tracker: Tracker::default(),
+        }
+    }
+}
+
+impl Node for TrackerNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        if let Ok(detections) = self.input.next() {
+            let tracks = self.tracker.update(detections, &self.config);
+            self.output.send(tracks).map_err(|e| UpdateError::Other(e.into()))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Zone {
+    pub name: String,
+    /// Polygon vertices in pixel coordinates.
+    pub polygon: Vec<(f32, f32)>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct CountingLine {
+    pub name: String,
+    pub start: (f32, f32),
+    pub end: (f32, f32),
+}
+
+/// Point of a box that is tested against zones and lines.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum AnchorPoint {
+    Center,
+    /// Approximates the ground contact point of people and vehicles.
+    #[default]
+    BottomCenter,
+}
+
+impl AnchorPoint {
+    pub fn of(&self, r: &Rect) -> (f32, f32) {
+        let cx = r.x as f32 + r.width as f32 / 2.0;
+        match self {
+            AnchorPoint::Center => (cx, r.y as f32 + r.height as f32 / 2.0),
+            AnchorPoint::BottomCenter => (cx, (r.y + r.height) as f32),
+        }
+    }
+}
+
+/// Crossing direction, as seen when looking from the line's start towards its end.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum CrossDirection {
+    LeftToRight,
+    RightToLeft,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub enum AnalyticsEventKind {
+    Enter,
+    Exit,
+    Cross(CrossDirection),
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct AnalyticsEvent {
+    pub kind: AnalyticsEventKind,
+    /// Name of the zone or line.
+    pub name: String,
+    /// Track that caused the event; `None` for zone occupancy changes from plain detections.
+    pub track_id: Option<u64>,
+    pub label: String,
+    pub position: (f32, f32),
+}
+
+/// Even-odd rule point-in-polygon test.
+pub fn point_in_polygon(p: (f32, f32), polygon: &[(f32, f32)]) -> bool {
+    let mut inside = false;
+    for (i, a) in polygon.iter().enumerate() {
+        let b = polygon[(i + 1) % polygon.len()];
+        if (a.1 > p.1) != (b.1 > p.1) && p.0 < a.0 + (p.1 - a.1) * (b.0 - a.0) / (b.1 - a.1) {
+            inside = !inside;
+        }
+    }
+    inside
+}
+
+fn side(line: &CountingLine, p: (f32, f32)) -> f32 {
+    (line.end.0 - line.start.0) * (p.1 - line.start.1) - (line.end.1 - line.start.1) * (p.0 - line.start.0)
+}
+
+/// Direction in which the movement `from -> to` crosses the line segment, if it does.
+pub fn line_crossing(line: &CountingLine, from: (f32, f32), to: (f32, f32)) -> Option<CrossDirection> {
+    let (s0, s1) = (side(line, from), side(line, to));
+    if s0 == 0.0 || s0.signum() == s1.signum() {
+        return None;
+    }
+    // The movement must also straddle the infinite extension of the path, i.e. hit the segment itself.
+    let path = CountingLine { name: String::new(), start: from, end: to };
+    if side(&path, line.start).signum() == side(&path, line.end).signum() {
+        return None;
+    }
+    // Image y points down, so a positive cross product lies to the right of the line.
+    Some(if s1 > 0.0 { CrossDirection::LeftToRight } else { CrossDirection::RightToLeft })
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ZoneAnalyticsNodeConfig {
+    pub zones: Vec<Zone>,
+    pub lines: Vec<CountingLine>,
+    pub anchor: AnchorPoint,
+    /// Forget tracks not seen for this many updates.
+    pub forget_after: u32,
+}
+
+impl Default for ZoneAnalyticsNodeConfig {
+    fn default() -> Self {
+        Self {
+            zones: Vec::new(),
+            lines: Vec::new(),
+            anchor: AnchorPoint::BottomCenter,
+            forget_after: 30,
+        }
+    }
+}
+
+impl Validate for ZoneAnalyticsNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        for (i, zone) in self.zones.iter().enumerate() {
+            ensure(zone.polygon.len() >= 3, &format!("zones[{}].polygon", i), "needs at least 3 vertices")?;
+        }
+        for (i, line) in self.lines.iter().enumerate() {
+            ensure(line.start != line.end, &format!("lines[{}]", i), "start and end must differ")?;
+        }
+        ensure(self.forget_after > 0, "forget_after", "must be positive")
+    }
+}
+
+config_builder!(ZoneAnalyticsNodeConfig for ZoneAnalyticsNode {
+    zones: Vec<Zone>,
+    lines: Vec<CountingLine>,
+    anchor: AnchorPoint,
+    forget_after: u32,
+});
+
+struct TrackHistory {
+    position: (f32, f32),
+    inside: Vec<bool>,
+    last_seen: u64,
+}
+
+/// Emits zone entry/exit and directional line-crossing events.
+///
+/// Tracks give per-object events including line crossings. Plain detections
+/// carry no identity, so they only report zones becoming occupied (`Enter`)
+/// or empty (`Exit`).
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct ZoneAnalyticsNode {
+    #[output]
+    pub output: Output<Vec<AnalyticsEvent>>,
+
+    #[input]
+    pub tracks: Input<Vec<Track>>,
+
+    #[input]
+    pub detections: Input<Vec<Detection>>,
+
+    pub config: ZoneAnalyticsNodeConfig,
+
+    #[serde(skip)]
+    history: HashMap<u64, TrackHistory>,
+    #[serde(skip)]
+    occupied: Vec<bool>,
+    #[serde(skip)]
+    updates: u64,
+}
+
+impl ZoneAnalyticsNode {
+    pub fn new(config: ZoneAnalyticsNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            tracks: Input::new(),
+            detections: Input::new(),
+            config,
+            history: HashMap::new(),
+            occupied: Vec::new(),
+            updates: 0,
+        }
+    }
+
+    fn track_events(&mut self, tracks: Vec<Track>) -> Vec<AnalyticsEvent> {
+        self.updates += 1;
+        let mut events = Vec::new();
+        for t in tracks {
+            let position = self.config.anchor.of(&t.rect);
+            let inside: Vec<bool> = self.config.zones.iter().map(|z| point_in_polygon(position, &z.polygon)).collect();
+            let event = |kind, name: &str| AnalyticsEvent {
+                kind,
+                name: name.to_string(),
+                track_id: Some(t.id),
+                label: t.label.clone(),
+                position,
+            };
+
+            match self.history.get(&t.id) {
+                Some(h) => {
+                    for (zone, (was, is)) in self.config.zones.iter().zip(h.inside.iter().zip(&inside)) {
+                        match (was, is) {
+                            (false, true) => events.push(event(AnalyticsEventKind::Enter, &zone.name)),
+                            (true, false) => events.push(event(AnalyticsEventKind::Exit, &zone.name)),
+                            _ => {}
+                        }
+                    }
+                    for line in &self.config.lines {
+                        if let Some(direction) = line_crossing(line, h.position, position) {
+                            events.push(event(AnalyticsEventKind::Cross(direction), &line.name));
+                        }
+                    }
+                }
+                // Objects appearing inside a zone have entered it as far as the flow can tell.
+                None => {
+                    for (zone, _) in self.config.zones.iter().zip(&inside).filter(|(_, i)| **i) {
+                        events.push(event(AnalyticsEventKind::Enter, &zone.name));
+                    }
+                }
+            }
+            self.history.insert(t.id, TrackHistory { position, inside, last_seen: self.updates });
+        }
+
+        let (now, forget) = (self.updates, self.config.forget_after as u64);
+        self.history.retain(|_, h| now - h.last_seen <= forget);
+        events
+    }
+
+    fn occupancy_events(&mut self, detections: Vec<Detection>) -> Vec<AnalyticsEvent> {
+        self.occupied.resize(self.config.zones.len(), false);
+        let mut events = Vec::new();
+        for (zone, occupied) in self.config.zones.iter().zip(self.occupied.iter_mut()) {
+            let first = detections
+                .iter()
+                .map(|d| (d, self.config.anchor.of(&d.rect)))
+                .find(|(_, p)| point_in_polygon(*p, &zone.polygon));
+            let kind = match (*occupied, &first) {
+                (false, Some(_)) => AnalyticsEventKind::Enter,
+                (true, None) => AnalyticsEventKind::Exit,
+                _ => continue,
+            };
+            *occupied = first.is_some();
+            let (label, position) = first.map_or((String::new(), (0.0, 0.0)), |(d, p)| (d.label.clone(), p));
+            events.push(AnalyticsEvent { kind, name: zone.name.clone(), track_id: None, label, position });
+        }
+        events
+    }
+}
+
+impl Node for ZoneAnalyticsNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        let mut events = Vec::new();
+        while let Ok(tracks) = self.tracks.next() {
+            events.extend(self.track_events(tracks));
+        }
+        while let Ok(detections) = self.detections.next() {
+            events.extend(self.occupancy_events(detections));
+        }
+
+        if !events.is_empty() {
+            self.output.send(events).map_err(|e| UpdateError::Other(e.into()))?;
+        }
+        Ok(())
+    }
+}
@@ -1,8 +1,17 @@
-use flowrs::{node::{Node, UpdateError, ChangeObserver}, connection::{Input, Output}};
+use flowrs::{node::{Node, ShutdownError, UpdateError, ChangeObserver}, connection::{Input, Output}};
 use flowrs::RuntimeConnectable;
 
+use std::collections::{BTreeMap, VecDeque};
 use std::io::Cursor;
-use image::{DynamicImage, io::Reader as ImageReader, ImageBuffer, Pixel};
+use std::sync::{mpsc::{self, Receiver, Sender}, Arc, Mutex};
+use std::thread::JoinHandle;
+use image::{DynamicImage, GenericImageView, io::Reader as ImageReader, ImageBuffer, ImageOutputFormat, Pixel};
+use image::imageops::{self, FilterType};
+use image::error::{DecodingError, EncodingError, ImageFormatHint};
+use image::ImageError;
+use tiff::decoder::{Decoder as TiffDecoder, DecodingResult};
+use tiff::encoder::{colortype, TiffEncoder};
+use tiff::{ColorType as TiffColorType, TiffError};
 use ndarray::{Array3, ArrayBase, OwnedRepr, Dim};
 use nshare::ToNdarray3;
 use anyhow::{anyhow};
@@ -10,8 +19,38 @@ use anyhow::{anyhow};
 
 use serde::{Deserialize, Serialize};
 
+use crate::analysis::non_max_suppression;
+use crate::color::{resize_exact, resize_to_fit};
+use crate::config::{ensure, ConfigError, Validate};
+use crate::error::Error;
+use crate::types::{Detection, Rect, TileInfo};
+
 extern crate alloc;
 
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct DecodeImageNodeConfig {
+    /// Number of decode worker threads. `0` decodes inline on the calling thread.
+    ///
+    /// Buffers are decoded in parallel off the update loop: each update emits the
+    /// frames finished by then without waiting for the rest, which follow in later
+    /// updates or at shutdown.
+    pub workers: usize,
+    /// Emit frames in the order they were received, even if decoded out of order.
+    pub preserve_order: bool,
+    /// Emit every page of multi-page TIFFs instead of only the first.
+    pub all_pages: bool,
+}
+
+impl Validate for DecodeImageNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        // `0` workers decodes inline.
+        Ok(())
+    }
+}
+
+config_builder!(DecodeImageNodeConfig { workers: usize, preserve_order: bool, all_pages: bool });
+
 #[derive(RuntimeConnectable, Deserialize, Serialize)]
 pub struct DecodeImageNode {
     #[output]
@@ -19,33 +58,1203 @@ pub struct DecodeImageNode {
 
     #[input]
     pub input: Input<Vec<u8>>,
+
+    pub config: DecodeImageNodeConfig,
+
+    #[serde(skip)]
+    pool: Option<DecodePool>,
+    #[serde(skip)]
+    frames: u64,
 }
 
 impl DecodeImageNode {
     pub fn new(change_observer: Option<&ChangeObserver>) -> Self {
+        Self::with_config(DecodeImageNodeConfig::default(), change_observer)
+    }
+
+    pub fn with_config(config: DecodeImageNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
         Self {
             output: Output::new(change_observer),
-            input: Input::new()
+            input: Input::new(),
+            config,
+            pool: None,
+            frames: 0,
         }
     }
+
+    fn update_parallel(&mut self) -> Result<(), UpdateError> {
+        let workers = self.config.workers;
+        let all_pages = self.config.all_pages;
+        let pool = self.pool.get_or_insert_with(|| DecodePool::new(workers, all_pages));
+
+        while let Ok(data) = self.input.next() {
+            frame_span!("decode_image", frame = self.frames, bytes = data.len());
+            self.frames += 1;
+            pool.submit(data)?;
+        }
+
+        let results = pool.drain(self.config.preserve_order, false);
+        self.emit(results).map_err(UpdateError::Other)
+    }
+
+    /// Sends the frames of decoded buffers, returning the first decode failure.
+    fn emit(&mut self, results: Vec<DecodeResult>) -> Result<(), anyhow::Error> {
+        // A corrupt buffer must not take the frames decoded alongside it down with it.
+        let mut failure = None;
+        for result in results {
+            match result {
+                Ok(frames) => {
+                    for img in frames {
+                        self.output.send(img)?;
+                    }
+                }
+                Err(e) => failure = failure.or(Some(e)),
+            }
+        }
+        failure.map_or(Ok(()), |e| Err(e.into()))
+    }
 }
 
 impl Node for DecodeImageNode {
     fn on_update(&mut self) -> Result<(), UpdateError> {
 
+        if self.config.workers > 0 {
+            return self.update_parallel();
+        }
+
         if let Ok(data) = self.input.next() {
+            frame_span!("decode_image", frame = self.frames, bytes = data.len());
+            self.frames += 1;
+
+            for img in decode_frames(data, self.config.all_pages)? {
+                self.output.send(img).map_err(|e| UpdateError::Other(e.into()))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn on_shutdown(&mut self) -> Result<(), ShutdownError> {
+        let preserve_order = self.config.preserve_order;
+        let Some(pool) = &mut self.pool else { return Ok(()) };
+        // Buffers still being decoded are waited for, so no frame is lost.
+        let results = pool.drain(preserve_order, true);
+        self.emit(results).map_err(ShutdownError::Other)
+    }
+}
+
+type DecodeResult = Result<Vec<DynamicImage>, Error>;
+
+/// Worker threads decoding sequence-numbered buffers off the node's update loop.
+struct DecodePool {
+    jobs: Option<Sender<(u64, Vec<u8>)>>,
+    results: Receiver<(u64, DecodeResult)>,
+    workers: Vec<JoinHandle<()>>,
+    next_seq: u64,
+    next_emit: u64,
+    received: u64,
+    pending: BTreeMap<u64, DecodeResult>,
+}
+
+impl DecodePool {
+    fn new(workers: usize, all_pages: bool) -> Self {
+        let (job_tx, job_rx) = mpsc::channel::<(u64, Vec<u8>)>();
+        let (result_tx, result_rx) = mpsc::channel();
+        let job_rx = Arc::new(Mutex::new(job_rx));
+
+        let workers = (0..workers)
+            .map(|_| {
+                let jobs = job_rx.clone();
+                let results = result_tx.clone();
+                std::thread::spawn(move || loop {
+                    let job = match jobs.lock() {
+                        Ok(jobs) => jobs.recv(),
+                        Err(_) => break,
+                    };
+                    let Ok((seq, data)) = job else { break };
+                    if results.send((seq, decode_frames(data, all_pages))).is_err() {
+                        break;
+                    }
+                })
+            })
+            .collect();
+
+        Self {
+            jobs: Some(job_tx),
+            results: result_rx,
+            workers,
+            next_seq: 0,
+            next_emit: 0,
+            received: 0,
+            pending: BTreeMap::new(),
+        }
+    }
+
+    fn submit(&mut self, data: Vec<u8>) -> Result<(), UpdateError> {
+        let jobs = self.jobs.as_ref().ok_or_else(|| UpdateError::Other(anyhow!("Decode pool is shut down.")))?;
+        jobs.send((self.next_seq, data))
+            .map_err(|_| UpdateError::Other(anyhow!("All decode workers have stopped.")))?;
+        self.next_seq += 1;
+        Ok(())
+    }
+
+    /// Returns the results of the buffers decoded so far, in submission order if asked
+    /// to. With `wait`, first waits for every submitted buffer.
+    fn drain(&mut self, preserve_order: bool, wait: bool) -> Vec<DecodeResult> {
+        let mut finished = Vec::new();
+        while self.received < self.next_seq {
+            // Waiting only fails once every worker has exited.
+            let result = if wait { self.results.recv().ok() } else { self.results.try_recv().ok() };
+            let Some(result) = result else { break };
+            finished.push(result);
+            self.received += 1;
+        }
+        if !preserve_order {
+            return finished.into_iter().map(|(_, r)| r).collect();
+        }
+
+        self.pending.extend(finished);
+        let mut ready = Vec::new();
+        while let Some(result) = self.pending.remove(&self.next_emit) {
+            ready.push(result);
+            self.next_emit += 1;
+        }
+        ready
+    }
+}
 
-            let img = ImageReader::new(Cursor::new(data))
-            .with_guessed_format().map_err(|e| UpdateError::Other(e.into()))?
-            .decode().map_err(|e| UpdateError::Other(e.into()))?;
+impl Drop for DecodePool {
+    fn drop(&mut self) {
+        // Closing the job channel lets every worker leave its receive loop.
+        self.jobs.take();
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ScaledDecodeNodeConfig {
+    /// Longest side of the emitted images; larger images are scaled down to it.
+    pub max_dimension: u32,
+}
+
+impl Default for ScaledDecodeNodeConfig {
+    fn default() -> Self {
+        Self { max_dimension: 512 }
+    }
+}
+
+impl Validate for ScaledDecodeNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        ensure(self.max_dimension > 0, "max_dimension", "must be positive")
+    }
+}
+
+config_builder!(ScaledDecodeNodeConfig for ScaledDecodeNode { max_dimension: u32 });
+
+/// Decodes images at a reduced scale, without holding the full resolution for JPEG and
+/// non-interlaced PNG, e.g. for thumbnails of very large images.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct ScaledDecodeNode {
+    #[output]
+    pub output: Output<DynamicImage>,
+
+    #[input]
+    pub input: Input<Vec<u8>>,
+
+    pub config: ScaledDecodeNodeConfig,
+}
+
+impl ScaledDecodeNode {
+    pub fn new(config: ScaledDecodeNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            input: Input::new(),
+            config,
+        }
+    }
+}
 
+impl Node for ScaledDecodeNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        if let Ok(data) = self.input.next() {
+            frame_span!("scaled_decode", bytes = data.len());
+            let img = decode_image_scaled(data, self.config.max_dimension)?;
             self.output.send(img).map_err(|e| UpdateError::Other(e.into()))?;
         }
         Ok(())
     }
 }
 
-// TODO:    - EncodeImageNode, Array3ToImage, 
+/// Decodes an image so that its longest side is at most `max_dimension`.
+///
+/// JPEGs are reduced during the inverse DCT and non-interlaced PNGs by keeping only every
+/// n-th row and column while reading, so neither is ever held at full resolution. Other
+/// formats are decoded fully and then resized.
+pub fn decode_image_scaled(data: Vec<u8>, max_dimension: u32) -> Result<DynamicImage, Error> {
+    let max_dimension = max_dimension.max(1);
+    let reduced = match image::guess_format(&data) {
+        Ok(image::ImageFormat::Jpeg) => decode_jpeg_reduced(&data, max_dimension)?,
+        Ok(image::ImageFormat::Png) => decode_png_subsampled(&data, max_dimension)?,
+        _ => None,
+    };
+    let img = match reduced {
+        Some(img) => img,
+        None => decode_image(data)?,
+    };
+
+    if img.width().max(img.height()) <= max_dimension {
+        return Ok(img);
+    }
+    Ok(resize_to_fit(&img, max_dimension, max_dimension))
+}
+
+/// Lets the decoder pick the smallest IDCT scale of 1/8 to 1 that covers the target size.
+/// `None` for layouts it cannot scale.
+fn decode_jpeg_reduced(data: &[u8], max_dimension: u32) -> Result<Option<DynamicImage>, Error> {
+    let jpeg_error = |e: jpeg_decoder::Error| Error::Decode(ImageError::Decoding(DecodingError::new(ImageFormatHint::Exact(image::ImageFormat::Jpeg), e)));
+    let mut decoder = jpeg_decoder::Decoder::new(Cursor::new(data));
+    decoder.read_info().map_err(jpeg_error)?;
+    let Some(info) = decoder.info() else { return Ok(None) };
+    if !matches!(info.pixel_format, jpeg_decoder::PixelFormat::L8 | jpeg_decoder::PixelFormat::RGB24) {
+        return Ok(None);
+    }
+
+    let (width, height) = (info.width as u32, info.height as u32);
+    let longest = width.max(height);
+    if longest > max_dimension {
+        let target = |side: u32| (side * max_dimension).div_ceil(longest).max(1) as u16;
+        decoder.scale(target(width), target(height)).map_err(jpeg_error)?;
+    }
+    let pixels = decoder.decode().map_err(jpeg_error)?;
+    let Some(info) = decoder.info() else { return Ok(None) };
+    let (width, height) = (info.width as u32, info.height as u32);
+
+    let img = match info.pixel_format {
+        jpeg_decoder::PixelFormat::L8 => ImageBuffer::from_raw(width, height, pixels).map(DynamicImage::ImageLuma8),
+        _ => ImageBuffer::from_raw(width, height, pixels).map(DynamicImage::ImageRgb8),
+    };
+    img.map(Some).ok_or_else(|| Error::Conversion(format!("JPEG holds too few pixels for {}x{}", width, height)))
+}
+
+/// Reads the PNG row by row, keeping every n-th pixel of every n-th row as 8 bit samples.
+/// `None` for interlaced images, whose rows arrive out of order.
+fn decode_png_subsampled(data: &[u8], max_dimension: u32) -> Result<Option<DynamicImage>, Error> {
+    let png_error = |e: png::DecodingError| Error::Decode(ImageError::Decoding(DecodingError::new(ImageFormatHint::Exact(image::ImageFormat::Png), e)));
+    let mut decoder = png::Decoder::new(Cursor::new(data));
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info().map_err(png_error)?;
+    if reader.info().interlaced {
+        return Ok(None);
+    }
+
+    let (width, height) = reader.info().size();
+    // Stay at or above the bound, the caller resizes the rest of the way.
+    let step = (width.max(height) / max_dimension).max(1) as usize;
+    let (color, _) = reader.output_color_type();
+    let channels = color.samples();
+    let (out_width, out_height) = ((width as usize).div_ceil(step), (height as usize).div_ceil(step));
+
+    let mut pixels = Vec::with_capacity(out_width * out_height * channels);
+    let mut y = 0;
+    while let Some(row) = reader.next_row().map_err(png_error)? {
+        if y % step == 0 {
+            for pixel in row.data().chunks_exact(channels).step_by(step) {
+                pixels.extend_from_slice(pixel);
+            }
+        }
+        y += 1;
+    }
+
+    let (out_width, out_height) = (out_width as u32, out_height as u32);
+    let img = match color {
+        png::ColorType::Grayscale => ImageBuffer::from_raw(out_width, out_height, pixels).map(DynamicImage::ImageLuma8),
+        png::ColorType::GrayscaleAlpha => ImageBuffer::from_raw(out_width, out_height, pixels).map(DynamicImage::ImageLumaA8),
+        png::ColorType::Rgba => ImageBuffer::from_raw(out_width, out_height, pixels).map(DynamicImage::ImageRgba8),
+        // Indexed images are expanded to RGB by the transformations.
+        _ => ImageBuffer::from_raw(out_width, out_height, pixels).map(DynamicImage::ImageRgb8),
+    };
+    img.map(Some).ok_or_else(|| Error::Conversion(format!("PNG holds too few pixels for {}x{}", width, height)))
+}
+
+/// Decodes an encoded image, guessing its format from the content.
+///
+/// With the `jpeg-fast` feature enabled, JPEG data is routed through zune-jpeg,
+/// falling back to the generic image-rs decoder if zune-jpeg rejects the stream.
+/// Float TIFFs, which image-rs cannot read, are decoded to 32 bit float images, and
+/// JPEG XL files with jxl-oxide if the `jxl` feature is enabled.
+pub fn decode_image(data: Vec<u8>) -> Result<DynamicImage, Error> {
+    #[cfg(feature = "jxl")]
+    if is_jxl(&data) {
+        return decode_jxl(&data);
+    }
+
+    let reader = ImageReader::new(Cursor::new(data)).with_guessed_format()?;
+
+    #[cfg(feature = "jpeg-fast")]
+    if reader.format() == Some(image::ImageFormat::Jpeg) {
+        let data = reader.into_inner().into_inner();
+        return match decode_jpeg_fast(&data) {
+            Ok(img) => Ok(img),
+            Err(_) => ImageReader::with_format(Cursor::new(data), image::ImageFormat::Jpeg).decode().map_err(Error::Decode),
+        };
+    }
+
+    if reader.format() == Some(image::ImageFormat::Tiff) {
+        let data = reader.into_inner().into_inner();
+        return match ImageReader::with_format(Cursor::new(&data), image::ImageFormat::Tiff).decode() {
+            Err(ImageError::Unsupported(_)) => read_tiff_pages(&data, 1).map(|mut pages| pages.remove(0)),
+            result => result.map_err(Error::Decode),
+        };
+    }
+
+    reader.decode().map_err(Error::Decode)
+}
+
+/// Whether `data` starts like a bare JPEG XL codestream or its ISOBMFF container.
+#[cfg(feature = "jxl")]
+fn is_jxl(data: &[u8]) -> bool {
+    data.starts_with(&[0xff, 0x0a]) || data.starts_with(b"\0\0\0\x0cJXL \r\n\x87\n")
+}
+
+/// Decodes the first frame to 8 or 16 bit integer, or float samples, after the stored depth.
+#[cfg(feature = "jxl")]
+fn decode_jxl(data: &[u8]) -> Result<DynamicImage, Error> {
+    use jxl_oxide::{JxlImage, PixelFormat};
+
+    let jxl_error = |e: Box<dyn std::error::Error + Send + Sync>| Error::Decode(ImageError::Decoding(DecodingError::new(ImageFormatHint::Name("JPEG XL".into()), e)));
+    let image = JxlImage::builder().read(data).map_err(jxl_error)?;
+    let render = image.render_frame(0).map_err(jxl_error)?;
+    let frame = render.image();
+    let (width, height) = (frame.width() as u32, frame.height() as u32);
+    let samples = frame.buf();
+
+    let bit_depth = image.image_header().metadata.bit_depth;
+    let float = matches!(bit_depth, jxl_oxide::image::BitDepth::FloatSample { .. });
+    let to_u8 = || samples.iter().map(|&v| (v.clamp(0.0, 1.0) * 255.0).round() as u8).collect::<Vec<_>>();
+    let to_u16 = || samples.iter().map(|&v| (v.clamp(0.0, 1.0) * 65535.0).round() as u16).collect::<Vec<_>>();
+    let short = || Error::Conversion(format!("JPEG XL frame holds too few samples for {}x{}", width, height));
+
+    let img = match (image.pixel_format(), float, bit_depth.bits_per_sample() > 8) {
+        (PixelFormat::Rgb, true, _) => ImageBuffer::from_raw(width, height, samples.to_vec()).map(DynamicImage::ImageRgb32F),
+        (PixelFormat::Rgba, true, _) => ImageBuffer::from_raw(width, height, samples.to_vec()).map(DynamicImage::ImageRgba32F),
+        (PixelFormat::Gray, true, _) => {
+            let rgb = samples.iter().flat_map(|&v| [v, v, v]).collect();
+            ImageBuffer::from_raw(width, height, rgb).map(DynamicImage::ImageRgb32F)
+        }
+        (PixelFormat::Gray, _, false) => ImageBuffer::from_raw(width, height, to_u8()).map(DynamicImage::ImageLuma8),
+        (PixelFormat::Gray, _, true) => ImageBuffer::from_raw(width, height, to_u16()).map(DynamicImage::ImageLuma16),
+        (PixelFormat::Graya, _, false) => ImageBuffer::from_raw(width, height, to_u8()).map(DynamicImage::ImageLumaA8),
+        (PixelFormat::Graya, _, true) => ImageBuffer::from_raw(width, height, to_u16()).map(DynamicImage::ImageLumaA16),
+        (PixelFormat::Rgb, _, false) => ImageBuffer::from_raw(width, height, to_u8()).map(DynamicImage::ImageRgb8),
+        (PixelFormat::Rgb, _, true) => ImageBuffer::from_raw(width, height, to_u16()).map(DynamicImage::ImageRgb16),
+        (PixelFormat::Rgba, _, false) => ImageBuffer::from_raw(width, height, to_u8()).map(DynamicImage::ImageRgba8),
+        (PixelFormat::Rgba, _, true) => ImageBuffer::from_raw(width, height, to_u16()).map(DynamicImage::ImageRgba16),
+        (format, _, _) => return Err(Error::UnsupportedFormat(format!("JPEG XL {:?}", format))),
+    };
+    img.ok_or_else(short)
+}
+
+fn decode_frames(data: Vec<u8>, all_pages: bool) -> Result<Vec<DynamicImage>, Error> {
+    if all_pages && image::guess_format(&data).ok() == Some(image::ImageFormat::Tiff) {
+        return decode_tiff_pages(&data);
+    }
+    Ok(vec![decode_image(data)?])
+}
+
+/// Decodes every page of a (multi-page) TIFF, in file order.
+pub fn decode_tiff_pages(data: &[u8]) -> Result<Vec<DynamicImage>, Error> {
+    read_tiff_pages(data, usize::MAX)
+}
+
+/// Decodes up to `limit` pages, at least one.
+fn read_tiff_pages(data: &[u8], limit: usize) -> Result<Vec<DynamicImage>, Error> {
+    let mut decoder = TiffDecoder::new(Cursor::new(data)).map_err(tiff_error)?;
+    let mut pages = Vec::new();
+    loop {
+        let (width, height) = decoder.dimensions().map_err(tiff_error)?;
+        let color = decoder.colortype().map_err(tiff_error)?;
+        let pixels = decoder.read_image().map_err(tiff_error)?;
+        pages.push(tiff_page(width, height, color, pixels)?);
+
+        if pages.len() >= limit || !decoder.more_images() {
+            return Ok(pages);
+        }
+        decoder.next_image().map_err(tiff_error)?;
+    }
+}
+
+fn tiff_page(width: u32, height: u32, color: TiffColorType, pixels: DecodingResult) -> Result<DynamicImage, Error> {
+    let short = || Error::Conversion(format!("TIFF page holds too few pixels for {}x{} {:?}", width, height, color));
+    match (color, pixels) {
+        (TiffColorType::Gray(8), DecodingResult::U8(p)) => ImageBuffer::from_raw(width, height, p).map(DynamicImage::ImageLuma8).ok_or_else(short),
+        (TiffColorType::Gray(16), DecodingResult::U16(p)) => ImageBuffer::from_raw(width, height, p).map(DynamicImage::ImageLuma16).ok_or_else(short),
+        (TiffColorType::GrayA(8), DecodingResult::U8(p)) => ImageBuffer::from_raw(width, height, p).map(DynamicImage::ImageLumaA8).ok_or_else(short),
+        (TiffColorType::GrayA(16), DecodingResult::U16(p)) => ImageBuffer::from_raw(width, height, p).map(DynamicImage::ImageLumaA16).ok_or_else(short),
+        (TiffColorType::RGB(8), DecodingResult::U8(p)) => ImageBuffer::from_raw(width, height, p).map(DynamicImage::ImageRgb8).ok_or_else(short),
+        (TiffColorType::RGB(16), DecodingResult::U16(p)) => ImageBuffer::from_raw(width, height, p).map(DynamicImage::ImageRgb16).ok_or_else(short),
+        (TiffColorType::RGBA(8), DecodingResult::U8(p)) => ImageBuffer::from_raw(width, height, p).map(DynamicImage::ImageRgba8).ok_or_else(short),
+        (TiffColorType::RGBA(16), DecodingResult::U16(p)) => ImageBuffer::from_raw(width, height, p).map(DynamicImage::ImageRgba16).ok_or_else(short),
+        (TiffColorType::RGB(32), DecodingResult::F32(p)) => ImageBuffer::from_raw(width, height, p).map(DynamicImage::ImageRgb32F).ok_or_else(short),
+        (TiffColorType::RGBA(32), DecodingResult::F32(p)) => ImageBuffer::from_raw(width, height, p).map(DynamicImage::ImageRgba32F).ok_or_else(short),
+        // There is no single-channel float image; spread the value over RGB.
+        (TiffColorType::Gray(32), DecodingResult::F32(p)) => {
+            let rgb = p.iter().flat_map(|&v| [v, v, v]).collect();
+            ImageBuffer::from_raw(width, height, rgb).map(DynamicImage::ImageRgb32F).ok_or_else(short)
+        }
+        (color, _) => Err(Error::UnsupportedFormat(format!("TIFF {:?}", color))),
+    }
+}
+
+fn tiff_error(e: TiffError) -> Error {
+    Error::Decode(ImageError::Decoding(DecodingError::new(ImageFormatHint::Exact(image::ImageFormat::Tiff), e)))
+}
+
+fn tiff_encode_error(e: TiffError) -> Error {
+    Error::Encode(ImageError::Encoding(EncodingError::new(ImageFormatHint::Exact(image::ImageFormat::Tiff), e)))
+}
+
+#[cfg(feature = "jpeg-fast")]
+fn decode_jpeg_fast(data: &[u8]) -> Result<DynamicImage, anyhow::Error> {
+    use image::{GrayImage, RgbImage, RgbaImage};
+
+    let mut decoder = zune_jpeg::JpegDecoder::new(data);
+    let pixels = decoder.decode().map_err(|e| anyhow!("JPEG decode failed: {:?}", e))?;
+    let (width, height) = decoder
+        .dimensions()
+        .ok_or_else(|| anyhow!("JPEG decoder did not report dimensions."))?;
+    let components = decoder
+        .get_output_colorspace()
+        .ok_or_else(|| anyhow!("JPEG decoder did not report a colorspace."))?
+        .num_components();
+
+    let (width, height) = (width as u32, height as u32);
+    let img = match components {
+        1 => GrayImage::from_raw(width, height, pixels).map(DynamicImage::ImageLuma8),
+        3 => RgbImage::from_raw(width, height, pixels).map(DynamicImage::ImageRgb8),
+        4 => RgbaImage::from_raw(width, height, pixels).map(DynamicImage::ImageRgba8),
+        _ => None,
+    };
+    img.ok_or_else(|| anyhow!("Unsupported JPEG output layout with {} components.", components))
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum EncodeFormat {
+    #[default]
+    Png,
+    Jpeg { quality: u8 },
+    Bmp,
+    Gif,
+    Tiff,
+    /// Requires the `avif` feature. `speed` ranges from 1, smallest, to 10, fastest.
+    Avif { quality: u8, speed: u8 },
+    /// Lossless JPEG XL, requires the `jxl` feature. Higher `effort`, up to 127, is slower but smaller.
+    ///
+    /// There is no lossy mode: the pure-Rust encoder only writes lossless files, so use
+    /// `Avif` or `Jpeg` where size matters more than exact pixels.
+    Jxl { effort: u8 },
+}
+
+impl Validate for EncodeFormat {
+    fn validate(&self) -> Result<(), ConfigError> {
+        match self {
+            EncodeFormat::Jpeg { quality } => ensure((1..=100).contains(quality), "quality", "must be between 1 and 100"),
+            EncodeFormat::Avif { quality, speed } => {
+                ensure(cfg!(feature = "avif"), "avif", "requires the `avif` feature")?;
+                ensure((1..=100).contains(quality), "quality", "must be between 1 and 100")?;
+                ensure((1..=10).contains(speed), "speed", "must be between 1 and 10")
+            }
+            EncodeFormat::Jxl { effort } => {
+                ensure(cfg!(feature = "jxl"), "jxl", "requires the `jxl` feature")?;
+                ensure(*effort <= 127, "effort", "must be at most 127")
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl From<EncodeFormat> for ImageOutputFormat {
+    fn from(format: EncodeFormat) -> Self {
+        match format {
+            EncodeFormat::Png => ImageOutputFormat::Png,
+            EncodeFormat::Jpeg { quality } => ImageOutputFormat::Jpeg(quality),
+            EncodeFormat::Bmp => ImageOutputFormat::Bmp,
+            EncodeFormat::Gif => ImageOutputFormat::Gif,
+            EncodeFormat::Tiff => ImageOutputFormat::Tiff,
+            EncodeFormat::Avif { .. } | EncodeFormat::Jxl { .. } => ImageOutputFormat::Unsupported(format!("{:?}", format)),
+        }
+    }
+}
+
+/// Region-of-interest encoding: the configured and received regions keep full
+/// detail, the remaining frame is low-pass filtered so it compresses aggressively.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct RoiEncoding {
+    pub regions: Vec<Rect>,
+    /// Factor by which the background is downsampled before re-expansion. Larger is smaller output.
+    pub background_downscale: u32,
+}
+
+/// How images with more than 8 bits per channel are encoded.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum BitDepthPolicy {
+    /// Keep the depth where the format allows it, i.e. 16 bit in PNG and TIFF and float in
+    /// TIFF; otherwise use the deepest layout the format has.
+    #[default]
+    Preserve,
+    /// Always encode 8 bits per channel, e.g. for display.
+    Reduce8,
+    /// Fail instead of storing fewer bits than the image has.
+    Strict,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct EncodeImageNodeConfig {
+    pub format: EncodeFormat,
+    pub bit_depth: BitDepthPolicy,
+    pub roi: Option<RoiEncoding>,
+}
+
+impl Validate for EncodeImageNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        self.format.validate_in("format")
+    }
+}
+
+config_builder!(EncodeImageNodeConfig for EncodeImageNode {
+    format: EncodeFormat,
+    bit_depth: BitDepthPolicy,
+    roi: Option<RoiEncoding>,
+});
+
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct EncodeImageNode {
+    #[output]
+    pub output: Output<Vec<u8>>,
+
+    #[input]
+    pub input: Input<DynamicImage>,
+
+    /// Dynamically detected regions (e.g. faces, plates), used for all following frames.
+    #[input]
+    pub regions: Input<Vec<Rect>>,
+
+    pub config: EncodeImageNodeConfig,
+
+    #[serde(skip)]
+    detected: Vec<Rect>,
+    #[serde(skip)]
+    frames: u64,
+}
+
+impl EncodeImageNode {
+    pub fn new(config: EncodeImageNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            input: Input::new(),
+            regions: Input::new(),
+            config,
+            detected: Vec::new(),
+            frames: 0,
+        }
+    }
+}
+
+impl Node for EncodeImageNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        while let Ok(regions) = self.regions.next() {
+            self.detected = regions;
+        }
+
+        if let Ok(img) = self.input.next() {
+            frame_span!("encode_image", frame = self.frames);
+            self.frames += 1;
+
+            let img = match &self.config.roi {
+                Some(roi) => {
+                    let regions: Vec<Rect> = roi.regions.iter().chain(self.detected.iter()).copied().collect();
+                    degrade_background(&img, &regions, roi.background_downscale)
+                }
+                None => img,
+            };
+
+            let data = encode_image_with_depth(&img, self.config.format, self.config.bit_depth)?;
+            self.output.send(data).map_err(|e| UpdateError::Other(e.into()))?;
+        }
+        Ok(())
+    }
+}
+
+/// Encodes an image, converting it first if the format cannot represent its layout.
+pub fn encode_image(img: &DynamicImage, format: EncodeFormat) -> Result<Vec<u8>, Error> {
+    encode_image_with_depth(img, format, BitDepthPolicy::Preserve)
+}
+
+/// Like [`encode_image`], with `policy` deciding what happens to deep images.
+pub fn encode_image_with_depth(img: &DynamicImage, format: EncodeFormat, policy: BitDepthPolicy) -> Result<Vec<u8>, Error> {
+    let reduced;
+    let img = match policy {
+        BitDepthPolicy::Reduce8 if bits_per_channel(img) > 8 => {
+            reduced = to_8bit(img);
+            &reduced
+        }
+        _ => img,
+    };
+
+    let converted = storable_layout(img, format);
+    let stored = converted.as_ref().unwrap_or(img);
+    if policy == BitDepthPolicy::Strict && bits_per_channel(stored) < bits_per_channel(img) {
+        return Err(Error::UnsupportedFormat(format!("{:?} cannot store {} bit samples", format, bits_per_channel(img))));
+    }
+
+    let mut buf = Cursor::new(Vec::new());
+    match (format, stored) {
+        (EncodeFormat::Tiff, DynamicImage::ImageRgb32F(_) | DynamicImage::ImageRgba32F(_)) => write_tiff_float(&mut buf, stored)?,
+        (EncodeFormat::Avif { quality, speed }, _) => return encode_avif(stored, quality, speed),
+        (EncodeFormat::Jxl { effort }, _) => return encode_jxl(stored, effort),
+        _ => stored.write_to(&mut buf, ImageOutputFormat::from(format)).map_err(Error::Encode)?,
+    }
+    Ok(buf.into_inner())
+}
+
+/// The image converted to a layout `format` can encode, or `None` if it can encode it as is.
+fn storable_layout(img: &DynamicImage, format: EncodeFormat) -> Option<DynamicImage> {
+    use DynamicImage::*;
+
+    match (format, img) {
+        (EncodeFormat::Jpeg { .. }, ImageLuma8(_) | ImageRgb8(_)) => None,
+        (EncodeFormat::Jpeg { .. }, ImageLuma16(_)) => Some(ImageLuma8(img.to_luma8())),
+        (EncodeFormat::Jpeg { .. }, _) => Some(ImageRgb8(img.to_rgb8())),
+        (EncodeFormat::Png | EncodeFormat::Jxl { .. }, ImageRgb32F(_)) => Some(ImageRgb16(img.to_rgb16())),
+        (EncodeFormat::Png | EncodeFormat::Jxl { .. }, ImageRgba32F(_)) => Some(ImageRgba16(img.to_rgba16())),
+        (EncodeFormat::Tiff, ImageLumaA8(_)) => Some(ImageRgba8(img.to_rgba8())),
+        (EncodeFormat::Tiff, ImageLumaA16(_)) => Some(ImageRgba16(img.to_rgba16())),
+        (EncodeFormat::Bmp, _) if bits_per_channel(img) > 8 => Some(to_8bit(img)),
+        (EncodeFormat::Gif, ImageRgba8(_)) => None,
+        (EncodeFormat::Gif, _) => Some(ImageRgba8(img.to_rgba8())),
+        (EncodeFormat::Avif { .. }, ImageRgb8(_) | ImageRgba8(_)) => None,
+        (EncodeFormat::Avif { .. }, _) if img.color().has_alpha() => Some(ImageRgba8(img.to_rgba8())),
+        (EncodeFormat::Avif { .. }, _) => Some(ImageRgb8(img.to_rgb8())),
+        _ => None,
+    }
+}
+
+fn bits_per_channel(img: &DynamicImage) -> u16 {
+    let color = img.color();
+    color.bits_per_pixel() / color.channel_count() as u16
+}
+
+/// The 8 bit counterpart of the image's layout, with alpha kept if it has one.
+fn to_8bit(img: &DynamicImage) -> DynamicImage {
+    match img {
+        DynamicImage::ImageLuma16(_) => DynamicImage::ImageLuma8(img.to_luma8()),
+        DynamicImage::ImageLumaA16(_) => DynamicImage::ImageLumaA8(img.to_luma_alpha8()),
+        _ if img.color().has_alpha() => DynamicImage::ImageRgba8(img.to_rgba8()),
+        _ => DynamicImage::ImageRgb8(img.to_rgb8()),
+    }
+}
+
+#[cfg(feature = "avif")]
+fn encode_avif(img: &DynamicImage, quality: u8, speed: u8) -> Result<Vec<u8>, Error> {
+    use ravif::{Img, RGB8, RGBA8};
+
+    let (width, height) = (img.width() as usize, img.height() as usize);
+    let encoder = ravif::Encoder::new().with_quality(quality.clamp(1, 100) as f32).with_speed(speed.clamp(1, 10));
+    let encoded = match img {
+        DynamicImage::ImageRgba8(rgba) => {
+            let pixels: Vec<RGBA8> = rgba.pixels().map(|p| RGBA8::new(p[0], p[1], p[2], p[3])).collect();
+            encoder.encode_rgba(Img::new(&pixels[..], width, height))
+        }
+        _ => {
+            let pixels: Vec<RGB8> = img.to_rgb8().pixels().map(|p| RGB8::new(p[0], p[1], p[2])).collect();
+            encoder.encode_rgb(Img::new(&pixels[..], width, height))
+        }
+    };
+    encoded
+        .map(|e| e.avif_file)
+        .map_err(|e| Error::Encode(ImageError::Encoding(EncodingError::new(ImageFormatHint::Exact(image::ImageFormat::Avif), e))))
+}
+
+#[cfg(not(feature = "avif"))]
+fn encode_avif(_: &DynamicImage, _: u8, _: u8) -> Result<Vec<u8>, Error> {
+    Err(Error::UnsupportedFormat("AVIF requires the `avif` feature".into()))
+}
+
+#[cfg(feature = "jxl")]
+fn encode_jxl(img: &DynamicImage, effort: u8) -> Result<Vec<u8>, Error> {
+    use zune_core::{bit_depth::BitDepth, colorspace::ColorSpace, options::EncoderOptions};
+
+    let colorspace = match img.color().channel_count() {
+        1 => ColorSpace::Luma,
+        2 => ColorSpace::LumaA,
+        3 => ColorSpace::RGB,
+        _ => ColorSpace::RGBA,
+    };
+    let depth = if bits_per_channel(img) > 8 { BitDepth::Sixteen } else { BitDepth::Eight };
+    let options = EncoderOptions::new(img.width() as usize, img.height() as usize, colorspace, depth).set_effort(effort);
+    zune_jpegxl::JxlSimpleEncoder::new(img.as_bytes(), options)
+        .encode()
+        .map_err(|e| Error::Encode(ImageError::Encoding(EncodingError::new(ImageFormatHint::Name("JPEG XL".into()), format!("{:?}", e)))))
+}
+
+#[cfg(not(feature = "jxl"))]
+fn encode_jxl(_: &DynamicImage, _: u8) -> Result<Vec<u8>, Error> {
+    Err(Error::UnsupportedFormat("JPEG XL requires the `jxl` feature".into()))
+}
+
+fn write_tiff_float(buf: &mut Cursor<Vec<u8>>, img: &DynamicImage) -> Result<(), Error> {
+    let (width, height) = img.dimensions();
+    let mut encoder = TiffEncoder::new(buf).map_err(tiff_encode_error)?;
+    let written = match img {
+        DynamicImage::ImageRgb32F(rgb) => encoder.write_image::<colortype::RGB32Float>(width, height, rgb.as_raw()),
+        DynamicImage::ImageRgba32F(rgba) => encoder.write_image::<colortype::RGBA32Float>(width, height, rgba.as_raw()),
+        _ => return Err(Error::UnsupportedFormat(format!("{:?} as float TIFF", img.color()))),
+    };
+    written.map_err(tiff_encode_error)
+}
+
+/// Low-pass filters everything outside `regions` by down- and upsampling.
+pub fn degrade_background(img: &DynamicImage, regions: &[Rect], downscale: u32) -> DynamicImage {
+    let (width, height) = img.dimensions();
+    let downscale = downscale.max(1);
+    let mut out = img
+        .resize_exact((width / downscale).max(1), (height / downscale).max(1), FilterType::Triangle)
+        .resize_exact(width, height, FilterType::Triangle);
+
+    for region in regions.iter().filter_map(|r| r.clamp_to(width, height)) {
+        let patch = img.crop_imm(region.x, region.y, region.width, region.height);
+        imageops::replace(&mut out, &patch, region.x as i64, region.y as i64);
+    }
+    out
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ResizeNodeConfig {
+    pub width: u32,
+    pub height: u32,
+    /// Scale to fit within `width` x `height` keeping the aspect ratio, instead of stretching to it.
+    pub keep_aspect: bool,
+}
+
+impl Default for ResizeNodeConfig {
+    fn default() -> Self {
+        Self { width: 640, height: 480, keep_aspect: true }
+    }
+}
+
+impl Validate for ResizeNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        ensure(self.width > 0 && self.height > 0, "width", "frames must not be empty")
+    }
+}
+
+config_builder!(ResizeNodeConfig for ResizeNode { width: u32, height: u32, keep_aspect: bool });
+
+/// Bilinear resize of `img` per `config`; empty images cannot be scaled and are rejected.
+pub fn resize(img: &DynamicImage, config: &ResizeNodeConfig) -> Result<DynamicImage, Error> {
+    if img.width() == 0 || img.height() == 0 {
+        return Err(Error::Conversion("empty images cannot be resized".into()));
+    }
+    Ok(if config.keep_aspect {
+        resize_to_fit(img, config.width, config.height)
+    } else {
+        resize_exact(img, config.width, config.height)
+    })
+}
+
+/// Resizes frames on the CPU; see `gpu_resize` for the GPU counterpart.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct ResizeNode {
+    #[output]
+    pub output: Output<DynamicImage>,
+
+    #[input]
+    pub input: Input<DynamicImage>,
+
+    pub config: ResizeNodeConfig,
+}
+
+impl ResizeNode {
+    pub fn new(config: ResizeNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            input: Input::new(),
+            config,
+        }
+    }
+}
+
+impl Node for ResizeNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        if let Ok(img) = self.input.next() {
+            let resized = resize(&img, &self.config)?;
+            self.output.send(resized).map_err(|e| UpdateError::Other(e.into()))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub enum PyramidMode {
+    /// Gaussian blur before subsampling, avoiding aliasing in the coarser levels.
+    #[default]
+    Gaussian,
+    /// Plain 2x box-filtered downscaling.
+    Half,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct PyramidNodeConfig {
+    /// Number of levels including the full-resolution input.
+    pub levels: usize,
+    pub mode: PyramidMode,
+}
+
+impl Default for PyramidNodeConfig {
+    fn default() -> Self {
+        Self { levels: 4, mode: PyramidMode::Gaussian }
+    }
+}
+
+impl Validate for PyramidNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        ensure(self.levels > 0, "levels", "must be positive")
+    }
+}
+
+config_builder!(PyramidNodeConfig for PyramidNode { levels: usize, mode: PyramidMode });
+
+/// Emits the input and successively halved levels, finest first.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct PyramidNode {
+    #[output]
+    pub output: Output<Vec<DynamicImage>>,
+
+    #[input]
+    pub input: Input<DynamicImage>,
+
+    pub config: PyramidNodeConfig,
+
+    #[serde(skip)]
+    frames: u64,
+}
+
+impl PyramidNode {
+    pub fn new(config: PyramidNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            input: Input::new(),
+            config,
+            frames: 0,
+        }
+    }
+}
+
+/// Builds up to `levels` pyramid levels, stopping early once a side would shrink below one pixel.
+pub fn build_pyramid(img: DynamicImage, levels: usize, mode: PyramidMode) -> Vec<DynamicImage> {
+    let mut pyramid = vec![img];
+    while pyramid.len() < levels {
+        let prev = &pyramid[pyramid.len() - 1];
+        let (w, h) = (prev.width() / 2, prev.height() / 2);
+        if w == 0 || h == 0 {
+            break;
+        }
+        let next = match mode {
+            PyramidMode::Gaussian => prev.blur(1.0).resize_exact(w, h, FilterType::Nearest),
+            PyramidMode::Half => prev.resize_exact(w, h, FilterType::Triangle),
+        };
+        pyramid.push(next);
+    }
+    pyramid
+}
+
+impl Node for PyramidNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        if let Ok(img) = self.input.next() {
+            frame_span!("build_pyramid", frame = self.frames, levels = self.config.levels);
+            self.frames += 1;
+            let pyramid = build_pyramid(img, self.config.levels.max(1), self.config.mode);
+            self.output.send(pyramid).map_err(|e| UpdateError::Other(e.into()))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct TileSplitNodeConfig {
+    pub tile_width: u32,
+    pub tile_height: u32,
+    /// Pixels shared by neighbouring tiles, so objects on a seam appear whole in one tile.
+    pub overlap: u32,
+}
+
+impl Default for TileSplitNodeConfig {
+    fn default() -> Self {
+        Self { tile_width: 512, tile_height: 512, overlap: 32 }
+    }
+}
+
+impl Validate for TileSplitNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        ensure(self.tile_width > 0 && self.tile_height > 0, "tile_width", "tiles must not be empty")
+    }
+}
+
+config_builder!(TileSplitNodeConfig for TileSplitNode { tile_width: u32, tile_height: u32, overlap: u32 });
+
+/// Tile origins along one axis and the seams between their cores.
+fn tile_axis(length: u32, tile: u32, overlap: u32) -> Vec<(u32, u32, u32)> {
+    let tile = tile.clamp(1, length.max(1));
+    let step = tile.saturating_sub(overlap).max(1);
+    let mut starts: Vec<u32> = (0..length.saturating_sub(tile)).step_by(step as usize).collect();
+    starts.push(length.saturating_sub(tile));
+    starts.dedup();
+
+    (0..starts.len())
+        .map(|i| {
+            let seam = |j: usize| (starts[j] + tile + starts[j + 1]) / 2;
+            let core_start = if i == 0 { 0 } else { seam(i - 1) };
+            let core_end = if i + 1 == starts.len() { length } else { seam(i) };
+            (starts[i], core_start, core_end)
+        })
+        .collect()
+}
+
+/// Tile layout covering a `width`x`height` frame.
+pub fn tile_layout(width: u32, height: u32, config: &TileSplitNodeConfig, frame: u64) -> Vec<TileInfo> {
+    let xs = tile_axis(width, config.tile_width, config.overlap);
+    let ys = tile_axis(height, config.tile_height, config.overlap);
+    let (tw, th) = (config.tile_width.clamp(1, width.max(1)), config.tile_height.clamp(1, height.max(1)));
+    let count = xs.len() * ys.len();
+    ys.iter()
+        .flat_map(|y| xs.iter().map(move |x| (*x, *y)))
+        .enumerate()
+        .map(|(index, ((x, cx0, cx1), (y, cy0, cy1)))| TileInfo {
+            frame,
+            index,
+            count,
+            frame_width: width,
+            frame_height: height,
+            region: Rect::new(x, y, tw, th),
+            core: Rect::new(cx0, cy0, cx1 - cx0, cy1 - cy0),
+        })
+        .collect()
+}
+
+/// Cuts large frames into overlapping tiles for per-tile processing.
+///
+/// Tiles are sent one by one on `output`, each preceded by its [`TileInfo`] on
+/// `info`, so a [`TileMergeNode`] can pair results with their tiles in order.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct TileSplitNode {
+    #[output]
+    pub output: Output<DynamicImage>,
+
+    #[output]
+    pub info: Output<TileInfo>,
+
+    #[input]
+    pub input: Input<DynamicImage>,
+
+    pub config: TileSplitNodeConfig,
+
+    #[serde(skip)]
+    frame: u64,
+}
+
+impl TileSplitNode {
+    pub fn new(config: TileSplitNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            info: Output::new(change_observer),
+            input: Input::new(),
+            config,
+            frame: 0,
+        }
+    }
+}
+
+impl Node for TileSplitNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        if let Ok(img) = self.input.next() {
+            frame_span!("split_tiles", frame = self.frame);
+            for tile in tile_layout(img.width(), img.height(), &self.config, self.frame) {
+                let r = tile.region;
+                self.info.send(tile).map_err(|e| UpdateError::Other(e.into()))?;
+                self.output.send(img.crop_imm(r.x, r.y, r.width, r.height)).map_err(|e| UpdateError::Other(e.into()))?;
+            }
+            self.frame += 1;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub enum TileMergeMode {
+    /// Reassemble processed tile images into a full frame.
+    #[default]
+    Images,
+    /// Map per-tile detections back to frame coordinates and suppress duplicates on the seams.
+    Detections { iou_threshold: f32 },
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct TileMergeNodeConfig {
+    pub mode: TileMergeMode,
+}
+
+impl Validate for TileMergeNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        Ok(())
+    }
+}
+
+config_builder!(TileMergeNodeConfig for TileMergeNode { mode: TileMergeMode });
+
+/// Stitches per-tile results from a [`TileSplitNode`] back into per-frame results.
+///
+/// Results must arrive in the same order as the tile infos, one per tile.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct TileMergeNode {
+    #[output]
+    pub output: Output<DynamicImage>,
+
+    #[output]
+    pub detections_out: Output<Vec<Detection>>,
+
+    #[input]
+    pub info: Input<TileInfo>,
+
+    #[input]
+    pub input: Input<DynamicImage>,
+
+    #[input]
+    pub detections: Input<Vec<Detection>>,
+
+    pub config: TileMergeNodeConfig,
+
+    #[serde(skip)]
+    pending: VecDeque<TileInfo>,
+    #[serde(skip)]
+    canvas: Option<(u64, DynamicImage)>,
+    #[serde(skip)]
+    merged: Vec<Detection>,
+}
+
+impl TileMergeNode {
+    pub fn new(config: TileMergeNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            detections_out: Output::new(change_observer),
+            info: Input::new(),
+            input: Input::new(),
+            detections: Input::new(),
+            config,
+            pending: VecDeque::new(),
+            canvas: None,
+            merged: Vec::new(),
+        }
+    }
+
+    fn merge_image(&mut self, tile: TileInfo, img: DynamicImage) -> Result<(), UpdateError> {
+        frame_span!("merge_tile", frame = tile.frame, tile = tile.index);
+        let mut canvas = match self.canvas.take() {
+            Some((frame, canvas)) if frame == tile.frame => canvas,
+            _ => blank_like(&img, tile.frame_width, tile.frame_height),
+        };
+        // Processed tiles may have been rescaled; map the core into tile pixel coordinates.
+        let (sx, sy) = (img.width() as f64 / tile.region.width as f64, img.height() as f64 / tile.region.height as f64);
+        let core = img
+            .crop_imm(
+                ((tile.core.x - tile.region.x) as f64 * sx) as u32,
+                ((tile.core.y - tile.region.y) as f64 * sy) as u32,
+                (tile.core.width as f64 * sx).round() as u32,
+                (tile.core.height as f64 * sy).round() as u32,
+            )
+            .resize_exact(tile.core.width, tile.core.height, FilterType::Triangle);
+        imageops::replace(&mut canvas, &core, tile.core.x as i64, tile.core.y as i64);
+
+        if tile.index + 1 == tile.count {
+            self.output.send(canvas).map_err(|e| UpdateError::Other(e.into()))?;
+        } else {
+            self.canvas = Some((tile.frame, canvas));
+        }
+        Ok(())
+    }
+
+    fn merge_detections(&mut self, tile: TileInfo, detections: Vec<Detection>, iou_threshold: f32) -> Result<(), UpdateError> {
+        frame_span!("merge_tile", frame = tile.frame, tile = tile.index);
+        if tile.index == 0 {
+            self.merged.clear();
+        }
+        self.merged.extend(detections.into_iter().map(|mut d| {
+            d.rect = d.rect.translated(tile.region.x as i64, tile.region.y as i64);
+            d
+        }));
+
+        if tile.index + 1 == tile.count {
+            let merged = non_max_suppression(std::mem::take(&mut self.merged), iou_threshold);
+            self.detections_out.send(merged).map_err(|e| UpdateError::Other(e.into()))?;
+        }
+        Ok(())
+    }
+}
+
+/// Empty image with the same pixel layout as `img`.
+fn blank_like(img: &DynamicImage, width: u32, height: u32) -> DynamicImage {
+    match img {
+        DynamicImage::ImageLuma8(_) => DynamicImage::new_luma8(width, height),
+        DynamicImage::ImageLumaA8(_) => DynamicImage::new_luma_a8(width, height),
+        DynamicImage::ImageRgb8(_) => DynamicImage::new_rgb8(width, height),
+        DynamicImage::ImageLuma16(_) => DynamicImage::new_luma16(width, height),
+        DynamicImage::ImageLumaA16(_) => DynamicImage::new_luma_a16(width, height),
+        DynamicImage::ImageRgb16(_) => DynamicImage::new_rgb16(width, height),
+        DynamicImage::ImageRgba16(_) => DynamicImage::new_rgba16(width, height),
+        DynamicImage::ImageRgb32F(_) => DynamicImage::new_rgb32f(width, height),
+        DynamicImage::ImageRgba32F(_) => DynamicImage::new_rgba32f(width, height),
+        _ => DynamicImage::new_rgba8(width, height),
+    }
+}
+
+impl Node for TileMergeNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        while let Ok(tile) = self.info.next() {
+            self.pending.push_back(tile);
+        }
+
+        match self.config.mode {
+            TileMergeMode::Images => {
+                while !self.pending.is_empty() {
+                    let Ok(img) = self.input.next() else { break };
+                    let tile = self.pending.pop_front().expect("checked above");
+                    self.merge_image(tile, img)?;
+                }
+            }
+            TileMergeMode::Detections { iou_threshold } => {
+                while !self.pending.is_empty() {
+                    let Ok(detections) = self.detections.next() else { break };
+                    let tile = self.pending.pop_front().expect("checked above");
+                    self.merge_detections(tile, detections, iou_threshold)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+// TODO:    - Array3ToImage,
 //          - How to replace DynamicImage with something like ImageBuffer<P, Vec<<P as Pixel>::Subpixel>>
 
 
@@ -56,6 +1265,9 @@ pub struct ImageToArray3Node<T> {
 
     #[input]
     pub input: Input<DynamicImage>,
+
+    #[serde(skip)]
+    frames: u64,
 }
 
 impl<T> ImageToArray3Node<T>
@@ -63,7 +1275,8 @@ where T: Send + Sync {
     pub fn new(change_observer: Option<&ChangeObserver>) -> Self {
         Self {
             output: Output::new(change_observer),
-            input: Input::new()
+            input: Input::new(),
+            frames: 0,
         }
     }
 
@@ -84,6 +1297,8 @@ where T: Send + Sync + From<u8> + From<u16> + From<f32> {
     fn on_update(&mut self) -> Result<(), UpdateError> {
 
         if let Ok(data) = self.input.next() {
+            frame_span!("image_to_array3", frame = self.frames);
+            self.frames += 1;
 
             match data {
                 DynamicImage::ImageLuma8(img) => self.handle_image(img),    
@@ -0,0 +1,947 @@
+use flowrs::{node::{Node, UpdateError, ChangeObserver}, connection::{Input, Output}};
+use flowrs::RuntimeConnectable;
+
+use std::io::{self, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::mpsc::{self, Receiver};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use image::{DynamicImage, GenericImageView, GrayAlphaImage, GrayImage, RgbImage, RgbaImage};
+use anyhow::anyhow;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::{ensure, ConfigError, Validate};
+use crate::flow::{SourceGate, StatusReporter};
+use crate::transform::{decode_image, encode_image, EncodeFormat};
+use crate::types::{NodeState, NodeStatus, SourceControl};
+
+/// Pixel layouts that can be transported without conversion. Other formats are sent as RGBA8.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[repr(u32)]
+pub enum RawPixelFormat {
+    Luma8 = 1,
+    LumaA8 = 2,
+    Rgb8 = 3,
+    Rgba8 = 4,
+}
+
+impl RawPixelFormat {
+    pub fn from_code(code: u32) -> Option<Self> {
+        match code {
+            1 => Some(Self::Luma8),
+            2 => Some(Self::LumaA8),
+            3 => Some(Self::Rgb8),
+            4 => Some(Self::Rgba8),
+            _ => None,
+        }
+    }
+}
+
+/// Splits an image into its raw 8-bit pixel data and layout.
+pub fn image_to_raw(img: &DynamicImage) -> (RawPixelFormat, u32, u32, Vec<u8>) {
+    match img {
+        DynamicImage::ImageLuma8(i) => (RawPixelFormat::Luma8, i.width(), i.height(), i.as_raw().clone()),
+        DynamicImage::ImageLumaA8(i) => (RawPixelFormat::LumaA8, i.width(), i.height(), i.as_raw().clone()),
+        DynamicImage::ImageRgb8(i) => (RawPixelFormat::Rgb8, i.width(), i.height(), i.as_raw().clone()),
+        other => {
+            let i = other.to_rgba8();
+            (RawPixelFormat::Rgba8, i.width(), i.height(), i.into_raw())
+        }
+    }
+}
+
+/// Inverse of [`image_to_raw`].
+pub fn raw_to_image(format: RawPixelFormat, width: u32, height: u32, data: Vec<u8>) -> Option<DynamicImage> {
+    match format {
+        RawPixelFormat::Luma8 => GrayImage::from_raw(width, height, data).map(DynamicImage::ImageLuma8),
+        RawPixelFormat::LumaA8 => GrayAlphaImage::from_raw(width, height, data).map(DynamicImage::ImageLumaA8),
+        RawPixelFormat::Rgb8 => RgbImage::from_raw(width, height, data).map(DynamicImage::ImageRgb8),
+        RawPixelFormat::Rgba8 => RgbaImage::from_raw(width, height, data).map(DynamicImage::ImageRgba8),
+    }
+}
+
+/// Magic bytes opening every network frame.
+const FRAME_MAGIC: &[u8; 4] = b"FLIM";
+/// Upper bound for a single frame, protecting subscribers against corrupt length prefixes.
+const MAX_FRAME_LEN: usize = 256 * 1024 * 1024;
+
+/// Metadata sent in front of every encoded image.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct FrameHeader {
+    pub seq: u64,
+    pub width: u32,
+    pub height: u32,
+    pub format: EncodeFormat,
+    /// Milliseconds since the Unix epoch at which the publisher sent the frame.
+    pub timestamp_ms: u64,
+}
+
+/// Serializes a frame as `FLIM`, `u32` header length, `u32` payload length
+/// (both little-endian), the JSON header and the encoded image payload.
+pub fn write_frame<W: Write>(writer: &mut W, header: &FrameHeader, payload: &[u8]) -> io::Result<()> {
+    let header = serde_json::to_vec(header)?;
+    writer.write_all(FRAME_MAGIC)?;
+    writer.write_all(&(header.len() as u32).to_le_bytes())?;
+    writer.write_all(&(payload.len() as u32).to_le_bytes())?;
+    writer.write_all(&header)?;
+    writer.write_all(payload)?;
+    writer.flush()
+}
+
+/// Reads one frame written by [`write_frame`].
+pub fn read_frame<R: Read>(reader: &mut R) -> io::Result<(FrameHeader, Vec<u8>)> {
+    let mut prefix = [0u8; 12];
+    reader.read_exact(&mut prefix)?;
+    if &prefix[0..4] != FRAME_MAGIC {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "Invalid frame magic."));
+    }
+    let header_len = u32::from_le_bytes([prefix[4], prefix[5], prefix[6], prefix[7]]) as usize;
+    let payload_len = u32::from_le_bytes([prefix[8], prefix[9], prefix[10], prefix[11]]) as usize;
+    // Two u32 lengths can overflow a 32-bit usize, e.g. on wasm32.
+    if header_len.checked_add(payload_len).is_none_or(|len| len > MAX_FRAME_LEN) {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "Frame exceeds maximum size."));
+    }
+
+    let mut header = vec![0u8; header_len];
+    reader.read_exact(&mut header)?;
+    let mut payload = vec![0u8; payload_len];
+    reader.read_exact(&mut payload)?;
+    Ok((serde_json::from_slice(&header)?, payload))
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_millis() as u64)
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum TransportEndpoint {
+    /// Plain TCP. Publishers listen on the address, subscribers connect to it.
+    Tcp { address: String },
+    /// ZeroMQ PUB/SUB. Publishers bind the endpoint, subscribers connect to it.
+    #[cfg(feature = "zmq")]
+    Zmq { endpoint: String },
+}
+
+impl Default for TransportEndpoint {
+    fn default() -> Self {
+        TransportEndpoint::Tcp { address: "127.0.0.1:5555".into() }
+    }
+}
+
+impl Validate for TransportEndpoint {
+    fn validate(&self) -> Result<(), ConfigError> {
+        match self {
+            TransportEndpoint::Tcp { address } => ensure(!address.is_empty(), "address", "must be set"),
+            #[cfg(feature = "zmq")]
+            TransportEndpoint::Zmq { endpoint } => ensure(!endpoint.is_empty(), "endpoint", "must be set"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ImagePublisherNodeConfig {
+    pub endpoint: TransportEndpoint,
+    pub format: EncodeFormat,
+}
+
+impl Validate for ImagePublisherNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        self.endpoint.validate_in("endpoint")?;
+        self.format.validate_in("format")
+    }
+}
+
+config_builder!(ImagePublisherNodeConfig for ImagePublisherNode { endpoint: TransportEndpoint, format: EncodeFormat });
+
+/// TCP subscribers that cannot accept a frame within this time are disconnected.
+const WRITE_TIMEOUT: Duration = Duration::from_millis(500);
+
+enum PublisherSocket {
+    Tcp { listener: TcpListener, clients: Vec<TcpStream> },
+    #[cfg(feature = "zmq")]
+    Zmq(zmq::Socket),
+}
+
+/// Sends encoded frames to remote [`ImageSubscriberNode`]s.
+///
+/// A TCP subscriber that disconnects or stalls for longer than `WRITE_TIMEOUT`
+/// is dropped, so a slow client never holds up the flow.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct ImagePublisherNode {
+    #[output]
+    pub health: Output<NodeStatus>,
+
+    #[input]
+    pub input: Input<DynamicImage>,
+
+    pub config: ImagePublisherNodeConfig,
+
+    #[serde(skip)]
+    socket: Option<PublisherSocket>,
+    #[serde(skip)]
+    seq: u64,
+    #[serde(skip)]
+    reporter: StatusReporter,
+}
+
+impl ImagePublisherNode {
+    pub fn new(config: ImagePublisherNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            health: Output::new(change_observer),
+            input: Input::new(),
+            config,
+            socket: None,
+            seq: 0,
+            reporter: StatusReporter::default(),
+        }
+    }
+
+    fn open(&self) -> Result<PublisherSocket, anyhow::Error> {
+        match &self.config.endpoint {
+            TransportEndpoint::Tcp { address } => {
+                let listener = TcpListener::bind(address)?;
+                listener.set_nonblocking(true)?;
+                Ok(PublisherSocket::Tcp { listener, clients: Vec::new() })
+            }
+            #[cfg(feature = "zmq")]
+            TransportEndpoint::Zmq { endpoint } => {
+                let socket = zmq::Context::new().socket(zmq::PUB)?;
+                socket.bind(endpoint)?;
+                Ok(PublisherSocket::Zmq(socket))
+            }
+        }
+    }
+
+    fn update(&mut self) -> Result<(), UpdateError> {
+
+        if self.socket.is_none() {
+            self.socket = Some(self.open().map_err(UpdateError::Other)?);
+        }
+
+        if let Some(PublisherSocket::Tcp { listener, clients }) = self.socket.as_mut() {
+            while let Ok((stream, _)) = listener.accept() {
+                // Accepted streams inherit non-blocking mode on some platforms.
+                if stream.set_nonblocking(false).is_ok() && stream.set_write_timeout(Some(WRITE_TIMEOUT)).is_ok() {
+                    clients.push(stream);
+                }
+            }
+        }
+
+        if let Ok(img) = self.input.next() {
+            let (width, height) = img.dimensions();
+            frame_span!("image_publish", frame = self.seq + 1, width = width, height = height);
+            let payload = encode_image(&img, self.config.format)?;
+            self.seq += 1;
+            let header = FrameHeader { seq: self.seq, width, height, format: self.config.format, timestamp_ms: now_ms() };
+
+            match self.socket.as_mut().expect("opened above") {
+                PublisherSocket::Tcp { clients, .. } => {
+                    // Disconnected or stalled clients are dropped rather than failing the flow;
+                    // a partly written frame would leave their stream out of step anyway.
+                    clients.retain_mut(|client| write_frame(client, &header, &payload).is_ok());
+                }
+                #[cfg(feature = "zmq")]
+                PublisherSocket::Zmq(socket) => {
+                    let mut message = Vec::with_capacity(payload.len() + 256);
+                    write_frame(&mut message, &header, &payload).map_err(|e| UpdateError::Other(e.into()))?;
+                    socket.send(message, 0).map_err(|e| UpdateError::Other(e.into()))?;
+                }
+            }
+            self.reporter.frame();
+        }
+        Ok(())
+    }
+}
+
+impl Node for ImagePublisherNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+        let result = self.update();
+        self.reporter.track(result, &mut self.health)
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ImageSubscriberNodeConfig {
+    pub endpoint: TransportEndpoint,
+}
+
+impl Validate for ImageSubscriberNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        self.endpoint.validate_in("endpoint")
+    }
+}
+
+config_builder!(ImageSubscriberNodeConfig for ImageSubscriberNode { endpoint: TransportEndpoint });
+
+enum SubscriberSocket {
+    Tcp(Receiver<io::Result<(FrameHeader, Vec<u8>)>>),
+    #[cfg(feature = "zmq")]
+    Zmq(zmq::Socket),
+}
+
+/// Receives frames sent by an [`ImagePublisherNode`].
+///
+/// Frames arriving while `control` has paused the node are dropped; stopping it disconnects.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct ImageSubscriberNode {
+    #[output]
+    pub output: Output<DynamicImage>,
+
+    #[output]
+    pub metadata: Output<FrameHeader>,
+
+    #[output]
+    pub health: Output<NodeStatus>,
+
+    #[input]
+    pub control: Input<SourceControl>,
+
+    pub config: ImageSubscriberNodeConfig,
+
+    #[serde(skip)]
+    socket: Option<SubscriberSocket>,
+    #[serde(skip)]
+    reporter: StatusReporter,
+    #[serde(skip)]
+    gate: SourceGate,
+}
+
+impl ImageSubscriberNode {
+    pub fn new(config: ImageSubscriberNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            metadata: Output::new(change_observer),
+            health: Output::new(change_observer),
+            control: Input::new(),
+            config,
+            socket: None,
+            reporter: StatusReporter::default(),
+            gate: SourceGate::default(),
+        }
+    }
+
+    fn open(&self) -> Result<SubscriberSocket, anyhow::Error> {
+        match &self.config.endpoint {
+            TransportEndpoint::Tcp { address } => {
+                let mut stream = TcpStream::connect(address)?;
+                let (tx, rx) = mpsc::channel();
+                // Blocking reads happen off the update loop; the thread ends with the connection.
+                std::thread::spawn(move || loop {
+                    let frame = read_frame(&mut stream);
+                    let failed = frame.is_err();
+                    if tx.send(frame).is_err() || failed {
+                        break;
+                    }
+                });
+                Ok(SubscriberSocket::Tcp(rx))
+            }
+            #[cfg(feature = "zmq")]
+            TransportEndpoint::Zmq { endpoint } => {
+                let socket = zmq::Context::new().socket(zmq::SUB)?;
+                socket.connect(endpoint)?;
+                socket.set_subscribe(b"")?;
+                Ok(SubscriberSocket::Zmq(socket))
+            }
+        }
+    }
+
+    fn emit(&mut self, header: FrameHeader, payload: Vec<u8>) -> Result<(), UpdateError> {
+        frame_span!("image_subscribe", frame = header.seq, bytes = payload.len());
+        let img = decode_image(payload)?;
+        self.reporter.frame();
+        self.gate.produced();
+        self.output.send(img).map_err(|e| UpdateError::Other(e.into()))?;
+        self.metadata.send(header).map_err(|e| UpdateError::Other(e.into()))?;
+        Ok(())
+    }
+
+    fn update(&mut self) -> Result<(), UpdateError> {
+
+        if self.gate.poll(&mut self.control) {
+            self.socket = None;
+        }
+        if !self.gate.running() {
+            self.reporter.set_state(NodeState::Paused);
+        }
+        if self.gate.mode() == SourceControl::Stop {
+            return Ok(());
+        }
+        if self.socket.is_none() {
+            self.socket = Some(self.open().map_err(UpdateError::Other)?);
+        }
+
+        let mut frames = Vec::new();
+        let mut lost = None;
+        match self.socket.as_ref().expect("opened above") {
+            SubscriberSocket::Tcp(rx) => {
+                for frame in rx.try_iter() {
+                    match frame {
+                        Ok(frame) => frames.push(frame),
+                        Err(e) => {
+                            lost = Some(e);
+                            break;
+                        }
+                    }
+                }
+            }
+            #[cfg(feature = "zmq")]
+            SubscriberSocket::Zmq(socket) => {
+                while let Ok(message) = socket.recv_bytes(zmq::DONTWAIT) {
+                    frames.push(read_frame(&mut message.as_slice()).map_err(|e| UpdateError::Other(e.into()))?);
+                }
+            }
+        }
+
+        // Frames arriving while paused are discarded, so they cannot pile up.
+        for (header, payload) in frames {
+            if self.gate.running() {
+                self.emit(header, payload)?;
+            }
+        }
+
+        if let Some(e) = lost {
+            // Reconnect on the next update.
+            self.socket = None;
+            return Err(UpdateError::Other(anyhow!("Image subscription lost: {}", e)));
+        }
+        Ok(())
+    }
+}
+
+impl Node for ImageSubscriberNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+        let result = self.update();
+        self.reporter.track(result, &mut self.health)
+    }
+}
+
+/// Lane a frame travels on between a [`LaneSplitNode`] and a [`LaneMergeNode`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum FramePriority {
+    /// Reduced frames for interactive feedback; stale ones may be dropped.
+    Preview,
+    /// Full-quality frames that must not be dropped, e.g. for recording.
+    Quality,
+}
+
+/// A frame tagged with its lane and its position in the original stream.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PrioritizedFrame {
+    pub seq: u64,
+    pub priority: FramePriority,
+    pub image: DynamicImage,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct LaneSplitNodeConfig {
+    /// Every n-th frame also goes to the quality lane; `1` sends all of them.
+    pub quality_every: u32,
+    /// Preview frames are scaled down to fit this width, if set.
+    pub preview_max_width: Option<u32>,
+}
+
+impl Default for LaneSplitNodeConfig {
+    fn default() -> Self {
+        Self { quality_every: 1, preview_max_width: None }
+    }
+}
+
+impl Validate for LaneSplitNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        ensure(self.quality_every > 0, "quality_every", "must be positive")?;
+        ensure(self.preview_max_width.is_none_or(|w| w > 0), "preview_max_width", "must be positive")
+    }
+}
+
+config_builder!(LaneSplitNodeConfig for LaneSplitNode { quality_every: u32, preview_max_width: Option<u32> });
+
+/// Tags frames with a priority and sends them on a preview and a quality lane.
+///
+/// Every frame goes to `preview`, and every `quality_every`-th one to `quality`
+/// too. Put the slow path (e.g. a high-quality encode) behind `quality` and join
+/// both lanes again with a [`LaneMergeNode`], so a congested quality path no
+/// longer holds back the preview.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct LaneSplitNode {
+    #[output]
+    pub preview: Output<PrioritizedFrame>,
+
+    #[output]
+    pub quality: Output<PrioritizedFrame>,
+
+    #[input]
+    pub input: Input<DynamicImage>,
+
+    pub config: LaneSplitNodeConfig,
+
+    #[serde(skip)]
+    seq: u64,
+}
+
+impl LaneSplitNode {
+    pub fn new(config: LaneSplitNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            preview: Output::new(change_observer),
+            quality: Output::new(change_observer),
+            input: Input::new(),
+            config,
+            seq: 0,
+        }
+    }
+}
+
+impl Node for LaneSplitNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        if let Ok(img) = self.input.next() {
+            let seq = self.seq;
+            frame_span!("lane_split", frame = seq);
+            self.seq += 1;
+
+            if seq.is_multiple_of(self.config.quality_every.max(1) as u64) {
+                let frame = PrioritizedFrame { seq, priority: FramePriority::Quality, image: img.clone() };
+                self.quality.send(frame).map_err(|e| UpdateError::Other(e.into()))?;
+            }
+
+            let image = match self.config.preview_max_width {
+                Some(w) if img.width() > w => img.resize(w, u32::MAX, image::imageops::FilterType::Triangle),
+                _ => img,
+            };
+            let frame = PrioritizedFrame { seq, priority: FramePriority::Preview, image };
+            self.preview.send(frame).map_err(|e| UpdateError::Other(e.into()))?;
+        }
+        Ok(())
+    }
+}
+
+/// Orders the frames that arrived on both lanes for output.
+///
+/// Every quality frame is kept, also when the preview of the same frame was
+/// emitted before. The preview survives only if it is newer than `last_seq`,
+/// the newest frame emitted so far, and than every quality frame of this batch;
+/// a quality frame thereby wins over a preview with the same sequence number.
+pub fn merge_lanes(last_seq: Option<u64>, quality: Vec<PrioritizedFrame>, preview: Option<PrioritizedFrame>) -> Vec<PrioritizedFrame> {
+    let mut frames = quality;
+    frames.sort_by_key(|f| f.seq);
+    frames.dedup_by_key(|f| f.seq);
+    let newest = last_seq.max(frames.last().map(|f| f.seq));
+    frames.extend(preview.filter(|p| newest.is_none_or(|n| p.seq > n)));
+    frames
+}
+
+/// Joins the lanes of a [`LaneSplitNode`] into one stream.
+///
+/// Previews are forwarded as soon as they arrive and never step back in time.
+/// Quality frames are always forwarded and replace the preview of their frame,
+/// so they may follow previews of newer frames; consumers tell them apart by
+/// `seq` and `priority`.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct LaneMergeNode {
+    #[output]
+    pub output: Output<PrioritizedFrame>,
+
+    #[input]
+    pub preview: Input<PrioritizedFrame>,
+
+    #[input]
+    pub quality: Input<PrioritizedFrame>,
+
+    #[serde(skip)]
+    last_seq: Option<u64>,
+}
+
+impl LaneMergeNode {
+    pub fn new(change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            preview: Input::new(),
+            quality: Input::new(),
+            last_seq: None,
+        }
+    }
+}
+
+impl Node for LaneMergeNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        let mut quality = Vec::new();
+        while let Ok(frame) = self.quality.next() {
+            quality.push(frame);
+        }
+        let mut preview = None;
+        while let Ok(frame) = self.preview.next() {
+            preview = Some(frame);
+        }
+
+        for frame in merge_lanes(self.last_seq, quality, preview) {
+            frame_span!("lane_merge", frame = frame.seq);
+            self.last_seq = self.last_seq.max(Some(frame.seq));
+            self.output.send(frame).map_err(|e| UpdateError::Other(e.into()))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "shm")]
+pub use self::shm::{SharedMemReaderNode, SharedMemReaderNodeConfig, SharedMemWriterNode, SharedMemWriterNodeConfig};
+
+/// Memory-mapped frame ring buffer.
+///
+/// Layout (all integers little-endian):
+/// - file header, 32 bytes: magic `FLSH`, version `u32`, slot count `u32`, generation `u32`,
+///   slot size `u64`, latest committed sequence number `u64`
+/// - `slot_count` slots of `SLOT_HEADER_LEN + slot_size` bytes, each starting with
+///   sequence `u64`, width `u32`, height `u32`, format `u32`, data length `u32`, padding
+///
+/// Sequence numbers start at 1. Writers zero a slot's sequence before overwriting it,
+/// so readers can detect torn reads by checking the sequence before and after copying.
+/// Magic and version form one 64-bit word that is written last, so readers never see a
+/// half-initialized header. Each writer start bumps the generation so readers know to
+/// start over at sequence 1. The file is only ever grown, so a restarting writer cannot
+/// invalidate the pages readers still have mapped.
+#[cfg(feature = "shm")]
+mod shm {
+    use super::*;
+
+    use std::fs::{self, OpenOptions};
+    use std::path::PathBuf;
+    use std::sync::atomic::{fence, AtomicU64, Ordering};
+
+    use memmap2::{Mmap, MmapMut};
+
+    use crate::config::ensure;
+
+    const MAGIC: &[u8; 4] = b"FLSH";
+    const VERSION: u32 = 1;
+    const FILE_HEADER_LEN: usize = 32;
+    const SLOT_HEADER_LEN: usize = 32;
+    const LATEST_SEQ_OFFSET: usize = 24;
+
+    /// Magic and version as they appear in the first header word.
+    fn header_word() -> u64 {
+        u64::from(u32::from_le_bytes(*MAGIC)) | (u64::from(VERSION) << 32)
+    }
+
+    fn atomic_at(buf: &[u8], offset: usize) -> &AtomicU64 {
+        let bytes = &buf[offset..offset + 8];
+        // Offsets are multiples of 8 into a page-aligned mapping.
+        assert_eq!(bytes.as_ptr() as usize % 8, 0, "unaligned shared memory atomic");
+        unsafe { &*(bytes.as_ptr() as *const AtomicU64) }
+    }
+
+    fn read_u32(buf: &[u8], offset: usize) -> u32 {
+        u32::from_le_bytes(buf[offset..offset + 4].try_into().expect("4-byte slice"))
+    }
+
+    fn read_u64(buf: &[u8], offset: usize) -> u64 {
+        u64::from_le_bytes(buf[offset..offset + 8].try_into().expect("8-byte slice"))
+    }
+
+    fn slot_offset(slot_size: usize, index: usize) -> usize {
+        // Slot size is rounded to 8 bytes so every slot header stays aligned.
+        FILE_HEADER_LEN + index * (SLOT_HEADER_LEN + slot_size)
+    }
+
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    #[serde(default)]
+    pub struct SharedMemWriterNodeConfig {
+        pub path: PathBuf,
+        pub slot_count: u32,
+        /// Maximum pixel payload per frame in bytes.
+        pub slot_size: u64,
+    }
+
+    impl Default for SharedMemWriterNodeConfig {
+        fn default() -> Self {
+            Self { path: "/dev/shm/flowrs-img".into(), slot_count: 4, slot_size: 3840 * 2160 * 4 }
+        }
+    }
+
+    impl Validate for SharedMemWriterNodeConfig {
+        fn validate(&self) -> Result<(), ConfigError> {
+            ensure(self.slot_count > 0 && self.slot_size > 0, "slot_count", "the ring must hold at least one slot")
+        }
+    }
+
+    config_builder!(SharedMemWriterNodeConfig for SharedMemWriterNode { path: PathBuf, slot_count: u32, slot_size: u64 });
+
+    /// Publishes frames into a memory-mapped ring buffer file.
+    #[derive(RuntimeConnectable, Deserialize, Serialize)]
+    pub struct SharedMemWriterNode {
+        #[output]
+        pub health: Output<NodeStatus>,
+
+        #[input]
+        pub input: Input<DynamicImage>,
+
+        pub config: SharedMemWriterNodeConfig,
+
+        #[serde(skip)]
+        mmap: Option<MmapMut>,
+        #[serde(skip)]
+        seq: u64,
+        #[serde(skip)]
+        reporter: StatusReporter,
+    }
+
+    impl SharedMemWriterNode {
+        pub fn new(config: SharedMemWriterNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+            Self {
+                health: Output::new(change_observer),
+                input: Input::new(),
+                config,
+                mmap: None,
+                seq: 0,
+                reporter: StatusReporter::default(),
+            }
+        }
+
+        fn slot_size(&self) -> usize {
+            (self.config.slot_size as usize + 7) & !7
+        }
+
+        fn open(&self) -> Result<MmapMut, anyhow::Error> {
+            if self.config.slot_count == 0 {
+                return Err(anyhow!("Shared memory ring buffer needs at least one slot."));
+            }
+            let len = slot_offset(self.slot_size(), self.config.slot_count as usize);
+            // Truncating would pull the pages from under readers still mapping a previous run.
+            let file = OpenOptions::new()
+                .read(true)
+                .write(true)
+                .create(true)
+                .truncate(false)
+                .open(&self.config.path)?;
+            let existing = file.metadata()?.len();
+            let mut generation = 0u32;
+            if existing >= FILE_HEADER_LEN as u64 {
+                // Readers let go of the ring until the new header is published.
+                let mmap = unsafe { MmapMut::map_mut(&file)? };
+                atomic_at(&mmap, 0).store(0, Ordering::Release);
+                generation = read_u32(&mmap, 12).wrapping_add(1);
+            }
+            if existing < len as u64 {
+                file.set_len(len as u64)?;
+            }
+            let mut mmap = unsafe { MmapMut::map_mut(&file)? };
+
+            mmap[8..12].copy_from_slice(&self.config.slot_count.to_le_bytes());
+            mmap[12..16].copy_from_slice(&generation.to_le_bytes());
+            mmap[16..24].copy_from_slice(&(self.slot_size() as u64).to_le_bytes());
+            atomic_at(&mmap, LATEST_SEQ_OFFSET).store(0, Ordering::Relaxed);
+            for index in 0..self.config.slot_count as usize {
+                atomic_at(&mmap, slot_offset(self.slot_size(), index)).store(0, Ordering::Relaxed);
+            }
+            atomic_at(&mmap, 0).store(header_word(), Ordering::Release);
+            Ok(mmap)
+        }
+
+        fn update(&mut self) -> Result<(), UpdateError> {
+
+            if let Ok(img) = self.input.next() {
+                frame_span!("shm_write", frame = self.seq + 1, width = img.width(), height = img.height());
+                if self.mmap.is_none() {
+                    self.mmap = Some(self.open().map_err(UpdateError::Other)?);
+                }
+                let slot_size = self.slot_size();
+                let slot_count = self.config.slot_count as u64;
+                let mmap = self.mmap.as_mut().expect("mapped above");
+
+                let (format, width, height, data) = image_to_raw(&img);
+                if data.len() > slot_size {
+                    return Err(UpdateError::Other(anyhow!(
+                        "Frame of {} bytes exceeds shared memory slot size of {} bytes.", data.len(), slot_size)));
+                }
+
+                self.seq += 1;
+                let offset = slot_offset(slot_size, ((self.seq - 1) % slot_count) as usize);
+                atomic_at(mmap, offset).store(0, Ordering::Relaxed);
+                // Keeps the payload writes below from becoming visible before the zeroed sequence.
+                fence(Ordering::Release);
+
+                mmap[offset + 8..offset + 12].copy_from_slice(&width.to_le_bytes());
+                mmap[offset + 12..offset + 16].copy_from_slice(&height.to_le_bytes());
+                mmap[offset + 16..offset + 20].copy_from_slice(&(format as u32).to_le_bytes());
+                mmap[offset + 20..offset + 24].copy_from_slice(&(data.len() as u32).to_le_bytes());
+                let start = offset + SLOT_HEADER_LEN;
+                mmap[start..start + data.len()].copy_from_slice(&data);
+
+                atomic_at(mmap, offset).store(self.seq, Ordering::Release);
+                atomic_at(mmap, LATEST_SEQ_OFFSET).store(self.seq, Ordering::Release);
+                self.reporter.frame();
+            }
+            Ok(())
+        }
+    }
+
+    impl Node for SharedMemWriterNode {
+        fn on_update(&mut self) -> Result<(), UpdateError> {
+            let result = self.update();
+            self.reporter.track(result, &mut self.health)
+        }
+    }
+
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    #[serde(default)]
+    pub struct SharedMemReaderNodeConfig {
+        pub path: PathBuf,
+    }
+
+    impl Default for SharedMemReaderNodeConfig {
+        fn default() -> Self {
+            Self { path: "/dev/shm/flowrs-img".into() }
+        }
+    }
+
+    impl Validate for SharedMemReaderNodeConfig {
+        fn validate(&self) -> Result<(), ConfigError> {
+            ensure(!self.path.as_os_str().is_empty(), "path", "must be set")
+        }
+    }
+
+    config_builder!(SharedMemReaderNodeConfig for SharedMemReaderNode { path: PathBuf });
+
+    /// Reads frames published by a [`SharedMemWriterNode`], possibly in another process.
+    ///
+    /// Frames overwritten before they could be read are skipped, as are frames published
+    /// while `control` has paused the node. Stopping it unmaps the ring.
+    #[derive(RuntimeConnectable, Deserialize, Serialize)]
+    pub struct SharedMemReaderNode {
+        #[output]
+        pub output: Output<DynamicImage>,
+
+        #[output]
+        pub health: Output<NodeStatus>,
+
+        #[input]
+        pub control: Input<SourceControl>,
+
+        pub config: SharedMemReaderNodeConfig,
+
+        #[serde(skip)]
+        mmap: Option<Mmap>,
+        #[serde(skip)]
+        last_seq: u64,
+        #[serde(skip)]
+        generation: Option<u32>,
+        #[serde(skip)]
+        reporter: StatusReporter,
+        #[serde(skip)]
+        gate: SourceGate,
+    }
+
+    impl SharedMemReaderNode {
+        pub fn new(config: SharedMemReaderNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+            Self {
+                output: Output::new(change_observer),
+                health: Output::new(change_observer),
+                control: Input::new(),
+                config,
+                mmap: None,
+                last_seq: 0,
+                generation: None,
+                reporter: StatusReporter::default(),
+                gate: SourceGate::default(),
+            }
+        }
+
+        fn read_slot(mmap: &Mmap, slot_size: usize, slot_count: u64, seq: u64) -> Option<DynamicImage> {
+            let offset = slot_offset(slot_size, ((seq - 1) % slot_count) as usize);
+            let slot_seq = atomic_at(mmap, offset);
+            if slot_seq.load(Ordering::Acquire) != seq {
+                return None;
+            }
+            let width = read_u32(mmap, offset + 8);
+            let height = read_u32(mmap, offset + 12);
+            let format = RawPixelFormat::from_code(read_u32(mmap, offset + 16))?;
+            let len = (read_u32(mmap, offset + 20) as usize).min(slot_size);
+            let start = offset + SLOT_HEADER_LEN;
+            let data = mmap[start..start + len].to_vec();
+            // Orders the copy above before the second look at the sequence.
+            fence(Ordering::Acquire);
+            if slot_seq.load(Ordering::Relaxed) != seq {
+                return None;
+            }
+            raw_to_image(format, width, height, data)
+        }
+
+        fn update(&mut self) -> Result<(), UpdateError> {
+
+            if self.gate.poll(&mut self.control) {
+                self.mmap = None;
+            }
+            if !self.gate.running() {
+                self.reporter.set_state(NodeState::Paused);
+            }
+            if self.gate.mode() == SourceControl::Stop {
+                return Ok(());
+            }
+
+            // The writer may not have created the file yet, or grown it since it was mapped.
+            let Ok(metadata) = fs::metadata(&self.config.path) else {
+                self.mmap = None;
+                return Ok(());
+            };
+            if self.mmap.as_ref().is_some_and(|mmap| mmap.len() as u64 != metadata.len()) {
+                self.mmap = None;
+            }
+            if self.mmap.is_none() {
+                if metadata.len() < FILE_HEADER_LEN as u64 {
+                    return Ok(());
+                }
+                let Ok(file) = OpenOptions::new().read(true).open(&self.config.path) else {
+                    return Ok(());
+                };
+                self.mmap = Some(unsafe { Mmap::map(&file) }.map_err(|e| UpdateError::Other(e.into()))?);
+            }
+            let mmap = self.mmap.as_ref().expect("mapped above");
+
+            if atomic_at(mmap, 0).load(Ordering::Acquire) != header_word() {
+                // Not yet initialized, or a writer is starting over.
+                return Ok(());
+            }
+            let generation = read_u32(mmap, 12);
+            if self.generation != Some(generation) {
+                self.generation = Some(generation);
+                self.last_seq = 0;
+            }
+            let slot_count = read_u32(mmap, 8) as u64;
+            let slot_size = read_u64(mmap, 16) as usize;
+            let end = slot_size
+                .checked_add(SLOT_HEADER_LEN)
+                .and_then(|slot| slot.checked_mul(slot_count as usize))
+                .and_then(|slots| slots.checked_add(FILE_HEADER_LEN));
+            if slot_count == 0 || !slot_size.is_multiple_of(8) || end.is_none_or(|end| end > mmap.len()) {
+                return Err(UpdateError::Other(anyhow!(
+                    "Shared memory file {} has an invalid ring buffer header.", self.config.path.display())));
+            }
+            let latest = atomic_at(mmap, LATEST_SEQ_OFFSET).load(Ordering::Acquire);
+
+            let oldest = latest.saturating_sub(slot_count - 1).max(self.last_seq + 1);
+            for seq in oldest..=latest {
+                if !self.gate.running() {
+                    break;
+                }
+                frame_span!("shm_read", frame = seq);
+                if let Some(img) = Self::read_slot(mmap, slot_size, slot_count, seq) {
+                    self.reporter.frame();
+                    self.gate.produced();
+                    self.output.send(img).map_err(|e| UpdateError::Other(e.into()))?;
+                }
+            }
+            self.last_seq = latest;
+            Ok(())
+        }
+    }
+
+    impl Node for SharedMemReaderNode {
+        fn on_update(&mut self) -> Result<(), UpdateError> {
+            let result = self.update();
+            self.reporter.track(result, &mut self.health)
+        }
+    }
+}
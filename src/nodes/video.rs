@@ -0,0 +1,518 @@
+use flowrs::{node::{Node, ShutdownError, UpdateError, ChangeObserver}, connection::{Input, Output}};
+use flowrs::RuntimeConnectable;
+
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+use std::sync::mpsc::{self, Receiver};
+
+use image::{DynamicImage, imageops::FilterType};
+use anyhow::anyhow;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::{ensure, ConfigError, Validate};
+use crate::flow::StatusReporter;
+use crate::types::NodeStatus;
+
+/// An `ffmpeg` child process consuming raw RGB24 frames on stdin.
+pub struct FfmpegProcess {
+    child: Child,
+    stdin: Option<ChildStdin>,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl FfmpegProcess {
+    /// Spawns `ffmpeg` reading `width`x`height` RGB24 frames at `fps`, followed by `output_args`.
+    pub fn spawn(width: u32, height: u32, fps: f32, output_args: &[String]) -> Result<Self, anyhow::Error> {
+        let mut child = Command::new("ffmpeg")
+            .args(["-loglevel", "error", "-y", "-f", "rawvideo", "-pix_fmt", "rgb24"])
+            .arg("-s").arg(format!("{}x{}", width, height))
+            .arg("-r").arg(fps.to_string())
+            .args(["-i", "-"])
+            .args(output_args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .map_err(|e| anyhow!("Could not start ffmpeg: {}", e))?;
+        let stdin = child.stdin.take();
+        Ok(Self { child, stdin, width, height })
+    }
+
+    /// Encoded output, for processes writing to `pipe:1`.
+    pub fn take_stdout(&mut self) -> Option<ChildStdout> {
+        self.child.stdout.take()
+    }
+
+    /// Writes a frame, resizing it if its dimensions differ from the stream's.
+    pub fn write_frame(&mut self, img: &DynamicImage) -> Result<(), anyhow::Error> {
+        let rgb = if img.width() != self.width || img.height() != self.height {
+            img.resize_exact(self.width, self.height, FilterType::Triangle).into_rgb8()
+        } else {
+            img.to_rgb8()
+        };
+        let stdin = self.stdin.as_mut().ok_or_else(|| anyhow!("ffmpeg input is closed."))?;
+        stdin.write_all(rgb.as_raw()).map_err(|e| anyhow!("ffmpeg stopped accepting frames: {}", e))
+    }
+
+    /// Closes stdin and waits for ffmpeg to write out everything it has buffered and exit.
+    pub fn finish(&mut self) -> Result<(), anyhow::Error> {
+        self.stdin.take();
+        let status = self.child.wait()?;
+        if !status.success() {
+            return Err(anyhow!("ffmpeg exited with {}", status));
+        }
+        Ok(())
+    }
+}
+
+impl Drop for FfmpegProcess {
+    fn drop(&mut self) {
+        // Closing stdin lets ffmpeg flush the last segment and exit.
+        self.stdin.take();
+        let _ = self.child.wait();
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct HlsSinkNodeConfig {
+    pub output_dir: PathBuf,
+    pub playlist_name: String,
+    pub fps: f32,
+    pub segment_duration_secs: u32,
+    /// Number of segments kept in the playlist and on disk.
+    pub retained_segments: u32,
+    /// ffmpeg video encoder, e.g. `libx264`.
+    pub encoder: String,
+    pub bitrate_kbps: u32,
+}
+
+impl Default for HlsSinkNodeConfig {
+    fn default() -> Self {
+        Self {
+            output_dir: PathBuf::from("hls"),
+            playlist_name: "stream.m3u8".into(),
+            fps: 30.0,
+            segment_duration_secs: 2,
+            retained_segments: 5,
+            encoder: "libx264".into(),
+            bitrate_kbps: 2000,
+        }
+    }
+}
+
+impl Validate for HlsSinkNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        ensure(self.fps > 0.0, "fps", "must be positive")?;
+        ensure(self.segment_duration_secs > 0, "segment_duration_secs", "must be positive")?;
+        ensure(self.retained_segments > 0, "retained_segments", "must be positive")
+    }
+}
+
+config_builder!(HlsSinkNodeConfig for HlsSinkNode {
+    output_dir: PathBuf,
+    playlist_name: String,
+    fps: f32,
+    segment_duration_secs: u32,
+    retained_segments: u32,
+    encoder: String,
+    bitrate_kbps: u32,
+});
+
+/// Encodes frames to H.264 and writes a rolling HLS playlist with segments to disk.
+///
+/// Encoding is delegated to an `ffmpeg` binary on the `PATH`. The stream size is fixed
+/// by the first frame; later frames of a different size are scaled to it.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct HlsSinkNode {
+    #[input]
+    pub input: Input<DynamicImage>,
+
+    #[output]
+    pub health: Output<NodeStatus>,
+
+    pub config: HlsSinkNodeConfig,
+
+    #[serde(skip)]
+    process: Option<FfmpegProcess>,
+    #[serde(skip)]
+    reporter: StatusReporter,
+}
+
+impl HlsSinkNode {
+    pub fn new(config: HlsSinkNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            input: Input::new(),
+            health: Output::new(change_observer),
+            config,
+            process: None,
+            reporter: StatusReporter::default(),
+        }
+    }
+
+    /// ffmpeg arguments after the raw video input, ending with the playlist path.
+    pub fn output_args(&self) -> Vec<String> {
+        let c = &self.config;
+        let segment_pattern = c.output_dir.join("segment_%05d.ts");
+        // A keyframe per segment boundary keeps segment durations exact.
+        let gop = ((c.fps * c.segment_duration_secs as f32).round() as u32).max(1);
+        let bitrate = format!("{}k", c.bitrate_kbps);
+        let gop = gop.to_string();
+        let segment_time = c.segment_duration_secs.to_string();
+        let list_size = c.retained_segments.to_string();
+        let segment_pattern = segment_pattern.to_string_lossy().into_owned();
+        let playlist = c.output_dir.join(&c.playlist_name).to_string_lossy().into_owned();
+        let args: &[&str] = &[
+            "-c:v", &c.encoder, "-b:v", &bitrate, "-pix_fmt", "yuv420p",
+            "-g", &gop, "-sc_threshold", "0",
+            "-f", "hls", "-hls_time", &segment_time, "-hls_list_size", &list_size,
+            "-hls_flags", "delete_segments+independent_segments",
+            "-hls_segment_filename", &segment_pattern, &playlist,
+        ];
+        args.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn update(&mut self) -> Result<(), UpdateError> {
+
+        if let Ok(img) = self.input.next() {
+            frame_span!("hls_sink", width = img.width(), height = img.height());
+            if self.process.is_none() {
+                std::fs::create_dir_all(&self.config.output_dir).map_err(|e| UpdateError::Other(e.into()))?;
+                // H.264 with 4:2:0 chroma needs even dimensions.
+                let (width, height) = (img.width() & !1, img.height() & !1);
+                let process = FfmpegProcess::spawn(width, height, self.config.fps, &self.output_args())
+                    .map_err(UpdateError::Other)?;
+                self.process = Some(process);
+            }
+            self.process.as_mut().expect("spawned above").write_frame(&img).map_err(UpdateError::Other)?;
+            self.reporter.frame();
+        }
+        Ok(())
+    }
+}
+
+impl Node for HlsSinkNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+        let result = self.update();
+        self.reporter.track(result, &mut self.health)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum VideoCodec {
+    #[default]
+    H264,
+    H265,
+}
+
+impl VideoCodec {
+    /// Name of the codec in ffmpeg encoder, muxer and bitstream filter names.
+    pub fn ffmpeg_name(&self) -> &'static str {
+        match self {
+            VideoCodec::H264 => "h264",
+            VideoCodec::H265 => "hevc",
+        }
+    }
+}
+
+/// Encoder implementation used by [`HwEncodeNode`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum HwBackend {
+    /// CPU encoding via libx264/libx265, for development machines without a supported GPU.
+    #[default]
+    Software,
+    #[cfg(feature = "vaapi")]
+    Vaapi { device: String },
+    #[cfg(feature = "nvenc")]
+    Nvenc,
+    #[cfg(feature = "videotoolbox")]
+    VideoToolbox,
+}
+
+impl HwBackend {
+    fn encoder_name(&self, codec: VideoCodec) -> String {
+        match self {
+            HwBackend::Software => match codec {
+                VideoCodec::H264 => "libx264".to_string(),
+                VideoCodec::H265 => "libx265".to_string(),
+            },
+            #[cfg(feature = "vaapi")]
+            HwBackend::Vaapi { .. } => format!("{}_vaapi", codec.ffmpeg_name()),
+            #[cfg(feature = "nvenc")]
+            HwBackend::Nvenc => format!("{}_nvenc", codec.ffmpeg_name()),
+            #[cfg(feature = "videotoolbox")]
+            HwBackend::VideoToolbox => format!("{}_videotoolbox", codec.ffmpeg_name()),
+        }
+    }
+
+    fn encoder_args(&self, codec: VideoCodec) -> Vec<String> {
+        let encoder = self.encoder_name(codec);
+        let args: Vec<&str> = match self {
+            HwBackend::Software => vec!["-c:v", encoder.as_str(), "-pix_fmt", "yuv420p"],
+            // Frames are uploaded to GPU surfaces by ffmpeg's filter graph.
+            #[cfg(feature = "vaapi")]
+            HwBackend::Vaapi { device } => vec!["-vaapi_device", device.as_str(), "-vf", "format=nv12,hwupload", "-c:v", encoder.as_str()],
+            #[cfg(feature = "nvenc")]
+            HwBackend::Nvenc => vec!["-c:v", encoder.as_str(), "-pix_fmt", "yuv420p"],
+            #[cfg(feature = "videotoolbox")]
+            HwBackend::VideoToolbox => vec!["-c:v", encoder.as_str(), "-pix_fmt", "nv12"],
+        };
+        args.into_iter().map(String::from).collect()
+    }
+}
+
+/// One access unit of an Annex-B elementary stream.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct VideoPacket {
+    pub seq: u64,
+    pub codec: VideoCodec,
+    pub keyframe: bool,
+    pub data: Vec<u8>,
+}
+
+/// Returns the NAL unit types contained in an Annex-B buffer.
+pub fn nal_types(data: &[u8], codec: VideoCodec) -> Vec<u8> {
+    let mut types = Vec::new();
+    let mut i = 0;
+    while i + 3 < data.len() {
+        if data[i] == 0 && data[i + 1] == 0 && data[i + 2] == 1 {
+            let header = data[i + 3];
+            types.push(match codec {
+                VideoCodec::H264 => header & 0x1f,
+                VideoCodec::H265 => (header >> 1) & 0x3f,
+            });
+            i += 3;
+        } else {
+            i += 1;
+        }
+    }
+    types
+}
+
+fn is_access_unit_delimiter(nal_type: u8, codec: VideoCodec) -> bool {
+    match codec {
+        VideoCodec::H264 => nal_type == 9,
+        VideoCodec::H265 => nal_type == 35,
+    }
+}
+
+/// Whether NAL unit types from [`nal_types`] include an IDR (H.264) or IRAP (H.265) picture.
+pub fn is_keyframe(types: &[u8], codec: VideoCodec) -> bool {
+    match codec {
+        VideoCodec::H264 => types.contains(&5),
+        VideoCodec::H265 => types.iter().any(|t| (16..=21).contains(t)),
+    }
+}
+
+/// Splits an Annex-B stream into access units at access unit delimiters.
+///
+/// Feed chunks with [`AccessUnitSplitter::push`]; complete units are returned as soon as
+/// the delimiter of the following unit has been seen. The last unit has none, so it is
+/// only returned by [`AccessUnitSplitter::finish`] at the end of the stream.
+pub struct AccessUnitSplitter {
+    codec: VideoCodec,
+    buffer: Vec<u8>,
+}
+
+impl AccessUnitSplitter {
+    pub fn new(codec: VideoCodec) -> Self {
+        Self { codec, buffer: Vec::new() }
+    }
+
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<Vec<u8>> {
+        self.buffer.extend_from_slice(chunk);
+        let mut units = Vec::new();
+        // Searching past the leading start code keeps a unit from splitting at its own delimiter.
+        while let Some(next) = self.find_delimiter(4) {
+            let rest = self.buffer.split_off(next);
+            let unit = std::mem::replace(&mut self.buffer, rest);
+            if !unit.is_empty() {
+                units.push(unit);
+            }
+        }
+        units
+    }
+
+    /// Returns the buffered remainder of the stream, i.e. its last access unit.
+    pub fn finish(&mut self) -> Option<Vec<u8>> {
+        Some(std::mem::take(&mut self.buffer)).filter(|unit| !unit.is_empty())
+    }
+
+    fn find_delimiter(&self, from: usize) -> Option<usize> {
+        let b = &self.buffer;
+        (from..b.len().saturating_sub(3)).find(|&i| {
+            b[i] == 0 && b[i + 1] == 0 && b[i + 2] == 1 && {
+                let header = b[i + 3];
+                let t = match self.codec {
+                    VideoCodec::H264 => header & 0x1f,
+                    VideoCodec::H265 => (header >> 1) & 0x3f,
+                };
+                is_access_unit_delimiter(t, self.codec)
+            }
+        })
+        // Include the leading zero of a four-byte start code.
+        .map(|i| if b[i - 1] == 0 { i - 1 } else { i })
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct HwEncodeNodeConfig {
+    pub backend: HwBackend,
+    pub codec: VideoCodec,
+    pub fps: f32,
+    pub bitrate_kbps: u32,
+    /// Frames between keyframes.
+    pub gop: u32,
+}
+
+impl Default for HwEncodeNodeConfig {
+    fn default() -> Self {
+        Self {
+            backend: HwBackend::Software,
+            codec: VideoCodec::H264,
+            fps: 30.0,
+            bitrate_kbps: 4000,
+            gop: 60,
+        }
+    }
+}
+
+impl Validate for HwEncodeNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        ensure(self.fps > 0.0, "fps", "must be positive")?;
+        ensure(self.bitrate_kbps > 0, "bitrate_kbps", "must be positive")?;
+        ensure(self.gop > 0, "gop", "must be positive")
+    }
+}
+
+config_builder!(HwEncodeNodeConfig for HwEncodeNode {
+    backend: HwBackend,
+    codec: VideoCodec,
+    fps: f32,
+    bitrate_kbps: u32,
+    gop: u32,
+});
+
+/// Encodes frames into H.264/H.265 packets using hardware encoders.
+///
+/// Encoding is delegated to an `ffmpeg` binary with the selected encoder; its Annex-B
+/// output is split into one [`VideoPacket`] per access unit. On shutdown the encoder
+/// is flushed, so the packets of the last frames are sent too.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct HwEncodeNode {
+    #[output]
+    pub output: Output<VideoPacket>,
+
+    #[input]
+    pub input: Input<DynamicImage>,
+
+    #[output]
+    pub health: Output<NodeStatus>,
+
+    pub config: HwEncodeNodeConfig,
+
+    #[serde(skip)]
+    process: Option<(FfmpegProcess, Receiver<Vec<u8>>)>,
+    #[serde(skip)]
+    seq: u64,
+    #[serde(skip)]
+    reporter: StatusReporter,
+}
+
+impl HwEncodeNode {
+    pub fn new(config: HwEncodeNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            input: Input::new(),
+            health: Output::new(change_observer),
+            config,
+            process: None,
+            seq: 0,
+            reporter: StatusReporter::default(),
+        }
+    }
+
+    fn spawn(&self, width: u32, height: u32) -> Result<(FfmpegProcess, Receiver<Vec<u8>>), anyhow::Error> {
+        let c = &self.config;
+        let format = c.codec.ffmpeg_name();
+        let bsf = format!("{}_metadata=aud=insert", format);
+        let mut args = c.backend.encoder_args(c.codec);
+        args.extend([
+            "-b:v".to_string(), format!("{}k", c.bitrate_kbps),
+            "-g".to_string(), c.gop.max(1).to_string(),
+            "-bsf:v".to_string(), bsf,
+            "-f".to_string(), format.to_string(), "pipe:1".to_string(),
+        ]);
+
+        let mut process = FfmpegProcess::spawn(width, height, c.fps, &args)?;
+        let mut stdout = process.take_stdout().ok_or_else(|| anyhow!("ffmpeg output is not piped."))?;
+        let mut splitter = AccessUnitSplitter::new(c.codec);
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            let mut chunk = vec![0u8; 64 * 1024];
+            while let Ok(n) = stdout.read(&mut chunk) {
+                if n == 0 {
+                    break;
+                }
+                for unit in splitter.push(&chunk[..n]) {
+                    if tx.send(unit).is_err() {
+                        return;
+                    }
+                }
+            }
+            if let Some(unit) = splitter.finish() {
+                let _ = tx.send(unit);
+            }
+        });
+        Ok((process, rx))
+    }
+
+    fn send_packets(&mut self, units: Vec<Vec<u8>>) -> Result<(), anyhow::Error> {
+        for data in units {
+            let keyframe = is_keyframe(&nal_types(&data, self.config.codec), self.config.codec);
+            let packet = VideoPacket { seq: self.seq, codec: self.config.codec, keyframe, data };
+            self.seq += 1;
+            self.output.send(packet)?;
+        }
+        Ok(())
+    }
+
+    fn update(&mut self) -> Result<(), UpdateError> {
+
+        if let Ok(img) = self.input.next() {
+            frame_span!("hw_encode", width = img.width(), height = img.height());
+            if self.process.is_none() {
+                let (width, height) = (img.width() & !1, img.height() & !1);
+                self.process = Some(self.spawn(width, height).map_err(UpdateError::Other)?);
+            }
+            let (process, _) = self.process.as_mut().expect("spawned above");
+            process.write_frame(&img).map_err(UpdateError::Other)?;
+            self.reporter.frame();
+        }
+
+        if let Some((_, packets)) = &self.process {
+            let units: Vec<Vec<u8>> = packets.try_iter().collect();
+            self.send_packets(units).map_err(UpdateError::Other)?;
+        }
+        Ok(())
+    }
+}
+
+impl Node for HwEncodeNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+        let result = self.update();
+        self.reporter.track(result, &mut self.health)
+    }
+
+    fn on_shutdown(&mut self) -> Result<(), ShutdownError> {
+        let Some((mut process, packets)) = self.process.take() else { return Ok(()) };
+        let finished = process.finish();
+        // The reader thread ends, and with it the channel, once ffmpeg has closed its output.
+        let units: Vec<Vec<u8>> = packets.iter().collect();
+        self.send_packets(units).map_err(ShutdownError::Other)?;
+        finished.map_err(ShutdownError::Other)
+    }
+}
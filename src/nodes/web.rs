@@ -0,0 +1,373 @@
+use flowrs::{node::{Node, UpdateError, ChangeObserver}, connection::{Input, Output}};
+use flowrs::RuntimeConnectable;
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::mpsc::{self, Receiver, Sender};
+
+use anyhow::anyhow;
+use image::{DynamicImage, RgbaImage};
+use js_sys::{Function, Promise, Uint8Array};
+use wasm_bindgen::{closure::Closure, prelude::wasm_bindgen, Clamped, JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{
+    CanvasRenderingContext2d, DragEvent, Event, FileList, HtmlCanvasElement, HtmlInputElement, ImageBitmap, ImageData,
+    OffscreenCanvas, OffscreenCanvasRenderingContext2d,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::{ensure, ConfigError, Validate};
+use crate::transform::{decode_image, encode_image, EncodeFormat};
+
+fn js_error(e: JsValue) -> anyhow::Error {
+    anyhow!("{}", e.as_string().unwrap_or_else(|| format!("{:?}", e)))
+}
+
+/// A file the user picked or dropped.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WebFile {
+    pub name: String,
+    pub bytes: Vec<u8>,
+}
+
+fn read_files(files: Option<FileList>, tx: &Sender<Result<WebFile, String>>) {
+    let Some(files) = files else { return };
+    for file in (0..files.length()).filter_map(|i| files.item(i)) {
+        let tx = tx.clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            let read = JsFuture::from(file.array_buffer())
+                .await
+                .map(|buffer| WebFile { name: file.name(), bytes: Uint8Array::new(&buffer).to_vec() })
+                .map_err(|e| format!("Could not read {}: {}", file.name(), js_error(e)));
+            let _ = tx.send(read);
+        });
+    }
+}
+
+/// Hooks a file input, or makes any other element a drop target.
+fn listen(element_id: &str, tx: Sender<Result<WebFile, String>>) -> Result<(), anyhow::Error> {
+    let element = web_sys::window()
+        .and_then(|window| window.document())
+        .and_then(|document| document.get_element_by_id(element_id))
+        .ok_or_else(|| anyhow!("No element with id {}", element_id))?;
+
+    // Listeners stay installed for the lifetime of the page, so the closures are leaked.
+    if let Some(input) = element.dyn_ref::<HtmlInputElement>() {
+        let target = input.clone();
+        let on_change = Closure::<dyn FnMut()>::new(move || read_files(target.files(), &tx));
+        input.add_event_listener_with_callback("change", on_change.as_ref().unchecked_ref()).map_err(js_error)?;
+        on_change.forget();
+    } else {
+        // Without this the browser opens dropped files instead of firing `drop`.
+        let on_dragover = Closure::<dyn FnMut(Event)>::new(|event: Event| event.prevent_default());
+        let on_drop = Closure::<dyn FnMut(DragEvent)>::new(move |event: DragEvent| {
+            event.prevent_default();
+            read_files(event.data_transfer().and_then(|data| data.files()), &tx);
+        });
+        element.add_event_listener_with_callback("dragover", on_dragover.as_ref().unchecked_ref()).map_err(js_error)?;
+        element.add_event_listener_with_callback("drop", on_drop.as_ref().unchecked_ref()).map_err(js_error)?;
+        on_dragover.forget();
+        on_drop.forget();
+    }
+    Ok(())
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct FileInputNodeConfig {
+    /// Id of a file `<input>`, or of any element to use as a drop target.
+    pub element_id: String,
+}
+
+impl Validate for FileInputNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        ensure(!self.element_id.is_empty(), "element_id", "must be set")
+    }
+}
+
+config_builder!(FileInputNodeConfig for FileInputNode { element_id: String });
+
+/// Emits the files a user selects in a file input or drops on an element.
+///
+/// Every file is sent on `file`; those that decode as images are also sent on
+/// `output`, and the rest are reported on `errors`.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct FileInputNode {
+    #[output]
+    pub output: Output<DynamicImage>,
+
+    #[output]
+    pub file: Output<WebFile>,
+
+    #[output]
+    pub errors: Output<String>,
+
+    pub config: FileInputNodeConfig,
+
+    #[serde(skip)]
+    files: Option<Receiver<Result<WebFile, String>>>,
+}
+
+impl FileInputNode {
+    pub fn new(config: FileInputNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            file: Output::new(change_observer),
+            errors: Output::new(change_observer),
+            config,
+            files: None,
+        }
+    }
+}
+
+impl Node for FileInputNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        if self.files.is_none() {
+            let (tx, files) = mpsc::channel();
+            listen(&self.config.element_id, tx).map_err(UpdateError::Other)?;
+            self.files = Some(files);
+        }
+        let received: Vec<Result<WebFile, String>> = self.files.as_ref().expect("listening above").try_iter().collect();
+        for file in received {
+            let file = match file {
+                Ok(file) => file,
+                Err(e) => {
+                    self.errors.send(e).map_err(|e| UpdateError::Other(e.into()))?;
+                    continue;
+                }
+            };
+            match decode_image(file.bytes.clone()) {
+                Ok(img) => self.output.send(img).map_err(|e| UpdateError::Other(e.into()))?,
+                Err(e) => self.errors.send(format!("{} is not an image: {}", file.name, e)).map_err(|e| UpdateError::Other(e.into()))?,
+            }
+            self.file.send(file).map_err(|e| UpdateError::Other(e.into()))?;
+        }
+        Ok(())
+    }
+}
+
+/// Copies a frame into canvas pixel data.
+pub fn to_image_data(img: &DynamicImage) -> Result<ImageData, JsValue> {
+    let rgba = img.to_rgba8();
+    ImageData::new_with_u8_clamped_array_and_sh(Clamped(rgba.as_raw()), rgba.width(), rgba.height())
+}
+
+pub fn from_image_data(data: &ImageData) -> DynamicImage {
+    let rgba = RgbaImage::from_raw(data.width(), data.height(), data.data().0).expect("canvas data is RGBA");
+    DynamicImage::ImageRgba8(rgba)
+}
+
+/// Starts uploading a frame to an `ImageBitmap`, which WebGL and canvases can draw without copying.
+pub fn to_image_bitmap(img: &DynamicImage) -> Result<Promise, JsValue> {
+    let window = web_sys::window().ok_or_else(|| JsValue::from_str("No window"))?;
+    window.create_image_bitmap_with_image_data(&to_image_data(img)?)
+}
+
+pub fn from_image_bitmap(bitmap: &ImageBitmap) -> Result<DynamicImage, JsValue> {
+    let (width, height) = (bitmap.width(), bitmap.height());
+    let canvas = OffscreenCanvas::new(width, height)?;
+    let context: OffscreenCanvasRenderingContext2d = canvas
+        .get_context("2d")?
+        .ok_or_else(|| JsValue::from_str("No 2d context"))?
+        .dyn_into()?;
+    context.draw_image_with_image_bitmap(bitmap, 0.0, 0.0)?;
+    Ok(from_image_data(&context.get_image_data(0.0, 0.0, width as f64, height as f64)?))
+}
+
+fn canvas_context(element_id: &str) -> Result<(HtmlCanvasElement, CanvasRenderingContext2d), anyhow::Error> {
+    let canvas: HtmlCanvasElement = web_sys::window()
+        .and_then(|window| window.document())
+        .and_then(|document| document.get_element_by_id(element_id))
+        .ok_or_else(|| anyhow!("No element with id {}", element_id))?
+        .dyn_into()
+        .map_err(|_| anyhow!("Element {} is not a canvas", element_id))?;
+    let context = canvas
+        .get_context("2d")
+        .map_err(js_error)?
+        .ok_or_else(|| anyhow!("Canvas {} has no 2d context", element_id))?
+        .unchecked_into();
+    Ok((canvas, context))
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct CanvasNodeConfig {
+    /// Id of a `<canvas>` element.
+    pub element_id: String,
+}
+
+impl Validate for CanvasNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        ensure(!self.element_id.is_empty(), "element_id", "must be set")
+    }
+}
+
+config_builder!(CanvasNodeConfig { element_id: String });
+
+/// Draws incoming frames into a canvas, resizing it to fit, and passes them through.
+///
+/// Canvases with a WebGL context cannot be drawn into; JavaScript can copy from
+/// the canvas into its own textures instead.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct CanvasSinkNode {
+    #[output]
+    pub output: Output<DynamicImage>,
+
+    #[input]
+    pub input: Input<DynamicImage>,
+
+    pub config: CanvasNodeConfig,
+}
+
+impl CanvasSinkNode {
+    pub fn new(config: CanvasNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            input: Input::new(),
+            config,
+        }
+    }
+}
+
+impl Node for CanvasSinkNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        if let Ok(img) = self.input.next() {
+            let (canvas, context) = canvas_context(&self.config.element_id).map_err(UpdateError::Other)?;
+            if (canvas.width(), canvas.height()) != (img.width(), img.height()) {
+                canvas.set_width(img.width());
+                canvas.set_height(img.height());
+            }
+            let data = to_image_data(&img).map_err(|e| UpdateError::Other(js_error(e)))?;
+            context.put_image_data(&data, 0.0, 0.0).map_err(|e| UpdateError::Other(js_error(e)))?;
+            self.output.send(img).map_err(|e| UpdateError::Other(e.into()))?;
+        }
+        Ok(())
+    }
+}
+
+/// Grabs the contents of a canvas each time `trigger` receives `true`.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct CanvasSourceNode {
+    #[output]
+    pub output: Output<DynamicImage>,
+
+    #[input]
+    pub trigger: Input<bool>,
+
+    pub config: CanvasNodeConfig,
+}
+
+impl CanvasSourceNode {
+    pub fn new(config: CanvasNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            trigger: Input::new(),
+            config,
+        }
+    }
+}
+
+impl Node for CanvasSourceNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        if let Ok(true) = self.trigger.next() {
+            let (canvas, context) = canvas_context(&self.config.element_id).map_err(UpdateError::Other)?;
+            let data = context
+                .get_image_data(0.0, 0.0, canvas.width() as f64, canvas.height() as f64)
+                .map_err(|e| UpdateError::Other(js_error(e)))?;
+            self.output.send(from_image_data(&data)).map_err(|e| UpdateError::Other(e.into()))?;
+        }
+        Ok(())
+    }
+}
+
+thread_local! {
+    /// JS functions cannot be sent between threads, so nodes refer to them by name.
+    static CALLBACKS: RefCell<HashMap<String, Function>> = RefCell::new(HashMap::new());
+}
+
+/// Registers `callback` under `name` for [`JsCallbackNode`]s, replacing any earlier one.
+#[wasm_bindgen]
+pub fn register_frame_callback(name: &str, callback: Function) {
+    CALLBACKS.with(|callbacks| callbacks.borrow_mut().insert(name.to_string(), callback));
+}
+
+#[wasm_bindgen]
+pub fn unregister_frame_callback(name: &str) {
+    CALLBACKS.with(|callbacks| callbacks.borrow_mut().remove(name));
+}
+
+/// How frames are handed to JavaScript.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize)]
+pub enum JsFrameFormat {
+    /// An `ImageData`, ready for `putImageData` or `createImageBitmap`.
+    #[default]
+    ImageData,
+    /// A `Uint8Array` holding the encoded file.
+    Encoded(EncodeFormat),
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct JsCallbackNodeConfig {
+    /// Name passed to `register_frame_callback`.
+    pub callback: String,
+    pub format: JsFrameFormat,
+}
+
+impl Validate for JsCallbackNodeConfig {
+    fn validate(&self) -> Result<(), ConfigError> {
+        ensure(!self.callback.is_empty(), "callback", "must be set")
+    }
+}
+
+config_builder!(JsCallbackNodeConfig for JsCallbackNode { callback: String, format: JsFrameFormat });
+
+/// Calls a JavaScript function with every frame, and passes the frames through.
+///
+/// Frames arriving before the callback is registered are passed through without
+/// calling anything. Exceptions thrown by the callback fail the update.
+#[derive(RuntimeConnectable, Deserialize, Serialize)]
+pub struct JsCallbackNode {
+    #[output]
+    pub output: Output<DynamicImage>,
+
+    #[input]
+    pub input: Input<DynamicImage>,
+
+    pub config: JsCallbackNodeConfig,
+}
+
+impl JsCallbackNode {
+    pub fn new(config: JsCallbackNodeConfig, change_observer: Option<&ChangeObserver>) -> Self {
+        Self {
+            output: Output::new(change_observer),
+            input: Input::new(),
+            config,
+        }
+    }
+}
+
+impl Node for JsCallbackNode {
+    fn on_update(&mut self) -> Result<(), UpdateError> {
+
+        if let Ok(img) = self.input.next() {
+            let callback = CALLBACKS.with(|callbacks| callbacks.borrow().get(&self.config.callback).cloned());
+            if let Some(callback) = callback {
+                let frame: JsValue = match self.config.format {
+                    JsFrameFormat::ImageData => to_image_data(&img).map_err(|e| UpdateError::Other(js_error(e)))?.into(),
+                    JsFrameFormat::Encoded(format) => {
+                        let bytes = encode_image(&img, format)?;
+                        Uint8Array::from(bytes.as_slice()).into()
+                    }
+                };
+                callback.call1(&JsValue::NULL, &frame).map_err(|e| UpdateError::Other(js_error(e)))?;
+            }
+            self.output.send(img).map_err(|e| UpdateError::Other(e.into()))?;
+        }
+        Ok(())
+    }
+}
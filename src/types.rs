@@ -0,0 +1,228 @@
+use std::collections::HashMap;
+use std::time::SystemTime;
+
+use image::DynamicImage;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Axis-aligned rectangle in pixel coordinates.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct Rect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Rect {
+    pub fn new(x: u32, y: u32, width: u32, height: u32) -> Self {
+        Self { x, y, width, height }
+    }
+
+    pub fn area(&self) -> u64 {
+        self.width as u64 * self.height as u64
+    }
+
+    /// Clips the rectangle to an image of the given size, returning `None` if nothing remains.
+    pub fn clamp_to(&self, width: u32, height: u32) -> Option<Rect> {
+        if self.x >= width || self.y >= height {
+            return None;
+        }
+        let w = self.width.min(width - self.x);
+        let h = self.height.min(height - self.y);
+        (w > 0 && h > 0).then(|| Rect::new(self.x, self.y, w, h))
+    }
+
+    pub fn intersection(&self, other: &Rect) -> Option<Rect> {
+        let x0 = self.x.max(other.x);
+        let y0 = self.y.max(other.y);
+        let x1 = (self.x + self.width).min(other.x + other.width);
+        let y1 = (self.y + self.height).min(other.y + other.height);
+        (x1 > x0 && y1 > y0).then(|| Rect::new(x0, y0, x1 - x0, y1 - y0))
+    }
+
+    /// Intersection over union, in `0.0..=1.0`.
+    pub fn iou(&self, other: &Rect) -> f32 {
+        let inter = self.intersection(other).map_or(0, |r| r.area());
+        let union = self.area() + other.area() - inter;
+        if union == 0 { 0.0 } else { inter as f32 / union as f32 }
+    }
+
+    pub fn contains(&self, x: u32, y: u32) -> bool {
+        x >= self.x && y >= self.y && x < self.x + self.width && y < self.y + self.height
+    }
+
+    /// Moves the rectangle, saturating at the image origin.
+    pub fn translated(&self, dx: i64, dy: i64) -> Rect {
+        let shift = |v: u32, d: i64| (v as i64 + d).clamp(0, u32::MAX as i64) as u32;
+        Rect::new(shift(self.x, dx), shift(self.y, dy), self.width, self.height)
+    }
+}
+
+/// Labelled, scored region produced by detection nodes.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct Detection {
+    pub rect: Rect,
+    pub label: String,
+    pub score: f32,
+}
+
+/// Detection with a persistent identity across frames.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct Track {
+    pub id: u64,
+    pub rect: Rect,
+    pub label: String,
+    pub score: f32,
+    /// Estimated motion of the box center, in pixels per frame.
+    pub velocity: (f32, f32),
+    /// Frames since the track was created.
+    pub age: u32,
+    /// Frames in which the track was matched to a detection.
+    pub hits: u32,
+}
+
+/// Position of a tile within the frame it was cut from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct TileInfo {
+    /// Sequence number of the source frame.
+    pub frame: u64,
+    pub index: usize,
+    pub count: usize,
+    pub frame_width: u32,
+    pub frame_height: u32,
+    /// Area covered by the tile, including overlap with its neighbours.
+    pub region: Rect,
+    /// Part of `region` this tile is responsible for when merging; cores partition the frame.
+    pub core: Rect,
+}
+
+/// Placement of an overlay relative to the image borders.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum Anchor {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    #[default]
+    BottomRight,
+    Center,
+}
+
+impl Anchor {
+    /// Top-left corner of an item of size `item` placed in `canvas`, `margin` pixels from the borders.
+    pub fn position(&self, canvas: (u32, u32), item: (u32, u32), margin: u32) -> (i64, i64) {
+        let (cw, ch) = (canvas.0 as i64, canvas.1 as i64);
+        let (iw, ih) = (item.0 as i64, item.1 as i64);
+        let m = margin as i64;
+        match self {
+            Anchor::TopLeft => (m, m),
+            Anchor::TopRight => (cw - iw - m, m),
+            Anchor::BottomLeft => (m, ch - ih - m),
+            Anchor::BottomRight => (cw - iw - m, ch - ih - m),
+            Anchor::Center => ((cw - iw) / 2, (ch - ih) / 2),
+        }
+    }
+}
+
+/// Geometry of a segmented object, in pixels.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct ObjectMeasurement {
+    pub id: u32,
+    pub bounds: Rect,
+    pub area: f64,
+    pub perimeter: f64,
+    pub centroid: (f64, f64),
+}
+
+/// A frame with its sequence number and capture time, for latency measurements.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TimedImage {
+    pub seq: u64,
+    pub captured_at: SystemTime,
+    pub image: DynamicImage,
+}
+
+/// A frame with metadata that travels along with it, such as the camera id,
+/// exposure or detection results.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImagePacket {
+    pub image: DynamicImage,
+    pub metadata: HashMap<String, Value>,
+    /// Capture time of the frame.
+    pub timestamp: SystemTime,
+    pub seq: u64,
+}
+
+impl ImagePacket {
+    /// A packet without metadata, stamped with the current time.
+    pub fn new(image: DynamicImage, seq: u64) -> Self {
+        Self { image, metadata: HashMap::new(), timestamp: SystemTime::now(), seq }
+    }
+
+    /// A packet with the metadata, timestamp and sequence number of this one, but a different image.
+    pub fn with_image(&self, image: DynamicImage) -> Self {
+        Self { image, metadata: self.metadata.clone(), timestamp: self.timestamp, seq: self.seq }
+    }
+}
+
+/// Capture settings a controller asks a camera to apply; `None` leaves a setting unchanged.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub struct CameraControl {
+    /// Exposure time in microseconds.
+    pub exposure_us: Option<f32>,
+    /// Red and blue gains relative to green.
+    pub white_balance: Option<(f32, f32)>,
+}
+
+/// Pinhole model of a camera stream, in pixels.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+pub struct CameraIntrinsics {
+    pub width: u32,
+    pub height: u32,
+    pub fx: f32,
+    pub fy: f32,
+    /// Principal point.
+    pub ppx: f32,
+    pub ppy: f32,
+}
+
+/// Lifecycle state of a source or sink node.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum NodeState {
+    /// No frame has been handled yet.
+    #[default]
+    Starting,
+    Running,
+    /// Halted by a [`SourceControl`] command.
+    Paused,
+    /// The last operation failed, but the node keeps trying.
+    Degraded,
+    /// The node gave up, e.g. after exhausting its retry policy.
+    Failed,
+}
+
+/// Health of a source or sink node, sent on its `health` output.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub struct NodeStatus {
+    pub state: NodeState,
+    /// Frames read or written since the node was created.
+    pub frames: u64,
+    pub errors: u64,
+    /// Frame rate over the last reporting interval.
+    pub fps: f64,
+    pub last_error: Option<String>,
+}
+
+/// Command on the `control` input of source nodes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum SourceControl {
+    /// Produce frames continuously.
+    #[default]
+    Start,
+    /// Stop producing frames and release the device, file or connection; `Start` reopens it.
+    Stop,
+    /// Stop producing frames but keep the source open.
+    Pause,
+    /// Produce one frame, then pause.
+    SingleShot,
+}
@@ -0,0 +1,4 @@
+pub mod test_logos;
+pub mod test_particles;
+pub mod test_quality;
+pub mod test_thermal;
@@ -0,0 +1,57 @@
+#[cfg(test)]
+mod analysis {
+    use flowrs_img::analysis::{detect_logos, LogoDetectNodeConfig};
+    use flowrs_img::types::Rect;
+    use image::imageops::{self, FilterType};
+    use image::{DynamicImage, GrayImage, Luma};
+
+    fn logo() -> GrayImage {
+        GrayImage::from_fn(24, 24, |x, y| {
+            Luma([if x < 6 { 230 } else if y > 16 { 30 } else if (x as i32 - 12).pow(2) + (y as i32 - 8).pow(2) < 20 { 250 } else { 90 }])
+        })
+    }
+
+    fn noise() -> GrayImage {
+        GrayImage::from_fn(160, 120, |x, y| Luma([80 + ((x * 160 + y).wrapping_mul(2654435761) >> 26) as u8]))
+    }
+
+    /// Noise with the logo pasted at (50, 30), twice its template size.
+    fn scene() -> DynamicImage {
+        let mut frame = noise();
+        imageops::replace(&mut frame, &imageops::resize(&logo(), 48, 48, FilterType::Triangle), 50, 30);
+        DynamicImage::ImageLuma8(frame)
+    }
+
+    fn config(working_width: u32) -> LogoDetectNodeConfig {
+        LogoDetectNodeConfig { working_width, scales: vec![1.0, 2.0], ..Default::default() }
+    }
+
+    #[test]
+    fn finds_logo_at_offset_and_scale() {
+        let gallery = vec![("acme".to_string(), logo())];
+        let found = detect_logos(&scene(), &gallery, &config(640));
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].label, "acme");
+        assert_eq!(found[0].rect, Rect::new(50, 30, 48, 48));
+        assert!(found[0].score > 0.99, "score {}", found[0].score);
+    }
+
+    #[test]
+    fn downscaled_search_maps_back_to_frame_pixels() {
+        let gallery = vec![("acme".to_string(), logo())];
+        let found = detect_logos(&scene(), &gallery, &config(80));
+        assert_eq!(found.len(), 1);
+        let rect = found[0].rect;
+        assert!(rect.x.abs_diff(50) <= 2 && rect.y.abs_diff(30) <= 2, "{rect:?}");
+        assert_eq!((rect.width, rect.height), (48, 48));
+    }
+
+    #[test]
+    fn missing_logo_is_not_reported() {
+        let gallery = vec![("acme".to_string(), logo())];
+        // Plain cross-correlation stays fairly high on any bright texture, hence the strict threshold.
+        let config = LogoDetectNodeConfig { threshold: 0.95, ..config(640) };
+        assert!(detect_logos(&DynamicImage::ImageLuma8(noise()), &gallery, &config).is_empty());
+        assert_eq!(detect_logos(&scene(), &gallery, &config).len(), 1);
+    }
+}
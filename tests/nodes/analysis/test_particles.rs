@@ -0,0 +1,29 @@
+#[cfg(test)]
+mod analysis {
+    use flowrs_img::analysis::{measure_labels, watershed_labels};
+    use image::{GrayImage, Luma};
+
+    fn discs(centers: &[(f32, f32)], radius: f32) -> GrayImage {
+        GrayImage::from_fn(64, 32, |x, y| {
+            let inside = centers.iter().any(|(cx, cy)| (x as f32 - cx).hypot(y as f32 - cy) <= radius);
+            Luma([if inside { 255 } else { 0 }])
+        })
+    }
+
+    #[test]
+    fn separate_objects_are_counted() {
+        let mask = discs(&[(10.0, 16.0), (40.0, 16.0)], 6.0);
+        let (labels, count) = watershed_labels(&mask, false, 0.0);
+        assert_eq!(measure_labels(&labels, count, 64, 32).len(), 2);
+    }
+
+    #[test]
+    fn touching_objects_are_split() {
+        let mask = discs(&[(20.0, 16.0), (31.0, 16.0)], 7.0);
+        let (labels, count) = watershed_labels(&mask, false, 0.0);
+        assert_eq!(measure_labels(&labels, count, 64, 32).len(), 1);
+
+        let (labels, count) = watershed_labels(&mask, true, 3.0);
+        assert_eq!(measure_labels(&labels, count, 64, 32).len(), 2);
+    }
+}
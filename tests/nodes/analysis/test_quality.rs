@@ -0,0 +1,60 @@
+#[cfg(test)]
+mod analysis {
+    use flowrs::connection::{connect, Input};
+    use flowrs::node::Node;
+    use flowrs_img::analysis::{measure_quality, quality_issues, FrameQuality, QualityGateNode, QualityGateNodeConfig, QualityIssue};
+    use image::{DynamicImage, GrayImage, Luma};
+
+    /// A fine checkerboard between `dark` and `bright`.
+    fn checker(dark: u8, bright: u8) -> DynamicImage {
+        DynamicImage::ImageLuma8(GrayImage::from_fn(64, 64, |x, y| Luma([if (x / 2 + y / 2) % 2 == 0 { dark } else { bright }])))
+    }
+
+    fn issues(img: &DynamicImage) -> Vec<QualityIssue> {
+        quality_issues(&measure_quality(img), &QualityGateNodeConfig::default())
+    }
+
+    #[test]
+    fn passes_sharp_well_exposed_frames() {
+        assert_eq!(issues(&checker(40, 210)), vec![]);
+    }
+
+    #[test]
+    fn rejects_blurred_frames() {
+        // A smooth ramp has plenty of contrast but no detail at all.
+        let ramp = DynamicImage::ImageLuma8(GrayImage::from_fn(64, 64, |x, _| Luma([(x * 4) as u8])));
+        assert_eq!(issues(&ramp), vec![QualityIssue::Blurry]);
+        assert!(measure_quality(&checker(40, 210).blur(4.0)).sharpness < measure_quality(&checker(40, 210)).sharpness);
+    }
+
+    #[test]
+    fn rejects_badly_exposed_frames_with_every_reason() {
+        let flat = |v| DynamicImage::ImageLuma8(GrayImage::from_pixel(64, 64, Luma([v])));
+        assert_eq!(issues(&flat(3)), vec![QualityIssue::Blurry, QualityIssue::TooDark, QualityIssue::LowContrast]);
+        assert_eq!(issues(&flat(252)), vec![QualityIssue::Blurry, QualityIssue::TooBright, QualityIssue::LowContrast]);
+    }
+
+    #[test]
+    fn disabled_thresholds_pass_everything() {
+        let config = QualityGateNodeConfig { min_sharpness: None, min_brightness: None, max_brightness: None, min_contrast: None };
+        assert_eq!(quality_issues(&measure_quality(&checker(0, 0)), &config), vec![]);
+    }
+
+    #[test]
+    fn empty_frames_are_rejected() {
+        for (width, height) in [(0, 0), (0, 10), (10, 0)] {
+            let quality = measure_quality(&DynamicImage::new_luma8(width, height));
+            assert_eq!(quality, FrameQuality { sharpness: 0.0, brightness: 0.0, contrast: 0.0 });
+        }
+
+        let config = QualityGateNodeConfig { min_sharpness: None, min_brightness: None, max_brightness: None, min_contrast: None };
+        let mut node = QualityGateNode::new(config, None);
+        let (passed, rejected) = (Input::new(), Input::new());
+        connect(node.output.clone(), passed.clone());
+        connect(node.rejected.clone(), rejected.clone());
+        node.input.send(DynamicImage::new_luma8(0, 10)).unwrap();
+        node.on_update().unwrap();
+        assert!(passed.next().is_err());
+        assert_eq!(rejected.next().unwrap().reasons, vec![QualityIssue::Empty]);
+    }
+}
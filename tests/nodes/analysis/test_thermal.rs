@@ -0,0 +1,33 @@
+#[cfg(test)]
+mod analysis {
+    use flowrs_img::analysis::{detect_thermal_anomalies, ThermalAnomalyNodeConfig, ThermalThreshold};
+    use ndarray::Array2;
+
+    fn scene() -> Array2<f32> {
+        let mut t = Array2::from_elem((20, 30), 25.0f32);
+        t.slice_mut(ndarray::s![2..5, 3..6]).fill(60.0);
+        t[[3, 4]] = 95.0;
+        t.slice_mut(ndarray::s![12..16, 20..24]).fill(40.0);
+        t[[18, 1]] = 120.0;
+        t
+    }
+
+    #[test]
+    fn absolute_limit_reports_hotspot() {
+        let config = ThermalAnomalyNodeConfig { absolute_limit: Some(50.0), relative_limit: None, min_area: 2 };
+        let alarms = detect_thermal_anomalies(&scene(), &config);
+        assert_eq!(alarms.len(), 1);
+        assert_eq!(alarms[0].hotspot, (4, 3));
+        assert_eq!(alarms[0].max_temperature, 95.0);
+        assert_eq!(alarms[0].threshold, ThermalThreshold::Absolute);
+    }
+
+    #[test]
+    fn relative_limit_uses_scene_median() {
+        let config = ThermalAnomalyNodeConfig { absolute_limit: Some(80.0), relative_limit: Some(10.0), min_area: 2 };
+        let alarms = detect_thermal_anomalies(&scene(), &config);
+        assert_eq!(alarms.len(), 2);
+        assert!(alarms.iter().all(|a| a.ambient_temperature == 25.0));
+        assert!(alarms.iter().any(|a| a.threshold == ThermalThreshold::Relative && a.area == 16));
+    }
+}
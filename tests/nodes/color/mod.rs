@@ -0,0 +1,6 @@
+pub mod test_alpha;
+pub mod test_calibration;
+pub mod test_colormap;
+pub mod test_kernels;
+pub mod test_lut;
+pub mod test_quantize;
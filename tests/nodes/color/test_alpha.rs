@@ -0,0 +1,51 @@
+#[cfg(test)]
+mod color {
+    use flowrs::connection::{connect, Input};
+    use flowrs::node::Node;
+    use flowrs_img::color::{convert_alpha, merge_alpha, split_alpha, AlphaConversion, AlphaMergeNode};
+    use image::{DynamicImage, GrayImage, Luma, Rgb, RgbImage, Rgba, RgbaImage};
+
+    fn sample() -> DynamicImage {
+        DynamicImage::ImageRgba8(RgbaImage::from_fn(4, 4, |x, y| Rgba([200, 100, 50, (x * 60 + y) as u8])))
+    }
+
+    #[test]
+    fn split_and_merge_round_trip() {
+        let (color, alpha) = split_alpha(&sample());
+        assert!(matches!(color, DynamicImage::ImageRgb8(_)));
+        assert_eq!(alpha.to_luma8().get_pixel(2, 1)[0], 121);
+        assert_eq!(merge_alpha(&color, &alpha), sample());
+    }
+
+    #[test]
+    fn premultiply_round_trip() {
+        let premultiplied = convert_alpha(&sample(), AlphaConversion::Premultiply).to_rgba8();
+        let px = premultiplied.get_pixel(2, 0);
+        assert_eq!(px.0, [94, 47, 24, 120]);
+        let transparent = premultiplied.get_pixel(0, 0);
+        assert_eq!(transparent.0, [0, 0, 0, 0]);
+
+        let straight = convert_alpha(&DynamicImage::ImageRgba8(premultiplied), AlphaConversion::Unpremultiply).to_rgba8();
+        let px = straight.get_pixel(3, 3);
+        assert!((px[0] as i32 - 200).abs() <= 1 && (px[2] as i32 - 50).abs() <= 1);
+    }
+
+    #[test]
+    fn stalled_alpha_keeps_only_latest_colors() {
+        let mut node = AlphaMergeNode::new(None);
+        let mut out = Input::new();
+        connect(node.output.clone(), out.clone());
+
+        for value in 0..20 {
+            node.color.send(DynamicImage::ImageRgb8(RgbImage::from_pixel(2, 2, Rgb([value, 0, 0])))).unwrap();
+            node.on_update().unwrap();
+        }
+        assert!(out.next().is_err());
+        for _ in 0..10 {
+            node.alpha.send(DynamicImage::ImageLuma8(GrayImage::from_pixel(2, 2, Luma([255])))).unwrap();
+        }
+        node.on_update().unwrap();
+        let merged: Vec<u8> = std::iter::from_fn(|| out.next().ok()).map(|img| img.to_rgba8().get_pixel(0, 0)[0]).collect();
+        assert_eq!(merged, (12..20).collect::<Vec<u8>>());
+    }
+}
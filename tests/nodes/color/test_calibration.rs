@@ -0,0 +1,27 @@
+#[cfg(test)]
+mod color {
+    use flowrs_img::color::{compute_correction_matrix, COLOR_CHECKER_SRGB};
+
+    #[test]
+    fn reference_chart_yields_identity_matrix() {
+        let measured = COLOR_CHECKER_SRGB.map(|p| p.map(|c| c as f32));
+        let m = compute_correction_matrix(&measured).unwrap();
+        let identity = [1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0];
+        for (a, b) in m.iter().zip(identity) {
+            assert!((a - b).abs() < 1e-3, "{:?}", m);
+        }
+    }
+
+    #[test]
+    fn channel_gain_is_corrected() {
+        // Halve the linear red channel; the matrix should restore it.
+        let measured = COLOR_CHECKER_SRGB.map(|p| {
+            let mut p = p.map(|c| c as f32);
+            let lin = flowrs_img::color::srgb_to_linear(p[0] / 255.0) * 0.5;
+            p[0] = flowrs_img::color::linear_to_srgb(lin) * 255.0;
+            p
+        });
+        let m = compute_correction_matrix(&measured).unwrap();
+        assert!((m[0] - 2.0).abs() < 0.05, "{:?}", m);
+    }
+}
@@ -0,0 +1,25 @@
+#[cfg(test)]
+mod color {
+    use flowrs_img::color::{apply_colormap, resolve_range, Colormap, ColormapRange};
+
+    #[test]
+    fn palette_endpoints() {
+        assert_eq!(Colormap::Viridis.color(0.0), [68, 1, 84]);
+        assert_eq!(Colormap::Viridis.color(1.0), [253, 231, 37]);
+        assert_eq!(Colormap::Jet.color(0.0), [0, 0, 128]);
+        assert_eq!(Colormap::Jet.color(0.5), [128, 255, 128]);
+        assert_eq!(Colormap::Inferno.color(f32::NAN), [0, 0, 4]);
+    }
+
+    #[test]
+    fn ranges() {
+        let mut values: Vec<f32> = (0..100).map(|v| v as f32).collect();
+        values.push(10_000.0);
+        assert_eq!(resolve_range(&values, ColormapRange::Auto), (0.0, 10_000.0));
+        assert_eq!(resolve_range(&values, ColormapRange::Percentile { low: 0.0, high: 99.0 }), (0.0, 99.0));
+
+        let img = apply_colormap(&[1000.0, 2000.0, 3000.0], 3, 1, Colormap::Turbo, ColormapRange::Fixed { min: 1000.0, max: 2000.0 });
+        assert_eq!(img.get_pixel(0, 0).0, Colormap::Turbo.color(0.0));
+        assert_eq!(img.get_pixel(2, 0).0, Colormap::Turbo.color(1.0));
+    }
+}
@@ -0,0 +1,57 @@
+#[cfg(test)]
+mod color {
+    use flowrs_img::color::{bgr_to_rgb, resize_rgb8, rgb_to_luma, scalar, swap_rb_in_place};
+    use image::{imageops, DynamicImage, Rgb, RgbImage};
+
+    // Odd sizes leave remainders behind every vector loop.
+    fn noise(width: u32, height: u32) -> RgbImage {
+        RgbImage::from_fn(width, height, |x, y| {
+            let v = (x * 7919 + y * 104729) ^ (x * y);
+            Rgb([v as u8, (v >> 8) as u8, (v >> 16) as u8])
+        })
+    }
+
+    #[test]
+    fn luma_matches_scalar_kernel() {
+        for (width, height) in [(1, 1), (7, 3), (37, 19), (640, 9)] {
+            let img = noise(width, height);
+            assert_eq!(rgb_to_luma(&img), scalar::rgb_to_luma(&img));
+        }
+        // Same weights as image-rs, which rounds in integer arithmetic.
+        let img = noise(33, 5);
+        let theirs = DynamicImage::ImageRgb8(img.clone()).into_luma8();
+        assert!(rgb_to_luma(&img).as_raw().iter().zip(theirs.as_raw()).all(|(a, b)| a.abs_diff(*b) <= 1));
+    }
+
+    #[test]
+    fn channel_swap_matches_scalar_kernel() {
+        for len in [0, 3, 15, 18, 48, 51, 3 * 1001] {
+            let original: Vec<u8> = (0..len).map(|i| (i * 31) as u8).collect();
+            let (mut fast, mut reference) = (original.clone(), original.clone());
+            swap_rb_in_place(&mut fast);
+            scalar::swap_rb_in_place(&mut reference);
+            assert_eq!(fast, reference);
+        }
+        let rgb = bgr_to_rgb(2, 1, vec![1, 2, 3, 4, 5, 6]).unwrap();
+        assert_eq!(rgb.as_raw(), &[3, 2, 1, 6, 5, 4]);
+    }
+
+    #[test]
+    fn resize_matches_scalar_kernel() {
+        let img = noise(101, 67);
+        for (width, height) in [(1, 1), (13, 9), (50, 33), (101, 67), (240, 150)] {
+            assert_eq!(resize_rgb8(&img, width, height), scalar::resize_rgb8(&img, width, height));
+        }
+    }
+
+    #[test]
+    fn resize_is_close_to_image_rs() {
+        let img = RgbImage::from_fn(96, 64, |x, y| Rgb([(x * 2) as u8, (y * 3) as u8, ((x + y) * 2) as u8]));
+        for (width, height) in [(48, 32), (31, 17), (150, 100)] {
+            let ours = resize_rgb8(&img, width, height);
+            let theirs = imageops::resize(&img, width, height, imageops::FilterType::Triangle);
+            let worst = ours.as_raw().iter().zip(theirs.as_raw()).map(|(a, b)| a.abs_diff(*b)).max().unwrap();
+            assert!(worst <= 1, "{}x{} differs by {}", width, height, worst);
+        }
+    }
+}
@@ -0,0 +1,64 @@
+#[cfg(test)]
+mod color {
+    use flowrs::connection::{connect, Input};
+    use flowrs::node::Node;
+    use flowrs_img::color::{parse_cube, Lut, LutNode, LutNodeConfig};
+    use image::{DynamicImage, GrayImage, Luma};
+
+    /// 2×2×2 identity lattice, red varying fastest.
+    const IDENTITY: &str = "TITLE \"identity\"\n# comment\nLUT_3D_SIZE 2\n\
+        0 0 0\n1 0 0\n0 1 0\n1 1 0\n0 0 1\n1 0 1\n0 1 1\n1 1 1\n";
+
+    #[test]
+    fn identity_3d_interpolates() {
+        let lut = parse_cube(IDENTITY).unwrap();
+        assert!(matches!(lut, Lut::ThreeD { size: 2, .. }));
+        let out = lut.apply([0.25, 0.5, 0.75]);
+        for (o, e) in out.iter().zip([0.25, 0.5, 0.75]) {
+            assert!((o - e).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn curves_1d() {
+        let lut = parse_cube("LUT_1D_SIZE 3\n0 0 1\n0.25 0.5 0.5\n1 1 0\n").unwrap();
+        let out = lut.apply([0.5, 0.25, 1.0]);
+        assert_eq!(out, [0.25, 0.25, 0.0]);
+    }
+
+    #[test]
+    fn rejects_truncated_tables() {
+        let err = parse_cube("LUT_3D_SIZE 2\n0 0 0\n").unwrap_err();
+        assert_eq!(err.to_string(), "LUT size does not match its 1 entries");
+        assert!(parse_cube("LUT_1D_SIZE 2\n0 0\n1 1 1\n").is_err());
+    }
+
+    #[test]
+    fn failed_reload_keeps_the_previous_table() {
+        let dir = tempfile::tempdir().unwrap();
+        let invert = dir.path().join("invert.cube");
+        std::fs::write(&invert, "LUT_1D_SIZE 2\n1 1 1\n0 0 0\n").unwrap();
+        let broken = dir.path().join("broken.cube");
+        std::fs::write(&broken, "LUT_3D_SIZE 2\n0 0 0\n").unwrap();
+
+        let mut node = LutNode::new(LutNodeConfig { path: invert.clone() }, None);
+        let mut out = Input::new();
+        let mut errors = Input::new();
+        connect(node.output.clone(), out.clone());
+        connect(node.errors.clone(), errors.clone());
+        let frame = DynamicImage::ImageLuma8(GrayImage::from_pixel(1, 1, Luma([0])));
+
+        node.input.send(frame.clone()).unwrap();
+        node.on_update().unwrap();
+        assert_eq!(out.next().unwrap().to_luma8().get_pixel(0, 0)[0], 255);
+
+        node.reload.send(broken).unwrap();
+        node.reload.send(dir.path().join("missing.cube")).unwrap();
+        node.input.send(frame).unwrap();
+        node.on_update().unwrap();
+        assert_eq!(out.next().unwrap().to_luma8().get_pixel(0, 0)[0], 255);
+        assert!(errors.next().unwrap().contains("broken.cube"));
+        assert!(errors.next().unwrap().contains("missing.cube"));
+        assert_eq!(node.config.path, invert);
+    }
+}
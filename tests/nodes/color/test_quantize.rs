@@ -0,0 +1,25 @@
+#[cfg(test)]
+mod color {
+    use flowrs_img::color::{median_cut, quantize};
+    use image::{Rgb, RgbImage};
+
+    #[test]
+    fn median_cut_separates_clusters() {
+        let img = RgbImage::from_fn(8, 8, |x, _| if x < 4 { Rgb([250, 10, 10]) } else { Rgb([10, 10, 250]) });
+        let mut palette = median_cut(&img, 2);
+        palette.sort();
+        assert_eq!(palette, vec![[10, 10, 250], [250, 10, 10]]);
+        let indexed = quantize(&img, palette, false);
+        assert_eq!(indexed.to_rgb().get_pixel(0, 0).0, [250, 10, 10]);
+    }
+
+    #[test]
+    fn dithering_preserves_mean_gray() {
+        let img = RgbImage::from_pixel(16, 16, Rgb([64, 64, 64]));
+        let palette = vec![[0, 0, 0], [255, 255, 255]];
+        assert!(quantize(&img, palette.clone(), false).indices.iter().all(|&i| i == 0));
+        let dithered = quantize(&img, palette, true);
+        let white = dithered.indices.iter().filter(|&&i| i == 1).count() as f32 / 256.0;
+        assert!((white - 0.25).abs() < 0.03, "white share {}", white);
+    }
+}
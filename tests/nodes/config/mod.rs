@@ -0,0 +1,4 @@
+pub mod test_builder;
+pub mod test_parse;
+pub mod test_validate;
+pub mod test_watcher;
@@ -0,0 +1,33 @@
+#[cfg(test)]
+mod config {
+    use flowrs_img::color::{PaletteMethod, QuantizeNode, QuantizeNodeConfig};
+    use flowrs_img::source::{CaptureNode, CaptureNodeConfig, FrameSourceConfig, TestPattern};
+
+    #[test]
+    fn builder_starts_from_defaults() {
+        let config = QuantizeNodeConfig::builder().colors(8).build().unwrap();
+        assert_eq!(config.colors, 8);
+        assert_eq!(config.method, QuantizeNodeConfig::default().method);
+        assert!(QuantizeNodeConfig::builder().method(PaletteMethod::MedianCut).dither(true).build().is_ok());
+    }
+
+    #[test]
+    fn builder_validates() {
+        let err = CaptureNodeConfig::builder()
+            .source(FrameSourceConfig::Pattern { pattern: TestPattern::Gradient, width: 640, height: 0 })
+            .fps(Some(30.0))
+            .build()
+            .unwrap_err();
+        assert_eq!(err.field, "source.width");
+        assert_eq!(CaptureNodeConfig::builder().preview_width(Some(320)).passthrough(true).build().unwrap_err().field, "preview_width");
+    }
+
+    #[test]
+    fn checked_constructors_reject_invalid_configs() {
+        assert!(CaptureNode::try_new(CaptureNodeConfig::default(), None).is_ok());
+        let config = CaptureNodeConfig { fps: Some(-1.0), ..Default::default() };
+        assert_eq!(CaptureNode::try_new(config, None).err().unwrap().field, "fps");
+        let config = QuantizeNodeConfig { colors: 300, ..Default::default() };
+        assert!(QuantizeNode::try_new(config, None).is_err());
+    }
+}
@@ -0,0 +1,35 @@
+#[cfg(test)]
+mod config {
+    use flowrs_img::color::{ColorConvertNodeConfig, ColorFormat};
+    use flowrs_img::config::{parse_config, ConfigFormat, Validate};
+    use flowrs_img::source::{TestPattern, TestPatternNodeConfig};
+    use flowrs_img::tracking::TrackerNodeConfig;
+
+    const DOCUMENT: &str = r#"{ "gray": { "target": "Luma8" }, "color": { "target": "Rgb8" } }"#;
+
+    #[test]
+    fn sections_by_pointer() {
+        let gray: ColorConvertNodeConfig = parse_config(DOCUMENT, ConfigFormat::Json, Some("/gray")).unwrap();
+        assert_eq!(gray.target, ColorFormat::Luma8);
+        let err = parse_config::<ColorConvertNodeConfig>(DOCUMENT, ConfigFormat::Json, Some("/missing")).unwrap_err();
+        assert_eq!(err.to_string(), "No config at /missing");
+        assert!(parse_config::<ColorConvertNodeConfig>(r#"{ "target": 3 }"#, ConfigFormat::Json, None).is_err());
+    }
+
+    #[test]
+    fn omitted_fields_use_defaults() {
+        let config: TestPatternNodeConfig = parse_config(r#"{ "width": 320 }"#, ConfigFormat::Json, None).unwrap();
+        assert!(matches!(config.pattern, TestPattern::ColorBars));
+        assert_eq!((config.width, config.height), (320, 480));
+        let tracker: TrackerNodeConfig = parse_config("{}", ConfigFormat::Json, None).unwrap();
+        assert!(tracker.validate().is_ok());
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn toml_documents() {
+        let text = "[encoder]\ntarget = \"Rgba16\"\n";
+        let config: ColorConvertNodeConfig = parse_config(text, ConfigFormat::Toml, Some("/encoder")).unwrap();
+        assert_eq!(config.target, ColorFormat::Rgba16);
+    }
+}
@@ -0,0 +1,50 @@
+#[cfg(test)]
+mod config {
+    use flowrs_img::color::{PaletteMethod, QuantizeNodeConfig};
+    use flowrs_img::config::Validate;
+    use flowrs_img::expr::PixelExprNodeConfig;
+    use flowrs_img::source::{CaptureNodeConfig, FrameSourceConfig, TestPattern};
+
+    #[test]
+    fn rejects_out_of_range_fields() {
+        let config = QuantizeNodeConfig { colors: 16, method: PaletteMethod::MedianCut, dither: false };
+        assert!(config.validate().is_ok());
+        let err = QuantizeNodeConfig { colors: 1, ..config }.validated().err().unwrap();
+        assert_eq!(err.field, "colors");
+        assert_eq!(err.to_string(), "Invalid colors: must be between 2 and 256");
+    }
+
+    #[test]
+    fn nested_fields_are_prefixed() {
+        let config = CaptureNodeConfig {
+            source: FrameSourceConfig::Pattern { pattern: TestPattern::Gradient, width: 0, height: 480 },
+            fps: Some(30.0),
+            ..Default::default()
+        };
+        assert_eq!(config.validate().unwrap_err().field, "source.width");
+    }
+
+    #[test]
+    fn preview_requires_decoded_frames() {
+        let config = CaptureNodeConfig { preview_width: Some(320), ..Default::default() };
+        assert!(config.validate().is_ok());
+        let config = CaptureNodeConfig { passthrough: true, ..config };
+        assert_eq!(config.validate().unwrap_err().field, "preview_width");
+    }
+
+    #[test]
+    fn passthrough_requires_encoded_frames() {
+        let config = CaptureNodeConfig { passthrough: true, ..Default::default() };
+        assert_eq!(config.validate().unwrap_err().field, "passthrough");
+        let source = FrameSourceConfig::Directory { path: "frames".into(), looping: false };
+        assert!(CaptureNodeConfig { source, ..config }.validate().is_ok());
+    }
+
+    #[test]
+    fn expressions_are_compiled() {
+        let config = PixelExprNodeConfig { expressions: vec!["a.r * 2".into(), "a.g +".into(), "a.b".into()] };
+        assert_eq!(config.validate().unwrap_err().field, "expressions[1]");
+        let config = PixelExprNodeConfig { expressions: vec!["a.r".into(), "a.g".into()] };
+        assert_eq!(config.validate().unwrap_err().field, "expressions");
+    }
+}
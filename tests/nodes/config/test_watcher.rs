@@ -0,0 +1,58 @@
+#[cfg(test)]
+mod config {
+    use std::path::Path;
+    use std::time::{Duration, SystemTime};
+
+    use flowrs::connection::{connect, Input};
+    use flowrs::node::Node;
+    use flowrs_img::analysis::{QualityGateNode, QualityGateNodeConfig};
+    use flowrs_img::config::{ConfigFileWatcherNode, ConfigFileWatcherNodeConfig};
+    use image::{DynamicImage, GrayImage, Luma};
+
+    /// Writes `text` with a modification time the watcher cannot mistake for the previous one.
+    fn rewrite(path: &Path, text: &str, age: u64) {
+        std::fs::write(path, text).unwrap();
+        let modified = SystemTime::now() - Duration::from_secs(age);
+        std::fs::File::options().write(true).open(path).unwrap().set_modified(modified).unwrap();
+    }
+
+    #[test]
+    fn file_changes_retune_a_running_node() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("gate.json");
+        rewrite(&path, r#"{ "min_sharpness": null, "min_contrast": null }"#, 60);
+
+        let config = ConfigFileWatcherNodeConfig { path: path.clone(), poll_interval_ms: 0, pointer: None };
+        let mut watcher = ConfigFileWatcherNode::<QualityGateNodeConfig>::new(config, None);
+        let mut gate = QualityGateNode::new(QualityGateNodeConfig::default(), None);
+        let mut output = Input::new();
+        connect(watcher.output.clone(), gate.reconfigure.clone());
+        connect(gate.output.clone(), output.clone());
+
+        // A flat gray frame is neither sharp nor contrasty enough for the defaults.
+        let frame = DynamicImage::ImageLuma8(GrayImage::from_pixel(8, 8, Luma([128])));
+        gate.input.send(frame.clone()).unwrap();
+        gate.on_update().unwrap();
+        assert!(output.next().is_err());
+
+        watcher.on_update().unwrap();
+        gate.input.send(frame.clone()).unwrap();
+        gate.on_update().unwrap();
+        assert!(output.next().is_ok());
+
+        // An invalid edit fails the update once and leaves the previous thresholds in effect.
+        rewrite(&path, r#"{ "min_brightness": 2.0 }"#, 30);
+        watcher.on_update().unwrap();
+        gate.input.send(frame.clone()).unwrap();
+        assert!(gate.on_update().is_err());
+        gate.on_update().unwrap();
+        assert!(output.next().is_ok());
+        assert_eq!(gate.config.min_brightness, Some(0.05));
+
+        rewrite(&path, r#"{ "min_sharpness": null, "min_contrast": null, "max_brightness": 0.4 }"#, 0);
+        watcher.on_update().unwrap();
+        gate.input.send(frame).unwrap();
+        gate.on_update().unwrap();
+        assert!(output.next().is_err());
+    }
+}
@@ -0,0 +1,52 @@
+#[cfg(test)]
+mod control {
+    use flowrs_img::control::{AutoExposureNodeConfig, ExposureController};
+    use image::{DynamicImage, Rgb, RgbImage};
+
+    fn config() -> AutoExposureNodeConfig {
+        AutoExposureNodeConfig {
+            target_luma: 0.5,
+            deadband: 0.05,
+            damping: 1.0,
+            min_exposure_us: 100.0,
+            max_exposure_us: 20000.0,
+            initial_exposure_us: 1000.0,
+            max_clipped: 0.02,
+            white_balance: true,
+        }
+    }
+
+    /// A gray scene whose brightness is proportional to the exposure, with a blue cast.
+    fn capture(exposure_us: f32) -> DynamicImage {
+        let level = |gain: f32| (exposure_us / 40.0 * gain).min(255.0) as u8;
+        DynamicImage::ImageRgb8(RgbImage::from_pixel(16, 16, Rgb([level(0.8), level(1.0), level(1.25)])))
+    }
+
+    #[test]
+    fn converges_on_a_simulated_camera() {
+        let config = config();
+        let mut controller = ExposureController::default();
+        let mut exposure = config.initial_exposure_us;
+        let mut wb = (1.0, 1.0);
+        for _ in 0..10 {
+            let frame = capture(exposure);
+            // The simulated camera applies the white balance gains to the raw channels.
+            let frame = DynamicImage::ImageRgb8(RgbImage::from_fn(16, 16, |x, y| {
+                let p = frame.as_rgb8().unwrap().get_pixel(x, y);
+                Rgb([(p[0] as f32 * wb.0).min(255.0) as u8, p[1], (p[2] as f32 * wb.1).min(255.0) as u8])
+            }));
+            if let Some(control) = controller.update(&frame, &config) {
+                exposure = control.exposure_us.unwrap_or(exposure);
+                wb = control.white_balance.unwrap_or(wb);
+            }
+        }
+        // Mid gray is reached at 5100us, up to the deadband.
+        assert!((exposure / 5100.0 - 1.0).abs() < 0.06, "exposure {}", exposure);
+        assert!((wb.0 - 1.25).abs() < 0.1 && (wb.1 - 0.8).abs() < 0.1, "white balance {:?}", wb);
+
+        // Settled: no more adjustments.
+        let frame = capture(exposure);
+        let settled = AutoExposureNodeConfig { white_balance: false, ..config };
+        assert_eq!(controller.update(&frame, &settled), None);
+    }
+}
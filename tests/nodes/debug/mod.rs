@@ -0,0 +1,2 @@
+pub mod test_clipboard;
+pub mod test_summary;
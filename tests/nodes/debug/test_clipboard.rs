@@ -0,0 +1,34 @@
+#[cfg(test)]
+#[cfg(feature = "clipboard")]
+mod debug {
+    use std::borrow::Cow;
+
+    use arboard::ImageData;
+    use flowrs_img::config::Validate;
+    use flowrs_img::debug::{clipboard_data, clipboard_image, ClipboardSourceNodeConfig};
+    use image::{DynamicImage, Rgb, RgbImage, Rgba};
+
+    #[test]
+    fn source_config_is_valid_and_defaults_when_omitted() {
+        let config: ClipboardSourceNodeConfig = serde_json::from_str("{}").unwrap();
+        assert_eq!(config.poll_interval_ms, 500);
+        assert!(config.validate().is_ok());
+        assert!(ClipboardSourceNodeConfig { poll_interval_ms: 0 }.validate().is_ok());
+    }
+
+    #[test]
+    fn frames_round_trip_as_rgba() {
+        let img = DynamicImage::ImageRgb8(RgbImage::from_fn(3, 2, |x, y| Rgb([x as u8 * 80, y as u8 * 200, 7])));
+        let data = clipboard_data(&img);
+        assert_eq!((data.width, data.height, data.bytes.len()), (3, 2, 24));
+        let back = clipboard_image(data).unwrap();
+        assert_eq!(back, img.to_rgba8());
+        assert_eq!(*back.get_pixel(2, 1), Rgba([160, 200, 7, 255]));
+    }
+
+    #[test]
+    fn mismatched_clipboard_data_is_rejected() {
+        let data = ImageData { width: 4, height: 4, bytes: Cow::Owned(vec![0; 15]) };
+        assert!(clipboard_image(data).is_err());
+    }
+}
@@ -0,0 +1,38 @@
+#[cfg(test)]
+mod debug {
+    use flowrs_img::debug::{ascii_thumbnail, draw_number, summarize};
+    use image::{DynamicImage, GrayImage, ImageBuffer, Luma, Rgb, Rgba, RgbaImage};
+
+    #[test]
+    fn per_channel_statistics() {
+        let img = DynamicImage::ImageRgb16(ImageBuffer::from_fn(2, 1, |x, _| Rgb([x as u16 * 1000, 40000, 7])));
+        let summary = summarize(&img);
+        assert_eq!((summary.width, summary.height, summary.format.as_str()), (2, 1, "Rgb16"));
+        assert_eq!(summary.channels.len(), 3);
+        assert_eq!((summary.channels[0].min, summary.channels[0].max, summary.channels[0].mean), (0.0, 1000.0, 500.0));
+        assert_eq!(summary.channels[1].mean, 40000.0);
+        assert_eq!(summary.channels[2].max, 7.0);
+    }
+
+    #[test]
+    fn ascii_rows() {
+        let img = DynamicImage::ImageLuma8(GrayImage::from_fn(40, 20, |x, _| Luma([if x < 20 { 0 } else { 255 }])));
+        let art = ascii_thumbnail(&img, 8);
+        let lines: Vec<&str> = art.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines.iter().all(|l| l.starts_with("   ") && l.ends_with("@@@")));
+    }
+
+    #[test]
+    fn number_overlay() {
+        let mut img = RgbaImage::from_pixel(20, 10, Rgba([9, 9, 9, 255]));
+        draw_number(&mut img, "1.", 1);
+        // The box spans two glyphs plus a border: 9x7 pixels.
+        assert_eq!(img.get_pixel(8, 6), &Rgba([0, 0, 0, 255]));
+        assert_eq!(img.get_pixel(9, 0), &Rgba([9, 9, 9, 255]));
+        // Top of the `1` stem and the `.` in the last glyph row.
+        assert_eq!(img.get_pixel(2, 1), &Rgba([255, 255, 0, 255]));
+        assert_eq!(img.get_pixel(6, 5), &Rgba([255, 255, 0, 255]));
+        assert_eq!(img.get_pixel(5, 5), &Rgba([0, 0, 0, 255]));
+    }
+}
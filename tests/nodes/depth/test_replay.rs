@@ -0,0 +1,33 @@
+#[cfg(test)]
+mod depth {
+    use flowrs_img::depth::{depth_from_image, depth_to_image, DepthReplaySource, DepthSource};
+    use flowrs_img::types::CameraIntrinsics;
+    use image::{DynamicImage, ImageBuffer, Luma, Rgb, RgbImage};
+
+    #[test]
+    fn replays_aligned_pairs() {
+        let dir = std::env::temp_dir().join(format!("flowrs_img_depth_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let intrinsics = CameraIntrinsics { width: 4, height: 2, fx: 380.0, fy: 380.0, ppx: 2.0, ppy: 1.0 };
+        std::fs::write(dir.join("intrinsics.json"), serde_json::to_vec(&intrinsics).unwrap()).unwrap();
+        for i in 0..2u16 {
+            RgbImage::from_pixel(4, 2, Rgb([i as u8; 3])).save(dir.join(format!("color_{:04}.png", i))).unwrap();
+            let depth = ImageBuffer::<Luma<u16>, Vec<u16>>::from_fn(4, 2, |x, y| Luma([1000 * i + (y * 4 + x) as u16]));
+            depth.save(dir.join(format!("depth_{:04}.png", i))).unwrap();
+        }
+        // A color frame without depth is skipped.
+        RgbImage::new(4, 2).save(dir.join("color_0002.png")).unwrap();
+
+        let mut source = DepthReplaySource::open(&dir, 0.001, false).unwrap();
+        let first = source.next_frame().unwrap().unwrap();
+        assert_eq!(first.intrinsics, Some(intrinsics));
+        assert_eq!(first.depth[[1, 2]], 6);
+        let second = source.next_frame().unwrap().unwrap();
+        assert_eq!(second.depth[[0, 0]], 1000);
+        assert!(source.next_frame().unwrap().is_none());
+
+        assert_eq!(depth_from_image(&depth_to_image(&second.depth)).unwrap(), second.depth);
+        assert!(depth_from_image(&DynamicImage::new_rgb8(1, 1)).is_err());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
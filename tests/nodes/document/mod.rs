@@ -0,0 +1,2 @@
+pub mod test_deskew;
+pub mod test_scan;
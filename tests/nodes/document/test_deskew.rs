@@ -0,0 +1,42 @@
+#[cfg(test)]
+mod document {
+    use flowrs_img::document::{deskew, estimate_skew};
+    use image::{DynamicImage, GrayImage, Luma};
+    use imageproc::drawing::draw_filled_rect_mut;
+    use imageproc::geometric_transformations::{rotate_about_center, Interpolation};
+    use imageproc::rect::Rect;
+
+    /// Ten dark "text lines" on white paper.
+    fn page() -> GrayImage {
+        let mut img = GrayImage::from_pixel(400, 300, Luma([255]));
+        for line in 0..10 {
+            draw_filled_rect_mut(&mut img, Rect::at(60, 40 + line * 22).of_size(280, 8), Luma([0]));
+        }
+        img
+    }
+
+    /// `page` with its lines rising to the right by `angle` degrees.
+    fn skewed(angle: f32) -> GrayImage {
+        rotate_about_center(&page(), -angle.to_radians(), Interpolation::Bilinear, Luma([255]))
+    }
+
+    #[test]
+    fn estimates_skew_either_way() {
+        for angle in [-7.3, -1.0, 0.0, 2.5, 11.0] {
+            let found = estimate_skew(&skewed(angle), 15.0, 0.1);
+            assert!((found - angle).abs() <= 0.15, "{} estimated as {}", angle, found);
+        }
+    }
+
+    #[test]
+    fn deskew_straightens_the_lines() {
+        let straight = deskew(&DynamicImage::ImageLuma8(skewed(4.0)), 4.0).to_luma8();
+        assert!(estimate_skew(&straight, 15.0, 0.1).abs() <= 0.15);
+        assert_eq!(straight.dimensions(), (400, 300));
+    }
+
+    #[test]
+    fn blank_pages_are_not_skewed() {
+        assert_eq!(estimate_skew(&GrayImage::from_pixel(100, 100, Luma([255])), 15.0, 0.1), 0.0);
+    }
+}
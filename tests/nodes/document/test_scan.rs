@@ -0,0 +1,44 @@
+#[cfg(test)]
+mod document {
+    use flowrs_img::document::{find_page, scan_document, DocumentScanNodeConfig};
+    use image::{DynamicImage, GrayImage, Luma};
+    use imageproc::drawing::{draw_filled_rect_mut, draw_polygon_mut};
+    use imageproc::point::Point;
+    use imageproc::rect::Rect;
+
+    /// A white sheet photographed at an angle on a dark desk, with a black block printed on it.
+    fn photo() -> GrayImage {
+        let mut img = GrayImage::from_pixel(400, 300, Luma([40]));
+        let sheet = [Point::new(60, 40), Point::new(330, 60), Point::new(350, 260), Point::new(40, 240)];
+        draw_polygon_mut(&mut img, &sheet, Luma([230]));
+        draw_filled_rect_mut(&mut img, Rect::at(170, 130).of_size(30, 20), Luma([10]));
+        img
+    }
+
+    #[test]
+    fn finds_the_sheet_corners() {
+        let corners = find_page(&photo(), 0.2).expect("page found");
+        let expected = [(60.0, 40.0), (330.0, 60.0), (350.0, 260.0), (40.0, 240.0)];
+        for (found, expected) in corners.iter().zip(expected) {
+            assert!((found.0 - expected.0).abs() < 4.0 && (found.1 - expected.1).abs() < 4.0, "{:?} vs {:?}", corners, expected);
+        }
+    }
+
+    #[test]
+    fn scans_to_a_binarized_upright_page() {
+        let page = scan_document(&DynamicImage::ImageLuma8(photo()), &DocumentScanNodeConfig::default()).to_luma8();
+        assert!((page.width() as i32 - 311).abs() < 6 && (page.height() as i32 - 201).abs() < 6, "{:?}", page.dimensions());
+        // The desk is cut away, leaving white paper with the printed block in black.
+        assert_eq!(page.get_pixel(10, 10)[0], 255);
+        assert_eq!(page.get_pixel(page.width() - 10, page.height() - 10)[0], 255);
+        let dark = page.pixels().filter(|p| p[0] == 0).count();
+        assert!(dark > 300 && dark < 1500, "{}", dark);
+    }
+
+    #[test]
+    fn leaves_frames_without_a_page_unwarped() {
+        let blank = DynamicImage::ImageLuma8(GrayImage::from_pixel(64, 48, Luma([40])));
+        let config = DocumentScanNodeConfig { binarize: false, ..Default::default() };
+        assert_eq!(scan_document(&blank, &config).to_luma8().dimensions(), (64, 48));
+    }
+}
@@ -0,0 +1,62 @@
+#[cfg(test)]
+mod expr {
+    use flowrs::connection::{connect, Input};
+    use flowrs::node::Node;
+    use flowrs_img::expr::{evaluate, PixelContext, PixelExpr, PixelExprNode, PixelExprNodeConfig};
+    use image::{DynamicImage, GrayImage, Luma, Rgb, RgbImage};
+
+    fn eval(src: &str, ctx: &PixelContext) -> f32 {
+        PixelExpr::compile(src).unwrap().eval(ctx, &mut Vec::new())
+    }
+
+    #[test]
+    fn precedence_and_functions() {
+        let ctx = PixelContext { a: [100.0, 50.0, 0.0, 255.0], b: [120.0, 0.0, 0.0, 255.0], x: 3.0, ..Default::default() };
+        assert_eq!(eval("1 + 2 * 3 - -4", &ctx), 11.0);
+        assert_eq!(eval("(1 + 2) * 3 % 5", &ctx), 4.0);
+        assert_eq!(eval("clamp(a.r - b.r, 0, 255)", &ctx), 0.0);
+        assert_eq!(eval("select(x >= 3, pow(2, 3), 0)", &ctx), 8.0);
+        assert_eq!(eval("max(a.g, 7) + min(1, 2) + abs(-1)", &ctx), 52.0);
+        let ndvi = eval("(a.r - a.g) / (a.r + a.g)", &ctx);
+        assert!((ndvi - 1.0 / 3.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn compile_errors() {
+        assert!(PixelExpr::compile("a.r +").is_err());
+        assert!(PixelExpr::compile("c.r").is_err());
+        assert!(PixelExpr::compile("clamp(a.r, 0)").is_err());
+        assert!(PixelExpr::compile("a.r b.r").is_err());
+        assert!(!PixelExpr::compile("a.r * 2").unwrap().uses_b());
+        assert!(PixelExpr::compile("a.r - b.g").unwrap().uses_b());
+    }
+
+    #[test]
+    fn difference_map() {
+        let a = DynamicImage::ImageRgb8(RgbImage::from_pixel(2, 2, Rgb([200, 10, 0])));
+        let b = DynamicImage::ImageRgb8(RgbImage::from_pixel(2, 2, Rgb([50, 20, 0])));
+        let exprs = vec![PixelExpr::compile("abs(a.r - b.r) + abs(a.g - b.g)").unwrap()];
+        let diff = evaluate(&exprs, &a, Some(&b)).unwrap();
+        assert_eq!(diff.to_luma8().get_pixel(1, 1)[0], 160);
+    }
+
+    #[test]
+    fn stalled_input_keeps_only_recent_frames() {
+        let frame = |v: u8| DynamicImage::ImageLuma8(GrayImage::from_pixel(1, 1, Luma([v])));
+        let mut node = PixelExprNode::new(PixelExprNodeConfig { expressions: vec!["a.r + b.r".into()] }, None);
+        let mut out = Input::new();
+        connect(node.output.clone(), out.clone());
+
+        for v in 0..20 {
+            node.a.send(frame(v)).unwrap();
+            node.on_update().unwrap();
+        }
+        assert!(out.next().is_err());
+
+        // The oldest frames on `a` were dropped, so `b` pairs with the eighth-latest.
+        node.b.send(frame(100)).unwrap();
+        node.on_update().unwrap();
+        assert_eq!(out.next().unwrap().to_luma8().get_pixel(0, 0)[0], 112);
+        assert!(out.next().is_err());
+    }
+}
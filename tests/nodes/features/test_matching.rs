@@ -0,0 +1,63 @@
+#[cfg(test)]
+mod features {
+    use flowrs::connection::{connect, Input};
+    use flowrs::node::Node;
+    use flowrs_img::features::{estimate_homography, extract_features, match_features, Correspondence, FeatureExtractNodeConfig, FeatureKind, FeatureMatchNodeConfig, StitchNode, StitchNodeConfig};
+    use image::{DynamicImage, GenericImageView, GrayImage, Luma};
+
+    fn scene(shift: (u32, u32)) -> DynamicImage {
+        DynamicImage::ImageLuma8(GrayImage::from_fn(160, 120, |x, y| {
+            let (x, y) = (x.wrapping_sub(shift.0), y.wrapping_sub(shift.1));
+            let h = x.wrapping_mul(73_856_093) ^ y.wrapping_mul(19_349_663);
+            let block = ((x / 9).wrapping_mul(31) ^ (y / 7).wrapping_mul(17)) % 5;
+            Luma([(block * 50) as u8 ^ (h % 7) as u8])
+        }))
+    }
+
+    #[test]
+    fn shifted_frame_matches_with_constant_offset() {
+        let extract = FeatureExtractNodeConfig { kind: FeatureKind::Orb, max_features: 200, fast_threshold: 20 };
+        let a = extract_features(&scene((0, 0)), &extract);
+        let b = extract_features(&scene((5, 3)), &extract);
+        assert!(a.keypoints.len() > 20);
+        assert_eq!(a.keypoints.len(), a.descriptors.len());
+
+        let config = FeatureMatchNodeConfig { max_distance: 40, ratio: 0.8, cross_check: true };
+        let matches = match_features(&a, &b, &config);
+        assert!(matches.len() > 10, "{}", matches.len());
+        let consistent = matches.iter().filter(|m| m.to.0 - m.from.0 == 5.0 && m.to.1 - m.from.1 == 3.0).count();
+        assert!(consistent * 10 >= matches.len() * 9, "{} of {}", consistent, matches.len());
+    }
+
+    #[test]
+    fn ransac_rejects_outliers() {
+        let mut matches: Vec<Correspondence> = (0..40)
+            .map(|i| {
+                let from = ((i * 37 % 150) as f32, (i * 53 % 110) as f32);
+                Correspondence { left: i, right: i, from, to: (from.0 - 12.0, from.1 + 4.0), distance: 0 }
+            })
+            .collect();
+        for m in matches.iter_mut().step_by(5) {
+            m.to = (m.to.1, m.to.0);
+        }
+
+        let (h, inliers) = estimate_homography(&matches, 1.0, 200).unwrap();
+        assert_eq!(inliers, 32);
+        let (x, y) = h * (50.0, 50.0);
+        assert!((x - 62.0).abs() < 0.1 && (y - 46.0).abs() < 0.1, "{} {}", x, y);
+    }
+
+    #[test]
+    fn panorama_respects_size_limit() {
+        let stitch = |max_pixels| {
+            let mut node = StitchNode::new(StitchNodeConfig { max_pixels, ..Default::default() }, None);
+            let mut out = Input::new();
+            connect(node.output.clone(), out.clone());
+            node.input.send(scene((0, 0))).unwrap();
+            node.flush.send(true).unwrap();
+            node.on_update().map(|_| out.next().unwrap().dimensions())
+        };
+        assert_eq!(stitch(160 * 120).unwrap(), (160, 120));
+        assert!(stitch(160 * 120 - 1).is_err());
+    }
+}
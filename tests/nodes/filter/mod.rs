@@ -0,0 +1,6 @@
+pub mod test_deinterlace;
+pub mod test_denoise;
+pub mod test_flat_field;
+pub mod test_inpaint;
+pub mod test_lens;
+pub mod test_temporal;
@@ -0,0 +1,24 @@
+#[cfg(test)]
+mod filter {
+    use flowrs_img::filter::{bob_field, linear_blend, Field};
+    use image::{Rgb, Rgb32FImage};
+
+    /// Top field white, bottom field black, as a fast horizontal motion would comb.
+    fn combed() -> Rgb32FImage {
+        Rgb32FImage::from_fn(4, 6, |_, y| if y % 2 == 0 { Rgb([1.0; 3]) } else { Rgb([0.0; 3]) })
+    }
+
+    #[test]
+    fn bob_keeps_one_field() {
+        let top = bob_field(&combed(), Field::Top);
+        assert!(top.pixels().all(|p| p[0] == 1.0));
+        let bottom = bob_field(&combed(), Field::Bottom);
+        assert!(bottom.pixels().all(|p| p[0] == 0.0));
+    }
+
+    #[test]
+    fn blend_removes_combing() {
+        let blended = linear_blend(&combed());
+        assert!((1..5).all(|y| blended.get_pixel(2, y)[0] == 0.5));
+    }
+}
@@ -0,0 +1,32 @@
+#[cfg(test)]
+mod filter {
+    use flowrs_img::filter::{bilateral, non_local_means};
+    use image::{Rgb, Rgb32FImage};
+
+    /// Dark left and bright right half with a deterministic +-0.05 checker noise.
+    fn noisy_edge() -> Rgb32FImage {
+        Rgb32FImage::from_fn(16, 16, |x, y| {
+            let base = if x < 8 { 0.2 } else { 0.8 };
+            let noise = if (x + y) % 2 == 0 { 0.05 } else { -0.05 };
+            Rgb([base + noise; 3])
+        })
+    }
+
+    fn check(denoised: &Rgb32FImage) {
+        // Noise is gone inside the halves...
+        assert!((denoised.get_pixel(3, 4)[0] - 0.2).abs() < 0.02, "{:?}", denoised.get_pixel(3, 4));
+        assert!((denoised.get_pixel(12, 4)[0] - 0.8).abs() < 0.02, "{:?}", denoised.get_pixel(12, 4));
+        // ...while the edge stays sharp.
+        assert!(denoised.get_pixel(7, 8)[0] < 0.3 && denoised.get_pixel(8, 8)[0] > 0.7);
+    }
+
+    #[test]
+    fn bilateral_smooths_but_keeps_edges() {
+        check(&bilateral(&noisy_edge(), 2, 2.0, 0.3));
+    }
+
+    #[test]
+    fn non_local_means_smooths_but_keeps_edges() {
+        check(&non_local_means(&noisy_edge(), 1, 3, 0.2));
+    }
+}
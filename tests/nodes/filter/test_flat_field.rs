@@ -0,0 +1,75 @@
+#[cfg(test)]
+mod filter {
+    use flowrs::connection::{connect, Input};
+    use flowrs::node::Node;
+    use flowrs_img::config::Validate;
+    use flowrs_img::filter::{CalibrationTarget, FlatFieldNode, FlatFieldNodeConfig};
+    use image::{DynamicImage, GrayImage, Luma, Rgb, Rgb32FImage};
+
+    fn field(f: impl Fn(u32, u32) -> u8) -> DynamicImage {
+        DynamicImage::ImageLuma8(GrayImage::from_fn(16, 8, |x, y| Luma([f(x, y)])))
+    }
+
+    fn correct(dark: Option<DynamicImage>, flat: DynamicImage, raw: DynamicImage) -> GrayImage {
+        run(dark, flat, raw).into_luma8()
+    }
+
+    fn correct_f32(flat: DynamicImage, raw: DynamicImage) -> Rgb32FImage {
+        run(None, flat, raw).into_rgb32f()
+    }
+
+    /// Records the given dark and flat frames, then corrects `raw`.
+    fn run(dark: Option<DynamicImage>, flat: DynamicImage, raw: DynamicImage) -> DynamicImage {
+        let config = FlatFieldNodeConfig { calibration_frames: 1, ..Default::default() };
+        let mut node = FlatFieldNode::new(config, None);
+        let mut out = Input::new();
+        connect(node.output.clone(), out.clone());
+        let masters = dark.map(|d| (CalibrationTarget::Dark, d)).into_iter().chain([(CalibrationTarget::Flat, flat)]);
+        for (target, master) in masters {
+            node.calibrate.send(target).unwrap();
+            node.input.send(master).unwrap();
+            node.on_update().unwrap();
+            out.next().unwrap();
+        }
+        node.input.send(raw).unwrap();
+        node.on_update().unwrap();
+        out.next().unwrap()
+    }
+
+    fn spread(img: &GrayImage) -> (u8, u8) {
+        (*img.iter().min().unwrap(), *img.iter().max().unwrap())
+    }
+
+    #[test]
+    fn uniform_field_is_unchanged() {
+        let dark = field(|_, _| 10);
+        let corrected = correct(Some(dark), field(|_, _| 200), field(|_, _| 130));
+        assert_eq!(spread(&corrected), (120, 120));
+    }
+
+    #[test]
+    fn vignetting_is_flattened() {
+        let vignette = |x: u32, y: u32| 10 + 200 - (x.abs_diff(8) * 8 + y.abs_diff(4) * 10) as u8;
+        let corrected = correct(Some(field(|_, _| 10)), field(vignette), field(vignette));
+        let (min, max) = spread(&corrected);
+        assert!(max - min <= 1, "{min}..{max}");
+    }
+
+    #[test]
+    fn dead_pixels_get_bounded_gain() {
+        let flat = field(|x, y| if (x, y) == (3, 3) { 0 } else { 200 });
+        let raw = DynamicImage::ImageRgb32F(Rgb32FImage::from_pixel(16, 8, Rgb([0.01; 3])));
+        let corrected = correct_f32(flat, raw);
+        assert!((corrected.get_pixel(3, 3)[0] - 0.16).abs() < 1e-6, "{:?}", corrected.get_pixel(3, 3));
+        assert!((corrected.get_pixel(0, 0)[0] - 0.01).abs() < 1e-3);
+
+        // A flat without any response leaves the image as it is.
+        assert_eq!(spread(&correct(None, field(|_, _| 0), field(|_, _| 50))), (50, 50));
+    }
+
+    #[test]
+    fn default_config_is_valid() {
+        assert!(FlatFieldNodeConfig::default().validate().is_ok());
+        assert!(FlatFieldNodeConfig::builder().build().is_ok());
+    }
+}
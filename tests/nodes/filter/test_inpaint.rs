@@ -0,0 +1,43 @@
+#[cfg(test)]
+mod filter {
+    use flowrs_img::filter::{inpaint, InpaintMethod};
+    use image::{GrayImage, Luma, Rgb, Rgb32FImage};
+
+    /// A horizontal gradient with a bright "timestamp" block burned into the middle.
+    fn overlaid() -> (Rgb32FImage, GrayImage) {
+        let img = Rgb32FImage::from_fn(20, 12, |x, y| {
+            if (6..14).contains(&x) && (4..8).contains(&y) { Rgb([1.0; 3]) } else { Rgb([x as f32 / 20.0; 3]) }
+        });
+        let mask = GrayImage::from_fn(20, 12, |x, y| Luma([if (6..14).contains(&x) && (4..8).contains(&y) { 255 } else { 0 }]));
+        (img, mask)
+    }
+
+    fn check(filled: &Rgb32FImage) {
+        for y in 4..8 {
+            for x in 6..14 {
+                let expected = x as f32 / 20.0;
+                assert!((filled.get_pixel(x, y)[0] - expected).abs() < 0.1, "({}, {}): {:?}", x, y, filled.get_pixel(x, y));
+            }
+        }
+        // Pixels outside the mask are untouched.
+        assert_eq!(filled.get_pixel(2, 2)[0], 0.1);
+    }
+
+    #[test]
+    fn telea_fills_from_the_surroundings() {
+        let (img, mask) = overlaid();
+        check(&inpaint(&img, &mask, InpaintMethod::Telea { radius: 4 }));
+    }
+
+    #[test]
+    fn diffusion_fills_from_the_surroundings() {
+        let (img, mask) = overlaid();
+        check(&inpaint(&img, &mask, InpaintMethod::Diffusion { iterations: 50 }));
+    }
+
+    #[test]
+    fn empty_mask_changes_nothing() {
+        let (img, _) = overlaid();
+        assert_eq!(inpaint(&img, &GrayImage::new(20, 12), InpaintMethod::default()), img);
+    }
+}
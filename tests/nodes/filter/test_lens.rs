@@ -0,0 +1,36 @@
+#[cfg(test)]
+mod filter {
+    use flowrs_img::filter::{correct_lens, LensProfile};
+    use image::{Rgb, Rgb32FImage};
+
+    #[test]
+    fn vignette_is_flattened() {
+        let k1 = 0.5;
+        // Light falloff that the gain `1 + k1 r²` exactly undoes.
+        let (cx, cy) = (15.0f32, 10.0f32);
+        let norm = cx * cx + cy * cy;
+        let vignetted = Rgb32FImage::from_fn(31, 21, |x, y| {
+            let r2 = ((x as f32 - cx).powi(2) + (y as f32 - cy).powi(2)) / norm;
+            Rgb([0.6 / (1.0 + k1 * r2); 3])
+        });
+        let profile = LensProfile { vignette: [k1, 0.0, 0.0], ..LensProfile::default() };
+        let corrected = correct_lens(&vignetted, &profile);
+        assert!(corrected.pixels().all(|p| (p[0] - 0.6).abs() < 1e-4));
+    }
+
+    #[test]
+    fn red_fringe_is_realigned() {
+        // Red is magnified by 1.1, so its edge sits further from the center than green's.
+        let img = Rgb32FImage::from_fn(41, 1, |x, _| {
+            let d = x as f32 - 20.0;
+            Rgb([(d <= 11.0) as u8 as f32, (d <= 10.0) as u8 as f32, 0.0])
+        });
+        let profile = LensProfile { red_scale: 1.1, ..LensProfile::default() };
+        let corrected = correct_lens(&img, &profile);
+        for x in 0..41 {
+            let p = corrected.get_pixel(x, 0);
+            assert!((p[0] - p[1]).abs() < 0.2, "x = {}: {:?}", x, p);
+        }
+        assert_eq!(correct_lens(&img, &LensProfile::default()), img);
+    }
+}
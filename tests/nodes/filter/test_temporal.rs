@@ -0,0 +1,35 @@
+#[cfg(test)]
+mod filter {
+    use flowrs_img::filter::{TemporalDenoiseNodeConfig, TemporalFilter, TemporalMode};
+    use image::{Rgb, Rgb32FImage};
+
+    fn flat(value: f32) -> Rgb32FImage {
+        Rgb32FImage::from_pixel(4, 4, Rgb([value; 3]))
+    }
+
+    #[test]
+    fn median_rejects_outliers() {
+        let config = TemporalDenoiseNodeConfig { mode: TemporalMode::Median { frames: 3 }, motion_threshold: None };
+        let mut filter = TemporalFilter::default();
+        filter.apply(&flat(0.5), &config);
+        filter.apply(&flat(0.5), &config);
+        let out = filter.apply(&flat(1.0), &config);
+        assert!(out.pixels().all(|p| p[0] == 0.5));
+    }
+
+    #[test]
+    fn motion_bypasses_average() {
+        let mode = TemporalMode::ExponentialAverage { alpha: 0.25 };
+        let mut filter = TemporalFilter::default();
+        let config = TemporalDenoiseNodeConfig { mode, motion_threshold: Some(0.1) };
+        filter.apply(&flat(0.2), &config);
+        let noisy = filter.apply(&flat(0.28), &config);
+        assert!((noisy.get_pixel(0, 0)[0] - 0.22).abs() < 1e-6);
+
+        let mut moved = flat(0.22);
+        moved.put_pixel(1, 1, Rgb([0.9; 3]));
+        let out = filter.apply(&moved, &config);
+        assert_eq!(out.get_pixel(1, 1)[0], 0.9);
+        assert!((out.get_pixel(0, 0)[0] - 0.22).abs() < 1e-6);
+    }
+}
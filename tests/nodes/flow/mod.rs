@@ -0,0 +1,7 @@
+pub mod test_drop_old;
+pub mod test_gate;
+pub mod test_packet;
+pub mod test_parallelize;
+pub mod test_queue;
+pub mod test_status;
+pub mod test_throughput;
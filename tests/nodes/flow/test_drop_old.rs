@@ -0,0 +1,44 @@
+#[cfg(test)]
+mod flow {
+    use flowrs::connection::{connect, Input};
+    use flowrs::node::Node;
+    use flowrs_img::flow::DropOldFramesNode;
+    use image::{DynamicImage, GrayImage, Luma};
+
+    fn frame(value: u8) -> DynamicImage {
+        DynamicImage::ImageLuma8(GrayImage::from_pixel(1, 1, Luma([value])))
+    }
+
+    #[test]
+    fn forwards_latest_and_counts_dropped() {
+        let mut node = DropOldFramesNode::new(None);
+        let mut out = Input::new();
+        let mut dropped = Input::new();
+        connect(node.output.clone(), out.clone());
+        connect(node.dropped.clone(), dropped.clone());
+
+        for value in 0..4 {
+            node.input.send(frame(value)).unwrap();
+        }
+        node.on_update().unwrap();
+        assert_eq!(out.next().unwrap().to_luma8().get_pixel(0, 0)[0], 3);
+        assert!(out.next().is_err());
+        assert_eq!(dropped.next().unwrap(), 3);
+
+        // A lone frame passes without touching the count.
+        node.input.send(frame(4)).unwrap();
+        node.on_update().unwrap();
+        assert_eq!(out.next().unwrap().to_luma8().get_pixel(0, 0)[0], 4);
+        assert!(dropped.next().is_err());
+
+        node.on_update().unwrap();
+        assert!(out.next().is_err());
+
+        for value in 5..7 {
+            node.input.send(frame(value)).unwrap();
+        }
+        node.on_update().unwrap();
+        assert_eq!(out.next().unwrap().to_luma8().get_pixel(0, 0)[0], 6);
+        assert_eq!(dropped.next().unwrap(), 4);
+    }
+}
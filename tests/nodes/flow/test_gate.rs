@@ -0,0 +1,24 @@
+#[cfg(test)]
+mod flow {
+    use flowrs_img::flow::SourceGate;
+    use flowrs_img::types::SourceControl;
+
+    #[test]
+    fn single_shot_pauses_after_one_frame() {
+        let mut gate = SourceGate::default();
+        assert!(gate.running());
+        gate.produced();
+        assert_eq!(gate.mode(), SourceControl::Start);
+
+        gate.apply(SourceControl::Pause);
+        assert!(!gate.running());
+
+        gate.apply(SourceControl::SingleShot);
+        assert!(gate.running());
+        gate.produced();
+        assert_eq!(gate.mode(), SourceControl::Pause);
+
+        gate.apply(SourceControl::Stop);
+        assert!(!gate.running());
+    }
+}
@@ -0,0 +1,19 @@
+#[cfg(test)]
+mod flow {
+    use flowrs_img::flow::CaptureTime;
+    use flowrs_img::types::ImagePacket;
+    use image::DynamicImage;
+    use serde_json::json;
+
+    #[test]
+    fn packets_keep_metadata_across_images() {
+        let mut packet = ImagePacket::new(DynamicImage::new_rgb8(640, 480), 7);
+        packet.metadata.insert("camera".into(), json!("left"));
+
+        let resized = packet.with_image(DynamicImage::new_rgb8(320, 240));
+        assert_eq!(resized.image.width(), 320);
+        assert_eq!((resized.seq, resized.timestamp), (7, packet.timestamp));
+        assert_eq!(resized.metadata["camera"], "left");
+        assert_eq!(resized.captured_at(), Some(packet.timestamp));
+    }
+}
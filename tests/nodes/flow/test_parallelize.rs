@@ -0,0 +1,118 @@
+#[cfg(test)]
+mod flow {
+    use flowrs::connection::{connect, Input, Output};
+    use flowrs::node::{Node, UpdateError};
+    use image::{DynamicImage, GrayImage, Luma};
+    use std::time::{Duration, Instant};
+
+    use flowrs_img::flow::ParallelizeNode;
+    use flowrs_img::transform::{encode_image, DecodeImageNode, EncodeFormat};
+
+    /// PNGs of decreasing size, so later items tend to finish first.
+    fn buffer(i: u8) -> Vec<u8> {
+        let size = 400 - i as u32 * 30;
+        encode_image(&DynamicImage::ImageLuma8(GrayImage::from_pixel(size, size, Luma([i]))), EncodeFormat::Png).unwrap()
+    }
+
+    fn parallel_decoder() -> (ParallelizeNode<Vec<u8>, DynamicImage>, Input<DynamicImage>) {
+        let node = ParallelizeNode::new(4, || DecodeImageNode::new(None), |n| &n.input, |n| &n.output, None);
+        let output = Input::new();
+        connect(node.output.clone(), output.clone());
+        (node, output)
+    }
+
+    fn values(output: &mut Input<DynamicImage>) -> Vec<u8> {
+        std::iter::from_fn(|| output.next().ok()).map(|img| img.to_luma8().get_pixel(0, 0)[0]).collect()
+    }
+
+    #[test]
+    fn restores_input_order_across_updates() {
+        let (mut node, mut output) = parallel_decoder();
+        for i in 0..10 {
+            node.input.send(buffer(i)).unwrap();
+        }
+        let mut seen = Vec::new();
+        while seen.len() < 10 {
+            node.on_update().unwrap();
+            seen.extend(values(&mut output));
+        }
+        assert_eq!(seen, (0..10).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn shutdown_flushes_items_in_flight() {
+        let (mut node, mut output) = parallel_decoder();
+        for i in 0..10 {
+            node.input.send(buffer(i)).unwrap();
+        }
+        node.on_update().unwrap();
+        let mut seen = values(&mut output);
+        node.on_shutdown().unwrap();
+        seen.extend(values(&mut output));
+        assert_eq!(seen, (0..10).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn failed_items_do_not_stall_later_ones() {
+        let (mut node, mut output) = parallel_decoder();
+        node.input.send(buffer(0)).unwrap();
+        node.input.send(b"not an image".to_vec()).unwrap();
+        node.input.send(buffer(1)).unwrap();
+        let _ = node.on_update();
+        assert!(node.on_shutdown().is_err());
+        assert_eq!(values(&mut output), [0, 1]);
+    }
+
+    struct PanicNode {
+        input: Input<u8>,
+        output: Output<u8>,
+    }
+
+    impl Node for PanicNode {
+        fn on_update(&mut self) -> Result<(), UpdateError> {
+            if let Ok(value) = self.input.next() {
+                assert!(value != 0, "worker crashed");
+                self.output.send(value).map_err(|e| UpdateError::Other(e.into()))?;
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn dead_worker_is_an_error() {
+        let mut node = ParallelizeNode::new(2, || PanicNode { input: Input::new(), output: Output::new(None) }, |n| &n.input, |n| &n.output, None);
+        node.input.send(0).unwrap();
+        node.input.send(1).unwrap();
+        let _ = node.on_update();
+        assert!(node.on_shutdown().is_err());
+        node.input.send(2).unwrap();
+        node.input.send(3).unwrap();
+        assert!(node.on_update().is_err());
+    }
+
+    struct StuckNode {
+        input: Input<u8>,
+        output: Output<u8>,
+    }
+
+    impl Node for StuckNode {
+        fn on_update(&mut self) -> Result<(), UpdateError> {
+            if let Ok(value) = self.input.next() {
+                std::thread::sleep(Duration::from_secs(2));
+                self.output.send(value).map_err(|e| UpdateError::Other(e.into()))?;
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn shutdown_gives_up_on_stuck_workers() {
+        let mut node = ParallelizeNode::new(1, || StuckNode { input: Input::new(), output: Output::new(None) }, |n| &n.input, |n| &n.output, None)
+            .with_shutdown_timeout(Duration::from_millis(100));
+        node.input.send(0).unwrap();
+        node.on_update().unwrap();
+        let started = Instant::now();
+        assert!(node.on_shutdown().is_err());
+        assert!(started.elapsed() < Duration::from_secs(1));
+    }
+}
@@ -0,0 +1,43 @@
+#[cfg(test)]
+mod flow {
+    use std::collections::VecDeque;
+
+    use flowrs::connection::{connect, Input};
+    use flowrs::node::Node;
+    use flowrs_img::flow::{push_bounded, BoundedQueueNode, BoundedQueueNodeConfig, OverflowPolicy};
+
+    fn fill(policy: OverflowPolicy) -> (Vec<u32>, u64) {
+        let mut queue = VecDeque::new();
+        let dropped = (0..5).map(|i| push_bounded(&mut queue, i, 3, policy)).sum();
+        (queue.into_iter().collect(), dropped)
+    }
+
+    #[test]
+    fn overflow_policies() {
+        assert_eq!(fill(OverflowPolicy::DropOldest), (vec![2, 3, 4], 2));
+        assert_eq!(fill(OverflowPolicy::DropNewest), (vec![0, 1, 2], 2));
+        assert_eq!(fill(OverflowPolicy::CoalesceToLatest), (vec![4], 4));
+        assert_eq!(fill(OverflowPolicy::Block), (vec![0, 1, 2, 3, 4], 0));
+    }
+
+    #[test]
+    fn default_queue_retains_at_most_capacity() {
+        let config = BoundedQueueNodeConfig { capacity: 4, ..Default::default() };
+        let mut node = BoundedQueueNode::<u32>::new(config, None);
+        let mut output = Input::new();
+        let mut dropped = Input::new();
+        connect(node.output.clone(), output.clone());
+        connect(node.dropped.clone(), dropped.clone());
+
+        for i in 0..20 {
+            node.input.send(i).unwrap();
+        }
+        for _ in 0..10 {
+            node.on_update().unwrap();
+        }
+        let forwarded: Vec<u32> = std::iter::from_fn(|| output.next().ok()).collect();
+        assert_eq!(forwarded, [16, 17, 18, 19]);
+        assert_eq!(dropped.next().ok(), Some(16));
+        assert!(node.input.next().is_err());
+    }
+}
@@ -0,0 +1,37 @@
+#[cfg(test)]
+mod flow {
+    use flowrs::connection::Output;
+    use flowrs::node::UpdateError;
+
+    use anyhow::anyhow;
+
+    use flowrs_img::flow::StatusReporter;
+    use flowrs_img::types::{NodeState, NodeStatus};
+
+    #[test]
+    fn state_follows_frames_and_errors() {
+        let mut health = Output::<NodeStatus>::new(None);
+        let mut reporter = StatusReporter::default();
+        assert_eq!(reporter.status().state, NodeState::Starting);
+
+        reporter.frame();
+        reporter.frame();
+        reporter.track(Ok(()), &mut health).unwrap();
+        assert_eq!(reporter.status().state, NodeState::Running);
+        assert_eq!(reporter.status().frames, 2);
+
+        let result = reporter.track(Err(UpdateError::Other(anyhow!("Connection reset"))), &mut health);
+        assert!(result.is_err());
+        assert_eq!(reporter.status().state, NodeState::Degraded);
+        assert_eq!(reporter.status().errors, 1);
+        assert_eq!(reporter.status().last_error.as_deref(), Some("Connection reset"));
+
+        reporter.set_state(NodeState::Failed);
+        reporter.error("Still unreachable");
+        assert_eq!(reporter.status().state, NodeState::Failed);
+
+        reporter.frame();
+        assert_eq!(reporter.status().state, NodeState::Running);
+        assert_eq!(reporter.status().errors, 2);
+    }
+}
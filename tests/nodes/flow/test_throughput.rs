@@ -0,0 +1,30 @@
+#[cfg(test)]
+mod flow {
+    use std::time::{Duration, Instant};
+
+    use flowrs_img::flow::ThroughputWindow;
+
+    #[test]
+    fn window_statistics() {
+        let start = Instant::now();
+        let mut window = ThroughputWindow::default();
+        for (i, gap_ms) in [0u64, 40, 80, 120].iter().enumerate() {
+            window.record(start + Duration::from_millis(*gap_ms), Some(Duration::from_millis(10 + i as u64 * 10)));
+        }
+
+        let stats = window.stats(Duration::from_millis(200));
+        assert_eq!(stats.frames, 4);
+        assert!((stats.fps - 20.0).abs() < 1e-9);
+        assert!(stats.jitter_ms.abs() < 1e-9);
+        assert!((stats.mean_latency_ms.unwrap() - 25.0).abs() < 1e-9);
+        assert!((stats.max_latency_ms.unwrap() - 40.0).abs() < 1e-9);
+
+        let mut uneven = ThroughputWindow::default();
+        for t in [0u64, 10, 50] {
+            uneven.record(start + Duration::from_millis(t), None);
+        }
+        let stats = uneven.stats(Duration::from_secs(1));
+        assert!((stats.jitter_ms - 15.0).abs() < 1e-9);
+        assert_eq!(stats.mean_latency_ms, None);
+    }
+}
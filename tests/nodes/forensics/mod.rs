@@ -0,0 +1,4 @@
+pub mod test_audit;
+pub mod test_ela;
+pub mod test_privacy;
+pub mod test_watermark;
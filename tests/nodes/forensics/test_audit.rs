@@ -0,0 +1,147 @@
+#[cfg(test)]
+mod forensics {
+    use flowrs::connection::{connect, Input};
+    use flowrs::node::Node;
+    use flowrs_img::forensics::{verify_audit_chain, AnonymizationAuditNode, AnonymizationAuditNodeConfig, AuditEntry};
+    use flowrs_img::types::{Detection, Rect};
+    use image::{DynamicImage, Rgb, RgbImage};
+
+    fn face(x: u32) -> Detection {
+        Detection { rect: Rect::new(x, 2, 4, 4), label: "face".into(), score: 0.9 }
+    }
+
+    /// Runs four frames through the node, the second and third with redactions.
+    fn audit(config: AnonymizationAuditNodeConfig) -> Vec<AuditEntry> {
+        let mut node = AnonymizationAuditNode::new(config, None);
+        let mut log = Input::new();
+        connect(node.log.clone(), log.clone());
+        let regions = [vec![], vec![face(0)], vec![face(4), face(8)], vec![]];
+        for (i, detections) in regions.into_iter().enumerate() {
+            node.regions.send(detections).unwrap();
+            node.input.send(DynamicImage::ImageRgb8(RgbImage::from_pixel(16, 8, Rgb([i as u8 * 40, 0, 0])))).unwrap();
+            node.on_update().unwrap();
+        }
+        std::iter::from_fn(|| log.next().ok()).collect()
+    }
+
+    #[test]
+    fn entries_form_a_valid_chain() {
+        let entries = audit(AnonymizationAuditNodeConfig::default());
+        assert_eq!(entries.len(), 4);
+        assert_eq!(entries.iter().map(|e| e.regions.len()).collect::<Vec<_>>(), vec![0, 1, 2, 0]);
+        assert!(entries.windows(2).all(|w| w[1].previous_hash == w[0].entry_hash && w[1].seq == w[0].seq + 1));
+        assert_eq!(verify_audit_chain(&entries), Ok(()));
+    }
+
+    #[test]
+    fn tampering_is_located() {
+        let entries = audit(AnonymizationAuditNodeConfig::default());
+
+        let mut modified = entries.clone();
+        modified[2].regions[1].rect.width = 1;
+        assert_eq!(verify_audit_chain(&modified), Err(2));
+
+        let mut relabeled = entries.clone();
+        relabeled[1].regions[0].reason = "plate".into();
+        assert_eq!(verify_audit_chain(&relabeled), Err(1));
+
+        let mut removed = entries.clone();
+        removed.remove(1);
+        assert_eq!(verify_audit_chain(&removed), Err(1));
+
+        let mut reordered = entries;
+        reordered.swap(2, 3);
+        assert_eq!(verify_audit_chain(&reordered), Err(2));
+    }
+
+    #[test]
+    fn log_file_holds_the_chain() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.jsonl");
+        let sent = audit(AnonymizationAuditNodeConfig { log_path: Some(path.clone()) });
+        let logged: Vec<AuditEntry> = std::fs::read_to_string(path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(logged, sent);
+        assert_eq!(verify_audit_chain(&logged), Ok(()));
+    }
+
+    #[test]
+    fn reopened_log_continues_the_chain() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.jsonl");
+        let config = AnonymizationAuditNodeConfig { log_path: Some(path.clone()) };
+        let first = audit(config.clone());
+        let second = audit(config);
+        assert_eq!(second[0].seq, first.len() as u64);
+        assert_eq!(second[0].previous_hash, first.last().unwrap().entry_hash);
+
+        let logged: Vec<AuditEntry> = std::fs::read_to_string(path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(logged.len(), 8);
+        assert_eq!(verify_audit_chain(&logged), Ok(()));
+    }
+
+    #[test]
+    fn pending_regions_are_bounded() {
+        let mut node = AnonymizationAuditNode::new(AnonymizationAuditNodeConfig::default(), None);
+        let mut log = Input::new();
+        let mut unpaired = Input::new();
+        connect(node.log.clone(), log.clone());
+        connect(node.unpaired.clone(), unpaired.clone());
+        for x in 0..20 {
+            node.regions.send(vec![face(x)]).unwrap();
+        }
+        node.on_update().unwrap();
+        assert_eq!(unpaired.next().ok(), Some(12));
+        for _ in 0..10 {
+            node.input.send(DynamicImage::ImageRgb8(RgbImage::new(32, 8))).unwrap();
+            node.on_update().unwrap();
+        }
+        // The oldest lists were kept for the next frames; the last two frames wait for theirs.
+        let paired: Vec<u32> = std::iter::from_fn(|| log.next().ok()).map(|e| e.regions[0].rect.x).collect();
+        assert_eq!(paired, (0..8).collect::<Vec<_>>());
+        assert!(unpaired.next().is_err());
+    }
+
+    #[test]
+    fn backed_up_frames_keep_their_regions() {
+        let mut node = AnonymizationAuditNode::new(AnonymizationAuditNodeConfig::default(), None);
+        let mut log = Input::new();
+        let mut unpaired = Input::new();
+        connect(node.log.clone(), log.clone());
+        connect(node.unpaired.clone(), unpaired.clone());
+        for x in 0..10 {
+            node.regions.send(vec![face(x)]).unwrap();
+        }
+        for _ in 0..10 {
+            node.input.send(DynamicImage::ImageRgb8(RgbImage::new(32, 8))).unwrap();
+        }
+        node.on_update().unwrap();
+        let entries: Vec<AuditEntry> = std::iter::from_fn(|| log.next().ok()).collect();
+        assert_eq!(entries.iter().map(|e| e.regions[0].rect.x).collect::<Vec<_>>(), (0..10).collect::<Vec<_>>());
+        assert_eq!(verify_audit_chain(&entries), Ok(()));
+        assert!(unpaired.next().is_err());
+    }
+
+    #[test]
+    fn frames_without_regions_are_logged_as_unpaired() {
+        let mut node = AnonymizationAuditNode::new(AnonymizationAuditNodeConfig::default(), None);
+        let mut log = Input::new();
+        let mut unpaired = Input::new();
+        connect(node.log.clone(), log.clone());
+        connect(node.unpaired.clone(), unpaired.clone());
+        for _ in 0..10 {
+            node.input.send(DynamicImage::ImageRgb8(RgbImage::new(32, 8))).unwrap();
+        }
+        node.on_update().unwrap();
+        let logged: Vec<usize> = std::iter::from_fn(|| log.next().ok()).map(|e| e.regions.len()).collect();
+        assert_eq!(logged, [0, 0]);
+        assert_eq!(unpaired.next().ok(), Some(2));
+    }
+}
@@ -0,0 +1,37 @@
+#[cfg(test)]
+mod forensics {
+    use flowrs_img::forensics::error_level_analysis;
+    use flowrs_img::transform::{decode_image, encode_image, EncodeFormat};
+    use image::{imageops, DynamicImage, Rgb, RgbImage};
+
+    fn texture(seed: u32) -> RgbImage {
+        RgbImage::from_fn(128, 128, |x, y| {
+            let v = (x.wrapping_mul(7919) ^ y.wrapping_mul(104729)).wrapping_add(seed).wrapping_mul(2654435761);
+            Rgb([(v >> 24) as u8, (v >> 16) as u8, (v >> 8) as u8])
+        })
+    }
+
+    fn mean(residual: &RgbImage, x0: u32, y0: u32, size: u32, inside: bool) -> f32 {
+        let within = |x: u32, y: u32| (x0..x0 + size).contains(&x) && (y0..y0 + size).contains(&y);
+        let values: Vec<f32> = residual
+            .enumerate_pixels()
+            .filter(|(x, y, _)| within(*x, *y) == inside)
+            .flat_map(|(_, _, p)| p.0.map(f32::from))
+            .collect();
+        values.iter().sum::<f32>() / values.len() as f32
+    }
+
+    #[test]
+    fn pasted_patch_stands_out() {
+        // A JPEG at the analysis quality with a never-compressed patch pasted in on the block grid.
+        let jpeg = encode_image(&DynamicImage::ImageRgb8(texture(0)), EncodeFormat::Jpeg { quality: 80 }).unwrap();
+        let mut forged = decode_image(jpeg).unwrap().to_rgb8();
+        imageops::replace(&mut forged, &imageops::crop_imm(&texture(1), 0, 0, 32, 32).to_image(), 48, 48);
+
+        let (residual, report) = error_level_analysis(&DynamicImage::ImageRgb8(forged), 80, 1.0).unwrap();
+        let (patch, rest) = (mean(&residual, 48, 48, 32, true), mean(&residual, 48, 48, 32, false));
+        assert!(patch > 3.0 * rest, "patch {patch}, rest {rest}");
+        assert!(report.mean_error > rest && report.mean_error < patch);
+        assert!(report.max_error > 0);
+    }
+}
@@ -0,0 +1,119 @@
+#[cfg(test)]
+mod forensics {
+    use flowrs::connection::{connect, Input};
+    use flowrs::node::Node;
+    use flowrs_img::forensics::{apply_privacy_mask, privacy_mask, MaskStyle, PrivacyMaskNode, PrivacyMaskNodeConfig, UNPAIRED_LABEL};
+    use flowrs_img::types::{Detection, Rect};
+    use image::{DynamicImage, Rgb, RgbImage};
+
+    fn face(rect: Rect) -> Detection {
+        Detection { rect, label: "face".into(), score: 0.9 }
+    }
+
+    fn node(config: PrivacyMaskNodeConfig) -> (PrivacyMaskNode, Input<DynamicImage>, Input<Vec<Detection>>) {
+        let node = PrivacyMaskNode::new(PrivacyMaskNodeConfig { style: MaskStyle::Fill([0, 0, 0]), ..config }, None);
+        let (out, masked) = (Input::new(), Input::new());
+        connect(node.output.clone(), out.clone());
+        connect(node.masked.clone(), masked.clone());
+        (node, out, masked)
+    }
+
+    fn white(width: u32, height: u32) -> DynamicImage {
+        DynamicImage::ImageRgb8(RgbImage::from_pixel(width, height, Rgb([255; 3])))
+    }
+
+    #[test]
+    fn mask_covers_polygons_and_rects() {
+        let triangle = vec![(0.0, 0.0), (8.0, 0.0), (0.0, 8.0)];
+        let mask = privacy_mask(10, 10, &[triangle], &[Rect::new(8, 8, 2, 2)]);
+        assert_eq!(mask.get_pixel(1, 1)[0], 255);
+        assert_eq!(mask.get_pixel(6, 6)[0], 0);
+        assert_eq!(mask.get_pixel(9, 9)[0], 255);
+    }
+
+    #[test]
+    fn only_masked_pixels_change() {
+        let original = RgbImage::from_fn(8, 8, |x, y| Rgb([(x * 30) as u8, (y * 30) as u8, 0]));
+        let mask = privacy_mask(8, 8, &[], &[Rect::new(0, 0, 4, 4)]);
+
+        let mut filled = original.clone();
+        apply_privacy_mask(&mut filled, &mask, MaskStyle::Fill([0, 0, 0]));
+        assert_eq!(*filled.get_pixel(3, 3), Rgb([0, 0, 0]));
+        assert_eq!(filled.get_pixel(4, 4), original.get_pixel(4, 4));
+
+        let mut pixelated = original.clone();
+        apply_privacy_mask(&mut pixelated, &mask, MaskStyle::Pixelate { block_size: 4 });
+        assert_eq!(*pixelated.get_pixel(0, 0), Rgb([45, 45, 0]));
+        assert_eq!(pixelated.get_pixel(3, 2), pixelated.get_pixel(0, 0));
+        assert_eq!(pixelated.get_pixel(7, 7), original.get_pixel(7, 7));
+    }
+
+    #[test]
+    fn frames_wait_for_their_own_detections() {
+        let (mut node, mut out, mut masked) = node(PrivacyMaskNodeConfig::default());
+        node.input.send(white(8, 8)).unwrap();
+        node.input.send(white(8, 8)).unwrap();
+        node.on_update().unwrap();
+        assert!(out.next().is_err());
+
+        node.regions.send(vec![face(Rect::new(0, 0, 2, 2))]).unwrap();
+        node.on_update().unwrap();
+        let first = out.next().unwrap().into_rgb8();
+        assert_eq!(*first.get_pixel(1, 1), Rgb([0; 3]));
+        assert_eq!(*first.get_pixel(5, 5), Rgb([255; 3]));
+        assert!(out.next().is_err());
+
+        // The second frame gets the second list, not the regions of the first.
+        node.regions.send(vec![face(Rect::new(4, 4, 2, 2))]).unwrap();
+        node.on_update().unwrap();
+        let second = out.next().unwrap().into_rgb8();
+        assert_eq!(*second.get_pixel(1, 1), Rgb([255; 3]));
+        assert_eq!(*second.get_pixel(5, 5), Rgb([0; 3]));
+        assert_eq!(masked.next().unwrap()[0].rect, Rect::new(0, 0, 2, 2));
+        assert_eq!(masked.next().unwrap()[0].rect, Rect::new(4, 4, 2, 2));
+    }
+
+    #[test]
+    fn lagging_detector_blacks_out_frames() {
+        let (mut node, mut out, mut masked) = node(PrivacyMaskNodeConfig { max_pending: 2, ..Default::default() });
+        for _ in 0..3 {
+            node.input.send(white(4, 4)).unwrap();
+        }
+        node.on_update().unwrap();
+        assert!(out.next().unwrap().into_rgb8().pixels().all(|p| *p == Rgb([0; 3])));
+        assert!(out.next().is_err());
+        let regions = masked.next().unwrap();
+        assert_eq!((regions[0].rect, regions[0].label.as_str()), (Rect::new(0, 0, 4, 4), UNPAIRED_LABEL));
+    }
+
+    #[test]
+    fn padding_is_clamped_to_the_frame() {
+        let (mut node, _out, mut masked) = node(PrivacyMaskNodeConfig { padding: u32::MAX, ..Default::default() });
+        node.input.send(white(6, 6)).unwrap();
+        node.regions.send(vec![face(Rect::new(u32::MAX - 1, 2, u32::MAX, 1)), face(Rect::new(2, 2, 1, 1))]).unwrap();
+        node.on_update().unwrap();
+        let rects: Vec<Rect> = masked.next().unwrap().iter().map(|d| d.rect).collect();
+        assert_eq!(rects, vec![Rect::new(0, 0, 6, 6); 2]);
+    }
+
+    #[test]
+    fn masking_keeps_the_bit_depth() {
+        let (mut node, mut out, _) = node(PrivacyMaskNodeConfig::default());
+        node.input.send(DynamicImage::ImageRgb16(image::ImageBuffer::from_pixel(6, 6, Rgb([1000u16, 2000, 3000])))).unwrap();
+        node.regions.send(vec![face(Rect::new(0, 0, 1, 1))]).unwrap();
+        node.on_update().unwrap();
+        let DynamicImage::ImageRgb16(result) = out.next().unwrap() else { panic!("format changed") };
+        assert_eq!(*result.get_pixel(0, 0), Rgb([0; 3]));
+        assert_eq!(*result.get_pixel(3, 3), Rgb([1000, 2000, 3000]));
+    }
+
+    #[test]
+    fn static_polygons_need_no_detector() {
+        let square = vec![(0.0, 0.0), (2.0, 0.0), (2.0, 2.0), (0.0, 2.0)];
+        let (mut node, mut out, _) = node(PrivacyMaskNodeConfig { polygons: vec![square], wait_for_detections: false, ..Default::default() });
+        node.input.send(white(4, 4)).unwrap();
+        node.on_update().unwrap();
+        let img = out.next().unwrap().into_rgb8();
+        assert_eq!((*img.get_pixel(0, 0), *img.get_pixel(3, 3)), (Rgb([0; 3]), Rgb([255; 3])));
+    }
+}
@@ -0,0 +1,29 @@
+#[cfg(test)]
+mod forensics {
+    use flowrs_img::forensics::{embed_lsb_watermark, extract_lsb_watermark};
+    use image::{DynamicImage, RgbImage, Rgb};
+
+    fn image() -> DynamicImage {
+        DynamicImage::ImageRgb8(RgbImage::from_pixel(32, 32, Rgb([120, 64, 200])))
+    }
+
+    #[test]
+    fn embedded_watermark_round_trips() {
+        let marked = DynamicImage::ImageRgb8(embed_lsb_watermark(&image(), b"camera-7").unwrap());
+        let report = extract_lsb_watermark(&marked, Some(b"camera-7"));
+        assert!(report.present && report.passed);
+        assert_eq!(report.payload.as_deref(), Some(&b"camera-7"[..]));
+    }
+
+    #[test]
+    fn unmarked_image_has_no_watermark() {
+        let report = extract_lsb_watermark(&image(), None);
+        assert!(!report.present && !report.passed);
+    }
+
+    #[test]
+    fn payload_mismatch_fails() {
+        let marked = DynamicImage::ImageRgb8(embed_lsb_watermark(&image(), b"camera-7").unwrap());
+        assert!(!extract_lsb_watermark(&marked, Some(b"camera-8")).passed);
+    }
+}
@@ -0,0 +1,148 @@
+#[cfg(test)]
+mod gpu {
+    use std::sync::Arc;
+
+    use flowrs::connection::{connect, Input, Output};
+    use flowrs::node::Node;
+    use flowrs_img::gpu::{
+        GpuBlurNode, GpuBlurNodeConfig, GpuColorConvertNode, GpuColorConvertNodeConfig, GpuColorOp, GpuContext,
+        GpuImage, GpuResizeNode, GpuResizeNodeConfig, GpuUploadNode,
+    };
+    use image::{DynamicImage, Rgba, RgbaImage};
+
+    /// The shared context, or `None` on machines without a usable adapter, where these tests are skipped.
+    fn context() -> Option<Arc<GpuContext>> {
+        match GpuContext::shared() {
+            Ok(ctx) => Some(ctx),
+            Err(e) => {
+                eprintln!("Skipping GPU test: {}", e);
+                None
+            }
+        }
+    }
+
+    fn pattern(width: u32, height: u32) -> RgbaImage {
+        RgbaImage::from_fn(width, height, |x, y| {
+            Rgba([(x * 37 + y * 11) as u8, (y * 53) as u8, (x * y * 7 + 20) as u8, (255 - x * 9) as u8])
+        })
+    }
+
+    fn run<N: Node>(ctx: &GpuContext, node: &mut N, input: Input<GpuImage>, output: Output<GpuImage>, img: &RgbaImage) -> RgbaImage {
+        let received = Input::new();
+        connect(output, received.clone());
+        input.send(GpuImage::upload(ctx, &DynamicImage::ImageRgba8(img.clone())).unwrap()).unwrap();
+        node.on_update().unwrap();
+        received.next().unwrap().download(ctx).unwrap().to_rgba8()
+    }
+
+    fn assert_close(actual: &RgbaImage, expected: &RgbaImage) {
+        assert_eq!(actual.dimensions(), expected.dimensions());
+        for ((x, y, a), e) in actual.enumerate_pixels().zip(expected.pixels()) {
+            let off = a.0.iter().zip(e.0).any(|(a, e)| a.abs_diff(e) > 1);
+            assert!(!off, "pixel ({}, {}) is {:?}, expected {:?}", x, y, a.0, e.0);
+        }
+    }
+
+    fn unorm(v: f32) -> u8 {
+        (v.clamp(0.0, 1.0) * 255.0).round() as u8
+    }
+
+    fn channel(img: &RgbaImage, x: i64, y: i64, c: usize) -> f32 {
+        let x = x.clamp(0, img.width() as i64 - 1) as u32;
+        let y = y.clamp(0, img.height() as i64 - 1) as u32;
+        img.get_pixel(x, y)[c] as f32 / 255.0
+    }
+
+    /// Bilinear sampling at pixel centres with clamped edges, as in the resize shader.
+    fn resize_reference(src: &RgbaImage, width: u32, height: u32) -> RgbaImage {
+        let (sw, sh) = (src.width() as f32, src.height() as f32);
+        RgbaImage::from_fn(width, height, |x, y| {
+            let sx = (x as f32 + 0.5) * sw / width as f32 - 0.5;
+            let sy = (y as f32 + 0.5) * sh / height as f32 - 0.5;
+            let (x0, y0) = (sx.floor() as i64, sy.floor() as i64);
+            let (fx, fy) = (sx - sx.floor(), sy - sy.floor());
+            Rgba(std::array::from_fn(|c| {
+                let top = channel(src, x0, y0, c) * (1.0 - fx) + channel(src, x0 + 1, y0, c) * fx;
+                let bottom = channel(src, x0, y0 + 1, c) * (1.0 - fx) + channel(src, x0 + 1, y0 + 1, c) * fx;
+                unorm(top * (1.0 - fy) + bottom * fy)
+            }))
+        })
+    }
+
+    /// One pass of the separable Gaussian, rounded to 8 bits like the intermediate GPU image.
+    fn blur_pass(src: &RgbaImage, sigma: f32, horizontal: bool) -> RgbaImage {
+        let radius = (sigma * 3.0).ceil() as i64;
+        RgbaImage::from_fn(src.width(), src.height(), |x, y| {
+            let (mut acc, mut total) = ([0.0f32; 4], 0.0);
+            for i in -radius..=radius {
+                let (sx, sy) = if horizontal { (x as i64 + i, y as i64) } else { (x as i64, y as i64 + i) };
+                let weight = (-((i * i) as f32) / (2.0 * sigma * sigma)).exp();
+                for (c, acc) in acc.iter_mut().enumerate() {
+                    *acc += weight * channel(src, sx, sy, c);
+                }
+                total += weight;
+            }
+            Rgba(acc.map(|a| unorm(a / total)))
+        })
+    }
+
+    #[test]
+    fn empty_images_are_not_uploaded() {
+        let Some(ctx) = context() else { return };
+        assert!(GpuImage::upload(&ctx, &DynamicImage::new_rgba8(0, 4)).is_err());
+        assert!(GpuImage::upload(&ctx, &DynamicImage::new_rgba8(4, 0)).is_err());
+
+        let mut node = GpuUploadNode::new(None);
+        node.input.send(DynamicImage::new_rgb8(0, 0)).unwrap();
+        assert!(node.on_update().is_err());
+    }
+
+    #[test]
+    fn resize_matches_cpu_bilinear() {
+        let Some(ctx) = context() else { return };
+        let src = pattern(7, 5);
+        for (width, height) in [(14, 10), (3, 2), (7, 5)] {
+            let mut node = GpuResizeNode::new(GpuResizeNodeConfig { width, height }, None);
+            let (input, output) = (node.input.clone(), node.output.clone());
+            let actual = run(&ctx, &mut node, input, output, &src);
+            assert_close(&actual, &resize_reference(&src, width, height));
+        }
+    }
+
+    #[test]
+    fn blur_matches_cpu_gaussian() {
+        let Some(ctx) = context() else { return };
+        let src = pattern(9, 6);
+        for sigma in [0.5, 1.5] {
+            let mut node = GpuBlurNode::new(GpuBlurNodeConfig { sigma }, None);
+            let (input, output) = (node.input.clone(), node.output.clone());
+            let actual = run(&ctx, &mut node, input, output, &src);
+            assert_close(&actual, &blur_pass(&blur_pass(&src, sigma, true), sigma, false));
+        }
+    }
+
+    #[test]
+    fn color_ops_match_cpu() {
+        let Some(ctx) = context() else { return };
+        let src = pattern(5, 4);
+        let reference = |op: GpuColorOp| {
+            RgbaImage::from_fn(src.width(), src.height(), |x, y| {
+                let [r, g, b, a] = src.get_pixel(x, y).0;
+                match op {
+                    GpuColorOp::Grayscale => {
+                        let l = unorm((0.2126 * r as f32 + 0.7152 * g as f32 + 0.0722 * b as f32) / 255.0);
+                        Rgba([l, l, l, a])
+                    }
+                    GpuColorOp::SwapRedBlue => Rgba([b, g, r, a]),
+                    GpuColorOp::Invert => Rgba([255 - r, 255 - g, 255 - b, a]),
+                }
+            })
+        };
+        for op in [GpuColorOp::Grayscale, GpuColorOp::SwapRedBlue, GpuColorOp::Invert] {
+            let mut node = GpuColorConvertNode::new(GpuColorConvertNodeConfig { op }, None);
+            let (input, output) = (node.input.clone(), node.output.clone());
+            let actual = run(&ctx, &mut node, input, output, &src);
+            assert_close(&actual, &reference(op));
+        }
+    }
+}
@@ -0,0 +1,32 @@
+#[cfg(test)]
+mod hashing {
+    use flowrs_img::hashing::{image_hash, HashKind};
+    use image::{DynamicImage, GrayImage, Luma};
+
+    fn scene(width: u32, height: u32, brightness: i32) -> DynamicImage {
+        DynamicImage::ImageLuma8(GrayImage::from_fn(width, height, |x, y| {
+            let (u, v) = (x as f32 / width as f32, y as f32 / height as f32);
+            let value = 128.0 + 60.0 * (5.3 * u + 2.1 * v).sin() + 40.0 * (7.0 * v - 3.7 * u).cos();
+            Luma([(value as i32 + brightness).clamp(0, 255) as u8])
+        }))
+    }
+
+    #[test]
+    fn near_duplicates_are_close() {
+        for kind in [HashKind::Average, HashKind::Difference, HashKind::Perceptual] {
+            let original = image_hash(&scene(320, 240, 0), kind);
+            let resized = image_hash(&scene(160, 120, 10), kind);
+            let other = image_hash(&scene(320, 240, 0).fliph(), kind);
+            let near = original.distance(&resized).unwrap();
+            let far = original.distance(&other).unwrap();
+            assert!(near <= 6, "{:?}: near {}", kind, near);
+            assert!(far > near + 10, "{:?}: near {} far {}", kind, near, far);
+        }
+    }
+
+    #[test]
+    fn different_kinds_are_not_comparable() {
+        let img = scene(64, 64, 0);
+        assert_eq!(image_hash(&img, HashKind::Average).distance(&image_hash(&img, HashKind::Perceptual)), None);
+    }
+}
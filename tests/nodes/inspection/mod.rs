@@ -0,0 +1,4 @@
+pub mod test_caliper;
+pub mod test_defects;
+pub mod test_pcb;
+pub mod test_barcode;
@@ -0,0 +1,40 @@
+#[cfg(test)]
+mod inspection {
+    use flowrs_img::inspection::{grade_barcode, Grade};
+    use image::{GrayImage, Luma};
+
+    /// Bars of varying width between 20 px quiet zones.
+    fn barcode(bar: u8, space: u8, smudge: bool) -> GrayImage {
+        let widths = [2u32, 4, 2, 2, 6, 2, 4, 4, 2, 6, 2, 2, 4, 2, 2];
+        let mut columns = vec![space; 20];
+        for (i, w) in widths.iter().enumerate() {
+            let v = if i % 2 == 0 { bar } else { space };
+            columns.extend(std::iter::repeat_n(v, *w as usize * 2));
+        }
+        columns.extend(std::iter::repeat_n(space, 20));
+        GrayImage::from_fn(columns.len() as u32, 40, |x, _| {
+            if smudge && (80..83).contains(&x) {
+                Luma([space / 2 + 20])
+            } else {
+                Luma([columns[x as usize]])
+            }
+        })
+    }
+
+    #[test]
+    fn crisp_print_grades_a() {
+        let report = grade_barcode(&barcode(10, 240, false), 10, 10);
+        assert_eq!(report.grade, Grade::A, "{:?}", report.scans[0]);
+        assert_eq!(report.scans[0].elements, 15);
+    }
+
+    #[test]
+    fn low_contrast_and_defects_lower_grade() {
+        let faded = grade_barcode(&barcode(110, 200, false), 10, 10);
+        assert!(faded.grade < Grade::B, "{:?}", faded.scans[0]);
+
+        let smudged = grade_barcode(&barcode(10, 240, true), 10, 10);
+        assert!(smudged.scans[0].defects > 0.15, "{:?}", smudged.scans[0]);
+        assert!(smudged.grade < Grade::A);
+    }
+}
@@ -0,0 +1,42 @@
+#[cfg(test)]
+mod inspection {
+    use flowrs_img::inspection::{measure_caliper, Caliper, EdgePolarity};
+    use image::{GrayImage, Luma};
+
+    /// A bright bar between `left` and `right` with area-sampled, anti-aliased edges.
+    fn bar(left: f64, right: f64) -> GrayImage {
+        GrayImage::from_fn(48, 16, |x, _| {
+            let (x0, x1) = (x as f64, x as f64 + 1.0);
+            let coverage = (x1.min(right) - x0.max(left)).clamp(0.0, 1.0);
+            Luma([(40.0 + 160.0 * coverage).round() as u8])
+        })
+    }
+
+    fn caliper(polarity: EdgePolarity) -> Caliper {
+        Caliper {
+            name: "width".into(),
+            start: (2.0, 8.0),
+            end: (45.0, 8.0),
+            half_width: 3,
+            polarity,
+            min_contrast: 10.0,
+        }
+    }
+
+    #[test]
+    fn measures_sub_pixel_width() {
+        let m = measure_caliper(&bar(10.3, 30.7), &caliper(EdgePolarity::Any), 0.5);
+        assert_eq!(m.edges.len(), 2);
+        let distance = m.distance.unwrap();
+        assert!((distance.value - 20.4 * 0.5).abs() < 0.1, "{}", distance.value);
+        assert!(distance.uncertainty.unwrap() < 0.05);
+    }
+
+    #[test]
+    fn polarity_filters_edges() {
+        let m = measure_caliper(&bar(10.3, 30.7), &caliper(EdgePolarity::Falling), 1.0);
+        assert_eq!(m.edges.len(), 1);
+        assert!(m.edges[0].strength < 0.0);
+        assert!(m.distance.is_none());
+    }
+}
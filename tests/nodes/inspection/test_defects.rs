@@ -0,0 +1,36 @@
+#[cfg(test)]
+mod inspection {
+    use flowrs_img::inspection::{golden_defect_mask, texture_defect_mask, TextureModel};
+    use image::{GrayImage, Luma};
+
+    fn stripes(scratch: bool) -> GrayImage {
+        GrayImage::from_fn(64, 64, |x, y| {
+            if scratch && (40..44).contains(&x) && (8..24).contains(&y) {
+                Luma([250])
+            } else {
+                Luma([if (x / 2) % 2 == 0 { 90 } else { 130 }])
+            }
+        })
+    }
+
+    #[test]
+    fn learned_texture_flags_scratch() {
+        let mut model = TextureModel::default();
+        model.learn(&stripes(false), 8);
+        model.learn(&GrayImage::from_fn(64, 64, |x, _| Luma([if (x / 2) % 2 == 0 { 92 } else { 128 }])), 8);
+
+        let clean = texture_defect_mask(&stripes(false), &model, 8, 4.0);
+        assert!(clean.as_raw().iter().all(|&v| v == 0));
+
+        let mask = texture_defect_mask(&stripes(true), &model, 8, 4.0);
+        assert_eq!(mask.get_pixel(41, 10)[0], 255);
+        assert_eq!(mask.get_pixel(8, 50)[0], 0);
+    }
+
+    #[test]
+    fn golden_difference_localizes_defect() {
+        let mask = golden_defect_mask(&stripes(true), &stripes(false), 40);
+        assert_eq!(mask.get_pixel(42, 16)[0], 255);
+        assert_eq!(mask.get_pixel(10, 50)[0], 0);
+    }
+}
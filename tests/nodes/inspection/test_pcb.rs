@@ -0,0 +1,51 @@
+#[cfg(test)]
+mod inspection {
+    use flowrs_img::inspection::{inspect_board, InspectionPlan};
+    use image::{GrayImage, Luma};
+
+    /// Board with a fiducial ring and a component whose polarity dot sits top-left, or bottom-right if `flipped`.
+    fn board(shift: (u32, u32), flipped: bool) -> GrayImage {
+        GrayImage::from_fn(96, 96, |x, y| {
+            let (x, y) = (x.wrapping_sub(shift.0), y.wrapping_sub(shift.1));
+            let ring = (x as f32 - 16.0).hypot(y as f32 - 16.0);
+            let dot = if flipped { (64, 64) } else { (48, 48) };
+            let v = if (5.0..8.0).contains(&ring) {
+                230
+            } else if x.abs_diff(dot.0) < 3 && y.abs_diff(dot.1) < 3 {
+                250
+            } else if (44..68).contains(&x) && (44..68).contains(&y) {
+                30
+            } else {
+                110
+            };
+            Luma([v])
+        })
+    }
+
+    fn plan() -> InspectionPlan {
+        serde_json::from_value(serde_json::json!({
+            "golden_image": "golden.png",
+            "fiducials": [{ "name": "F1", "region": { "x": 6, "y": 6, "width": 21, "height": 21 }, "search_radius": 6, "min_score": 0.8 }],
+            "checks": [
+                { "type": "Presence", "name": "U1", "region": { "x": 36, "y": 36, "width": 40, "height": 40 }, "min_score": 0.5 },
+                { "type": "Polarity", "name": "U1-pol", "region": { "x": 44, "y": 44, "width": 24, "height": 24 }, "min_margin": 0.2 }
+            ]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn aligned_board_passes() {
+        let report = inspect_board(&board((3, 2), false), &board((0, 0), false), &plan());
+        assert_eq!(report.offset, (3, 2));
+        assert!(report.passed, "{:?}", report);
+    }
+
+    #[test]
+    fn reversed_component_fails_polarity() {
+        let report = inspect_board(&board((3, 2), true), &board((0, 0), false), &plan());
+        assert!(!report.passed);
+        assert!(report.checks[0].passed, "{:?}", report);
+        assert!(!report.checks[1].passed);
+    }
+}
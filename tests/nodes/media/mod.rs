@@ -0,0 +1,3 @@
+pub mod test_seam_carve;
+pub mod test_smart_crop;
+pub mod test_thumbnail;
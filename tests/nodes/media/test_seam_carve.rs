@@ -0,0 +1,45 @@
+#[cfg(test)]
+mod media {
+    #[cfg(feature = "seam-carving")]
+    use flowrs_img::media::seam_carve;
+    #[cfg(feature = "seam-carving")]
+    use image::{DynamicImage, GenericImageView, ImageBuffer, Rgb};
+
+    /// A sharp-edged square on a flat background, which seams should route around.
+    #[cfg(feature = "seam-carving")]
+    fn square() -> DynamicImage {
+        DynamicImage::ImageRgb8(ImageBuffer::from_fn(80, 40, |x, y| {
+            if (10..30).contains(&x) && (10..30).contains(&y) { Rgb([200, 20, 20]) } else { Rgb([40, 40, 40]) }
+        }))
+    }
+
+    #[cfg(feature = "seam-carving")]
+    fn square_width(img: &DynamicImage) -> usize {
+        (0..img.width()).filter(|&x| img.get_pixel(x, 20)[0] > 150).count()
+    }
+
+    #[cfg(feature = "seam-carving")]
+    #[test]
+    fn narrowing_keeps_the_subject() {
+        let carved = seam_carve(&square(), 60, 40);
+        assert_eq!(carved.dimensions(), (60, 40));
+        assert_eq!(square_width(&carved), 20);
+    }
+
+    #[cfg(feature = "seam-carving")]
+    #[test]
+    fn widening_and_heightening_keep_the_subject() {
+        let carved = seam_carve(&square(), 100, 50);
+        assert_eq!(carved.dimensions(), (100, 50));
+        assert_eq!(square_width(&carved), 20);
+    }
+
+    #[cfg(feature = "seam-carving")]
+    #[test]
+    fn empty_images_are_returned_unchanged() {
+        for (w, h) in [(0, 0), (0, 10), (10, 0)] {
+            let empty = DynamicImage::ImageRgb8(ImageBuffer::new(w, h));
+            assert_eq!(seam_carve(&empty, 20, 20), empty);
+        }
+    }
+}
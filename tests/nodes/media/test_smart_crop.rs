@@ -0,0 +1,28 @@
+#[cfg(test)]
+mod media {
+    use flowrs_img::media::{salient_crop, Saliency};
+    use flowrs_img::types::Rect;
+    use image::{DynamicImage, ImageBuffer, Rgb};
+
+    /// Checkered detail on the right third of an otherwise black landscape frame.
+    fn detail_right() -> DynamicImage {
+        DynamicImage::ImageRgb8(ImageBuffer::from_fn(300, 100, |x, y| {
+            if x > 200 && (x / 5 + y / 5) % 2 == 0 { Rgb([255, 255, 255]) } else { Rgb([0, 0, 0]) }
+        }))
+    }
+
+    #[test]
+    fn both_saliency_modes_find_the_detail() {
+        for saliency in [Saliency::Edges, Saliency::Entropy] {
+            let r = salient_crop(&detail_right(), 1.0, saliency, &[], 0.0);
+            assert!(r.x >= 180, "{:?}: {:?}", saliency, r);
+        }
+    }
+
+    #[test]
+    fn regions_outweigh_detail() {
+        let face = Rect::new(10, 30, 40, 40);
+        let r = salient_crop(&detail_right(), 1.0, Saliency::Edges, &[face], 50.0);
+        assert!(r.x <= face.x && r.x + r.width >= face.x + face.width, "{:?}", r);
+    }
+}
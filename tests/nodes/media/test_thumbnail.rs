@@ -0,0 +1,55 @@
+#[cfg(test)]
+mod media {
+    use flowrs_img::media::{apply_orientation, exif_orientation, make_thumbnails, smart_crop, ThumbnailFit, ThumbnailNodeConfig, ThumbnailSize};
+    use flowrs_img::transform::{decode_image, encode_image, EncodeFormat};
+    use image::{DynamicImage, GenericImageView, ImageBuffer, Rgb};
+
+    /// A JPEG with a little-endian EXIF block holding only the orientation tag.
+    fn jpeg_with_orientation(img: &DynamicImage, orientation: u8) -> Vec<u8> {
+        let jpeg = encode_image(img, EncodeFormat::Jpeg { quality: 90 }).unwrap();
+        let mut exif = b"Exif\0\0II*\0\x08\0\0\0\x01\0\x12\x01\x03\0\x01\0\0\0".to_vec();
+        exif.extend_from_slice(&[orientation, 0, 0, 0, 0, 0, 0, 0]);
+        let mut data = vec![0xff, 0xd8, 0xff, 0xe1];
+        data.extend_from_slice(&((exif.len() + 2) as u16).to_be_bytes());
+        data.extend_from_slice(&exif);
+        data.extend_from_slice(&jpeg[2..]);
+        data
+    }
+
+    #[test]
+    fn orientation_is_read_and_applied() {
+        let img = DynamicImage::new_rgb8(40, 20);
+        let data = jpeg_with_orientation(&img, 6);
+        assert_eq!(exif_orientation(&data), Some(6));
+        assert_eq!(exif_orientation(&encode_image(&img, EncodeFormat::Jpeg { quality: 90 }).unwrap()), None);
+        assert_eq!(apply_orientation(img, 6).dimensions(), (20, 40));
+    }
+
+    #[test]
+    fn smart_crop_keeps_the_detailed_side() {
+        let img = DynamicImage::ImageRgb8(ImageBuffer::from_fn(300, 100, |x, y| {
+            if x > 200 && (x / 5 + y / 5) % 2 == 0 { Rgb([255, 255, 255]) } else { Rgb([0, 0, 0]) }
+        }));
+        let r = smart_crop(&img, 1.0);
+        assert_eq!((r.width, r.height), (100, 100));
+        assert!(r.x >= 180, "{:?}", r);
+        assert_eq!(smart_crop(&DynamicImage::new_rgb8(300, 100), 1.0).x, 100);
+    }
+
+    #[test]
+    fn thumbnails_of_every_size() {
+        let data = jpeg_with_orientation(&DynamicImage::new_rgb8(400, 200), 6);
+        let config = ThumbnailNodeConfig {
+            sizes: vec![ThumbnailSize { width: 50, height: 50 }, ThumbnailSize { width: 1000, height: 1000 }],
+            fit: ThumbnailFit::Contain,
+            format: EncodeFormat::Png,
+        };
+        let thumbs = make_thumbnails(data.clone(), &config).unwrap();
+        let dims: Vec<_> = thumbs.into_iter().map(|t| decode_image(t.data).unwrap().dimensions()).collect();
+        assert_eq!(dims, vec![(25, 50), (200, 400)]);
+
+        let config = ThumbnailNodeConfig { fit: ThumbnailFit::SmartCrop, sizes: vec![ThumbnailSize { width: 64, height: 32 }], ..config };
+        let thumbs = make_thumbnails(data, &config).unwrap();
+        assert_eq!(decode_image(thumbs[0].data.clone()).unwrap().dimensions(), (64, 32));
+    }
+}
@@ -0,0 +1,50 @@
+#[cfg(test)]
+mod ml {
+    use flowrs::node::Node;
+    use flowrs_img::config::Validate;
+    use flowrs_img::ml::{preprocess, softmax, ClassifierConfig, ContentModerationNode};
+    use image::{DynamicImage, Rgb, RgbImage};
+
+    #[test]
+    fn config_is_validated() {
+        assert!(ClassifierConfig::default().validate().is_ok());
+        let config = ClassifierConfig { input_height: 0, ..Default::default() };
+        assert_eq!(config.validate().unwrap_err().field, "input_width");
+        let config = ClassifierConfig { std: [0.5, 0.0, 0.5], ..Default::default() };
+        assert_eq!(config.validate().unwrap_err().field, "std");
+    }
+
+    #[test]
+    fn softmax_is_a_stable_distribution() {
+        let mut scores = [1.0, 2.0, 3.0];
+        softmax(&mut scores);
+        assert!((scores.iter().sum::<f32>() - 1.0).abs() < 1e-6);
+        assert!(scores[0] < scores[1] && scores[1] < scores[2]);
+        assert!((scores[2] - 0.665_241).abs() < 1e-5);
+
+        // Shifting every score changes nothing, and huge scores do not overflow.
+        let mut shifted = [1001.0, 1002.0, 1003.0];
+        softmax(&mut shifted);
+        assert!(shifted.iter().zip(scores).all(|(a, b)| (a - b).abs() < 1e-6));
+    }
+
+    #[test]
+    fn input_is_resized_and_normalized() {
+        let img = DynamicImage::ImageRgb8(RgbImage::from_pixel(8, 4, Rgb([255, 0, 51])));
+        let config = ClassifierConfig { input_width: 4, input_height: 2, mean: [0.5; 3], std: [0.5; 3], ..Default::default() };
+        let tensor = preprocess(&img, &config);
+        assert_eq!(tensor.shape(), [1, 3, 2, 4]);
+        let values = tensor.to_array_view::<f32>().unwrap();
+        assert!((values[[0, 0, 1, 3]] - 1.0).abs() < 1e-6);
+        assert!((values[[0, 1, 0, 0]] + 1.0).abs() < 1e-6);
+        assert!((values[[0, 2, 0, 2]] + 0.6).abs() < 1e-6);
+    }
+
+    #[test]
+    fn missing_model_fails_the_update() {
+        let config = ClassifierConfig { model_path: "/nonexistent/model.onnx".into(), ..Default::default() };
+        let mut node = ContentModerationNode::new(config, None);
+        node.input.send(DynamicImage::new_rgb8(4, 4)).unwrap();
+        assert!(node.on_update().is_err());
+    }
+}
@@ -1,2 +1,35 @@
+pub mod analysis;
+pub mod color;
+pub mod config;
+pub mod control;
+pub mod debug;
+pub mod depth;
+pub mod document;
+pub mod expr;
+pub mod features;
+pub mod filter;
+pub mod flow;
+pub mod forensics;
+#[cfg(feature = "gpu")]
+pub mod gpu;
+pub mod hashing;
+pub mod inspection;
+pub mod media;
+#[cfg(feature = "onnx")]
+pub mod ml;
+pub mod net;
+#[cfg(feature = "ocr")]
+pub mod ocr;
+pub mod overlay;
+pub mod registry;
+pub mod replay;
+pub mod sequence;
+pub mod shape;
+pub mod source;
+pub mod storage;
+pub mod testing;
+pub mod tracking;
 pub mod transform;
+pub mod transport;
+pub mod video;
 
@@ -0,0 +1,7 @@
+pub mod test_backoff;
+pub mod test_http;
+pub mod test_image_source;
+pub mod test_key_template;
+pub mod test_mqtt;
+pub mod test_rate_limit;
+pub mod test_websocket;
@@ -0,0 +1,30 @@
+#[cfg(test)]
+mod net {
+    use std::time::Duration;
+
+    use flowrs_img::net::backoff_delay;
+
+    const BASE: Duration = Duration::from_millis(100);
+    const MAX: Duration = Duration::from_secs(2);
+
+    #[test]
+    fn delay_doubles_per_attempt() {
+        let delays: Vec<u128> = (1..=4).map(|attempt| backoff_delay(BASE, MAX, attempt).as_millis()).collect();
+        assert_eq!(delays, [100, 200, 400, 800]);
+        assert_eq!(backoff_delay(BASE, MAX, 0), BASE);
+    }
+
+    #[test]
+    fn delay_is_capped() {
+        assert_eq!(backoff_delay(BASE, MAX, 5), Duration::from_millis(1600));
+        assert_eq!(backoff_delay(BASE, MAX, 6), MAX);
+        assert_eq!(backoff_delay(BASE, MAX, 20), MAX);
+    }
+
+    #[test]
+    fn large_attempts_do_not_overflow() {
+        assert_eq!(backoff_delay(BASE, MAX, u32::MAX), MAX);
+        assert_eq!(backoff_delay(Duration::MAX, Duration::MAX, u32::MAX), Duration::MAX);
+        assert_eq!(backoff_delay(Duration::from_secs(u64::MAX / 2), Duration::MAX, 3), Duration::MAX);
+    }
+}
@@ -0,0 +1,13 @@
+#[cfg(test)]
+#[cfg(feature = "http")]
+mod net {
+    use flowrs_img::config::Validate;
+    use flowrs_img::net::HttpPostNodeConfig;
+
+    #[test]
+    fn post_backoff_must_fit_its_cap() {
+        assert!(HttpPostNodeConfig::default().validate().is_ok());
+        let config = HttpPostNodeConfig { backoff_ms: 20_000, max_backoff_ms: 10_000, ..Default::default() };
+        assert_eq!(config.validate().unwrap_err().field, "max_backoff_ms");
+    }
+}
@@ -0,0 +1,92 @@
+#[cfg(test)]
+#[cfg(feature = "http")]
+mod net {
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::sync::mpsc::{self, Receiver};
+    use std::time::{Duration, Instant};
+
+    use flowrs::connection::{connect, Input};
+    use flowrs::node::Node;
+    use flowrs_img::config::Validate;
+    use flowrs_img::net::{HttpImageSourceNode, HttpImageSourceNodeConfig};
+    use flowrs_img::transform::{encode_image, EncodeFormat};
+    use image::DynamicImage;
+
+    /// Answers every request with `status` and `body`, passing on the request heads.
+    fn serve(status: u16, body: Vec<u8>) -> (String, Receiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/snapshot.jpg", listener.local_addr().unwrap());
+        let (tx, requests) = mpsc::channel();
+        std::thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                let mut head = Vec::new();
+                let mut byte = [0; 1];
+                while !head.ends_with(b"\r\n\r\n") && stream.read(&mut byte).unwrap_or(0) == 1 {
+                    head.push(byte[0]);
+                }
+                let _ = tx.send(String::from_utf8_lossy(&head).into_owned());
+                let _ = write!(stream, "HTTP/1.1 {} X\r\nContent-Length: {}\r\nConnection: close\r\n\r\n", status, body.len());
+                let _ = stream.write_all(&body);
+            }
+        });
+        (url, requests)
+    }
+
+    /// Updates the node until it has sent something on `output`.
+    fn next<T: Clone + Send + 'static>(node: &mut HttpImageSourceNode, output: &Input<T>) -> T {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while Instant::now() < deadline {
+            node.on_update().unwrap();
+            if let Ok(item) = output.next() {
+                return item;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        panic!("node sent nothing");
+    }
+
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        encode_image(&DynamicImage::new_rgb8(width, height), EncodeFormat::Png).unwrap()
+    }
+
+    #[test]
+    fn config_is_validated() {
+        assert!(HttpImageSourceNodeConfig::default().validate().is_ok());
+        let config = HttpImageSourceNodeConfig { timeout_ms: 0, ..Default::default() };
+        assert_eq!(config.validate().unwrap_err().field, "timeout_ms");
+        let config = HttpImageSourceNodeConfig { max_bytes: 0, ..Default::default() };
+        assert_eq!(config.validate().unwrap_err().field, "max_bytes");
+    }
+
+    #[test]
+    fn snapshots_are_decoded_with_headers_sent() {
+        let (url, requests) = serve(200, png(5, 3));
+        let headers = vec![("Authorization".to_string(), "Basic dXNlcjpwYXNz".to_string())];
+        let mut node = HttpImageSourceNode::new(HttpImageSourceNodeConfig { url, headers, ..Default::default() }, None);
+        let frames = Input::new();
+        connect(node.output.clone(), frames.clone());
+
+        let frame = next(&mut node, &frames);
+        assert_eq!((frame.width(), frame.height()), (5, 3));
+        let head = requests.recv().unwrap().to_ascii_lowercase();
+        assert!(head.starts_with("get /snapshot.jpg"));
+        assert!(head.contains("authorization: basic dxnlcjpwyxnz"));
+    }
+
+    #[test]
+    fn failed_fetches_are_reported() {
+        let (url, _requests) = serve(404, Vec::new());
+        let mut node = HttpImageSourceNode::new(HttpImageSourceNodeConfig { url, ..Default::default() }, None);
+        let errors = Input::new();
+        connect(node.errors.clone(), errors.clone());
+        assert!(next(&mut node, &errors).contains("404"));
+
+        let (url, _requests) = serve(200, png(64, 64));
+        let config = HttpImageSourceNodeConfig { url, max_bytes: 16, ..Default::default() };
+        let mut node = HttpImageSourceNode::new(config, None);
+        let errors = Input::new();
+        connect(node.errors.clone(), errors.clone());
+        assert!(next(&mut node, &errors).contains("larger than 16 bytes"));
+    }
+}
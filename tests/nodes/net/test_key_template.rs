@@ -0,0 +1,47 @@
+#[cfg(test)]
+mod net {
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    use flowrs_img::net::render_key_template;
+
+    fn at(secs: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(secs)
+    }
+
+    fn date(secs: u64) -> String {
+        render_key_template("{date}", 0, at(secs), "jpg")
+    }
+
+    #[test]
+    fn placeholders_are_expanded() {
+        let key = render_key_template("cam/{date}/{timestamp}-{seq}.{ext}", 42, at(1_703_980_800) + Duration::from_millis(5), "png");
+        assert_eq!(key, "cam/2023-12-31/1703980800005-42.png");
+        assert_eq!(render_key_template("{seq}-{seq}", 7, UNIX_EPOCH, "jpg"), "7-7");
+        assert_eq!(render_key_template("fixed", 7, UNIX_EPOCH, "jpg"), "fixed");
+    }
+
+    #[test]
+    fn dates_follow_leap_years() {
+        assert_eq!(date(0), "1970-01-01");
+        assert_eq!(date(951_782_400), "2000-02-29");
+        assert_eq!(date(1_709_164_800), "2024-02-29");
+        assert_eq!(date(1_709_251_200 - 1), "2024-02-29");
+        assert_eq!(date(1_709_251_200), "2024-03-01");
+        // 2100 is divisible by 100 but not 400, so it has no February 29.
+        assert_eq!(date(4_107_456_000), "2100-02-28");
+        assert_eq!(date(4_107_542_400), "2100-03-01");
+    }
+
+    #[cfg(feature = "s3")]
+    #[test]
+    fn upload_retries_are_validated() {
+        use flowrs_img::config::Validate;
+        use flowrs_img::net::ObjectStoreUploadNodeConfig;
+
+        assert!(ObjectStoreUploadNodeConfig::default().validate().is_ok());
+        let config = ObjectStoreUploadNodeConfig { max_attempts: 0, ..Default::default() };
+        assert_eq!(config.validate().unwrap_err().field, "max_attempts");
+        let config = ObjectStoreUploadNodeConfig { backoff_ms: 20_000, ..Default::default() };
+        assert_eq!(config.validate().unwrap_err().field, "max_backoff_ms");
+    }
+}
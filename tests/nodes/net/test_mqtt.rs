@@ -0,0 +1,23 @@
+#[cfg(test)]
+#[cfg(feature = "mqtt")]
+mod net {
+    use flowrs_img::config::Validate;
+    use flowrs_img::net::{mqtt_client_id, MqttNodeConfig};
+
+    #[test]
+    fn generated_client_ids_are_unique() {
+        let config = MqttNodeConfig::default();
+        let (a, b) = (mqtt_client_id(&config), mqtt_client_id(&config));
+        assert_ne!(a, b);
+        assert!(a.starts_with("flowrs-img-"));
+
+        let named = MqttNodeConfig { client_id: "camera-1".into(), ..Default::default() };
+        assert_eq!(mqtt_client_id(&named), "camera-1");
+    }
+
+    #[test]
+    fn packets_fit_real_frames() {
+        assert!(MqttNodeConfig::default().max_packet_size >= 1024 * 1024);
+        assert!(MqttNodeConfig { max_packet_size: 0, ..Default::default() }.validate().is_err());
+    }
+}
@@ -0,0 +1,26 @@
+#[cfg(test)]
+mod net {
+    use std::time::Duration;
+
+    use flowrs_img::net::RateLimiter;
+
+    #[test]
+    fn frames_pass_at_most_at_the_rate() {
+        let mut limiter = RateLimiter::default();
+        assert!(limiter.allow(Some(20.0)));
+        assert!(!limiter.ready(Some(20.0)));
+        assert!(!limiter.allow(Some(20.0)));
+
+        std::thread::sleep(Duration::from_millis(60));
+        assert!(limiter.ready(Some(20.0)));
+        // Checking does not consume the slot.
+        assert!(limiter.allow(Some(20.0)));
+        assert!(!limiter.allow(Some(20.0)));
+    }
+
+    #[test]
+    fn missing_rate_is_unlimited() {
+        let mut limiter = RateLimiter::default();
+        assert!((0..5).all(|_| limiter.allow(None)));
+    }
+}
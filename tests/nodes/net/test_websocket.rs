@@ -0,0 +1,60 @@
+#[cfg(test)]
+#[cfg(feature = "websocket")]
+mod net {
+    use std::net::TcpListener;
+    use std::time::Duration;
+
+    use base64::Engine;
+    use flowrs::node::Node;
+    use flowrs_img::config::Validate;
+    use flowrs_img::net::{WebSocketEncoding, WebSocketImageNode, WebSocketImageNodeConfig};
+    use flowrs_img::transform::decode_image;
+    use image::DynamicImage;
+    use tungstenite::stream::MaybeTlsStream;
+    use tungstenite::Message;
+
+    #[test]
+    fn config_is_validated() {
+        assert!(WebSocketImageNodeConfig::default().validate().is_ok());
+        let config = WebSocketImageNodeConfig { jpeg_quality: 0, ..Default::default() };
+        assert_eq!(config.validate().unwrap_err().field, "jpeg_quality");
+        let config = WebSocketImageNodeConfig { max_rate_hz: Some(0.0), ..Default::default() };
+        assert_eq!(config.validate().unwrap_err().field, "max_rate_hz");
+    }
+
+    /// Sends frames through a node until the connected client receives one.
+    fn first_message(encoding: WebSocketEncoding) -> Message {
+        let address = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().to_string();
+        let config = WebSocketImageNodeConfig { address: address.clone(), encoding, ..Default::default() };
+        let mut node = WebSocketImageNode::new(config, None);
+        node.on_update().unwrap();
+
+        let (mut client, _) = tungstenite::connect(format!("ws://{}", address)).unwrap();
+        if let MaybeTlsStream::Plain(stream) = client.get_mut() {
+            stream.set_read_timeout(Some(Duration::from_millis(50))).unwrap();
+        }
+        // The node picks up the client on a later update than the handshake.
+        for _ in 0..100 {
+            node.input.send(DynamicImage::new_rgb8(8, 6)).unwrap();
+            node.on_update().unwrap();
+            if let Ok(message) = client.read() {
+                return message;
+            }
+        }
+        panic!("no frame received");
+    }
+
+    #[test]
+    fn clients_receive_binary_jpeg() {
+        let Message::Binary(jpeg) = first_message(WebSocketEncoding::Binary) else { panic!("expected a binary message") };
+        let img = decode_image(jpeg).unwrap();
+        assert_eq!((img.width(), img.height()), (8, 6));
+    }
+
+    #[test]
+    fn clients_receive_base64_jpeg() {
+        let Message::Text(text) = first_message(WebSocketEncoding::Base64) else { panic!("expected a text message") };
+        let jpeg = base64::engine::general_purpose::STANDARD.decode(text).unwrap();
+        assert_eq!(decode_image(jpeg).unwrap().width(), 8);
+    }
+}
@@ -0,0 +1,40 @@
+#[cfg(test)]
+mod ocr {
+    use flowrs_img::config::Validate;
+    use flowrs_img::ocr::{parse_tesseract_tsv, OcrGranularity, OcrNodeConfig};
+    use flowrs_img::types::Rect;
+
+    const TSV: &str = "level\tpage_num\tblock_num\tpar_num\tline_num\tword_num\tleft\ttop\twidth\theight\tconf\ttext
+1\t1\t0\t0\t0\t0\t0\t0\t200\t60\t-1\t
+4\t1\t1\t1\t1\t0\t10\t5\t120\t20\t-1\t
+5\t1\t1\t1\t1\t1\t10\t5\t50\t20\t96.5\tHELLO
+5\t1\t1\t1\t1\t2\t70\t6\t60\t19\t88\tWORLD
+5\t1\t1\t1\t2\t1\t12\t35\t40\t18\t91\tAB-123
+5\t1\t1\t1\t2\t2\t60\t35\t5\t18\t95\t ";
+
+    #[test]
+    fn words_skip_layout_rows_and_blanks() {
+        let words = parse_tesseract_tsv(TSV, OcrGranularity::Word);
+        let texts: Vec<&str> = words.iter().map(|w| w.text.as_str()).collect();
+        assert_eq!(texts, ["HELLO", "WORLD", "AB-123"]);
+        assert_eq!(words[0].rect, Rect::new(10, 5, 50, 20));
+        assert!((words[1].confidence - 0.88).abs() < 1e-6);
+    }
+
+    #[test]
+    fn lines_merge_boxes_and_keep_lowest_confidence() {
+        let lines = parse_tesseract_tsv(TSV, OcrGranularity::Line);
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].text, "HELLO WORLD");
+        assert_eq!(lines[0].rect, Rect::new(10, 5, 120, 20));
+        assert!((lines[0].confidence - 0.88).abs() < 1e-6);
+        assert_eq!(lines[1].text, "AB-123");
+    }
+
+    #[test]
+    fn min_confidence_uses_the_region_scale() {
+        let config = |min_confidence| OcrNodeConfig { min_confidence, ..Default::default() };
+        assert!(config(0.9).validate().is_ok());
+        assert!(config(90.0).validate().is_err());
+    }
+}
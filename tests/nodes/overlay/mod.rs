@@ -0,0 +1,3 @@
+pub mod test_chroma_key;
+pub mod test_scale_bar;
+pub mod test_watermark;
@@ -0,0 +1,32 @@
+#[cfg(test)]
+mod overlay {
+    use flowrs_img::overlay::{chroma_key, ChromaKeyNodeConfig};
+    use image::{Rgba, RgbaImage};
+
+    fn config() -> ChromaKeyNodeConfig {
+        ChromaKeyNodeConfig { key: [0, 177, 64], tolerance: 0.1, softness: 0.05, spill_suppression: 1.0 }
+    }
+
+    /// Left half green screen, right half a skin tone with a green cast.
+    fn scene() -> RgbaImage {
+        RgbaImage::from_fn(8, 2, |x, _| if x < 4 { Rgba([10, 180, 60, 255]) } else { Rgba([200, 210, 120, 255]) })
+    }
+
+    #[test]
+    fn backdrop_becomes_transparent() {
+        let keyed = chroma_key(&scene(), None, &config());
+        assert_eq!(keyed.get_pixel(0, 0)[3], 0);
+        let fg = keyed.get_pixel(7, 0);
+        assert_eq!(fg[3], 255);
+        // Spill suppression caps green at the red level.
+        assert_eq!(fg.0, [200, 200, 120, 255]);
+    }
+
+    #[test]
+    fn background_shows_through() {
+        let bg = RgbaImage::from_pixel(8, 2, Rgba([0, 0, 255, 255]));
+        let keyed = chroma_key(&scene(), Some(&bg), &config());
+        assert_eq!(keyed.get_pixel(1, 1).0, [0, 0, 255, 255]);
+        assert_eq!(keyed.get_pixel(6, 1).0, [200, 200, 120, 255]);
+    }
+}
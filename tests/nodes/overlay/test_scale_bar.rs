@@ -0,0 +1,80 @@
+#[cfg(test)]
+mod overlay {
+    use flowrs_img::overlay::{draw_scale_bar, PhysicalMeasurement, ScaleBarNodeConfig};
+    use flowrs_img::types::{Anchor, ObjectMeasurement, Rect};
+    use image::{Rgba, RgbaImage};
+
+    const BLACK: Rgba<u8> = Rgba([0, 0, 0, 255]);
+    const WHITE: Rgba<u8> = Rgba([255; 4]);
+
+    #[test]
+    fn bar_is_calibrated_and_padded() {
+        let mut img = RgbaImage::from_pixel(20, 10, BLACK);
+        let config = ScaleBarNodeConfig {
+            um_per_pixel: 0.5,
+            bar_length_um: 4.0,
+            bar_thickness: 2,
+            anchor: Anchor::TopLeft,
+            margin: 1,
+            background: Some([0, 0, 255, 255]),
+            ..Default::default()
+        };
+        draw_scale_bar(&mut img, &config);
+
+        // 8 px long, 2 px thick, inside a 2 px backing box starting at the margin.
+        assert_eq!(*img.get_pixel(3, 3), WHITE);
+        assert_eq!(*img.get_pixel(10, 4), WHITE);
+        assert_eq!(img.get_pixel(11, 4).0, [0, 0, 255, 255]);
+        assert_eq!(img.get_pixel(2, 3).0, [0, 0, 255, 255]);
+        assert_eq!(img.get_pixel(1, 1).0, [0, 0, 255, 255]);
+        assert_eq!(*img.get_pixel(0, 0), BLACK);
+        assert_eq!(*img.get_pixel(13, 3), BLACK);
+        assert_eq!(*img.get_pixel(3, 7), BLACK);
+    }
+
+    #[test]
+    fn oversized_bar_is_clamped_to_the_image() {
+        let mut img = RgbaImage::from_pixel(20, 10, BLACK);
+        let config = ScaleBarNodeConfig { bar_length_um: 1e12, margin: 0, ..Default::default() };
+        draw_scale_bar(&mut img, &config);
+        // Clamped to the image width, then anchored bottom-right past the padding.
+        assert_eq!(*img.get_pixel(0, 2), WHITE);
+        assert_eq!(*img.get_pixel(15, 5), WHITE);
+        assert_eq!(*img.get_pixel(16, 2), BLACK);
+        assert_eq!(*img.get_pixel(0, 6), BLACK);
+
+        let extreme = ScaleBarNodeConfig {
+            bar_length_um: f64::MAX,
+            bar_thickness: u32::MAX,
+            margin: u32::MAX,
+            background: Some([0; 4]),
+            ..Default::default()
+        };
+        draw_scale_bar(&mut img, &extreme);
+        draw_scale_bar(&mut img, &ScaleBarNodeConfig { anchor: Anchor::TopLeft, ..extreme.clone() });
+        draw_scale_bar(&mut RgbaImage::new(0, 0), &extreme);
+    }
+
+    #[test]
+    fn measurements_convert_to_micrometers() {
+        let pixels = ObjectMeasurement {
+            id: 7,
+            bounds: Rect::new(4, 4, 10, 6),
+            area: 50.0,
+            perimeter: 30.0,
+            centroid: (9.0, 7.0),
+        };
+        let physical = PhysicalMeasurement::from_pixels(&pixels, 0.5);
+        assert_eq!(
+            physical,
+            PhysicalMeasurement {
+                id: 7,
+                area_um2: 12.5,
+                perimeter_um: 15.0,
+                width_um: 5.0,
+                height_um: 3.0,
+                centroid_um: (4.5, 3.5),
+            }
+        );
+    }
+}
@@ -0,0 +1,38 @@
+#[cfg(test)]
+mod overlay {
+    use flowrs_img::overlay::{apply_watermark, WatermarkMode, WatermarkNodeConfig};
+    use flowrs_img::types::Anchor;
+    use image::{Rgba, RgbaImage};
+
+    fn config(mode: WatermarkMode) -> WatermarkNodeConfig {
+        WatermarkNodeConfig {
+            logo_path: "logo.png".into(),
+            mode,
+            anchor: Anchor::BottomRight,
+            margin: 1,
+            scale: None,
+            opacity: 0.5,
+        }
+    }
+
+    #[test]
+    fn single_logo_at_anchor() {
+        let mut img = RgbaImage::from_pixel(10, 10, Rgba([0, 0, 0, 255]));
+        let logo = RgbaImage::from_pixel(2, 2, Rgba([200, 200, 200, 255]));
+        apply_watermark(&mut img, &logo, &config(WatermarkMode::Single));
+        assert_eq!(img.get_pixel(7, 7).0, [100, 100, 100, 255]);
+        assert_eq!(img.get_pixel(9, 9).0, [0, 0, 0, 255]);
+        assert_eq!(img.get_pixel(6, 6).0, [0, 0, 0, 255]);
+    }
+
+    #[test]
+    fn tiles_repeat_with_spacing() {
+        let mut img = RgbaImage::from_pixel(10, 4, Rgba([0, 0, 0, 255]));
+        let logo = RgbaImage::from_pixel(2, 1, Rgba([200, 200, 200, 255]));
+        apply_watermark(&mut img, &logo, &config(WatermarkMode::Tiled { spacing: 1 }));
+        let row: Vec<u8> = (0..10).map(|x| img.get_pixel(x, 0)[0]).collect();
+        assert_eq!(row, vec![100, 100, 0, 100, 100, 0, 100, 100, 0, 100]);
+        assert_eq!(img.get_pixel(0, 1)[0], 0);
+        assert_eq!(img.get_pixel(0, 2)[0], 100);
+    }
+}
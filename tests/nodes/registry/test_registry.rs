@@ -0,0 +1,124 @@
+#[cfg(test)]
+mod registry {
+    use flowrs::connection::{connect, Input, Output};
+    use flowrs_img::registry::{connect_ports, NodeRegistry, NoConfig};
+    use flowrs_img::flow::TimestampNode;
+    use image::{DynamicImage, GrayImage, Luma};
+    use serde_json::{json, Value};
+
+    #[test]
+    fn builtin_nodes_from_json() {
+        let registry = NodeRegistry::builtin();
+        assert!(registry.contains("decode_image"));
+        let mut node = registry.create("quantize", json!({ "colors": 8 }), None).unwrap();
+        assert!(node.on_update().is_ok());
+        assert!(registry.create("alpha_split", Value::Null, None).is_ok());
+
+        let err = registry.create("quantize", json!({ "colors": 1 }), None).err().unwrap();
+        assert_eq!(err.to_string(), "Invalid config for 'quantize': Invalid colors: must be between 2 and 256");
+        assert!(registry.create("tracker", json!({ "max_age": "long" }), None).is_err());
+        assert_eq!(registry.create("webcam", Value::Null, None).err().unwrap().to_string(), "Unknown node type 'webcam'");
+    }
+
+    #[test]
+    fn custom_nodes() {
+        let mut registry = NodeRegistry::new();
+        registry.register("stamp", |_: NoConfig, co| TimestampNode::new(co));
+        assert_eq!(registry.names().collect::<Vec<_>>(), vec!["stamp"]);
+        assert!(registry.create("stamp", json!({}), None).is_ok());
+    }
+
+    #[test]
+    fn nodes_from_json_can_be_wired() {
+        let registry = NodeRegistry::builtin();
+        let mut invert = registry.create("pixel_expr", json!({ "expressions": ["255 - a.r"] }), None).unwrap();
+        let mut debug = registry.create("image_debug", json!({ "every_nth": 1000 }), None).unwrap();
+        connect_ports::<DynamicImage>(invert.as_ref(), 0, debug.as_ref(), 0).unwrap();
+        assert!(connect_ports::<u64>(invert.as_ref(), 0, debug.as_ref(), 0).is_err());
+
+        let mut out = Input::new();
+        connect((*debug.output_at(0).downcast::<Output<DynamicImage>>().unwrap()).clone(), out.clone());
+        let input = invert.input_at(0).downcast::<Input<DynamicImage>>().unwrap();
+        input.send(DynamicImage::ImageLuma8(GrayImage::from_pixel(2, 2, Luma([55])))).unwrap();
+        invert.on_update().unwrap();
+        debug.on_update().unwrap();
+        assert_eq!(out.next().unwrap().to_luma8().get_pixel(0, 0)[0], 200);
+    }
+
+    #[test]
+    fn every_node_is_registered() {
+        // Nodes added to the crate belong in `NodeRegistry::builtin` and in this list.
+        let mut expected = vec![
+            "alpha_merge", "alpha_split", "anonymization_audit", "assert_image", "auto_exposure",
+            "barcode_grade", "bounded_queue", "capture", "chroma_key", "color_calibration", "color_convert",
+            "colormap", "decode_image", "dedup", "deinterlace", "denoise", "depth_camera", "deskew",
+            "document_scan", "drop_old_frames", "edge_measure", "ela", "encode_image", "event_recorder",
+            "feature_extract", "feature_match", "flat_field", "fps_convert", "hash_distance", "hls_sink",
+            "hw_encode", "image_debug", "image_hash", "image_publisher", "image_subscriber", "inpaint",
+            "lane_merge", "lane_split", "lens_correction", "logo_detect", "loop", "lut", "packetize",
+            "particle_count", "pcb_inspection", "pixel_expr", "premultiply", "privacy_mask", "pyramid",
+            "quality_gate", "quantize", "replay", "replay_recorder", "resize", "retention", "scale_bar",
+            "scaled_decode", "smart_crop", "stitch", "surface_defect", "temporal_denoise", "test_pattern",
+            "thermal_anomaly", "throughput_probe", "thumbnail", "tile_merge", "tile_split", "timestamp",
+            "tracker", "unpack", "watchfolder_transcode", "watermark", "watermark_verify", "zone_analytics",
+        ];
+        if cfg!(feature = "preview") {
+            expected.push("preview_window");
+        }
+        if cfg!(feature = "clipboard") {
+            expected.extend(["clipboard_sink", "clipboard_source"]);
+        }
+        if cfg!(feature = "dicom") {
+            expected.push("dicom_decode");
+        }
+        if cfg!(feature = "gpu") {
+            expected.extend(["gpu_blur", "gpu_color_convert", "gpu_download", "gpu_resize", "gpu_upload", "gpu_warp"]);
+        }
+        if cfg!(feature = "seam-carving") {
+            expected.push("seam_carve");
+        }
+        if cfg!(feature = "onnx") {
+            expected.push("content_moderation");
+        }
+        if cfg!(feature = "mqtt") {
+            expected.extend(["mqtt_image_publisher", "mqtt_image_subscriber"]);
+        }
+        if cfg!(feature = "websocket") {
+            expected.push("websocket_image");
+        }
+        if cfg!(feature = "http") {
+            expected.extend(["http_image_source", "http_post"]);
+        }
+        if cfg!(feature = "s3") {
+            expected.push("object_store_upload");
+        }
+        if cfg!(feature = "ocr") {
+            expected.push("ocr");
+        }
+        if cfg!(feature = "picamera") {
+            expected.push("pi_camera");
+        }
+        if cfg!(feature = "otlp") {
+            expected.push("otlp_exporter");
+        }
+        if cfg!(feature = "shm") {
+            expected.extend(["shared_mem_reader", "shared_mem_writer"]);
+        }
+        expected.sort_unstable();
+        assert_eq!(NodeRegistry::builtin().names().collect::<Vec<_>>(), expected);
+    }
+
+    #[test]
+    fn resize_from_json() {
+        let registry = NodeRegistry::builtin();
+        let mut node = registry.create("resize", json!({ "width": 4, "height": 2, "keep_aspect": false }), None).unwrap();
+        let mut out = Input::new();
+        connect((*node.output_at(0).downcast::<Output<DynamicImage>>().unwrap()).clone(), out.clone());
+        let input = node.input_at(0).downcast::<Input<DynamicImage>>().unwrap();
+        input.send(DynamicImage::new_rgb8(10, 10)).unwrap();
+        node.on_update().unwrap();
+        let resized = out.next().unwrap();
+        assert_eq!((resized.width(), resized.height()), (4, 2));
+        assert!(registry.create("resize", json!({ "width": 0 }), None).is_err());
+    }
+}
@@ -0,0 +1,30 @@
+#[cfg(test)]
+mod replay {
+    use std::collections::BTreeMap;
+
+    use flowrs_img::replay::{ReplayBundle, ReplayWriter};
+    use image::{DynamicImage, ImageBuffer, Luma};
+
+    #[test]
+    fn bundle_round_trips() {
+        let dir = std::env::temp_dir().join(format!("flowrs-img-replay-{}", std::process::id()));
+        let mut configs = BTreeMap::new();
+        configs.insert("threshold".to_string(), serde_json::json!({ "level": 128 }));
+
+        let frames: Vec<DynamicImage> = (0..3u16)
+            .map(|i| DynamicImage::ImageLuma16(ImageBuffer::from_fn(8, 4, |x, y| Luma([i * 1000 + (x * y) as u16]))))
+            .collect();
+        let mut writer = ReplayWriter::create(&dir, &configs).unwrap();
+        for (i, img) in frames.iter().enumerate() {
+            writer.write(img, i as u64 * 40).unwrap();
+        }
+
+        let bundle = ReplayBundle::load(&dir).unwrap();
+        assert_eq!(bundle.configs, configs);
+        assert_eq!(bundle.frames.iter().map(|f| f.offset_ms).collect::<Vec<_>>(), [0, 40, 80]);
+        for (frame, img) in bundle.frames.iter().zip(&frames) {
+            assert_eq!(&bundle.read_frame(frame).unwrap(), img);
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,4 @@
+pub mod test_dedup;
+pub mod test_fps;
+pub mod test_loop;
+pub mod test_recorder;
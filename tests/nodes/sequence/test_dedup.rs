@@ -0,0 +1,39 @@
+#[cfg(test)]
+mod sequence {
+    use flowrs::connection::{connect, Input};
+    use flowrs::node::Node;
+    use flowrs_img::sequence::{DedupNode, DedupNodeConfig};
+    use image::{DynamicImage, GrayImage, Luma};
+
+    fn frame(value: u8) -> DynamicImage {
+        DynamicImage::ImageLuma8(GrayImage::from_pixel(64, 48, Luma([value])))
+    }
+
+    fn forwarded(threshold: f32, frames: impl IntoIterator<Item = DynamicImage>) -> Vec<u8> {
+        let mut node = DedupNode::new(DedupNodeConfig { threshold }, None);
+        let mut out = Input::new();
+        connect(node.output.clone(), out.clone());
+        for img in frames {
+            node.input.send(img).unwrap();
+            node.on_update().unwrap();
+        }
+        std::iter::from_fn(|| out.next().ok()).map(|f| f.to_luma8().get_pixel(0, 0)[0]).collect()
+    }
+
+    #[test]
+    fn near_duplicates_are_dropped_at_the_threshold() {
+        // Each step is below the threshold, but the drift from the last forwarded frame adds up.
+        let frames = [100, 102, 103, 104, 105, 107, 108, 108, 90].map(frame);
+        assert_eq!(forwarded(4.0, frames.clone()), vec![100, 104, 108, 90]);
+        assert_eq!(forwarded(0.0, frames.clone()).len(), frames.len());
+        assert_eq!(forwarded(255.0, frames), vec![100]);
+    }
+
+    #[test]
+    fn small_local_changes_count_as_duplicates() {
+        let mut speck = frame(100).into_luma8();
+        speck.put_pixel(10, 10, Luma([255]));
+        let frames = [frame(100), DynamicImage::ImageLuma8(speck), frame(140)];
+        assert_eq!(forwarded(1.0, frames), vec![100, 140]);
+    }
+}
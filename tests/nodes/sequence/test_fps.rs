@@ -0,0 +1,30 @@
+#[cfg(test)]
+mod sequence {
+    use flowrs_img::sequence::{blend_frames, output_positions};
+    use image::{DynamicImage, GrayImage, Luma};
+
+    #[test]
+    fn upsampling_interleaves_frames() {
+        let positions: Vec<Vec<f64>> = (0..3).map(|i| output_positions(i, 24.0, 60.0)).collect();
+        assert_eq!(positions[0], vec![1.0]);
+        assert_eq!(positions[1].len(), 2);
+        assert!((positions[1][0] - 0.4).abs() < 1e-9 && (positions[1][1] - 0.8).abs() < 1e-9);
+        assert_eq!(positions[2].len(), 3);
+        let total: usize = (0..24).map(|i| output_positions(i, 24.0, 60.0).len()).sum();
+        assert_eq!(total, 58);
+    }
+
+    #[test]
+    fn downsampling_drops_frames() {
+        let counts: Vec<usize> = (0..6).map(|i| output_positions(i, 30.0, 15.0).len()).collect();
+        assert_eq!(counts, vec![1, 0, 1, 0, 1, 0]);
+        assert_eq!(output_positions(4, 30.0, 30.0), vec![1.0]);
+    }
+
+    #[test]
+    fn blend_mixes_by_weight() {
+        let a = DynamicImage::ImageLuma8(GrayImage::from_pixel(2, 2, Luma([0])));
+        let b = DynamicImage::ImageLuma8(GrayImage::from_pixel(2, 2, Luma([200])));
+        assert_eq!(blend_frames(&a, &b, 0.25).to_luma8().get_pixel(0, 0)[0], 50);
+    }
+}
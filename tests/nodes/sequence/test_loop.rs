@@ -0,0 +1,23 @@
+#[cfg(test)]
+mod sequence {
+    use flowrs_img::sequence::{best_loop_point, boomerang};
+    use image::{DynamicImage, GrayImage, Luma};
+
+    fn frame(value: u8) -> DynamicImage {
+        DynamicImage::ImageLuma8(GrayImage::from_pixel(8, 8, Luma([value])))
+    }
+
+    #[test]
+    fn boomerang_skips_turning_frames() {
+        let frames = vec![frame(0), frame(1), frame(2), frame(3)];
+        let looped = boomerang(frames);
+        let values: Vec<u8> = looped.iter().map(|f| f.to_luma8().get_pixel(0, 0).0[0]).collect();
+        assert_eq!(values, vec![0, 1, 2, 3, 2, 1]);
+    }
+
+    #[test]
+    fn best_loop_point_finds_matching_frame() {
+        let frames = vec![frame(10), frame(80), frame(160), frame(12), frame(200)];
+        assert_eq!(best_loop_point(&frames, 2), Some(3));
+    }
+}
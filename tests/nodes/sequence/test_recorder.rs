@@ -0,0 +1,86 @@
+#[cfg(test)]
+mod sequence {
+    use std::thread::sleep;
+    use std::time::Duration;
+
+    use flowrs::connection::{connect, Input};
+    use flowrs::node::Node;
+    use flowrs_img::config::Validate;
+    use flowrs_img::sequence::{EventRecorderNode, EventRecorderNodeConfig};
+    use image::{DynamicImage, GrayImage, Luma};
+
+    fn frame(value: u8) -> DynamicImage {
+        DynamicImage::ImageLuma8(GrayImage::from_pixel(1, 1, Luma([value])))
+    }
+
+    fn values(clip: &[DynamicImage]) -> Vec<u8> {
+        clip.iter().map(|f| f.to_luma8().get_pixel(0, 0)[0]).collect()
+    }
+
+    #[test]
+    fn trigger_emits_pre_and_post_roll() {
+        let config = EventRecorderNodeConfig { pre_roll_secs: 60.0, post_roll_secs: 0.3, max_frames: 3 };
+        let mut node = EventRecorderNode::new(config, None);
+        let mut out = Input::new();
+        connect(node.output.clone(), out.clone());
+        let push = |node: &mut EventRecorderNode, value: u8| {
+            node.input.send(frame(value)).unwrap();
+            node.on_update().unwrap();
+        };
+
+        for value in 0..5 {
+            push(&mut node, value);
+        }
+        node.trigger.send(true).unwrap();
+        for value in 5..8 {
+            push(&mut node, value);
+        }
+        // Clips are split at `max_frames`.
+        assert_eq!(values(&out.next().unwrap()), vec![2, 3, 4]);
+        assert!(out.next().is_err());
+
+        // The first update after the post-roll closes the clip; its frame starts the next pre-roll.
+        sleep(Duration::from_millis(400));
+        push(&mut node, 8);
+        assert_eq!(values(&out.next().unwrap()), vec![5, 6, 7]);
+
+        node.trigger.send(true).unwrap();
+        push(&mut node, 9);
+        sleep(Duration::from_millis(400));
+        push(&mut node, 10);
+        assert_eq!(values(&out.next().unwrap()), vec![8, 9]);
+        assert!(out.next().is_err());
+    }
+
+    #[test]
+    fn clips_end_without_further_frames() {
+        let config = EventRecorderNodeConfig { pre_roll_secs: 60.0, post_roll_secs: 0.1, max_frames: 10 };
+        let mut node = EventRecorderNode::new(config, None);
+        let mut out = Input::new();
+        connect(node.output.clone(), out.clone());
+
+        node.input.send(frame(0)).unwrap();
+        node.on_update().unwrap();
+        node.trigger.send(true).unwrap();
+        node.input.send(frame(1)).unwrap();
+        node.on_update().unwrap();
+        sleep(Duration::from_millis(200));
+        node.on_update().unwrap();
+        assert_eq!(values(&out.next().unwrap()), vec![0, 1]);
+
+        node.trigger.send(true).unwrap();
+        node.input.send(frame(2)).unwrap();
+        node.on_update().unwrap();
+        node.on_shutdown().unwrap();
+        assert_eq!(values(&out.next().unwrap()), vec![2]);
+        assert!(out.next().is_err());
+    }
+
+    #[test]
+    fn rejects_non_finite_rolls() {
+        let config = EventRecorderNodeConfig { pre_roll_secs: f32::INFINITY, ..Default::default() };
+        assert_eq!(config.validate().unwrap_err().field, "pre_roll_secs");
+        let config = EventRecorderNodeConfig { post_roll_secs: f32::NAN, ..Default::default() };
+        assert_eq!(config.validate().unwrap_err().field, "post_roll_secs");
+    }
+}
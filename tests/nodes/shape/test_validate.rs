@@ -0,0 +1,50 @@
+#[cfg(test)]
+mod shape {
+    use flowrs_img::color::{ColorCalibrationNode, ColorCalibrationNodeConfig, ColorConvertNode, ColorConvertNodeConfig, ColorFormat};
+    use flowrs_img::shape::{ImageDescriptor, ShapeError, ShapeGraph};
+    use flowrs_img::source::{TestPattern, TestPatternNode, TestPatternNodeConfig};
+    use flowrs_img::transform::{TileSplitNode, TileSplitNodeConfig};
+
+    fn camera() -> TestPatternNode {
+        let config = TestPatternNodeConfig { pattern: TestPattern::ColorBars, width: 640, height: 480, fps: 30.0, frame_count: None };
+        TestPatternNode::new(config, None)
+    }
+
+    #[test]
+    fn descriptors_propagate() {
+        let camera = camera();
+        let tiles = TileSplitNode::new(TileSplitNodeConfig { tile_width: 256, tile_height: 256, overlap: 16 }, None);
+        let gray = ColorConvertNode::new(ColorConvertNodeConfig { target: ColorFormat::Luma8 }, None);
+
+        let mut graph = ShapeGraph::new();
+        let (c, t, g) = (graph.add("camera", &camera), graph.add("tiles", &tiles), graph.add("gray", &gray));
+        graph.connect(c, t);
+        graph.connect(t, g);
+
+        let outputs = graph.validate().unwrap();
+        assert_eq!(outputs[g], ImageDescriptor { width: 256, height: 256, format: ColorFormat::Luma8 });
+    }
+
+    #[test]
+    fn grayscale_into_color_only_node_is_reported() {
+        let camera = camera();
+        let gray = ColorConvertNode::new(ColorConvertNodeConfig { target: ColorFormat::Luma8 }, None);
+        let calibration = ColorCalibrationNode::new(
+            ColorCalibrationNodeConfig { matrix: None, continuous: false, emit_only: false }, None);
+        let orphan = ColorConvertNode::new(ColorConvertNodeConfig { target: ColorFormat::Rgb8 }, None);
+
+        let mut graph = ShapeGraph::new();
+        let (c, g, k) = (graph.add("camera", &camera), graph.add("gray", &gray), graph.add("calibration", &calibration));
+        graph.add("orphan", &orphan);
+        graph.connect(c, g);
+        graph.connect(g, k);
+
+        let mismatches = graph.validate().unwrap_err();
+        assert_eq!(mismatches.len(), 2);
+        assert_eq!(mismatches[0].node, "orphan");
+        assert_eq!(mismatches[0].error, ShapeError::MissingInput);
+        assert_eq!(mismatches[1].node, "calibration");
+        assert!(matches!(mismatches[1].error, ShapeError::UnsupportedFormat { format: ColorFormat::Luma8, .. }));
+        assert!(mismatches[1].to_string().starts_with("calibration given 640x480 Luma8"));
+    }
+}
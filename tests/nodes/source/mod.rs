@@ -0,0 +1,5 @@
+pub mod test_frame_source;
+pub mod test_genicam;
+pub mod test_patterns;
+pub mod test_row_padding;
+pub mod test_webcam;
@@ -0,0 +1,147 @@
+#[cfg(test)]
+mod source {
+    use flowrs::connection::{connect, Input};
+    use flowrs::node::{Node, UpdateError};
+    use flowrs_img::Error;
+    use flowrs_img::source::{CaptureNode, CropSource, CaptureNodeConfig, CaptureStatus, FrameSource, FrameSourceConfig, MockSource, RetryPolicy, TestPattern};
+    use flowrs_img::transform::{encode_image, EncodeFormat};
+    use flowrs_img::types::{Rect, SourceControl};
+    use image::DynamicImage;
+
+    #[test]
+    fn mock_source_loops_in_order() {
+        let frames: Vec<DynamicImage> = (1..=2).map(|w| DynamicImage::new_rgb8(w, 1)).collect();
+        let mut source = MockSource::new(frames, true);
+        let widths: Vec<u32> = (0..5).map(|_| source.next_frame().unwrap().unwrap().width()).collect();
+        assert_eq!(widths, [1, 2, 1, 2, 1]);
+
+        let mut once = MockSource::new(vec![DynamicImage::new_rgb8(1, 1)], false);
+        assert!(once.next_frame().unwrap().is_some());
+        assert!(once.next_frame().unwrap().is_none());
+    }
+
+    #[test]
+    fn pattern_source_from_config() {
+        let config = FrameSourceConfig::Pattern { pattern: TestPattern::Gradient, width: 64, height: 48 };
+        let frame = config.open().unwrap().next_frame().unwrap().unwrap();
+        assert_eq!((frame.width(), frame.height()), (64, 48));
+    }
+
+    #[test]
+    fn sources_without_roi_support_are_cropped_after_decoding() {
+        let mut source = MockSource::new(vec![DynamicImage::new_rgb8(8, 8)], false);
+        assert!(!source.set_roi(Some(Rect::new(0, 0, 1, 1))));
+        let mut cropped = CropSource::wrap(Box::new(source), Some(Rect::new(2, 2, 3, 10)));
+        let frame = cropped.next_frame().unwrap().unwrap();
+        assert_eq!((frame.width(), frame.height()), (3, 6));
+    }
+
+    #[test]
+    fn cropped_source_delivers_region() {
+        let config = FrameSourceConfig::Pattern { pattern: TestPattern::Gradient, width: 64, height: 48 };
+        let full = config.open().unwrap().next_frame().unwrap().unwrap();
+        let mut source = config.open_cropped(Some(Rect::new(8, 4, 16, 100))).unwrap();
+        let frame = source.next_frame().unwrap().unwrap();
+        assert_eq!((frame.width(), frame.height()), (16, 44));
+        assert_eq!(frame.to_rgb8().get_pixel(0, 0), full.to_rgb8().get_pixel(8, 4));
+
+        let mut outside = config.open_cropped(Some(Rect::new(64, 0, 8, 8))).unwrap();
+        assert!(matches!(outside.next_frame(), Err(Error::Conversion(_))));
+    }
+
+    #[test]
+    fn directory_frames_pass_through_encoded() {
+        let dir = std::env::temp_dir().join(format!("flowrs-img-passthrough-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let data = encode_image(&DynamicImage::new_rgb8(4, 3), EncodeFormat::Png).unwrap();
+        std::fs::write(dir.join("0.png"), &data).unwrap();
+
+        let config = FrameSourceConfig::Directory { path: dir.clone(), looping: false };
+        assert_eq!(config.open().unwrap().next_encoded().unwrap(), Some(data));
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let pattern = FrameSourceConfig::default().open().unwrap().next_encoded();
+        assert!(matches!(pattern, Err(Error::UnsupportedFormat(_))));
+    }
+
+    #[test]
+    fn open_failures_are_typed() {
+        let source = FrameSourceConfig::Directory { path: "/nonexistent/frames".into(), looping: false };
+        assert!(matches!(source.open().err(), Some(Error::CameraOpen(_))));
+
+        let retry = RetryPolicy { max_consecutive_failures: 0, ..Default::default() };
+        let mut node = CaptureNode::new(CaptureNodeConfig { source, retry, ..Default::default() }, None);
+        let result = node.on_update();
+        assert!(matches!(result, Err(UpdateError::Other(e)) if matches!(e.downcast_ref::<Error>(), Some(Error::CameraOpen(_)))));
+    }
+
+    /// Fails the first `failures` reads.
+    struct FlakySource {
+        failures: u32,
+    }
+
+    impl FrameSource for FlakySource {
+        fn next_frame(&mut self) -> Result<Option<DynamicImage>, Error> {
+            if self.failures > 0 {
+                self.failures -= 1;
+                return Err(Error::CameraRead("timeout".into()));
+            }
+            Ok(Some(DynamicImage::new_rgb8(1, 1)))
+        }
+    }
+
+    #[test]
+    fn read_failures_are_retried() {
+        let retry = RetryPolicy { max_consecutive_failures: 2, backoff_ms: 0, max_backoff_ms: 0, reopen: true };
+        let config = CaptureNodeConfig { retry, ..Default::default() };
+        let mut node = CaptureNode::with_source(config.clone(), Box::new(FlakySource { failures: 2 }), None);
+        assert!((0..4).all(|_| node.on_update().is_ok()));
+
+        let mut node = CaptureNode::with_source(config, Box::new(FlakySource { failures: 3 }), None);
+        assert!(node.on_update().is_ok() && node.on_update().is_ok());
+        assert!(node.on_update().is_err());
+    }
+
+    #[test]
+    fn exhausted_node_waits_for_start() {
+        let retry = RetryPolicy { max_consecutive_failures: 1, backoff_ms: 0, max_backoff_ms: 0, reopen: true };
+        let config = CaptureNodeConfig { retry, ..Default::default() };
+        let mut node = CaptureNode::with_source(config, Box::new(FlakySource { failures: 4 }), None);
+        let mut status = Input::new();
+        connect(node.status.clone(), status.clone());
+
+        assert!(node.on_update().is_ok());
+        assert!(node.on_update().is_err());
+        // Given up: no further attempts, and so no further errors.
+        assert!((0..5).all(|_| node.on_update().is_ok()));
+        let seen: Vec<CaptureStatus> = std::iter::from_fn(|| status.next().ok()).collect();
+        assert_eq!(seen, [CaptureStatus::Reconnecting, CaptureStatus::Failed]);
+
+        node.control.send(SourceControl::Start).unwrap();
+        assert!(node.on_update().is_ok());
+        assert!(node.on_update().is_err());
+        node.control.send(SourceControl::Start).unwrap();
+        assert!(node.on_update().is_ok());
+        assert_eq!(std::iter::from_fn(|| status.next().ok()).last(), Some(CaptureStatus::Connected));
+    }
+
+    #[test]
+    fn open_and_read_failures_are_counted_apart() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = FrameSourceConfig::Directory { path: dir.path().join("frames"), looping: true };
+        let retry = RetryPolicy { max_consecutive_failures: 1, backoff_ms: 0, max_backoff_ms: 0, reopen: true };
+        let mut node = CaptureNode::new(CaptureNodeConfig { source, retry, ..Default::default() }, None);
+
+        // One failed open is tolerated, and a successful open forgets it.
+        assert!(node.on_update().is_ok());
+        std::fs::create_dir(dir.path().join("frames")).unwrap();
+        let png = encode_image(&DynamicImage::new_rgb8(2, 2), EncodeFormat::Png).unwrap();
+        std::fs::write(dir.path().join("frames/0.png"), png).unwrap();
+        assert!(node.on_update().is_ok());
+        std::fs::remove_dir_all(dir.path().join("frames")).unwrap();
+        // A failed read followed by a failed reopen is one failure of each kind.
+        assert!(node.on_update().is_ok());
+        assert!(node.on_update().is_ok());
+        assert!(node.on_update().is_err());
+    }
+}
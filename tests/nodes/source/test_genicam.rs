@@ -0,0 +1,52 @@
+#[cfg(test)]
+mod source {
+    use flowrs_img::source::{crop_raw_frame, decode_raw_frame, GenICamPixelFormat};
+    use flowrs_img::types::Rect;
+
+    #[test]
+    fn mono16_is_little_endian() {
+        let img = decode_raw_frame(GenICamPixelFormat::Mono16, 2, 1, &[0x34, 0x12, 0xff, 0x00]).unwrap();
+        let luma = img.as_luma16().unwrap();
+        assert_eq!(luma.get_pixel(0, 0).0, [0x1234]);
+        assert_eq!(luma.get_pixel(1, 0).0, [0x00ff]);
+    }
+
+    #[test]
+    fn bayer_cells_are_demosaiced() {
+        // R G / G B and G R / B G describe the same colors with a shifted mosaic.
+        let rg = decode_raw_frame(GenICamPixelFormat::BayerRg8, 2, 2, &[200, 100, 50, 10]).unwrap().to_rgb8();
+        let gr = decode_raw_frame(GenICamPixelFormat::BayerGr8, 2, 2, &[100, 200, 10, 50]).unwrap().to_rgb8();
+        assert!(rg.pixels().all(|p| p.0 == [200, 75, 10]));
+        assert!(gr.pixels().all(|p| p.0 == [200, 75, 10]));
+    }
+
+    #[test]
+    fn bgr_is_swapped_to_rgb() {
+        let img = decode_raw_frame(GenICamPixelFormat::Bgr8, 2, 1, &[10, 20, 30, 40, 50, 60]).unwrap();
+        assert_eq!(img.as_rgb8().unwrap().as_raw(), &[30, 20, 10, 60, 50, 40]);
+    }
+
+    #[test]
+    fn short_frames_are_rejected() {
+        assert!(decode_raw_frame(GenICamPixelFormat::Rgb8, 2, 2, &[0; 11]).is_err());
+        assert!(decode_raw_frame(GenICamPixelFormat::BayerBg8, 1, 1, &[0]).is_err());
+    }
+
+    #[test]
+    fn raw_regions_are_cut_before_decoding() {
+        let data: Vec<u8> = (0..12).collect();
+        let raw = crop_raw_frame(GenICamPixelFormat::Mono16, 3, 2, &data, Rect::new(1, 1, 5, 5)).unwrap();
+        assert_eq!((raw.width, raw.height), (2, 1));
+        assert_eq!(raw.data, [8, 9, 10, 11]);
+        assert!(crop_raw_frame(GenICamPixelFormat::Mono8, 3, 2, &data, Rect::new(3, 0, 1, 1)).is_err());
+    }
+
+    #[test]
+    fn odd_bayer_regions_shift_the_mosaic() {
+        let data = [200, 100, 200, 100, 50, 10, 50, 10, 200, 100, 200, 100, 50, 10, 50, 10];
+        let raw = crop_raw_frame(GenICamPixelFormat::BayerRg8, 4, 4, &data, Rect::new(1, 1, 2, 2)).unwrap();
+        assert_eq!(raw.format, GenICamPixelFormat::BayerBg8);
+        let img = decode_raw_frame(raw.format, raw.width, raw.height, &raw.data).unwrap().to_rgb8();
+        assert!(img.pixels().all(|p| p.0 == [200, 75, 10]));
+    }
+}
@@ -0,0 +1,63 @@
+#[cfg(test)]
+mod source {
+    use flowrs::connection::{connect, Input};
+    use flowrs::node::Node;
+    use image::Rgb;
+
+    use flowrs_img::source::{render_pattern, TestPattern, TestPatternNode, TestPatternNodeConfig};
+    use flowrs_img::types::{NodeState, SourceControl};
+
+    #[test]
+    fn static_patterns() {
+        let bars = render_pattern(&TestPattern::ColorBars, 80, 10, 0);
+        assert_eq!(bars.get_pixel(0, 0), &Rgb([255, 255, 255]));
+        assert_eq!(bars.get_pixel(15, 9), &Rgb([255, 255, 0]));
+        assert_eq!(bars.get_pixel(79, 5), &Rgb([0, 0, 0]));
+
+        let checker = render_pattern(&TestPattern::Checkerboard { cell_size: 4 }, 16, 16, 0);
+        assert_eq!(checker.get_pixel(1, 1), &Rgb([255, 255, 255]));
+        assert_eq!(checker.get_pixel(5, 1), &Rgb([0, 0, 0]));
+        assert_eq!(checker.get_pixel(5, 5), &Rgb([255, 255, 255]));
+
+        let gradient = render_pattern(&TestPattern::Gradient, 256, 2, 0);
+        assert_eq!(gradient.get_pixel(0, 0)[0], 0);
+        assert_eq!(gradient.get_pixel(255, 1)[0], 255);
+    }
+
+    #[test]
+    fn noise_is_reproducible() {
+        let noise = TestPattern::Noise { seed: 7 };
+        assert_eq!(render_pattern(&noise, 32, 32, 3), render_pattern(&noise, 32, 32, 3));
+        assert_ne!(render_pattern(&noise, 32, 32, 3), render_pattern(&noise, 32, 32, 4));
+    }
+
+    #[test]
+    fn moving_box_bounces() {
+        let pattern = TestPattern::MovingBox { size: 10, pixels_per_frame: 15 };
+        let corner = |frame| {
+            let img = render_pattern(&pattern, 40, 40, frame);
+            img.enumerate_pixels().find(|(_, _, p)| p[0] == 255).map(|(x, y, _)| (x, y))
+        };
+        assert_eq!(corner(0), Some((0, 0)));
+        // 30 pixels of travel room: forward to the far corner, then back.
+        assert_eq!(corner(2), Some((30, 30)));
+        assert_eq!(corner(3), Some((15, 15)));
+    }
+
+    #[test]
+    fn node_reports_its_health() {
+        let config = TestPatternNodeConfig { width: 8, height: 8, fps: 1000.0, ..Default::default() };
+        let mut node = TestPatternNode::new(config, None);
+        let mut health = Input::new();
+        connect(node.health.clone(), health.clone());
+
+        node.on_update().unwrap();
+        let status = health.next().unwrap();
+        assert_eq!(status.state, NodeState::Running);
+        assert_eq!(status.frames, 1);
+
+        node.control.send(SourceControl::Pause).unwrap();
+        node.on_update().unwrap();
+        assert_eq!(health.next().unwrap().state, NodeState::Paused);
+    }
+}
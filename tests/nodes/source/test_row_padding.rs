@@ -0,0 +1,12 @@
+#[cfg(test)]
+mod source {
+    use flowrs_img::source::strip_row_padding;
+
+    #[test]
+    fn padding_is_removed() {
+        let data = [1, 2, 0, 0, 3, 4, 0, 0, 5, 6];
+        assert_eq!(strip_row_padding(&data, 4, 2, 3).unwrap(), [1, 2, 3, 4, 5, 6]);
+        assert!(strip_row_padding(&data, 4, 2, 4).is_err());
+        assert!(strip_row_padding(&data, 1, 2, 1).is_err());
+    }
+}
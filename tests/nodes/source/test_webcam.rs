@@ -0,0 +1,40 @@
+#[cfg(test)]
+mod source {
+    use flowrs_img::config::Validate;
+    use flowrs_img::source::{yuyv_region_to_rgb, yuyv_to_rgb, WebcamConfig};
+    use flowrs_img::types::Rect;
+
+    #[test]
+    fn yuyv_pairs_share_chroma() {
+        // Black and white with neutral chroma: Y0 U Y1 V.
+        let img = yuyv_to_rgb(2, 1, &[16, 128, 235, 128]).unwrap();
+        assert_eq!(img.as_raw(), &[0, 0, 0, 255, 255, 255]);
+    }
+
+    #[test]
+    fn yuyv_chroma_tints_both_pixels() {
+        let img = yuyv_to_rgb(2, 1, &[128, 128, 128, 240]).unwrap();
+        assert!(img.pixels().all(|p| p.0[0] > p.0[1] && p.0[0] > p.0[2]));
+    }
+
+    #[test]
+    fn yuyv_regions_may_split_pairs() {
+        let img = yuyv_region_to_rgb(4, 1, &[16, 128, 235, 128, 16, 128, 235, 128], Rect::new(1, 0, 2, 1)).unwrap();
+        assert_eq!(img.as_raw(), &[255, 255, 255, 0, 0, 0]);
+    }
+
+    #[test]
+    fn malformed_yuyv_is_rejected() {
+        assert!(yuyv_to_rgb(3, 1, &[0; 6]).is_err());
+        assert!(yuyv_to_rgb(2, 2, &[0; 7]).is_err());
+    }
+
+    #[test]
+    fn webcam_config_is_validated() {
+        assert!(WebcamConfig::default().validate().is_ok());
+        let config = WebcamConfig { width: 0, ..Default::default() };
+        assert_eq!(config.validate().unwrap_err().field, "width");
+        let config = WebcamConfig { fps: 0, ..Default::default() };
+        assert_eq!(config.validate().unwrap_err().field, "fps");
+    }
+}
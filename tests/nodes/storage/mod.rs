@@ -0,0 +1,2 @@
+pub mod test_retention;
+pub mod test_watchfolder;
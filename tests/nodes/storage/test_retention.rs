@@ -0,0 +1,96 @@
+#[cfg(test)]
+mod storage {
+    use std::fs::File;
+    use std::path::{Path, PathBuf};
+    use std::time::{Duration, SystemTime};
+
+    use flowrs::connection::{connect, Input};
+    use flowrs::node::Node;
+
+    use flowrs_img::storage::{RetentionNode, RetentionNodeConfig};
+
+    const DAY: Duration = Duration::from_secs(24 * 3600);
+
+    fn file(dir: &Path, name: &str, age: Duration) -> PathBuf {
+        let path = dir.join(name);
+        File::create(&path).unwrap().set_modified(SystemTime::now() - age).unwrap();
+        path
+    }
+
+    fn node(directories: Vec<PathBuf>, exclude: &[&str]) -> (RetentionNode, Input<PathBuf>) {
+        let config = RetentionNodeConfig {
+            retention_secs: DAY.as_secs(),
+            directories,
+            exclude: exclude.iter().map(|p| p.to_string()).collect(),
+            sweep_interval_secs: 0,
+        };
+        let node = RetentionNode::new(config, None);
+        let deleted = Input::new();
+        connect(node.deleted.clone(), deleted.clone());
+        (node, deleted)
+    }
+
+    #[test]
+    fn expired_files_are_deleted_and_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        let old = file(dir.path(), "old.mp4", 2 * DAY);
+        let fresh = file(dir.path(), "fresh.mp4", Duration::ZERO);
+
+        let reported = old.canonicalize().unwrap();
+
+        let (mut node, mut deleted) = node(vec![dir.path().into()], &[]);
+        node.on_update().unwrap();
+
+        assert!(!old.exists() && fresh.exists());
+        assert_eq!(deleted.next().ok(), Some(reported));
+        assert!(deleted.next().is_err());
+    }
+
+    #[test]
+    fn excluded_and_held_files_survive() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("rec")).unwrap();
+        let playlist = file(dir.path(), "rec/live.m3u8", 2 * DAY);
+        let evidence = file(dir.path(), "rec/clip.mp4", 2 * DAY);
+        let other = file(dir.path(), "rec/other.mp4", 2 * DAY);
+
+        let (mut node, _deleted) = node(vec![dir.path().join("rec")], &[".m3u8"]);
+        // Held through a different spelling of the same path.
+        node.hold.send(dir.path().join("rec/../rec/./clip.mp4")).unwrap();
+        node.on_update().unwrap();
+
+        assert!(playlist.exists() && evidence.exists());
+        assert!(!other.exists());
+    }
+
+    #[test]
+    fn missing_directory_does_not_stop_the_sweep() {
+        let dir = tempfile::tempdir().unwrap();
+        let old = file(dir.path(), "old.mp4", 2 * DAY);
+
+        let (mut node, _deleted) = node(vec![dir.path().join("unmounted"), dir.path().into()], &[]);
+        let result = node.on_update();
+
+        assert!(!old.exists());
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn failed_deletions_finish_the_sweep_and_are_retried() {
+        let dir = tempfile::tempdir().unwrap();
+        let old = file(dir.path(), "old.mp4", 2 * DAY);
+        // Directories cannot be removed as files, whoever runs the test.
+        let stuck = tempfile::tempdir().unwrap();
+        File::open(stuck.path()).unwrap().set_modified(SystemTime::now() - 2 * DAY).unwrap();
+        let reported = old.canonicalize().unwrap();
+
+        let (mut node, mut deleted) = node(vec![dir.path().into()], &[]);
+        node.files.send(stuck.path().into()).unwrap();
+        assert!(node.on_update().is_err());
+        assert!(!old.exists());
+        assert_eq!(deleted.next().ok(), Some(reported));
+
+        // Still tracked, so the next sweep reports it again.
+        assert!(node.on_update().is_err());
+    }
+}
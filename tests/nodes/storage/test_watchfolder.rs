@@ -0,0 +1,94 @@
+#[cfg(test)]
+mod storage {
+    use std::path::Path;
+
+    use flowrs::connection::{connect, Input};
+    use flowrs::node::Node;
+    use image::{DynamicImage, GrayImage, Luma};
+
+    use flowrs_img::config::Validate;
+    use flowrs_img::storage::{SourceDisposition, TranscodeReport, WatchfolderTranscodeNode, WatchfolderTranscodeNodeConfig};
+    use flowrs_img::transform::EncodeFormat;
+
+    fn save(path: &Path, value: u8, format: image::ImageFormat) {
+        DynamicImage::ImageLuma8(GrayImage::from_pixel(4, 4, Luma([value]))).save_with_format(path, format).unwrap();
+    }
+
+    fn config(input: &Path, output: &Path) -> WatchfolderTranscodeNodeConfig {
+        WatchfolderTranscodeNodeConfig {
+            input_dir: input.into(),
+            output_dir: output.into(),
+            poll_interval_ms: 0,
+            settle_ms: 0,
+            ..Default::default()
+        }
+    }
+
+    /// Runs the node until the folder is drained and returns its reports.
+    fn run(config: WatchfolderTranscodeNodeConfig) -> Vec<TranscodeReport> {
+        let mut node = WatchfolderTranscodeNode::new(config, None);
+        let mut reports = Input::new();
+        connect(node.report.clone(), reports.clone());
+        for _ in 0..10 {
+            let _ = node.on_update();
+        }
+        std::iter::from_fn(|| reports.next().ok()).collect()
+    }
+
+    fn value(path: &Path) -> u8 {
+        image::open(path).unwrap().to_luma8().get_pixel(0, 0)[0]
+    }
+
+    #[test]
+    fn refuses_to_overwrite_the_source() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("a.png");
+        save(&source, 7, image::ImageFormat::Png);
+
+        let mut config = config(dir.path(), dir.path());
+        config.on_success = SourceDisposition::Delete;
+        let reports = run(config);
+
+        assert_eq!(reports.len(), 1);
+        assert!(reports[0].error.as_deref().is_some_and(|e| e.contains("overwrite the source")), "{:?}", reports[0]);
+        assert_eq!(value(&source), 7);
+    }
+
+    #[test]
+    fn sources_sharing_a_stem_get_separate_outputs() {
+        let (input, output) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+        save(&input.path().join("a.bmp"), 10, image::ImageFormat::Bmp);
+        save(&input.path().join("a.tiff"), 20, image::ImageFormat::Tiff);
+
+        let reports = run(config(input.path(), output.path()));
+        assert!(reports.iter().all(|r| r.error.is_none()), "{:?}", reports);
+        assert_eq!(value(&output.path().join("a.png")), 10);
+        assert_eq!(value(&output.path().join("a-tiff.png")), 20);
+    }
+
+    #[test]
+    fn outputs_in_the_watched_folder_do_not_clobber_other_sources() {
+        let dir = tempfile::tempdir().unwrap();
+        save(&dir.path().join("a.bmp"), 10, image::ImageFormat::Bmp);
+        save(&dir.path().join("a.png"), 20, image::ImageFormat::Png);
+
+        let mut config = config(dir.path(), dir.path());
+        config.format = EncodeFormat::Png;
+        let reports = run(config);
+
+        assert_eq!(value(&dir.path().join("a.png")), 20);
+        assert_eq!(value(&dir.path().join("a-bmp.png")), 10);
+        assert_eq!(reports.iter().filter(|r| r.error.is_some()).count(), 1, "{:?}", reports);
+    }
+
+    #[test]
+    fn config_needs_distinct_directories() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(WatchfolderTranscodeNodeConfig::default().validate().is_err());
+        assert!(config(dir.path(), dir.path()).validate().is_ok());
+
+        let mut config = config(dir.path(), dir.path());
+        config.on_success = SourceDisposition::MoveTo(dir.path().into());
+        assert!(config.validate().is_err());
+    }
+}
@@ -0,0 +1,39 @@
+#[cfg(test)]
+mod testing {
+    use flowrs_img::testing::{compare_images, ssim, ImageTolerance};
+    use image::{DynamicImage, GrayImage, Luma};
+
+    fn textured(offset: u8) -> GrayImage {
+        GrayImage::from_fn(32, 32, |x, y| Luma([((x * 7 + y * 13) % 200) as u8 + offset]))
+    }
+
+    #[test]
+    fn tolerances() {
+        let reference = DynamicImage::ImageLuma8(textured(0));
+        let mut touched = textured(0);
+        touched.put_pixel(3, 3, Luma([255]));
+        touched.put_pixel(4, 3, Luma([0]));
+        let touched = DynamicImage::ImageLuma8(touched);
+
+        assert!(compare_images(&reference, &reference, &ImageTolerance::Exact).is_ok());
+        let err = compare_images(&touched, &reference, &ImageTolerance::Exact).unwrap_err();
+        assert!(err.starts_with("2 pixels differ"), "{}", err);
+
+        let lenient = ImageTolerance::PerPixel { max_difference: 4, max_mismatched: 0.01 };
+        assert!(compare_images(&touched, &reference, &lenient).is_ok());
+        let shifted = DynamicImage::ImageLuma8(textured(10));
+        assert!(compare_images(&shifted, &reference, &lenient).is_err());
+
+        let size = compare_images(&DynamicImage::new_luma8(8, 8), &reference, &ImageTolerance::Exact).unwrap_err();
+        assert!(size.contains("8x8") && size.contains("32x32"), "{}", size);
+    }
+
+    #[test]
+    fn ssim_scores() {
+        let a = textured(0);
+        assert!((ssim(&a, &a) - 1.0).abs() < 1e-9);
+        assert!(ssim(&a, &textured(3)) > 0.95);
+        let flat = GrayImage::from_pixel(32, 32, Luma([100]));
+        assert!(ssim(&a, &flat) < 0.5);
+    }
+}
@@ -0,0 +1,2 @@
+pub mod test_tracker;
+pub mod test_zones;
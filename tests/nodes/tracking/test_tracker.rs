@@ -0,0 +1,60 @@
+#[cfg(test)]
+mod tracking {
+    use flowrs_img::config::Validate;
+    use flowrs_img::tracking::{Tracker, TrackerNodeConfig};
+    use flowrs_img::types::{Detection, Rect};
+
+    fn config() -> TrackerNodeConfig {
+        TrackerNodeConfig { iou_threshold: 0.3, max_age: 2, min_hits: 2, process_noise: 1.0, measurement_noise: 4.0 }
+    }
+
+    fn det(x: u32, y: u32) -> Detection {
+        Detection { rect: Rect::new(x, y, 20, 20), label: "car".into(), score: 0.9 }
+    }
+
+    #[test]
+    fn ids_persist_and_velocity_is_estimated() {
+        let mut tracker = Tracker::default();
+        let mut ids = Vec::new();
+        for i in 0..10 {
+            let tracks = tracker.update(vec![det(10 + 4 * i, 50), det(200, 10 + 3 * i)], &config());
+            assert_eq!(tracks.len(), 2);
+            ids.push(tracks.iter().map(|t| t.id).collect::<Vec<_>>());
+        }
+        assert!(ids.windows(2).all(|w| w[0] == w[1]));
+
+        let tracks = tracker.update(vec![det(50, 50), det(200, 40)], &config());
+        let fast = tracks.iter().find(|t| t.rect.y == 50).unwrap();
+        assert!((fast.velocity.0 - 4.0).abs() < 1.0, "{:?}", fast.velocity);
+        assert_eq!(fast.age, 10);
+    }
+
+    #[test]
+    fn coasting_track_is_recovered_then_dropped() {
+        let mut tracker = Tracker::default();
+        for i in 0..4 {
+            tracker.update(vec![det(10 + 5 * i, 10)], &config());
+        }
+        let id = tracker.update(vec![det(30, 10)], &config())[0].id;
+        assert!(tracker.update(vec![], &config()).is_empty());
+        assert_eq!(tracker.update(vec![det(40, 10)], &config())[0].id, id);
+
+        for _ in 0..3 {
+            tracker.update(vec![], &config());
+        }
+        // A new track must be confirmed before it is reported again.
+        assert!(tracker.update(vec![det(40, 10)], &config()).is_empty());
+        assert_ne!(tracker.update(vec![det(40, 10)], &config())[0].id, id);
+    }
+
+    #[test]
+    fn noise_and_age_are_validated() {
+        assert!(TrackerNodeConfig::default().validate().is_ok());
+        let field = |config: TrackerNodeConfig| config.validate().unwrap_err().field;
+        assert_eq!(field(TrackerNodeConfig { max_age: 0, ..config() }), "max_age");
+        for noise in [0.0, -1.0, f32::NAN, f32::INFINITY] {
+            assert_eq!(field(TrackerNodeConfig { process_noise: noise, ..config() }), "process_noise");
+            assert_eq!(field(TrackerNodeConfig { measurement_noise: noise, ..config() }), "measurement_noise");
+        }
+    }
+}
@@ -0,0 +1,22 @@
+#[cfg(test)]
+mod tracking {
+    use flowrs_img::tracking::{line_crossing, point_in_polygon, CountingLine, CrossDirection};
+
+    #[test]
+    fn polygon_containment() {
+        let l_shape = [(0.0, 0.0), (10.0, 0.0), (10.0, 4.0), (4.0, 4.0), (4.0, 10.0), (0.0, 10.0)];
+        assert!(point_in_polygon((2.0, 8.0), &l_shape));
+        assert!(point_in_polygon((8.0, 2.0), &l_shape));
+        assert!(!point_in_polygon((8.0, 8.0), &l_shape));
+    }
+
+    #[test]
+    fn crossing_direction_and_extent() {
+        // A horizontal line pointing right; below it is its right-hand side in image coordinates.
+        let line = CountingLine { name: "gate".into(), start: (0.0, 50.0), end: (100.0, 50.0) };
+        assert_eq!(line_crossing(&line, (40.0, 40.0), (42.0, 60.0)), Some(CrossDirection::LeftToRight));
+        assert_eq!(line_crossing(&line, (42.0, 60.0), (40.0, 40.0)), Some(CrossDirection::RightToLeft));
+        assert_eq!(line_crossing(&line, (140.0, 40.0), (142.0, 60.0)), None);
+        assert_eq!(line_crossing(&line, (40.0, 40.0), (60.0, 45.0)), None);
+    }
+}
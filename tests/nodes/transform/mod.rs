@@ -1 +1,11 @@
-//pub mod test_encoding;
\ No newline at end of file
+//pub mod test_encoding;
+pub mod test_bit_depth;
+pub mod test_codecs;
+pub mod test_decode_pool;
+pub mod test_fast_jpeg;
+pub mod test_pages;
+pub mod test_pyramid;
+pub mod test_resize;
+pub mod test_roi;
+pub mod test_scaled_decode;
+pub mod test_tiles;
@@ -0,0 +1,28 @@
+#[cfg(test)]
+mod transform {
+    use flowrs_img::transform::{decode_image, encode_image, encode_image_with_depth, BitDepthPolicy, EncodeFormat};
+    use image::{DynamicImage, ImageBuffer, Rgb};
+
+    #[test]
+    fn deep_images_keep_their_depth() {
+        let deep = DynamicImage::ImageLuma16(ImageBuffer::from_pixel(3, 2, image::Luma([40_000u16])));
+        let png = decode_image(encode_image(&deep, EncodeFormat::Png).unwrap()).unwrap();
+        assert_eq!(png.as_luma16().unwrap().get_pixel(2, 1).0, [40_000]);
+
+        let float = DynamicImage::ImageRgb32F(ImageBuffer::from_pixel(2, 2, Rgb([0.25f32, 1.5, -2.0])));
+        let tiff = decode_image(encode_image(&float, EncodeFormat::Tiff).unwrap()).unwrap();
+        assert_eq!(tiff.as_rgb32f().unwrap().get_pixel(1, 1).0, [0.25, 1.5, -2.0]);
+    }
+
+    #[test]
+    fn depth_policy_reduces_or_rejects() {
+        let float = DynamicImage::ImageRgb32F(ImageBuffer::from_pixel(2, 2, Rgb([0.5f32; 3])));
+        assert!(encode_image_with_depth(&float, EncodeFormat::Png, BitDepthPolicy::Strict).is_err());
+        assert!(encode_image_with_depth(&float, EncodeFormat::Tiff, BitDepthPolicy::Strict).is_ok());
+
+        let preserved = decode_image(encode_image(&float, EncodeFormat::Png).unwrap()).unwrap();
+        assert!(preserved.as_rgb16().is_some());
+        let reduced = encode_image_with_depth(&float, EncodeFormat::Png, BitDepthPolicy::Reduce8).unwrap();
+        assert!(decode_image(reduced).unwrap().as_rgb8().is_some());
+    }
+}
@@ -0,0 +1,34 @@
+#[cfg(test)]
+mod transform {
+    use flowrs_img::config::Validate;
+    use flowrs_img::transform::{encode_image, EncodeFormat};
+    use image::{DynamicImage, ImageBuffer, Rgb};
+
+    fn gradient() -> DynamicImage {
+        DynamicImage::ImageRgb16(ImageBuffer::from_fn(16, 8, |x, y| Rgb([x as u16 * 4000, y as u16 * 8000, 1234])))
+    }
+
+    #[test]
+    fn modern_codecs_need_their_feature() {
+        let avif = EncodeFormat::Avif { quality: 80, speed: 6 };
+        let jxl = EncodeFormat::Jxl { effort: 7 };
+        assert_eq!(avif.validate().is_ok(), cfg!(feature = "avif"));
+        assert_eq!(jxl.validate().is_ok(), cfg!(feature = "jxl"));
+        assert_eq!(encode_image(&gradient(), jxl).is_ok(), cfg!(feature = "jxl"));
+        assert!(EncodeFormat::Avif { quality: 80, speed: 0 }.validate().is_err());
+    }
+
+    #[cfg(feature = "jxl")]
+    #[test]
+    fn jxl_round_trip_is_lossless() {
+        let data = encode_image(&gradient(), EncodeFormat::Jxl { effort: 7 }).unwrap();
+        assert_eq!(flowrs_img::transform::decode_image(data).unwrap(), gradient());
+    }
+
+    #[cfg(feature = "avif")]
+    #[test]
+    fn avif_output_is_an_avif_file() {
+        let data = encode_image(&gradient(), EncodeFormat::Avif { quality: 80, speed: 10 }).unwrap();
+        assert_eq!(&data[4..12], b"ftypavif");
+    }
+}
@@ -0,0 +1,96 @@
+#[cfg(test)]
+mod transform {
+    use flowrs::connection::{connect, Input};
+    use flowrs::node::{Node, UpdateError};
+    use image::{DynamicImage, GrayImage, Luma};
+
+    use flowrs_img::transform::{encode_image, DecodeImageNode, DecodeImageNodeConfig, EncodeFormat};
+
+    /// PNGs of decreasing size, so later buffers tend to finish decoding first.
+    fn buffers(count: u8) -> Vec<Vec<u8>> {
+        (0..count)
+            .map(|i| {
+                let size = 400 - i as u32 * 30;
+                let img = DynamicImage::ImageLuma8(GrayImage::from_pixel(size, size, Luma([i])));
+                encode_image(&img, EncodeFormat::Png).unwrap()
+            })
+            .collect()
+    }
+
+    fn decoder(preserve_order: bool) -> (DecodeImageNode, Input<DynamicImage>) {
+        let node = DecodeImageNode::with_config(DecodeImageNodeConfig { workers: 4, preserve_order, all_pages: false }, None);
+        let output = Input::new();
+        connect(node.output.clone(), output.clone());
+        (node, output)
+    }
+
+    fn values(output: &mut Input<DynamicImage>) -> Vec<u8> {
+        std::iter::from_fn(|| output.next().ok()).map(|img| img.to_luma8().get_pixel(0, 0)[0]).collect()
+    }
+
+    /// Updates until `count` frames came out, collecting the first error.
+    fn update_until(node: &mut DecodeImageNode, output: &mut Input<DynamicImage>, count: usize) -> (Vec<u8>, Option<UpdateError>) {
+        let (mut seen, mut error) = (Vec::new(), None);
+        while seen.len() < count {
+            if let Err(e) = node.on_update() {
+                error.get_or_insert(e);
+            }
+            seen.extend(values(output));
+        }
+        (seen, error)
+    }
+
+    #[test]
+    fn frames_are_emitted_in_order_across_updates() {
+        let (mut node, mut output) = decoder(true);
+        for data in buffers(8) {
+            node.input.send(data).unwrap();
+        }
+        let (seen, error) = update_until(&mut node, &mut output, 8);
+        assert!(error.is_none());
+        assert_eq!(seen, (0..8).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn unordered_mode_still_emits_everything() {
+        let (mut node, mut output) = decoder(false);
+        for data in buffers(8) {
+            node.input.send(data).unwrap();
+        }
+        let (mut seen, _) = update_until(&mut node, &mut output, 8);
+        seen.sort();
+        assert_eq!(seen, (0..8).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn shutdown_flushes_buffers_in_flight() {
+        let (mut node, mut output) = decoder(true);
+        for data in buffers(8) {
+            node.input.send(data).unwrap();
+        }
+        node.on_update().unwrap();
+        let mut seen = values(&mut output);
+        node.on_shutdown().unwrap();
+        seen.extend(values(&mut output));
+        assert_eq!(seen, (0..8).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn corrupt_buffer_does_not_drop_its_neighbours() {
+        let (mut node, mut output) = decoder(true);
+        let mut data = buffers(6);
+        data.insert(2, b"not an image".to_vec());
+        for data in data {
+            node.input.send(data).unwrap();
+        }
+        let (seen, error) = update_until(&mut node, &mut output, 6);
+        assert!(error.is_some());
+        assert_eq!(seen, [0, 1, 2, 3, 4, 5]);
+
+        // The pool keeps going after the failure.
+        node.input.send(buffers(1).remove(0)).unwrap();
+        let (seen, error) = update_until(&mut node, &mut output, 1);
+        assert!(error.is_none());
+        assert_eq!(seen, [0]);
+    }
+}
@@ -0,0 +1,47 @@
+#[cfg(test)]
+#[cfg(feature = "jpeg-fast")]
+mod transform {
+    use flowrs_img::transform::decode_image;
+    use image::ImageFormat;
+    use jpeg_encoder::{ColorType, Encoder};
+
+    const WIDTH: u16 = 45;
+    const HEIGHT: u16 = 30;
+
+    /// A JPEG of a smooth pattern with `channels` bytes per pixel in `color`.
+    fn jpeg(color: ColorType, channels: usize) -> Vec<u8> {
+        let pixels: Vec<u8> = (0..HEIGHT as usize)
+            .flat_map(|y| (0..WIDTH as usize).map(move |x| (x, y)))
+            .flat_map(|(x, y)| (0..channels).map(move |c| ((x * 5 + y * 3 + c * 60) % 256) as u8))
+            .collect();
+        let mut data = Vec::new();
+        Encoder::new(&mut data, 90).encode(&pixels, WIDTH, HEIGHT, color).unwrap();
+        data
+    }
+
+    /// Largest per-sample difference between the fast decoder and image-rs; the two round
+    /// differently in the IDCT and chroma upsampling, but must agree on layout and colours.
+    fn max_difference(data: Vec<u8>) -> u8 {
+        let reference = image::load_from_memory_with_format(&data, ImageFormat::Jpeg).unwrap();
+        let fast = decode_image(data).unwrap();
+        assert_eq!(fast.color(), reference.color());
+        assert_eq!((fast.width(), fast.height()), (WIDTH as u32, HEIGHT as u32));
+        fast.as_bytes().iter().zip(reference.as_bytes()).map(|(a, b)| a.abs_diff(*b)).max().unwrap()
+    }
+
+    #[test]
+    fn gray_matches_image_rs() {
+        assert!(max_difference(jpeg(ColorType::Luma, 1)) <= 1);
+    }
+
+    #[test]
+    fn rgb_matches_image_rs() {
+        assert!(max_difference(jpeg(ColorType::Rgb, 3)) <= 3);
+    }
+
+    #[test]
+    fn cmyk_matches_image_rs() {
+        assert!(max_difference(jpeg(ColorType::Cmyk, 4)) <= 3);
+        assert!(max_difference(jpeg(ColorType::CmykAsYcck, 4)) <= 3);
+    }
+}
@@ -0,0 +1,21 @@
+#[cfg(test)]
+mod transform {
+    use std::io::Cursor;
+
+    use flowrs_img::transform::decode_tiff_pages;
+    use tiff::encoder::{colortype, TiffEncoder};
+
+    #[test]
+    fn every_tiff_page_is_decoded() {
+        let mut data = Cursor::new(Vec::new());
+        let mut encoder = TiffEncoder::new(&mut data).unwrap();
+        encoder.write_image::<colortype::Gray8>(4, 2, &[7; 8]).unwrap();
+        encoder.write_image::<colortype::RGB16>(3, 3, &[1000; 27]).unwrap();
+
+        let pages = decode_tiff_pages(data.get_ref()).unwrap();
+        assert_eq!(pages.len(), 2);
+        assert_eq!(pages[0].as_luma8().unwrap().dimensions(), (4, 2));
+        assert_eq!(pages[0].as_luma8().unwrap().get_pixel(3, 1).0, [7]);
+        assert_eq!(pages[1].as_rgb16().unwrap().get_pixel(2, 2).0, [1000; 3]);
+    }
+}
@@ -0,0 +1,37 @@
+#[cfg(test)]
+mod transform {
+    use flowrs::connection::{connect, Input};
+    use flowrs::node::Node;
+    use flowrs_img::transform::{build_pyramid, PyramidMode, PyramidNode, PyramidNodeConfig};
+    use image::{DynamicImage, GenericImageView, Rgb, RgbImage};
+
+    fn sizes(levels: &[DynamicImage]) -> Vec<(u32, u32)> {
+        levels.iter().map(|l| l.dimensions()).collect()
+    }
+
+    #[test]
+    fn levels_halve_and_round_down() {
+        let img = DynamicImage::ImageRgb8(RgbImage::from_pixel(101, 40, Rgb([50, 100, 150])));
+        for mode in [PyramidMode::Gaussian, PyramidMode::Half] {
+            let pyramid = build_pyramid(img.clone(), 4, mode);
+            assert_eq!(sizes(&pyramid), vec![(101, 40), (50, 20), (25, 10), (12, 5)]);
+            assert!(pyramid.iter().all(|l| l.as_rgb8().is_some_and(|l| l.get_pixel(0, 0) == &Rgb([50, 100, 150]))));
+        }
+    }
+
+    #[test]
+    fn stops_before_a_side_vanishes() {
+        let img = DynamicImage::ImageRgb8(RgbImage::new(16, 3));
+        assert_eq!(sizes(&build_pyramid(img, 10, PyramidMode::Half)), vec![(16, 3), (8, 1)]);
+    }
+
+    #[test]
+    fn node_sends_all_levels_at_once() {
+        let mut node = PyramidNode::new(PyramidNodeConfig { levels: 3, mode: PyramidMode::Gaussian }, None);
+        let mut out = Input::new();
+        connect(node.output.clone(), out.clone());
+        node.input.send(DynamicImage::ImageRgb8(RgbImage::new(64, 48))).unwrap();
+        node.on_update().unwrap();
+        assert_eq!(sizes(&out.next().unwrap()), vec![(64, 48), (32, 24), (16, 12)]);
+    }
+}
@@ -0,0 +1,31 @@
+#[cfg(test)]
+mod transform {
+    use flowrs_img::Error;
+    use flowrs_img::color::resize_rgb8;
+    use flowrs_img::transform::{resize, ResizeNodeConfig};
+    use image::{DynamicImage, GenericImageView, Rgb, RgbImage};
+
+    #[test]
+    fn keeps_or_ignores_the_aspect_ratio() {
+        let img = DynamicImage::ImageRgb8(RgbImage::from_pixel(100, 50, Rgb([10, 20, 30])));
+        let fit = resize(&img, &ResizeNodeConfig { width: 40, height: 40, keep_aspect: true }).unwrap();
+        assert_eq!(fit.dimensions(), (40, 20));
+        let stretched = resize(&img, &ResizeNodeConfig { width: 40, height: 40, keep_aspect: false }).unwrap();
+        assert_eq!(stretched.dimensions(), (40, 40));
+        assert_eq!(stretched.to_rgb8().get_pixel(20, 20).0, [10, 20, 30]);
+    }
+
+    #[test]
+    fn exact_rgb8_resizes_use_the_kernel() {
+        let rgb = RgbImage::from_fn(64, 48, |x, y| Rgb([x as u8 * 4, y as u8 * 5, (x ^ y) as u8]));
+        let config = ResizeNodeConfig { width: 30, height: 30, keep_aspect: false };
+        let stretched = resize(&DynamicImage::ImageRgb8(rgb.clone()), &config).unwrap();
+        assert_eq!(stretched.as_rgb8().unwrap(), &resize_rgb8(&rgb, 30, 30));
+    }
+
+    #[test]
+    fn empty_frames_are_rejected() {
+        let result = resize(&DynamicImage::new_rgb8(0, 5), &ResizeNodeConfig::default());
+        assert!(matches!(result, Err(Error::Conversion(_))));
+    }
+}
@@ -0,0 +1,65 @@
+#[cfg(test)]
+mod transform {
+    use flowrs::connection::{connect, Input};
+    use flowrs::node::Node;
+    use image::{DynamicImage, GenericImageView, Rgb, RgbImage};
+
+    use flowrs_img::transform::{decode_image, EncodeFormat, EncodeImageNode, EncodeImageNodeConfig, RoiEncoding};
+    use flowrs_img::types::Rect;
+
+    const ROI: Rect = Rect { x: 32, y: 32, width: 64, height: 64 };
+
+    fn noise() -> DynamicImage {
+        DynamicImage::ImageRgb8(RgbImage::from_fn(128, 128, |x, y| {
+            let v = (x * 7919 + y * 104729) ^ (x * y * 31);
+            Rgb([v as u8, (v >> 8) as u8, (v >> 16) as u8])
+        }))
+    }
+
+    fn encode(roi: Option<RoiEncoding>, detected: Option<Vec<Rect>>) -> Vec<u8> {
+        let config = EncodeImageNodeConfig { format: EncodeFormat::Jpeg { quality: 90 }, roi, ..Default::default() };
+        let mut node = EncodeImageNode::new(config, None);
+        let mut out = Input::new();
+        connect(node.output.clone(), out.clone());
+        if let Some(regions) = detected {
+            node.regions.send(regions).unwrap();
+        }
+        node.input.send(noise()).unwrap();
+        node.on_update().unwrap();
+        out.next().unwrap()
+    }
+
+    /// Mean absolute difference to the source over the pixels `inside` (or outside) `ROI`.
+    fn error(encoded: &[u8], inside: bool) -> f64 {
+        let decoded = decode_image(encoded.to_vec()).unwrap().to_rgb8();
+        let source = noise().to_rgb8();
+        let within = |x: u32, y: u32| (ROI.x..ROI.x + ROI.width).contains(&x) && (ROI.y..ROI.y + ROI.height).contains(&y);
+        let (sum, count) = source
+            .enumerate_pixels()
+            .filter(|(x, y, _)| within(*x, *y) == inside)
+            .map(|(x, y, p)| p.0.iter().zip(decoded.get_pixel(x, y).0).map(|(a, b)| a.abs_diff(b) as f64).sum::<f64>())
+            .fold((0.0, 0), |(sum, n), e| (sum + e, n + 3));
+        sum / count as f64
+    }
+
+    #[test]
+    fn background_is_degraded_and_regions_are_kept() {
+        let plain = encode(None, None);
+        let roi = encode(Some(RoiEncoding { regions: vec![ROI], background_downscale: 8 }), None);
+        assert_eq!(decode_image(roi.clone()).unwrap().dimensions(), (128, 128));
+        assert!(roi.len() * 2 < plain.len(), "{} vs {} bytes", roi.len(), plain.len());
+
+        // The region costs no more than in the plain encoding; the background loses its detail.
+        assert!(error(&roi, true) < error(&plain, true) + 1.0);
+        assert!(error(&roi, false) > 4.0 * error(&plain, false));
+    }
+
+    #[test]
+    fn detected_regions_are_kept_too() {
+        let config = RoiEncoding { regions: Vec::new(), background_downscale: 8 };
+        let without = encode(Some(config.clone()), None);
+        let with = encode(Some(config), Some(vec![ROI]));
+        assert!(with.len() > without.len());
+        assert!(error(&with, true) * 4.0 < error(&without, true));
+    }
+}
@@ -0,0 +1,28 @@
+#[cfg(test)]
+mod transform {
+    use flowrs_img::transform::{decode_image_scaled, encode_image, EncodeFormat};
+    use image::{DynamicImage, GenericImageView, ImageBuffer, Rgb};
+
+    fn stripes(width: u32, height: u32) -> DynamicImage {
+        DynamicImage::ImageRgb8(ImageBuffer::from_fn(width, height, |x, _| if x < width / 2 { Rgb([255, 0, 0]) } else { Rgb([0, 0, 255]) }))
+    }
+
+    #[test]
+    fn large_images_decode_within_the_bound() {
+        let jpeg = encode_image(&stripes(800, 400), EncodeFormat::Jpeg { quality: 90 }).unwrap();
+        let img = decode_image_scaled(jpeg, 100).unwrap();
+        assert_eq!(img.dimensions(), (100, 50));
+
+        let png = encode_image(&stripes(410, 205), EncodeFormat::Png).unwrap();
+        let img = decode_image_scaled(png, 50).unwrap();
+        assert_eq!(img.dimensions(), (50, 25));
+        assert_eq!(img.get_pixel(2, 2).0, [255, 0, 0, 255]);
+        assert_eq!(img.get_pixel(47, 22).0, [0, 0, 255, 255]);
+    }
+
+    #[test]
+    fn small_images_keep_their_size() {
+        let png = encode_image(&stripes(30, 20), EncodeFormat::Png).unwrap();
+        assert_eq!(decode_image_scaled(png, 50).unwrap().dimensions(), (30, 20));
+    }
+}
@@ -0,0 +1,32 @@
+#[cfg(test)]
+mod transform {
+    use flowrs_img::transform::{tile_layout, TileSplitNodeConfig};
+
+    #[test]
+    fn cores_partition_frame() {
+        let config = TileSplitNodeConfig { tile_width: 256, tile_height: 200, overlap: 32 };
+        let tiles = tile_layout(1000, 450, &config, 7);
+        assert_eq!(tiles.len(), tiles[0].count);
+
+        let mut covered = vec![0u8; 1000 * 450];
+        for t in &tiles {
+            assert_eq!((t.region.width, t.region.height), (256, 200));
+            assert!(t.region.x + t.region.width <= 1000 && t.region.y + t.region.height <= 450);
+            assert!(t.core.x >= t.region.x && t.core.x + t.core.width <= t.region.x + t.region.width);
+            for y in t.core.y..t.core.y + t.core.height {
+                for x in t.core.x..t.core.x + t.core.width {
+                    covered[(y * 1000 + x) as usize] += 1;
+                }
+            }
+        }
+        assert!(covered.iter().all(|&c| c == 1));
+    }
+
+    #[test]
+    fn small_frame_is_single_tile() {
+        let config = TileSplitNodeConfig { tile_width: 512, tile_height: 512, overlap: 64 };
+        let tiles = tile_layout(300, 200, &config, 0);
+        assert_eq!(tiles.len(), 1);
+        assert_eq!((tiles[0].core.width, tiles[0].core.height), (300, 200));
+    }
+}
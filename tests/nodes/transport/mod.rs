@@ -0,0 +1,4 @@
+pub mod test_framing;
+pub mod test_lanes;
+pub mod test_publisher;
+pub mod test_shm;
@@ -0,0 +1,33 @@
+#[cfg(test)]
+mod transport {
+    use flowrs_img::transform::EncodeFormat;
+    use flowrs_img::transport::{read_frame, write_frame, FrameHeader};
+
+    #[test]
+    fn frame_round_trips() {
+        let header = FrameHeader { seq: 7, width: 640, height: 480, format: EncodeFormat::Png, timestamp_ms: 42 };
+        let payload = vec![1u8, 2, 3, 4, 5];
+
+        let mut buf = Vec::new();
+        write_frame(&mut buf, &header, &payload).unwrap();
+        let (read_header, read_payload) = read_frame(&mut buf.as_slice()).unwrap();
+
+        assert_eq!(read_header, header);
+        assert_eq!(read_payload, payload);
+    }
+
+    #[test]
+    fn invalid_magic_is_rejected() {
+        let buf = vec![0u8; 32];
+        assert!(read_frame(&mut buf.as_slice()).is_err());
+    }
+
+    #[test]
+    fn oversized_lengths_are_invalid_data() {
+        let mut buf = b"FLIM".to_vec();
+        buf.extend_from_slice(&u32::MAX.to_le_bytes());
+        buf.extend_from_slice(&u32::MAX.to_le_bytes());
+        let err = read_frame(&mut buf.as_slice()).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,56 @@
+#[cfg(test)]
+mod transport {
+    use flowrs::connection::{connect, Input};
+    use flowrs::node::Node;
+    use flowrs_img::transport::{merge_lanes, FramePriority, LaneMergeNode, PrioritizedFrame};
+    use image::DynamicImage;
+
+    fn frame(seq: u64, priority: FramePriority) -> PrioritizedFrame {
+        PrioritizedFrame { seq, priority, image: DynamicImage::new_luma8(1, 1) }
+    }
+
+    fn lanes(frames: &[PrioritizedFrame]) -> Vec<(u64, FramePriority)> {
+        frames.iter().map(|f| (f.seq, f.priority)).collect()
+    }
+
+    #[test]
+    fn previews_bypass_and_quality_always_arrives() {
+        use FramePriority::*;
+
+        // The quality lane is behind: the preview of frame 5 is shown right away.
+        let out = merge_lanes(Some(2), vec![], Some(frame(5, Preview)));
+        assert_eq!(lanes(&out), [(5, Preview)]);
+
+        // Quality frames whose previews were shown already still come through.
+        let out = merge_lanes(Some(5), vec![frame(6, Quality), frame(4, Quality)], Some(frame(8, Preview)));
+        assert_eq!(lanes(&out), [(4, Quality), (6, Quality), (8, Preview)]);
+
+        // Quality replaces the preview of the same frame, and stale previews are discarded.
+        let out = merge_lanes(None, vec![frame(3, Quality)], Some(frame(3, Preview)));
+        assert_eq!(lanes(&out), [(3, Quality)]);
+        let out = merge_lanes(None, vec![frame(4, Quality)], Some(frame(3, Preview)));
+        assert_eq!(lanes(&out), [(4, Quality)]);
+        let out = merge_lanes(Some(8), vec![], Some(frame(7, Preview)));
+        assert!(out.is_empty());
+    }
+
+    #[test]
+    fn node_forwards_every_quality_frame() {
+        let mut node = LaneMergeNode::new(None);
+        let mut out = Input::new();
+        connect(node.output.clone(), out.clone());
+        let mut forwarded = Vec::new();
+        for seq in 0..4 {
+            // Each quality frame lags one frame behind its preview.
+            node.preview.send(frame(seq, FramePriority::Preview)).unwrap();
+            if seq > 0 {
+                node.quality.send(frame(seq - 1, FramePriority::Quality)).unwrap();
+            }
+            node.on_update().unwrap();
+            forwarded.extend(std::iter::from_fn(|| out.next().ok()));
+        }
+        let quality: Vec<u64> = forwarded.iter().filter(|f| f.priority == FramePriority::Quality).map(|f| f.seq).collect();
+        assert_eq!(quality, [0, 1, 2]);
+        assert_eq!(forwarded.iter().filter(|f| f.priority == FramePriority::Preview).count(), 4);
+    }
+}
@@ -0,0 +1,28 @@
+#[cfg(test)]
+mod transport {
+    use std::net::{TcpListener, TcpStream};
+    use std::time::{Duration, Instant};
+
+    use flowrs::node::Node;
+    use flowrs_img::transform::EncodeFormat;
+    use flowrs_img::transport::{ImagePublisherNode, ImagePublisherNodeConfig, TransportEndpoint};
+    use image::{DynamicImage, RgbImage};
+
+    #[test]
+    fn stalled_subscribers_are_dropped() {
+        let address = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().to_string();
+        let endpoint = TransportEndpoint::Tcp { address: address.clone() };
+        let mut node = ImagePublisherNode::new(ImagePublisherNodeConfig { endpoint, format: EncodeFormat::Bmp }, None);
+        node.on_update().unwrap();
+
+        // Connected but never reading; uncompressed frames soon fill the socket buffers.
+        let _client = TcpStream::connect(&address).unwrap();
+        let frame = DynamicImage::ImageRgb8(RgbImage::new(4096, 2048));
+        let start = Instant::now();
+        for _ in 0..3 {
+            node.input.send(frame.clone()).unwrap();
+            node.on_update().unwrap();
+        }
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+}
@@ -0,0 +1,128 @@
+#[cfg(test)]
+#[cfg(feature = "shm")]
+mod transport {
+    use flowrs::connection::{connect, Input};
+    use flowrs::node::Node;
+    use image::{DynamicImage, GrayImage, Luma};
+
+    use flowrs_img::transport::{SharedMemReaderNode, SharedMemReaderNodeConfig, SharedMemWriterNode, SharedMemWriterNodeConfig};
+    use flowrs_img::types::SourceControl;
+
+    fn frame(value: u8) -> DynamicImage {
+        DynamicImage::ImageLuma8(GrayImage::from_pixel(8, 4, Luma([value])))
+    }
+
+    fn writer(path: &std::path::Path, slot_size: u64) -> SharedMemWriterNode {
+        SharedMemWriterNode::new(SharedMemWriterNodeConfig { path: path.into(), slot_count: 4, slot_size }, None)
+    }
+
+    fn write(writer: &mut SharedMemWriterNode, values: impl IntoIterator<Item = u8>) {
+        for value in values {
+            writer.input.send(frame(value)).unwrap();
+            writer.on_update().unwrap();
+        }
+    }
+
+    fn read(reader: &mut SharedMemReaderNode, received: &mut Input<DynamicImage>) -> Vec<u8> {
+        reader.on_update().unwrap();
+        std::iter::from_fn(|| received.next().ok()).map(|img| img.to_luma8().get_pixel(0, 0)[0]).collect()
+    }
+
+    #[test]
+    fn frames_round_trip_in_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("ring");
+        let mut reader = SharedMemReaderNode::new(SharedMemReaderNodeConfig { path: path.clone() }, None);
+        let mut received = Input::new();
+        connect(reader.output.clone(), received.clone());
+
+        // Nothing to read before the writer created the ring.
+        assert_eq!(read(&mut reader, &mut received), Vec::<u8>::new());
+
+        let mut writer = writer(&path, 64);
+        write(&mut writer, [1, 2, 3]);
+        assert_eq!(read(&mut reader, &mut received), [1, 2, 3]);
+        assert_eq!(read(&mut reader, &mut received), Vec::<u8>::new());
+
+        // Frames overwritten before the reader got to them are skipped.
+        write(&mut writer, 4..=9);
+        assert_eq!(read(&mut reader, &mut received), [6, 7, 8, 9]);
+        assert_eq!(received.next().ok().map(|img| img.to_luma8()), None);
+    }
+
+    #[test]
+    fn reader_follows_a_restarted_writer() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("ring");
+        let mut reader = SharedMemReaderNode::new(SharedMemReaderNodeConfig { path: path.clone() }, None);
+        let mut received = Input::new();
+        connect(reader.output.clone(), received.clone());
+
+        write(&mut writer(&path, 32), [1, 2, 3]);
+        assert_eq!(read(&mut reader, &mut received), [1, 2, 3]);
+
+        // Larger slots grow the file, which the reader has to map again.
+        write(&mut writer(&path, 4096), [10]);
+        assert_eq!(read(&mut reader, &mut received), [10]);
+
+        // Smaller slots reuse the file without shrinking it.
+        write(&mut writer(&path, 32), [20, 21]);
+        assert_eq!(read(&mut reader, &mut received), [20, 21]);
+    }
+
+    #[test]
+    fn oversized_frames_are_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut writer = writer(&dir.path().join("ring"), 16);
+        writer.input.send(frame(1)).unwrap();
+        assert!(writer.on_update().is_err());
+    }
+
+    #[test]
+    fn both_ends_report_frames() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("ring");
+        let mut writer = writer(&path, 32);
+        let mut reader = SharedMemReaderNode::new(SharedMemReaderNodeConfig { path }, None);
+        let (mut writer_health, mut reader_health) = (Input::new(), Input::new());
+        connect(writer.health.clone(), writer_health.clone());
+        connect(reader.health.clone(), reader_health.clone());
+
+        write(&mut writer, [1]);
+        reader.on_update().unwrap();
+        assert_eq!(writer_health.next().unwrap().frames, 1);
+        assert_eq!(reader_health.next().unwrap().frames, 1);
+
+        writer.input.send(DynamicImage::ImageLuma8(GrayImage::new(64, 64))).unwrap();
+        assert!(writer.on_update().is_err());
+        let status = writer_health.next().unwrap();
+        assert_eq!(status.errors, 1);
+        assert!(status.last_error.unwrap().contains("exceeds"));
+    }
+
+    #[test]
+    fn control_pauses_and_single_shots_the_reader() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("ring");
+        let mut writer = writer(&path, 32);
+        let mut reader = SharedMemReaderNode::new(SharedMemReaderNodeConfig { path }, None);
+        let mut received = Input::new();
+        connect(reader.output.clone(), received.clone());
+
+        reader.control.send(SourceControl::Pause).unwrap();
+        write(&mut writer, [1, 2]);
+        assert_eq!(read(&mut reader, &mut received), Vec::<u8>::new());
+
+        // Frames published while paused are not replayed later.
+        reader.control.send(SourceControl::SingleShot).unwrap();
+        write(&mut writer, [3, 4]);
+        assert_eq!(read(&mut reader, &mut received), [3]);
+        write(&mut writer, [5]);
+        assert_eq!(read(&mut reader, &mut received), Vec::<u8>::new());
+
+        reader.control.send(SourceControl::Stop).unwrap();
+        reader.control.send(SourceControl::Start).unwrap();
+        write(&mut writer, [6]);
+        assert_eq!(read(&mut reader, &mut received), [6]);
+    }
+}
@@ -0,0 +1,2 @@
+pub mod test_annexb;
+pub mod test_hls;
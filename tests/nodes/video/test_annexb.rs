@@ -0,0 +1,56 @@
+#[cfg(test)]
+mod video {
+    use flowrs_img::video::{is_keyframe, nal_types, AccessUnitSplitter, VideoCodec};
+
+    /// H.264 access units: delimiter, SPS and IDR slice; then delimiter and a non-IDR slice.
+    const H264_IDR: &[u8] = &[0, 0, 0, 1, 0x09, 0xf0, 0, 0, 0, 1, 0x67, 0x42, 0, 0, 1, 0x65, 0x88, 0x84];
+    const H264_P: &[u8] = &[0, 0, 0, 1, 0x09, 0xf0, 0, 0, 1, 0x41, 0x9a, 0x02];
+
+    /// H.265 access units: delimiter and IDR_W_RADL slice; then delimiter and TRAIL_R slice.
+    const H265_IDR: &[u8] = &[0, 0, 1, 0x46, 0x01, 0x50, 0, 0, 1, 0x26, 0x01, 0xaf];
+    const H265_TRAIL: &[u8] = &[0, 0, 1, 0x46, 0x01, 0x50, 0, 0, 1, 0x02, 0x01, 0xd0];
+
+    #[test]
+    fn nal_types_are_read_from_start_codes() {
+        assert_eq!(nal_types(H264_IDR, VideoCodec::H264), [9, 7, 5]);
+        assert_eq!(nal_types(H264_P, VideoCodec::H264), [9, 1]);
+        assert_eq!(nal_types(H265_IDR, VideoCodec::H265), [35, 19]);
+        assert_eq!(nal_types(H265_TRAIL, VideoCodec::H265), [35, 1]);
+        assert!(nal_types(&[0, 0, 1], VideoCodec::H264).is_empty());
+    }
+
+    #[test]
+    fn keyframes_are_detected() {
+        assert!(is_keyframe(&nal_types(H264_IDR, VideoCodec::H264), VideoCodec::H264));
+        assert!(!is_keyframe(&nal_types(H264_P, VideoCodec::H264), VideoCodec::H264));
+        assert!(is_keyframe(&nal_types(H265_IDR, VideoCodec::H265), VideoCodec::H265));
+        assert!(!is_keyframe(&nal_types(H265_TRAIL, VideoCodec::H265), VideoCodec::H265));
+    }
+
+    fn split(codec: VideoCodec, units: &[&[u8]], chunk: usize) -> Vec<Vec<u8>> {
+        let stream = units.concat();
+        let mut splitter = AccessUnitSplitter::new(codec);
+        let mut out: Vec<Vec<u8>> = stream.chunks(chunk).flat_map(|c| splitter.push(c)).collect();
+        out.extend(splitter.finish());
+        assert_eq!(splitter.finish(), None);
+        out
+    }
+
+    #[test]
+    fn stream_splits_at_delimiters() {
+        let units = [H264_IDR, H264_P, H264_P];
+        for chunk in [1, 3, 64] {
+            assert_eq!(split(VideoCodec::H264, &units, chunk), units);
+        }
+        let units = [H265_IDR, H265_TRAIL];
+        assert_eq!(split(VideoCodec::H265, &units, 5), units);
+    }
+
+    #[test]
+    fn last_unit_waits_for_finish() {
+        let mut splitter = AccessUnitSplitter::new(VideoCodec::H264);
+        assert!(splitter.push(H264_IDR).is_empty());
+        assert_eq!(splitter.push(H264_P), [H264_IDR]);
+        assert_eq!(splitter.finish().as_deref(), Some(H264_P));
+    }
+}
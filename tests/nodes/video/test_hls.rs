@@ -0,0 +1,34 @@
+#[cfg(test)]
+mod video {
+    use flowrs_img::video::{HlsSinkNode, HlsSinkNodeConfig};
+
+    #[test]
+    fn output_args_describe_the_playlist() {
+        let config = HlsSinkNodeConfig {
+            output_dir: "/srv/hls".into(),
+            playlist_name: "live.m3u8".into(),
+            fps: 25.0,
+            segment_duration_secs: 4,
+            retained_segments: 3,
+            encoder: "libx264".into(),
+            bitrate_kbps: 1500,
+        };
+        let args = HlsSinkNode::new(config, None).output_args();
+        let expected = [
+            "-c:v", "libx264", "-b:v", "1500k", "-pix_fmt", "yuv420p",
+            "-g", "100", "-sc_threshold", "0",
+            "-f", "hls", "-hls_time", "4", "-hls_list_size", "3",
+            "-hls_flags", "delete_segments+independent_segments",
+            "-hls_segment_filename", "/srv/hls/segment_%05d.ts", "/srv/hls/live.m3u8",
+        ];
+        assert_eq!(args, expected);
+    }
+
+    #[test]
+    fn gop_is_at_least_one_frame() {
+        let config = HlsSinkNodeConfig { fps: 0.1, segment_duration_secs: 1, ..Default::default() };
+        let args = HlsSinkNode::new(config, None).output_args();
+        let gop = args.iter().position(|a| a == "-g").map(|i| args[i + 1].as_str());
+        assert_eq!(gop, Some("1"));
+    }
+}